@@ -0,0 +1,195 @@
+//! Implementation of the `#[neon::export_config]` attribute
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, parse::Parser, punctuated::Punctuated, spanned::Spanned};
+
+use crate::{function::Options, rename::RenameRule};
+
+// Default `rename_all` of the functions in the module, as written
+struct Config(Option<(syn::Lit, RenameRule)>);
+
+impl Config {
+    fn parse(args: syn::AttributeArgs) -> syn::Result<Self> {
+        let mut rename_all = None;
+
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(meta))
+                    if meta.path.is_ident("rename_all") =>
+                {
+                    let rule = RenameRule::parse(&meta.lit)?;
+
+                    if rename_all.replace((meta.lit, rule)).is_some() {
+                        return Err(syn::Error::new(meta.path.span(), "duplicate `rename_all`"));
+                    }
+                }
+                arg => return Err(syn::Error::new(arg.span(), "unknown export_config option")),
+            }
+        }
+
+        Ok(Self(rename_all))
+    }
+}
+
+// Functions exported as properties of an object, e.g., `addon.imaging.resize`
+struct Namespace {
+    name: String,
+    lazy: bool,
+    names: Vec<TokenStream>,
+    fns: Vec<syn::Ident>,
+}
+
+impl Namespace {
+    // Statement exporting the namespace from `cx`
+    fn export(&self) -> TokenStream {
+        let Self {
+            name, names, fns, ..
+        } = self;
+
+        let build = quote!(
+            let namespace = neon::context::Context::empty_object(cx);
+            #(neon::macro_internal::export_function(cx, namespace, #names, #fns)?;)*
+        );
+
+        if self.lazy {
+            quote!(cx.export_lazy(#name, |cx| {
+                #build
+                Ok(namespace)
+            })?;)
+        } else {
+            quote!({
+                let cx = &mut *cx;
+                #build
+                cx.export_value(#name, namespace)?;
+            })
+        }
+    }
+}
+
+// Matches `#[neon::function]` and `#[function]`
+fn is_function(path: &syn::Path) -> bool {
+    let segments = &path.segments;
+
+    path.is_ident("function")
+        || (segments.len() == 2 && segments[0].ident == "neon" && segments[1].ident == "function")
+}
+
+fn function_args(attr: &syn::Attribute) -> syn::Result<syn::AttributeArgs> {
+    if attr.tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let parser = |input: syn::parse::ParseStream| {
+        let content;
+
+        syn::parenthesized!(content in input);
+        Punctuated::<syn::NestedMeta, syn::Token![,]>::parse_terminated(&content)
+    };
+
+    Ok(parser.parse2(attr.tokens.clone())?.into_iter().collect())
+}
+
+pub(crate) fn expand(
+    args: syn::AttributeArgs,
+    mut module: syn::ItemMod,
+) -> syn::Result<TokenStream> {
+    let Config(rename_all) = Config::parse(args)?;
+
+    let items = match &mut module.content {
+        Some((_, items)) => items,
+        None => {
+            return Err(syn::Error::new(
+                module.semi.span(),
+                "`#[neon::export_config]` requires an inline module",
+            ))
+        }
+    };
+
+    let mut names = Vec::new();
+    let mut fns = Vec::new();
+    let mut namespaces = Vec::<Namespace>::new();
+
+    for item in items.iter_mut() {
+        let f = match item {
+            syn::Item::Fn(f) => f,
+            _ => continue,
+        };
+
+        let ident = f.sig.ident.clone();
+
+        for attr in f.attrs.iter_mut().filter(|attr| is_function(&attr.path)) {
+            let args = function_args(attr)?;
+            let options = Options::parse(args.clone())?;
+
+            // Options of the function override the module
+            let rule = match (options.rename_all, &rename_all) {
+                (Some(rule), _) => Some(rule),
+                (None, Some((lit, rule))) => {
+                    attr.tokens = quote!((#(#args,)* rename_all = #lit));
+                    Some(*rule)
+                }
+                (None, None) => None,
+            };
+
+            // Names are converted when exporting, by the same code as the names of
+            // parameters in errors
+            let raw = ident.unraw().to_string();
+            let name = match (&options.name, rule) {
+                (Some(name), _) => quote!(#name),
+                (None, Some(rule)) => quote!(&#rule.apply(#raw)),
+                (None, None) => quote!(#raw),
+            };
+
+            let namespace = match options.namespace {
+                Some(namespace) => namespace,
+                None => {
+                    names.push(name);
+                    fns.push(ident.clone());
+                    continue;
+                }
+            };
+
+            let i = match namespaces
+                .iter()
+                .position(|ns| ns.name == namespace.value())
+            {
+                Some(i) => i,
+                None => {
+                    namespaces.push(Namespace {
+                        name: namespace.value(),
+                        lazy: options.lazy,
+                        names: Vec::new(),
+                        fns: Vec::new(),
+                    });
+                    namespaces.len() - 1
+                }
+            };
+
+            let ns = &mut namespaces[i];
+
+            if ns.lazy != options.lazy {
+                return Err(syn::Error::new(
+                    namespace.span(),
+                    "every function in a namespace must be `lazy` if one is",
+                ));
+            }
+
+            ns.names.push(name);
+            ns.fns.push(ident.clone());
+        }
+    }
+
+    let namespaces = namespaces.iter().map(Namespace::export);
+
+    items.push(syn::parse_quote!(
+        /// Exports the functions in this module marked with `#[neon::function]`
+        pub fn export(cx: &mut neon::context::ModuleContext) -> neon::result::NeonResult<()> {
+            #(cx.export_function(#names, #fns)?;)*
+            #(#namespaces)*
+            Ok(())
+        }
+    ));
+
+    Ok(quote!(#module))
+}
@@ -0,0 +1,418 @@
+//! Implementation of the `#[neon::function]` attribute
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
+use syn::spanned::Spanned;
+
+use crate::{out, rename::RenameRule};
+
+#[derive(Clone, Copy)]
+/// How an `Err` returned by the function is converted, with the `error` option
+pub(crate) enum ErrorMode {
+    Throw,
+    Value,
+    Tuple,
+}
+
+impl ErrorMode {
+    fn parse(lit: &syn::Lit) -> syn::Result<Self> {
+        let lit = match lit {
+            syn::Lit::Str(lit) => lit,
+            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+        };
+
+        match lit.value().as_str() {
+            "throw" => Ok(Self::Throw),
+            "value" => Ok(Self::Value),
+            "tuple" => Ok(Self::Tuple),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "expected \"throw\", \"value\" or \"tuple\"",
+            )),
+        }
+    }
+}
+
+impl ToTokens for ErrorMode {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        tokens.extend(match self {
+            Self::Throw => quote!(neon::macro_internal::ErrorMode::Throw),
+            Self::Value => quote!(neon::macro_internal::ErrorMode::Value),
+            Self::Tuple => quote!(neon::macro_internal::ErrorMode::Tuple),
+        });
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Options {
+    requires: Option<syn::LitStr>,
+    deprecated: Option<syn::LitStr>,
+    pub(crate) error: Option<ErrorMode>,
+    non_reentrant: bool,
+    allow_nested: Vec<syn::LitStr>,
+    trace: Option<syn::LitStr>,
+    pub(crate) name: Option<syn::LitStr>,
+    pub(crate) rename_all: Option<RenameRule>,
+    pub(crate) namespace: Option<syn::LitStr>,
+    pub(crate) lazy: bool,
+}
+
+impl Options {
+    pub(crate) fn parse(args: syn::AttributeArgs) -> syn::Result<Self> {
+        let mut options = Self::default();
+
+        for arg in args {
+            match arg {
+                syn::NestedMeta::Meta(syn::Meta::NameValue(meta)) => {
+                    let path = &meta.path;
+                    let option = match path.get_ident() {
+                        Some(ident) => ident.to_string(),
+                        None => return Err(syn::Error::new(path.span(), "unknown option")),
+                    };
+
+                    match option.as_str() {
+                        "name" => set_once(&mut options.name, lit_str(meta.lit)?, path)?,
+                        "namespace" => set_once(&mut options.namespace, lit_str(meta.lit)?, path)?,
+                        "requires" => set_once(&mut options.requires, lit_str(meta.lit)?, path)?,
+                        "deprecated" => {
+                            set_once(&mut options.deprecated, lit_str(meta.lit)?, path)?
+                        }
+                        "trace" => set_once(&mut options.trace, lit_str(meta.lit)?, path)?,
+                        "rename_all" => {
+                            set_once(&mut options.rename_all, RenameRule::parse(&meta.lit)?, path)?
+                        }
+                        "error" => {
+                            set_once(&mut options.error, ErrorMode::parse(&meta.lit)?, path)?
+                        }
+                        _ => return Err(syn::Error::new(path.span(), "unknown option")),
+                    }
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("lazy") => {
+                    set_flag(&mut options.lazy, &path)?
+                }
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("non_reentrant") => {
+                    set_flag(&mut options.non_reentrant, &path)?
+                }
+                syn::NestedMeta::Meta(syn::Meta::List(list))
+                    if list.path.is_ident("allow_nested") =>
+                {
+                    for name in list.nested {
+                        match name {
+                            syn::NestedMeta::Lit(lit) => options.allow_nested.push(lit_str(lit)?),
+                            name => return Err(syn::Error::new(name.span(), "expected a string")),
+                        }
+                    }
+                }
+                arg => return Err(syn::Error::new(arg.span(), "unknown option")),
+            }
+        }
+
+        if !options.non_reentrant {
+            if let Some(name) = options.allow_nested.first() {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`allow_nested` requires `non_reentrant`",
+                ));
+            }
+        }
+
+        if options.lazy && options.namespace.is_none() {
+            return Err(syn::Error::new(
+                Span::call_site(),
+                "`lazy` requires `namespace`",
+            ));
+        }
+
+        Ok(options)
+    }
+
+    // Statements checking the options before the function is called
+    pub(crate) fn prologue(&self, cx: &syn::Ident, name: &str) -> TokenStream {
+        let napi_6 = self.napi_6();
+
+        let requires = self.requires.as_ref().map(
+            |requires| quote!(neon::macro_internal::require_capability(&mut #cx, #requires)?;),
+        );
+
+        let deprecated = self.deprecated.as_ref().map(
+            |message| quote!(neon::macro_internal::warn_deprecated(&mut #cx, #name, #message)?;),
+        );
+
+        // Held until the original function returns, throws or panics
+        let guard = self.non_reentrant.then(|| {
+            let guard = syn::Ident::new("__neon_guard", Span::mixed_site());
+            let allow = &self.allow_nested;
+
+            quote!(
+                let #guard = neon::macro_internal::reentrancy_guard::<_, &str>(&mut #cx, #name, &[#(#allow),*])?;
+            )
+        });
+
+        let rename = self
+            .rename_all
+            .map(|rule| quote!(neon::macro_internal::rename_arguments(&mut #cx, #rule);));
+
+        quote!(
+            #(#napi_6)*
+            #rename
+            #deprecated
+            #requires
+            #guard
+        )
+    }
+
+    // Compile errors for the options that are used without the `napi-6` feature of `neon`
+    fn napi_6(&self) -> Vec<TokenStream> {
+        let options = [
+            ("requires", self.requires.as_ref().map(|lit| lit.span())),
+            ("deprecated", self.deprecated.as_ref().map(|lit| lit.span())),
+            ("non_reentrant", self.non_reentrant.then(Span::call_site)),
+            ("trace", self.trace.as_ref().map(|lit| lit.span())),
+        ];
+
+        options
+            .iter()
+            .filter_map(|(option, span)| {
+                let span = (*span)?;
+
+                Some(quote_spanned!(span=> neon::macro_internal::requires_napi_6!(#option);))
+            })
+            .collect()
+    }
+
+    // Publishes the start of the call to the tracing channel, after the prologue
+    fn trace_start(&self, cx: &syn::Ident, name: &str) -> Option<TokenStream> {
+        let channel = self.trace.as_ref()?;
+        let trace = syn::Ident::new("__neon_trace", Span::mixed_site());
+
+        Some(quote!(
+            let #trace = neon::macro_internal::trace_start(&mut #cx, #channel, #name)?;
+        ))
+    }
+
+    // Publishes the end of the call, and its exception, with the result of `call`
+    pub(crate) fn traced(&self, cx: &syn::Ident, name: &str, call: TokenStream) -> TokenStream {
+        let start = match self.trace_start(cx, name) {
+            Some(start) => start,
+            None => return call,
+        };
+
+        let trace = syn::Ident::new("__neon_trace", Span::mixed_site());
+        let result = syn::Ident::new("__neon_traced", Span::mixed_site());
+
+        // A closure, so that the end is published when `call` returns early with `?`
+        quote!(
+            #start
+
+            #[allow(clippy::redundant_closure_call)]
+            let #result = (|| { #call })();
+
+            neon::macro_internal::trace_end(#trace, #result)
+        )
+    }
+}
+
+fn lit_str(lit: syn::Lit) -> syn::Result<syn::LitStr> {
+    match lit {
+        syn::Lit::Str(lit) => Ok(lit),
+        lit => Err(syn::Error::new(lit.span(), "expected a string")),
+    }
+}
+
+// Sets an option that takes a value, which may only be given once
+fn set_once<T>(option: &mut Option<T>, value: T, path: &syn::Path) -> syn::Result<()> {
+    if option.replace(value).is_some() {
+        return Err(duplicate(path));
+    }
+
+    Ok(())
+}
+
+// Sets an option without a value, which may only be given once
+fn set_flag(flag: &mut bool, path: &syn::Path) -> syn::Result<()> {
+    if std::mem::replace(flag, true) {
+        return Err(duplicate(path));
+    }
+
+    Ok(())
+}
+
+fn duplicate(path: &syn::Path) -> syn::Error {
+    let name = path.to_token_stream().to_string();
+
+    syn::Error::new(path.span(), format!("duplicate `{}`", name))
+}
+
+pub(crate) fn expand(args: syn::AttributeArgs, input: syn_mid::ItemFn) -> syn::Result<TokenStream> {
+    let options = Options::parse(args)?;
+    let has_out = input.sig.inputs.iter().any(out::is_marked);
+
+    // `name`, `namespace` and `lazy` are only used by `#[neon::export_config]`
+    if !has_out
+        && options.requires.is_none()
+        && options.deprecated.is_none()
+        && options.error.is_none()
+        && !options.non_reentrant
+        && options.rename_all.is_none()
+        && options.trace.is_none()
+    {
+        return Ok(quote!(#input));
+    }
+
+    let syn_mid::ItemFn {
+        attrs,
+        vis,
+        sig,
+        block,
+    } = input;
+
+    if let Some(asyncness) = &sig.asyncness {
+        return Err(syn::Error::new(
+            asyncness.span(),
+            "`#[neon::function]` does not support `async` functions",
+        ));
+    }
+
+    if has_out {
+        return out::expand(&options, attrs, vis, sig, block);
+    }
+
+    // The original function is nested and called with the same arguments, which are
+    // renamed since they may be patterns
+    let mut args = Vec::new();
+    let mut tys = Vec::new();
+
+    for (i, input) in sig.inputs.iter().enumerate() {
+        match input {
+            syn_mid::FnArg::Typed(pat) => {
+                args.push(format_ident!("__neon_arg{}", i));
+                tys.push(&pat.ty);
+            }
+            syn_mid::FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "`#[neon::function]` does not support methods",
+                ));
+            }
+        }
+    }
+
+    let cx = match args.first() {
+        Some(cx) => cx.clone(),
+        None => {
+            return Err(syn::Error::new(
+                sig.ident.span(),
+                "expected the first argument to be a context",
+            ))
+        }
+    };
+
+    let rest = &args[1..];
+    let cx_ty = tys[0];
+    let rest_tys = &tys[1..];
+    let inner = syn::Ident::new("__neon_export", Span::mixed_site());
+    let syn_mid::Signature {
+        constness,
+        unsafety,
+        abi,
+        fn_token,
+        ident,
+        generics,
+        inputs,
+        output,
+        ..
+    } = &sig;
+
+    let where_clause = &generics.where_clause;
+
+    let name = ident.to_string();
+    let prologue = options.prologue(&cx, &name);
+
+    let mode = match options.error {
+        Some(mode) => mode,
+        None => {
+            let call = options.traced(&cx, &name, quote!(#inner(#cx, #(#rest),*)));
+
+            return Ok(quote!(
+                #(#attrs) *
+                #vis #constness #unsafety #abi #fn_token #ident #generics(
+                    mut #cx: #cx_ty,
+                    #(#rest: #rest_tys),*
+                ) #output #where_clause {
+                    #constness #unsafety #abi #fn_token #inner #generics(#inputs) #output #where_clause
+                    #block
+
+                    #prologue
+
+                    #call
+                }
+            ));
+        }
+    };
+
+    // With an `error` mode, the body is called as a closure, since the context is
+    // needed to convert the result after the body returns
+    let ret = match output {
+        syn::ReturnType::Type(_, ty) => ty,
+        syn::ReturnType::Default => {
+            return Err(syn::Error::new(
+                ident.span(),
+                "`error` requires the function to return a `Result`",
+            ))
+        }
+    };
+
+    let cx_name = match sig.inputs.first() {
+        Some(syn_mid::FnArg::Typed(pat)) => match &*pat.pat {
+            syn_mid::Pat::Ident(pat) => &pat.ident,
+            pat => {
+                return Err(syn::Error::new(
+                    pat.span(),
+                    "`error` requires the context to be bound to a name",
+                ))
+            }
+        },
+        _ => unreachable!("the first argument was checked to be typed"),
+    };
+
+    let pats = sig.inputs.iter().map(|input| match input {
+        syn_mid::FnArg::Typed(pat) => &pat.pat,
+        syn_mid::FnArg::Receiver(_) => unreachable!("methods were rejected"),
+    });
+
+    // The lifetime of the context, if it is named
+    let lifetime = generics.lifetimes().next().map(|def| {
+        let lifetime = &def.lifetime;
+
+        quote!(#lifetime,)
+    });
+
+    let result = syn::Ident::new("__neon_result", Span::mixed_site());
+    let call = options.traced(
+        &cx,
+        &name,
+        quote!(
+            #(let #pats = #args;)*
+
+            #[allow(clippy::redundant_closure_call)]
+            let #result = (|| -> #ret #block)();
+
+            // The context may have been bound without `mut`
+            let mut #cx = #cx_name;
+
+            neon::macro_internal::ExportResult::into_js(#result, &mut #cx, #mode)
+        ),
+    );
+
+    Ok(quote!(
+        #(#attrs) *
+        #vis #constness #unsafety #abi #fn_token #ident #generics(
+            mut #cx: #cx_ty,
+            #(#rest: #rest_tys),*
+        ) -> neon::result::JsResult<#lifetime neon::types::JsValue> #where_clause {
+            #prologue
+
+            #call
+        }
+    ))
+}
@@ -7,7 +7,9 @@
 /// This attribute should only be used _once_ in a module and will
 /// be called each time the module is initialized in a context.
 ///
-/// ```ignore
+/// ```no_run
+/// # mod example {
+/// # use neon::prelude::*;
 /// #[neon::main]
 /// fn main(mut cx: ModuleContext) -> NeonResult<()> {
 ///     let version = cx.string("1.0.0");
@@ -16,8 +18,19 @@
 ///
 ///     Ok(())
 /// }
+/// # }
 /// ```
 ///
+/// If the function throws an `Error`, `require()` throws a new `Error` with the
+/// message prefixed with the name of the crate, e.g.,
+/// `"my-addon: Invalid configuration"`, and the original error as its `cause`. If it
+/// panics, `require()` throws an `Error` with a `code` of `"ERR_NEON_INIT_PANIC"`,
+/// a message with the payload of the panic, and the `phase` of the initialization
+/// that panicked, either `"pre-export"`, while Neon prepares the module, or `"main"`.
+///
+/// Setting the environment variable `NEON_INIT_TRACE` to anything other than `0`
+/// logs the start, end and duration of each phase to stderr.
+///
 /// If multiple functions are marked with `#[neon::main]`, there may be a compile error:
 ///
 /// ```sh
@@ -43,7 +56,7 @@ pub fn main(
                 env: *mut std::ffi::c_void,
                 m: *mut std::ffi::c_void,
             ) -> *mut std::ffi::c_void {
-                neon::macro_internal::initialize_module(env, m, #name);
+                neon::macro_internal::initialize_module(env, m, #name, env!("CARGO_PKG_NAME"));
                 m
             }
 
@@ -52,3 +65,215 @@ pub fn main(
     )
     .into()
 }
+
+#[proc_macro_attribute]
+/// Configures the behavior of a Neon function that is exported from a module.
+///
+/// The attribute does not export the function: it must still be exported with
+/// `ModuleContext::export_function`, or by
+/// [`#[neon::export_config]`](macro@export_config) on the module containing it.
+/// The first argument of the function must be its context, unless it has
+/// [output arrays](#output-arrays). Unless the `error` option is set, the result of
+/// the function is returned without conversion, so a function returning one of its
+/// arguments with `FunctionContext::passthrough` returns the same value it was passed.
+///
+/// ## Options
+///
+/// * `requires = "name"`: Throws an `Error` with a `code` of
+///   `"ERR_NEON_MISSING_CAPABILITY"` instead of calling the function unless the
+///   capability `name` was registered as available with
+///   `ModuleContext::export_capabilities`. Requires the `napi-6` feature.
+/// * `deprecated = "message"`: Emits a `DeprecationWarning` with `message` the first
+///   time the function is called, with `process.emitWarning`, then calls the function.
+///   The warning has a `code` derived from the Rust name of the function, e.g.,
+///   `"NEON_DEP_PARSE_URI"` for `parse_uri`. No warning is emitted with
+///   `--no-deprecation`, and the call throws the warning with `--throw-deprecation`.
+///   Requires the `napi-6` feature.
+/// * `non_reentrant`: Throws an `Error` with a `code` of `"ERR_NEON_REENTRANT_CALL"`
+///   instead of calling the function if it, or another `non_reentrant` function, is
+///   already running, for example, when called from a callback of the running
+///   function. Functions are identified by their Rust name. Requires the `napi-6`
+///   feature.
+/// * `allow_nested("name", ...)`: Allows the `non_reentrant` functions with these
+///   names to be called while this function is running.
+/// * `error = "value"`: The function returns a `Result<T, E>`, or a
+///   `NeonResult<Result<T, E>>` to also throw exceptions, where `T` and `E` implement
+///   `TryIntoJs`. Both `Ok` and `Err` are converted and returned. With
+///   `error = "tuple"`, `Ok(value)` is returned as `[null, value]` and `Err(error)` as
+///   `[error, null]`, like the arguments of a Node-style callback. With
+///   `error = "throw"`, `Err` is converted and thrown. An error converting either
+///   value is thrown in every mode.
+/// * `trace = "name"`: Publishes each call to the channels of the Node.js
+///   `diagnostics_channel.tracingChannel(name)`, like `traceSync`. The `start` and
+///   `end` channels receive an object with the `name` of the function and its
+///   `arguments`; if the call throws, the exception is set as its `error` and it is
+///   published to `error` before `end`. Nothing is published, or allocated, while the
+///   channels have no subscribers. Requires the `napi-6` feature.
+/// * `rename_all = "camelCase"`: Converts the parameter names in errors thrown by
+///   `FunctionContext::named_argument`, and the name the function is exported with by
+///   [`#[neon::export_config]`](macro@export_config), from snake case. The rules are
+///   `"camelCase"`, `"PascalCase"` and `"snake_case"`, which keeps names unchanged.
+/// * `name = "jsName"`: The name the function is exported with by
+///   [`#[neon::export_config]`](macro@export_config). It is used as written.
+/// * `namespace = "imaging"`: Exports the function as a property of an object, e.g.,
+///   `addon.imaging.resize`, instead of the module, with
+///   [`#[neon::export_config]`](macro@export_config).
+/// * `lazy`: Creates the functions of the `namespace` when the namespace is first
+///   read, with `ModuleContext::export_lazy`. Every function in the namespace must be
+///   `lazy`. Requires the `napi-5` feature.
+///
+/// ```no_run
+/// # use neon::prelude::*;
+/// #[neon::function(requires = "vips")]
+/// fn resize(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     // e.g., `unsafe { ffi::vips_resize(..) }`
+///     Ok(cx.undefined())
+/// }
+///
+/// #[neon::function(non_reentrant, allow_nested("row_count"))]
+/// fn for_each_row(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let callback = cx.argument::<JsFunction>(0)?;
+///
+///     callback.call_with(&cx).exec(&mut cx)?;
+///     Ok(cx.undefined())
+/// }
+///
+/// #[neon::function(trace = "mydb:query")]
+/// fn query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     Ok(cx.undefined())
+/// }
+///
+/// #[neon::function(deprecated = "use parseUrl() instead")]
+/// fn parse_uri(mut cx: FunctionContext) -> JsResult<JsString> {
+///     cx.argument::<JsString>(0)
+/// }
+///
+/// // `[null, 42]` or `["not a number", null]`
+/// #[neon::function(error = "tuple")]
+/// fn parse_int(mut cx: FunctionContext) -> NeonResult<Result<f64, String>> {
+///     let s = cx.argument::<JsString>(0)?.value(&mut cx);
+///
+///     Ok(s.parse::<i32>().map(f64::from).map_err(|err| err.to_string()))
+/// }
+/// ```
+///
+/// ## Output arrays
+///
+/// A function can write its results into a typed array passed by the caller, instead
+/// of allocating a new one for each call, with a `&mut [T]` parameter marked
+/// `#[neon(out)]`. Such a function does not take a context: each parameter is the
+/// argument at its position, a `&[T]` or `&mut [T]` borrowed from a typed array of
+/// `T` and any other type extracted with `TryFromJs`, and the function returns the
+/// number of elements it wrote as a `usize`. The arrays are borrowed for the duration
+/// of the call, while JavaScript can't run.
+///
+/// A `TypeError` is thrown instead of calling the function if an array is backed by
+/// a `SharedArrayBuffer`, since other threads could access it while it is borrowed,
+/// or, with the `napi-7` feature, by a detached `ArrayBuffer`. An `Error` is thrown
+/// if an output overlaps another array.
+///
+/// With `#[neon(out)]`, a `RangeError` is thrown if the output does not have as many
+/// elements as the first `&[T]` parameter. With `#[neon(out(partial))]`, the output
+/// may have any length, and the function reports how much of it was filled.
+///
+/// ```no_run
+/// // `scale(input, 2, output)`, where `output` has the length of `input`
+/// #[neon::function]
+/// fn scale(input: &[f64], factor: f64, #[neon(out)] output: &mut [f64]) -> usize {
+///     for (y, x) in output.iter_mut().zip(input) {
+///         *y = x * factor;
+///     }
+///
+///     output.len()
+/// }
+/// ```
+pub fn function(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
+    let input = syn::parse_macro_input!(item as syn_mid::ItemFn);
+
+    function::expand(args, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_attribute]
+/// Exports the functions of an inline module that are marked with
+/// [`#[neon::function]`](macro@function), with a default `rename_all` rule.
+///
+/// The attribute adds a function `export` to the module, which exports each marked
+/// function with [`ModuleContext::export_function`]. Unless a function has its own
+/// `name` or `rename_all` option, it is exported with its name converted by the
+/// rule of the module, and the rule is applied to the parameter names in its errors.
+///
+/// Names are converted by capitalizing the first letter of each word after the
+/// first, keeping the rest of the word, so acronyms are written as words:
+/// `parse_url_fast` is exported as `parseUrlFast` and `get_http_status` as
+/// `getHttpStatus`. A function can set its exact name with `name`.
+///
+/// ```no_run
+/// # mod example {
+/// #[neon::export_config(rename_all = "camelCase")]
+/// mod api {
+///     use neon::prelude::*;
+///
+///     // Exported as `parseUrlFast`
+///     #[neon::function]
+///     fn parse_url_fast(mut cx: FunctionContext) -> JsResult<JsString> {
+///         // TypeError [ERR_INVALID_ARG_TYPE]: The "inputUrl" argument must be of type string
+///         let url = cx.named_argument::<JsString>(0, "input_url")?;
+///
+///         Ok(url)
+///     }
+///
+///     // Exported as `getHTTPStatus`
+///     #[neon::function(name = "getHTTPStatus")]
+///     fn get_http_status(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///         Ok(cx.number(200))
+///     }
+/// }
+///
+/// # use neon::prelude::*;
+/// #[neon::main]
+/// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+///     api::export(&mut cx)
+/// }
+/// # }
+/// ```
+///
+/// Functions with a `namespace` are grouped into an object exported with the name of
+/// the namespace, which is created when the namespace is first read if the functions
+/// are `lazy`:
+///
+/// ```no_run
+/// #[neon::export_config(rename_all = "camelCase")]
+/// mod imaging {
+///     use neon::prelude::*;
+///
+///     // `addon.imaging.resizeImage()`
+///     #[neon::function(namespace = "imaging", lazy)]
+///     fn resize_image(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///         Ok(cx.undefined())
+///     }
+/// }
+/// ```
+///
+/// [`ModuleContext::export_function`]: https://docs.rs/neon/latest/neon/context/struct.ModuleContext.html#method.export_function
+pub fn export_config(
+    attr: proc_macro::TokenStream,
+    item: proc_macro::TokenStream,
+) -> proc_macro::TokenStream {
+    let args = syn::parse_macro_input!(attr as syn::AttributeArgs);
+    let input = syn::parse_macro_input!(item as syn::ItemMod);
+
+    export_config::expand(args, input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+mod export_config;
+mod function;
+mod out;
+mod rename;
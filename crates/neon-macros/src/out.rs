@@ -0,0 +1,271 @@
+//! Implementation of the `#[neon(out)]` parameters of `#[neon::function]`
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+
+use crate::function::Options;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+// How the length of an output is checked before the function is called
+enum Len {
+    // The same as the first input, with `#[neon(out)]`
+    Exact,
+    // Any, with `#[neon(out(partial))]`
+    Partial,
+}
+
+// How an argument is passed to the function
+enum Param {
+    // `&[T]`, borrowed from a typed array
+    Input(syn::Type),
+    // `#[neon(out)] output: &mut [T]`, borrowed mutably from a typed array
+    Output(syn::Type, Len, Span),
+    // Extracted with `TryFromJs`
+    Value(syn::Type),
+}
+
+fn is_neon(attr: &syn::Attribute) -> bool {
+    attr.path.is_ident("neon")
+}
+
+// Whether the parameter has a `#[neon(..)]` attribute
+pub(crate) fn is_marked(input: &syn_mid::FnArg) -> bool {
+    match input {
+        syn_mid::FnArg::Typed(pat) => pat.attrs.iter().any(is_neon),
+        syn_mid::FnArg::Receiver(_) => false,
+    }
+}
+
+// Parses `#[neon(out)]` and `#[neon(out(partial))]`
+fn parse_attr(attr: &syn::Attribute) -> syn::Result<Len> {
+    let meta = attr.parse_meta()?;
+    let expected = || syn::Error::new(meta.span(), "expected `out` or `out(partial)`");
+
+    let nested = match &meta {
+        syn::Meta::List(list) if list.nested.len() == 1 => &list.nested[0],
+        _ => return Err(expected()),
+    };
+
+    match nested {
+        syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("out") => Ok(Len::Exact),
+        syn::NestedMeta::Meta(syn::Meta::List(list))
+            if list.path.is_ident("out") && list.nested.len() == 1 =>
+        {
+            match &list.nested[0] {
+                syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("partial") => {
+                    Ok(Len::Partial)
+                }
+                _ => Err(expected()),
+            }
+        }
+        _ => Err(expected()),
+    }
+}
+
+// The element type of `&[T]` or `&mut [T]`, and whether it is mutable
+fn slice_elem(ty: &syn::Type) -> Option<(&syn::Type, bool)> {
+    match ty {
+        syn::Type::Reference(r) => match &*r.elem {
+            syn::Type::Slice(slice) => Some((&slice.elem, r.mutability.is_some())),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn parse_param(pat: &syn_mid::PatType) -> syn::Result<Param> {
+    let mut len = None;
+
+    for attr in pat.attrs.iter().filter(|attr| is_neon(attr)) {
+        if len.replace((parse_attr(attr)?, attr.span())).is_some() {
+            return Err(syn::Error::new(attr.span(), "duplicate `#[neon(out)]`"));
+        }
+    }
+
+    match (slice_elem(&pat.ty), len) {
+        (Some((elem, true)), Some((len, span))) => Ok(Param::Output(elem.clone(), len, span)),
+        (_, Some(_)) => Err(syn::Error::new(
+            pat.ty.span(),
+            "`#[neon(out)]` requires a `&mut [T]` parameter",
+        )),
+        (Some((_, true)), None) => Err(syn::Error::new(
+            pat.ty.span(),
+            "a `&mut [T]` parameter must be marked `#[neon(out)]`",
+        )),
+        (Some((elem, false)), None) => Ok(Param::Input(elem.clone())),
+        (None, None) => Ok(Param::Value((*pat.ty).clone())),
+    }
+}
+
+// A function with `#[neon(out)]` parameters does not take a context, since JavaScript
+// can't run while its arrays are borrowed. Every parameter is the argument at its
+// position, and the function returns the number of elements it wrote.
+pub(crate) fn expand(
+    options: &Options,
+    attrs: Vec<syn::Attribute>,
+    vis: syn::Visibility,
+    mut sig: syn_mid::Signature,
+    block: Box<syn_mid::Block>,
+) -> syn::Result<TokenStream> {
+    if options.error.is_some() {
+        return Err(syn::Error::new(
+            Span::call_site(),
+            "`error` is not supported with `#[neon(out)]` parameters",
+        ));
+    }
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.generics.span(),
+            "`#[neon(out)]` does not support generic functions",
+        ));
+    }
+
+    if let syn::ReturnType::Default = sig.output {
+        return Err(syn::Error::new(
+            sig.ident.span(),
+            "`#[neon(out)]` requires the function to return the number of elements written",
+        ));
+    }
+
+    let mut params = Vec::new();
+    let mut names = Vec::new();
+
+    for (i, input) in sig.inputs.iter_mut().enumerate() {
+        let pat = match input {
+            syn_mid::FnArg::Typed(pat) => pat,
+            syn_mid::FnArg::Receiver(receiver) => {
+                return Err(syn::Error::new(
+                    receiver.span(),
+                    "`#[neon::function]` does not support methods",
+                ))
+            }
+        };
+
+        params.push(parse_param(pat)?);
+        names.push(match &*pat.pat {
+            syn_mid::Pat::Ident(pat) => pat.ident.to_string().trim_start_matches("r#").to_string(),
+            _ => format!("arguments[{}]", i),
+        });
+
+        // The attribute is only known to this macro
+        pat.attrs.retain(|attr| !is_neon(attr));
+    }
+
+    let cx = syn::Ident::new("__neon_cx", Span::mixed_site());
+    let lock = syn::Ident::new("__neon_lock", Span::mixed_site());
+    let result = syn::Ident::new("__neon_result", Span::mixed_site());
+    let inner = syn::Ident::new("__neon_export", Span::mixed_site());
+    let args = (0..params.len())
+        .map(|i| format_ident!("__neon_arg{}", i))
+        .collect::<Vec<_>>();
+
+    // The length of every `Exact` output is checked against the first input
+    let input = params
+        .iter()
+        .position(|param| matches!(param, Param::Input(_)));
+    let mut extract = Vec::new();
+    let mut checks = Vec::new();
+    let mut borrows = Vec::new();
+    let mut call = Vec::new();
+
+    for ((param, arg), (i, name)) in params.iter().zip(&args).zip(names.iter().enumerate()) {
+        match param {
+            Param::Input(elem) => {
+                extract.push(quote!(
+                    let #arg = neon::macro_internal::typed_array_argument::<#elem>(&mut #cx, #i, #name)?;
+                ));
+                borrows.push(quote!(
+                    let #arg = neon::types::buffer::TypedArray::try_borrow(&*#arg, &#lock)?;
+                ));
+                call.push(quote!(&*#arg));
+            }
+            Param::Output(elem, len, span) => {
+                extract.push(quote!(
+                    let mut #arg = neon::macro_internal::typed_array_argument::<#elem>(&mut #cx, #i, #name)?;
+                ));
+                borrows.push(quote!(
+                    let mut #arg = neon::types::buffer::TypedArray::try_borrow_mut(&mut *#arg, &#lock)?;
+                ));
+                call.push(quote!(&mut *#arg));
+
+                if *len == Len::Partial {
+                    continue;
+                }
+
+                let input = match input {
+                    Some(input) => input,
+                    None => {
+                        return Err(syn::Error::new(
+                            *span,
+                            "`#[neon(out)]` checks the length against the first `&[T]` parameter; use `#[neon(out(partial))]` to accept any length",
+                        ))
+                    }
+                };
+
+                let input_arg = &args[input];
+                let input_name = &names[input];
+
+                checks.push(quote!(
+                    neon::macro_internal::check_len(&mut #cx, (#input_arg, #input_name), (#arg, #name))?;
+                ));
+            }
+            Param::Value(ty) => {
+                extract.push(quote!(
+                    let #arg = neon::macro_internal::extract_argument::<#ty>(&mut #cx, #i, #name)?;
+                ));
+                call.push(quote!(#arg));
+            }
+        }
+    }
+
+    let name = sig.ident.to_string();
+    let prologue = options.prologue(&cx, &name);
+    let body = options.traced(
+        &cx,
+        &name,
+        quote!(
+            #(#extract)*
+            #(#checks)*
+
+            // The arrays are borrowed while the lock keeps JavaScript from running
+            let #result = {
+                let #lock = neon::context::Context::lock(&mut #cx);
+
+                #[allow(clippy::redundant_closure_call)]
+                (|| -> ::std::result::Result<_, neon::types::buffer::BorrowError> {
+                    #(#borrows)*
+                    Ok(#inner(#(#call),*))
+                })()
+            };
+
+            let #result = neon::macro_internal::borrowed(&mut #cx, #result)?;
+
+            neon::macro_internal::written(&mut #cx, #result)
+        ),
+    );
+    let syn_mid::Signature {
+        constness,
+        unsafety,
+        abi,
+        fn_token,
+        ident,
+        inputs,
+        output,
+        ..
+    } = &sig;
+
+    Ok(quote!(
+        #(#attrs) *
+        #vis #fn_token #ident(
+            mut #cx: neon::context::FunctionContext,
+        ) -> neon::result::JsResult<neon::types::JsValue> {
+            #constness #unsafety #abi #fn_token #inner(#inputs) #output
+            #block
+
+            #prologue
+            #body
+        }
+    ))
+}
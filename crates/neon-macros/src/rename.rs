@@ -0,0 +1,41 @@
+//! Parsing of the `rename_all` option. Names are converted by
+//! `neon::macro_internal::RenameRule`, which is shared with the runtime.
+
+use quote::{quote, ToTokens};
+
+#[derive(Clone, Copy)]
+/// A `rename_all` rule
+pub(crate) enum RenameRule {
+    Camel,
+    Pascal,
+    Snake,
+}
+
+impl RenameRule {
+    pub(crate) fn parse(lit: &syn::Lit) -> syn::Result<Self> {
+        let lit = match lit {
+            syn::Lit::Str(lit) => lit,
+            lit => return Err(syn::Error::new(lit.span(), "expected a string")),
+        };
+
+        match lit.value().as_str() {
+            "camelCase" => Ok(Self::Camel),
+            "PascalCase" => Ok(Self::Pascal),
+            "snake_case" => Ok(Self::Snake),
+            _ => Err(syn::Error::new(
+                lit.span(),
+                "expected \"camelCase\", \"PascalCase\" or \"snake_case\"",
+            )),
+        }
+    }
+}
+
+impl ToTokens for RenameRule {
+    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+        tokens.extend(match self {
+            Self::Camel => quote!(neon::macro_internal::RenameRule::Camel),
+            Self::Pascal => quote!(neon::macro_internal::RenameRule::Pascal),
+            Self::Snake => quote!(neon::macro_internal::RenameRule::Snake),
+        });
+    }
+}
@@ -0,0 +1,450 @@
+//! Adapters that implement Rust traits by calling the methods of JavaScript objects.
+//!
+//! JavaScript APIs often accept any object with the right methods, for example, a
+//! sink with `write(chunk)` and `end()`. The [`interface!`] macro declares a Rust
+//! trait for such an interface and a struct that implements it by calling the methods
+//! of a JavaScript object on its JavaScript thread, so the object can be used from
+//! any Rust thread:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::thread;
+//!
+//! use neon::adapt::interface;
+//!
+//! interface! {
+//!     /// A destination for chunks of bytes
+//!     pub trait Sink = "Sink" {
+//!         fn write(&self, chunk: Vec<u8>);
+//!         #[nonblocking]
+//!         fn end(&self);
+//!     }
+//!
+//!     /// A JavaScript object that implements `Sink`
+//!     pub struct JsSink;
+//! }
+//!
+//! fn produce(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let sink = cx.argument::<JsValue>(0)?;
+//!     let sink: Box<dyn Sink + Send> = Box::new(JsSink::new(&mut cx, sink)?);
+//!
+//!     thread::spawn(move || {
+//!         for chunk in [b"hello", b"world"] {
+//!             if sink.write(chunk.to_vec()).is_err() {
+//!                 break;
+//!             }
+//!         }
+//!
+//!         sink.end();
+//!     });
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+//!
+//! Arguments are converted with [`TryIntoJs`](crate::types::extract::TryIntoJs) and
+//! return values with [`TryFromJs`](crate::types::extract::TryFromJs). Like a
+//! [`Channel`], an adapter keeps the event loop running until it is dropped.
+
+use std::{
+    any, error, fmt,
+    sync::Arc,
+    thread::{self, ThreadId},
+};
+
+#[cfg(feature = "futures")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{self, Poll},
+};
+
+use crate::{
+    context::{Context, TaskContext},
+    event::{Channel, JoinHandle},
+    handle::{Handle, Root},
+    object::Object,
+    result::NeonResult,
+    types::{extract::TryFromJs, JsFunction, JsObject, JsValue, Value},
+};
+
+/// Declares a Rust trait for a JavaScript interface and a struct that implements it by
+/// calling the methods of a JavaScript object.
+///
+/// Each method of the trait calls the JavaScript method with the same name. Methods
+/// are blocking by default and return `Result<T, CallError>`, where `T` is the
+/// declared return type, or `()` if there isn't one. They wait for the method to
+/// return on the JavaScript thread and fail instead of blocking if they are called
+/// on that thread. Methods with the `#[nonblocking]` attribute return a
+/// [`PendingCall<T>`] immediately.
+///
+/// The struct has a `new` constructor that throws a `TypeError` listing the missing
+/// methods if the object does not implement the interface. See the
+/// [module docs](crate::adapt) for an example.
+#[doc(inline)]
+pub use crate::__adapt_interface as interface;
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __adapt_interface {
+    (
+        $(#[$trait_attr:meta])*
+        $trait_vis:vis trait $trait:ident = $name:literal {
+            $(
+                $(#[$($method_attr:tt)*])*
+                fn $method:ident(&self $(, $arg:ident: $arg_ty:ty)* $(,)?) $(-> $ret:ty)?;
+            )*
+        }
+
+        $(#[$struct_attr:meta])*
+        $struct_vis:vis struct $struct:ident;
+    ) => {
+        $(#[$trait_attr])*
+        $trait_vis trait $trait {
+            $(
+                $crate::__adapt_interface! {
+                    @signature [$([$($method_attr)*])*] [] [$([$($method_attr)*])*] ($($ret)?)
+                    fn $method(&self $(, $arg: $arg_ty)*)
+                }
+            )*
+        }
+
+        $(#[$struct_attr])*
+        #[derive(Clone)]
+        $struct_vis struct $struct($crate::adapt::Adapter);
+
+        impl $struct {
+            #[doc = concat!(
+                "Adapts `object`, throwing a `TypeError` if it does not implement `",
+                $name,
+                "`",
+            )]
+            $struct_vis fn new<'cx, C: $crate::context::Context<'cx>>(
+                cx: &mut C,
+                object: $crate::handle::Handle<'cx, $crate::types::JsValue>,
+            ) -> $crate::result::NeonResult<Self> {
+                let methods = [$(stringify!($method)),*];
+
+                $crate::adapt::Adapter::new(cx, $name, object, &methods).map(Self)
+            }
+
+            /// The adapter of the JavaScript object
+            $struct_vis fn adapter(&self) -> &$crate::adapt::Adapter {
+                &self.0
+            }
+        }
+
+        impl $trait for $struct {
+            $(
+                fn $method(&self $(, $arg: $arg_ty)*)
+                    -> $crate::__adapt_interface!(
+                        @output [$([$($method_attr)*])*] ($($ret)?)
+                    )
+                {
+                    $crate::__adapt_interface!(
+                        @call [$([$($method_attr)*])*] self, $method, move |cx| {
+                            Ok(vec![$(
+                                $crate::types::extract::TryIntoJs::try_into_js($arg, cx)?
+                                    .upcast::<$crate::types::JsValue>()
+                            ),*])
+                        }
+                    )
+                }
+            )*
+        }
+    };
+
+    // Declares a method of the trait with the attributes other than the mode
+    (@signature $attrs:tt [$($kept:tt)*] [[blocking] $($rest:tt)*] $($tail:tt)*) => {
+        $crate::__adapt_interface! { @signature $attrs [$($kept)*] [$($rest)*] $($tail)* }
+    };
+    (@signature $attrs:tt [$($kept:tt)*] [[nonblocking] $($rest:tt)*] $($tail:tt)*) => {
+        $crate::__adapt_interface! { @signature $attrs [$($kept)*] [$($rest)*] $($tail)* }
+    };
+    (@signature $attrs:tt [$($kept:tt)*] [$attr:tt $($rest:tt)*] $($tail:tt)*) => {
+        $crate::__adapt_interface! { @signature $attrs [$($kept)* $attr] [$($rest)*] $($tail)* }
+    };
+    (@signature $attrs:tt [$($kept:tt)*] [] $ret:tt $($sig:tt)*) => {
+        $(#$kept)*
+        $($sig)* -> $crate::__adapt_interface!(@output $attrs $ret);
+    };
+
+    // The return type of a method, a `PendingCall` if it is `#[nonblocking]`
+    (@output [[nonblocking] $($rest:tt)*] ()) => { $crate::adapt::PendingCall<()> };
+    (@output [[nonblocking] $($rest:tt)*] ($ret:ty)) => { $crate::adapt::PendingCall<$ret> };
+    (@output [$attr:tt $($rest:tt)*] $ret:tt) => {
+        $crate::__adapt_interface!(@output [$($rest)*] $ret)
+    };
+    (@output [] ()) => { ::std::result::Result<(), $crate::adapt::CallError> };
+    (@output [] ($ret:ty)) => { ::std::result::Result<$ret, $crate::adapt::CallError> };
+
+    // The body of a method
+    (@call [[nonblocking] $($rest:tt)*] $this:ident, $method:ident, $args:expr) => {
+        $this.0.call(stringify!($method), $args)
+    };
+    (@call [$attr:tt $($rest:tt)*] $($tail:tt)*) => {
+        $crate::__adapt_interface!(@call [$($rest)*] $($tail)*)
+    };
+    (@call [] $this:ident, $method:ident, $args:expr) => {
+        $this.0.call_blocking(stringify!($method), $args)
+    };
+}
+
+/// A JavaScript object with methods that can be called from any thread, used by the
+/// structs declared with [`interface!`]
+///
+/// The object and its methods are rooted until the adapter and all of its clones are
+/// dropped.
+#[derive(Clone)]
+pub struct Adapter {
+    object: Arc<AdaptedObject>,
+    channel: Channel,
+    thread: ThreadId,
+}
+
+struct AdaptedObject {
+    interface: &'static str,
+    this: Root<JsObject>,
+    methods: Vec<(&'static str, Root<JsFunction>)>,
+}
+
+impl Adapter {
+    /// Adapts `object` as an implementation of `interface`, throwing a `TypeError` if
+    /// it is not an object or if any of `methods` is not a function
+    pub fn new<'cx, C: Context<'cx>>(
+        cx: &mut C,
+        interface: &'static str,
+        object: Handle<'cx, JsValue>,
+        methods: &[&'static str],
+    ) -> NeonResult<Self> {
+        let this = match object.downcast::<JsObject, _>(cx) {
+            Ok(this) => this,
+            Err(_) => return cx.throw_type_error(format!("expected a {} object", interface)),
+        };
+
+        let mut missing = Vec::new();
+        let mut functions = Vec::with_capacity(methods.len());
+
+        for &method in methods {
+            match this.get_value(cx, method)?.downcast::<JsFunction, _>(cx) {
+                Ok(f) => functions.push((method, f.root(cx))),
+                Err(_) => missing.push(method),
+            }
+        }
+
+        if !missing.is_empty() {
+            return cx.throw_type_error(format!(
+                "expected a {} object, missing methods: {}",
+                interface,
+                missing.join(", ")
+            ));
+        }
+
+        Ok(Self {
+            object: Arc::new(AdaptedObject {
+                interface,
+                this: this.root(cx),
+                methods: functions,
+            }),
+            channel: Channel::new(cx),
+            thread: thread::current().id(),
+        })
+    }
+
+    /// The name of the interface the object implements
+    pub fn interface(&self) -> &'static str {
+        self.object.interface
+    }
+
+    /// Schedules a call of `method` on the JavaScript thread without waiting for it.
+    /// `args` creates the arguments of the call on the JavaScript thread.
+    pub fn call<T, A>(&self, method: &'static str, args: A) -> PendingCall<T>
+    where
+        T: for<'cx> TryFromJs<'cx> + Send + 'static,
+        A: for<'cx> FnOnce(&mut TaskContext<'cx>) -> NeonResult<Vec<Handle<'cx, JsValue>>>
+            + Send
+            + 'static,
+    {
+        let object = Arc::clone(&self.object);
+        let handle = self
+            .channel
+            .try_send(move |mut cx| Ok(object.call(&mut cx, method, args)));
+
+        PendingCall {
+            handle: handle.ok(),
+            error: self.error(method, CallErrorKind::Unavailable),
+        }
+    }
+
+    /// Calls `method` on the JavaScript thread and waits for it to return. Fails
+    /// instead of blocking if it is called on the JavaScript thread.
+    pub fn call_blocking<T, A>(&self, method: &'static str, args: A) -> Result<T, CallError>
+    where
+        T: for<'cx> TryFromJs<'cx> + Send + 'static,
+        A: for<'cx> FnOnce(&mut TaskContext<'cx>) -> NeonResult<Vec<Handle<'cx, JsValue>>>
+            + Send
+            + 'static,
+    {
+        if thread::current().id() == self.thread {
+            return Err(self.error(method, CallErrorKind::Blocking));
+        }
+
+        self.call(method, args).join()
+    }
+
+    fn error(&self, method: &'static str, kind: CallErrorKind) -> CallError {
+        CallError {
+            interface: self.object.interface,
+            method,
+            kind,
+        }
+    }
+}
+
+impl AdaptedObject {
+    fn call<'cx, T, A>(
+        &self,
+        cx: &mut TaskContext<'cx>,
+        method: &'static str,
+        args: A,
+    ) -> Result<T, CallError>
+    where
+        T: for<'a> TryFromJs<'a>,
+        A: FnOnce(&mut TaskContext<'cx>) -> NeonResult<Vec<Handle<'cx, JsValue>>>,
+    {
+        let error = |kind| CallError {
+            interface: self.interface,
+            method,
+            kind,
+        };
+
+        let result = cx.try_catch(|cx| {
+            let this = self.this.to_inner(cx);
+            let f = match self.methods.iter().find(|(name, _)| *name == method) {
+                Some((_, f)) => f.to_inner(cx),
+                None => return cx.throw_type_error(format!("unknown method {}", method)),
+            };
+
+            let args = args(cx)?;
+            let value = f.call(cx, this, args)?;
+
+            T::try_from_js(cx, value)
+        });
+
+        match result {
+            Ok(Some(value)) => Ok(value),
+            Ok(None) => Err(error(CallErrorKind::InvalidReturn(any::type_name::<T>()))),
+            Err(exception) => Err(error(CallErrorKind::Threw(describe(cx, exception)))),
+        }
+    }
+}
+
+// Converts an exception to a string for an error that can be sent to another thread
+fn describe<'cx, C: Context<'cx>>(cx: &mut C, exception: Handle<'cx, JsValue>) -> String {
+    cx.try_catch(|cx| Ok(exception.to_string(cx)?.value(cx)))
+        .unwrap_or_else(|_| String::from("<exception cannot be converted to a string>"))
+}
+
+/// A call of a method of an [`Adapter`] scheduled on the JavaScript thread
+///
+/// With the `futures` feature, the call is also a `Future` that completes when the
+/// method returns.
+pub struct PendingCall<T> {
+    handle: Option<JoinHandle<Result<T, CallError>>>,
+    // Returned if the call could not be scheduled or did not complete
+    error: CallError,
+}
+
+impl<T> PendingCall<T> {
+    /// Waits for the method to return
+    ///
+    /// # Panics
+    ///
+    /// This function panics if called within an asynchronous execution context.
+    pub fn join(self) -> Result<T, CallError> {
+        match self.handle {
+            Some(handle) => handle.join().unwrap_or(Err(self.error)),
+            None => Err(self.error),
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T> Future for PendingCall<T> {
+    type Output = Result<T, CallError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        let this = &mut *self;
+
+        match &mut this.handle {
+            Some(handle) => match Pin::new(handle).poll(cx) {
+                Poll::Ready(result) => Poll::Ready(result.unwrap_or(Err(this.error.clone()))),
+                Poll::Pending => Poll::Pending,
+            },
+            None => Poll::Ready(Err(this.error.clone())),
+        }
+    }
+}
+
+/// An error calling a method of an [`Adapter`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CallError {
+    interface: &'static str,
+    method: &'static str,
+    kind: CallErrorKind,
+}
+
+/// The reason a call of a method of an [`Adapter`] failed
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CallErrorKind {
+    /// The method threw an exception, converted to a string
+    Threw(String),
+    /// The method returned a value that cannot be converted to the Rust type with this
+    /// name
+    InvalidReturn(&'static str),
+    /// The call could not be scheduled or did not complete, for example, because the
+    /// JavaScript thread exited
+    Unavailable,
+    /// A blocking method was called on the JavaScript thread, which would deadlock
+    Blocking,
+}
+
+impl CallError {
+    /// The name of the interface
+    pub fn interface(&self) -> &'static str {
+        self.interface
+    }
+
+    /// The name of the method that was called
+    pub fn method(&self) -> &'static str {
+        self.method
+    }
+
+    /// The reason the call failed
+    pub fn kind(&self) -> &CallErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for CallError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{} ", self.interface, self.method)?;
+
+        match &self.kind {
+            CallErrorKind::Threw(exception) => write!(f, "threw an exception: {}", exception),
+            CallErrorKind::InvalidReturn(ty) => {
+                write!(f, "returned a value that cannot be converted to `{}`", ty)
+            }
+            CallErrorKind::Unavailable => {
+                f.write_str("could not be called on the JavaScript thread")
+            }
+            CallErrorKind::Blocking => {
+                f.write_str("cannot be called on the JavaScript thread without blocking it")
+            }
+        }
+    }
+}
+
+impl error::Error for CallError {}
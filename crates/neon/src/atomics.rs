@@ -0,0 +1,341 @@
+//! Synchronizing Rust threads with JavaScript through `Atomics`.
+//!
+//! JavaScript threads coordinate through a `SharedArrayBuffer` with
+//! [`Atomics.wait`](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Atomics/wait)
+//! and `Atomics.notify`. An [`AtomicI32View`] gives Rust threads access to the 32-bit
+//! slots of an `Int32Array` backed by a `SharedArrayBuffer`, and [`store_notify`] and
+//! [`wait_for_change`] take part in the same protocol:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::atomics::{self, AtomicI32View};
+//!
+//! // `native.compute(flags)`, then `Atomics.wait(flags, 0, 0)` in a worker
+//! fn compute(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let flags = cx.argument::<JsInt32Array>(0)?;
+//!     let flags = AtomicI32View::new(&mut cx, flags)?;
+//!
+//!     std::thread::spawn(move || {
+//!         // ... compute ...
+//!
+//!         // Wakes the worker waiting for `flags[0]` to change from `0`
+//!         atomics::store_notify(&flags, 0, 1, u32::MAX).unwrap();
+//!     });
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+//!
+//! ## Memory ordering
+//!
+//! Every access through this module is sequentially consistent, like the `Atomics`
+//! methods. A Rust store observed by `Atomics.load` or `Atomics.wait` happens before
+//! the JavaScript code that observed it, and a JavaScript `Atomics.store` observed by
+//! Rust happens before the Rust code that observed it. Plain writes made before the
+//! store, such as the result of a computation in another slot, are visible after it.
+//!
+//! ## Waking and waiting
+//!
+//! JavaScript waiters are queued by the engine and can only be woken by
+//! `Atomics.notify`. [`store_notify`] stores the value immediately and calls
+//! `Atomics.notify` on the JavaScript thread that created the view, through a
+//! [`Channel`](crate::event::Channel). That thread must not itself be blocked in
+//! `Atomics.wait` on the view; it is usually the main thread, with waiters in workers.
+//!
+//! Rust threads are not known to the engine, so [`wait_for_change`] can't be woken by
+//! `Atomics.notify`: the engine only wakes the waiters it queued itself, and Node-API
+//! has no way to join its queue. It is therefore not a drop-in `Atomics.wait`. Instead,
+//! it observes changes to the value, with a backoff that starts by spinning and ends
+//! sleeping about a millisecond at a time. JavaScript wakes a Rust waiter by changing
+//! the value, e.g., with `Atomics.store`; a notify that does not change the value is
+//! never observed.
+//!
+//! A protocol that must wake both kinds of waiters without a new value can use a slot
+//! as a counter, which is incremented before each notification. Rust waits for the
+//! count it last read to change:
+//!
+//! ```js
+//! // Wakes the Rust threads in `wait_for_change(view, 0, count)` and the JavaScript
+//! // threads in `Atomics.wait(flags, 0, count)`
+//! Atomics.add(flags, 0, 1);
+//! Atomics.notify(flags, 0);
+//! ```
+
+use std::{
+    error::Error,
+    fmt, hint,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    context::Context,
+    event::{Channel, JoinHandle, SendError},
+    handle::{Handle, Managed, Root},
+    object::Object,
+    result::NeonResult,
+    sys,
+    types::{JsFunction, JsInt32Array, JsNumber, JsObject, JsValue},
+};
+
+// Number of checks of the value before `wait` starts yielding and then sleeping
+const SPINS: u32 = 64;
+const YIELDS: u32 = 64;
+
+// Bounds of the sleeps of `wait`
+const MIN_SLEEP: Duration = Duration::from_micros(10);
+const MAX_SLEEP: Duration = Duration::from_millis(1);
+
+/// The error returned when an index is outside of an [`AtomicI32View`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct OutOfBounds {
+    /// The index that was accessed
+    pub index: usize,
+    /// The length of the view
+    pub len: usize,
+}
+
+impl fmt::Display for OutOfBounds {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "index {} is out of bounds for a view of {} elements",
+            self.index, self.len
+        )
+    }
+}
+
+impl Error for OutOfBounds {}
+
+/// The error returned by [`store_notify`]
+#[derive(Debug)]
+pub enum NotifyError {
+    /// The index is outside of the view. Nothing was stored.
+    OutOfBounds(OutOfBounds),
+    /// The value was stored, but `Atomics.notify` could not be scheduled because the
+    /// instance of the addon that created the view was torn down
+    Send(SendError),
+}
+
+impl fmt::Display for NotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::OutOfBounds(err) => fmt::Display::fmt(err, f),
+            Self::Send(_) => f.write_str("failed to schedule Atomics.notify"),
+        }
+    }
+}
+
+impl Error for NotifyError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::OutOfBounds(err) => Some(err),
+            Self::Send(err) => Some(err),
+        }
+    }
+}
+
+impl From<OutOfBounds> for NotifyError {
+    fn from(err: OutOfBounds) -> Self {
+        Self::OutOfBounds(err)
+    }
+}
+
+/// The result of [`wait_for_change`], named like the results of `Atomics.wait`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WaitResult {
+    /// The value changed from `expected` while waiting (`"ok"`)
+    Ok,
+    /// The value was not `expected` when called (`"not-equal"`)
+    NotEqual,
+    /// The value was still `expected` when the timeout elapsed (`"timed-out"`)
+    TimedOut,
+}
+
+/// The 32-bit slots of an `Int32Array` backed by a `SharedArrayBuffer`, accessible
+/// from any thread
+///
+/// The view keeps the buffer alive and can be cloned and sent to other threads.
+/// Accesses are bounds-checked against the length of the array when the view was
+/// created. See the [module documentation](crate::atomics) for details.
+#[derive(Clone)]
+pub struct AtomicI32View {
+    data: *const AtomicI32,
+    len: usize,
+    channel: Channel,
+    // Keeps the buffer alive, and the array is passed to `Atomics.notify`
+    array: Arc<Root<JsInt32Array>>,
+}
+
+// Safety: `data` points into a `SharedArrayBuffer` kept alive by `array`, and is only
+// accessed atomically
+unsafe impl Send for AtomicI32View {}
+unsafe impl Sync for AtomicI32View {}
+
+impl AtomicI32View {
+    /// Creates a view of `array`.
+    ///
+    /// Throws a `TypeError` if `array` is not backed by a `SharedArrayBuffer`, like
+    /// `Atomics.wait`.
+    pub fn new<'a, C: Context<'a>>(cx: &mut C, array: Handle<JsInt32Array>) -> NeonResult<Self> {
+        let info = unsafe { sys::typedarray::info(cx.env().to_raw(), array.to_raw()) };
+        let buffer = Handle::new_internal(JsValue::from_raw(cx.env(), info.buf));
+        let global = cx.global();
+        let shared = global.get::<JsFunction, _, _>(cx, "SharedArrayBuffer")?;
+        let is_shared = unsafe {
+            sys::tag::is_instance_of(cx.env().to_raw(), buffer.to_raw(), shared.to_raw())
+        };
+
+        if is_shared != Some(true) {
+            return cx.throw_type_error("the array must be backed by a SharedArrayBuffer");
+        }
+
+        let mut channel = cx.channel();
+
+        // Pending notifications are still delivered, but the view does not keep the
+        // event loop alive
+        channel.unref(cx);
+
+        Ok(Self {
+            data: info.data as *const AtomicI32,
+            len: info.length,
+            channel,
+            array: Arc::new(array.root(cx)),
+        })
+    }
+
+    /// The number of slots
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the view has no slots
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the slot at `index`, or `None` if it is out of bounds.
+    ///
+    /// Use `Ordering::SeqCst` to match the ordering of `Atomics`.
+    pub fn get(&self, index: usize) -> Option<&AtomicI32> {
+        // Safety: the slot is within the array, which is aligned to 4 bytes
+        (index < self.len).then(|| unsafe { &*self.data.add(index) })
+    }
+
+    fn slot(&self, index: usize) -> Result<&AtomicI32, OutOfBounds> {
+        self.get(index).ok_or(OutOfBounds {
+            index,
+            len: self.len,
+        })
+    }
+}
+
+impl fmt::Debug for AtomicI32View {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("AtomicI32View")
+            .field("len", &self.len)
+            .finish()
+    }
+}
+
+/// Stores `value` at `index` and wakes up to `count` JavaScript waiters on the slot,
+/// like `Atomics.store` followed by `Atomics.notify`.
+///
+/// The value is stored immediately and `Atomics.notify` is called on the JavaScript
+/// thread that created the view. The returned handle can be [joined](JoinHandle::join)
+/// from another thread for the number of woken waiters.
+///
+/// Returns [`NotifyError::Send`], after storing the value, if the instance of the
+/// addon that created the view was torn down, like [`Channel::try_send`].
+pub fn store_notify(
+    view: &AtomicI32View,
+    index: usize,
+    value: i32,
+    count: u32,
+) -> Result<JoinHandle<u32>, NotifyError> {
+    view.slot(index)?.store(value, Ordering::SeqCst);
+
+    let array = Arc::clone(&view.array);
+
+    view.channel
+        .try_send(move |mut cx| {
+            let global = cx.global();
+            let atomics = global.get::<JsObject, _, _>(&mut cx, "Atomics")?;
+            let notify = atomics.get::<JsFunction, _, _>(&mut cx, "notify")?;
+            let array = array.to_inner(&mut cx);
+            let index = cx.number(index as f64);
+            let count = cx.number(count);
+            let woken = notify
+                .call(
+                    &mut cx,
+                    atomics,
+                    [array.upcast::<JsValue>(), index.upcast(), count.upcast()],
+                )?
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?;
+
+            Ok(woken.value(&mut cx) as u32)
+        })
+        .map_err(NotifyError::Send)
+}
+
+/// Blocks the current thread until the value at `index` is no longer `expected`, for
+/// at most `timeout` if it is not `None`. A timeout too large to be represented as a
+/// deadline waits forever.
+///
+/// Unlike `Atomics.wait`, the thread is only woken by a change of the value. An
+/// `Atomics.notify` that does not change the value is never observed; see the
+/// [module documentation](crate::atomics#waking-and-waiting).
+///
+/// # Panics
+///
+/// Panics if called on a JavaScript thread, which must not be blocked.
+pub fn wait_for_change(
+    view: &AtomicI32View,
+    index: usize,
+    expected: i32,
+    timeout: Option<Duration>,
+) -> Result<WaitResult, OutOfBounds> {
+    crate::sync::assert_off_thread("neon::atomics::wait_for_change");
+
+    let slot = view.slot(index)?;
+
+    if slot.load(Ordering::SeqCst) != expected {
+        return Ok(WaitResult::NotEqual);
+    }
+
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout));
+    let mut sleep = MIN_SLEEP;
+    let mut checks = 0;
+
+    loop {
+        if slot.load(Ordering::SeqCst) != expected {
+            return Ok(WaitResult::Ok);
+        }
+
+        let now = Instant::now();
+
+        if deadline.is_some_and(|deadline| now >= deadline) {
+            return Ok(WaitResult::TimedOut);
+        }
+
+        checks += 1;
+
+        if checks <= SPINS {
+            hint::spin_loop();
+        } else if checks <= SPINS + YIELDS {
+            thread::yield_now();
+        } else {
+            let sleep_for = match deadline {
+                Some(deadline) => sleep.min(deadline - now),
+                None => sleep,
+            };
+
+            thread::sleep(sleep_for);
+            sleep = (sleep * 2).min(MAX_SLEEP);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Optional features of an addon that may be unavailable at runtime.
+//!
+//! An addon may optionally depend on a system library or hardware feature. Instead of
+//! failing to load when it is missing, the addon can probe for it during initialization,
+//! register the result with
+//! [`ModuleContext::export_capabilities`](crate::context::ModuleContext::export_capabilities)
+//! and only fail the functions that require it.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! # fn vips_is_installed() -> bool { false }
+//! #[neon::function(requires = "vips")]
+//! fn resize(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     // e.g., `unsafe { ffi::vips_resize(..) }`
+//!     Ok(cx.undefined())
+//! }
+//!
+//! #[neon::main]
+//! fn main(mut cx: ModuleContext) -> NeonResult<()> {
+//!     cx.export_capabilities([("vips", vips_is_installed())])?;
+//!     cx.export_function("resize", resize)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! JavaScript can check `addon.capabilities.vips` before calling `addon.resize()`.
+//! Otherwise, the call throws an `Error` with a `code` of `"ERR_NEON_MISSING_CAPABILITY"`
+//! and a `capability` property naming the missing capability.
+//!
+//! Capabilities are registered separately for each instance of the addon. A capability
+//! that was never registered is unavailable, so a misspelled name fails every call
+//! instead of silently skipping the check.
+
+use std::collections::HashMap;
+
+use crate::{
+    context::Context,
+    lifecycle::InstanceData,
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{JsError, JsFunction, JsObject, JsValue},
+};
+
+/// The `code` of the error thrown when a required capability is unavailable
+pub const MISSING_CAPABILITY: &str = "ERR_NEON_MISSING_CAPABILITY";
+
+#[derive(Default)]
+/// Capabilities registered by an instance of the addon
+pub(crate) struct Registry(HashMap<String, bool>);
+
+/// Returns whether the capability `name` was registered as available, or `None` if
+/// it was never registered.
+pub fn is_available<'a, C: Context<'a>>(cx: &mut C, name: &str) -> Option<bool> {
+    InstanceData::capabilities(cx).0.get(name).copied()
+}
+
+/// Throws an `Error` with a `code` of [`MISSING_CAPABILITY`] unless the capability
+/// `name` was registered as available.
+///
+/// This is the check performed by functions marked with
+/// [`#[neon::function(requires = "...")]`](crate::export).
+pub fn require<'a, C: Context<'a>>(cx: &mut C, name: &str) -> NeonResult<()> {
+    let reason = match is_available(cx, name) {
+        Some(true) => return Ok(()),
+        Some(false) => "is not available",
+        None => "was not registered",
+    };
+
+    let err = missing_capability_error(cx, name, reason)?;
+
+    cx.throw(err)
+}
+
+/// Registers the capabilities and returns a frozen object of `{ [name]: available }`
+pub(crate) fn register<'a, C, I, K>(cx: &mut C, capabilities: I) -> JsResult<'a, JsObject>
+where
+    C: Context<'a>,
+    I: IntoIterator<Item = (K, bool)>,
+    K: AsRef<str>,
+{
+    let o = cx.empty_object();
+
+    for (name, available) in capabilities {
+        let name = name.as_ref();
+        let value = cx.boolean(available);
+
+        o.set(cx, name, value)?;
+        InstanceData::capabilities(cx)
+            .0
+            .insert(name.to_string(), available);
+    }
+
+    let global = cx.global();
+    let freeze = global
+        .get::<JsFunction, _, _>(cx, "Object")?
+        .get::<JsFunction, _, _>(cx, "freeze")?;
+
+    freeze.exec(cx, global, [o.upcast::<JsValue>()])?;
+
+    Ok(o)
+}
+
+fn missing_capability_error<'a, C: Context<'a>>(
+    cx: &mut C,
+    name: &str,
+    reason: &str,
+) -> JsResult<'a, JsError> {
+    let err = JsError::error(cx, format!("capability `{}` {}", name, reason))?;
+    let code = cx.string(MISSING_CAPABILITY);
+    let capability = cx.string(name);
+
+    err.set(cx, "code", code)?;
+    err.set(cx, "capability", capability)?;
+
+    Ok(err)
+}
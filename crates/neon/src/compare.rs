@@ -0,0 +1,788 @@
+//! Structural comparison of JavaScript values.
+//!
+//! [`deep_equals`] tests whether two values are structurally equal without
+//! round-tripping through JSON, which loses `undefined`, `Date`, typed arrays
+//! and collections. [`deep_diff`] reports each difference between two values,
+//! which is useful for debugging.
+//!
+//! Objects are compared by their own enumerable string keys. Cyclic values are
+//! supported; a pair of objects that is encountered again while it is already
+//! being compared is assumed to be equal.
+//!
+//! Boxed primitives, e.g., `new Number(1)`, are also compared by the result of
+//! `valueOf()`, and regular expressions by their `source` and `flags`. The elements
+//! of `Float32Array` and `Float64Array` are compared as numbers, following the
+//! [`Options`] for `NaN` and `-0`; other typed arrays, `DataView` and `ArrayBuffer`
+//! are compared byte by byte.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::compare::{deep_equals, Options};
+//!
+//! fn is_same(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+//!     let a = cx.argument::<JsValue>(0)?;
+//!     let b = cx.argument::<JsValue>(1)?;
+//!     let equal = deep_equals(&mut cx, a, b, Options::default())?;
+//!
+//!     Ok(cx.boolean(equal))
+//! }
+//! ```
+
+use std::{collections::HashSet, convert::TryInto};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    limits,
+    object::Object,
+    result::{JsResult, NeonResult},
+    sys::{self, TypedArrayType},
+    types::{JsArray, JsFunction, JsNumber, JsObject, JsString, JsValue, Value},
+};
+
+/// Options controlling the behavior of [`deep_equals`] and [`deep_diff`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// `NaN` is equal to `NaN`. _Default: `true`_
+    pub nan_equal: bool,
+    /// `-0` is not equal to `+0`. _Default: `false`_
+    pub distinguish_negative_zero: bool,
+    /// `Map` and `Set` are compared by their entries. If `false`, collections are
+    /// compared by identity. _Default: `true`_
+    pub compare_collections: bool,
+    /// Typed arrays, `DataView` and `ArrayBuffer` are compared by their contents. If
+    /// `false`, they are compared by identity. _Default: `true`_
+    pub compare_typed_arrays: bool,
+    /// Objects must have the same prototype. _Default: `false`_
+    pub compare_prototypes: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            nan_equal: true,
+            distinguish_negative_zero: false,
+            compare_collections: true,
+            compare_typed_arrays: true,
+            compare_prototypes: false,
+        }
+    }
+}
+
+/// Tests whether two JavaScript values are structurally equal.
+///
+/// Throws if reading a property of either value throws.
+pub fn deep_equals<'a, C, A, B>(
+    cx: &mut C,
+    a: Handle<'a, A>,
+    b: Handle<'a, B>,
+    options: Options,
+) -> NeonResult<bool>
+where
+    C: Context<'a>,
+    A: Value,
+    B: Value,
+{
+    Comparer::new(cx, options, false)?.compare(a.upcast(), b.upcast())
+}
+
+/// Reports the differences between two JavaScript values.
+///
+/// Returns an array of `{ path, left, right }` records, where `path` is an array
+/// of property keys (or the keys of `Map` entries) leading to the difference.
+/// Values that are missing on one side are reported as `undefined`. An empty
+/// array indicates the values are equal with the default [`Options`].
+pub fn deep_diff<'a, C, A, B>(
+    cx: &mut C,
+    a: Handle<'a, A>,
+    b: Handle<'a, B>,
+) -> JsResult<'a, JsArray>
+where
+    C: Context<'a>,
+    A: Value,
+    B: Value,
+{
+    let mut comparer = Comparer::new(cx, Options::default(), true)?;
+
+    comparer.compare(a.upcast(), b.upcast())?;
+
+    let Comparer { cx, diffs, .. } = comparer;
+    let diffs = diffs.unwrap_or_default();
+    let arr = JsArray::new(cx, diffs.len() as u32);
+
+    for (i, diff) in diffs.into_iter().enumerate() {
+        let record = cx.empty_object();
+        let path = JsArray::new(cx, diff.path.len() as u32);
+
+        for (j, segment) in diff.path.into_iter().enumerate() {
+            path.set(cx, j as u32, segment)?;
+        }
+
+        record.set(cx, "path", path)?;
+        record.set(cx, "left", diff.left)?;
+        record.set(cx, "right", diff.right)?;
+        arr.set(cx, i as u32, record)?;
+    }
+
+    Ok(arr)
+}
+
+struct Diff<'a> {
+    path: Vec<Handle<'a, JsValue>>,
+    left: Handle<'a, JsValue>,
+    right: Handle<'a, JsValue>,
+}
+
+// Builtin functions used for reflection
+struct Builtins<'a> {
+    keys: Handle<'a, JsFunction>,
+    get_prototype_of: Handle<'a, JsFunction>,
+    to_string: Handle<'a, JsFunction>,
+    array_from: Handle<'a, JsFunction>,
+}
+
+struct Comparer<'a, 'c, C: Context<'a>> {
+    cx: &'c mut C,
+    options: Options,
+    builtins: Builtins<'a>,
+    // Pairs of objects currently being compared, used to detect cycles
+    stack: Vec<(Handle<'a, JsObject>, Handle<'a, JsObject>)>,
+    path: Vec<Handle<'a, JsValue>>,
+    // `None` if only testing for equality
+    diffs: Option<Vec<Diff<'a>>>,
+}
+
+impl<'a, 'c, C: Context<'a>> Comparer<'a, 'c, C> {
+    fn new(cx: &'c mut C, options: Options, diff: bool) -> NeonResult<Self> {
+        let global = cx.global();
+        let object = global.get::<JsFunction, _, _>(cx, "Object")?;
+        let array = global.get::<JsFunction, _, _>(cx, "Array")?;
+        let builtins = Builtins {
+            keys: object.get(cx, "keys")?,
+            get_prototype_of: object.get(cx, "getPrototypeOf")?,
+            to_string: object
+                .get::<JsObject, _, _>(cx, "prototype")?
+                .get(cx, "toString")?,
+            array_from: array.get(cx, "from")?,
+        };
+
+        Ok(Self {
+            cx,
+            options,
+            builtins,
+            stack: Vec::new(),
+            path: Vec::new(),
+            diffs: if diff { Some(Vec::new()) } else { None },
+        })
+    }
+
+    // Record a difference at the current path. Always returns `false`.
+    fn differ(&mut self, left: Handle<'a, JsValue>, right: Handle<'a, JsValue>) -> bool {
+        if let Some(diffs) = &mut self.diffs {
+            diffs.push(Diff {
+                path: self.path.clone(),
+                left,
+                right,
+            });
+        }
+
+        false
+    }
+
+    // Record a difference at a child of the current path
+    fn differ_at(
+        &mut self,
+        segment: Handle<'a, JsValue>,
+        left: Handle<'a, JsValue>,
+        right: Handle<'a, JsValue>,
+    ) -> bool {
+        self.path.push(segment);
+        self.differ(left, right);
+        self.path.pop();
+
+        false
+    }
+
+    fn is_diffing(&self) -> bool {
+        self.diffs.is_some()
+    }
+
+    // Compare without recording differences
+    fn equals(&mut self, a: Handle<'a, JsValue>, b: Handle<'a, JsValue>) -> NeonResult<bool> {
+        let diffs = self.diffs.take();
+        let result = self.compare(a, b);
+
+        self.diffs = diffs;
+        result
+    }
+
+    fn compare_at(
+        &mut self,
+        segment: Handle<'a, JsValue>,
+        a: Handle<'a, JsValue>,
+        b: Handle<'a, JsValue>,
+    ) -> NeonResult<bool> {
+        self.path.push(segment);
+        let result = self.compare(a, b);
+        self.path.pop();
+
+        result
+    }
+
+    fn compare(&mut self, a: Handle<'a, JsValue>, b: Handle<'a, JsValue>) -> NeonResult<bool> {
+        if let (Ok(x), Ok(y)) = (
+            a.downcast::<JsNumber, _>(self.cx),
+            b.downcast::<JsNumber, _>(self.cx),
+        ) {
+            let x = x.value(self.cx);
+            let y = y.value(self.cx);
+
+            return Ok(self.numbers_equal(x, y) || self.differ(a, b));
+        }
+
+        if a.strict_equals(self.cx, b) {
+            return Ok(true);
+        }
+
+        let (x, y) = match (
+            a.downcast::<JsObject, _>(self.cx),
+            b.downcast::<JsObject, _>(self.cx),
+        ) {
+            (Ok(x), Ok(y)) => (x, y),
+            _ => return Ok(self.differ(a, b)),
+        };
+
+        let cx = &mut *self.cx;
+        let in_progress = self
+            .stack
+            .iter()
+            .any(|(l, r)| l.strict_equals(cx, x) && r.strict_equals(cx, y));
+
+        if in_progress {
+            return Ok(true);
+        }
+
+        let _guard = limits::enter(self.cx)?;
+
+        self.stack.push((x, y));
+        let result = self.compare_objects(x, y);
+        self.stack.pop();
+
+        result
+    }
+
+    fn numbers_equal(&self, x: f64, y: f64) -> bool {
+        if x.is_nan() && y.is_nan() {
+            return self.options.nan_equal;
+        }
+
+        if x != y {
+            return false;
+        }
+
+        !self.options.distinguish_negative_zero || x.is_sign_negative() == y.is_sign_negative()
+    }
+
+    fn compare_objects(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+    ) -> NeonResult<bool> {
+        let tag = self.tag(a)?;
+
+        if tag != self.tag(b)? {
+            return Ok(self.differ(a.upcast(), b.upcast()));
+        }
+
+        if self.options.compare_prototypes {
+            let this = self.cx.undefined();
+            let getter = self.builtins.get_prototype_of;
+            let x = getter.call(self.cx, this, [a.upcast()])?;
+            let y = getter.call(self.cx, this, [b.upcast()])?;
+
+            if !x.strict_equals(self.cx, y) {
+                return Ok(self.differ(a.upcast(), b.upcast()));
+            }
+        }
+
+        let env = self.cx.env().to_raw();
+        let is_bytes = unsafe {
+            (sys::tag::is_typedarray(env, a.to_raw()) && sys::tag::is_typedarray(env, b.to_raw()))
+                || (sys::tag::is_arraybuffer(env, a.to_raw())
+                    && sys::tag::is_arraybuffer(env, b.to_raw()))
+                || (sys::tag::is_dataview(env, a.to_raw())
+                    && sys::tag::is_dataview(env, b.to_raw()))
+        };
+
+        if is_bytes {
+            if !self.options.compare_typed_arrays {
+                return Ok(self.differ(a.upcast(), b.upcast()));
+            }
+
+            return self.compare_bytes(a, b);
+        }
+
+        match tag.as_str() {
+            "[object Map]" | "[object Set]" if !self.options.compare_collections => {
+                Ok(self.differ(a.upcast(), b.upcast()))
+            }
+            "[object Map]" => self.compare_maps(a, b),
+            "[object Set]" => self.compare_sets(a, b),
+            "[object Date]" => {
+                let x = a
+                    .call_method_with(self.cx, "getTime")?
+                    .apply::<JsNumber, _>(self.cx)?;
+                let y = b
+                    .call_method_with(self.cx, "getTime")?
+                    .apply::<JsNumber, _>(self.cx)?;
+                let x = x.value(self.cx);
+                let y = y.value(self.cx);
+
+                // Invalid dates are equal to each other
+                Ok(x == y || (x.is_nan() && y.is_nan()) || self.differ(a.upcast(), b.upcast()))
+            }
+            // `name` and `message` are typically not enumerable
+            "[object Error]" => self.compare_properties(a, b, &["name", "message"]),
+            // `source` and `flags` are getters of `RegExp.prototype`
+            "[object RegExp]" => self.compare_properties(a, b, &["source", "flags"]),
+            "[object Number]" => self.compare_boxed(a, b, "Number"),
+            "[object String]" => self.compare_boxed(a, b, "String"),
+            "[object Boolean]" => self.compare_boxed(a, b, "Boolean"),
+            "[object BigInt]" => self.compare_boxed(a, b, "BigInt"),
+            "[object Symbol]" => self.compare_boxed(a, b, "Symbol"),
+            "[object Array]" => self.compare_keys(a, b, true),
+            _ => self.compare_keys(a, b, false),
+        }
+    }
+
+    // Compare the named properties, which may not be enumerable, and then the keys
+    fn compare_properties(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+        keys: &[&str],
+    ) -> NeonResult<bool> {
+        let mut equal = true;
+
+        for &key in keys {
+            let x = a.get_value(self.cx, key)?;
+            let y = b.get_value(self.cx, key)?;
+            let segment = self.cx.string(key).upcast();
+
+            if !self.compare_at(segment, x, y)? {
+                equal = false;
+
+                if !self.is_diffing() {
+                    return Ok(false);
+                }
+            }
+        }
+
+        Ok(self.compare_keys(a, b, false)? && equal)
+    }
+
+    // Compare boxed primitives by their primitive values and then the keys. The tag
+    // can be faked with `Symbol.toStringTag`, so objects that `valueOf` of the
+    // prototype of `constructor` rejects are only compared by their keys.
+    fn compare_boxed(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+        constructor: &str,
+    ) -> NeonResult<bool> {
+        let global = self.cx.global();
+        let value_of = global
+            .get::<JsFunction, _, _>(self.cx, constructor)?
+            .get::<JsObject, _, _>(self.cx, "prototype")?
+            .get::<JsFunction, _, _>(self.cx, "valueOf")?;
+        let values = self.cx.try_catch(|cx| {
+            let x = value_of.call(cx, a, [])?;
+            let y = value_of.call(cx, b, [])?;
+
+            Ok((x, y))
+        });
+
+        let equal = match values {
+            Ok((x, y)) => self.compare(x, y)?,
+            Err(_) => true,
+        };
+
+        if !equal && !self.is_diffing() {
+            return Ok(false);
+        }
+
+        Ok(self.compare_keys(a, b, false)? && equal)
+    }
+
+    // Returns the `Object.prototype.toString` tag, e.g., `[object Map]`
+    fn tag(&mut self, v: Handle<'a, JsObject>) -> NeonResult<String> {
+        let tag = self
+            .builtins
+            .to_string
+            .call(self.cx, v, [])?
+            .downcast_or_throw::<JsString, _>(self.cx)?;
+
+        Ok(tag.value(self.cx))
+    }
+
+    fn own_keys(&mut self, v: Handle<'a, JsObject>) -> NeonResult<Vec<String>> {
+        let this = self.cx.undefined();
+        let keys = self
+            .builtins
+            .keys
+            .call(self.cx, this, [v.upcast()])?
+            .downcast_or_throw::<JsArray, _>(self.cx)?
+            .to_vec(self.cx)?;
+
+        keys.into_iter()
+            .map(|key| {
+                Ok(key
+                    .downcast_or_throw::<JsString, _>(self.cx)?
+                    .value(self.cx))
+            })
+            .collect()
+    }
+
+    fn segment(&mut self, key: &str, is_array: bool) -> Handle<'a, JsValue> {
+        match key.parse::<u32>() {
+            Ok(i) if is_array && i.to_string() == key => self.cx.number(i).upcast(),
+            _ => self.cx.string(key).upcast(),
+        }
+    }
+
+    fn compare_keys(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+        is_array: bool,
+    ) -> NeonResult<bool> {
+        let keys_a = self.own_keys(a)?;
+        let keys_b = self.own_keys(b)?;
+
+        // Sets of the keys, so that objects with many keys are compared in linear time
+        let set_a = keys_a.iter().map(String::as_str).collect::<HashSet<_>>();
+        let set_b = keys_b.iter().map(String::as_str).collect::<HashSet<_>>();
+        let mut equal = true;
+
+        if is_array {
+            let x = a.get_value(self.cx, "length")?;
+            let y = b.get_value(self.cx, "length")?;
+
+            if !x.strict_equals(self.cx, y) {
+                let segment = self.cx.string("length").upcast();
+
+                equal = self.differ_at(segment, x, y);
+
+                if !self.is_diffing() {
+                    return Ok(false);
+                }
+            }
+        }
+
+        for key in keys_a.iter() {
+            let segment = self.segment(key, is_array);
+            let x = a.get_value(self.cx, key.as_str())?;
+
+            let same = if set_b.contains(key.as_str()) {
+                let y = b.get_value(self.cx, key.as_str())?;
+
+                self.compare_at(segment, x, y)?
+            } else {
+                let y = self.cx.undefined().upcast();
+
+                self.differ_at(segment, x, y)
+            };
+
+            if !same {
+                equal = false;
+
+                if !self.is_diffing() {
+                    return Ok(false);
+                }
+            }
+        }
+
+        for key in keys_b.iter().filter(|key| !set_a.contains(key.as_str())) {
+            let segment = self.segment(key, is_array);
+            let x = self.cx.undefined().upcast();
+            let y = b.get_value(self.cx, key.as_str())?;
+
+            equal = self.differ_at(segment, x, y);
+
+            if !self.is_diffing() {
+                return Ok(false);
+            }
+        }
+
+        Ok(equal)
+    }
+
+    // Converts a collection to an array with `Array.from`
+    fn entries(&mut self, v: Handle<'a, JsObject>) -> NeonResult<Vec<Handle<'a, JsValue>>> {
+        let this = self.cx.undefined();
+
+        self.builtins
+            .array_from
+            .call(self.cx, this, [v.upcast()])?
+            .downcast_or_throw::<JsArray, _>(self.cx)?
+            .to_vec(self.cx)
+    }
+
+    fn compare_maps(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+    ) -> NeonResult<bool> {
+        let entries_a = self.pairs(a)?;
+        let entries_b = self.pairs(b)?;
+
+        if !self.is_diffing() && entries_a.len() != entries_b.len() {
+            return Ok(false);
+        }
+
+        let mut used = vec![false; entries_b.len()];
+        let mut equal = true;
+
+        for (key, x) in entries_a {
+            // Prefer an entry where both the key and the value are equal, falling
+            // back to the first entry with an equal key
+            let mut matched = None;
+
+            for (i, (other, y)) in entries_b.iter().enumerate() {
+                if used[i] || !self.equals(key, *other)? {
+                    continue;
+                }
+
+                if self.equals(x, *y)? {
+                    matched = Some(i);
+                    break;
+                }
+
+                matched.get_or_insert(i);
+            }
+
+            let same = match matched {
+                Some(i) => {
+                    used[i] = true;
+                    self.compare_at(key, x, entries_b[i].1)?
+                }
+                None => {
+                    let y = self.cx.undefined().upcast();
+
+                    self.differ_at(key, x, y)
+                }
+            };
+
+            if !same {
+                equal = false;
+
+                if !self.is_diffing() {
+                    return Ok(false);
+                }
+            }
+        }
+
+        for (i, (key, y)) in entries_b.into_iter().enumerate() {
+            if !used[i] {
+                let x = self.cx.undefined().upcast();
+
+                equal = self.differ_at(key, x, y);
+            }
+        }
+
+        Ok(equal)
+    }
+
+    // Map entries as `(key, value)` pairs
+    fn pairs(
+        &mut self,
+        map: Handle<'a, JsObject>,
+    ) -> NeonResult<Vec<(Handle<'a, JsValue>, Handle<'a, JsValue>)>> {
+        self.entries(map)?
+            .into_iter()
+            .map(|entry| {
+                let entry = entry.downcast_or_throw::<JsArray, _>(self.cx)?;
+                let key = entry.get_value(self.cx, 0)?;
+                let value = entry.get_value(self.cx, 1)?;
+
+                Ok((key, value))
+            })
+            .collect()
+    }
+
+    fn compare_sets(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+    ) -> NeonResult<bool> {
+        let values_a = self.entries(a)?;
+        let values_b = self.entries(b)?;
+
+        if !self.is_diffing() && values_a.len() != values_b.len() {
+            return Ok(false);
+        }
+
+        let mut used = vec![false; values_b.len()];
+        let mut equal = true;
+
+        for x in values_a {
+            let mut matched = None;
+
+            for (i, y) in values_b.iter().enumerate() {
+                if !used[i] && self.equals(x, *y)? {
+                    matched = Some(i);
+                    break;
+                }
+            }
+
+            match matched {
+                Some(i) => used[i] = true,
+                None => {
+                    let y = self.cx.undefined().upcast();
+
+                    equal = self.differ(x, y);
+
+                    if !self.is_diffing() {
+                        return Ok(false);
+                    }
+                }
+            }
+        }
+
+        for (i, y) in values_b.into_iter().enumerate() {
+            if !used[i] {
+                let x = self.cx.undefined().upcast();
+
+                equal = self.differ(x, y);
+            }
+        }
+
+        Ok(equal)
+    }
+
+    // Compare the contents of typed arrays or array buffers
+    fn compare_bytes(
+        &mut self,
+        a: Handle<'a, JsObject>,
+        b: Handle<'a, JsObject>,
+    ) -> NeonResult<bool> {
+        let env = self.cx.env().to_raw();
+        let (x, y) = unsafe { (Bytes::new(env, a.to_raw()), Bytes::new(env, b.to_raw())) };
+
+        if x.typ != y.typ || x.len() != y.len() {
+            return Ok(self.differ(a.upcast(), b.upcast()));
+        }
+
+        let size = x.element_size();
+        let mut equal = true;
+
+        for (i, (l, r)) in x.bytes.chunks(size).zip(y.bytes.chunks(size)).enumerate() {
+            if self.elements_equal(x.typ, l, r) {
+                continue;
+            }
+
+            equal = false;
+
+            if !self.is_diffing() {
+                return Ok(false);
+            }
+
+            let segment = self.cx.number(i as f64).upcast();
+            let (left, right) = if x.typ.is_none() {
+                (self.cx.number(l[0]).upcast(), self.cx.number(r[0]).upcast())
+            } else {
+                (
+                    a.get_value(self.cx, i as u32)?,
+                    b.get_value(self.cx, i as u32)?,
+                )
+            };
+
+            self.differ_at(segment, left, right);
+        }
+
+        Ok(equal)
+    }
+
+    // Compare the bytes of an element, as a number for floating point elements
+    fn elements_equal(&self, typ: Option<TypedArrayType>, l: &[u8], r: &[u8]) -> bool {
+        match typ {
+            Some(TypedArrayType::F32) => {
+                let x = f32::from_ne_bytes(l.try_into().unwrap());
+                let y = f32::from_ne_bytes(r.try_into().unwrap());
+
+                self.numbers_equal(x.into(), y.into())
+            }
+            Some(TypedArrayType::F64) => {
+                let x = f64::from_ne_bytes(l.try_into().unwrap());
+                let y = f64::from_ne_bytes(r.try_into().unwrap());
+
+                self.numbers_equal(x, y)
+            }
+            _ => l == r,
+        }
+    }
+}
+
+// Contents of a typed array, data view or array buffer
+struct Bytes<'a> {
+    // `None` for an `ArrayBuffer` or a `DataView`
+    typ: Option<TypedArrayType>,
+    bytes: &'a [u8],
+}
+
+impl<'a> Bytes<'a> {
+    // Safety: `value` must be a typed array, data view or array buffer and the
+    // returned slice must not be held across calls into JavaScript
+    unsafe fn new(env: sys::raw::Env, value: sys::raw::Local) -> Self {
+        if sys::tag::is_arraybuffer(env, value) {
+            return Self {
+                typ: None,
+                bytes: sys::arraybuffer::as_mut_slice(env, value),
+            };
+        }
+
+        if sys::tag::is_dataview(env, value) {
+            let (data, len) = sys::typedarray::dataview_data(env, value);
+            let bytes = if len == 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(data.cast(), len)
+            };
+
+            return Self { typ: None, bytes };
+        }
+
+        let info = sys::typedarray::info(env, value);
+        let len = info.length * element_size(Some(info.typ));
+        let bytes = if len == 0 {
+            &[]
+        } else {
+            std::slice::from_raw_parts(info.data.cast(), len)
+        };
+
+        Self {
+            typ: Some(info.typ),
+            bytes,
+        }
+    }
+
+    fn element_size(&self) -> usize {
+        element_size(self.typ)
+    }
+
+    fn len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+fn element_size(typ: Option<TypedArrayType>) -> usize {
+    match typ {
+        None
+        | Some(TypedArrayType::I8)
+        | Some(TypedArrayType::U8)
+        | Some(TypedArrayType::U8Clamped) => 1,
+        Some(TypedArrayType::I16) | Some(TypedArrayType::U16) => 2,
+        Some(TypedArrayType::I32) | Some(TypedArrayType::U32) | Some(TypedArrayType::F32) => 4,
+        Some(TypedArrayType::F64) | Some(TypedArrayType::I64) | Some(TypedArrayType::U64) => 8,
+    }
+}
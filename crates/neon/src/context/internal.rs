@@ -1,11 +1,21 @@
-use std::{cell::RefCell, ffi::c_void, mem::MaybeUninit};
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    ffi::c_void,
+    fmt,
+    mem::MaybeUninit,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr,
+    time::Instant,
+};
 
 use crate::{
-    context::ModuleContext,
+    context::{Context, ModuleContext},
     handle::{Handle, Managed},
-    result::NeonResult,
+    object::Object,
+    result::{NeonResult, Throw},
     sys::{self, raw},
-    types::JsObject,
+    types::{JsError, JsObject, JsString},
 };
 
 #[repr(C)]
@@ -48,25 +58,300 @@ impl Env {
 
 pub trait ContextInternal<'a>: Sized {
     fn env(&self) -> Env;
+    fn constants(&mut self) -> &mut Constants;
+}
+
+// Integers from `MIN_CACHED_INTEGER` to `MAX_CACHED_INTEGER` are cached, like the small
+// integers stored without allocating by engines
+const MIN_CACHED_INTEGER: i32 = -1;
+const MAX_CACHED_INTEGER: i32 = 256;
+
+// Slots of the table of constants: `undefined`, `null`, `false`, `true`, then integers
+const UNDEFINED: usize = 0;
+const NULL: usize = 1;
+const BOOLEANS: usize = 2;
+const INTEGERS: usize = 4;
+const CACHED_CONSTANTS: usize = INTEGERS + (MAX_CACHED_INTEGER - MIN_CACHED_INTEGER + 1) as usize;
+
+thread_local! {
+    // Generation of the last context created on this thread
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+
+    // Each constant with the generation of the context that created it. Generations
+    // start at `1`, so no slot is filled initially.
+    static CACHE: RefCell<[(u64, raw::Local); CACHED_CONSTANTS]> =
+        const { RefCell::new([(0, ptr::null_mut()); CACHED_CONSTANTS]) };
+}
+
+/// Handles to `undefined`, `null`, booleans and small integers created by a context,
+/// reused instead of creating a new handle each time.
+///
+/// A handle is only valid in the scope it was created in, and Node-API only allows
+/// persistent references to objects, so constants can't be shared between scopes.
+/// Each context is given a new generation instead, and the handles are cached in a
+/// table per thread, stamped with the generation of the context that created them. A
+/// context only reuses the handles of its own generation, so it recreates a constant
+/// that was replaced by a nested context, and creating a context does not allocate or
+/// clear the table. Generations are never reused, so instances of the module loaded
+/// on the same thread never see each other's handles.
+pub struct Constants {
+    generation: u64,
+}
+
+impl Default for Constants {
+    fn default() -> Self {
+        let generation = GENERATION.with(|generation| {
+            let next = generation.get() + 1;
+
+            generation.set(next);
+            next
+        });
+
+        Self { generation }
+    }
+}
+
+impl Constants {
+    // Returns the constant in `slot` if it was created by this context, or creates it
+    fn get(&mut self, slot: usize, create: impl FnOnce() -> raw::Local) -> raw::Local {
+        CACHE.with(|cache| {
+            let mut cache = cache.borrow_mut();
+            let (generation, local) = &mut cache[slot];
+
+            if *generation != self.generation {
+                *local = create();
+                *generation = self.generation;
+            }
+
+            *local
+        })
+    }
+
+    pub(crate) fn undefined(&mut self, env: Env) -> raw::Local {
+        self.get(UNDEFINED, || unsafe {
+            let mut local: raw::Local = std::mem::zeroed();
+            sys::primitive::undefined(&mut local, env.to_raw());
+            local
+        })
+    }
+
+    pub(crate) fn null(&mut self, env: Env) -> raw::Local {
+        self.get(NULL, || unsafe {
+            let mut local: raw::Local = std::mem::zeroed();
+            sys::primitive::null(&mut local, env.to_raw());
+            local
+        })
+    }
+
+    pub(crate) fn boolean(&mut self, env: Env, b: bool) -> raw::Local {
+        self.get(BOOLEANS + b as usize, || unsafe {
+            let mut local: raw::Local = std::mem::zeroed();
+            sys::primitive::boolean(&mut local, env.to_raw(), b);
+            local
+        })
+    }
+
+    pub(crate) fn number(&mut self, env: Env, v: f64) -> raw::Local {
+        let create = || unsafe {
+            let mut local: raw::Local = std::mem::zeroed();
+            sys::primitive::number(&mut local, env.to_raw(), v);
+            local
+        };
+
+        // `-0` is not an integer, it must be created to keep its sign
+        let is_cached = v.fract() == 0.0
+            && (MIN_CACHED_INTEGER as f64..=MAX_CACHED_INTEGER as f64).contains(&v)
+            && !(v == 0.0 && v.is_sign_negative());
+
+        if !is_cached {
+            return create();
+        }
+
+        self.get(INTEGERS + (v as i32 - MIN_CACHED_INTEGER) as usize, create)
+    }
+}
+
+/// The environment variable that enables tracing the phases of the initialization of
+/// a module to stderr, when it is set to anything other than `""` or `"0"`
+const INIT_TRACE: &str = "NEON_INIT_TRACE";
+
+/// The phases of the initialization of a module, named in the errors thrown for
+/// panics and in the trace
+const PRE_EXPORT: &str = "pre-export";
+const MAIN: &str = "main";
+
+/// Logs the phases of the initialization of the module `name`, if `NEON_INIT_TRACE`
+/// is set
+struct Trace {
+    name: &'static str,
+    enabled: bool,
+}
+
+impl Trace {
+    fn new(name: &'static str) -> Self {
+        let enabled = matches!(std::env::var_os(INIT_TRACE), Some(v) if !v.is_empty() && v != "0");
+
+        Self { name, enabled }
+    }
+
+    fn log(&self, args: fmt::Arguments) {
+        if self.enabled {
+            eprintln!("[neon] {}: {}", self.name, args);
+        }
+    }
+}
+
+fn panic_message(name: &str, phase: &str, panic: &(dyn Any + Send)) -> String {
+    let payload = if let Some(string) = panic.downcast_ref::<String>() {
+        string.as_str()
+    } else if let Some(str) = panic.downcast_ref::<&str>() {
+        str
+    } else {
+        "Box<dyn Any>"
+    };
+
+    format!("{}: panicked during {}: {}", name, phase, payload)
 }
 
+/// Initializes the module `name` with its `#[neon::main]` function `init`
+///
+/// Panics are thrown as an `Error` naming the module and the phase that panicked,
+/// and errors thrown by `init` are wrapped in an `Error` with the name of the module
+/// prefixed to their message, so that they can be told apart when `require()` throws.
+///
+/// # Safety
+///
+/// Must only be called by `napi_register_module_v1`, with its arguments.
 pub unsafe fn initialize_module(
     env: *mut c_void,
     exports: *mut c_void,
     init: fn(ModuleContext) -> NeonResult<()>,
+    name: &'static str,
 ) {
+    let trace = Trace::new(name);
     let env = env.cast();
 
-    sys::setup(env);
+    trace.log(format_args!("{} started", PRE_EXPORT));
+
+    // Without the symbols of Node-API, an error can't be thrown
+    if let Err(panic) = catch_unwind(|| sys::setup(env)) {
+        eprintln!("{}", panic_message(name, PRE_EXPORT, &*panic));
+        std::process::abort();
+    }
 
     IS_RUNNING.with(|v| {
         *v.borrow_mut() = true;
     });
 
     let env = Env(env);
-    let exports = Handle::new_internal(JsObject::from_raw(env, exports.cast()));
+    let exports: raw::Local = exports.cast();
 
-    ModuleContext::with(env, exports, |cx| {
-        let _ = init(cx);
-    });
+    if !run_phase(env, exports, &trace, PRE_EXPORT, pre_export) {
+        return;
+    }
+
+    trace.log(format_args!("{} started", MAIN));
+    run_phase(env, exports, &trace, MAIN, init);
+}
+
+#[cfg_attr(
+    not(any(feature = "single-instance", feature = "napi-6")),
+    allow(unused_mut, unused_variables)
+)]
+fn pre_export(mut cx: ModuleContext) -> NeonResult<()> {
+    #[cfg(feature = "single-instance")]
+    if !crate::lifecycle::single::initialize() {
+        return cx.throw_error(
+            "Module was built with the `single-instance` feature and cannot be loaded \
+             more than once per process",
+        );
+    }
+
+    // Lists the instance in `instance::registry` before any code runs
+    #[cfg(feature = "napi-6")]
+    crate::lifecycle::InstanceData::get(&mut cx);
+
+    Ok(())
+}
+
+/// Runs a phase of the initialization, returning `true` if it succeeded
+unsafe fn run_phase<F>(env: Env, exports: raw::Local, trace: &Trace, phase: &str, f: F) -> bool
+where
+    F: for<'b> FnOnce(ModuleContext<'b>) -> NeonResult<()>,
+{
+    let exports = || Handle::new_internal(JsObject::from_raw(env, exports));
+    let start = Instant::now();
+    let result = catch_unwind(AssertUnwindSafe(|| ModuleContext::with(env, exports(), f)));
+
+    match result {
+        Ok(Ok(())) => {
+            trace.log(format_args!("{} finished in {:?}", phase, start.elapsed()));
+
+            return true;
+        }
+        Ok(Err(_)) => {
+            trace.log(format_args!("{} failed: threw an exception", phase));
+
+            let _ = ModuleContext::with(env, exports(), |cx| prefix_exception(cx, trace.name));
+        }
+        Err(panic) => {
+            let msg = panic_message(trace.name, phase, &*panic);
+
+            trace.log(format_args!("{} failed: {}", phase, msg));
+
+            // An exception may be pending if the panic happened after a throw
+            sys::error::clear_exception(env.to_raw());
+
+            let _ = ModuleContext::with(env, exports(), |cx| throw_panic(cx, phase, msg));
+        }
+    }
+
+    false
+}
+
+// Throws an `Error` with the message of the pending exception, if it is an `Error`,
+// prefixed with the name of the module, and the exception as its `cause`. Other
+// exceptions are thrown again unchanged.
+fn prefix_exception(mut cx: ModuleContext, name: &str) -> NeonResult<()> {
+    let exception = match cx.try_catch(|_| Err::<(), _>(Throw::new())) {
+        Err(exception) => exception,
+        // `init` returned `Err` without throwing
+        Ok(()) => return Ok(()),
+    };
+
+    if let Ok(err) = exception.downcast::<JsError, _>(&mut cx) {
+        // The message may be a throwing getter; the original error is thrown then
+        let wrapper = cx.try_catch(|cx| {
+            let message = err.get_value(cx, "message")?;
+
+            match message.downcast::<JsString, _>(cx) {
+                Ok(message) => {
+                    let message = format!("{}: {}", name, message.value(cx));
+                    let wrapper = cx.error(message)?;
+
+                    wrapper.set(cx, "cause", err)?;
+
+                    Ok(Some(wrapper))
+                }
+                Err(_) => Ok(None),
+            }
+        });
+
+        if let Ok(Some(wrapper)) = wrapper {
+            return cx.throw(wrapper);
+        }
+    }
+
+    cx.throw(exception)
+}
+
+fn throw_panic(mut cx: ModuleContext, phase: &str, msg: String) -> NeonResult<()> {
+    let err = cx.error(msg)?;
+    let code = cx.string("ERR_NEON_INIT_PANIC");
+    let phase = cx.string(phase);
+
+    err.set(&mut cx, "code", code)?;
+    err.set(&mut cx, "phase", phase)?;
+
+    cx.throw(err)
 }
@@ -115,10 +115,25 @@
 //! with the context is now throwing. This allows Rust code to perform any
 //! cleanup before returning, but with an important restriction:
 //!
-//! > **While a JavaScript thread is throwing, its context cannot be used.**
+//! > **While a JavaScript thread is throwing, its context cannot run JavaScript.**
 //!
-//! Unless otherwise documented, any Neon API that uses a context (as `self` or as
-//! a parameter) immediately panics if called while the context's thread is throwing.
+//! Operations that do not run JavaScript are safe while the thread is throwing and
+//! leave the exception pending:
+//!
+//! * dropping guards, such as a [`Lock`](crate::types::buffer::Lock) or a borrow of
+//!   a [`JsBox`](crate::types::JsBox)
+//! * rooting and unrooting objects with [`Root`](crate::handle::Root)
+//! * creating strings, numbers, objects, arrays, errors, boxes, dates and promises,
+//!   and settling a [`Deferred`](crate::types::Deferred)
+//! * reading values and checking types, for example, with
+//!   [`Handle::downcast`](crate::handle::Handle::downcast),
+//!   [`JsArray::len`](crate::types::JsArray::len) or
+//!   [`Handle::strict_equals`](crate::handle::Handle::strict_equals)
+//! * throwing, with [`Context::throw`], which keeps the pending exception
+//!
+//! Operations that may run JavaScript, such as getting or setting properties,
+//! calling functions and coercing values, return an `Err` result without running,
+//! and the original exception remains pending.
 //!
 //! Typically, Neon code can manage JavaScript exceptions correctly and conveniently
 //! by using Rust's [question mark (`?`)][question-mark] operator. This ensures that
@@ -127,7 +142,10 @@
 //!
 //! Alternatively, to invoke a Neon API and catch any JavaScript exceptions, use the
 //! [`Context::try_catch`](Context::try_catch) method, which catches any thrown
-//! exception and restores the context to non-throwing state.
+//! exception and restores the context to non-throwing state. Cleanup that must run
+//! JavaScript after an exception was thrown can use
+//! [`Context::with_suppressed_exception`](Context::with_suppressed_exception), which
+//! sets the exception aside and throws it again afterward.
 //!
 //! ## See also
 //!
@@ -141,15 +159,16 @@
 
 pub(crate) mod internal;
 
-use std::{convert::Into, marker::PhantomData, panic::UnwindSafe};
+use std::{convert::Into, marker::PhantomData, panic::UnwindSafe, path::Path};
 
 pub use crate::types::buffer::lock::Lock;
 
 use crate::{
-    event::TaskBuilder,
+    event::{self, AsyncWork, TaskBuilder, WorkHandle},
     handle::{Handle, Managed},
+    macro_internal::RenameRule,
     object::Object,
-    result::{JsResult, NeonResult, Throw},
+    result::{self, JsResult, NeonResult, Throw},
     sys::{
         self, raw,
         scope::{EscapableHandleScope, HandleScope},
@@ -157,21 +176,38 @@ use crate::{
     types::{
         boxed::{Finalize, JsBox},
         error::JsError,
+        extract::TryFromJs,
         Deferred, JsArray, JsArrayBuffer, JsBoolean, JsBuffer, JsFunction, JsNull, JsNumber,
-        JsObject, JsPromise, JsString, JsUndefined, JsValue, StringResult, Value,
+        JsObject, JsPromise, JsString, JsUndefined, JsValue, PathEncoding, StringResult,
+        Utf8ErrorAt, Value,
     },
 };
 
-use self::internal::{ContextInternal, Env};
+use self::internal::{Constants, ContextInternal, Env};
+
+#[cfg(feature = "debug-handles")]
+use crate::handle::debug::ScopeGuard;
 
 #[cfg(feature = "napi-4")]
-use crate::event::Channel;
+use crate::event::{Channel, Priority};
 
 #[cfg(feature = "napi-5")]
 use crate::types::date::{DateError, JsDate};
 
 #[cfg(feature = "napi-6")]
-use crate::lifecycle::InstanceData;
+use std::time::Duration;
+
+#[cfg(feature = "operation-ids")]
+use crate::operation::{self, OperationId};
+
+#[cfg(feature = "napi-6")]
+use crate::{
+    capabilities,
+    lifecycle::InstanceData,
+    reentrancy::{self, ReentrancyGuard},
+    state::Watchable,
+    types::extract::{Nanos, TryIntoJs},
+};
 
 #[repr(C)]
 pub(crate) struct CallbackInfo<'a> {
@@ -195,6 +231,12 @@ impl CallbackInfo<'_> {
         }
     }
 
+    fn new_target<'b, C: Context<'b>>(&self, cx: &C) -> Option<raw::Local> {
+        let target = unsafe { sys::call::new_target(cx.env().to_raw(), self.info) };
+
+        (!target.is_null()).then_some(target)
+    }
+
     pub fn len<'b, C: Context<'b>>(&self, cx: &C) -> usize {
         unsafe { sys::call::len(cx.env().to_raw(), self.info) }
     }
@@ -248,8 +290,11 @@ pub trait Context<'a>: ContextInternal<'a> {
     {
         let env = self.env();
         let scope = unsafe { HandleScope::new(env.to_raw()) };
+        #[cfg(feature = "debug-handles")]
+        let _scope = ScopeGuard::enter();
         let result = f(ExecuteContext {
             env,
+            constants: Constants::default(),
             _phantom_inner: PhantomData,
         });
 
@@ -273,10 +318,17 @@ pub trait Context<'a>: ContextInternal<'a> {
         let scope = unsafe { EscapableHandleScope::new(env.to_raw()) };
         let cx = ComputeContext {
             env,
+            constants: Constants::default(),
             phantom_inner: PhantomData,
         };
 
-        let escapee = unsafe { scope.escape(f(cx)?.to_raw()) };
+        let escapee = {
+            // The escaped handle is created in the outer context, after the scope ends
+            #[cfg(feature = "debug-handles")]
+            let _scope = ScopeGuard::enter();
+
+            unsafe { scope.escape(f(cx)?.to_raw()) }
+        };
 
         Ok(Handle::new_internal(V::from_raw(self.env(), escapee)))
     }
@@ -296,12 +348,50 @@ pub trait Context<'a>: ContextInternal<'a> {
         }
     }
 
+    /// Runs `f` with the pending exception, if any, set aside, and throws it again
+    /// afterward.
+    ///
+    /// While an exception is pending, operations that run JavaScript fail. This
+    /// allows cleanup code, such as releasing a lock held by a JavaScript object, to
+    /// run after an error without discarding it:
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn update(mut cx: FunctionContext) -> JsResult<JsValue> {
+    ///     let resource = cx.argument::<JsObject>(0)?;
+    ///     let update = resource.get::<JsFunction, _, _>(&mut cx, "update")?;
+    ///     let result = update.call_with(&cx).this(resource).apply(&mut cx);
+    ///
+    ///     cx.with_suppressed_exception(|cx| {
+    ///         let release = resource.get::<JsFunction, _, _>(cx, "release")?;
+    ///
+    ///         release.call_with(cx).this(resource).exec(cx)
+    ///     })?;
+    ///
+    ///     result
+    /// }
+    /// ```
+    ///
+    /// If an exception was set aside, it is thrown again even if `f` throws, and the
+    /// exception thrown by `f` is discarded. Otherwise, an exception thrown by `f`
+    /// remains pending.
+    fn with_suppressed_exception<T, F>(&mut self, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let env = self.env().to_raw();
+
+        unsafe { sys::error::suppressed(env, move || f(self)) }
+    }
+
     /// Convenience method for creating a `JsBoolean` value.
+    #[track_caller]
     fn boolean(&mut self, b: bool) -> Handle<'a, JsBoolean> {
         JsBoolean::new(self, b)
     }
 
     /// Convenience method for creating a `JsNumber` value.
+    #[track_caller]
     fn number<T: Into<f64>>(&mut self, x: T) -> Handle<'a, JsNumber> {
         JsNumber::new(self, x.into())
     }
@@ -320,12 +410,61 @@ pub trait Context<'a>: ContextInternal<'a> {
         JsString::try_new(self, s)
     }
 
+    /// Convenience method for creating a `JsString` value from bytes, replacing each
+    /// invalid UTF-8 sequence with U+FFFD. See [`JsString::from_utf8_lossy`].
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    fn string_from_utf8_lossy(&mut self, bytes: &[u8]) -> Handle<'a, JsString> {
+        JsString::from_utf8_lossy(self, bytes)
+    }
+
+    /// Convenience method for creating a `JsString` value from bytes, returning an
+    /// error with the offset of the first invalid sequence if they are not valid UTF-8.
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    fn try_string_from_utf8(&mut self, bytes: &[u8]) -> Result<Handle<'a, JsString>, Utf8ErrorAt> {
+        JsString::try_from_utf8(self, bytes)
+    }
+
+    /// Convenience method for creating a `JsString` value from a path, representing a
+    /// path that is not valid Unicode with `encoding`. See [`PathEncoding`].
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// use neon::types::PathEncoding;
+    ///
+    /// fn current_dir(mut cx: FunctionContext) -> JsResult<JsString> {
+    ///     let dir = std::env::current_dir().or_else(|err| cx.throw_error(err.to_string()))?;
+    ///
+    ///     Ok(cx.string_from_path(dir, PathEncoding::Percent))
+    /// }
+    /// ```
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    fn string_from_path<P: AsRef<Path>>(
+        &mut self,
+        path: P,
+        encoding: PathEncoding,
+    ) -> Handle<'a, JsString> {
+        JsString::from_path(self, path.as_ref(), encoding)
+    }
+
+    /// Convenience method for creating a `JsString` value from UTF-16 code units,
+    /// keeping unpaired surrogates. See [`JsString::from_wide`].
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    fn string_from_wide(&mut self, wide: &[u16]) -> Handle<'a, JsString> {
+        JsString::from_wide(self, wide)
+    }
+
     /// Convenience method for creating a `JsNull` value.
+    #[track_caller]
     fn null(&mut self) -> Handle<'a, JsNull> {
         JsNull::new(self)
     }
 
     /// Convenience method for creating a `JsUndefined` value.
+    #[track_caller]
     fn undefined(&mut self) -> Handle<'a, JsUndefined> {
         JsUndefined::new(self)
     }
@@ -335,6 +474,22 @@ pub trait Context<'a>: ContextInternal<'a> {
         JsObject::new(self)
     }
 
+    /// Creates an empty object with a `null` prototype, like `Object.create(null)`.
+    ///
+    /// An object without a prototype does not inherit properties, such as
+    /// `constructor` or the `__proto__` accessor, from `Object.prototype`. Combined
+    /// with [`Object::set_safe`], it is suitable for building objects from untrusted
+    /// keys. See also [`SafeDict`](crate::types::SafeDict).
+    fn dict(&mut self) -> JsResult<'a, JsObject> {
+        let object = self.global().get::<JsFunction, _, _>(self, "Object")?;
+        let create = object.get::<JsFunction, _, _>(self, "create")?;
+        let null = self.null();
+
+        create
+            .call(self, object, [null.upcast()])?
+            .downcast_or_throw(self)
+    }
+
     /// Convenience method for creating an empty `JsArray` value.
     fn empty_array(&mut self) -> Handle<'a, JsArray> {
         JsArray::new(self, 0)
@@ -356,6 +511,16 @@ pub trait Context<'a>: ContextInternal<'a> {
         JsDate::new(self, value)
     }
 
+    #[cfg(feature = "sys")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sys")))]
+    /// Returns the raw `napi_env` of this context, for calling Node-API functions that
+    /// Neon does not wrap.
+    ///
+    /// See [`sys_interop`](crate::sys_interop) for the invariants that must be upheld.
+    fn raw_env(&self) -> crate::sys_interop::RawEnv<'a> {
+        crate::sys_interop::RawEnv::new(self.env().to_raw().cast())
+    }
+
     /// Produces a handle to the JavaScript global object.
     fn global(&mut self) -> Handle<'a, JsObject> {
         JsObject::build(|out| unsafe {
@@ -364,6 +529,10 @@ pub trait Context<'a>: ContextInternal<'a> {
     }
 
     /// Throws a JS value.
+    ///
+    /// If an exception is already pending, it is kept and `v` is discarded, since
+    /// JavaScript can't throw while an exception propagates. Either way, `Err(Throw)`
+    /// is returned, to be propagated with `?`.
     fn throw<T: Value, U>(&mut self, v: Handle<T>) -> NeonResult<U> {
         unsafe {
             sys::error::throw(self.env().to_raw(), v.to_raw());
@@ -455,6 +624,15 @@ pub trait Context<'a>: ContextInternal<'a> {
         channel
     }
 
+    #[cfg(feature = "napi-4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+    /// Returns a channel like [`channel`](Context::channel) that sends closures with
+    /// `priority`. Channels sharing a queue execute closures with a higher priority
+    /// first. See [priorities](Channel#priorities).
+    fn channel_with_priority(&mut self, priority: Priority) -> Channel {
+        self.channel().with_priority(priority)
+    }
+
     #[cfg_attr(
         feature = "promise-api",
         deprecated = "`promise-api` feature has no impact and may be removed"
@@ -505,12 +683,124 @@ pub trait Context<'a>: ContextInternal<'a> {
     {
         TaskBuilder::new(self, execute)
     }
+
+    /// Queues an [`AsyncWork`] item to execute on the
+    /// [Node worker pool](https://nodejs.org/en/docs/guides/dont-block-the-event-loop/),
+    /// returning a [`WorkHandle`] that may be used to cancel it.
+    ///
+    /// See [`AsyncWork`] for an example.
+    fn queue_work<W: AsyncWork>(&mut self, work: W) -> WorkHandle {
+        event::work::queue(self.env(), work)
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Marks the function `name` as running until the returned guard is dropped,
+    /// throwing an `Error` if it is called again in the meantime. See
+    /// [`reentrancy`](crate::reentrancy) for details.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn for_each_row(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let _guard = cx.reentrancy_guard("for_each_row")?;
+    ///     let callback = cx.argument::<JsFunction>(0)?;
+    ///
+    ///     callback.call_with(&cx).exec(&mut cx)?;
+    ///
+    ///     Ok(cx.undefined())
+    /// }
+    /// ```
+    fn reentrancy_guard(&mut self, name: &str) -> NeonResult<ReentrancyGuard> {
+        reentrancy::guard::<_, &str>(self, name, &[])
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Reads the high-resolution clock of Node.js, returning the same time as
+    /// [`process.hrtime.bigint()`](https://nodejs.org/api/process.html#processhrtimebigint).
+    ///
+    /// The time is relative to an arbitrary point in the past and only meaningful
+    /// compared to another reading, for example, to correlate timestamps taken in Rust
+    /// with timestamps taken in JavaScript. The clock is monotonic.
+    fn hrtime(&mut self) -> NeonResult<Duration> {
+        let global = self.global();
+        let process = global.get::<JsObject, _, _>(self, "process")?;
+        let hrtime = process.get::<JsFunction, _, _>(self, "hrtime")?;
+        let bigint = hrtime.get::<JsFunction, _, _>(self, "bigint")?;
+        let Nanos(time) = bigint.call_with(self).this(hrtime).apply_into(self)?;
+
+        Ok(time)
+    }
+
+    #[cfg(feature = "operation-ids")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "operation-ids")))]
+    /// Calls `f` with `id` as the current operation id, restoring the previous id
+    /// afterwards. Channels, tasks and promises created by `f` carry the id to their
+    /// callbacks, and errors created while it is current have an `operationId`. See
+    /// [`operation`](crate::operation) for details.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// use neon::operation::OperationId;
+    ///
+    /// fn handle(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    ///
+    ///     // Throws an `Error` with an `operationId` of `"000000000000002a"` for `42`
+    ///     cx.with_operation_id(OperationId::new(id), |cx| {
+    ///         cx.throw_error("request failed")
+    ///     })
+    /// }
+    /// ```
+    fn with_operation_id<T, F>(&mut self, id: OperationId, f: F) -> T
+    where
+        F: FnOnce(&mut Self) -> T,
+    {
+        let _scope = operation::Scope::enter(self, Some(id));
+
+        f(self)
+    }
+
+    #[cfg(feature = "operation-ids")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "operation-ids")))]
+    /// Returns the current operation id, set with
+    /// [`with_operation_id`](Context::with_operation_id) or restored for a callback of
+    /// the operation.
+    fn operation_id(&mut self) -> Option<OperationId> {
+        operation::current(self)
+    }
+}
+
+#[cfg(feature = "napi-5")]
+// Defines a property of `exports` with `Object.defineProperty`
+fn define_export<'a, C: Context<'a>>(
+    cx: &mut C,
+    exports: Handle<JsObject>,
+    key: &str,
+    descriptor: Handle<JsObject>,
+) -> NeonResult<()> {
+    let object = cx.global().get::<JsFunction, _, _>(cx, "Object")?;
+    let define = object.get::<JsFunction, _, _>(cx, "defineProperty")?;
+    let key = cx.string(key);
+
+    define.call(
+        cx,
+        object,
+        [
+            exports.upcast(),
+            key.upcast(),
+            descriptor.upcast::<JsValue>(),
+        ],
+    )?;
+
+    Ok(())
 }
 
 /// An execution context of module initialization.
 pub struct ModuleContext<'a> {
     env: Env,
     exports: Handle<'a, JsObject>,
+    constants: Constants,
 }
 
 impl<'a> UnwindSafe for ModuleContext<'a> {}
@@ -521,7 +811,14 @@ impl<'a> ModuleContext<'a> {
         exports: Handle<'a, JsObject>,
         f: F,
     ) -> T {
-        f(ModuleContext { env, exports })
+        #[cfg(feature = "debug-handles")]
+        let _scope = ScopeGuard::enter();
+
+        f(ModuleContext {
+            env,
+            exports,
+            constants: Constants::default(),
+        })
     }
 
     #[cfg(not(feature = "napi-5"))]
@@ -543,7 +840,9 @@ impl<'a> ModuleContext<'a> {
         F: Fn(FunctionContext) -> JsResult<V> + 'static,
         V: Value,
     {
-        let value = JsFunction::new(self, f)?.upcast::<JsValue>();
+        let id = crate::diagnostics::register_export(key);
+        let value = JsFunction::new(self, move |cx| crate::diagnostics::track(id, || f(cx)))?
+            .upcast::<JsValue>();
         // Note: Cloning `exports` is necessary to avoid holding a shared reference to
         // `self` while attempting to use it mutably in `set`.
         self.exports.clone().set(self, key, value)?;
@@ -556,6 +855,250 @@ impl<'a> ModuleContext<'a> {
         Ok(())
     }
 
+    #[cfg(feature = "napi-5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-5")))]
+    /// Exports a read-only property whose value is computed by calling `getter` each
+    /// time the property is read.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// use std::sync::atomic::{AtomicU32, Ordering};
+    ///
+    /// static REQUESTS: AtomicU32 = AtomicU32::new(0);
+    ///
+    /// #[neon::main]
+    /// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    ///     cx.export_live("requests", |mut cx| {
+    ///         Ok(cx.number(REQUESTS.load(Ordering::Relaxed)))
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// ## CommonJS and ES modules
+    ///
+    /// Reading the property from the module object, e.g., `addon.requests`, always
+    /// observes the current value. However, Node.js copies the named exports of a
+    /// CommonJS module into an ES module namespace once, when it is first imported, so
+    /// `import { requests } from "./addon.cjs"` does _not_ observe changes. Live
+    /// bindings can be provided with an ES module wrapper that re-exports a `let`
+    /// binding and updates it, for example, with
+    /// [`export_watchable`](ModuleContext::export_watchable):
+    ///
+    /// ```js
+    /// // index.mjs
+    /// import { createRequire } from "node:module";
+    ///
+    /// const addon = createRequire(import.meta.url)("./index.node");
+    ///
+    /// export let status = addon.status;
+    ///
+    /// addon.subscribeStatus((value) => {
+    ///     status = value;
+    /// });
+    /// ```
+    pub fn export_live<F, V>(&mut self, key: &str, getter: F) -> NeonResult<()>
+    where
+        F: Fn(FunctionContext) -> JsResult<V> + 'static,
+        V: Value,
+    {
+        let descriptor = self.empty_object();
+        let getter = JsFunction::new(self, getter)?;
+        let enumerable = self.boolean(true);
+
+        descriptor.set(self, "get", getter)?;
+        descriptor.set(self, "enumerable", enumerable)?;
+
+        let exports = self.exports;
+
+        define_export(self, exports, key, descriptor)
+    }
+
+    #[cfg(feature = "napi-5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-5")))]
+    /// Exports an object named `key` that is created by `init` when the property is
+    /// first read, for example, a group of functions that most users of the module do
+    /// not need.
+    ///
+    /// Once `init` returns, the property is replaced with the object, so later reads
+    /// are plain property reads and `init` is not called again. If `init` throws, the
+    /// exception is thrown from the property read and `init` is called again on the
+    /// next read. The property is enumerable before it is first read.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # fn resize(mut cx: FunctionContext) -> JsResult<JsUndefined> { Ok(cx.undefined()) }
+    /// #[neon::main]
+    /// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    ///     // `addon.imaging.resize()`
+    ///     cx.export_lazy("imaging", |cx| {
+    ///         let imaging = cx.empty_object();
+    ///         let resize = JsFunction::new(cx, resize)?;
+    ///
+    ///         imaging.set(cx, "resize", resize)?;
+    ///
+    ///         Ok(imaging)
+    ///     })?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// Functions marked with `#[neon::function(namespace = "imaging", lazy)]` in a module
+    /// with [`#[neon::export_config]`](crate::export_config) are exported this way.
+    pub fn export_lazy<F>(&mut self, key: &str, init: F) -> NeonResult<()>
+    where
+        F: for<'cx> Fn(&mut FunctionContext<'cx>) -> JsResult<'cx, JsObject> + 'static,
+    {
+        let name = key.to_string();
+        let initializing = std::cell::Cell::new(false);
+        let getter = JsFunction::new(self, move |mut cx| {
+            if initializing.replace(true) {
+                return cx.throw_error(format!(
+                    "`{}` was read while it was being initialized",
+                    name
+                ));
+            }
+
+            let value = init(&mut cx);
+
+            initializing.set(false);
+
+            let value = value?;
+            let this = cx.this::<JsObject>()?;
+            let descriptor = cx.empty_object();
+            let t = cx.boolean(true);
+
+            descriptor.set(&mut cx, "value", value)?;
+            descriptor.set(&mut cx, "enumerable", t)?;
+            descriptor.set(&mut cx, "writable", t)?;
+            descriptor.set(&mut cx, "configurable", t)?;
+            define_export(&mut cx, this, &name, descriptor)?;
+
+            Ok(value)
+        })?;
+
+        let descriptor = self.empty_object();
+        let t = self.boolean(true);
+
+        descriptor.set(self, "get", getter)?;
+        descriptor.set(self, "enumerable", t)?;
+        // Allows the getter to be replaced with the object
+        descriptor.set(self, "configurable", t)?;
+
+        let exports = self.exports;
+
+        define_export(self, exports, key, descriptor)
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Exports the current value of a [`Watchable`] as a [live](ModuleContext::export_live)
+    /// property named `key`, and a function for subscribing to changes named `subscribe`
+    /// followed by `key` with the first letter capitalized.
+    ///
+    /// For example, exporting `"status"` defines `addon.status` and
+    /// `addon.subscribeStatus(callback)`. The subscribe function calls `callback` with
+    /// each new value and returns a function that unsubscribes, like the `subscribe`
+    /// method of [`Watchable::export`].
+    pub fn export_watchable<T>(&mut self, key: &str, watchable: &Watchable<T>) -> NeonResult<()>
+    where
+        T: Clone + Send + 'static,
+        for<'cx> T: TryIntoJs<'cx>,
+    {
+        self.export_live(key, {
+            let watchable = watchable.clone();
+
+            move |mut cx| {
+                let value = watchable.get();
+
+                value.try_into_js(&mut cx).map(|v| v.upcast::<JsValue>())
+            }
+        })?;
+
+        let mut chars = key.chars();
+        let subscribe_key = match chars.next() {
+            Some(first) => format!("subscribe{}{}", first.to_uppercase(), chars.as_str()),
+            None => String::from("subscribe"),
+        };
+
+        self.export_function(&subscribe_key, {
+            let watchable = watchable.clone();
+
+            move |mut cx| watchable.subscribe(&mut cx)
+        })
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Registers whether each optional [capability](crate::capabilities) of the addon is
+    /// available and exports them as a frozen `capabilities` object of
+    /// `{ [name]: available }`.
+    ///
+    /// Functions marked with [`#[neon::function(requires = "...")]`](crate::export) throw
+    /// when the capability they require was registered as unavailable.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # fn vips_is_installed() -> bool { false }
+    /// #[neon::main]
+    /// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    ///     cx.export_capabilities([("vips", vips_is_installed()), ("simd", true)])?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn export_capabilities<I, K>(&mut self, capabilities: I) -> NeonResult<()>
+    where
+        I: IntoIterator<Item = (K, bool)>,
+        K: AsRef<str>,
+    {
+        let capabilities = capabilities::register(self, capabilities)?;
+
+        self.export_value("capabilities", capabilities)
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Exports a function named `old` that emits a
+    /// [deprecation warning](crate::deprecation) and calls the function exported as
+    /// `new`, for an export that was renamed.
+    ///
+    /// The function `new` must already be exported. The alias is called with the same
+    /// `this` and arguments and returns the same value. The warning has a message
+    /// pointing to `new` and a `code` derived from `old` with
+    /// [`deprecation::code`](crate::deprecation::code).
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # fn parse_url(mut cx: FunctionContext) -> JsResult<JsString> { cx.argument(0) }
+    /// #[neon::main]
+    /// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    ///     cx.export_function("parseUrl", parse_url)?;
+    ///     cx.export_deprecated_alias("parseUri", "parseUrl")?;
+    ///
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn export_deprecated_alias(&mut self, old: &str, new: &str) -> NeonResult<()> {
+        let exports = self.exports;
+        let f = exports.get::<JsFunction, _, _>(self, new)?.root(self);
+        let name = old.to_string();
+        let message = format!("`{}` is deprecated. Use `{}` instead.", old, new);
+
+        self.export_function(old, move |mut cx| {
+            crate::deprecation::warn(&mut cx, &name, &message)?;
+
+            let this = cx.this_value();
+            let args = (0..cx.len())
+                .filter_map(|i| cx.argument_opt(i))
+                .collect::<Vec<_>>();
+
+            f.to_inner(&mut cx).call(&mut cx, this, args)
+        })
+    }
+
     /// Produces a handle to a module's exports object.
     pub fn exports_object(&mut self) -> JsResult<'a, JsObject> {
         Ok(self.exports)
@@ -566,6 +1109,10 @@ impl<'a> ContextInternal<'a> for ModuleContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for ModuleContext<'a> {}
@@ -573,6 +1120,7 @@ impl<'a> Context<'a> for ModuleContext<'a> {}
 /// An execution context of a scope created by [`Context::execute_scoped()`](Context::execute_scoped).
 pub struct ExecuteContext<'a> {
     env: Env,
+    constants: Constants,
     _phantom_inner: PhantomData<&'a ()>,
 }
 
@@ -580,6 +1128,10 @@ impl<'a> ContextInternal<'a> for ExecuteContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for ExecuteContext<'a> {}
@@ -587,6 +1139,7 @@ impl<'a> Context<'a> for ExecuteContext<'a> {}
 /// An execution context of a scope created by [`Context::compute_scoped()`](Context::compute_scoped).
 pub struct ComputeContext<'a> {
     env: Env,
+    constants: Constants,
     phantom_inner: PhantomData<&'a ()>,
 }
 
@@ -594,6 +1147,10 @@ impl<'a> ContextInternal<'a> for ComputeContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for ComputeContext<'a> {}
@@ -606,6 +1163,10 @@ pub struct FunctionContext<'a> {
     info: &'a CallbackInfo<'a>,
 
     arguments: Option<sys::call::Arguments>,
+    constants: Constants,
+
+    // Case of the parameter names in errors, set by `#[neon::function(rename_all = "..")]`
+    pub(crate) rename: Option<RenameRule>,
 }
 
 impl<'a> UnwindSafe for FunctionContext<'a> {}
@@ -616,15 +1177,26 @@ impl<'a> FunctionContext<'a> {
         self.info.kind(self)
     }
 
+    /// The `new.target` of the call, if the function was called with `new`. It is the
+    /// function itself, or a subclass constructing through `super()`.
+    pub(crate) fn new_target(&self) -> Option<Handle<'a, JsValue>> {
+        self.info.new_target(self).map(JsValue::new_internal)
+    }
+
     pub(crate) fn with<U, F: for<'b> FnOnce(FunctionContext<'b>) -> U>(
         env: Env,
         info: &'a CallbackInfo<'a>,
         f: F,
     ) -> U {
+        #[cfg(feature = "debug-handles")]
+        let _scope = ScopeGuard::enter();
+
         f(FunctionContext {
             env,
             info,
             arguments: None,
+            constants: Constants::default(),
+            rename: None,
         })
     }
 
@@ -652,13 +1224,106 @@ impl<'a> FunctionContext<'a> {
     }
 
     /// Produces the `i`th argument and casts it to the type `V`, or throws an exception if `i` is greater than or equal to `self.len()` or cannot be cast to `V`.
+    ///
+    /// The exception is a `TypeError` with a `code` of `"ERR_INVALID_ARG_TYPE"` naming the
+    /// argument `arguments[i]`; see [`ArgumentErrors`](crate::result::ArgumentErrors).
     pub fn argument<V: Value>(&mut self, i: usize) -> JsResult<'a, V> {
         match self.argument_opt(i) {
-            Some(v) => v.downcast_or_throw(self),
-            None => self.throw_type_error("not enough arguments"),
+            Some(v) => match v.downcast(self) {
+                Ok(v) => Ok(v),
+                Err(_) => result::throw_invalid_arg_type::<V, _, _>(
+                    self,
+                    &format!("arguments[{}]", i),
+                    Some(v),
+                ),
+            },
+            None => {
+                result::throw_invalid_arg_type::<V, _, _>(self, &format!("arguments[{}]", i), None)
+            }
         }
     }
 
+    /// Produces the `i`th argument and casts it to the type `V`, like
+    /// [`argument`](FunctionContext::argument), but names the argument `name` in the
+    /// exception if it is missing or cannot be cast to `V`.
+    ///
+    /// In a function exported with a `rename_all` rule, `name` is converted by the rule,
+    /// so the exception names the parameter as it appears in the JavaScript API. See
+    /// [`#[neon::function]`](crate::export).
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn open(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     // TypeError [ERR_INVALID_ARG_TYPE]: The "path" argument must be of type string. Received type number (42)
+    ///     let path = cx.named_argument::<JsString>(0, "path")?;
+    /// #   Ok(cx.undefined())
+    /// }
+    /// ```
+    pub fn named_argument<V: Value>(&mut self, i: usize, name: &str) -> JsResult<'a, V> {
+        let v = self.argument_opt(i);
+
+        match v.map(|v| v.downcast(self)) {
+            Some(Ok(v)) => Ok(v),
+            _ => match self.rename {
+                Some(rule) => result::throw_invalid_arg_type::<V, _, _>(self, &rule.apply(name), v),
+                None => result::throw_invalid_arg_type::<V, _, _>(self, name, v),
+            },
+        }
+    }
+
+    /// Extracts the `i`th argument as `T` with [`TryFromJs`], returning `Ok(None)` if it
+    /// is not of the expected type.
+    ///
+    /// Unlike extracting the value of [`argument_opt`](FunctionContext::argument_opt),
+    /// the extractor knows the number of arguments passed, so a missing argument can
+    /// be told apart from `undefined` with [`Provided`](crate::types::extract::Provided),
+    /// and the remaining arguments extracted with [`Rest`](crate::types::extract::Rest).
+    /// See [`TryFromJs::try_from_argument`].
+    pub fn extract_argument<T: TryFromJs<'a>>(&mut self, i: usize) -> NeonResult<Option<T>> {
+        T::try_from_argument(self, i)
+    }
+
+    /// Returns the `i`th argument unchanged, for a function that returns one of its
+    /// arguments, or throws a `RangeError` if `i` is greater than or equal to
+    /// `self.len()`.
+    ///
+    /// The argument is neither cast nor converted, so the function returns the same value
+    /// it was passed: for an object, `returned === passed` in JavaScript. Other arguments
+    /// may still be extracted.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// // `tap(f, value)` calls `f` with `value` and returns `value`
+    /// fn tap(mut cx: FunctionContext) -> JsResult<JsValue> {
+    ///     let f = cx.argument::<JsFunction>(0)?;
+    ///     let value = cx.passthrough(1)?;
+    ///
+    ///     f.call_with(&cx).arg(value).exec(&mut cx)?;
+    ///     cx.passthrough(1)
+    /// }
+    /// ```
+    pub fn passthrough(&mut self, i: usize) -> JsResult<'a, JsValue> {
+        match self.argument_opt(i) {
+            Some(v) => Ok(v),
+            None => {
+                let len = self.len();
+
+                self.throw_range_error(format!(
+                    "cannot return arguments[{}]: the function was called with {} argument{}",
+                    i,
+                    len,
+                    if len == 1 { "" } else { "s" },
+                ))
+            }
+        }
+    }
+
+    /// Returns the `this`-binding unchanged, for a function that returns `this`, e.g.,
+    /// to allow chaining method calls. Equivalent to `Ok(cx.this_value())`.
+    pub fn return_this(&mut self) -> JsResult<'a, JsValue> {
+        Ok(self.this_value())
+    }
+
     /// Produces a handle to the `this`-binding and attempts to downcast as a specific type.
     /// Equivalent to calling `cx.this_value().downcast_or_throw(&mut cx)`.
     ///
@@ -677,6 +1342,10 @@ impl<'a> ContextInternal<'a> for FunctionContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for FunctionContext<'a> {}
@@ -684,13 +1353,18 @@ impl<'a> Context<'a> for FunctionContext<'a> {}
 /// An execution context of a task completion callback.
 pub struct TaskContext<'a> {
     env: Env,
+    constants: Constants,
     _phantom_inner: PhantomData<&'a ()>,
 }
 
 impl<'a> TaskContext<'a> {
     pub(crate) fn with_context<T, F: for<'b> FnOnce(TaskContext<'b>) -> T>(env: Env, f: F) -> T {
+        #[cfg(feature = "debug-handles")]
+        let _scope = ScopeGuard::enter();
+
         f(Self {
             env,
+            constants: Constants::default(),
             _phantom_inner: PhantomData,
         })
     }
@@ -700,6 +1374,10 @@ impl<'a> ContextInternal<'a> for TaskContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for TaskContext<'a> {}
@@ -707,13 +1385,18 @@ impl<'a> Context<'a> for TaskContext<'a> {}
 /// A view of the JS engine in the context of a finalize method on garbage collection
 pub(crate) struct FinalizeContext<'a> {
     env: Env,
+    constants: Constants,
     _phantom_inner: PhantomData<&'a ()>,
 }
 
 impl<'a> FinalizeContext<'a> {
     pub(crate) fn with<T, F: for<'b> FnOnce(FinalizeContext<'b>) -> T>(env: Env, f: F) -> T {
+        #[cfg(feature = "debug-handles")]
+        let _scope = ScopeGuard::enter();
+
         f(Self {
             env,
+            constants: Constants::default(),
             _phantom_inner: PhantomData,
         })
     }
@@ -723,6 +1406,10 @@ impl<'a> ContextInternal<'a> for FinalizeContext<'a> {
     fn env(&self) -> Env {
         self.env
     }
+
+    fn constants(&mut self) -> &mut Constants {
+        &mut self.constants
+    }
 }
 
 impl<'a> Context<'a> for FinalizeContext<'a> {}
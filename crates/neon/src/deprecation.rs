@@ -0,0 +1,171 @@
+//! Deprecation warnings for exported functions.
+//!
+//! Renaming or removing part of an addon's API breaks its users. Instead, the old
+//! function can keep working and warn that it is deprecated, the way Node.js core warns
+//! about its own deprecated APIs. A function marked with
+//! [`#[neon::function(deprecated = "...")]`](crate::export) emits a `DeprecationWarning`
+//! with [`process.emitWarning`](https://nodejs.org/api/process.html#processemitwarningwarning-options)
+//! the first time it is called, then runs normally:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! #[neon::function(deprecated = "use parseUrl() instead")]
+//! fn parse_uri(mut cx: FunctionContext) -> JsResult<JsString> {
+//!     cx.argument::<JsString>(0)
+//! }
+//!
+//! fn parse_url(mut cx: FunctionContext) -> JsResult<JsString> {
+//!     cx.argument::<JsString>(0)
+//! }
+//!
+//! #[neon::main]
+//! fn main(mut cx: ModuleContext) -> NeonResult<()> {
+//!     cx.export_function("parseUri", parse_uri)?;
+//!     cx.export_function("parseUrl", parse_url)?;
+//!
+//!     // `addon.getUrl()` warns and calls `addon.parseUrl()`
+//!     cx.export_deprecated_alias("getUrl", "parseUrl")?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! The warning has the message given to the attribute and a `code` derived from the
+//! name of the function with [`code`], e.g., `"NEON_DEP_PARSE_URI"`, which can be used
+//! to filter it. Each warning is emitted at most once for each instance of the addon.
+//!
+//! Like the warnings of Node.js, no warning is emitted if `process.noDeprecation` is
+//! `true`, for example, when Node.js is started with `--no-deprecation`. If
+//! `process.throwDeprecation` is `true`, e.g., with `--throw-deprecation`, calling a
+//! deprecated function throws the warning instead of calling the function.
+
+use std::collections::HashSet;
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    lifecycle::InstanceData,
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{JsBoolean, JsError, JsFunction, JsObject, JsValue},
+};
+
+/// The `name` of deprecation warnings
+pub const DEPRECATION_WARNING: &str = "DeprecationWarning";
+
+#[derive(Default)]
+/// Codes of the warnings emitted by an instance of the addon
+pub(crate) struct Emitted(HashSet<String>);
+
+/// Derives the `code` of the deprecation warning of the function `name`.
+///
+/// The name is converted to upper snake case, splitting camel case words and
+/// acronyms, and prefixed with `NEON_DEP_`: both `parse_uri` and `parseUri` have the
+/// code `"NEON_DEP_PARSE_URI"`, and `getHTTPStatus` has the code
+/// `"NEON_DEP_GET_HTTP_STATUS"`.
+///
+/// ```
+/// assert_eq!(neon::deprecation::code("parseUri"), "NEON_DEP_PARSE_URI");
+/// ```
+pub fn code(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut code = String::from("NEON_DEP_");
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' || c == '-' {
+            if !code.ends_with('_') {
+                code.push('_');
+            }
+
+            continue;
+        }
+
+        // A word starts at an uppercase letter after a lowercase letter or digit, or
+        // at the last uppercase letter of an acronym followed by a lowercase letter
+        if c.is_uppercase() && i > 0 && !code.ends_with('_') {
+            let prev = chars[i - 1];
+            let next = chars.get(i + 1).copied();
+
+            if !prev.is_uppercase() || next.is_some_and(char::is_lowercase) {
+                code.push('_');
+            }
+        }
+
+        code.extend(c.to_uppercase());
+    }
+
+    code
+}
+
+/// Emits a `DeprecationWarning` with `message` and the [`code`] of the function
+/// `name`, unless it was already emitted by this instance of the addon.
+///
+/// Does nothing if `process.noDeprecation` is `true`, and throws the warning without
+/// marking it as emitted if `process.throwDeprecation` is `true`.
+///
+/// This is the warning emitted by functions marked with
+/// [`#[neon::function(deprecated = "...")]`](crate::export).
+pub fn warn<'a, C: Context<'a>>(cx: &mut C, name: &str, message: &str) -> NeonResult<()> {
+    let code = code(name);
+
+    if InstanceData::deprecations(cx).0.contains(&code) {
+        return Ok(());
+    }
+
+    let global = cx.global();
+    let process = global.get::<JsObject, _, _>(cx, "process")?;
+
+    if flag(cx, process, "noDeprecation")? {
+        return Ok(());
+    }
+
+    if flag(cx, process, "throwDeprecation")? {
+        let err = deprecation_error(cx, &code, message)?;
+
+        return cx.throw(err);
+    }
+
+    let options = cx.empty_object();
+    let typ = cx.string(DEPRECATION_WARNING);
+    let code_value = cx.string(&code);
+    let message = cx.string(message);
+
+    options.set(cx, "type", typ)?;
+    options.set(cx, "code", code_value)?;
+
+    process.get::<JsFunction, _, _>(cx, "emitWarning")?.exec(
+        cx,
+        process,
+        [message.upcast::<JsValue>(), options.upcast()],
+    )?;
+
+    InstanceData::deprecations(cx).0.insert(code);
+
+    Ok(())
+}
+
+// Tests whether `process[key]` is `true`
+fn flag<'a, C: Context<'a>>(cx: &mut C, process: Handle<JsObject>, key: &str) -> NeonResult<bool> {
+    let value = process.get_value(cx, key)?;
+
+    Ok(value
+        .downcast::<JsBoolean, _>(cx)
+        .map(|b| b.value(cx))
+        .unwrap_or(false))
+}
+
+// The warning thrown with `--throw-deprecation`, like the one thrown by Node.js
+fn deprecation_error<'a, C: Context<'a>>(
+    cx: &mut C,
+    code: &str,
+    message: &str,
+) -> JsResult<'a, JsError> {
+    let err = JsError::error(cx, message)?;
+    let name = cx.string(DEPRECATION_WARNING);
+    let code = cx.string(code);
+
+    err.set(cx, "name", name)?;
+    err.set(cx, "code", code)?;
+
+    Ok(err)
+}
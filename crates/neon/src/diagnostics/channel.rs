@@ -0,0 +1,382 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    mem::MaybeUninit,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    context::Context,
+    event::Channel,
+    handle::{Handle, Root},
+    object::Object,
+    reflect,
+    result::{JsResult, NeonResult},
+    sys,
+    thread::LocalKey,
+    types::{extract::TryIntoJs, JsArray, JsBoolean, JsFunction, JsObject, JsValue},
+};
+
+// Channels opened by an instance of the addon, by name
+static CHANNELS: LocalKey<Mutex<HashMap<String, DiagChannel>>> = LocalKey::new();
+
+struct Inner {
+    name: String,
+    channel: Root<JsObject>,
+    // `hasSubscribers`, as of the last time a subscriber was added or removed
+    subscribed: Arc<AtomicBool>,
+    events: Channel,
+}
+
+#[derive(Clone)]
+/// A named channel of the Node.js
+/// [`diagnostics_channel`](https://nodejs.org/api/diagnostics_channel.html) module,
+/// returned by [`channel`]
+///
+/// Publishing is cheap while nothing subscribes to the channel. A `DiagChannel` can be
+/// cloned and sent to other threads, which publish with
+/// [`publish_from`](DiagChannel::publish_from).
+pub struct DiagChannel(Arc<Inner>);
+
+impl fmt::Debug for DiagChannel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DiagChannel")
+            .field("name", &self.0.name)
+            .finish()
+    }
+}
+
+/// Returns the channel `name`, like `diagnostics_channel.channel(name)`.
+///
+/// The channel is looked up once for each instance of the addon; later calls with the
+/// same name return a clone.
+///
+/// Throws if the `diagnostics_channel` module can't be loaded. On Node versions
+/// without `process.getBuiltinModule` (before 20.16 and 22.3), this is the case when
+/// the entry point of the program is an ES module and in workers started with
+/// `eval: true`, since Neon loads the module with the `require` of the main module.
+pub fn channel<'cx, C: Context<'cx>>(cx: &mut C, name: &str) -> NeonResult<DiagChannel> {
+    let channels = CHANNELS.get_or_init_default(cx);
+
+    if let Some(channel) = channels.lock().unwrap().get(name) {
+        return Ok(channel.clone());
+    }
+
+    let subscribed = Arc::new(AtomicBool::new(false));
+    let update = JsFunction::new(cx, {
+        let subscribed = subscribed.clone();
+
+        move |mut cx| {
+            let value = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+
+            subscribed.store(value, Ordering::Relaxed);
+            Ok(cx.undefined())
+        }
+    })?;
+
+    let channel = open_channel(cx, name, update)?;
+
+    let mut events = cx.channel();
+
+    // Published messages are not part of the operation that opened the channel
+    #[cfg(feature = "operation-ids")]
+    events.in_operation(None);
+
+    // Events published from other threads are still delivered, but do not keep the
+    // event loop alive
+    events.unref(cx);
+
+    let channel = DiagChannel(Arc::new(Inner {
+        name: name.to_string(),
+        channel: channel.root(cx),
+        subscribed,
+        events,
+    }));
+
+    channels
+        .lock()
+        .unwrap()
+        .insert(name.to_string(), channel.clone());
+
+    Ok(channel)
+}
+
+// Returns `diagnostics_channel.channel(name)`, after arranging for `update` to be called
+// with `hasSubscribers` whenever a subscriber is added or removed. Subscribing and
+// unsubscribing switch the prototype of the channel between an inactive and an active
+// class; the methods are wrapped on the instance, once for every addon that uses it.
+fn open_channel<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    name: &str,
+    update: Handle<'cx, JsFunction>,
+) -> NeonResult<Handle<'cx, JsObject>> {
+    let dc = match reflect::builtin_module(cx, "diagnostics_channel")? {
+        Some(dc) => dc,
+        None => return cx.throw_error("the diagnostics_channel module is not available"),
+    };
+
+    let name = cx.string(name);
+    let channel = dc
+        .get::<JsFunction, _, _>(cx, "channel")?
+        .call_with(cx)
+        .this(dc)
+        .arg(name)
+        .apply::<JsObject, _>(cx)?;
+
+    let key = updates_key(cx)?;
+    let updates = match channel.get_opt::<JsArray, _, _>(cx, key)? {
+        Some(updates) => updates,
+        None => wrap_channel(cx, channel)?,
+    };
+
+    let len = updates.len(cx);
+    let subscribed = channel.get_value(cx, "hasSubscribers")?;
+
+    updates.set(cx, len, update)?;
+    update.call_with(cx).arg(subscribed).exec(cx)?;
+
+    Ok(channel)
+}
+
+// Key of the array of `update` functions of a channel, shared by every addon
+fn updates_key<'cx, C: Context<'cx>>(cx: &mut C) -> JsResult<'cx, JsValue> {
+    let global = cx.global();
+    let symbol = global.get::<JsFunction, _, _>(cx, "Symbol")?;
+    let key = cx.string("neon.diagnostics.updates");
+
+    symbol
+        .get::<JsFunction, _, _>(cx, "for")?
+        .call_with(cx)
+        .this(symbol)
+        .arg(key)
+        .apply(cx)
+}
+
+// Wraps `subscribe` and `unsubscribe` on the channel to call each of its `update`
+// functions, and returns the empty array of `update` functions
+fn wrap_channel<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    channel: Handle<'cx, JsObject>,
+) -> JsResult<'cx, JsArray> {
+    for method in ["subscribe", "unsubscribe"] {
+        let wrapper = JsFunction::new(cx, move |mut cx| {
+            let this = cx.this::<JsObject>()?;
+            let args = (0..cx.len())
+                .map(|i| cx.argument::<JsValue>(i))
+                .collect::<NeonResult<Vec<_>>>()?;
+
+            let prototype = cx.global().get::<JsFunction, _, _>(&mut cx, "Object")?;
+            let prototype = prototype
+                .get::<JsFunction, _, _>(&mut cx, "getPrototypeOf")?
+                .call_with(&cx)
+                .this(prototype)
+                .arg(this)
+                .apply::<JsObject, _>(&mut cx)?;
+
+            let result = prototype
+                .get::<JsFunction, _, _>(&mut cx, method)?
+                .call(&mut cx, this, args)?;
+
+            let key = updates_key(&mut cx)?;
+            let updates = this.get::<JsArray, _, _>(&mut cx, key)?.to_vec(&mut cx)?;
+            let subscribed = this.get_value(&mut cx, "hasSubscribers")?;
+
+            for update in updates {
+                update
+                    .downcast_or_throw::<JsFunction, _>(&mut cx)?
+                    .call_with(&cx)
+                    .arg(subscribed)
+                    .exec(&mut cx)?;
+            }
+
+            Ok(result)
+        })?;
+
+        let descriptor = cx.empty_object();
+        let yes = cx.boolean(true);
+        let method = cx.string(method);
+
+        descriptor.set(cx, "configurable", yes)?;
+        descriptor.set(cx, "writable", yes)?;
+        descriptor.set(cx, "value", wrapper)?;
+        reflect::define_property(cx, channel, method, descriptor)?;
+    }
+
+    let updates = cx.empty_array();
+    let descriptor = cx.empty_object();
+    let key = updates_key(cx)?;
+
+    descriptor.set(cx, "value", updates)?;
+    reflect::define_property(cx, channel, key, descriptor)?;
+
+    Ok(updates)
+}
+
+impl DiagChannel {
+    /// The name of the channel
+    pub fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    /// Returns `true` if the channel had subscribers the last time one was added or
+    /// removed.
+    ///
+    /// This is a single atomic load that can be called from any thread. Subscribers
+    /// are tracked when they are added or removed with `subscribe` and `unsubscribe`;
+    /// a channel that is only active because of `bindStore` is not counted.
+    pub fn has_subscribers(&self) -> bool {
+        self.0.subscribed.load(Ordering::Relaxed)
+    }
+
+    /// Publishes `message` to the subscribers of the channel, like `channel.publish`.
+    ///
+    /// Checks `hasSubscribers` first, and returns without converting `message` if it
+    /// is `false`. Exceptions thrown by subscribers are not propagated; Node.js
+    /// reports them as uncaught exceptions.
+    pub fn publish<'cx, C, T>(&self, cx: &mut C, message: T) -> NeonResult<()>
+    where
+        C: Context<'cx>,
+        T: TryIntoJs<'cx>,
+    {
+        let channel = self.0.channel.to_inner(cx);
+        let subscribed = channel
+            .get::<JsBoolean, _, _>(cx, "hasSubscribers")?
+            .value(cx);
+
+        if !subscribed {
+            return Ok(());
+        }
+
+        let message = message.try_into_js(cx)?;
+
+        channel.get::<JsFunction, _, _>(cx, "publish")?.exec(
+            cx,
+            channel,
+            [message.upcast::<JsValue>()],
+        )
+    }
+
+    /// Publishes `message` from any thread, returning `true` if it was sent to the
+    /// JavaScript thread that opened the channel.
+    ///
+    /// Nothing is sent if the channel has no subscribers, according to
+    /// [`has_subscribers`](DiagChannel::has_subscribers), or if the instance of the
+    /// addon that opened it was torn down. A message that was sent is converted and
+    /// published with [`publish`](DiagChannel::publish) on the JavaScript thread, so
+    /// it is dropped if the last subscriber was removed in the meantime.
+    pub fn publish_from<T>(&self, message: T) -> bool
+    where
+        T: for<'cx> TryIntoJs<'cx> + Send + 'static,
+    {
+        if !self.has_subscribers() {
+            return false;
+        }
+
+        let channel = self.clone();
+
+        self.0
+            .events
+            .try_send(move |mut cx| channel.publish(&mut cx, message))
+            .is_ok()
+    }
+}
+
+#[derive(Clone, Debug)]
+/// The `start`, `end` and `error` channels of a Node.js
+/// [`TracingChannel`](https://nodejs.org/api/diagnostics_channel.html#class-tracingchannel),
+/// returned by [`tracing_channel`]
+///
+/// Functions exported with
+/// [`#[neon::function(trace = "name")]`](crate::export) publish to the tracing channel
+/// `name` when they are called.
+pub struct TracingChannel {
+    pub(crate) start: DiagChannel,
+    end: DiagChannel,
+    error: DiagChannel,
+}
+
+/// Returns the channels `tracing:{name}:start`, `tracing:{name}:end` and
+/// `tracing:{name}:error`, the synchronous channels of
+/// `diagnostics_channel.tracingChannel(name)`.
+pub fn tracing_channel<'cx, C: Context<'cx>>(cx: &mut C, name: &str) -> NeonResult<TracingChannel> {
+    Ok(TracingChannel {
+        start: channel(cx, &format!("tracing:{}:start", name))?,
+        end: channel(cx, &format!("tracing:{}:end", name))?,
+        error: channel(cx, &format!("tracing:{}:error", name))?,
+    })
+}
+
+impl TracingChannel {
+    /// Returns `true` if any of the channels has subscribers. See
+    /// [`DiagChannel::has_subscribers`].
+    pub fn has_subscribers(&self) -> bool {
+        self.start.has_subscribers() || self.end.has_subscribers() || self.error.has_subscribers()
+    }
+
+    /// Calls `f`, publishing `context` to `start` before and to `end` after, like
+    /// `tracingChannel.traceSync`.
+    ///
+    /// If `f` throws, the exception is set as the `error` property of `context`, which
+    /// is published to `error` before `end`, and the exception is thrown again. If
+    /// nothing subscribes to any of the channels, `f` is called without publishing.
+    pub fn trace<'cx, C, T, F>(
+        &self,
+        cx: &mut C,
+        context: Handle<'cx, JsObject>,
+        f: F,
+    ) -> NeonResult<T>
+    where
+        C: Context<'cx>,
+        F: FnOnce(&mut C) -> NeonResult<T>,
+    {
+        if !self.has_subscribers() {
+            return f(cx);
+        }
+
+        self.start.publish(cx, context)?;
+
+        let result = f(cx);
+
+        self.finish(cx, context, result)
+    }
+
+    // Publishes to `error` if `result` is an exception, then to `end`
+    pub(crate) fn finish<'cx, C, T>(
+        &self,
+        cx: &mut C,
+        context: Handle<'cx, JsObject>,
+        result: NeonResult<T>,
+    ) -> NeonResult<T>
+    where
+        C: Context<'cx>,
+    {
+        let throw = match result {
+            Ok(v) => {
+                self.end.publish(cx, context)?;
+
+                return Ok(v);
+            }
+            Err(throw) => throw,
+        };
+
+        let mut local = MaybeUninit::zeroed();
+
+        // `Err` may be returned without throwing; there is no error to publish
+        if !unsafe { sys::error::catch_error(cx.env().to_raw(), local.as_mut_ptr()) } {
+            self.end.publish(cx, context)?;
+
+            return Err(throw);
+        }
+
+        let error = JsValue::new_internal(unsafe { local.assume_init() });
+
+        context.set(cx, "error", error)?;
+        self.error.publish(cx, context)?;
+        self.end.publish(cx, context)?;
+
+        cx.throw(error)
+    }
+}
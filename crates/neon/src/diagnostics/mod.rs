@@ -0,0 +1,327 @@
+//! Diagnostics for finding exported functions that block the event loop.
+//!
+//! A synchronous exported function that runs for a long time prevents JavaScript from
+//! running timers, I/O callbacks and rendering, which users experience as a frozen
+//! application. [`block_watchdog`] starts a monitor thread that reports exported
+//! functions that run longer than a threshold. Reports are made while the function is
+//! still running, so a function that never returns is reported as well.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::{sync::Mutex, time::Duration};
+//!
+//! use neon::diagnostics::{self, BlockWatchdog};
+//!
+//! static WATCHDOG: Mutex<Option<BlockWatchdog>> = Mutex::new(None);
+//!
+//! fn enable_watchdog(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let watchdog = diagnostics::block_watchdog(&mut cx, Duration::from_millis(50), |report| {
+//!         eprintln!("`{}` has blocked for {:?}", report.name, report.duration);
+//!     });
+//!
+//!     *WATCHDOG.lock().unwrap() = Some(watchdog);
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+//!
+//! Functions exported with
+//! [`ModuleContext::export_function`](crate::context::ModuleContext::export_function)
+//! are monitored. While no watchdog is enabled, the overhead of monitoring is a single
+//! relaxed atomic load per call. Durations are measured from the start of each call,
+//! including the time spent in nested calls to other exports, and a call is reported
+//! by sampling, up to a quarter of the threshold after it exceeds it.
+//!
+//! With the `napi-6` feature, an addon can also publish events to the Node.js
+//! [`diagnostics_channel`](https://nodejs.org/api/diagnostics_channel.html) module,
+//! where APM tools and other subscribers receive them. [`channel`] returns a named
+//! channel that is cheap to publish to while nothing subscribes to it, including from
+//! other threads, and [`tracing_channel`] the channels of a `TracingChannel`.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::diagnostics;
+//!
+//! fn query(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let sql = cx.argument::<JsString>(0)?.value(&mut cx);
+//!     let channel = diagnostics::channel(&mut cx, "mydb:query")?;
+//!
+//!     // Nothing is converted unless there are subscribers
+//!     channel.publish(&mut cx, sql.clone())?;
+//!
+//!     std::thread::spawn(move || {
+//!         // e.g., `let rows = db.query(&sql);`
+//!         channel.publish_from(format!("finished {}", sql));
+//!     });
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+
+use std::{
+    cell::Cell,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc, Condvar, Mutex, RwLock,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::context::Context;
+
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub use self::channel::{channel, tracing_channel, DiagChannel, TracingChannel};
+
+#[cfg(feature = "napi-6")]
+mod channel;
+
+// Number of enabled watchdogs in the process
+static WATCHDOGS: AtomicUsize = AtomicUsize::new(0);
+
+// Start times of calls are stored as nanoseconds since this instant
+static EPOCH: Lazy<Instant> = Lazy::new(Instant::now);
+
+// Shortest and longest time between samples of a slot
+const MIN_INTERVAL: Duration = Duration::from_millis(1);
+const MAX_INTERVAL: Duration = Duration::from_millis(100);
+
+// The exported functions of a JavaScript thread, shared with the monitor threads of
+// its watchdogs.
+#[derive(Debug, Default)]
+struct Slot {
+    // The exported function currently executing. The lower 32 bits are the index of
+    // the export plus one and the upper 32 bits are a sequence number that
+    // distinguishes consecutive calls to the same export. `0` when idle.
+    current: AtomicU64,
+    // When the current call started, in nanoseconds since `EPOCH`
+    started: AtomicU64,
+    // Names of the functions exported on this thread; `current` stores an index into
+    // this list. Each instance of an addon has its own JavaScript thread, so the names
+    // are freed with the instance instead of accumulating for the whole process.
+    exports: RwLock<Vec<Arc<str>>>,
+}
+
+struct Local {
+    slot: Arc<Slot>,
+    seq: Cell<u32>,
+}
+
+thread_local! {
+    static LOCAL: Local = Local {
+        slot: Default::default(),
+        seq: Cell::new(0),
+    };
+}
+
+impl Slot {
+    fn export(&self, value: u64) -> Option<Arc<str>> {
+        let index = (value as u32).checked_sub(1)? as usize;
+
+        self.exports
+            .read()
+            .unwrap_or_else(|err| err.into_inner())
+            .get(index)
+            .cloned()
+    }
+}
+
+/// Registers the name of a function exported on the current thread and returns its
+/// id for [`track`]
+pub(crate) fn register_export(name: &str) -> u32 {
+    LOCAL.with(|local| {
+        let mut exports = local
+            .slot
+            .exports
+            .write()
+            .unwrap_or_else(|err| err.into_inner());
+
+        exports.push(Arc::from(name));
+        (exports.len() - 1) as u32
+    })
+}
+
+/// Calls `f`, recording that the export `id` is executing if a watchdog is enabled
+pub(crate) fn track<T>(id: u32, f: impl FnOnce() -> T) -> T {
+    if WATCHDOGS.load(Ordering::Relaxed) == 0 {
+        return f();
+    }
+
+    // Restores the previous export and its start time, if any, when a call returns or
+    // panics. Calls are nested when an export calls JavaScript that calls another
+    // export, so the start times of the calls in progress form a stack.
+    //
+    // The start time is stored before the export when a call starts and after it when
+    // a call returns, so a monitor that reads them in between under-reports the
+    // duration of the call instead of over-reporting it.
+    struct Restore {
+        current: u64,
+        started: u64,
+    }
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let _ = LOCAL.try_with(|local| {
+                local.slot.current.store(self.current, Ordering::Relaxed);
+                local.slot.started.store(self.started, Ordering::Relaxed);
+            });
+        }
+    }
+
+    let _restore = LOCAL.with(|local| {
+        let seq = local.seq.get().wrapping_add(1);
+        let value = (u64::from(seq) << 32) | u64::from(id + 1);
+        let now = EPOCH.elapsed().as_nanos() as u64;
+
+        local.seq.set(seq);
+
+        let started = local.slot.started.swap(now, Ordering::Relaxed);
+        let current = local.slot.current.swap(value, Ordering::Relaxed);
+
+        Restore { current, started }
+    });
+
+    f()
+}
+
+#[derive(Clone, Debug)]
+/// An exported function that has blocked the event loop longer than the threshold
+/// of a [`block_watchdog`]
+pub struct BlockReport {
+    /// Name of the exported function
+    pub name: String,
+    /// How long the function had been running when it was reported
+    pub duration: Duration,
+    slot: Arc<Slot>,
+}
+
+impl BlockReport {
+    /// The name of the exported function executing at the moment this is called, or
+    /// `None` if the event loop is idle or running JavaScript.
+    ///
+    /// The blocking function may have returned since the report was made; this reads
+    /// the current state of the thread.
+    pub fn current_export(&self) -> Option<String> {
+        self.slot
+            .export(self.slot.current.load(Ordering::Relaxed))
+            .map(|name| name.to_string())
+    }
+}
+
+/// An enabled watchdog created by [`block_watchdog`]. The watchdog is disabled and its
+/// monitor thread is stopped when it is dropped.
+pub struct BlockWatchdog {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+    monitor: Option<JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for BlockWatchdog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BlockWatchdog")
+    }
+}
+
+impl Drop for BlockWatchdog {
+    fn drop(&mut self) {
+        let (stopped, cvar) = &*self.stop;
+
+        *stopped.lock().unwrap_or_else(|err| err.into_inner()) = true;
+        cvar.notify_all();
+
+        if let Some(monitor) = self.monitor.take() {
+            // A panic in the reporter has already been printed by the monitor thread
+            let _ = monitor.join();
+        }
+
+        WATCHDOGS.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Starts monitoring the exported functions called on the JavaScript thread of `cx`.
+///
+/// `reporter` is called on the monitor thread, at most once per call, for each call
+/// to an exported function that runs longer than `threshold`. Monitoring stops when
+/// the returned [`BlockWatchdog`] is dropped.
+pub fn block_watchdog<'a, C, F>(_cx: &mut C, threshold: Duration, reporter: F) -> BlockWatchdog
+where
+    C: Context<'a>,
+    F: FnMut(&BlockReport) + Send + 'static,
+{
+    let slot = LOCAL.with(|local| Arc::clone(&local.slot));
+    let stop = Arc::new((Mutex::new(false), Condvar::new()));
+    let interval = (threshold / 4).clamp(MIN_INTERVAL, MAX_INTERVAL);
+
+    WATCHDOGS.fetch_add(1, Ordering::Relaxed);
+
+    let monitor = {
+        let stop = Arc::clone(&stop);
+
+        thread::Builder::new()
+            .name("neon-block-watchdog".into())
+            .spawn(move || monitor(slot, threshold, interval, &stop, reporter))
+            .expect("Failed to spawn the block watchdog thread")
+    };
+
+    BlockWatchdog {
+        stop,
+        monitor: Some(monitor),
+    }
+}
+
+fn monitor<F>(
+    slot: Arc<Slot>,
+    threshold: Duration,
+    interval: Duration,
+    stop: &(Mutex<bool>, Condvar),
+    mut reporter: F,
+) where
+    F: FnMut(&BlockReport),
+{
+    let (stopped, cvar) = stop;
+
+    // The calls that were reported and may still be running, with their start times
+    let mut reported: Vec<(u64, u64)> = Vec::new();
+
+    loop {
+        {
+            let stopped = stopped.lock().unwrap_or_else(|err| err.into_inner());
+            let (stopped, _) = cvar
+                .wait_timeout_while(stopped, interval, |stopped| !*stopped)
+                .unwrap_or_else(|err| err.into_inner());
+
+            if *stopped {
+                return;
+            }
+        }
+
+        let value = slot.current.load(Ordering::Relaxed);
+        let started = slot.started.load(Ordering::Relaxed);
+
+        // Calls that started after the current call have returned
+        reported.retain(|&(_, start)| start <= started);
+
+        if value == 0 || reported.iter().any(|&(call, _)| call == value) {
+            continue;
+        }
+
+        let duration = EPOCH
+            .elapsed()
+            .saturating_sub(Duration::from_nanos(started));
+
+        if duration < threshold {
+            continue;
+        }
+
+        if let Some(name) = slot.export(value) {
+            reported.push((value, started));
+            reporter(&BlockReport {
+                name: name.to_string(),
+                duration,
+                slot: Arc::clone(&slot),
+            });
+        }
+    }
+}
@@ -1,15 +1,27 @@
 use std::{
-    error, fmt, mem,
+    collections::VecDeque,
+    error, fmt,
+    mem::{self, MaybeUninit},
+    panic::{self, AssertUnwindSafe},
     sync::{
-        atomic::{AtomicUsize, Ordering},
-        Arc,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
     },
 };
 
 use crate::{
     context::{Context, TaskContext},
     result::{NeonResult, ResultExt, Throw},
-    sys::{raw::Env, tsfn::ThreadsafeFunction},
+    sys::{self, raw::Env, tsfn::ThreadsafeFunction},
+};
+
+#[cfg(feature = "memory-stats")]
+use crate::memory::Tracker;
+
+#[cfg(feature = "operation-ids")]
+use crate::{
+    context::internal::ContextInternal,
+    operation::{self, OperationId},
 };
 
 #[cfg(feature = "futures")]
@@ -46,6 +58,8 @@ mod oneshot {
 
 type Callback = Box<dyn FnOnce(Env) + Send + 'static>;
 
+type CloseCallback = Box<dyn FnOnce() + Send + 'static>;
+
 /// Channel for scheduling Rust closures to execute on the JavaScript main thread.
 ///
 /// Cloning a `Channel` will create a new channel that shares a backing queue for
@@ -91,6 +105,50 @@ type Callback = Box<dyn FnOnce(Env) + Send + 'static>;
 ///     Ok(cx.undefined())
 /// }
 /// ```
+///
+/// # Priorities
+///
+/// Closures sent on the same queue are executed in the order they were sent, unless
+/// they were sent with different [priorities](Priority). A channel with a priority is
+/// created with [`cx.channel_with_priority`](crate::context::Context::channel_with_priority)
+/// or [`with_priority`](Channel::with_priority). Closures with a higher priority are
+/// executed first, but a waiting closure with a lower priority is executed after at
+/// most [`priority_ratio`](Channel::set_priority_ratio) closures with a higher
+/// priority, so it is never starved. Promises settled with
+/// [`Deferred::settle_with`](crate::types::Deferred::settle_with) are settled with
+/// [`Priority::High`], so flooding a channel can't delay them.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::event::Priority;
+///
+/// fn start_indexing(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     // Progress reports are not urgent and should not delay other callbacks
+///     let channel = cx.channel_with_priority(Priority::Low);
+///
+///     std::thread::spawn(move || {
+///         for i in 0..1000 {
+///             channel.send(move |_| {
+///                 println!("indexed {} files", i);
+///                 Ok(())
+///             });
+///         }
+///     });
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+///
+/// # Closing
+///
+/// A channel is closed when the instance of the addon that created it is torn down,
+/// for example, when a worker thread exits. Afterwards, [`try_send`](Channel::try_send)
+/// returns a [`SendError`]. A closure that was sent successfully is always executed,
+/// even if the channel closes before the event loop gets to it. Those closures are
+/// executed while the instance is torn down, when JavaScript may no longer run.
+///
+/// Background threads can stop producing with [`closed`](Channel::closed) and flush
+/// their state with [`on_close`](Channel::on_close).
 #[cfg_attr(
     feature = "channel-api",
     deprecated = "`channel-api` feature has no impact and may be removed"
@@ -103,6 +161,9 @@ type Callback = Box<dyn FnOnce(Env) + Send + 'static>;
 pub struct Channel {
     state: Arc<ChannelState>,
     has_ref: bool,
+    priority: Priority,
+    #[cfg(feature = "operation-ids")]
+    operation: Option<OperationId>,
 }
 
 impl fmt::Debug for Channel {
@@ -115,9 +176,84 @@ impl Channel {
     /// Creates an unbounded channel for scheduling closures on the JavaScript
     /// main thread
     pub fn new<'a, C: Context<'a>>(cx: &mut C) -> Self {
+        #[cfg(feature = "memory-stats")]
+        {
+            let tracker = Tracker::get(cx);
+
+            Self::with_tracker(cx, tracker)
+        }
+
+        #[cfg(not(feature = "memory-stats"))]
         Self {
             state: Arc::new(ChannelState::new(cx)),
             has_ref: true,
+            priority: Priority::Normal,
+            #[cfg(feature = "operation-ids")]
+            operation: operation::current(cx),
+        }
+    }
+
+    #[cfg(feature = "memory-stats")]
+    /// Creates a channel that counts its queue with `tracker`, for creating the shared
+    /// channel while the instance data is initialized
+    pub(crate) fn with_tracker<'a, C: Context<'a>>(cx: &mut C, tracker: Tracker) -> Self {
+        Self {
+            state: Arc::new(ChannelState::new(cx, tracker)),
+            has_ref: true,
+            priority: Priority::Normal,
+            #[cfg(feature = "operation-ids")]
+            operation: operation::current(cx),
+        }
+    }
+
+    /// Returns the channel with closures sent with `priority`. Clones of the channel
+    /// keep the priority.
+    pub fn with_priority(mut self, priority: Priority) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    #[cfg(feature = "operation-ids")]
+    /// Executes the closures sent on this channel in the operation `id`
+    pub(crate) fn in_operation(&mut self, id: Option<OperationId>) -> &mut Self {
+        self.operation = id;
+        self
+    }
+
+    /// Returns the priority of closures sent on this channel
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    /// Sets the number of closures with a higher priority that are executed while a
+    /// closure with a lower priority is waiting, before the waiting closure is
+    /// executed. A `ratio` of `0` is treated as `1`. Applies to every channel sharing
+    /// the queue. _Default: [`DEFAULT_PRIORITY_RATIO`](Channel::DEFAULT_PRIORITY_RATIO)_
+    pub fn set_priority_ratio(&self, ratio: u32) {
+        self.state.shared.lock().ratio = ratio.max(1);
+    }
+
+    /// The default [priority ratio](Channel::set_priority_ratio)
+    pub const DEFAULT_PRIORITY_RATIO: u32 = 16;
+
+    /// Returns counters of the closures sent on the queue shared by this channel,
+    /// for each priority
+    pub fn stats(&self) -> ChannelStats {
+        let queue = self.state.shared.lock();
+        let stats = |priority: Priority| {
+            let i = priority as usize;
+
+            PriorityStats {
+                sent: queue.sent[i],
+                executed: queue.sent[i] - queue.pending[i].len() as u64,
+                pending: queue.pending[i].len(),
+            }
+        };
+
+        ChannelStats {
+            high: stats(Priority::High),
+            normal: stats(Priority::Normal),
+            low: stats(Priority::Low),
         }
     }
 
@@ -160,32 +296,138 @@ impl Channel {
     /// Schedules a closure to execute on the JavaScript thread that created this Channel
     /// Returns an `Error` if the task could not be scheduled.
     ///
-    /// See [`SendError`] for additional details on failure causes.
+    /// If this returns `Ok`, the closure will be executed, even if the channel is closed
+    /// in the meantime. See [`SendError`] for additional details on failure causes.
     pub fn try_send<T, F>(&self, f: F) -> Result<JoinHandle<T>, SendError>
+    where
+        T: Send + 'static,
+        F: FnOnce(TaskContext) -> NeonResult<T> + Send + 'static,
+    {
+        self.try_send_with_priority(self.priority, f)
+    }
+
+    /// Schedules a closure like [`try_send`](Channel::try_send), with `priority`
+    /// instead of the priority of the channel
+    pub(crate) fn try_send_with_priority<T, F>(
+        &self,
+        priority: Priority,
+        f: F,
+    ) -> Result<JoinHandle<T>, SendError>
     where
         T: Send + 'static,
         F: FnOnce(TaskContext) -> NeonResult<T> + Send + 'static,
     {
         let (tx, rx) = oneshot::channel();
+        #[cfg(feature = "operation-ids")]
+        let operation = self.operation;
         let callback = Box::new(move |env| {
             let env = unsafe { mem::transmute(env) };
 
             // Note: It is sufficient to use `TaskContext`'s `InheritedHandleScope` because
             // N-API creates a `HandleScope` before calling the callback.
             TaskContext::with_context(env, move |cx| {
+                #[cfg(feature = "operation-ids")]
+                let _scope = operation::Scope::restore(cx.env().to_raw(), operation);
+
                 // Error can be ignored; it only means the user didn't join
                 let _ = tx.send(f(cx).map_err(Into::into));
             });
         });
 
-        self.state
-            .tsfn
-            .call(callback, None)
-            .map_err(|_| SendError)?;
+        let shared = &self.state.shared;
+        let mut queue = shared.lock();
+
+        if queue.drained {
+            return Err(SendError);
+        }
+
+        #[cfg(feature = "memory-stats")]
+        shared.tracker.queued(mem::size_of_val(&*callback));
+
+        queue.pending[priority as usize].push_back(callback);
+        queue.sent[priority as usize] += 1;
+
+        // Each call dispatches one closure, chosen by priority. A call only fails once
+        // the instance is being torn down; the closure remains queued and is executed
+        // when the channel closes.
+        let _ = self.state.tsfn.call(Arc::clone(shared), None);
 
         Ok(JoinHandle { rx })
     }
 
+    /// Registers a callback to run when the channel is closed because the instance of
+    /// the addon is being torn down. It is called on the JavaScript thread, before
+    /// closures that are still queued are executed, so closures sent by the callback
+    /// are executed as well.
+    ///
+    /// The callback is shared by every clone of the channel and is called exactly once.
+    /// If the channel is already closed, it is called immediately. If every clone of
+    /// the channel is dropped first, it is dropped without being called.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # use std::sync::{atomic::{AtomicBool, Ordering}, Arc};
+    /// fn start_producer(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let channel = cx.channel();
+    ///     let stop = Arc::new(AtomicBool::new(false));
+    ///
+    ///     channel.on_close({
+    ///         let stop = stop.clone();
+    ///         move || stop.store(true, Ordering::Release)
+    ///     });
+    ///
+    ///     std::thread::spawn(move || {
+    ///         while !stop.load(Ordering::Acquire) {
+    ///             // Produce data and send it with `channel.try_send(..)`
+    ///             # break;
+    ///         }
+    ///     });
+    ///
+    ///     Ok(cx.undefined())
+    /// }
+    /// ```
+    pub fn on_close<F>(&self, f: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let shared = &self.state.shared;
+        let mut queue = shared.lock();
+
+        if !shared.closed.load(Ordering::Acquire) {
+            queue.on_close.push(Box::new(f));
+            return;
+        }
+
+        drop(queue);
+        f();
+    }
+
+    /// Returns a handle for checking whether the channel is closed, because the instance
+    /// of the addon is being torn down or every clone of the channel was dropped.
+    ///
+    /// With the `futures` feature, the handle is also a `Future` that completes when the
+    /// channel is closed.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn start_producer(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let channel = cx.channel();
+    ///     let closed = channel.closed();
+    ///
+    ///     std::thread::spawn(move || {
+    ///         while !closed.is_closed() {
+    ///             // Produce data and send it with `channel.try_send(..)`
+    ///             # break;
+    ///         }
+    ///     });
+    ///
+    ///     Ok(cx.undefined())
+    /// }
+    /// ```
+    pub fn closed(&self) -> Closed {
+        Closed(Arc::clone(&self.state.shared))
+    }
+
     /// Returns a boolean indicating if this `Channel` will prevent the Node event
     /// loop from exiting.
     pub fn has_ref(&self) -> bool {
@@ -209,6 +451,9 @@ impl Clone for Channel {
             return Self {
                 state: self.state.clone(),
                 has_ref: false,
+                priority: self.priority,
+                #[cfg(feature = "operation-ids")]
+                operation: self.operation,
             };
         }
 
@@ -220,6 +465,9 @@ impl Clone for Channel {
         Self {
             state,
             has_ref: true,
+            priority: self.priority,
+            #[cfg(feature = "operation-ids")]
+            operation: self.operation,
         }
     }
 }
@@ -249,13 +497,61 @@ impl Drop for Channel {
         // UV thread if strong reference count goes to 0.
         let state = Arc::clone(&self.state);
 
-        self.send(move |mut cx| {
+        // Fails if the channel is closed, in which case the tsfn no longer needs
+        // to be unreferenced
+        let _ = self.try_send_with_priority(Priority::High, move |mut cx| {
             state.unref(&mut cx);
             Ok(())
         });
     }
 }
 
+/// A handle for checking whether a [`Channel`] is closed, created with
+/// [`Channel::closed`]
+///
+/// With the `futures` feature, `Closed` is a `Future` that completes when the channel
+/// is closed.
+#[derive(Clone)]
+pub struct Closed(Arc<Shared>);
+
+impl Closed {
+    /// Returns `true` if the channel is closed. Once closed, a channel stays closed.
+    pub fn is_closed(&self) -> bool {
+        self.0.closed.load(Ordering::Acquire)
+    }
+}
+
+impl fmt::Debug for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Closed").field(&self.is_closed()).finish()
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl Future for Closed {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        if self.is_closed() {
+            return Poll::Ready(());
+        }
+
+        let mut queue = self.0.lock();
+
+        // Check again while holding the lock, since wakers are taken when closing
+        if self.is_closed() {
+            return Poll::Ready(());
+        }
+
+        if !queue.wakers.iter().any(|waker| waker.will_wake(cx.waker())) {
+            queue.wakers.push(cx.waker().clone());
+        }
+
+        Poll::Pending
+    }
+}
+
 /// An owned permission to join on the result of a closure sent to the JavaScript main
 /// thread with [`Channel::send`].
 pub struct JoinHandle<T> {
@@ -354,9 +650,9 @@ impl<T> ResultExt<T> for Result<T, JoinError> {
 
 /// Error indicating that a closure was unable to be scheduled to execute on the event loop.
 ///
-/// The most likely cause of a failure is that Node is shutting down. This may occur if the
-/// process is forcefully exiting even if the channel is referenced. For example, by calling
-/// `process.exit()`.
+/// The most likely cause of a failure is that Node is shutting down and the channel is
+/// [closed](Channel#closing). This may occur if the process is forcefully exiting even if
+/// the channel is referenced. For example, by calling `process.exit()`.
 //
 // NOTE: These docs will need to be updated to include `QueueFull` if bounded queues are
 // implemented.
@@ -377,17 +673,197 @@ impl fmt::Debug for SendError {
 
 impl error::Error for SendError {}
 
+/// The priority of closures sent on a [`Channel`]. See [priorities](Channel#priorities).
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Priority {
+    /// Executed before closures with a lower priority
+    High,
+    /// The priority of channels created without a priority
+    #[default]
+    Normal,
+    /// Executed after closures with a higher priority
+    Low,
+}
+
+/// Counters of the closures sent on the queue of a [`Channel`], returned by
+/// [`Channel::stats`]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ChannelStats {
+    /// Closures sent with [`Priority::High`]
+    pub high: PriorityStats,
+    /// Closures sent with [`Priority::Normal`]
+    pub normal: PriorityStats,
+    /// Closures sent with [`Priority::Low`]
+    pub low: PriorityStats,
+}
+
+/// Counters of the closures sent with one [`Priority`], part of [`ChannelStats`]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PriorityStats {
+    /// Closures sent
+    pub sent: u64,
+    /// Closures that started executing
+    pub executed: u64,
+    /// Closures waiting to be executed
+    pub pending: usize,
+}
+
 struct ChannelState {
-    tsfn: ThreadsafeFunction<Callback>,
+    tsfn: ThreadsafeFunction<Arc<Shared>>,
     ref_count: AtomicUsize,
+    shared: Arc<Shared>,
+}
+
+// State shared by a `ChannelState` and its tsfn. Closures are queued here instead of
+// passed to the tsfn, because N-API drops queued items without an `Env` when the
+// tsfn is finalized; the finalize hook executes them instead.
+#[derive(Default)]
+struct Shared {
+    closed: AtomicBool,
+    queue: Mutex<Queue>,
+    #[cfg(feature = "memory-stats")]
+    tracker: Tracker,
+}
+
+struct Queue {
+    // Closures that were sent and have not been executed, in order, for each priority
+    pending: [VecDeque<Callback>; 3],
+    // Number of closures sent with each priority
+    sent: [u64; 3],
+    // Number of closures executed while the oldest closure of each priority waited
+    passed: [u32; 3],
+    ratio: u32,
+    // Set once the queue has been drained when closing; no closures are accepted
+    drained: bool,
+    // Set when the `ChannelState` is dropped
+    released: bool,
+    on_close: Vec<CloseCallback>,
+    #[cfg(feature = "futures")]
+    wakers: Vec<task::Waker>,
+}
+
+impl Default for Queue {
+    fn default() -> Self {
+        Self {
+            pending: Default::default(),
+            sent: [0; 3],
+            passed: [0; 3],
+            ratio: Channel::DEFAULT_PRIORITY_RATIO,
+            drained: false,
+            released: false,
+            on_close: Vec::new(),
+            #[cfg(feature = "futures")]
+            wakers: Vec::new(),
+        }
+    }
+}
+
+impl Queue {
+    // Takes the closure with the highest priority, unless a closure with a lower
+    // priority waited for `ratio` closures
+    fn pop(&mut self) -> Option<Callback> {
+        let highest = self
+            .pending
+            .iter()
+            .position(|pending| !pending.is_empty())?;
+        let starved = (highest + 1..self.pending.len())
+            .find(|&i| !self.pending[i].is_empty() && self.passed[i] >= self.ratio);
+        let next = starved.unwrap_or(highest);
+
+        for i in 0..self.pending.len() {
+            if i == next || self.pending[i].is_empty() {
+                self.passed[i] = 0;
+            } else {
+                self.passed[i] = self.passed[i].saturating_add(1);
+            }
+        }
+
+        self.pending[next].pop_front()
+    }
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    // Called on the JavaScript thread when the tsfn is finalized, either because every
+    // `Channel` was dropped or because the instance is being torn down
+    fn close(&self, env: Env) {
+        let (on_close, released) = {
+            let mut queue = self.lock();
+
+            self.closed.store(true, Ordering::Release);
+
+            (mem::take(&mut queue.on_close), queue.released)
+        };
+
+        // Futures polled from now on observe `closed` and do not register a waker
+        #[cfg(feature = "futures")]
+        for waker in mem::take(&mut self.lock().wakers) {
+            waker.wake();
+        }
+
+        // Producers can only be notified while a `Channel` exists
+        if !released {
+            for f in on_close {
+                // A panic is printed by the panic hook and there is no one to report it to
+                let _ = panic::catch_unwind(AssertUnwindSafe(f));
+            }
+        }
+
+        let pending = {
+            let mut queue = self.lock();
+
+            queue.drained = true;
+            mem::take(&mut queue.pending)
+        };
+
+        for callback in IntoIterator::into_iter(pending).flatten() {
+            #[cfg(feature = "memory-stats")]
+            self.tracker.dequeued(mem::size_of_val(&*callback));
+
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| callback(env)));
+
+            // Exceptions can't be reported while the instance is torn down
+            unsafe {
+                let mut exception = MaybeUninit::uninit();
+
+                sys::error::catch_error(env, exception.as_mut_ptr());
+            }
+        }
+    }
 }
 
 impl ChannelState {
-    fn new<'a, C: Context<'a>>(cx: &mut C) -> Self {
-        let tsfn = unsafe { ThreadsafeFunction::new(cx.env().to_raw(), Self::callback) };
+    fn new<'a, C: Context<'a>>(
+        cx: &mut C,
+        #[cfg(feature = "memory-stats")] tracker: Tracker,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            #[cfg(feature = "memory-stats")]
+            tracker,
+            ..Default::default()
+        });
+        let tsfn = {
+            let shared = Arc::clone(&shared);
+
+            unsafe {
+                ThreadsafeFunction::with_finalize_hook(
+                    cx.env().to_raw(),
+                    Self::callback,
+                    move |env| shared.close(env),
+                )
+            }
+        };
+
         Self {
             tsfn,
             ref_count: AtomicUsize::new(1),
+            shared,
         }
     }
 
@@ -416,9 +892,18 @@ impl ChannelState {
     }
 
     // Monomorphized trampoline funciton for calling the user provided closure
-    fn callback(env: Option<Env>, callback: Callback) {
+    fn callback(env: Option<Env>, shared: Arc<Shared>) {
         if let Some(env) = env {
-            callback(env);
+            // Each call dispatches the next closure. Closures are only queued without
+            // a call while closing, after which the tsfn no longer dispatches calls.
+            let callback = shared.lock().pop();
+
+            if let Some(callback) = callback {
+                #[cfg(feature = "memory-stats")]
+                shared.tracker.dequeued(mem::size_of_val(&*callback));
+
+                callback(env);
+            }
         } else {
             crate::context::internal::IS_RUNNING.with(|v| {
                 *v.borrow_mut() = false;
@@ -426,3 +911,9 @@ impl ChannelState {
         }
     }
 }
+
+impl Drop for ChannelState {
+    fn drop(&mut self) {
+        self.shared.lock().released = true;
+    }
+}
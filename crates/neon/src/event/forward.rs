@@ -0,0 +1,374 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Condvar, Mutex, MutexGuard,
+    },
+    thread,
+};
+
+use crate::{
+    context::{Context, TaskContext},
+    event::{Channel, ListenerRegistry},
+    handle::{Handle, Root},
+    result::NeonResult,
+    types::{extract::TryIntoJs, JsFunction, JsValue},
+};
+
+/// A receiver of items that can be [forwarded](forward) to JavaScript
+///
+/// Implemented for the receivers of [`std::sync::mpsc`] and, with the `futures`
+/// feature, of `tokio::sync::mpsc`. Other channels can be forwarded by implementing
+/// this trait.
+pub trait ForwardReceiver: Send + 'static {
+    /// The type of the received items
+    type Item: Send + 'static;
+
+    /// Blocks until an item is received, returning `None` once every sender is closed
+    /// and no items remain.
+    fn recv(&mut self) -> Option<Self::Item>;
+
+    /// Returns an item if one is available, without blocking.
+    fn try_recv(&mut self) -> Option<Self::Item>;
+}
+
+impl<T: Send + 'static> ForwardReceiver for mpsc::Receiver<T> {
+    type Item = T;
+
+    fn recv(&mut self) -> Option<T> {
+        mpsc::Receiver::recv(self).ok()
+    }
+
+    fn try_recv(&mut self) -> Option<T> {
+        mpsc::Receiver::try_recv(self).ok()
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T: Send + 'static> ForwardReceiver for tokio::sync::mpsc::Receiver<T> {
+    type Item = T;
+
+    fn recv(&mut self) -> Option<T> {
+        self.blocking_recv()
+    }
+
+    fn try_recv(&mut self) -> Option<T> {
+        tokio::sync::mpsc::Receiver::try_recv(self).ok()
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T: Send + 'static> ForwardReceiver for tokio::sync::mpsc::UnboundedReceiver<T> {
+    type Item = T;
+
+    fn recv(&mut self) -> Option<T> {
+        self.blocking_recv()
+    }
+
+    fn try_recv(&mut self) -> Option<T> {
+        tokio::sync::mpsc::UnboundedReceiver::try_recv(self).ok()
+    }
+}
+
+/// Where [`forward`] delivers items
+pub enum ForwardTarget {
+    /// Calls the function with each item
+    Callback(Root<JsFunction>),
+    /// Emits the event with each item, like [`ListenerRegistry::emit_from`]
+    Event(ListenerRegistry, String),
+}
+
+impl ForwardTarget {
+    fn deliver<'a, C: Context<'a>>(&self, cx: &mut C, item: Handle<'a, JsValue>) -> NeonResult<()> {
+        match self {
+            ForwardTarget::Callback(callback) => {
+                let this = cx.undefined();
+
+                callback.to_inner(cx).exec(cx, this, [item])
+            }
+            ForwardTarget::Event(registry, name) => registry.emit(cx, name, [item]),
+        }
+    }
+}
+
+impl From<Root<JsFunction>> for ForwardTarget {
+    fn from(callback: Root<JsFunction>) -> Self {
+        ForwardTarget::Callback(callback)
+    }
+}
+
+impl<S: Into<String>> From<(ListenerRegistry, S)> for ForwardTarget {
+    fn from((registry, name): (ListenerRegistry, S)) -> Self {
+        ForwardTarget::Event(registry, name.into())
+    }
+}
+
+/// Options controlling the behavior of [`forward_with`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ForwardOptions {
+    /// The maximum number of items sent to the JavaScript thread and not yet delivered.
+    /// Once it is reached, no items are received until JavaScript catches up, so a
+    /// bounded channel fills up and blocks its senders. _Default: `64`_
+    pub max_in_flight: usize,
+}
+
+impl Default for ForwardOptions {
+    fn default() -> Self {
+        Self { max_in_flight: 64 }
+    }
+}
+
+/// A pump started by [`forward`]
+///
+/// Dropping the handle detaches the pump, which keeps running until the receiver or
+/// the channel is closed.
+pub struct Forwarder {
+    state: Arc<State>,
+    pump: thread::JoinHandle<()>,
+}
+
+impl std::fmt::Debug for Forwarder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Forwarder")
+    }
+}
+
+impl Forwarder {
+    /// Stops forwarding. Items that were already sent to the JavaScript thread are
+    /// still delivered, and the receiver is dropped, closing the channel for its
+    /// senders.
+    ///
+    /// A pump waiting for an item stops when the next item is received or the senders
+    /// are closed.
+    pub fn stop(&self) {
+        self.state.stop();
+    }
+
+    /// Returns `true` once the pump has stopped
+    pub fn is_finished(&self) -> bool {
+        self.pump.is_finished()
+    }
+
+    /// Waits for the pump to stop. Must not be called on the JavaScript thread, which
+    /// the pump may be waiting for.
+    pub fn join(self) {
+        // The pump does not call user code that could panic, except when dropping
+        // the receiver
+        let _ = self.pump.join();
+    }
+}
+
+// State shared by the pump and the batches it sent
+struct State {
+    in_flight: Mutex<usize>,
+    changed: Condvar,
+    stopped: AtomicBool,
+}
+
+impl State {
+    fn lock(&self) -> MutexGuard<'_, usize> {
+        self.in_flight.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Acquire)
+    }
+
+    fn stop(&self) {
+        // Locking ensures the pump is either waiting and notified, or checks the
+        // flag before waiting
+        let _in_flight = self.lock();
+
+        self.stopped.store(true, Ordering::Release);
+        self.changed.notify_all();
+    }
+
+    // Waits until fewer than `max` items are in flight, returning how many more can be
+    // sent, or `None` if the pump was stopped
+    fn wait_capacity(&self, max: usize) -> Option<usize> {
+        let in_flight = self.lock();
+        let in_flight = self
+            .changed
+            .wait_while(in_flight, |in_flight| {
+                *in_flight >= max && !self.is_stopped()
+            })
+            .unwrap_or_else(|err| err.into_inner());
+
+        if self.is_stopped() {
+            None
+        } else {
+            Some(max - *in_flight)
+        }
+    }
+}
+
+// Items of a batch that are in flight, released when the batch is delivered. Batches
+// still queued when the instance is torn down are released when the channel drains
+// them, so the pump never waits for a closed channel.
+struct InFlight {
+    state: Arc<State>,
+    len: usize,
+}
+
+impl InFlight {
+    fn new(state: &Arc<State>, len: usize) -> Self {
+        *state.lock() += len;
+
+        Self {
+            state: Arc::clone(state),
+            len,
+        }
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        *self.state.lock() -= self.len;
+        self.state.changed.notify_all();
+    }
+}
+
+/// Forwards the items received by `receiver` to `target` on the JavaScript thread of
+/// `channel`, with the default [`ForwardOptions`].
+///
+/// ```
+/// # use neon::prelude::*;
+/// use std::sync::mpsc;
+///
+/// use neon::event;
+///
+/// // `native.watch((line) => {})`
+/// fn watch(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+///     let channel = cx.channel();
+///     let (tx, rx) = mpsc::sync_channel::<String>(16);
+///
+///     event::forward(rx, channel, callback);
+///
+///     std::thread::spawn(move || {
+///         for i in 0..100 {
+///             // Blocks while JavaScript is behind
+///             if tx.send(format!("line {}", i)).is_err() {
+///                 break;
+///             }
+///         }
+///     });
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+///
+/// See [`forward_with`] for details.
+pub fn forward<R, T>(receiver: R, channel: Channel, target: T) -> Forwarder
+where
+    R: ForwardReceiver,
+    for<'cx> R::Item: TryIntoJs<'cx>,
+    T: Into<ForwardTarget>,
+{
+    forward_with(receiver, channel, target, ForwardOptions::default())
+}
+
+/// Forwards the items received by `receiver` to `target` on the JavaScript thread of
+/// `channel`, with [`ForwardOptions`].
+///
+/// A pump thread receives the items, blocking on the receiver, and sends the items
+/// that are available, up to [`max_in_flight`](ForwardOptions::max_in_flight), to the
+/// JavaScript thread in a single closure. There, each item is converted with
+/// [`TryIntoJs`] and delivered in the order it was received. Every item of a batch is
+/// delivered even if converting or delivering an earlier one throws; the first
+/// exception is then thrown from the closure, where it becomes an uncaught exception.
+///
+/// The pump stops when every sender is closed and the remaining items were sent, when
+/// [`Forwarder::stop`] is called, or when the channel is closed because the instance of
+/// the addon is torn down. The channel keeps the event loop alive while forwarding
+/// unless it is [unreferenced](Channel::unref).
+pub fn forward_with<R, T>(
+    mut receiver: R,
+    channel: Channel,
+    target: T,
+    options: ForwardOptions,
+) -> Forwarder
+where
+    R: ForwardReceiver,
+    for<'cx> R::Item: TryIntoJs<'cx>,
+    T: Into<ForwardTarget>,
+{
+    let max_in_flight = options.max_in_flight.max(1);
+    let target = Arc::new(target.into());
+    let state = Arc::new(State {
+        in_flight: Mutex::new(0),
+        changed: Condvar::new(),
+        stopped: AtomicBool::new(false),
+    });
+
+    let pump = {
+        let state = Arc::clone(&state);
+
+        thread::Builder::new()
+            .name("neon-forward".into())
+            .spawn(move || {
+                while let Some(capacity) = state.wait_capacity(max_in_flight) {
+                    let first = match receiver.recv() {
+                        Some(item) => item,
+                        None => break,
+                    };
+
+                    if state.is_stopped() {
+                        break;
+                    }
+
+                    let mut batch = vec![first];
+
+                    while batch.len() < capacity {
+                        match receiver.try_recv() {
+                            Some(item) => batch.push(item),
+                            None => break,
+                        }
+                    }
+
+                    let in_flight = InFlight::new(&state, batch.len());
+                    let target = Arc::clone(&target);
+                    let sent = channel.try_send(move |cx| deliver(cx, &target, batch, in_flight));
+
+                    if sent.is_err() {
+                        break;
+                    }
+                }
+            })
+            .expect("Failed to spawn the forward thread")
+    };
+
+    Forwarder { state, pump }
+}
+
+fn deliver<I>(
+    mut cx: TaskContext,
+    target: &ForwardTarget,
+    batch: Vec<I>,
+    in_flight: InFlight,
+) -> NeonResult<()>
+where
+    for<'cx> I: TryIntoJs<'cx>,
+{
+    let mut exception = None;
+
+    for item in batch {
+        let result = cx.try_catch(|cx| {
+            let item = item.try_into_js(cx)?.upcast::<JsValue>();
+
+            target.deliver(cx, item)
+        });
+
+        if let Err(err) = result {
+            exception.get_or_insert(err);
+        }
+    }
+
+    drop(in_flight);
+
+    match exception {
+        Some(err) => cx.throw(err),
+        None => Ok(()),
+    }
+}
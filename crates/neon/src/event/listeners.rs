@@ -0,0 +1,220 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    context::Context,
+    event::{Channel, JoinHandle},
+    handle::{Handle, Root},
+    object::Object,
+    result::NeonResult,
+    types::{extract::TryIntoJs, JsFunction, JsValue},
+};
+
+/// Listeners registered from JavaScript for named events
+///
+/// A registry holds a [`Root`] of each listener and drops it when the listener is
+/// removed or the registry is [cleared](ListenerRegistry::clear). It is usually stored
+/// in a [`LocalKey`](crate::thread::LocalKey), so that each instance of the addon has
+/// its own listeners:
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::{event::ListenerRegistry, thread::LocalKey};
+///
+/// static LISTENERS: LocalKey<ListenerRegistry> = LocalKey::new();
+///
+/// // `native.on("progress", (percent) => {})`
+/// fn on(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let name = cx.argument::<JsString>(0)?.value(&mut cx);
+///     let listener = cx.argument::<JsFunction>(1)?;
+///     let id = LISTENERS
+///         .get_or_init_default(&mut cx)
+///         .on(&mut cx, &name, listener);
+///
+///     Ok(cx.number(id.as_u64() as f64))
+/// }
+///
+/// fn start(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let listeners = LISTENERS.get_or_init_default(&mut cx).clone();
+///     let channel = cx.channel();
+///
+///     std::thread::spawn(move || {
+///         for percent in [25.0, 50.0, 75.0, 100.0] {
+///             listeners.emit_from(&channel, "progress", percent);
+///         }
+///     });
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+///
+/// Listeners are called in the order they were registered. Each call to
+/// [`emit`](ListenerRegistry::emit) calls the listeners that were registered when it
+/// started, like Node's `EventEmitter`: a listener removed while the event is emitted
+/// is still called and a listener added while the event is emitted is not.
+///
+/// Cloning a `ListenerRegistry` produces another handle to the same listeners.
+#[derive(Clone, Default)]
+pub struct ListenerRegistry {
+    listeners: Arc<Mutex<Listeners>>,
+}
+
+/// Identifies a listener added to a [`ListenerRegistry`], for removing it with
+/// [`ListenerRegistry::off`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ListenerId(u64);
+
+impl ListenerId {
+    /// Returns the id as a number, for example, to return it to JavaScript
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+
+    /// Creates an id from a number returned by [`as_u64`](ListenerId::as_u64)
+    pub fn from_u64(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+#[derive(Default)]
+struct Listeners {
+    next_id: u64,
+    // Listeners in the order they were registered
+    entries: Vec<Listener>,
+}
+
+struct Listener {
+    id: ListenerId,
+    name: String,
+    callback: Root<JsFunction>,
+}
+
+impl ListenerRegistry {
+    /// Creates a registry without listeners
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Listeners> {
+        self.listeners.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Adds a listener for the event `name`. The same function may be added more than
+    /// once and is called once for each time it was added.
+    pub fn on<'a, C: Context<'a>>(
+        &self,
+        cx: &mut C,
+        name: &str,
+        listener: Handle<JsFunction>,
+    ) -> ListenerId {
+        let callback = listener.root(cx);
+        let mut listeners = self.lock();
+        let id = ListenerId(listeners.next_id);
+
+        listeners.next_id += 1;
+        listeners.entries.push(Listener {
+            id,
+            name: name.to_string(),
+            callback,
+        });
+
+        id
+    }
+
+    /// Removes a listener, returning `false` if it was already removed
+    pub fn off<'a, C: Context<'a>>(&self, cx: &mut C, id: ListenerId) -> bool {
+        let removed = {
+            let mut listeners = self.lock();
+            let i = listeners
+                .entries
+                .iter()
+                .position(|listener| listener.id == id);
+
+            i.map(|i| listeners.entries.remove(i))
+        };
+
+        match removed {
+            Some(listener) => {
+                listener.callback.drop(cx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the number of listeners for the event `name`
+    pub fn listener_count(&self, name: &str) -> usize {
+        self.lock()
+            .entries
+            .iter()
+            .filter(|listener| listener.name == name)
+            .count()
+    }
+
+    /// Calls the listeners for the event `name` with `args`.
+    ///
+    /// Every listener is called, even if an earlier listener throws. The first
+    /// exception is re-thrown once all the listeners have been called.
+    pub fn emit<'a, 'b, C, AS>(&self, cx: &mut C, name: &str, args: AS) -> NeonResult<()>
+    where
+        C: Context<'a>,
+        AS: AsRef<[Handle<'b, JsValue>]>,
+    {
+        // Listeners are called without holding the lock, so they may add or remove
+        // listeners
+        let callbacks = self
+            .lock()
+            .entries
+            .iter()
+            .filter(|listener| listener.name == name)
+            .map(|listener| listener.callback.to_inner(cx))
+            .collect::<Vec<_>>();
+
+        let this = cx.undefined();
+        let mut exception = None;
+
+        for callback in callbacks {
+            if let Err(err) = cx.try_catch(|cx| callback.call(cx, this, args.as_ref())) {
+                exception.get_or_insert(err);
+            }
+        }
+
+        match exception {
+            Some(err) => cx.throw(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Emits the event `name` on the JavaScript thread of `channel`, calling the
+    /// listeners with `payload` converted to JavaScript.
+    ///
+    /// Events emitted from the same thread are delivered in the order they were
+    /// emitted. An exception thrown by a listener is thrown from the closure sent to
+    /// the channel, where it becomes an uncaught exception.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the channel is closed, like [`Channel::send`].
+    pub fn emit_from<T>(&self, channel: &Channel, name: &str, payload: T) -> JoinHandle<()>
+    where
+        T: Send + 'static,
+        for<'cx> T: TryIntoJs<'cx>,
+    {
+        let registry = self.clone();
+        let name = name.to_string();
+
+        channel.send(move |mut cx| {
+            let payload = payload.try_into_js(&mut cx)?.upcast::<JsValue>();
+
+            registry.emit(&mut cx, &name, [payload])
+        })
+    }
+
+    /// Removes every listener
+    pub fn clear<'a, C: Context<'a>>(&self, cx: &mut C) {
+        let entries = std::mem::take(&mut self.lock().entries);
+
+        for listener in entries {
+            listener.callback.drop(cx);
+        }
+    }
+}
@@ -121,15 +121,36 @@
 
 #[cfg(feature = "napi-4")]
 mod channel;
+#[cfg(feature = "napi-6")]
+mod forward;
+#[cfg(feature = "napi-6")]
+mod listeners;
 
+#[cfg(feature = "napi-5")]
+mod oneshot;
 mod task;
+pub(crate) mod work;
 
+#[cfg(feature = "napi-5")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-5")))]
+pub use self::oneshot::{JsOneshot, JsOneshotReceiver, RecvError};
 pub use self::task::TaskBuilder;
+pub use self::work::{AsyncWork, Cancelled, WorkHandle};
 
 #[cfg(all(feature = "napi-5", feature = "futures"))]
 pub(crate) use self::channel::SendThrow;
 #[cfg(feature = "napi-4")]
-pub use self::channel::{Channel, JoinError, JoinHandle, SendError};
+pub use self::channel::{
+    Channel, ChannelStats, JoinError, JoinHandle, Priority, PriorityStats, SendError,
+};
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub use self::forward::{
+    forward, forward_with, ForwardOptions, ForwardReceiver, ForwardTarget, Forwarder,
+};
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub use self::listeners::{ListenerId, ListenerRegistry};
 
 #[cfg(feature = "napi-4")]
 #[deprecated(since = "0.9.0", note = "Please use the Channel type instead")]
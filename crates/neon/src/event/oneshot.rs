@@ -0,0 +1,285 @@
+use std::{
+    error, fmt,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::Waker,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    context::Context,
+    result::JsResult,
+    types::{extract::TryFromJs, JsFunction, JsUndefined, JsValue},
+};
+
+#[cfg(feature = "futures")]
+use {
+    std::future::Future,
+    std::pin::Pin,
+    std::task::{self, Poll},
+};
+
+/// A channel for receiving a single value from a JavaScript callback.
+///
+/// [`JsOneshot::channel`] creates a sender and a [`JsOneshotReceiver`]. The sender is
+/// converted into a JavaScript function with [`to_js_function`](JsOneshot::to_js_function)
+/// and the receiver is moved to the Rust code waiting for the value, for example, a
+/// background thread.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::event::JsOneshot;
+///
+/// // Calls `getToken(callback)` and waits for the token on a background thread
+/// fn login(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let get_token = cx.argument::<JsFunction>(0)?;
+///     let (tx, rx) = JsOneshot::<String>::channel();
+///     let callback = tx.to_js_function(&mut cx)?;
+///
+///     get_token.call_with(&cx).arg(callback).exec(&mut cx)?;
+///
+///     std::thread::spawn(move || {
+///         if let Ok(token) = rx.recv() {
+///             // e.g., connect with `token`
+///         }
+///     });
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+pub struct JsOneshot<T> {
+    // `None` after a value has been sent
+    shared: Option<Arc<Shared<T>>>,
+}
+
+/// The receiving half of a [`JsOneshot`]
+///
+/// The value can be received by blocking with [`recv`](JsOneshotReceiver::recv) or
+/// [`recv_timeout`](JsOneshotReceiver::recv_timeout), or, with the `futures` feature,
+/// by awaiting the receiver. Blocking must not be performed on the JavaScript main
+/// thread, since it would prevent the callback from being called.
+pub struct JsOneshotReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Error returned by [`JsOneshotReceiver`] when a value could not be received
+pub enum RecvError {
+    /// The sender, or the JavaScript function created from it, was dropped without
+    /// being called, or the value was already received
+    Dropped,
+    /// The JavaScript function was called with a value that could not be converted
+    Conversion,
+    /// No value was received before the timeout of
+    /// [`recv_timeout`](JsOneshotReceiver::recv_timeout) elapsed
+    Timeout,
+}
+
+impl fmt::Display for RecvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            RecvError::Dropped => "oneshot callback was dropped without being called",
+            RecvError::Conversion => "oneshot callback was called with a value of the wrong type",
+            RecvError::Timeout => "timed out waiting for the oneshot callback",
+        })
+    }
+}
+
+impl error::Error for RecvError {}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    ready: Condvar,
+}
+
+enum State<T> {
+    Empty(Option<Waker>),
+    Ready(Result<T, RecvError>),
+    Taken,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn complete(&self, result: Result<T, RecvError>) {
+        let waker = {
+            let mut state = self.lock();
+
+            match std::mem::replace(&mut *state, State::Ready(result)) {
+                State::Empty(waker) => waker,
+                _ => unreachable!("JsOneshot completed more than once"),
+            }
+        };
+
+        self.ready.notify_all();
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> JsOneshot<T> {
+    /// Creates a sender and receiver for a single value
+    pub fn channel() -> (Self, JsOneshotReceiver<T>) {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::Empty(None)),
+            ready: Condvar::new(),
+        });
+
+        let tx = Self {
+            shared: Some(Arc::clone(&shared)),
+        };
+
+        (tx, JsOneshotReceiver { shared })
+    }
+
+    fn send(mut self, result: Result<T, RecvError>) {
+        if let Some(shared) = self.shared.take() {
+            shared.complete(result);
+        }
+    }
+}
+
+impl<T> JsOneshot<T>
+where
+    T: for<'cx> TryFromJs<'cx> + Send + 'static,
+{
+    /// Converts the sender into a JavaScript function that sends its first argument to
+    /// the receiver.
+    ///
+    /// The function may only be called once; later calls throw an `Error`. If the
+    /// argument can't be converted to `T`, the function throws a `TypeError` and the
+    /// receiver fails with [`RecvError::Conversion`]. If the function is garbage
+    /// collected without being called, the receiver fails with [`RecvError::Dropped`].
+    pub fn to_js_function<'a, C: Context<'a>>(self, cx: &mut C) -> JsResult<'a, JsFunction> {
+        let tx = Mutex::new(Some(self));
+
+        JsFunction::new(cx, move |mut cx| -> JsResult<JsUndefined> {
+            let tx = tx.lock().ok().and_then(|mut tx| tx.take());
+            let tx = match tx {
+                Some(tx) => tx,
+                None => return cx.throw_error("oneshot callback may only be called once"),
+            };
+
+            let v = cx
+                .argument_opt(0)
+                .unwrap_or_else(|| cx.undefined().upcast::<JsValue>());
+
+            match T::try_from_js(&mut cx, v) {
+                Ok(Some(v)) => {
+                    tx.send(Ok(v));
+
+                    Ok(cx.undefined())
+                }
+                Ok(None) => {
+                    tx.send(Err(RecvError::Conversion));
+
+                    cx.throw_type_error(RecvError::Conversion.to_string())
+                }
+                Err(throw) => {
+                    tx.send(Err(RecvError::Conversion));
+
+                    Err(throw)
+                }
+            }
+        })
+    }
+}
+
+impl<T> Drop for JsOneshot<T> {
+    fn drop(&mut self) {
+        if let Some(shared) = self.shared.take() {
+            shared.complete(Err(RecvError::Dropped));
+        }
+    }
+}
+
+impl<T> fmt::Debug for JsOneshot<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("JsOneshot")
+    }
+}
+
+impl<T> JsOneshotReceiver<T> {
+    /// Blocks the current thread until the value is received
+    pub fn recv(self) -> Result<T, RecvError> {
+        let mut state = self.shared.lock();
+
+        loop {
+            if let Some(result) = take(&mut state) {
+                return result;
+            }
+
+            state = self
+                .shared
+                .ready
+                .wait(state)
+                .unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    /// Blocks the current thread until the value is received or `timeout` elapses.
+    ///
+    /// After a [`RecvError::Timeout`], the receiver may be used to wait again.
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<T, RecvError> {
+        let deadline = Instant::now() + timeout;
+        let mut state = self.shared.lock();
+
+        loop {
+            if let Some(result) = take(&mut state) {
+                return result;
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return Err(RecvError::Timeout);
+            }
+
+            state = self
+                .shared
+                .ready
+                .wait_timeout(state, deadline - now)
+                .unwrap_or_else(|err| err.into_inner())
+                .0;
+        }
+    }
+}
+
+// Takes the result if it is ready
+fn take<T>(state: &mut State<T>) -> Option<Result<T, RecvError>> {
+    match std::mem::replace(state, State::Taken) {
+        State::Ready(result) => Some(result),
+        State::Taken => Some(Err(RecvError::Dropped)),
+        empty => {
+            *state = empty;
+            None
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+#[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+impl<T> Future for JsOneshotReceiver<T> {
+    type Output = Result<T, RecvError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut task::Context) -> Poll<Self::Output> {
+        let mut state = self.shared.lock();
+
+        if let Some(result) = take(&mut state) {
+            return Poll::Ready(result);
+        }
+
+        *state = State::Empty(Some(cx.waker().clone()));
+
+        Poll::Pending
+    }
+}
+
+impl<T> fmt::Debug for JsOneshotReceiver<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("JsOneshotReceiver")
+    }
+}
@@ -1,4 +1,15 @@
-use std::{panic::resume_unwind, thread};
+use std::{
+    cell::Cell,
+    panic::resume_unwind,
+    thread,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "napi-5")]
+use std::{
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
     context::{internal::Env, Context, TaskContext},
@@ -8,6 +19,22 @@ use crate::{
     types::{Deferred, JsPromise, Value},
 };
 
+#[cfg(feature = "operation-ids")]
+use crate::operation::{self, OperationId};
+
+#[cfg(feature = "napi-5")]
+use crate::{
+    context::internal::ContextInternal,
+    handle::Root,
+    object::Object,
+    types::{JsError, JsFunction, JsObject, JsValue},
+};
+
+thread_local! {
+    // Deadline of the task executing on the current thread
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
 #[cfg_attr(
     feature = "task-api",
     deprecated = "`task-api` feature has no impact and may be removed"
@@ -29,6 +56,9 @@ use crate::{
 pub struct TaskBuilder<'cx, C, E> {
     cx: &'cx mut C,
     execute: E,
+    timeout: Option<Duration>,
+    #[cfg(feature = "operation-ids")]
+    operation: Option<OperationId>,
 }
 
 impl<'a: 'cx, 'cx, C, O, E> TaskBuilder<'cx, C, E>
@@ -40,7 +70,56 @@ where
     /// Construct a new task builder from an `execute` callback that can be
     /// scheduled to execute on the Node worker pool
     pub fn new(cx: &'cx mut C, execute: E) -> Self {
-        Self { cx, execute }
+        Self {
+            #[cfg(feature = "operation-ids")]
+            operation: operation::current(cx),
+            cx,
+            execute,
+            timeout: None,
+        }
+    }
+
+    #[cfg(feature = "napi-5")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-5")))]
+    /// Sets a deadline for the task, measured from when it is scheduled.
+    ///
+    /// If the task has not completed by the deadline, the promise returned by
+    /// [`promise`](TaskBuilder::promise) is rejected with an `Error` with a `name` of
+    /// `"TimeoutError"` and a `code` of `"TIMEOUT_ERR"`. The task can't be interrupted,
+    /// but it may poll [`TaskContext::deadline_exceeded`] to stop early. A task that
+    /// has not started executing by the deadline is cancelled, like
+    /// [`WorkHandle::cancel`](crate::event::WorkHandle::cancel), and never executes.
+    /// When a task completes after the deadline, its result is discarded and the
+    /// `complete` callback is not called.
+    ///
+    /// A timeout too large to be represented as a deadline is ignored.
+    ///
+    /// Tasks scheduled with [`and_then`](TaskBuilder::and_then) may poll the deadline,
+    /// but are always completed.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # use std::time::Duration;
+    /// fn search(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    ///     let promise = cx
+    ///         .task(|| {
+    ///             let mut tries = 0u64;
+    ///
+    ///             while !TaskContext::deadline_exceeded() && tries < 1_000_000 {
+    ///                 tries += 1;
+    ///             }
+    ///
+    ///             tries
+    ///         })
+    ///         .timeout(Duration::from_millis(100))
+    ///         .promise(|mut cx, tries| Ok(cx.number(tries as f64)));
+    ///
+    ///     Ok(promise)
+    /// }
+    /// ```
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
     }
 
     /// Schedules a task to execute on the Node worker pool, executing the
@@ -51,7 +130,18 @@ where
         F: FnOnce(TaskContext, O) -> NeonResult<()> + Send + 'static,
     {
         let env = self.cx.env();
-        let execute = self.execute;
+        let execute = with_deadline(deadline(self.timeout), self.execute);
+
+        #[cfg(feature = "operation-ids")]
+        let complete = {
+            let operation = self.operation;
+
+            move |cx: TaskContext, output| {
+                let _scope = operation::Scope::restore(cx.env().to_raw(), operation);
+
+                complete(cx, output)
+            }
+        };
 
         schedule(env, execute, complete);
     }
@@ -69,7 +159,15 @@ where
     {
         let env = self.cx.env();
         let (deferred, promise) = JsPromise::new(self.cx);
-        let execute = self.execute;
+        let deadline = deadline(self.timeout);
+        let execute = with_deadline(deadline, self.execute);
+
+        #[cfg(feature = "napi-5")]
+        if let (Some(timeout), Some(_)) = (self.timeout, deadline) {
+            schedule_promise_timeout(self.cx, execute, complete, deferred, timeout);
+
+            return promise;
+        }
 
         schedule_promise(env, execute, complete, deferred);
 
@@ -77,6 +175,47 @@ where
     }
 }
 
+impl<'a> TaskContext<'a> {
+    /// Returns `true` if the deadline set with [`TaskBuilder::timeout`] has passed.
+    ///
+    /// Called from the `execute` callback of a task to stop long running work early.
+    /// Always returns `false` if the task does not have a deadline or if it is called
+    /// from any other thread.
+    pub fn deadline_exceeded() -> bool {
+        DEADLINE.with(|deadline| match deadline.get() {
+            Some(deadline) => Instant::now() >= deadline,
+            None => false,
+        })
+    }
+}
+
+// A timeout that overflows an `Instant` is too far in the future to ever be reached
+fn deadline(timeout: Option<Duration>) -> Option<Instant> {
+    timeout.and_then(|timeout| Instant::now().checked_add(timeout))
+}
+
+// Wraps `execute` so that `TaskContext::deadline_exceeded` observes the deadline
+fn with_deadline<I, O>(deadline: Option<Instant>, execute: I) -> impl FnOnce() -> O + Send + 'static
+where
+    I: FnOnce() -> O + Send + 'static,
+    O: Send + 'static,
+{
+    move || {
+        struct Reset(Option<Instant>);
+
+        impl Drop for Reset {
+            fn drop(&mut self) {
+                DEADLINE.with(|deadline| deadline.set(self.0));
+            }
+        }
+
+        // Restores the previous deadline, even if `execute` panics
+        let _reset = Reset(DEADLINE.with(|current| current.replace(deadline)));
+
+        execute()
+    }
+}
+
 // Schedule a task to execute on the Node worker pool
 fn schedule<I, O, D>(env: Env, input: I, data: D)
 where
@@ -151,3 +290,182 @@ fn complete_promise<O, D, V>(
         })
     });
 }
+
+// Settlement state shared by the timer and the task completion. Whichever runs first
+// takes the `Deferred`.
+#[cfg(feature = "napi-5")]
+struct Pending {
+    deferred: Option<Deferred>,
+    timer: Option<Root<JsObject>>,
+}
+
+#[cfg(feature = "napi-5")]
+impl Pending {
+    // Takes the `Deferred` if it has not been settled and clears the timer
+    fn take<'a, C: Context<'a>>(pending: &Mutex<Pending>, cx: &mut C) -> Option<Deferred> {
+        let mut pending = pending.lock().unwrap();
+
+        if let Some(timer) = pending.timer.take() {
+            let timer = timer.into_inner(cx);
+
+            // Failing to clear the timer only delays the exit of Node
+            let _ = cx.try_catch(|cx| {
+                let clear_timeout = cx.global().get::<JsFunction, _, _>(cx, "clearTimeout")?;
+                let this = cx.undefined();
+
+                clear_timeout.call(cx, this, [timer.upcast::<JsValue>()])
+            });
+        }
+
+        pending.deferred.take()
+    }
+}
+
+// Longest delay accepted by `setTimeout`; longer delays fire immediately
+#[cfg(feature = "napi-5")]
+const MAX_DELAY: Duration = Duration::from_millis(i32::MAX as u64);
+
+// Schedule a task and a timer to reject the `Promise` if the task does not complete in time
+#[cfg(feature = "napi-5")]
+fn schedule_promise_timeout<'a, C, I, O, D, V>(
+    cx: &mut C,
+    input: I,
+    complete: D,
+    deferred: Deferred,
+    timeout: Duration,
+) where
+    C: Context<'a>,
+    I: FnOnce() -> O + Send + 'static,
+    O: Send + 'static,
+    D: FnOnce(TaskContext, O) -> JsResult<V> + Send + 'static,
+    V: Value,
+{
+    let pending = Arc::new(Mutex::new(Pending {
+        deferred: Some(deferred),
+        timer: None,
+    }));
+
+    let work = unsafe {
+        async_work::schedule_cancellable(
+            cx.env().to_raw(),
+            "neon_async_work",
+            input,
+            execute::<I, O>,
+            complete_promise_timeout::<I, O, D, V>,
+            (complete, Arc::clone(&pending)),
+        )
+    };
+
+    let work = Rc::new(work);
+    let result = cx.try_catch(|cx| start_timer(cx, &pending, &work, timeout, timeout));
+
+    if let Err(err) = result {
+        if let Some(deferred) = pending.lock().unwrap().deferred.take() {
+            deferred.reject(cx, err);
+        }
+
+        unsafe {
+            work.cancel(cx.env().to_raw());
+        }
+    }
+}
+
+// Starts a timer for the `remaining` part of the timeout. Delays longer than
+// `setTimeout` accepts are split into a chain of timers.
+#[cfg(feature = "napi-5")]
+fn start_timer<'a, C: Context<'a>>(
+    cx: &mut C,
+    pending: &Arc<Mutex<Pending>>,
+    work: &Rc<async_work::Work>,
+    remaining: Duration,
+    timeout: Duration,
+) -> NeonResult<()> {
+    let delay = remaining.min(MAX_DELAY);
+    let on_timeout = {
+        let pending = Arc::clone(pending);
+        let work = Rc::clone(work);
+
+        JsFunction::new(cx, move |mut cx| {
+            if delay < remaining {
+                // The task may have completed and cleared the timer
+                if pending.lock().unwrap().deferred.is_some() {
+                    start_timer(&mut cx, &pending, &work, remaining - delay, timeout)?;
+                }
+
+                return Ok(cx.undefined());
+            }
+
+            if let Some(deferred) = Pending::take(&pending, &mut cx) {
+                #[cfg(feature = "operation-ids")]
+                let _scope = operation::Scope::restore(cx.env().to_raw(), deferred.operation());
+
+                // A task that has not started will never be needed
+                unsafe {
+                    work.cancel(cx.env().to_raw());
+                }
+
+                let err = timeout_error(&mut cx, timeout)?;
+
+                deferred.reject(&mut cx, err);
+            }
+
+            Ok(cx.undefined())
+        })?
+    };
+
+    let ms = cx.number(delay.as_secs_f64() * 1000.0);
+    let set_timeout = cx.global().get::<JsFunction, _, _>(cx, "setTimeout")?;
+    let this = cx.undefined();
+    let timer = set_timeout.call(cx, this, [on_timeout.upcast(), ms.upcast()])?;
+
+    // Node returns a `Timeout` object and browsers a number. `clearTimeout` is
+    // only needed to allow Node to exit early, so a number can be ignored.
+    if let Ok(timer) = timer.downcast::<JsObject, _>(cx) {
+        pending.lock().unwrap().timer = Some(timer.root(cx));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "napi-5")]
+fn complete_promise_timeout<I, O, D, V>(
+    env: raw::Env,
+    output: async_work::Output<I, O>,
+    (complete, pending): (D, Arc<Mutex<Pending>>),
+) where
+    O: Send + 'static,
+    D: FnOnce(TaskContext, O) -> JsResult<V> + Send + 'static,
+    V: Value,
+{
+    // The task was cancelled by the timer, which rejected the promise
+    let output = match output {
+        async_work::Output::Completed(output) => output,
+        async_work::Output::Cancelled(_) => return,
+    };
+
+    TaskContext::with_context(env.into(), move |mut cx| {
+        let deferred = match Pending::take(&pending, &mut cx) {
+            Some(deferred) => deferred,
+            // The promise was rejected by the timer; discard the late result
+            None => return,
+        };
+
+        deferred.try_catch_settle(cx, move |cx| {
+            let output = output.unwrap_or_else(|panic| resume_unwind(panic));
+
+            complete(cx, output)
+        })
+    });
+}
+
+#[cfg(feature = "napi-5")]
+fn timeout_error<'a, C: Context<'a>>(cx: &mut C, timeout: Duration) -> JsResult<'a, JsError> {
+    let err = JsError::error(cx, format!("task timed out after {:?}", timeout))?;
+    let name = cx.string("TimeoutError");
+    let code = cx.string("TIMEOUT_ERR");
+
+    err.set(cx, "name", name)?;
+    err.set(cx, "code", code)?;
+
+    Ok(err)
+}
@@ -0,0 +1,155 @@
+use std::{error, fmt, panic::resume_unwind};
+
+use crate::{
+    context::{internal::Env, Context, TaskContext},
+    result::NeonResult,
+    sys::{async_work, raw},
+};
+
+/// A unit of work that can be executed on the Node worker pool with
+/// [`Context::queue_work`].
+///
+/// Unlike [`TaskBuilder`](crate::event::TaskBuilder), which accepts closures
+/// captured at the call site, `AsyncWork` allows defining a reusable worker
+/// type. The work item is passed back to [`complete`](AsyncWork::complete),
+/// where it may be queued again.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::event::{AsyncWork, Cancelled};
+///
+/// struct Fibonacci {
+///     n: u64,
+///     callback: Root<JsFunction>,
+/// }
+///
+/// impl AsyncWork for Fibonacci {
+///     type Output = u64;
+///
+///     const NAME: &'static str = "Fibonacci";
+///
+///     fn execute(&mut self) -> u64 {
+///         let (mut a, mut b) = (0u64, 1u64);
+///
+///         for _ in 0..self.n {
+///             (a, b) = (b, a.wrapping_add(b));
+///         }
+///
+///         a
+///     }
+///
+///     fn complete(
+///         self,
+///         cx: &mut TaskContext,
+///         output: Result<u64, Cancelled>,
+///     ) -> NeonResult<()> {
+///         let callback = self.callback.into_inner(cx);
+///         let this = cx.undefined();
+///         let arg = match output {
+///             Ok(n) => cx.number(n as f64).upcast::<JsValue>(),
+///             Err(_) => cx.null().upcast(),
+///         };
+///
+///         callback.call(cx, this, [arg])?;
+///
+///         Ok(())
+///     }
+/// }
+///
+/// fn fibonacci(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+///     let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+///
+///     cx.queue_work(Fibonacci { n, callback });
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+pub trait AsyncWork: Send + Sized + 'static {
+    /// Value produced by [`execute`](AsyncWork::execute) and passed to
+    /// [`complete`](AsyncWork::complete)
+    type Output: Send + 'static;
+
+    /// Name of the async resource, visible to `async_hooks`
+    const NAME: &'static str = "neon_async_work";
+
+    /// Executes on the Node worker pool. JavaScript may not be called.
+    fn execute(&mut self) -> Self::Output;
+
+    /// Executes on the JavaScript main thread after `execute` has finished.
+    ///
+    /// If the work was cancelled with [`WorkHandle::cancel`] before it started,
+    /// `execute` is not called and `output` is `Err(Cancelled)`.
+    fn complete(
+        self,
+        cx: &mut TaskContext,
+        output: Result<Self::Output, Cancelled>,
+    ) -> NeonResult<()>;
+}
+
+/// Handle to work queued with [`Context::queue_work`]
+pub struct WorkHandle {
+    work: async_work::Work,
+}
+
+impl WorkHandle {
+    /// Attempts to cancel the work.
+    ///
+    /// Work may only be cancelled if it has not started executing. Returns `true`
+    /// if the work was cancelled, in which case [`AsyncWork::complete`] will be
+    /// called with `Err(Cancelled)`. Returns `false` if the work has already
+    /// started or completed.
+    pub fn cancel<'a, C: Context<'a>>(&self, cx: &mut C) -> bool {
+        unsafe { self.work.cancel(cx.env().to_raw()) }
+    }
+}
+
+/// Indicates that [`AsyncWork`] was cancelled before it started executing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt("Async work was cancelled", f)
+    }
+}
+
+impl error::Error for Cancelled {}
+
+pub(crate) fn queue<W: AsyncWork>(env: Env, work: W) -> WorkHandle {
+    let work = unsafe {
+        async_work::schedule_cancellable(
+            env.to_raw(),
+            W::NAME,
+            work,
+            execute::<W>,
+            complete::<W>,
+            (),
+        )
+    };
+
+    WorkHandle { work }
+}
+
+fn execute<W: AsyncWork>(mut work: W) -> (W, W::Output) {
+    let output = work.execute();
+
+    (work, output)
+}
+
+fn complete<W: AsyncWork>(env: raw::Env, output: async_work::Output<W, (W, W::Output)>, _: ()) {
+    let (work, output) = match output {
+        async_work::Output::Completed(output) => {
+            // If a panic was caught while executing the work on the Node Worker
+            // pool, resume panicking on the main JavaScript thread
+            let (work, output) = output.unwrap_or_else(|panic| resume_unwind(panic));
+
+            (work, Ok(output))
+        }
+        async_work::Output::Cancelled(work) => (work, Err(Cancelled)),
+    };
+
+    TaskContext::with_context(env.into(), move |mut cx| {
+        let _ = work.complete(&mut cx, output);
+    });
+}
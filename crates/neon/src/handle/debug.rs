@@ -0,0 +1,84 @@
+//! Liveness checking of handles, enabled by the `debug-handles` feature.
+//!
+//! Every context pushes a _generation_ on a thread-local stack when it is created and
+//! pops it when it ends. Handles are branded with the generation of the innermost
+//! context when they are created, and a handle is live as long as its generation is
+//! on the stack. Using a handle that is not live panics with the location where it was
+//! created and the location where it was used.
+
+use std::{cell::RefCell, panic::Location};
+
+thread_local! {
+    // Generations of the live scopes, in increasing order, and the next generation
+    static SCOPES: RefCell<(Vec<u64>, u64)> = const { RefCell::new((Vec::new(), 1)) };
+}
+
+/// The generation of a live scope, which ends when the guard is dropped.
+pub(crate) struct ScopeGuard {
+    generation: u64,
+}
+
+impl ScopeGuard {
+    pub(crate) fn enter() -> Self {
+        let generation = SCOPES.with(|scopes| {
+            let (live, next) = &mut *scopes.borrow_mut();
+            let generation = *next;
+
+            *next += 1;
+            live.push(generation);
+            generation
+        });
+
+        Self { generation }
+    }
+}
+
+impl Drop for ScopeGuard {
+    fn drop(&mut self) {
+        SCOPES.with(|scopes| {
+            let (live, _) = &mut *scopes.borrow_mut();
+
+            // Scopes are strictly nested, so the guard is always the innermost scope
+            debug_assert_eq!(live.last(), Some(&self.generation));
+            live.pop();
+        });
+    }
+}
+
+/// The scope and creation location of a handle.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Brand {
+    // `0` if the handle was created outside of any context and can't be checked
+    generation: u64,
+    created: &'static Location<'static>,
+}
+
+impl Brand {
+    #[track_caller]
+    pub(crate) fn new() -> Self {
+        let generation = SCOPES.with(|scopes| scopes.borrow().0.last().copied().unwrap_or(0));
+
+        Self {
+            generation,
+            created: Location::caller(),
+        }
+    }
+
+    /// Panics if the scope of the handle has ended.
+    #[track_caller]
+    pub(crate) fn check(&self) {
+        if self.generation == 0 {
+            return;
+        }
+
+        let live = SCOPES.with(|scopes| scopes.borrow().0.binary_search(&self.generation).is_ok());
+
+        if !live {
+            panic!(
+                "handle created at {} used at {} after its scope ended",
+                self.created,
+                Location::caller(),
+            );
+        }
+    }
+}
@@ -46,6 +46,9 @@
 //! }
 //! ```
 
+#[cfg(feature = "debug-handles")]
+pub(crate) mod debug;
+
 pub(crate) mod internal;
 
 pub(crate) mod root;
@@ -63,9 +66,10 @@ pub use self::root::Root;
 use crate::{
     context::{internal::Env, Context},
     handle::internal::{SuperType, TransparentNoCopyWrapper},
-    result::{JsResult, ResultExt},
+    object::PropertyKey,
+    result::{JsResult, NeonResult, ResultExt},
     sys::{self, raw},
-    types::Value,
+    types::{build, JsFunction, JsNull, JsUndefined, JsValue, Value},
 };
 
 /// The trait of data owned by the JavaScript engine and that can only be accessed via handles.
@@ -76,31 +80,50 @@ pub trait Managed: TransparentNoCopyWrapper {
 }
 
 /// A handle to a JavaScript value that is owned by the JavaScript engine.
+///
+/// With the `debug-handles` feature, a handle also records the context it was created
+/// in and where it was created, and panics if it is used after the context ended.
 #[derive(Debug)]
-#[repr(transparent)]
+#[cfg_attr(not(feature = "debug-handles"), repr(transparent))]
 pub struct Handle<'a, T: Managed + 'a> {
     // Contains the actual `Copy` JavaScript value data. It will be wrapped in
     // in a `!Copy` type when dereferencing. Only `T` should be visible to the user.
     value: <T as TransparentNoCopyWrapper>::Inner,
     phantom: PhantomData<&'a T>,
+    #[cfg(feature = "debug-handles")]
+    brand: debug::Brand,
 }
 
+// Handles are passed to Node-API as arrays of `napi_value`
+#[cfg(not(feature = "debug-handles"))]
+const _: () = assert!(mem::size_of::<Handle<JsValue>>() == mem::size_of::<raw::Local>());
+
 impl<'a, T: Managed> Clone for Handle<'a, T> {
     fn clone(&self) -> Self {
-        Self {
-            value: self.value,
-            phantom: PhantomData,
-        }
+        *self
     }
 }
 
 impl<'a, T: Managed> Copy for Handle<'a, T> {}
 
 impl<'a, T: Managed + 'a> Handle<'a, T> {
+    #[track_caller]
     pub(crate) fn new_internal(value: T) -> Handle<'a, T> {
         Handle {
             value: value.into_inner(),
             phantom: PhantomData,
+            #[cfg(feature = "debug-handles")]
+            brand: debug::Brand::new(),
+        }
+    }
+
+    // Creates a handle to `value` in the same context as `self`
+    fn rebrand<U: Managed>(&self, value: U) -> Handle<'a, U> {
+        Handle {
+            value: value.into_inner(),
+            phantom: PhantomData,
+            #[cfg(feature = "debug-handles")]
+            brand: self.brand,
         }
     }
 }
@@ -147,12 +170,33 @@ impl<'a, F: Value, T: Value> ResultExt<Handle<'a, T>> for DowncastResult<'a, F,
     }
 }
 
+#[cfg(feature = "sys")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sys")))]
+impl<'a> Handle<'a, JsValue> {
+    /// Creates a handle from a raw `napi_value`.
+    ///
+    /// Use [`downcast`](Handle::downcast) to convert the handle to a more specific type.
+    ///
+    /// # Safety
+    ///
+    /// `value` must be a valid `napi_value` created in the `napi_env` of `cx`, in a
+    /// handle scope that is still open. See [`sys_interop`](crate::sys_interop) for
+    /// details.
+    pub unsafe fn from_raw<C: Context<'a>>(
+        _cx: &mut C,
+        value: crate::sys_interop::RawValue<'a>,
+    ) -> Self {
+        JsValue::new_internal(value.as_ptr().cast())
+    }
+}
+
 impl<'a, T: Value> Handle<'a, T> {
     /// Safely upcast a handle to a supertype.
     ///
     /// This method does not require an execution context because it only copies a handle.
+    #[track_caller]
     pub fn upcast<U: Value + SuperType<T>>(&self) -> Handle<'a, U> {
-        Handle::new_internal(SuperType::upcast_internal(self.deref()))
+        self.rebrand(SuperType::upcast_internal(self.deref()))
     }
 
     /// Tests whether this value is an instance of the given type.
@@ -169,6 +213,7 @@ impl<'a, T: Value> Handle<'a, T> {
     /// # Ok(cx.undefined())
     /// # }
     /// ```
+    #[track_caller]
     pub fn is_a<'b, U: Value, C: Context<'b>>(&self, cx: &mut C) -> bool {
         U::is_typeof(cx.env(), self.deref())
     }
@@ -177,9 +222,10 @@ impl<'a, T: Value> Handle<'a, T> {
     /// to downcast **does not** throw a JavaScript exception, so it's OK to
     /// continue interacting with the JS engine if this method produces an `Err`
     /// result.
+    #[track_caller]
     pub fn downcast<'b, U: Value, C: Context<'b>>(&self, cx: &mut C) -> DowncastResult<'a, T, U> {
         match U::downcast(cx.env(), self.deref()) {
-            Some(v) => Ok(Handle::new_internal(v)),
+            Some(v) => Ok(self.rebrand(v)),
             None => Err(DowncastError::new()),
         }
     }
@@ -187,10 +233,40 @@ impl<'a, T: Value> Handle<'a, T> {
     /// Attempts to downcast a handle to another type, raising a JavaScript `TypeError`
     /// exception on failure. This method is a convenient shorthand, equivalent to
     /// `self.downcast::<U>().or_throw::<C>(cx)`.
+    #[track_caller]
     pub fn downcast_or_throw<'b, U: Value, C: Context<'b>>(&self, cx: &mut C) -> JsResult<'a, U> {
         self.downcast(cx).or_throw(cx)
     }
 
+    #[cfg(feature = "sys")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "sys")))]
+    /// Returns the raw `napi_value` of this handle, for calling Node-API functions that
+    /// Neon does not wrap.
+    ///
+    /// See [`sys_interop`](crate::sys_interop) for the invariants that must be upheld.
+    pub fn raw_value(&self) -> crate::sys_interop::RawValue<'a> {
+        crate::sys_interop::RawValue::from_ptr(self.to_raw().cast())
+    }
+
+    /// Returns the value of `v`, or `undefined` if it is `None`.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn find(mut cx: FunctionContext) -> JsResult<JsValue> {
+    ///     let list = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+    ///     let found = list.into_iter().find(|v| v.is_a::<JsString, _>(&mut cx));
+    ///
+    ///     Ok(Handle::undefined_if_none(&mut cx, found))
+    /// }
+    /// ```
+    pub fn undefined_if_none<C: Context<'a>>(cx: &mut C, v: Option<Self>) -> Handle<'a, JsValue> {
+        match v {
+            Some(v) => v.upcast(),
+            None => cx.undefined().upcast(),
+        }
+    }
+
+    #[track_caller]
     pub fn strict_equals<'b, U: Value, C: Context<'b>>(
         &self,
         cx: &mut C,
@@ -200,15 +276,135 @@ impl<'a, T: Value> Handle<'a, T> {
     }
 }
 
+impl<'a> Handle<'a, JsValue> {
+    /// Gets a property from a value that may be `null` or `undefined`, similar
+    /// to the JavaScript [optional chaining](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Operators/Optional_chaining)
+    /// expression `value?.key`.
+    ///
+    /// Returns `None` instead of throwing a `TypeError` if the value is nullish. An
+    /// exception thrown by a getter or proxy is still propagated as an `Err`.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # fn run(mut cx: FunctionContext) -> JsResult<JsValue> {
+    /// let options: Handle<JsValue> = cx.argument(0)?;
+    ///
+    /// // Equivalent to `options?.retry?.limit`
+    /// let limit = options
+    ///     .opt_get(&mut cx, "retry")?
+    ///     .map(|retry| retry.opt_get(&mut cx, "limit"))
+    ///     .transpose()?
+    ///     .flatten();
+    /// # Ok(limit.unwrap_or_else(|| cx.undefined().upcast()))
+    /// # }
+    /// ```
+    ///
+    /// See also [`opt_chain!`](crate::opt_chain).
+    pub fn opt_get<'b, C: Context<'b>, K: PropertyKey>(
+        &self,
+        cx: &mut C,
+        key: K,
+    ) -> NeonResult<Option<Handle<'b, JsValue>>> {
+        if self.is_nullish(cx) {
+            return Ok(None);
+        }
+
+        build(cx.env(), |out| unsafe {
+            key.get_from(cx, out, self.to_raw())
+        })
+        .map(Some)
+    }
+
+    /// Calls a method on a value that may be `null` or `undefined`, similar to
+    /// the JavaScript expression `value?.key?.(...args)`.
+    ///
+    /// Returns `None` if the value is nullish or the method is `null` or `undefined`.
+    /// Throws a `TypeError` if the property exists but is not a function.
+    pub fn opt_call_method<'b, C, K, AS>(
+        &self,
+        cx: &mut C,
+        key: K,
+        args: AS,
+    ) -> NeonResult<Option<Handle<'b, JsValue>>>
+    where
+        C: Context<'b>,
+        K: PropertyKey,
+        AS: AsRef<[Handle<'a, JsValue>]>,
+    {
+        let method = match self.opt_get(cx, key)? {
+            Some(method) if !method.is_nullish(cx) => method,
+            _ => return Ok(None),
+        };
+
+        method
+            .downcast_or_throw::<JsFunction, _>(cx)?
+            .call(cx, *self, args)
+            .map(Some)
+    }
+
+    fn is_nullish<'b, C: Context<'b>>(&self, cx: &mut C) -> bool {
+        self.is_a::<JsUndefined, _>(cx) || self.is_a::<JsNull, _>(cx)
+    }
+}
+
+/// Reads a chain of properties from a value, returning `None` as soon as a
+/// `null` or `undefined` is encountered, similar to the JavaScript expression
+/// `value?.a?.b?.c`.
+///
+/// Expands to a sequence of calls to [`Handle::opt_get`] and evaluates to a
+/// `NeonResult<Option<Handle<JsValue>>>`.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::opt_chain;
+///
+/// fn get_port(mut cx: FunctionContext) -> JsResult<JsValue> {
+///     let config: Handle<JsValue> = cx.argument(0)?;
+///
+///     // Equivalent to `config?.server?.port`
+///     match opt_chain!(&mut cx, config, "server", "port")? {
+///         Some(port) => Ok(port),
+///         None => Ok(cx.number(8080).upcast()),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! opt_chain {
+    ($cx:expr, $value:expr $(, $key:expr)+ $(,)?) => {{
+        let cx = $cx;
+        let value: $crate::handle::Handle<$crate::types::JsValue> = $value.upcast();
+        let result: $crate::result::NeonResult<
+            ::std::option::Option<$crate::handle::Handle<$crate::types::JsValue>>,
+        > = ::std::result::Result::Ok(::std::option::Option::Some(value));
+
+        $(
+            let result = match result {
+                ::std::result::Result::Ok(::std::option::Option::Some(value)) => {
+                    value.opt_get(cx, $key)
+                }
+                result => result,
+            };
+        )+
+
+        result
+    }};
+}
+
 impl<'a, T: Managed> Deref for Handle<'a, T> {
     type Target = T;
+    #[track_caller]
     fn deref(&self) -> &T {
+        #[cfg(feature = "debug-handles")]
+        self.brand.check();
         unsafe { mem::transmute(&self.value) }
     }
 }
 
 impl<'a, T: Managed> DerefMut for Handle<'a, T> {
+    #[track_caller]
     fn deref_mut(&mut self) -> &mut T {
+        #[cfg(feature = "debug-handles")]
+        self.brand.check();
         unsafe { mem::transmute(&mut self.value) }
     }
 }
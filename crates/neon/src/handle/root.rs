@@ -10,13 +10,16 @@ use crate::{
 
 #[cfg(feature = "napi-6")]
 use {
-    crate::{
-        lifecycle::{DropData, InstanceData, InstanceId},
-        sys::tsfn::ThreadsafeFunction,
-    },
+    crate::lifecycle::{DropData, DropQueue, InstanceData, InstanceId},
     std::sync::Arc,
 };
 
+#[cfg(feature = "napi-4")]
+use crate::event::Channel;
+
+#[cfg(feature = "memory-stats")]
+use crate::memory::Tracker;
+
 #[cfg(not(feature = "napi-6"))]
 use std::thread::{self, ThreadId};
 
@@ -55,7 +58,9 @@ pub struct Root<T> {
     internal: Option<NapiRef>,
     instance_id: InstanceId,
     #[cfg(feature = "napi-6")]
-    drop_queue: Arc<ThreadsafeFunction<DropData>>,
+    drop_queue: Arc<DropQueue>,
+    #[cfg(feature = "memory-stats")]
+    tracker: Tracker,
     _phantom: PhantomData<T>,
 }
 
@@ -95,12 +100,19 @@ impl<T: Object> Root<T> {
     pub fn new<'a, C: Context<'a>>(cx: &mut C, value: &T) -> Self {
         let env = cx.env().to_raw();
         let internal = unsafe { reference::new(env, value.to_raw()) };
+        #[cfg(feature = "memory-stats")]
+        let tracker = Tracker::get(cx);
+
+        #[cfg(feature = "memory-stats")]
+        tracker.root_created();
 
         Self {
             internal: Some(NapiRef(internal as *mut _)),
             instance_id: instance_id(cx),
             #[cfg(feature = "napi-6")]
             drop_queue: InstanceData::drop_queue(cx),
+            #[cfg(feature = "memory-stats")]
+            tracker,
             _phantom: PhantomData,
         }
     }
@@ -125,11 +137,16 @@ impl<T: Object> Root<T> {
             reference::reference(env.to_raw(), internal);
         };
 
+        #[cfg(feature = "memory-stats")]
+        self.tracker.root_created();
+
         Self {
             internal: self.internal.clone(),
             instance_id: instance_id(cx),
             #[cfg(feature = "napi-6")]
             drop_queue: Arc::clone(&self.drop_queue),
+            #[cfg(feature = "memory-stats")]
+            tracker: self.tracker.clone(),
             _phantom: PhantomData,
         }
     }
@@ -144,6 +161,48 @@ impl<T: Object> Root<T> {
         }
     }
 
+    #[cfg(feature = "napi-4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+    /// Drops `roots` on the JavaScript thread of `channel`, from any thread, with a
+    /// single closure.
+    ///
+    /// Dropping a large number of roots at once, for example, when evicting a cache
+    /// on another thread, queues one drop per root. `drop_all_via` sends one closure
+    /// that drops every root instead.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn evict(channel: &Channel, cache: &mut Vec<Root<JsObject>>) {
+    ///     Root::drop_all_via(channel, cache.drain(..));
+    /// }
+    /// ```
+    ///
+    /// If the channel is closed, the roots are dropped like any other `Root`.
+    ///
+    /// # Panics
+    ///
+    /// The closure panics if `channel` was created by a different instance of the
+    /// module than the roots.
+    pub fn drop_all_via<I>(channel: &Channel, roots: I)
+    where
+        T: 'static,
+        I: IntoIterator<Item = Root<T>>,
+    {
+        let roots = roots.into_iter().collect::<Vec<_>>();
+
+        if roots.is_empty() {
+            return;
+        }
+
+        let _ = channel.try_send(move |mut cx| {
+            for root in roots {
+                root.drop(&mut cx);
+            }
+
+            Ok(())
+        });
+    }
+
     /// Return the referenced JavaScript object and allow it to be garbage collected.
     ///
     /// # Panics
@@ -193,6 +252,10 @@ impl<T: Object> Root<T> {
         let reference = self.as_napi_ref(cx).clone();
         // This uses `as_napi_ref` instead of `Option::take` for the instance id safety check
         self.internal = None;
+
+        #[cfg(feature = "memory-stats")]
+        self.tracker.root_dropped();
+
         reference
     }
 }
@@ -230,7 +293,10 @@ impl<T> Drop for Root<T> {
     fn drop(&mut self) {
         // If `None`, the `NapiRef` has already been manually dropped
         if let Some(internal) = self.internal.take() {
-            let _ = self.drop_queue.call(DropData::Ref(internal), None);
+            #[cfg(feature = "memory-stats")]
+            self.tracker.root_dropped();
+
+            self.drop_queue.push(DropData::Ref(internal));
         }
     }
 }
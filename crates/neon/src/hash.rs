@@ -0,0 +1,295 @@
+//! Hashing of JavaScript values for use as keys of Rust collections.
+//!
+//! [`hash_value`] feeds a JavaScript primitive to a [`Hasher`] without converting it to
+//! a Rust value, and [`values_hash_equal`] compares two values consistently with the
+//! hash. Together, they allow caching Rust data by JavaScript values:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+//!
+//! use neon::hash::hash_value;
+//!
+//! // Hashes a pair of values, for example, to look up a cache entry
+//! fn hash_pair(mut cx: FunctionContext) -> JsResult<JsString> {
+//!     let a = cx.argument::<JsValue>(0)?;
+//!     let b = cx.argument::<JsValue>(1)?;
+//!     let mut hasher = DefaultHasher::new();
+//!
+//!     hash_value(&mut cx, a, &mut hasher)?;
+//!     hash_value(&mut cx, b, &mut hasher)?;
+//!
+//!     Ok(cx.string(format!("{:x}", hasher.finish())))
+//! }
+//! ```
+//!
+//! Hashing several values into the same hasher hashes them as a tuple; the values are
+//! delimited, so `("ab", "c")` and `("a", "bc")` are hashed differently.
+//!
+//! Values are compared like the keys of a `Map`: `NaN` is equal to `NaN`, and `-0` is
+//! equal to `+0`. `undefined`, `null`, booleans, numbers and strings are hashed by
+//! value. Hashing a string copies its UTF-8 content to the stack if it is short, and to
+//! a temporary buffer otherwise; it never creates a Rust `String`.
+//!
+//! Objects have no stable content, so hashing or comparing an object throws a
+//! `TypeError`, unless [`Options::identity`] is set. Symbols and `BigInt` values
+//! always throw.
+
+use std::{
+    hash::Hasher,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed, Root},
+    object::Object,
+    result::NeonResult,
+    sys,
+    thread::LocalKey,
+    types::{
+        JsBoolean, JsFunction, JsNull, JsNumber, JsObject, JsString, JsUndefined, JsValue, Value,
+    },
+};
+
+// Strings with a shorter UTF-8 length are copied to the stack
+const STACK_BUFFER_LEN: usize = 256;
+
+/// Options controlling the behavior of [`hash_value_with`] and
+/// [`values_hash_equal_with`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Options {
+    /// Objects, including arrays and functions, are hashed and compared by identity.
+    /// Each object is assigned an id the first time it is hashed, which is stable
+    /// for the lifetime of the object. If `false`, objects throw a `TypeError`.
+    /// _Default: `false`_
+    pub identity: bool,
+}
+
+/// Feeds a JavaScript primitive to `state`.
+///
+/// Throws a `TypeError` if `value` is not `undefined`, `null`, a boolean, a number
+/// or a string.
+pub fn hash_value<'a, C, V, H>(cx: &mut C, value: Handle<V>, state: &mut H) -> NeonResult<()>
+where
+    C: Context<'a>,
+    V: Value,
+    H: Hasher,
+{
+    hash_value_with(cx, value, state, Options::default())
+}
+
+/// Feeds a JavaScript value to `state`, with [`Options`].
+///
+/// Throws a `TypeError` if `value` cannot be hashed with `options`.
+pub fn hash_value_with<'a, C, V, H>(
+    cx: &mut C,
+    value: Handle<V>,
+    state: &mut H,
+    options: Options,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    V: Value,
+    H: Hasher,
+{
+    let value = value.upcast::<JsValue>();
+
+    match kind(cx, value, options)? {
+        Kind::Undefined => state.write_u8(0),
+        Kind::Null => state.write_u8(1),
+        Kind::Boolean(b) => {
+            state.write_u8(2);
+            state.write_u8(b as u8);
+        }
+        Kind::Number(n) => {
+            state.write_u8(3);
+            state.write_u64(normalize(n).to_bits());
+        }
+        Kind::String(s) => {
+            state.write_u8(4);
+            hash_string(cx, s, state);
+        }
+        Kind::Object(o) => {
+            let id = Identities::id(cx, o)?;
+
+            state.write_u8(5);
+            state.write_u64(id);
+        }
+    }
+
+    Ok(())
+}
+
+/// Tests whether two JavaScript primitives are equal, consistently with
+/// [`hash_value`]: equal values have the same hash.
+///
+/// Throws a `TypeError` if either value cannot be hashed.
+pub fn values_hash_equal<'a, C, A, B>(cx: &mut C, a: Handle<A>, b: Handle<B>) -> NeonResult<bool>
+where
+    C: Context<'a>,
+    A: Value,
+    B: Value,
+{
+    values_hash_equal_with(cx, a, b, Options::default())
+}
+
+/// Tests whether two JavaScript values are equal, consistently with
+/// [`hash_value_with`] and the same [`Options`].
+///
+/// Throws a `TypeError` if either value cannot be hashed with `options`.
+pub fn values_hash_equal_with<'a, C, A, B>(
+    cx: &mut C,
+    a: Handle<A>,
+    b: Handle<B>,
+    options: Options,
+) -> NeonResult<bool>
+where
+    C: Context<'a>,
+    A: Value,
+    B: Value,
+{
+    let a = a.upcast::<JsValue>();
+    let b = b.upcast::<JsValue>();
+
+    let equal = match (kind(cx, a, options)?, kind(cx, b, options)?) {
+        (Kind::Undefined, Kind::Undefined) | (Kind::Null, Kind::Null) => true,
+        (Kind::Boolean(a), Kind::Boolean(b)) => a == b,
+        (Kind::Number(a), Kind::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+        (Kind::String(a), Kind::String(b)) => strict_equals(cx, a.upcast(), b.upcast()),
+        (Kind::Object(a), Kind::Object(b)) => strict_equals(cx, a, b),
+        _ => false,
+    };
+
+    Ok(equal)
+}
+
+enum Kind<'a> {
+    Undefined,
+    Null,
+    Boolean(bool),
+    Number(f64),
+    String(Handle<'a, JsString>),
+    Object(Handle<'a, JsValue>),
+}
+
+fn kind<'a, 'b, C: Context<'a>>(
+    cx: &mut C,
+    value: Handle<'b, JsValue>,
+    options: Options,
+) -> NeonResult<Kind<'b>> {
+    if value.is_a::<JsUndefined, _>(cx) {
+        return Ok(Kind::Undefined);
+    }
+
+    if value.is_a::<JsNull, _>(cx) {
+        return Ok(Kind::Null);
+    }
+
+    if let Ok(b) = value.downcast::<JsBoolean, _>(cx) {
+        return Ok(Kind::Boolean(b.value(cx)));
+    }
+
+    if let Ok(n) = value.downcast::<JsNumber, _>(cx) {
+        return Ok(Kind::Number(n.value(cx)));
+    }
+
+    if let Ok(s) = value.downcast::<JsString, _>(cx) {
+        return Ok(Kind::String(s));
+    }
+
+    if value.is_a::<JsObject, _>(cx) || value.is_a::<JsFunction, _>(cx) {
+        if options.identity {
+            return Ok(Kind::Object(value));
+        }
+
+        return cx.throw_type_error("cannot hash an object without the `identity` option");
+    }
+
+    let typ = unsafe { sys::tag::type_of(cx.env().to_raw(), value.to_raw()) };
+
+    cx.throw_type_error(format!("cannot hash a value of type `{}`", typ))
+}
+
+fn strict_equals<'a, C: Context<'a>>(cx: &mut C, a: Handle<JsValue>, b: Handle<JsValue>) -> bool {
+    unsafe { sys::mem::strict_equals(cx.env().to_raw(), a.to_raw(), b.to_raw()) }
+}
+
+// Maps `-0` to `+0` and every `NaN` to the same bits
+fn normalize(n: f64) -> f64 {
+    if n == 0.0 {
+        0.0
+    } else if n.is_nan() {
+        f64::NAN
+    } else {
+        n
+    }
+}
+
+fn hash_string<'a, C: Context<'a>, H: Hasher>(cx: &mut C, s: Handle<JsString>, state: &mut H) {
+    let env = cx.env().to_raw();
+    let len = unsafe { sys::string::utf8_len(env, s.to_raw()) } as usize;
+
+    state.write_usize(len);
+
+    // The content is written with a terminating nul byte
+    if len < STACK_BUFFER_LEN {
+        let mut buffer = [0u8; STACK_BUFFER_LEN];
+
+        unsafe { sys::string::data(env, buffer.as_mut_ptr(), len as isize + 1, s.to_raw()) };
+        state.write(&buffer[..len]);
+    } else {
+        let mut buffer = vec![0u8; len + 1];
+
+        unsafe { sys::string::data(env, buffer.as_mut_ptr(), len as isize + 1, s.to_raw()) };
+        state.write(&buffer[..len]);
+    }
+}
+
+static IDENTITIES: LocalKey<Identities> = LocalKey::new();
+
+// The ids of objects hashed by identity, in a `WeakMap` so that hashing does not
+// modify the objects or keep them alive
+struct Identities {
+    ids: Root<JsObject>,
+    get: Root<JsFunction>,
+    set: Root<JsFunction>,
+    next: AtomicU64,
+}
+
+impl Identities {
+    fn id<'a, C: Context<'a>>(cx: &mut C, object: Handle<JsValue>) -> NeonResult<u64> {
+        let this = IDENTITIES.get_or_try_init(cx, |cx| {
+            let global = cx.global();
+            let weak_map = global.get::<JsFunction, _, _>(cx, "WeakMap")?;
+            let ids = weak_map.construct_with(cx).apply::<JsObject, _>(cx)?;
+            let prototype = weak_map.get::<JsObject, _, _>(cx, "prototype")?;
+            let get = prototype.get::<JsFunction, _, _>(cx, "get")?;
+            let set = prototype.get::<JsFunction, _, _>(cx, "set")?;
+
+            Ok(Self {
+                ids: ids.root(cx),
+                get: get.root(cx),
+                set: set.root(cx),
+                next: AtomicU64::new(0),
+            })
+        })?;
+
+        let ids = this.ids.to_inner(cx);
+        let id = this.get.to_inner(cx).call(cx, ids, [object])?;
+
+        if let Ok(id) = id.downcast::<JsNumber, _>(cx) {
+            return Ok(id.value(cx) as u64);
+        }
+
+        // Ids are stored as numbers, which are exact up to 2^53
+        let id = this.next.fetch_add(1, Ordering::Relaxed);
+        let value = cx.number(id as f64);
+
+        this.set
+            .to_inner(cx)
+            .exec(cx, ids, [object, value.upcast()])?;
+
+        Ok(id)
+    }
+}
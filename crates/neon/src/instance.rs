@@ -0,0 +1,200 @@
+//! Identifiers of the instances of an addon and routing of closures between them.
+//!
+//! An addon loaded by the main thread and by [worker threads][workers] has an instance
+//! for each thread. Every instance is listed in the process-wide [`registry`] with the
+//! [`Channel`] of its JavaScript thread, so an instance can run closures on the others
+//! without relaying messages through JavaScript:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::sync::Mutex;
+//!
+//! use neon::{instance, thread::LocalKey};
+//!
+//! static CONFIG: LocalKey<Mutex<String>> = LocalKey::new();
+//!
+//! // Sets the configuration of every instance
+//! fn set_config(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let config = cx.argument::<JsString>(0)?.value(&mut cx);
+//!
+//!     instance::registry().broadcast(move |mut cx| {
+//!         *CONFIG.get_or_init_default(&mut cx).lock().unwrap() = config.clone();
+//!         Ok(())
+//!     });
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+//!
+//! The registry holds instances weakly: an instance is removed when its JavaScript
+//! thread exits. An instance can also [opt out](set_listed) of the registry, for
+//! example, if it runs untrusted code.
+//!
+//! [workers]: https://nodejs.org/api/worker_threads.html
+
+use std::{
+    error::Error,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard, Weak,
+    },
+};
+
+use crate::{
+    context::{Context, TaskContext},
+    event::{Channel, JoinHandle},
+    lifecycle::InstanceData,
+    result::NeonResult,
+};
+
+pub use crate::lifecycle::InstanceId;
+
+static REGISTRY: Registry = Registry {
+    entries: Mutex::new(Vec::new()),
+};
+
+/// Returns the identifier of the instance of `cx`. It is unique in the process and
+/// does not change while the instance is running.
+pub fn id<'cx, C: Context<'cx>>(cx: &mut C) -> InstanceId {
+    InstanceData::id(cx)
+}
+
+/// Returns the process-wide registry of the instances of the addon
+pub fn registry() -> &'static Registry {
+    &REGISTRY
+}
+
+/// Lists or removes the instance of `cx` from the [`registry`]. Instances are listed
+/// when they are loaded.
+pub fn set_listed<'cx, C: Context<'cx>>(cx: &mut C, listed: bool) {
+    InstanceData::listing(cx)
+        .listed
+        .store(listed, Ordering::Release);
+}
+
+/// The entry of an instance in the registry, owned by the instance
+pub(crate) struct Listing {
+    id: InstanceId,
+    channel: Channel,
+    listed: AtomicBool,
+}
+
+impl Listing {
+    /// Adds an instance to the registry until the returned listing is dropped.
+    /// `channel` should be unreferenced, so that the registry does not keep the
+    /// instance alive.
+    pub(crate) fn register(id: InstanceId, channel: Channel) -> Arc<Self> {
+        let listing = Arc::new(Self {
+            id,
+            channel,
+            listed: AtomicBool::new(true),
+        });
+
+        let mut entries = REGISTRY.lock();
+
+        entries.retain(|entry| entry.strong_count() > 0);
+        entries.push(Arc::downgrade(&listing));
+
+        listing
+    }
+}
+
+/// The instances of an addon in the process, returned by [`registry`]
+pub struct Registry {
+    entries: Mutex<Vec<Weak<Listing>>>,
+}
+
+impl Registry {
+    fn lock(&self) -> MutexGuard<'_, Vec<Weak<Listing>>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    fn listed(&self) -> Vec<Arc<Listing>> {
+        self.lock()
+            .iter()
+            .filter_map(Weak::upgrade)
+            .filter(|listing| listing.listed.load(Ordering::Acquire))
+            .collect()
+    }
+
+    /// Returns the listed instances with the channels of their JavaScript threads, in
+    /// the order they were loaded
+    pub fn instances(&self) -> Vec<(InstanceId, Channel)> {
+        self.listed()
+            .into_iter()
+            .map(|listing| (listing.id, listing.channel.clone()))
+            .collect()
+    }
+
+    /// Returns the identifiers of the listed instances, in the order they were loaded
+    pub fn ids(&self) -> Vec<InstanceId> {
+        self.listed()
+            .into_iter()
+            .map(|listing| listing.id)
+            .collect()
+    }
+
+    /// Schedules a closure to execute on the JavaScript thread of the instance `id`
+    ///
+    /// Returns an error if the instance is not listed, for example, because its
+    /// thread exited, or if its channel is closed.
+    pub fn send_to<T, F>(&self, id: InstanceId, f: F) -> Result<JoinHandle<T>, RoutingError>
+    where
+        T: Send + 'static,
+        F: FnOnce(TaskContext) -> NeonResult<T> + Send + 'static,
+    {
+        let listing = self
+            .listed()
+            .into_iter()
+            .find(|listing| listing.id == id)
+            .ok_or(RoutingError::NotListed(id))?;
+
+        listing
+            .channel
+            .try_send(f)
+            .map_err(|_| RoutingError::Closed(id))
+    }
+
+    /// Schedules a closure to execute on the JavaScript thread of every listed
+    /// instance, including the calling instance, returning a handle for each instance
+    /// the closure was scheduled on. Instances whose channel is closed are skipped.
+    pub fn broadcast<T, F>(&self, f: F) -> Vec<(InstanceId, JoinHandle<T>)>
+    where
+        T: Send + 'static,
+        F: Fn(TaskContext) -> NeonResult<T> + Send + Sync + 'static,
+    {
+        let f = Arc::new(f);
+
+        self.listed()
+            .into_iter()
+            .filter_map(|listing| {
+                let f = Arc::clone(&f);
+                let handle = listing.channel.try_send(move |cx| f(cx)).ok()?;
+
+                Some((listing.id, handle))
+            })
+            .collect()
+    }
+}
+
+/// An error sending a closure to an instance with [`Registry::send_to`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoutingError {
+    /// The instance is not listed in the registry. It may have exited or opted out.
+    NotListed(InstanceId),
+    /// The channel of the instance is closed, for example, because its thread is
+    /// exiting
+    Closed(InstanceId),
+}
+
+impl fmt::Display for RoutingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotListed(id) => write!(f, "instance {} is not running or not listed", id),
+            Self::Closed(id) => write!(f, "the channel of instance {} is closed", id),
+        }
+    }
+}
+
+impl Error for RoutingError {}
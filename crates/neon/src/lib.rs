@@ -78,15 +78,63 @@
 //! [supported]: https://github.com/neon-bindings/neon#platform-support
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod adapt;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod atomics;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod capabilities;
+pub mod compare;
 pub mod context;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod deprecation;
+#[cfg(feature = "napi-5")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-5")))]
+pub mod diagnostics;
 pub mod event;
 pub mod handle;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod hash;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod instance;
+pub mod limits;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod memory;
 pub mod meta;
 pub mod object;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod once;
+#[cfg(feature = "operation-ids")]
+#[cfg_attr(docsrs, doc(cfg(feature = "operation-ids")))]
+pub mod operation;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod patterns;
 pub mod prelude;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod reentrancy;
 pub mod reflect;
 pub mod result;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod ring;
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub mod state;
+pub mod sync;
 mod sys;
+#[cfg(feature = "sys")]
+#[cfg_attr(docsrs, doc(cfg(feature = "sys")))]
+pub mod sys_interop;
 #[cfg(feature = "napi-6")]
 pub mod thread;
 // To use the #[aquamarine] attribute on the top-level neon::types module docs, we have to
@@ -127,3 +175,28 @@ static MODULE_TAG: once_cell::sync::Lazy<crate::sys::TypeTag> = once_cell::sync:
     // https://github.com/nodejs/node/blob/5fad0b93667ffc6e4def52996b9529ac99b26319/src/js_native_api_v8.cc#L2455
     crate::sys::TypeTag { lower, upper: 1 }
 });
+
+#[cfg(feature = "napi-8")]
+/// A random type tag for each Rust type, shared by every instance of the addon in the
+/// process. Used to check the type of data attached to JavaScript objects.
+pub(crate) fn type_tag<T: 'static>() -> crate::sys::TypeTag {
+    use std::{any::TypeId, collections::HashMap, sync::Mutex};
+
+    static TYPE_TAGS: once_cell::sync::Lazy<Mutex<HashMap<TypeId, crate::sys::TypeTag>>> =
+        once_cell::sync::Lazy::new(Default::default);
+
+    *TYPE_TAGS
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .entry(TypeId::of::<T>())
+        .or_insert_with(|| {
+            let mut lower = [0; std::mem::size_of::<u64>()];
+
+            getrandom::getrandom(&mut lower).expect("Failed to generate a Neon type tag");
+
+            crate::sys::TypeTag {
+                lower: u64::from_ne_bytes(lower),
+                upper: 1,
+            }
+        })
+}
@@ -10,28 +10,39 @@
 
 use std::{
     any::Any,
+    fmt,
     marker::PhantomData,
+    ptr,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicPtr, AtomicU64, Ordering},
         Arc,
     },
 };
 
 use crate::{
-    context::Context,
-    event::Channel,
+    capabilities,
+    context::{internal::ContextInternal, Context},
+    deprecation,
+    event::{Channel, Priority},
     handle::root::NapiRef,
-    sys::{lifecycle, raw::Env, tsfn::ThreadsafeFunction},
+    instance::Listing,
+    once, reentrancy,
+    sys::{lifecycle, raw::Env},
     types::promise::NodeApiDeferred,
 };
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+#[cfg(feature = "memory-stats")]
+use crate::memory;
+
+#[cfg(feature = "operation-ids")]
+use crate::operation;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
 #[repr(transparent)]
 /// Uniquely identifies an instance of the module
 ///
-/// _Note_: Since `InstanceData` is created lazily, the order of `id` may not
-/// reflect the order that instances were created.
-pub(crate) struct InstanceId(u64);
+/// Ids are assigned in the order that instances are loaded and are never reused.
+pub struct InstanceId(u64);
 
 impl InstanceId {
     fn next() -> Self {
@@ -39,6 +50,17 @@ impl InstanceId {
 
         Self(NEXT_ID.fetch_add(1, Ordering::SeqCst))
     }
+
+    /// The id as a number, for example, to pass it to JavaScript
+    pub fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl fmt::Display for InstanceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
 }
 
 /// `InstanceData` holds Neon data associated with a particular instance of a
@@ -49,18 +71,42 @@ pub(crate) struct InstanceData {
 
     /// Used to free `Root` in the same JavaScript environment that created it
     ///
-    /// _Design Note_: An `Arc` ensures the `ThreadsafeFunction` outlives the unloading
-    /// of a module. Since it is unlikely that modules will be re-loaded frequently, this
-    /// could be replaced with a leaked `&'static ThreadsafeFunction<NapiRef>`. However,
-    /// given the cost of FFI, this optimization is omitted until the cost of an
-    /// `Arc` is demonstrated as significant.
-    drop_queue: Arc<ThreadsafeFunction<DropData>>,
+    /// _Design Note_: An `Arc` ensures the `DropQueue` outlives the unloading of a
+    /// module. Since it is unlikely that modules will be re-loaded frequently, this
+    /// could be replaced with a leaked `&'static DropQueue`. However, given the cost
+    /// of FFI, this optimization is omitted until the cost of an `Arc` is
+    /// demonstrated as significant.
+    drop_queue: Arc<DropQueue>,
 
     /// Shared `Channel` that is cloned to be returned by the `cx.channel()` method
     shared_channel: Channel,
 
     /// Table of user-defined instance-local cells.
     locals: LocalTable,
+
+    /// Process-wide values this instance is attached to with `process_once`
+    once_attachments: once::Attachments,
+
+    /// Capabilities registered with `export_capabilities`
+    capabilities: capabilities::Registry,
+
+    /// Functions running with a `ReentrancyGuard`
+    reentrancy: reentrancy::Active,
+
+    /// Deprecation warnings emitted with `deprecation::warn`
+    deprecations: deprecation::Emitted,
+
+    /// Counts of live Neon values for `memory::stats`
+    #[cfg(feature = "memory-stats")]
+    memory: memory::Tracker,
+
+    /// Current operation id, for `Context::with_operation_id`
+    #[cfg(feature = "operation-ids")]
+    operation: operation::State,
+
+    /// Entry in the process-wide `instance::registry`, removed when the instance is
+    /// dropped
+    listing: Arc<Listing>,
 }
 
 #[derive(Default)]
@@ -217,18 +263,108 @@ pub(crate) enum DropData {
 
 impl DropData {
     /// Drop a value on the main thread
-    fn drop(env: Option<Env>, data: Self) {
-        if let Some(env) = env {
-            unsafe {
-                match data {
-                    DropData::Deferred(data) => data.leaked(env),
-                    DropData::Ref(data) => data.unref(env),
-                }
+    fn drop(env: Env, data: Self) {
+        unsafe {
+            match data {
+                DropData::Deferred(data) => data.leaked(env),
+                DropData::Ref(data) => data.unref(env),
             }
         }
     }
 }
 
+/// Queue of values dropped without a `Context`, to be dropped on the main thread
+///
+/// Values are pushed on a lock-free list. Pushing on an empty list sends a closure on
+/// the shared channel that drains the whole list, so values dropped in bulk, for
+/// example, by a cache eviction on another thread, are dropped by a single closure
+/// instead of one closure each.
+pub(crate) struct DropQueue {
+    pending: AtomicPtr<DropNode>,
+    channel: Channel,
+}
+
+struct DropNode {
+    data: DropData,
+    next: *mut DropNode,
+}
+
+impl DropQueue {
+    fn new(channel: Channel) -> Self {
+        Self {
+            pending: AtomicPtr::new(ptr::null_mut()),
+            channel,
+        }
+    }
+
+    /// Queues `data` to be dropped on the main thread
+    pub(crate) fn push(self: &Arc<Self>, data: DropData) {
+        let node = Box::into_raw(Box::new(DropNode {
+            data,
+            next: ptr::null_mut(),
+        }));
+
+        let mut head = self.pending.load(Ordering::Relaxed);
+
+        loop {
+            unsafe { (*node).next = head };
+
+            match self.pending.compare_exchange_weak(
+                head,
+                node,
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => break,
+                Err(current) => head = current,
+            }
+        }
+
+        // A drain is already scheduled and will take `data` with the rest of the list
+        if !head.is_null() {
+            return;
+        }
+
+        let queue = Arc::clone(self);
+
+        // Fails once the instance is torn down. The values are then leaked when the
+        // queue is dropped, like the values dropped afterwards.
+        let _ = self
+            .channel
+            .try_send_with_priority(Priority::High, move |cx| {
+                queue.drain(cx.env().to_raw());
+                Ok(())
+            });
+    }
+
+    fn drain(&self, env: Env) {
+        let mut node = self.pending.swap(ptr::null_mut(), Ordering::Acquire);
+
+        while !node.is_null() {
+            let DropNode { data, next } = *unsafe { Box::from_raw(node) };
+
+            DropData::drop(env, data);
+            node = next;
+        }
+    }
+}
+
+impl Drop for DropQueue {
+    fn drop(&mut self) {
+        let mut node = *self.pending.get_mut();
+
+        while !node.is_null() {
+            node = unsafe { Box::from_raw(node) }.next;
+        }
+    }
+}
+
+// Safety: The list is only modified with atomic operations, and its values are
+// `Send`. They are only dropped with an `Env` on the main thread.
+unsafe impl Send for DropQueue {}
+
+unsafe impl Sync for DropQueue {}
+
 impl InstanceData {
     /// Return the data associated with this module instance, lazily initializing if
     /// necessary.
@@ -238,46 +374,82 @@ impl InstanceData {
     /// `Context` reference ensures serialized access.
     pub(crate) fn get<'cx, C: Context<'cx>>(cx: &mut C) -> &mut InstanceData {
         let env = cx.env().to_raw();
-        let data = unsafe { lifecycle::get_instance_data::<InstanceData>(env).as_mut() };
 
-        if let Some(data) = data {
+        if let Some(data) = unsafe { InstanceData::existing(env) } {
             return data;
         }
 
-        let drop_queue = unsafe {
-            let queue = ThreadsafeFunction::new(env, DropData::drop);
-            queue.unref(env);
-            queue
-        };
+        // Created before the shared channel, which counts its queue
+        #[cfg(feature = "memory-stats")]
+        let memory = memory::Tracker::default();
 
         let shared_channel = {
+            #[cfg(feature = "memory-stats")]
+            let mut channel = Channel::with_tracker(cx, memory.clone());
+            #[cfg(not(feature = "memory-stats"))]
             let mut channel = Channel::new(cx);
             channel.unref(cx);
             channel
         };
 
+        let id = InstanceId::next();
+        let listing = Listing::register(id, shared_channel.clone());
+        let drop_queue = Arc::new(DropQueue::new(shared_channel.clone()));
+
         let data = InstanceData {
-            id: InstanceId::next(),
-            drop_queue: Arc::new(drop_queue),
+            id,
+            drop_queue,
             shared_channel,
             locals: LocalTable::default(),
+            once_attachments: once::Attachments::default(),
+            capabilities: capabilities::Registry::default(),
+            reentrancy: reentrancy::Active::default(),
+            deprecations: deprecation::Emitted::default(),
+            #[cfg(feature = "memory-stats")]
+            memory,
+            #[cfg(feature = "operation-ids")]
+            operation: operation::State::default(),
+            listing,
         };
 
-        unsafe { &mut *lifecycle::set_instance_data(env, data) }
+        let data = unsafe { lifecycle::set_instance_data(env, data) };
+
+        #[cfg(feature = "single-instance")]
+        single::INSTANCE.store(data, Ordering::Release);
+
+        unsafe { &mut *data }
+    }
+
+    /// Returns the data associated with this module instance, if it was initialized
+    ///
+    /// # Safety
+    /// Must be called on the JavaScript thread of `env`, and the reference must not be
+    /// held across a call that may initialize or access the data.
+    unsafe fn existing<'a>(env: Env) -> Option<&'a mut InstanceData> {
+        #[cfg(feature = "single-instance")]
+        if let Some(data) = single::INSTANCE.load(Ordering::Acquire).as_mut() {
+            return Some(data);
+        }
+
+        lifecycle::get_instance_data::<InstanceData>(env).as_mut()
     }
 
     /// Helper to return a reference to the `drop_queue` field of `InstanceData`
-    pub(crate) fn drop_queue<'cx, C: Context<'cx>>(
-        cx: &mut C,
-    ) -> Arc<ThreadsafeFunction<DropData>> {
+    pub(crate) fn drop_queue<'cx, C: Context<'cx>>(cx: &mut C) -> Arc<DropQueue> {
         Arc::clone(&InstanceData::get(cx).drop_queue)
     }
 
     /// Clones the shared channel and references it since new channels should start
-    /// referenced, but the shared channel is unreferenced.
+    /// referenced, but the shared channel is unreferenced. The clone carries the
+    /// current operation id, like a new channel.
     pub(crate) fn channel<'cx, C: Context<'cx>>(cx: &mut C) -> Channel {
         let mut channel = InstanceData::get(cx).shared_channel.clone();
         channel.reference(cx);
+
+        // The shared channel was created outside of any operation
+        #[cfg(feature = "operation-ids")]
+        channel.in_operation(operation::current(cx));
+
         channel
     }
 
@@ -286,8 +458,95 @@ impl InstanceData {
         InstanceData::get(cx).id
     }
 
+    /// Helper to return a reference to the `listing` field of `InstanceData`.
+    pub(crate) fn listing<'cx, C: Context<'cx>>(cx: &mut C) -> &Listing {
+        &InstanceData::get(cx).listing
+    }
+
     /// Helper to return a reference to the `locals` field of `InstanceData`.
     pub(crate) fn locals<'cx, C: Context<'cx>>(cx: &mut C) -> &mut LocalTable {
         &mut InstanceData::get(cx).locals
     }
+
+    /// Helper to return a reference to the `once_attachments` field of `InstanceData`.
+    pub(crate) fn once_attachments<'cx, C: Context<'cx>>(cx: &mut C) -> &mut once::Attachments {
+        &mut InstanceData::get(cx).once_attachments
+    }
+
+    /// Helper to return a reference to the `capabilities` field of `InstanceData`.
+    pub(crate) fn capabilities<'cx, C: Context<'cx>>(cx: &mut C) -> &mut capabilities::Registry {
+        &mut InstanceData::get(cx).capabilities
+    }
+
+    /// Helper to return a reference to the `reentrancy` field of `InstanceData`.
+    pub(crate) fn reentrancy<'cx, C: Context<'cx>>(cx: &mut C) -> &mut reentrancy::Active {
+        &mut InstanceData::get(cx).reentrancy
+    }
+
+    /// Helper to return a reference to the `deprecations` field of `InstanceData`.
+    pub(crate) fn deprecations<'cx, C: Context<'cx>>(cx: &mut C) -> &mut deprecation::Emitted {
+        &mut InstanceData::get(cx).deprecations
+    }
+
+    #[cfg(feature = "memory-stats")]
+    /// Helper to return a reference to the `memory` field of `InstanceData`.
+    pub(crate) fn memory<'cx, C: Context<'cx>>(cx: &mut C) -> &mut memory::Tracker {
+        &mut InstanceData::get(cx).memory
+    }
+
+    #[cfg(feature = "operation-ids")]
+    /// Helper to return a reference to the `operation` field of `InstanceData`.
+    pub(crate) fn operation<'cx, C: Context<'cx>>(cx: &mut C) -> &mut operation::State {
+        &mut InstanceData::get(cx).operation
+    }
+
+    #[cfg(feature = "operation-ids")]
+    /// Helper to return a reference to the `operation` field of `InstanceData`, without
+    /// initializing it.
+    ///
+    /// # Safety
+    /// See [`InstanceData::existing`].
+    pub(crate) unsafe fn existing_operation<'a>(env: Env) -> Option<&'a mut operation::State> {
+        InstanceData::existing(env).map(|data| &mut data.operation)
+    }
+}
+
+#[cfg(feature = "single-instance")]
+impl Drop for InstanceData {
+    /// Called by the finalizer of the instance data when the environment is torn
+    /// down, so that `single::INSTANCE` never points to freed data
+    fn drop(&mut self) {
+        let data = self as *mut InstanceData;
+        let _ = single::INSTANCE.compare_exchange(
+            data,
+            ptr::null_mut(),
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+}
+
+#[cfg(feature = "single-instance")]
+/// With the `single-instance` feature, the module may only be initialized once
+/// per process, and `InstanceData` is stored in a static instead of being
+/// looked up on every call.
+pub(crate) mod single {
+    use std::{
+        ptr,
+        sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+    };
+
+    use super::InstanceData;
+
+    /// Pointer to the `InstanceData` of the only instance. It is owned by Node-API
+    /// and freed when the environment is torn down at process exit, which resets
+    /// the pointer to null.
+    pub(super) static INSTANCE: AtomicPtr<InstanceData> = AtomicPtr::new(ptr::null_mut());
+
+    static INITIALIZED: AtomicBool = AtomicBool::new(false);
+
+    /// Returns `false` if the module has already been initialized
+    pub(crate) fn initialize() -> bool {
+        !INITIALIZED.swap(true, Ordering::SeqCst)
+    }
 }
@@ -0,0 +1,504 @@
+//! Limits on the size of JavaScript values accepted from untrusted callers.
+//!
+//! Converting a JavaScript value into Rust data allocates memory proportional to the
+//! size of the value. A [`Quota`] bounds the nesting depth, number of items, and the
+//! total size of strings and binary data, so that a hostile caller can't make a
+//! single native call allocate an unbounded amount of memory.
+//!
+//! [`measure`] walks a value without converting it and throws a `RangeError` naming
+//! the exceeded limit and the path where it was exceeded. The
+//! [`Limited`](crate::types::extract::Limited) extractor counts the same usage while
+//! it extracts, throwing as soon as a limit is exceeded and before allocating the
+//! part of the value that exceeds it.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::limits::{measure, Quota};
+//!
+//! fn import(mut cx: FunctionContext) -> JsResult<JsNumber> {
+//!     let data = cx.argument::<JsValue>(0)?;
+//!     let quota = Quota {
+//!         max_depth: 8,
+//!         ..Quota::default()
+//!     };
+//!     let usage = measure(&mut cx, data, quota)?;
+//!
+//!     Ok(cx.number(usage.items as f64))
+//! }
+//! ```
+//!
+//! Arrays and the own enumerable properties of objects are measured. Property keys
+//! count towards [`max_string_bytes`](Quota::max_string_bytes). Typed arrays,
+//! `ArrayBuffer` and `Buffer` count towards [`max_buffer_bytes`](Quota::max_buffer_bytes)
+//! and their elements are not counted as items. An object that is encountered again
+//! while it is being measured (i.e., a cycle) is not measured again.
+//!
+//! ## Conversion depth
+//!
+//! Independently of any `Quota`, the conversions that walk nested values, such as
+//! extracting a `Vec` or a `HashMap` with
+//! [`TryFromJs`](crate::types::extract::TryFromJs), [`deep_equals`](crate::compare::deep_equals),
+//! [`measure`] and the [`json`](crate::types::json) parser, throw a `RangeError` when
+//! arrays and objects are nested more than
+//! [`DEFAULT_MAX_CONVERSION_DEPTH`] levels deep. Without the limit, a deeply nested
+//! value, e.g., a million nested arrays, would overflow the stack and abort the
+//! process. With the `napi-6` feature, the limit can be changed for each instance of
+//! the addon with `set_max_conversion_depth`.
+
+use std::cell::{Cell, RefCell};
+
+#[cfg(feature = "napi-6")]
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[cfg(feature = "napi-6")]
+use crate::thread::LocalKey;
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    object::Object,
+    result::NeonResult,
+    sys,
+    types::{JsArray, JsFunction, JsObject, JsString, JsValue, Value},
+};
+
+/// Limits on the size of a JavaScript value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quota {
+    /// Maximum nesting of arrays and objects. The top-level object or array has a
+    /// depth of `1`. _Default: `64`_
+    pub max_depth: usize,
+    /// Maximum total number of array elements and object properties.
+    /// _Default: `1_000_000`_
+    pub max_items: usize,
+    /// Maximum total UTF-8 length of strings and property keys.
+    /// _Default: `64 MiB`_
+    pub max_string_bytes: usize,
+    /// Maximum total length of typed arrays, `ArrayBuffer` and `Buffer`.
+    /// _Default: `256 MiB`_
+    pub max_buffer_bytes: usize,
+}
+
+impl Default for Quota {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            max_items: 1_000_000,
+            max_string_bytes: 64 << 20,
+            max_buffer_bytes: 256 << 20,
+        }
+    }
+}
+
+/// The default nesting limit of conversions. See the
+/// [module documentation](crate::limits#conversion-depth).
+pub const DEFAULT_MAX_CONVERSION_DEPTH: usize = 128;
+
+thread_local! {
+    // Number of nested arrays and objects being converted on this thread
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    // Quotas of the `Limited` extractions in progress on this thread, outermost first
+    static BUDGETS: RefCell<Vec<Budget>> = const { RefCell::new(Vec::new()) };
+}
+
+// The limit of the instance, if it was set
+#[cfg(feature = "napi-6")]
+static MAX_DEPTH: LocalKey<AtomicUsize> = LocalKey::new();
+
+// Number of instances that set a limit, so that conversions only look up the limit of
+// the instance if some instance set one
+#[cfg(feature = "napi-6")]
+static CONFIGURED_INSTANCES: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets how deeply arrays and objects may be nested in the conversions of the
+/// instance of the addon. See the [module documentation](crate::limits#conversion-depth).
+///
+/// A limit that is too large for the stack of the thread allows a deeply nested value
+/// to abort the process.
+///
+/// ```
+/// # use neon::prelude::*;
+/// #[neon::main]
+/// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+///     neon::limits::set_max_conversion_depth(&mut cx, 32);
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub fn set_max_conversion_depth<'a, C: Context<'a>>(cx: &mut C, max_depth: usize) {
+    let mut configured = false;
+    let limit = MAX_DEPTH.get_or_init(cx, || {
+        configured = true;
+        AtomicUsize::new(max_depth)
+    });
+
+    if configured {
+        CONFIGURED_INSTANCES.fetch_add(1, Ordering::Relaxed);
+    } else {
+        limit.store(max_depth, Ordering::Relaxed);
+    }
+}
+
+/// The nesting limit of the conversions of the instance of the addon,
+/// [`DEFAULT_MAX_CONVERSION_DEPTH`] unless it was [set](set_max_conversion_depth)
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub fn max_conversion_depth<'a, C: Context<'a>>(cx: &mut C) -> usize {
+    if CONFIGURED_INSTANCES.load(Ordering::Relaxed) == 0 {
+        return DEFAULT_MAX_CONVERSION_DEPTH;
+    }
+
+    match MAX_DEPTH.get(cx) {
+        Some(limit) => limit.load(Ordering::Relaxed),
+        None => DEFAULT_MAX_CONVERSION_DEPTH,
+    }
+}
+
+/// One level of nesting of a conversion, left when dropped
+pub(crate) struct DepthGuard(());
+
+impl Drop for DepthGuard {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(depth.get() - 1));
+    }
+}
+
+/// Enters an array or object being converted, throwing a `RangeError` if it is nested
+/// too deeply
+pub(crate) fn enter<'a, C: Context<'a>>(cx: &mut C) -> NeonResult<DepthGuard> {
+    let depth = DEPTH.with(|depth| depth.get()) + 1;
+
+    check_depth(cx, depth)?;
+    spend(cx, |budget| {
+        let depth = depth - budget.base_depth;
+
+        budget.usage.depth = budget.usage.depth.max(depth);
+
+        (depth > budget.quota.max_depth).then_some(("max_depth", budget.quota.max_depth))
+    })?;
+    DEPTH.with(|d| d.set(depth));
+
+    Ok(DepthGuard(()))
+}
+
+/// Throws a `RangeError` if `depth` levels of nesting exceed the limit of the instance
+pub(crate) fn check_depth<'a, C: Context<'a>>(cx: &mut C, depth: usize) -> NeonResult<()> {
+    #[cfg(feature = "napi-6")]
+    let max_depth = max_conversion_depth(cx);
+    #[cfg(not(feature = "napi-6"))]
+    let max_depth = DEFAULT_MAX_CONVERSION_DEPTH;
+
+    if depth > max_depth {
+        return cx.throw_range_error(format!(
+            "value is nested more than {} levels deep",
+            max_depth
+        ));
+    }
+
+    Ok(())
+}
+
+// A quota and the usage counted against it by an extraction
+struct Budget {
+    quota: Quota,
+    usage: Usage,
+    // Nesting of conversions when the extraction started
+    base_depth: usize,
+}
+
+/// An extraction limited by a quota, ended when dropped
+pub(crate) struct BudgetGuard(());
+
+impl Drop for BudgetGuard {
+    fn drop(&mut self) {
+        BUDGETS.with(|budgets| budgets.borrow_mut().pop());
+    }
+}
+
+/// Limits the conversions on this thread to `quota` until the guard is dropped.
+/// Nested limits are all enforced.
+pub(crate) fn limit(quota: Quota) -> BudgetGuard {
+    let budget = Budget {
+        quota,
+        usage: Usage::default(),
+        base_depth: DEPTH.with(|depth| depth.get()),
+    };
+
+    BUDGETS.with(|budgets| budgets.borrow_mut().push(budget));
+
+    BudgetGuard(())
+}
+
+/// Usage counted against the quotas of the extractions in progress
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Resource {
+    Items,
+    StringBytes,
+    BufferBytes,
+}
+
+/// Counts `n` of `resource`, throwing a `RangeError` if it exceeds the quota of an
+/// extraction in progress. Called before allocating memory for the resource.
+pub(crate) fn charge<'a, C: Context<'a>>(
+    cx: &mut C,
+    resource: Resource,
+    n: usize,
+) -> NeonResult<()> {
+    spend(cx, |budget| {
+        let (used, max, limit) = match resource {
+            Resource::Items => (&mut budget.usage.items, budget.quota.max_items, "max_items"),
+            Resource::StringBytes => (
+                &mut budget.usage.string_bytes,
+                budget.quota.max_string_bytes,
+                "max_string_bytes",
+            ),
+            Resource::BufferBytes => (
+                &mut budget.usage.buffer_bytes,
+                budget.quota.max_buffer_bytes,
+                "max_buffer_bytes",
+            ),
+        };
+
+        *used = used.saturating_add(n);
+
+        (*used > max).then_some((limit, max))
+    })
+}
+
+// Updates every budget with `f`, which returns the name and value of a limit that
+// was exceeded, and throws for the first limit that was exceeded
+fn spend<'a, C, F>(cx: &mut C, mut f: F) -> NeonResult<()>
+where
+    C: Context<'a>,
+    F: FnMut(&mut Budget) -> Option<(&'static str, usize)>,
+{
+    let exceeded = BUDGETS.with(|budgets| {
+        budgets
+            .borrow_mut()
+            .iter_mut()
+            .fold(None, |exceeded, budget| exceeded.or(f(budget)))
+    });
+
+    match exceeded {
+        Some((limit, max)) => {
+            cx.throw_range_error(format!("value exceeds the `{}` limit of {}", limit, max))
+        }
+        None => Ok(()),
+    }
+}
+
+/// The size of a JavaScript value, as counted by [`measure`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+    /// Deepest nesting of arrays and objects
+    pub depth: usize,
+    /// Total number of array elements and object properties
+    pub items: usize,
+    /// Total UTF-8 length of strings and property keys
+    pub string_bytes: usize,
+    /// Total length of typed arrays, `ArrayBuffer` and `Buffer`
+    pub buffer_bytes: usize,
+}
+
+/// Measures a JavaScript value, throwing a `RangeError` as soon as any limit of
+/// the [`Quota`] is exceeded.
+///
+/// Throws if reading a property of the value throws.
+pub fn measure<'a, C, V>(cx: &mut C, v: Handle<'a, V>, quota: Quota) -> NeonResult<Usage>
+where
+    C: Context<'a>,
+    V: Value,
+{
+    let global = cx.global();
+    let keys = global
+        .get::<JsFunction, _, _>(cx, "Object")?
+        .get::<JsFunction, _, _>(cx, "keys")?;
+    let mut meter = Meter {
+        cx,
+        quota,
+        keys,
+        usage: Usage::default(),
+        stack: Vec::new(),
+        path: Vec::new(),
+    };
+
+    meter.measure(v.upcast())?;
+
+    Ok(meter.usage)
+}
+
+// The length in bytes of a typed array, `Buffer` or `ArrayBuffer`. Read with Node-API
+// instead of the `byteLength` property, which a subclass or a proxy could override.
+fn byte_length<'a, C: Context<'a>>(cx: &mut C, o: Handle<JsObject>) -> Option<usize> {
+    let env = cx.env().to_raw();
+
+    unsafe {
+        if sys::tag::is_typedarray(env, o.to_raw()) {
+            Some(sys::typedarray::info(env, o.to_raw()).byte_length())
+        } else if sys::tag::is_arraybuffer(env, o.to_raw()) {
+            Some(sys::arraybuffer::size(env, o.to_raw()))
+        } else {
+            None
+        }
+    }
+}
+
+enum Segment {
+    Index(u32),
+    Key(String),
+}
+
+struct Meter<'a, 'c, C: Context<'a>> {
+    cx: &'c mut C,
+    quota: Quota,
+    // `Object.keys`
+    keys: Handle<'a, JsFunction>,
+    usage: Usage,
+    // Objects currently being measured, used to detect cycles
+    stack: Vec<Handle<'a, JsObject>>,
+    path: Vec<Segment>,
+}
+
+impl<'a, 'c, C: Context<'a>> Meter<'a, 'c, C> {
+    fn measure(&mut self, v: Handle<'a, JsValue>) -> NeonResult<()> {
+        if let Ok(s) = v.downcast::<JsString, _>(self.cx) {
+            let len = s.size(self.cx) as usize;
+
+            return self.add_string_bytes(len);
+        }
+
+        let o = match v.downcast::<JsObject, _>(self.cx) {
+            Ok(o) => o,
+            Err(_) => return Ok(()),
+        };
+
+        if let Some(len) = byte_length(self.cx, o) {
+            self.usage.buffer_bytes = self.usage.buffer_bytes.saturating_add(len);
+
+            return self.check(
+                self.usage.buffer_bytes > self.quota.max_buffer_bytes,
+                "max_buffer_bytes",
+                self.quota.max_buffer_bytes,
+            );
+        }
+
+        let cx = &mut *self.cx;
+
+        if self
+            .stack
+            .iter()
+            .any(|ancestor| ancestor.strict_equals(cx, o))
+        {
+            return Ok(());
+        }
+
+        let depth = self.stack.len() + 1;
+
+        self.check(
+            depth > self.quota.max_depth,
+            "max_depth",
+            self.quota.max_depth,
+        )?;
+        self.usage.depth = self.usage.depth.max(depth);
+
+        let _guard = enter(self.cx)?;
+
+        self.stack.push(o);
+
+        let result = match o.downcast::<JsArray, _>(self.cx) {
+            Ok(arr) => self.measure_array(arr),
+            Err(_) => self.measure_object(o),
+        };
+
+        self.stack.pop();
+
+        result
+    }
+
+    fn measure_array(&mut self, arr: Handle<'a, JsArray>) -> NeonResult<()> {
+        // Check the length before reading any elements
+        let len = arr.len(self.cx);
+
+        self.add_items(len as usize)?;
+
+        for i in 0..len {
+            let v = arr.get_value(self.cx, i)?;
+
+            self.path.push(Segment::Index(i));
+            self.measure(v)?;
+            self.path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn measure_object(&mut self, o: Handle<'a, JsObject>) -> NeonResult<()> {
+        let this = self.cx.undefined();
+        let keys = self
+            .keys
+            .call(self.cx, this, [o.upcast()])?
+            .downcast_or_throw::<JsArray, _>(self.cx)?;
+        let len = keys.len(self.cx);
+
+        // Check the number of keys before reading any of them
+        self.add_items(len as usize)?;
+
+        for i in 0..len {
+            let key = keys.get::<JsString, _, _>(self.cx, i)?;
+            let key_len = key.size(self.cx) as usize;
+
+            self.add_string_bytes(key_len)?;
+
+            let v = o.get_value(self.cx, key)?;
+
+            self.path.push(Segment::Key(key.value(self.cx)));
+            self.measure(v)?;
+            self.path.pop();
+        }
+
+        Ok(())
+    }
+
+    fn add_items(&mut self, n: usize) -> NeonResult<()> {
+        self.usage.items = self.usage.items.saturating_add(n);
+
+        self.check(
+            self.usage.items > self.quota.max_items,
+            "max_items",
+            self.quota.max_items,
+        )
+    }
+
+    fn add_string_bytes(&mut self, n: usize) -> NeonResult<()> {
+        self.usage.string_bytes = self.usage.string_bytes.saturating_add(n);
+
+        self.check(
+            self.usage.string_bytes > self.quota.max_string_bytes,
+            "max_string_bytes",
+            self.quota.max_string_bytes,
+        )
+    }
+
+    fn check(&mut self, exceeded: bool, limit: &str, max: usize) -> NeonResult<()> {
+        if !exceeded {
+            return Ok(());
+        }
+
+        let mut path = String::from("$");
+
+        for segment in &self.path {
+            match segment {
+                Segment::Index(i) => path.push_str(&format!("[{}]", i)),
+                Segment::Key(key) => path.push_str(&format!(".{}", key)),
+            }
+        }
+
+        self.cx.throw_range_error(format!(
+            "value exceeds the `{}` limit of {} at `{}`",
+            limit, max, path
+        ))
+    }
+}
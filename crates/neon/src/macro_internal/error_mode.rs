@@ -0,0 +1,82 @@
+//! Runtime half of the `error` option of `#[neon::function]`
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::Object,
+    result::{JsResult, Throw},
+    types::{extract::TryIntoJs, JsValue, Value},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// How an `Err` returned by an exported function is converted
+pub enum ErrorMode {
+    /// Thrown
+    Throw,
+    /// Returned
+    Value,
+    /// Returned as `[error, null]`, and `Ok` as `[null, value]`
+    Tuple,
+}
+
+/// The result of a function exported with an `error` mode. Implemented for
+/// `Result<T, E>`, and for `NeonResult<Result<T, E>>` where the outer `Err` is an
+/// exception that is always thrown.
+pub trait ExportResult<'cx> {
+    fn into_js<C: Context<'cx>>(self, cx: &mut C, mode: ErrorMode) -> JsResult<'cx, JsValue>;
+}
+
+impl<'cx, T, E> ExportResult<'cx> for Result<T, E>
+where
+    T: TryIntoJs<'cx>,
+    E: TryIntoJs<'cx>,
+{
+    fn into_js<C: Context<'cx>>(self, cx: &mut C, mode: ErrorMode) -> JsResult<'cx, JsValue> {
+        // Conversion errors are thrown in every mode
+        match (self, mode) {
+            (Ok(v), ErrorMode::Tuple) => {
+                let v = v.try_into_js(cx)?;
+                let null = cx.null();
+
+                pair(cx, null, v)
+            }
+            (Ok(v), _) => Ok(v.try_into_js(cx)?.upcast()),
+            (Err(err), ErrorMode::Throw) => {
+                let err = err.try_into_js(cx)?;
+
+                cx.throw(err)
+            }
+            (Err(err), ErrorMode::Value) => Ok(err.try_into_js(cx)?.upcast()),
+            (Err(err), ErrorMode::Tuple) => {
+                let err = err.try_into_js(cx)?;
+                let null = cx.null();
+
+                pair(cx, err, null)
+            }
+        }
+    }
+}
+
+impl<'cx, T, E> ExportResult<'cx> for Result<Result<T, E>, Throw>
+where
+    T: TryIntoJs<'cx>,
+    E: TryIntoJs<'cx>,
+{
+    fn into_js<C: Context<'cx>>(self, cx: &mut C, mode: ErrorMode) -> JsResult<'cx, JsValue> {
+        self?.into_js(cx, mode)
+    }
+}
+
+fn pair<'cx, C, A, B>(cx: &mut C, a: Handle<'cx, A>, b: Handle<'cx, B>) -> JsResult<'cx, JsValue>
+where
+    C: Context<'cx>,
+    A: Value,
+    B: Value,
+{
+    let pair = cx.empty_array();
+
+    pair.set(cx, 0, a)?;
+    pair.set(cx, 1, b)?;
+
+    Ok(pair.upcast())
+}
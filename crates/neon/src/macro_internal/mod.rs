@@ -1,3 +1,92 @@
 //! Internals needed by macros. These have to be exported for the macros to work
 
 pub use crate::context::internal::initialize_module;
+
+#[cfg(feature = "napi-5")]
+use crate::{
+    context::{Context, FunctionContext},
+    handle::Handle,
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{JsFunction, JsObject, Value},
+};
+
+pub use self::{
+    error_mode::{ErrorMode, ExportResult},
+    out::{borrowed, check_len, extract_argument, typed_array_argument, written},
+    rename::{rename_arguments, RenameRule},
+};
+
+pub use crate::{
+    context::internal::Env,
+    handle::internal::TransparentNoCopyWrapper,
+    sys::raw::Local as RawLocal,
+    types::{
+        custom::{is_typeof as is_custom_value, name as custom_value_name},
+        private::ValueInternal,
+    },
+};
+
+#[cfg(feature = "napi-6")]
+pub use crate::capabilities::require as require_capability;
+
+#[cfg(feature = "napi-6")]
+pub use crate::reentrancy::guard as reentrancy_guard;
+
+#[cfg(feature = "napi-6")]
+pub use crate::deprecation::warn as warn_deprecated;
+
+#[cfg(feature = "napi-6")]
+pub use self::trace::{trace_end, trace_start, Trace};
+
+pub use crate::__neon_requires_napi_6 as requires_napi_6;
+
+#[cfg(feature = "napi-6")]
+#[doc(hidden)]
+#[macro_export]
+/// Checks that an option of `#[neon::function]` is supported by the enabled features
+macro_rules! __neon_requires_napi_6 {
+    ($option:literal) => {};
+}
+
+#[cfg(not(feature = "napi-6"))]
+#[doc(hidden)]
+#[macro_export]
+/// Checks that an option of `#[neon::function]` is supported by the enabled features
+macro_rules! __neon_requires_napi_6 {
+    ($option:literal) => {
+        ::std::compile_error!(::std::concat!(
+            "the `",
+            $option,
+            "` option of `#[neon::function]` requires the `napi-6` feature of `neon`"
+        ));
+    };
+}
+
+mod error_mode;
+mod out;
+mod rename;
+#[cfg(feature = "napi-6")]
+mod trace;
+
+#[cfg(feature = "napi-5")]
+/// Sets `key` of a namespace object to a function that is monitored like functions
+/// exported with [`ModuleContext::export_function`](crate::context::ModuleContext::export_function)
+pub fn export_function<'a, C, F, V>(
+    cx: &mut C,
+    namespace: Handle<JsObject>,
+    key: &str,
+    f: F,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    F: Fn(FunctionContext) -> JsResult<V> + 'static,
+    V: Value,
+{
+    let id = crate::diagnostics::register_export(key);
+    let f = JsFunction::new(cx, move |cx| crate::diagnostics::track(id, || f(cx)))?;
+
+    namespace.set(cx, key, f)?;
+
+    Ok(())
+}
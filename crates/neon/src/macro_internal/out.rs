@@ -0,0 +1,122 @@
+//! Runtime half of the `#[neon(out)]` parameters of `#[neon::function]`
+
+use crate::{
+    context::{internal::ContextInternal, Context, FunctionContext},
+    handle::{Handle, Managed},
+    result::{JsResult, NeonResult},
+    sys,
+    types::{
+        buffer::{Binary, BorrowError},
+        extract::TryFromJs,
+        JsTypedArray, JsValue, Value,
+    },
+};
+
+// The name of a parameter in errors, converted by the `rename_all` rule of the function
+fn param_name(cx: &FunctionContext, name: &str) -> String {
+    match cx.rename {
+        Some(rule) => rule.apply(name),
+        None => name.to_string(),
+    }
+}
+
+/// Casts the `i`th argument to a typed array of `T`, to be borrowed for the call.
+///
+/// Throws a `TypeError` if the array is backed by a `SharedArrayBuffer`, since other
+/// threads could access it while it is borrowed, or, with the `napi-7` feature, by a
+/// detached `ArrayBuffer`.
+pub fn typed_array_argument<'cx, T>(
+    cx: &mut FunctionContext<'cx>,
+    i: usize,
+    name: &str,
+) -> JsResult<'cx, JsTypedArray<T>>
+where
+    T: Binary,
+    JsTypedArray<T>: Value,
+{
+    let array = cx.named_argument::<JsTypedArray<T>>(i, name)?;
+    let env = cx.env().to_raw();
+    let buffer = array.buffer(cx).to_raw();
+
+    // A `SharedArrayBuffer` is not an `ArrayBuffer`
+    if !unsafe { sys::tag::is_arraybuffer(env, buffer) } {
+        let name = param_name(cx, name);
+
+        return cx.throw_type_error(format!(
+            "The \"{}\" argument must not be backed by a SharedArrayBuffer",
+            name
+        ));
+    }
+
+    #[cfg(feature = "napi-7")]
+    if unsafe { sys::arraybuffer::is_detached(env, buffer) } {
+        let name = param_name(cx, name);
+
+        return cx.throw_type_error(format!(
+            "The \"{}\" argument is backed by a detached ArrayBuffer",
+            name
+        ));
+    }
+
+    Ok(array)
+}
+
+/// Extracts the `i`th argument as `T`, or throws a `TypeError` if it is not of the
+/// expected type.
+pub fn extract_argument<'cx, T>(
+    cx: &mut FunctionContext<'cx>,
+    i: usize,
+    name: &str,
+) -> NeonResult<T>
+where
+    T: TryFromJs<'cx>,
+{
+    match T::try_from_argument(cx, i)? {
+        Some(v) => Ok(v),
+        None => {
+            let name = param_name(cx, name);
+
+            cx.throw_type_error(format!("The \"{}\" argument has the wrong type", name))
+        }
+    }
+}
+
+/// Throws a `RangeError` if `output` does not have as many elements as `input`
+pub fn check_len<'cx, T, U>(
+    cx: &mut FunctionContext<'cx>,
+    (input, input_name): (Handle<JsTypedArray<T>>, &str),
+    (output, output_name): (Handle<JsTypedArray<U>>, &str),
+) -> NeonResult<()>
+where
+    T: Binary,
+    U: Binary,
+{
+    let expected = input.len(cx);
+    let len = output.len(cx);
+
+    if len == expected {
+        return Ok(());
+    }
+
+    let input_name = param_name(cx, input_name);
+    let output_name = param_name(cx, output_name);
+
+    cx.throw_range_error(format!(
+        "The \"{}\" argument must have {} elements, like the \"{}\" argument. Received {}",
+        output_name, expected, input_name, len
+    ))
+}
+
+/// Throws an `Error` if the arrays could not be borrowed, because an output overlaps
+/// another array
+pub fn borrowed<'cx, T>(
+    cx: &mut FunctionContext<'cx>,
+    result: Result<T, BorrowError>,
+) -> NeonResult<T> {
+    result.or_else(|err| cx.throw_error(err.to_string()))
+}
+
+/// Returns the number of elements written by the function
+pub fn written<'cx>(cx: &mut FunctionContext<'cx>, n: usize) -> JsResult<'cx, JsValue> {
+    Ok(cx.number(n as f64).upcast())
+}
@@ -0,0 +1,85 @@
+//! Runtime half of the `rename_all` option of `#[neon::function]`
+
+use crate::context::FunctionContext;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Case of the names of an exported function and its parameters
+pub enum RenameRule {
+    Camel,
+    Pascal,
+    Snake,
+}
+
+impl RenameRule {
+    /// Converts a snake case Rust name. The first letter of each word is capitalized
+    /// and the rest are kept, so `parse_url_fast` becomes `parseUrlFast`.
+    ///
+    /// Used for both the exported names of functions and the names of parameters in
+    /// errors, so that they are always converted the same way.
+    pub fn apply(self, name: &str) -> String {
+        let capitalize_first = match self {
+            Self::Camel => false,
+            Self::Pascal => true,
+            Self::Snake => return name.to_string(),
+        };
+
+        // Leading underscores are kept, since they often mark a private name
+        let trimmed = name.trim_start_matches('_');
+        let mut renamed = name[..name.len() - trimmed.len()].to_string();
+
+        for (i, word) in trimmed.split('_').filter(|w| !w.is_empty()).enumerate() {
+            let mut chars = word.chars();
+
+            if let Some(first) = chars.next() {
+                if i > 0 || capitalize_first {
+                    renamed.extend(first.to_uppercase());
+                } else {
+                    renamed.extend(first.to_lowercase());
+                }
+
+                renamed.push_str(chars.as_str());
+            }
+        }
+
+        renamed
+    }
+}
+
+/// Renames the parameters named in errors thrown by
+/// [`FunctionContext::named_argument`]
+pub fn rename_arguments(cx: &mut FunctionContext, rule: RenameRule) {
+    cx.rename = Some(rule);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RenameRule;
+
+    #[test]
+    fn test_camel() {
+        assert_eq!(RenameRule::Camel.apply("parse_url_fast"), "parseUrlFast");
+        assert_eq!(RenameRule::Camel.apply("Parse_url"), "parseUrl");
+        assert_eq!(RenameRule::Camel.apply("parse__url_"), "parseUrl");
+        assert_eq!(RenameRule::Camel.apply("to_HTML"), "toHTML");
+        assert_eq!(RenameRule::Camel.apply("get_2d"), "get2d");
+    }
+
+    #[test]
+    fn test_pascal() {
+        assert_eq!(RenameRule::Pascal.apply("parse_url_fast"), "ParseUrlFast");
+        assert_eq!(RenameRule::Pascal.apply("élan_vital"), "ÉlanVital");
+    }
+
+    #[test]
+    fn test_snake() {
+        assert_eq!(RenameRule::Snake.apply("parse_url_fast"), "parse_url_fast");
+        assert_eq!(RenameRule::Snake.apply("_private"), "_private");
+    }
+
+    #[test]
+    fn test_leading_underscores() {
+        assert_eq!(RenameRule::Camel.apply("_private_name"), "_privateName");
+        assert_eq!(RenameRule::Pascal.apply("__private_name"), "__PrivateName");
+        assert_eq!(RenameRule::Camel.apply("_"), "_");
+    }
+}
@@ -0,0 +1,78 @@
+//! Runtime half of the `trace` option of `#[neon::function]`
+
+use crate::{
+    context::{
+        internal::{ContextInternal, Env},
+        Context, FunctionContext, TaskContext,
+    },
+    diagnostics::{tracing_channel, TracingChannel},
+    handle::{Handle, Managed},
+    object::Object,
+    result::NeonResult,
+    types::{JsObject, JsValue},
+};
+
+/// A call of a traced function, between [`trace_start`] and [`trace_end`]
+pub struct Trace<'cx> {
+    env: Env,
+    channel: TracingChannel,
+    context: Handle<'cx, JsObject>,
+}
+
+/// Publishes the start of a call to the function `name` to the tracing channel
+/// `channel`, with a context object holding the name and the arguments of the call.
+///
+/// Returns `None` without publishing if nothing subscribes to the channel.
+pub fn trace_start<'cx>(
+    cx: &mut FunctionContext<'cx>,
+    channel: &str,
+    name: &str,
+) -> NeonResult<Option<Trace<'cx>>> {
+    let channel = tracing_channel(cx, channel)?;
+
+    if !channel.has_subscribers() {
+        return Ok(None);
+    }
+
+    let context = cx.empty_object();
+    let name = cx.string(name);
+    let args = cx.empty_array();
+
+    for i in 0..cx.len() {
+        let arg = cx.argument::<JsValue>(i)?;
+
+        args.set(cx, i as u32, arg)?;
+    }
+
+    context.set(cx, "name", name)?;
+    context.set(cx, "arguments", args)?;
+    channel.start.publish(cx, context)?;
+
+    Ok(Some(Trace {
+        env: cx.env(),
+        channel,
+        context,
+    }))
+}
+
+/// Publishes the end of a call, and its exception to the `error` channel if it threw.
+///
+/// Takes the result instead of the context of the call, since the function was passed
+/// its context.
+pub fn trace_end<'cx, T>(trace: Option<Trace<'cx>>, result: NeonResult<T>) -> NeonResult<T> {
+    let Trace {
+        env,
+        channel,
+        context,
+    } = match trace {
+        Some(trace) => trace,
+        None => return result,
+    };
+
+    // The handles of the call are still valid, since this is called before it returns
+    TaskContext::with_context(env, |mut cx| {
+        let context = Handle::new_internal(JsObject::from_raw(env, context.to_raw()));
+
+        channel.finish(&mut cx, context, result)
+    })
+}
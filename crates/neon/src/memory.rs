@@ -0,0 +1,250 @@
+//! Memory usage of the JavaScript engine and of Neon values.
+//!
+//! [`stats`] samples the memory usage reported by Node, `process.memoryUsage()` and
+//! `v8.getHeapStatistics()`, for capacity planning and leak detection:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! fn memory_usage(mut cx: FunctionContext) -> JsResult<JsObject> {
+//!     let stats = neon::memory::stats(&mut cx)?;
+//!
+//!     if stats.heap_used > 512 * 1024 * 1024 {
+//!         eprintln!("{} roots and {} boxes are alive", stats.roots, stats.boxes);
+//!     }
+//!
+//!     stats.to_js(&mut cx)
+//! }
+//! ```
+//!
+//! With the `memory-stats` feature, the stats also count the [`Root`](crate::handle::Root)
+//! and [`JsBox`](crate::types::JsBox) values that are alive and the closures queued on
+//! [`Channel`](crate::event::Channel)s in the instance of the addon. The counters are
+//! updated whenever these values are created and when they are dropped or finalized.
+//! Without the feature, updating the counters compiles to nothing and they are always
+//! `0`.
+
+use crate::{
+    context::Context,
+    handle::{Handle, Root},
+    object::{Object, ObjectBuilder},
+    reflect,
+    result::{JsResult, NeonResult},
+    thread::LocalKey,
+    types::{JsFunction, JsNumber, JsObject},
+};
+
+#[cfg(feature = "memory-stats")]
+use {
+    crate::lifecycle::InstanceData,
+    std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+static INTRINSICS: LocalKey<Intrinsics> = LocalKey::new();
+
+// Functions for sampling memory usage, looked up once per instance
+struct Intrinsics {
+    process: Root<JsObject>,
+    // `process.memoryUsage`
+    memory_usage: Root<JsFunction>,
+    // `v8.getHeapStatistics`
+    heap_statistics: Option<Root<JsFunction>>,
+}
+
+impl Intrinsics {
+    fn get<'a, C: Context<'a>>(cx: &mut C) -> NeonResult<&'a Self> {
+        INTRINSICS.get_or_try_init(cx, |cx| {
+            let global = cx.global();
+            let process = global.get::<JsObject, _, _>(cx, "process")?;
+            let memory_usage = process.get::<JsFunction, _, _>(cx, "memoryUsage")?;
+            let heap_statistics = heap_statistics(cx).map(|f| f.root(cx));
+
+            Ok(Self {
+                process: process.root(cx),
+                memory_usage: memory_usage.root(cx),
+                heap_statistics,
+            })
+        })
+    }
+}
+
+// Returns `v8.getHeapStatistics`, if the `v8` module can be loaded without `require`
+fn heap_statistics<'a, C: Context<'a>>(cx: &mut C) -> Option<Handle<'a, JsFunction>> {
+    let v8 = cx
+        .try_catch(|cx| reflect::builtin_module(cx, "v8"))
+        .ok()??;
+
+    cx.try_catch(|cx| v8.get_value(cx, "getHeapStatistics"))
+        .ok()?
+        .downcast::<JsFunction, _>(cx)
+        .ok()
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+#[non_exhaustive]
+/// A sample of memory usage, returned by [`stats`]. Sizes are in bytes.
+pub struct MemoryStats {
+    /// Resident set size of the process
+    pub rss: u64,
+    /// Size of the JavaScript heap
+    pub heap_total: u64,
+    /// Size of the JavaScript heap that is in use
+    pub heap_used: u64,
+    /// Memory used by C++ objects bound to JavaScript objects, including `ArrayBuffer`s
+    pub external: u64,
+    /// Memory allocated for `ArrayBuffer`s and `SharedArrayBuffer`s
+    pub array_buffers: u64,
+    /// Largest size the JavaScript heap may grow to, or `None` if `v8.getHeapStatistics`
+    /// is not available. On Node versions without `process.getBuiltinModule` (before
+    /// 20.16 and 22.3), the `v8` module is loaded with the `require` of the main
+    /// module, so it is not available when the entry point is an ES module or in a
+    /// worker started with `eval: true`.
+    pub heap_size_limit: Option<u64>,
+    /// Number of [`Root`] that have not been dropped, including those held by Neon
+    pub roots: usize,
+    /// Number of [`JsBox`](crate::types::JsBox) that have not been finalized
+    pub boxes: usize,
+    /// Approximate size of the closures queued on [`Channel`](crate::event::Channel)s
+    /// that have not been executed
+    pub channel_queue_bytes: usize,
+}
+
+impl MemoryStats {
+    /// Converts the stats to a JavaScript object with the same properties in
+    /// camel case, for example, `heapUsed` and `channelQueueBytes`. `heapSizeLimit` is
+    /// omitted if it is not available.
+    pub fn to_js<'a, C: Context<'a>>(&self, cx: &mut C) -> JsResult<'a, JsObject> {
+        Ok(ObjectBuilder::new(cx)
+            .skip_none()
+            .prop("rss", self.rss as f64)?
+            .prop("heapTotal", self.heap_total as f64)?
+            .prop("heapUsed", self.heap_used as f64)?
+            .prop("external", self.external as f64)?
+            .prop("arrayBuffers", self.array_buffers as f64)?
+            .opt("heapSizeLimit", self.heap_size_limit.map(|n| n as f64))?
+            .prop("roots", self.roots as f64)?
+            .prop("boxes", self.boxes as f64)?
+            .prop("channelQueueBytes", self.channel_queue_bytes as f64)?
+            .build())
+    }
+}
+
+/// Samples the memory usage of the process and of the instance of the addon.
+///
+/// The heap sizes are read from `process.memoryUsage()` and `v8.getHeapStatistics()`,
+/// which are looked up once per instance. The counts of Neon values are only available
+/// with the `memory-stats` feature and are `0` otherwise.
+pub fn stats<'a, C: Context<'a>>(cx: &mut C) -> NeonResult<MemoryStats> {
+    let intrinsics = Intrinsics::get(cx)?;
+    let process = intrinsics.process.to_inner(cx);
+    let usage = intrinsics
+        .memory_usage
+        .to_inner(cx)
+        .call_with(cx)
+        .this(process)
+        .apply::<JsObject, _>(cx)?;
+
+    let heap_size_limit = match &intrinsics.heap_statistics {
+        Some(heap_statistics) => {
+            let heap = heap_statistics
+                .to_inner(cx)
+                .call_with(cx)
+                .apply::<JsObject, _>(cx)?;
+
+            Some(number(cx, heap, "heap_size_limit")?)
+        }
+        None => None,
+    };
+
+    let (roots, boxes, channel_queue_bytes) = counts(cx);
+
+    Ok(MemoryStats {
+        rss: number(cx, usage, "rss")?,
+        heap_total: number(cx, usage, "heapTotal")?,
+        heap_used: number(cx, usage, "heapUsed")?,
+        external: number(cx, usage, "external")?,
+        array_buffers: number(cx, usage, "arrayBuffers")?,
+        heap_size_limit,
+        roots,
+        boxes,
+        channel_queue_bytes,
+    })
+}
+
+#[cfg(feature = "memory-stats")]
+// Live roots, live boxes and queued bytes
+fn counts<'a, C: Context<'a>>(cx: &mut C) -> (usize, usize, usize) {
+    let counters = &InstanceData::memory(cx).0;
+
+    (
+        counters.roots.load(Ordering::Relaxed),
+        counters.boxes.load(Ordering::Relaxed),
+        counters.channel_queue_bytes.load(Ordering::Relaxed),
+    )
+}
+
+#[cfg(not(feature = "memory-stats"))]
+fn counts<'a, C: Context<'a>>(_cx: &mut C) -> (usize, usize, usize) {
+    (0, 0, 0)
+}
+
+// Reads a size, treating a missing property as `0` for older versions of Node
+fn number<'a, C: Context<'a>>(cx: &mut C, obj: Handle<JsObject>, key: &str) -> NeonResult<u64> {
+    let v = obj.get_value(cx, key)?;
+
+    Ok(v.downcast::<JsNumber, _>(cx)
+        .map(|n| n.value(cx) as u64)
+        .unwrap_or_default())
+}
+
+#[cfg(feature = "memory-stats")]
+#[derive(Clone, Default)]
+/// Counters of the Neon values alive in an instance of the addon. Shared with values
+/// that are dropped off the JavaScript thread.
+pub(crate) struct Tracker(Arc<Counters>);
+
+#[cfg(feature = "memory-stats")]
+#[derive(Default)]
+pub(crate) struct Counters {
+    roots: AtomicUsize,
+    boxes: AtomicUsize,
+    channel_queue_bytes: AtomicUsize,
+}
+
+#[cfg(feature = "memory-stats")]
+impl Tracker {
+    /// Returns the counters of the instance of `cx`
+    pub(crate) fn get<'a, C: Context<'a>>(cx: &mut C) -> Self {
+        InstanceData::memory(cx).clone()
+    }
+
+    pub(crate) fn root_created(&self) {
+        self.0.roots.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn root_dropped(&self) {
+        self.0.roots.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn box_created(&self) {
+        self.0.boxes.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn box_finalized(&self) {
+        self.0.boxes.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn queued(&self, bytes: usize) {
+        self.0
+            .channel_queue_bytes
+            .fetch_add(bytes, Ordering::Relaxed);
+    }
+
+    pub(crate) fn dequeued(&self, bytes: usize) {
+        self.0
+            .channel_queue_bytes
+            .fetch_sub(bytes, Ordering::Relaxed);
+    }
+}
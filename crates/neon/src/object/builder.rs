@@ -0,0 +1,102 @@
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::{KeyPolicy, Object},
+    result::NeonResult,
+    types::{
+        extract::{try_into_js_at, TryIntoJs},
+        JsObject,
+    },
+};
+
+/// A builder for creating a JavaScript object from Rust values.
+///
+/// Values are converted with [`TryIntoJs`] and defined as own properties of a new
+/// object. Keys such as `__proto__` are defined without modification, as with
+/// [`KeyPolicy::AllowOwn`].
+///
+/// ## `null`, `undefined` and missing properties
+///
+/// Optional values may be added in two ways:
+///
+/// * [`prop`](ObjectBuilder::prop) converts an `Option` like any other value, so
+///   `None` becomes `null`.
+/// * [`opt`](ObjectBuilder::opt) treats `None` as an absent value. By default, the
+///   property is set to `undefined`. After [`skip_none`](ObjectBuilder::skip_none), the
+///   property is omitted, so `'key' in obj` is `false`.
+///
+/// A nested `Option<Option<T>>` passed to [`opt`](ObjectBuilder::opt) can describe all
+/// three: `None` is absent, `Some(None)` is `null` and `Some(Some(v))` is `v`. This is
+/// useful for patch-like APIs where `null` clears a field and a missing key leaves it
+/// unchanged.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::object::ObjectBuilder;
+///
+/// fn user(mut cx: FunctionContext) -> JsResult<JsObject> {
+///     let nickname: Option<String> = None;
+///     let email: Option<Option<String>> = Some(None);
+///
+///     // { name: "neon", email: null }
+///     Ok(ObjectBuilder::new(&mut cx)
+///         .skip_none()
+///         .prop("name", "neon")?
+///         .opt("nickname", nickname)?
+///         .opt("email", email)?
+///         .build())
+/// }
+/// ```
+pub struct ObjectBuilder<'a, 'cx, C: Context<'cx>> {
+    cx: &'a mut C,
+    object: Handle<'cx, JsObject>,
+    skip_none: bool,
+}
+
+impl<'a, 'cx, C: Context<'cx>> ObjectBuilder<'a, 'cx, C> {
+    /// Creates a builder for an empty object
+    pub fn new(cx: &'a mut C) -> Self {
+        let object = cx.empty_object();
+
+        Self {
+            cx,
+            object,
+            skip_none: false,
+        }
+    }
+
+    /// Omit the properties added with [`opt`](ObjectBuilder::opt) when the value is
+    /// `None`, instead of setting them to `undefined`
+    pub fn skip_none(mut self) -> Self {
+        self.skip_none = true;
+        self
+    }
+
+    /// Defines the property `key` with `value` converted to JavaScript. If the
+    /// conversion throws, `key` is added to the path of the error as with
+    /// [`try_into_js_at`].
+    pub fn prop<T: TryIntoJs<'cx>>(self, key: &str, value: T) -> NeonResult<Self> {
+        let value = try_into_js_at(self.cx, key, value)?;
+
+        self.object
+            .set_safe_with(self.cx, key, value, KeyPolicy::AllowOwn)?;
+
+        Ok(self)
+    }
+
+    /// Defines the property `key` with `value` if it is `Some`. If it is `None`, the
+    /// property is set to `undefined`, or omitted after
+    /// [`skip_none`](ObjectBuilder::skip_none).
+    pub fn opt<T: TryIntoJs<'cx>>(self, key: &str, value: Option<T>) -> NeonResult<Self> {
+        match value {
+            Some(value) => self.prop(key, value),
+            None if self.skip_none => Ok(self),
+            None => self.prop(key, ()),
+        }
+    }
+
+    /// Returns the object
+    pub fn build(self) -> Handle<'cx, JsObject> {
+        self.object
+    }
+}
@@ -29,20 +29,51 @@
 //! }
 //! ```
 //!
+//! Numeric keys follow JavaScript: the key `17` is the property `"17"`. Integers
+//! from `0` to 2<sup>32</sup> − 2 are array indices, which are counted by the
+//! `length` of an array. 2<sup>32</sup> − 1 is the largest `u32` key, but it is an
+//! ordinary property of an array. An `f64` key must be an integer in the range of a
+//! `u32`; other numbers throw a `RangeError` instead of being converted to a string.
+//! [`Key`] states whether a key is an index or a name:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::object::Key;
+//!
+//! fn set_both<'a>(cx: &mut impl Context<'a>, obj: Handle<'a, JsObject>) -> NeonResult<()> {
+//!     let value = cx.string("hello!");
+//!
+//!     obj.set(cx, Key::Index(4294967295), value)?;
+//!     obj.set(cx, Key::Name("4294967296"), value)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
 //! [hierarchy]: crate::types#the-javascript-type-hierarchy
 //! [symbol]: https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Global_Objects/Symbol
 
 use crate::{
     context::Context,
-    handle::{Handle, Root},
+    handle::{Handle, Managed, Root},
     result::{NeonResult, Throw},
     sys::{self, raw},
-    types::{build, function::CallOptions, utf8::Utf8, JsFunction, JsUndefined, JsValue, Value},
+    types::{
+        build, function::CallOptions, utf8::Utf8, JsFunction, JsString, JsUndefined, JsValue, Value,
+    },
 };
 
 #[cfg(feature = "napi-6")]
 use crate::{result::JsResult, types::JsArray};
 
+pub use self::{
+    builder::ObjectBuilder,
+    template::{BoundTemplate, ObjectTemplate},
+};
+
+mod builder;
+mod template;
+
 /// A property key in a JavaScript object.
 pub trait PropertyKey {
     unsafe fn get_from<'c, C: Context<'c>>(
@@ -134,6 +165,155 @@ impl<'a> PropertyKey for &'a str {
     }
 }
 
+/// Integral numbers from `0` to `u32::MAX` are the same keys as the `u32` values.
+/// Other numbers, including fractions, negative numbers and numbers of 2<sup>32</sup>
+/// or more, throw a `RangeError`. Use [`Key::Name`] for those keys.
+impl PropertyKey for f64 {
+    unsafe fn get_from<'c, C: Context<'c>>(
+        self,
+        cx: &mut C,
+        out: &mut raw::Local,
+        obj: raw::Local,
+    ) -> bool {
+        match index_from_f64(cx, self) {
+            Some(index) => index.get_from(cx, out, obj),
+            None => false,
+        }
+    }
+
+    unsafe fn set_from<'c, C: Context<'c>>(
+        self,
+        cx: &mut C,
+        out: &mut bool,
+        obj: raw::Local,
+        val: raw::Local,
+    ) -> bool {
+        match index_from_f64(cx, self) {
+            Some(index) => index.set_from(cx, out, obj, val),
+            None => false,
+        }
+    }
+}
+
+// Converts a number to a `u32` key, or throws a `RangeError`
+fn index_from_f64<'c, C: Context<'c>>(cx: &mut C, key: f64) -> Option<u32> {
+    if key.fract() == 0.0 && (0.0..=u32::MAX as f64).contains(&key) {
+        return Some(key as u32);
+    }
+
+    // Formatted like JavaScript, which prints infinity as `Infinity`
+    let found = if key.is_infinite() {
+        String::from(if key > 0.0 { "Infinity" } else { "-Infinity" })
+    } else {
+        key.to_string()
+    };
+
+    let _ = cx.throw_range_error::<_, ()>(format!(
+        "expected an integer property key from 0 to {}, found {}",
+        u32::MAX,
+        found
+    ));
+
+    None
+}
+
+/// A property key that is explicitly an integer or a string
+///
+/// `Key::Index(17)` and `Key::Name("17")` are the same property. Names that are not
+/// the decimal form of an integer, such as `"4294967296"` or `"1.5"`, can only be
+/// used with `Key::Name`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Key<'a> {
+    /// An integer key. Keys up to 2<sup>32</sup> − 2 are array indices.
+    Index(u32),
+    /// A string key
+    Name(&'a str),
+}
+
+impl<'a> PropertyKey for Key<'a> {
+    unsafe fn get_from<'c, C: Context<'c>>(
+        self,
+        cx: &mut C,
+        out: &mut raw::Local,
+        obj: raw::Local,
+    ) -> bool {
+        match self {
+            Key::Index(index) => index.get_from(cx, out, obj),
+            Key::Name(name) => name.get_from(cx, out, obj),
+        }
+    }
+
+    unsafe fn set_from<'c, C: Context<'c>>(
+        self,
+        cx: &mut C,
+        out: &mut bool,
+        obj: raw::Local,
+        val: raw::Local,
+    ) -> bool {
+        match self {
+            Key::Index(index) => index.set_from(cx, out, obj, val),
+            Key::Name(name) => name.set_from(cx, out, obj, val),
+        }
+    }
+}
+
+/// How [`Object::set_safe_with`] handles keys that can change the behavior of an
+/// object when it is copied with ordinary property assignment: `__proto__`,
+/// `constructor` and `prototype`
+///
+/// For example, `Object.assign(target, source)` calls the `__proto__` setter of
+/// `target` for an own `__proto__` property of `source`, replacing its prototype.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum KeyPolicy {
+    /// Throw a `TypeError`. This is the default. Only the unsafe keys themselves are
+    /// rejected; `$__proto__` is an ordinary key.
+    #[default]
+    Reject,
+    /// Prefix the key with `$`. Keys that look escaped (e.g., `$__proto__`) are
+    /// prefixed again, so that escaping can be reversed by removing one `$`.
+    Escape,
+    /// Define the key as an own property without modification
+    AllowOwn,
+}
+
+impl KeyPolicy {
+    const UNSAFE_KEYS: [&'static str; 3] = ["__proto__", "constructor", "prototype"];
+
+    fn is_unsafe(key: &str) -> bool {
+        Self::UNSAFE_KEYS.contains(&key)
+    }
+
+    // Unsafe keys and keys that would be confused with escaped unsafe keys
+    fn needs_escape(key: &str) -> bool {
+        Self::UNSAFE_KEYS.contains(&key.trim_start_matches('$'))
+    }
+}
+
+// Defines `key` as an own data property without checking it, for keys that are not
+// valid UTF-8, such as keys with unpaired surrogates
+pub(crate) fn define_own<'a, C: Context<'a>, O: Object, W: Value>(
+    cx: &mut C,
+    obj: &O,
+    key: Handle<JsString>,
+    val: Handle<W>,
+) -> NeonResult<()> {
+    let env = cx.env().to_raw();
+
+    unsafe {
+        if sys::object::define_own(env, obj.to_raw(), key.to_raw(), val.to_raw()) {
+            return Ok(());
+        }
+
+        if sys::error::is_throwing(env) {
+            return Err(Throw::new());
+        }
+    }
+
+    let key = key.value(cx);
+
+    cx.throw_type_error(format!("cannot define property `{}`", key))
+}
+
 /// The trait of all object types.
 pub trait Object: Value {
     /// Gets a property from a JavaScript object that may be `undefined` and
@@ -228,6 +408,65 @@ pub trait Object: Value {
         }
     }
 
+    /// Defines `key` as an own data property, rejecting unsafe keys with a `TypeError`.
+    ///
+    /// Equivalent to [`set_safe_with`](Object::set_safe_with) with [`KeyPolicy::Reject`].
+    fn set_safe<'a, C: Context<'a>, W: Value>(
+        &self,
+        cx: &mut C,
+        key: &str,
+        val: Handle<W>,
+    ) -> NeonResult<()> {
+        self.set_safe_with(cx, key, val, KeyPolicy::Reject)
+    }
+
+    /// Defines `key` as an own data property, handling unsafe keys according to
+    /// `policy`.
+    ///
+    /// Unlike [`set`](Object::set), the property is defined as if by
+    /// `Object.defineProperty`: setters, including the `__proto__` setter of
+    /// `Object.prototype`, are never called and the prototype chain is not consulted.
+    /// This makes it suitable for building objects from untrusted keys.
+    ///
+    /// Throws a `TypeError` if the property can't be defined, for example, because
+    /// the object is frozen.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// use neon::object::KeyPolicy;
+    ///
+    /// fn from_entries(mut cx: FunctionContext) -> JsResult<JsObject> {
+    ///     let entries = [("name", "neon"), ("__proto__", "polluted")];
+    ///     let obj = cx.empty_object();
+    ///
+    ///     for (key, value) in entries {
+    ///         let value = cx.string(value);
+    ///
+    ///         // Defines `$__proto__`
+    ///         obj.set_safe_with(&mut cx, key, value, KeyPolicy::Escape)?;
+    ///     }
+    ///
+    ///     Ok(obj)
+    /// }
+    /// ```
+    fn set_safe_with<'a, C: Context<'a>, W: Value>(
+        &self,
+        cx: &mut C,
+        key: &str,
+        val: Handle<W>,
+        policy: KeyPolicy,
+    ) -> NeonResult<()> {
+        let key = match policy {
+            KeyPolicy::Reject if KeyPolicy::is_unsafe(key) => {
+                return cx.throw_type_error(format!("unsafe property key `{}`", key));
+            }
+            KeyPolicy::Escape if KeyPolicy::needs_escape(key) => cx.string(format!("${}", key)),
+            _ => cx.string(key),
+        };
+
+        define_own(cx, self, key, val)
+    }
+
     fn root<'a, C: Context<'a>>(&self, cx: &mut C) -> Root<Self> {
         Root::new(cx, self)
     }
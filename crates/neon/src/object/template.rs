@@ -0,0 +1,219 @@
+use std::convert::TryFrom;
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed, Root},
+    object::Object,
+    result::{JsResult, NeonResult, Throw},
+    sys::{self, object::DataDescriptors},
+    types::{boxed::Finalize, JsArray, JsFunction, JsObject, JsString, JsValue},
+};
+
+/// A precompiled shape for creating many JavaScript objects with the same properties.
+///
+/// Setting each property of a new object creates the key string and looks up the
+/// property separately. A template creates the keys once, when it is created, and
+/// defines all the properties of an object with a single call. The properties are own,
+/// writable, enumerable and configurable data properties, in the order of the keys.
+///
+/// A template may be kept between calls, for example, in a
+/// [`LocalKey`](crate::thread::LocalKey). To create many objects in one call,
+/// [`bind`](ObjectTemplate::bind) the template once and
+/// [`instantiate`](BoundTemplate::instantiate) the bound template for each object:
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::object::ObjectTemplate;
+///
+/// fn results(mut cx: FunctionContext) -> JsResult<JsArray> {
+///     let template = ObjectTemplate::new(&mut cx, &["id", "score"])?;
+///     let mut template = template.bind(&mut cx)?;
+///     let results = cx.empty_array();
+///
+///     for i in 0..100 {
+///         let id = cx.number(i).upcast();
+///         let score = cx.number(i as f64 / 100.0).upcast();
+///         let result = template.instantiate(&mut cx, &[id, score])?;
+///
+///         results.set(&mut cx, i, result)?;
+///     }
+///
+///     Ok(results)
+/// }
+/// ```
+///
+/// Like a [`Root`], a template may only be used by the instance of the addon that
+/// created it.
+pub struct ObjectTemplate {
+    keys: Box<[String]>,
+    // The key strings, followed by `Object`, `Object.create` and the prototype if the
+    // template has a prototype
+    state: Root<JsArray>,
+    prototype: bool,
+}
+
+impl ObjectTemplate {
+    /// Creates a template for objects with the properties `keys`. Throws an `Error` if
+    /// a key is repeated.
+    pub fn new<'cx, C: Context<'cx>>(cx: &mut C, keys: &[&str]) -> NeonResult<Self> {
+        let len = u32::try_from(keys.len())
+            .or_else(|_| cx.throw_range_error("too many keys for an object template"))?;
+        let state = JsArray::new(cx, len);
+
+        for (i, &key) in keys.iter().enumerate() {
+            if keys[..i].contains(&key) {
+                return cx.throw_error(format!("duplicate key `{}` in object template", key));
+            }
+
+            let key = cx.string(key);
+
+            state.set(cx, i as u32, key)?;
+        }
+
+        Ok(Self {
+            keys: keys.iter().map(|&key| key.to_owned()).collect(),
+            state: state.root(cx),
+            prototype: false,
+        })
+    }
+
+    /// Creates objects with `prototype` instead of `Object.prototype`. `None` creates
+    /// objects with a `null` prototype, like `Object.create(null)`.
+    ///
+    /// Objects with a prototype are created by calling `Object.create`, which is
+    /// slower than creating a plain object.
+    pub fn with_prototype<'cx, C: Context<'cx>>(
+        mut self,
+        cx: &mut C,
+        prototype: Option<Handle<'cx, JsObject>>,
+    ) -> NeonResult<Self> {
+        let state = self.state.to_inner(cx);
+        let global = cx.global();
+        let object = global.get::<JsFunction, _, _>(cx, "Object")?;
+        let create = object.get::<JsFunction, _, _>(cx, "create")?;
+        let prototype = match prototype {
+            Some(prototype) => prototype.upcast::<JsValue>(),
+            None => cx.null().upcast(),
+        };
+        let len = self.keys.len() as u32;
+
+        state.set(cx, len, object)?;
+        state.set(cx, len + 1, create)?;
+        state.set(cx, len + 2, prototype)?;
+        self.prototype = true;
+
+        Ok(self)
+    }
+
+    /// The keys of the properties, in the order their values are passed to
+    /// [`instantiate`](ObjectTemplate::instantiate)
+    pub fn keys(&self) -> &[String] {
+        &self.keys
+    }
+
+    /// Resolves the keys of the template for creating objects in the current scope
+    pub fn bind<'cx, C: Context<'cx>>(&self, cx: &mut C) -> NeonResult<BoundTemplate<'cx>> {
+        let state = self.state.to_inner(cx);
+        let len = self.keys.len() as u32;
+        let mut keys = Vec::with_capacity(self.keys.len());
+
+        for i in 0..len {
+            keys.push(state.get::<JsString, _, _>(cx, i)?.to_raw());
+        }
+
+        let prototype = if self.prototype {
+            Some(Prototype {
+                object: state.get(cx, len)?,
+                create: state.get(cx, len + 1)?,
+                prototype: state.get_value(cx, len + 2)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(BoundTemplate {
+            descriptors: DataDescriptors::new(&keys),
+            len: self.keys.len(),
+            prototype,
+        })
+    }
+
+    /// Creates an object with a property for each key, with the value at the same
+    /// index of `values`. Throws a `RangeError` if there isn't one value per key.
+    pub fn instantiate<'cx, C: Context<'cx>>(
+        &self,
+        cx: &mut C,
+        values: &[Handle<'cx, JsValue>],
+    ) -> JsResult<'cx, JsObject> {
+        self.bind(cx)?.instantiate(cx, values)
+    }
+
+    /// Drops the template, allowing its keys to be garbage collected
+    pub fn drop<'cx, C: Context<'cx>>(self, cx: &mut C) {
+        self.state.drop(cx);
+    }
+}
+
+impl Finalize for ObjectTemplate {
+    fn finalize<'a, C: Context<'a>>(self, cx: &mut C) {
+        self.drop(cx);
+    }
+}
+
+/// An [`ObjectTemplate`] with its keys resolved in the current scope, returned by
+/// [`ObjectTemplate::bind`]
+pub struct BoundTemplate<'cx> {
+    descriptors: DataDescriptors,
+    len: usize,
+    prototype: Option<Prototype<'cx>>,
+}
+
+struct Prototype<'cx> {
+    object: Handle<'cx, JsFunction>,
+    create: Handle<'cx, JsFunction>,
+    prototype: Handle<'cx, JsValue>,
+}
+
+impl<'cx> BoundTemplate<'cx> {
+    /// Creates an object with a property for each key, with the value at the same
+    /// index of `values`. Throws a `RangeError` if there isn't one value per key.
+    pub fn instantiate<C: Context<'cx>>(
+        &mut self,
+        cx: &mut C,
+        values: &[Handle<'cx, JsValue>],
+    ) -> JsResult<'cx, JsObject> {
+        if values.len() != self.len {
+            return cx.throw_range_error(format!(
+                "expected {} values for the object template, found {}",
+                self.len,
+                values.len()
+            ));
+        }
+
+        let object = match &self.prototype {
+            Some(Prototype {
+                object,
+                create,
+                prototype,
+            }) => create
+                .call(cx, *object, [*prototype])?
+                .downcast_or_throw(cx)?,
+            None => cx.empty_object(),
+        };
+
+        let env = cx.env().to_raw();
+        let values = values.iter().map(|value| value.to_raw());
+
+        unsafe {
+            if self.descriptors.define(env, object.to_raw(), values) {
+                return Ok(object);
+            }
+
+            if sys::error::is_throwing(env) {
+                return Err(Throw::new());
+            }
+        }
+
+        cx.throw_type_error("cannot define the properties of the object template")
+    }
+}
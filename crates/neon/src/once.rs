@@ -0,0 +1,272 @@
+//! Process-wide initialization shared by every instance of an addon.
+//!
+//! An addon may be loaded more than once in a single process (e.g., by
+//! [worker threads](https://nodejs.org/api/worker_threads.html)), and its
+//! [`#[main]`](crate::main) function runs once per instance. Some resources,
+//! like a C library with global state, must only be initialized once per
+//! process. [`process_once`] runs an initializer the first time it is called
+//! with a given [`Key`] and returns the same [`Arc`] to every instance.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::sync::Arc;
+//!
+//! struct Library;
+//!
+//! impl Library {
+//!     fn init() -> Self {
+//!         // e.g., `unsafe { ffi::library_init() }`
+//!         Library
+//!     }
+//! }
+//!
+//! impl Drop for Library {
+//!     fn drop(&mut self) {
+//!         // e.g., `unsafe { ffi::library_deinit() }`
+//!     }
+//! }
+//!
+//! #[neon::main]
+//! fn main(mut cx: ModuleContext) -> NeonResult<()> {
+//!     let library: Arc<Library> = neon::once::process_once(&mut cx, "library", Library::init)?;
+//!
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ### Detaching
+//!
+//! Each instance that calls [`process_once`] is _attached_ to the key. When an
+//! instance is unloaded, it detaches; after the last instance detaches, the
+//! shared value is released. Once every clone of the `Arc` has been dropped, the
+//! value's `Drop` implementation runs, which makes it a natural place to
+//! deinitialize the resource. A later call to [`process_once`] with the same key
+//! will initialize a new value.
+//!
+//! ### Poisoning
+//!
+//! If the initializer panics, the panic propagates to the caller (and is thrown as
+//! a JavaScript exception) and the key is _poisoned_. Similar to
+//! [`std::sync::Once`], poisoning is permanent: every later call with the same key,
+//! from any instance, throws an `Error` without running the initializer.
+
+use std::{
+    any::{Any, TypeId},
+    borrow::Cow,
+    collections::HashMap,
+    fmt,
+    panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    thread::{self, ThreadId},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{context::Context, lifecycle::InstanceData, result::NeonResult};
+
+/// Identifies a value initialized by [`process_once`].
+///
+/// Keys may be created from a string or a [`TypeId`]:
+///
+/// ```
+/// # use neon::once::Key;
+/// struct Library;
+///
+/// let by_name = Key::from("my-library");
+/// let by_type = Key::of::<Library>();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Key(Repr);
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Repr {
+    Name(Cow<'static, str>),
+    Type(TypeId),
+}
+
+impl Key {
+    /// Creates a key from the type `T`
+    pub fn of<T: ?Sized + 'static>() -> Self {
+        Self(Repr::Type(TypeId::of::<T>()))
+    }
+}
+
+impl From<&'static str> for Key {
+    fn from(name: &'static str) -> Self {
+        Self(Repr::Name(Cow::Borrowed(name)))
+    }
+}
+
+impl From<String> for Key {
+    fn from(name: String) -> Self {
+        Self(Repr::Name(Cow::Owned(name)))
+    }
+}
+
+impl From<TypeId> for Key {
+    fn from(id: TypeId) -> Self {
+        Self(Repr::Type(id))
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.0 {
+            Repr::Name(name) => fmt::Debug::fmt(name, f),
+            Repr::Type(id) => fmt::Debug::fmt(id, f),
+        }
+    }
+}
+
+enum Entry {
+    // The initializer is running on the thread
+    Initializing(ThreadId),
+    Ready {
+        value: Arc<dyn Any + Send + Sync>,
+        // Number of instances attached to the value
+        attached: usize,
+    },
+    Poisoned,
+}
+
+struct Registry {
+    entries: Mutex<HashMap<Key, Entry>>,
+    // Notified when an entry leaves the `Initializing` state
+    initialized: Condvar,
+}
+
+static REGISTRY: Lazy<Registry> = Lazy::new(|| Registry {
+    entries: Mutex::new(HashMap::new()),
+    initialized: Condvar::new(),
+});
+
+impl Registry {
+    fn entries(&self) -> MutexGuard<'_, HashMap<Key, Entry>> {
+        self.entries.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+/// Keys that an instance is attached to. Detaches from each key when the
+/// instance is dropped.
+#[derive(Default)]
+pub(crate) struct Attachments(Vec<Key>);
+
+impl Drop for Attachments {
+    fn drop(&mut self) {
+        let mut released = Vec::new();
+
+        {
+            let mut entries = REGISTRY.entries();
+
+            for key in self.0.drain(..) {
+                if let Some(Entry::Ready { attached, .. }) = entries.get_mut(&key) {
+                    *attached -= 1;
+
+                    if *attached == 0 {
+                        released.extend(entries.remove(&key));
+                    }
+                }
+            }
+        }
+
+        // Values are dropped after releasing the lock since `Drop` may be arbitrary
+        // user code, including calls to `process_once`
+        drop(released);
+    }
+}
+
+/// Runs `init` exactly once per process for `key` and returns the shared value.
+///
+/// Every instance of the addon calling `process_once` with the same key receives
+/// the same [`Arc`]. If another thread is running the initializer, the current
+/// thread blocks until it completes.
+///
+/// Throws an `Error` if:
+/// * The key was [poisoned](self#poisoning) by a panicking initializer
+/// * The initializer calls `process_once` with its own key
+///
+/// Throws a `TypeError` if the key was previously initialized with a different type.
+pub fn process_once<'a, C, K, T, F>(cx: &mut C, key: K, init: F) -> NeonResult<Arc<T>>
+where
+    C: Context<'a>,
+    K: Into<Key>,
+    T: Send + Sync + 'static,
+    F: FnOnce() -> T,
+{
+    let key = key.into();
+    let current = thread::current().id();
+    let mut entries = REGISTRY.entries();
+
+    loop {
+        match entries.get(&key) {
+            Some(Entry::Initializing(id)) if *id == current => {
+                return cx.throw_error(format!(
+                    "process_once initializer for {} attempted to initialize itself",
+                    key
+                ));
+            }
+            Some(Entry::Initializing(_)) => {
+                entries = REGISTRY
+                    .initialized
+                    .wait(entries)
+                    .unwrap_or_else(|err| err.into_inner());
+            }
+            Some(Entry::Poisoned) => {
+                return cx.throw_error(format!(
+                    "process_once initializer for {} panicked and the key is poisoned",
+                    key
+                ));
+            }
+            Some(Entry::Ready { .. }) => break,
+            None => {
+                entries.insert(key.clone(), Entry::Initializing(current));
+                drop(entries);
+
+                let result = catch_unwind(AssertUnwindSafe(init));
+
+                entries = REGISTRY.entries();
+
+                let entry = match result {
+                    Ok(value) => Entry::Ready {
+                        value: Arc::new(value),
+                        attached: 0,
+                    },
+                    Err(panic) => {
+                        entries.insert(key, Entry::Poisoned);
+                        drop(entries);
+                        REGISTRY.initialized.notify_all();
+                        resume_unwind(panic);
+                    }
+                };
+
+                entries.insert(key.clone(), entry);
+                REGISTRY.initialized.notify_all();
+                break;
+            }
+        }
+    }
+
+    let value = match entries.get_mut(&key) {
+        Some(Entry::Ready { value, attached }) => {
+            let attachments = &mut InstanceData::once_attachments(cx).0;
+
+            if !attachments.contains(&key) {
+                attachments.push(key.clone());
+                *attached += 1;
+            }
+
+            Arc::clone(value)
+        }
+        _ => unreachable!(),
+    };
+
+    drop(entries);
+
+    value.downcast::<T>().or_else(|_| {
+        cx.throw_type_error(format!(
+            "process_once value for {} is not of type {}",
+            key,
+            std::any::type_name::<T>()
+        ))
+    })
+}
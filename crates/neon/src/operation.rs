@@ -0,0 +1,192 @@
+//! Ids of operations that surface in the errors they cause.
+//!
+//! An operation, for example, a request handled by the addon, often spans several
+//! callbacks: a function call schedules a task, whose completion sends a closure on
+//! a channel, which settles a promise. An [`OperationId`] set with
+//! [`cx.with_operation_id`](crate::context::Context::with_operation_id) is the
+//! _current_ id for the duration of the call, and is carried to those callbacks:
+//!
+//! * A [`Channel`](crate::event::Channel) created while an id is current, with
+//!   `cx.channel()` or `Channel::new`, runs its closures with the id.
+//! * A task created with `cx.task()` runs its `and_then` callback with the id.
+//! * A [`Deferred`](crate::types::Deferred) created with `cx.promise()`, including the
+//!   promise of a task, settles the promise with the id.
+//!
+//! Errors created with [`JsError`](crate::types::JsError), including those thrown
+//! with `cx.throw_error` and similar methods, while an id is current have an
+//! `operationId` property with the id, formatted as 16 hexadecimal digits. So do the
+//! errors Neon creates for panics, and for exceptions that escape a callback, in an
+//! operation.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::operation::OperationId;
+//!
+//! fn fetch(mut cx: FunctionContext) -> JsResult<JsPromise> {
+//!     let id = OperationId::generate();
+//!
+//!     cx.with_operation_id(id, |cx| {
+//!         let promise = cx
+//!             .task(|| std::fs::read_to_string("config.json").map_err(|err| err.to_string()))
+//!             .promise(|mut cx, config| match config {
+//!                 Ok(config) => Ok(cx.string(config)),
+//!                 // Rejected with an error with `operationId` set to `id`
+//!                 Err(err) => cx.throw_error(err),
+//!             });
+//!
+//!         Ok(promise)
+//!     })
+//! }
+//! ```
+//!
+//! The current id is tracked separately for each instance of the addon. Operations
+//! that are interleaved, for example, tasks that complete in a different order than
+//! they were started, each restore their own id. Code that runs outside of any of
+//! these callbacks, for example, a JavaScript callback called later by a timer, has
+//! no current id.
+
+use std::{
+    fmt,
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+};
+
+use crate::{
+    context::Context, handle::Handle, lifecycle::InstanceData, object::Object, result::NeonResult,
+    sys::raw::Env, types::JsError,
+};
+
+/// The name of the property of errors holding the id of their operation
+pub const PROPERTY: &str = "operationId";
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+#[repr(transparent)]
+/// Identifies an operation, for example, a request or a trace span
+///
+/// The id is an arbitrary `u64`, so it can be taken from the caller or from a
+/// tracing system. It is displayed, and set as the `operationId` of errors, as 16
+/// lowercase hexadecimal digits, like the span id of a W3C trace context.
+pub struct OperationId(u64);
+
+impl OperationId {
+    /// Creates an id from a number
+    pub const fn new(id: u64) -> Self {
+        Self(id)
+    }
+
+    /// Returns a new id that was never returned before by this process
+    pub fn generate() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    /// The id as a number
+    pub const fn as_u64(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<u64> for OperationId {
+    fn from(id: u64) -> Self {
+        Self(id)
+    }
+}
+
+impl fmt::Display for OperationId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+#[derive(Default)]
+/// Operation ids of an instance of the addon
+pub(crate) struct State {
+    current: Option<OperationId>,
+    // Id of the operation of the innermost scope a panic unwound through, kept until
+    // the panic is converted to an error
+    panicked: Option<OperationId>,
+}
+
+/// Sets the current operation id until it is dropped, then restores the previous one
+pub(crate) struct Scope {
+    env: Env,
+    previous: Option<OperationId>,
+}
+
+impl Scope {
+    /// Sets `id` as the current id, for `Context::with_operation_id`
+    pub(crate) fn enter<'cx, C: Context<'cx>>(cx: &mut C, id: Option<OperationId>) -> Self {
+        // Initializes the instance data
+        InstanceData::operation(cx);
+
+        Self::restore(cx.env().to_raw(), id)
+    }
+
+    /// Restores an id captured with [`current`] for a callback of the operation
+    pub(crate) fn restore(env: Env, id: Option<OperationId>) -> Self {
+        // An id was only captured if the instance data was initialized. Otherwise,
+        // there is no id to restore.
+        if let Some(state) = unsafe { InstanceData::existing_operation(env) } {
+            let previous = std::mem::replace(&mut state.current, id);
+
+            // A panic that was converted without reading the id, e.g., by a panic
+            // handler that was not in an operation, must not be attributed to this one
+            if !thread::panicking() {
+                state.panicked = None;
+            }
+
+            return Self { env, previous };
+        }
+
+        Self {
+            env,
+            previous: None,
+        }
+    }
+}
+
+impl Drop for Scope {
+    fn drop(&mut self) {
+        // The instance data outlives every callback that can hold a scope
+        let state = match unsafe { InstanceData::existing_operation(self.env) } {
+            Some(state) => state,
+            None => return,
+        };
+
+        if thread::panicking() && state.panicked.is_none() {
+            state.panicked = state.current;
+        }
+
+        state.current = self.previous;
+    }
+}
+
+/// Returns the current operation id
+pub(crate) fn current<'cx, C: Context<'cx>>(cx: &mut C) -> Option<OperationId> {
+    // Not initializing the instance data, since initializing it creates a channel,
+    // which reads the current id
+    unsafe { InstanceData::existing_operation(cx.env().to_raw()) }.and_then(|state| state.current)
+}
+
+/// Returns the id of the operation that panicked, or the current id, for the error
+/// created for a failure
+pub(crate) fn failed(env: Env) -> Option<OperationId> {
+    let state = unsafe { InstanceData::existing_operation(env) }?;
+
+    state.panicked.take().or(state.current)
+}
+
+/// Sets the `operationId` of `err` if there is a current operation
+pub(crate) fn tag<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    err: Handle<'cx, JsError>,
+) -> NeonResult<Handle<'cx, JsError>> {
+    if let Some(id) = current(cx) {
+        let id = cx.string(id.to_string());
+
+        err.set(cx, PROPERTY, id)?;
+    }
+
+    Ok(err)
+}
@@ -0,0 +1,384 @@
+//! Reusable scaffolds for common shapes of addon APIs.
+//!
+//! An [`Accumulator`] wraps Rust state that processes input fed in chunks, like a
+//! hash function, in a JavaScript object:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::patterns::{Accumulate, Accumulator};
+//!
+//! // A 32-bit FNV-1a hash
+//! struct Fnv(u32);
+//!
+//! impl Accumulate for Fnv {
+//!     fn update(&mut self, chunk: &[u8]) {
+//!         for &byte in chunk {
+//!             self.0 = (self.0 ^ byte as u32).wrapping_mul(0x0100_0193);
+//!         }
+//!     }
+//!
+//!     fn finish(self) -> Vec<u8> {
+//!         self.0.to_be_bytes().to_vec()
+//!     }
+//! }
+//!
+//! fn create_hasher(mut cx: FunctionContext) -> JsResult<JsObject> {
+//!     Accumulator::new(Fnv(0x811c_9dc5)).build(&mut cx)
+//! }
+//! ```
+//!
+//! ```js
+//! const hasher = addon.createHasher();
+//!
+//! hasher.update(Buffer.from("hello, "));
+//! await hasher.updateAsync(largeBuffer);
+//!
+//! const digest = hasher.digest();
+//! ```
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    slice,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+use crate::{
+    context::{Context, FunctionContext},
+    handle::{Handle, Managed},
+    object::Object,
+    result::{JsResult, NeonResult},
+    sys::{self, TypedArrayType},
+    types::{
+        buffer::TypedArray, JsArrayBuffer, JsBuffer, JsError, JsFunction, JsNumber, JsObject,
+        JsPromise, JsValue, Value,
+    },
+};
+
+/// The `code` of the error thrown when an [`Accumulator`] is used while an
+/// asynchronous update is running
+pub const ACCUMULATOR_BUSY: &str = "ERR_NEON_ACCUMULATOR_BUSY";
+
+/// The `code` of the error thrown when an [`Accumulator`] is used after its result
+/// was returned or after an update panicked
+pub const ACCUMULATOR_FINISHED: &str = "ERR_NEON_ACCUMULATOR_FINISHED";
+
+/// The default size in bytes from which `updateAsync` processes a chunk on the Node
+/// worker pool
+pub const DEFAULT_ASYNC_THRESHOLD: usize = 64 * 1024;
+
+/// State that processes input in chunks and produces a result, wrapped by an
+/// [`Accumulator`]
+pub trait Accumulate: Send + 'static {
+    /// Processes the next chunk of input
+    fn update(&mut self, chunk: &[u8]);
+
+    /// Returns the result of processing the input
+    fn finish(self) -> Vec<u8>;
+}
+
+/// A builder for a JavaScript object that feeds chunks of bytes to an [`Accumulate`]
+/// state and returns its result.
+///
+/// The object has these methods:
+///
+/// * `update(chunk)` processes a `TypedArray`, `Buffer` or `ArrayBuffer`. The bytes
+///   are borrowed, not copied.
+/// * `updateAsync(chunk)` returns a promise that resolves when the chunk is processed.
+///   Chunks of at least the [threshold](Accumulator::async_threshold) are copied and
+///   processed on the Node worker pool, so the event loop is not blocked.
+/// * `digest()` returns the result in a new `Buffer`.
+/// * `digestInto(target)` copies the result into a `TypedArray`, `Buffer` or
+///   `ArrayBuffer` and returns the number of bytes written. It throws a `RangeError`
+///   if `target` is too small, and the result can still be returned by another call.
+///
+/// Once the result is returned, the methods throw an `Error` with a `code` of
+/// [`ACCUMULATOR_FINISHED`]. While an asynchronous update is running, the methods
+/// throw an `Error` with a `code` of [`ACCUMULATOR_BUSY`].
+///
+/// The state is dropped when the result is returned or when the object is garbage
+/// collected.
+pub struct Accumulator<T> {
+    state: T,
+    async_threshold: usize,
+}
+
+impl<T: Accumulate> Accumulator<T> {
+    /// Creates a builder for an object wrapping `state`
+    pub fn new(state: T) -> Self {
+        Self {
+            state,
+            async_threshold: DEFAULT_ASYNC_THRESHOLD,
+        }
+    }
+
+    /// Sets the size in bytes from which `updateAsync` processes a chunk on the Node
+    /// worker pool. Smaller chunks are processed immediately. Defaults to
+    /// [`DEFAULT_ASYNC_THRESHOLD`].
+    pub fn async_threshold(mut self, bytes: usize) -> Self {
+        self.async_threshold = bytes;
+        self
+    }
+
+    /// Creates the object
+    pub fn build<'cx, C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, JsObject> {
+        let shared = Arc::new(Shared {
+            state: Mutex::new(State::Ready(self.state)),
+            async_threshold: self.async_threshold,
+        });
+
+        let obj = cx.empty_object();
+        let update = method(cx, &shared, Shared::update)?;
+        let update_async = method(cx, &shared, Shared::update_async)?;
+        let digest = method(cx, &shared, Shared::digest)?;
+        let digest_into = method(cx, &shared, Shared::digest_into)?;
+
+        obj.set(cx, "update", update)?;
+        obj.set(cx, "updateAsync", update_async)?;
+        obj.set(cx, "digest", digest)?;
+        obj.set(cx, "digestInto", digest_into)?;
+
+        Ok(obj)
+    }
+}
+
+// Creates a function that calls `f` with the state of an accumulator. The state is
+// dropped when all of the functions of the accumulator are garbage collected.
+fn method<'cx, C, T, V>(
+    cx: &mut C,
+    shared: &Arc<Shared<T>>,
+    f: for<'a> fn(&Arc<Shared<T>>, &mut FunctionContext<'a>) -> JsResult<'a, V>,
+) -> JsResult<'cx, JsFunction>
+where
+    C: Context<'cx>,
+    T: Accumulate,
+    V: Value,
+{
+    let shared = Arc::clone(shared);
+
+    JsFunction::new(cx, move |mut cx| f(&shared, &mut cx))
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    async_threshold: usize,
+}
+
+enum State<T> {
+    Ready(T),
+    // An asynchronous update owns the state
+    Busy,
+    // The result was computed, but could not be copied into the target
+    Finished(Vec<u8>),
+    Done,
+    Panicked,
+}
+
+impl<T: Accumulate> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        // A poisoned lock means that `update` panicked, leaving the state inconsistent
+        self.state.lock().unwrap_or_else(|err| {
+            let mut state = err.into_inner();
+
+            *state = State::Panicked;
+            state
+        })
+    }
+
+    fn update<'a>(self: &Arc<Self>, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsValue> {
+        let chunk = cx.argument::<JsValue>(0)?;
+        let mut state = self.lock();
+        let state = ready(cx, &mut state)?;
+
+        with_bytes(cx, chunk, |bytes| state.update(bytes))?;
+
+        Ok(cx.undefined().upcast())
+    }
+
+    fn update_async<'a>(self: &Arc<Self>, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsPromise> {
+        let chunk = cx.argument::<JsValue>(0)?;
+        let mut guard = self.lock();
+        let threshold = self.async_threshold;
+
+        // Small chunks are processed immediately, without copying
+        let chunk = {
+            let state = ready(cx, &mut guard)?;
+
+            with_bytes(cx, chunk, |bytes| {
+                if bytes.len() < threshold {
+                    state.update(bytes);
+                    None
+                } else {
+                    Some(bytes.to_vec())
+                }
+            })?
+        };
+
+        let chunk = match chunk {
+            Some(chunk) => chunk,
+            None => {
+                let undefined = cx.undefined();
+
+                return Ok(JsPromise::resolve(cx, undefined));
+            }
+        };
+
+        let mut state = match std::mem::replace(&mut *guard, State::Busy) {
+            State::Ready(state) => state,
+            _ => unreachable!("the state was checked to be ready"),
+        };
+
+        drop(guard);
+
+        let shared = Arc::clone(self);
+        let promise = cx
+            .task(move || {
+                panic::catch_unwind(AssertUnwindSafe(move || {
+                    state.update(&chunk);
+                    state
+                }))
+            })
+            .promise(move |mut cx, result| {
+                let mut guard = shared.lock();
+
+                match result {
+                    Ok(state) => {
+                        *guard = State::Ready(state);
+                        Ok(cx.undefined())
+                    }
+                    Err(_) => {
+                        *guard = State::Panicked;
+                        drop(guard);
+                        cx.throw_error("the accumulator panicked while processing a chunk")
+                    }
+                }
+            });
+
+        Ok(promise)
+    }
+
+    fn digest<'a>(self: &Arc<Self>, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsBuffer> {
+        let result = self.finish(cx)?;
+
+        JsBuffer::from_slice(cx, &result)
+    }
+
+    fn digest_into<'a>(self: &Arc<Self>, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsNumber> {
+        let target = cx.argument::<JsValue>(0)?;
+        let result = self.finish(cx)?;
+        let copied = with_bytes_mut(cx, target, |bytes| {
+            let target = bytes.get_mut(..result.len())?;
+
+            target.copy_from_slice(&result);
+            Some(())
+        })?;
+
+        if copied.is_none() {
+            let message = format!(
+                "the target must have room for {} bytes of the result",
+                result.len()
+            );
+
+            *self.lock() = State::Finished(result);
+
+            return cx.throw_range_error(message);
+        }
+
+        Ok(cx.number(result.len() as f64))
+    }
+
+    // Consumes the state and returns the result
+    fn finish(&self, cx: &mut FunctionContext) -> NeonResult<Vec<u8>> {
+        let mut guard = self.lock();
+
+        // A result that could not be copied into the target may be returned again
+        if !matches!(*guard, State::Finished(_)) {
+            ready(cx, &mut guard)?;
+        }
+
+        match std::mem::replace(&mut *guard, State::Done) {
+            State::Ready(state) => {
+                drop(guard);
+                Ok(state.finish())
+            }
+            State::Finished(result) => Ok(result),
+            _ => unreachable!("the state was checked to be ready"),
+        }
+    }
+}
+
+// Returns the state if it can be updated, or throws
+fn ready<'a, 's, C, T>(cx: &mut C, state: &'s mut State<T>) -> NeonResult<&'s mut T>
+where
+    C: Context<'a>,
+    T: Accumulate,
+{
+    let (code, message) = match state {
+        State::Ready(state) => return Ok(state),
+        State::Busy => (
+            ACCUMULATOR_BUSY,
+            "the accumulator is busy with an asynchronous update",
+        ),
+        State::Finished(_) | State::Done => (
+            ACCUMULATOR_FINISHED,
+            "the result of the accumulator was already returned",
+        ),
+        State::Panicked => (
+            ACCUMULATOR_FINISHED,
+            "the accumulator panicked while processing a chunk",
+        ),
+    };
+
+    let err = JsError::error(cx, message)?;
+    let code = cx.string(code);
+
+    err.set(cx, "code", code)?;
+    cx.throw(err)
+}
+
+// Calls `f` with the bytes of a `TypedArray` or `ArrayBuffer`, or throws a `TypeError`
+fn with_bytes<'a, C, F, R>(cx: &mut C, value: Handle<JsValue>, f: F) -> NeonResult<R>
+where
+    C: Context<'a>,
+    F: FnOnce(&[u8]) -> R,
+{
+    with_bytes_mut(cx, value, |bytes| f(bytes))
+}
+
+// Calls `f` with the mutable bytes of a `TypedArray` or `ArrayBuffer`, or throws a
+// `TypeError`
+fn with_bytes_mut<'a, C, F, R>(cx: &mut C, value: Handle<JsValue>, f: F) -> NeonResult<R>
+where
+    C: Context<'a>,
+    F: FnOnce(&mut [u8]) -> R,
+{
+    let env = cx.env().to_raw();
+
+    unsafe {
+        if sys::tag::is_typedarray(env, value.to_raw()) {
+            let info = sys::typedarray::info(env, value.to_raw());
+            let len = info.length * element_size(info.typ);
+
+            // Safety: JavaScript does not run while the bytes are borrowed
+            if len == 0 {
+                return Ok(f(&mut []));
+            }
+
+            return Ok(f(slice::from_raw_parts_mut(info.data.cast(), len)));
+        }
+    }
+
+    if let Ok(mut buffer) = value.downcast::<JsArrayBuffer, _>(cx) {
+        return Ok(f(buffer.as_mut_slice(cx)));
+    }
+
+    cx.throw_type_error("expected a TypedArray, Buffer or ArrayBuffer")
+}
+
+fn element_size(typ: TypedArrayType) -> usize {
+    match typ {
+        TypedArrayType::I8 | TypedArrayType::U8 | TypedArrayType::U8Clamped => 1,
+        TypedArrayType::I16 | TypedArrayType::U16 => 2,
+        TypedArrayType::I32 | TypedArrayType::U32 | TypedArrayType::F32 => 4,
+        TypedArrayType::F64 | TypedArrayType::I64 | TypedArrayType::U64 => 8,
+    }
+}
@@ -0,0 +1,173 @@
+//! Guards for functions that must not be called again while they are running.
+//!
+//! An exported function that calls JavaScript, for example, a user callback, may be
+//! called again from that JavaScript before it returns. If the function has mutable
+//! state that is only consistent once it returns, the nested call can observe or
+//! corrupt it. A [`ReentrancyGuard`] marks a function as active until it is dropped
+//! and throws instead of allowing a nested call.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! #[neon::function(non_reentrant)]
+//! fn for_each_row(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let callback = cx.argument::<JsFunction>(0)?;
+//!
+//!     // Calling `for_each_row` from `callback` throws
+//!     callback.call_with(&cx).exec(&mut cx)?;
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+//!
+//! A nested call throws an `Error` with a `code` of `"ERR_NEON_REENTRANT_CALL"`, an
+//! `active` property naming the function that is running and an `attempted` property
+//! naming the function that was called.
+//!
+//! Guarded functions exclude each other as well as themselves, since they usually
+//! share state. A function may allow specific guarded functions to be called while it
+//! is running, for example, functions that only read state:
+//!
+//! ```
+//! # use neon::prelude::*;
+//! #[neon::function(non_reentrant, allow_nested("row_count"))]
+//! fn for_each_row(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     // ...
+//! #   Ok(cx.undefined())
+//! }
+//!
+//! #[neon::function(non_reentrant)]
+//! fn row_count(mut cx: FunctionContext) -> JsResult<JsNumber> {
+//!     // ...
+//! #   Ok(cx.number(0))
+//! }
+//! ```
+//!
+//! Active functions are tracked separately for each instance of the addon. Functions
+//! without a guard are never rejected.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    context::Context,
+    lifecycle::InstanceData,
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::JsError,
+};
+
+/// The `code` of the error thrown when a guarded function is called while a guarded
+/// function that does not allow it is running
+pub const REENTRANT_CALL: &str = "ERR_NEON_REENTRANT_CALL";
+
+#[derive(Default)]
+/// Guarded functions that are running in an instance of the addon
+pub(crate) struct Active(Arc<Mutex<State>>);
+
+#[derive(Default)]
+struct State {
+    next_id: u64,
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    id: u64,
+    name: String,
+    allow: Vec<String>,
+}
+
+fn lock(state: &Mutex<State>) -> MutexGuard<'_, State> {
+    state.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[must_use = "the function is only guarded until the guard is dropped"]
+/// Marks a function as running until it is dropped. Created with
+/// [`Context::reentrancy_guard`] or [`guard`].
+///
+/// The guard is released when it is dropped, including when the function returns an
+/// error or panics.
+pub struct ReentrancyGuard {
+    state: Arc<Mutex<State>>,
+    id: u64,
+}
+
+impl std::fmt::Debug for ReentrancyGuard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ReentrancyGuard")
+    }
+}
+
+impl Drop for ReentrancyGuard {
+    fn drop(&mut self) {
+        lock(&self.state)
+            .entries
+            .retain(|entry| entry.id != self.id);
+    }
+}
+
+/// Marks the function `name` as running, throwing an `Error` with a `code` of
+/// [`REENTRANT_CALL`] if a guarded function is running that does not allow `name` to
+/// be called. While the guard is held, the guarded functions in `allow` may be called.
+///
+/// This is the check performed by functions marked with
+/// [`#[neon::function(non_reentrant)]`](crate::export).
+pub fn guard<'a, C, S>(cx: &mut C, name: &str, allow: &[S]) -> NeonResult<ReentrancyGuard>
+where
+    C: Context<'a>,
+    S: AsRef<str>,
+{
+    let state = Arc::clone(&InstanceData::reentrancy(cx).0);
+    let mut active = lock(&state);
+
+    let conflict = active
+        .entries
+        .iter()
+        .find(|entry| !entry.allow.iter().any(|allowed| allowed == name))
+        .map(|entry| entry.name.clone());
+
+    if let Some(conflict) = conflict {
+        drop(active);
+
+        let err = reentrant_call_error(cx, &conflict, name)?;
+
+        return cx.throw(err);
+    }
+
+    let id = active.next_id;
+
+    active.next_id += 1;
+    active.entries.push(Entry {
+        id,
+        name: name.to_string(),
+        allow: allow.iter().map(|name| name.as_ref().to_string()).collect(),
+    });
+
+    drop(active);
+
+    Ok(ReentrancyGuard { state, id })
+}
+
+fn reentrant_call_error<'a, C: Context<'a>>(
+    cx: &mut C,
+    active: &str,
+    attempted: &str,
+) -> JsResult<'a, JsError> {
+    let message = if active == attempted {
+        format!("`{}` cannot be called recursively", attempted)
+    } else {
+        format!(
+            "`{}` cannot be called while `{}` is running",
+            attempted, active
+        )
+    };
+
+    let err = JsError::error(cx, message)?;
+    let code = cx.string(REENTRANT_CALL);
+    let active = cx.string(active);
+    let attempted = cx.string(attempted);
+
+    err.set(cx, "code", code)?;
+    err.set(cx, "active", active)?;
+    err.set(cx, "attempted", attempted)?;
+
+    Ok(err)
+}
@@ -7,6 +7,13 @@ use crate::{
     types::{build, JsString, JsValue},
 };
 
+#[cfg(feature = "napi-6")]
+use crate::{
+    object::Object,
+    result::NeonResult,
+    types::{JsFunction, JsObject, Value},
+};
+
 pub fn eval<'a, 'b, C: Context<'a>>(
     cx: &mut C,
     script: Handle<'b, JsString>,
@@ -16,3 +23,67 @@ pub fn eval<'a, 'b, C: Context<'a>>(
         crate::sys::string::run_script(out, env, script.to_raw())
     })
 }
+
+/// Loads the built-in module `name` without `require`, with `process.getBuiltinModule`
+/// or, in a CommonJS program, the `require` of the main module.
+///
+/// Returns `None` if neither is available. `process.getBuiltinModule` was added in
+/// Node 20.16 and 22.3; on older versions, there is no main module when the entry
+/// point is an ES module or in a worker started from a string with `eval: true`.
+/// Node-API does not give an addon its own `module`, so there is no other `require`
+/// to fall back to.
+#[cfg(feature = "napi-6")]
+pub(crate) fn builtin_module<'a, C: Context<'a>>(
+    cx: &mut C,
+    name: &str,
+) -> NeonResult<Option<Handle<'a, JsObject>>> {
+    let global = cx.global();
+    let process = global.get::<JsObject, _, _>(cx, "process")?;
+    let name = cx.string(name);
+    let get_builtin_module = process.get_value(cx, "getBuiltinModule")?;
+
+    let module = if let Ok(get_builtin_module) = get_builtin_module.downcast::<JsFunction, _>(cx) {
+        get_builtin_module
+            .call_with(cx)
+            .this(process)
+            .arg(name)
+            .apply::<JsValue, _>(cx)?
+    } else if let Some(main_module) = process.get_opt::<JsObject, _, _>(cx, "mainModule")? {
+        main_module
+            .get::<JsFunction, _, _>(cx, "require")?
+            .call_with(cx)
+            .this(main_module)
+            .arg(name)
+            .apply::<JsValue, _>(cx)?
+    } else {
+        return Ok(None);
+    };
+
+    Ok(module.downcast::<JsObject, _>(cx).ok())
+}
+
+/// Defines the property `key` on `object` with `Object.defineProperty`
+#[cfg(feature = "napi-6")]
+pub(crate) fn define_property<'a, C, O, K>(
+    cx: &mut C,
+    object: Handle<'a, O>,
+    key: Handle<'a, K>,
+    descriptor: Handle<'a, JsObject>,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    O: Object,
+    K: Value,
+{
+    let global = cx.global();
+    let global_object = global.get::<JsFunction, _, _>(cx, "Object")?;
+
+    global_object
+        .get::<JsFunction, _, _>(cx, "defineProperty")?
+        .call_with(cx)
+        .this(global_object)
+        .arg(object)
+        .arg(key)
+        .arg(descriptor)
+        .exec(cx)
+}
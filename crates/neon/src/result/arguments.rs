@@ -0,0 +1,264 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    object::Object,
+    result::NeonResult,
+    sys,
+    types::{private::ValueInternal, JsError, JsFunction, JsObject, JsString, JsValue, Value},
+};
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+// Longest summary of a primitive value before it is truncated, matching Node.js
+const MAX_RECEIVED_LEN: usize = 28;
+
+/// The format of errors thrown by Neon when an argument has the wrong type or a value
+/// is out of range, for example, by
+/// [`FunctionContext::argument`](crate::context::FunctionContext::argument).
+///
+/// By default, errors follow the conventions of Node.js core:
+///
+/// ```text
+/// TypeError [ERR_INVALID_ARG_TYPE]: The "arguments[0]" argument must be of type string. Received type number (42)
+/// ```
+///
+/// A `TypeError` has a `code` of `"ERR_INVALID_ARG_TYPE"` and a `RangeError` has a
+/// `code` of `"ERR_OUT_OF_RANGE"`. Both have an `argument` property with the name of
+/// the argument, if it is known, and a `received` property summarizing the value. A
+/// `TypeError` also has an `expected` property with the name of the expected type.
+///
+/// [`Plain`](ArgumentErrors::Plain) restores the messages of earlier versions of Neon,
+/// e.g., `failed to downcast any to string`, for code that matches on messages.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::result::ArgumentErrors;
+///
+/// #[neon::main]
+/// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+///     ArgumentErrors::Plain.set();
+///
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ArgumentErrors {
+    /// Errors with a `code`, following the conventions of Node.js. This is the default.
+    #[default]
+    Node,
+    /// Errors with plain messages and no `code`
+    Plain,
+}
+
+impl ArgumentErrors {
+    /// Sets the format of argument errors for every instance of the addon in the process
+    pub fn set(self) {
+        PLAIN.store(self == ArgumentErrors::Plain, Ordering::Relaxed);
+    }
+
+    /// The current format of argument errors
+    pub fn current() -> Self {
+        if PLAIN.load(Ordering::Relaxed) {
+            ArgumentErrors::Plain
+        } else {
+            ArgumentErrors::Node
+        }
+    }
+}
+
+/// Throws an `ERR_INVALID_ARG_TYPE` error for the argument `name`, which was expected to
+/// be a `V`. `v` is `None` if the argument is missing.
+pub(crate) fn throw_invalid_arg_type<'a, V, C, T>(
+    cx: &mut C,
+    name: &str,
+    v: Option<Handle<JsValue>>,
+) -> NeonResult<T>
+where
+    V: Value,
+    C: Context<'a>,
+{
+    if ArgumentErrors::current() == ArgumentErrors::Plain {
+        return match v {
+            Some(_) => cx.throw_type_error(format!(
+                "failed to downcast {} to {}",
+                JsValue::name(),
+                V::name()
+            )),
+            None => cx.throw_type_error("not enough arguments"),
+        };
+    }
+
+    let expected = V::name();
+    let received = match v {
+        Some(v) => received(cx, v)?,
+        None => String::from("undefined"),
+    };
+
+    // Node.js uses "of type" for primitives and "an instance of" for classes
+    let phrase = if expected.starts_with(char::is_uppercase) {
+        format!("an instance of {}", expected)
+    } else {
+        format!("of type {}", expected)
+    };
+
+    let err = JsError::type_error(
+        cx,
+        format!(
+            "The \"{}\" argument must be {}. Received {}",
+            name, phrase, received
+        ),
+    )?;
+
+    set_properties(
+        cx,
+        err,
+        "ERR_INVALID_ARG_TYPE",
+        Some(name),
+        Some(&expected),
+        &received,
+    )?;
+
+    cx.throw(err)
+}
+
+#[cfg(feature = "napi-6")]
+/// Throws an `ERR_OUT_OF_RANGE` error. `range` completes the sentence "It must be",
+/// `received` is the out of range value and `plain` is the message of a
+/// [`Plain`](ArgumentErrors::Plain) error.
+pub(crate) fn throw_out_of_range<'a, C, T>(
+    cx: &mut C,
+    name: Option<&str>,
+    range: &str,
+    received: &str,
+    plain: impl FnOnce() -> String,
+) -> NeonResult<T>
+where
+    C: Context<'a>,
+{
+    if ArgumentErrors::current() == ArgumentErrors::Plain {
+        return cx.throw_range_error(plain());
+    }
+
+    let subject = match name {
+        Some(name) => format!("The value of \"{}\"", name),
+        None => String::from("The value"),
+    };
+
+    let err = JsError::range_error(
+        cx,
+        format!(
+            "{} is out of range. It must be {}. Received {}",
+            subject, range, received
+        ),
+    )?;
+
+    set_properties(cx, err, "ERR_OUT_OF_RANGE", name, None, received)?;
+
+    cx.throw(err)
+}
+
+fn set_properties<'a, C: Context<'a>>(
+    cx: &mut C,
+    err: Handle<JsError>,
+    code: &str,
+    name: Option<&str>,
+    expected: Option<&str>,
+    received: &str,
+) -> NeonResult<()> {
+    let code = cx.string(code);
+
+    err.set(cx, "code", code)?;
+
+    if let Some(name) = name {
+        let name = cx.string(name);
+
+        err.set(cx, "argument", name)?;
+    }
+
+    if let Some(expected) = expected {
+        let expected = cx.string(expected);
+
+        err.set(cx, "expected", expected)?;
+    }
+
+    let received = cx.string(received);
+
+    err.set(cx, "received", received)?;
+
+    Ok(())
+}
+
+/// Summarizes a value like the `Received ...` suffix of Node.js errors, e.g.,
+/// `type number (42)` or `an instance of Date`
+fn received<'a, C: Context<'a>>(cx: &mut C, v: Handle<JsValue>) -> NeonResult<String> {
+    let type_of = unsafe { sys::tag::type_of(cx.env().to_raw(), v.to_raw()) };
+
+    match type_of {
+        "undefined" => return Ok(String::from("undefined")),
+        "object" if unsafe { sys::tag::is_null(cx.env().to_raw(), v.to_raw()) } => {
+            return Ok(String::from("null"))
+        }
+        "function" => {
+            let f = v.downcast_or_throw::<JsFunction, _>(cx)?;
+
+            return Ok(match property_name(cx, f)? {
+                Some(name) => format!("function {}", name),
+                None => String::from("type function ([Function (anonymous)])"),
+            });
+        }
+        "object" => {
+            let o = v.downcast_or_throw::<JsObject, _>(cx)?;
+
+            let constructor = o.get_value(cx, "constructor")?;
+
+            return Ok(match constructor.downcast::<JsFunction, _>(cx) {
+                Ok(constructor) => match property_name(cx, constructor)? {
+                    Some(name) => format!("an instance of {}", name),
+                    None => String::from("type object"),
+                },
+                Err(_) => String::from("type object"),
+            });
+        }
+        _ => {}
+    }
+
+    let mut inspected = if let Ok(s) = v.downcast::<JsString, _>(cx) {
+        format!("'{}'", s.value(cx))
+    } else {
+        let global = cx.global();
+        let string = global.get::<JsFunction, _, _>(cx, "String")?;
+        let s = string
+            .call(cx, global, [v])?
+            .downcast_or_throw::<JsString, _>(cx)?
+            .value(cx);
+
+        if type_of == "bigint" {
+            format!("{}n", s)
+        } else {
+            s
+        }
+    };
+
+    if inspected.chars().count() > MAX_RECEIVED_LEN {
+        inspected = inspected.chars().take(25).collect::<String>() + "...";
+    }
+
+    Ok(format!("type {} ({})", type_of, inspected))
+}
+
+// The `name` of a function or constructor, if it is a non-empty string
+fn property_name<'a, C, O>(cx: &mut C, o: Handle<O>) -> NeonResult<Option<String>>
+where
+    C: Context<'a>,
+    O: Object,
+{
+    let name = o.get_value(cx, "name")?;
+
+    Ok(name
+        .downcast::<JsString, _>(cx)
+        .ok()
+        .map(|name| name.value(cx))
+        .filter(|name| !name.is_empty()))
+}
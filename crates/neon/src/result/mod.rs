@@ -40,6 +40,13 @@ use std::{
 
 use crate::{context::Context, handle::Handle, types::Value};
 
+pub(crate) use self::arguments::throw_invalid_arg_type;
+#[cfg(feature = "napi-6")]
+pub(crate) use self::arguments::throw_out_of_range;
+pub use self::arguments::ArgumentErrors;
+
+mod arguments;
+
 /// A [unit type][unit] indicating that the JavaScript thread is throwing an exception.
 ///
 /// `Throw` deliberately does not implement [`std::error::Error`](std::error::Error). It's
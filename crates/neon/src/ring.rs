@@ -0,0 +1,428 @@
+//! Streaming fixed-size records from Rust to JavaScript through shared memory.
+//!
+//! A [`RingBuffer`] is a single-producer, single-consumer queue stored in a
+//! `SharedArrayBuffer`. A Rust thread writes records with
+//! [`try_push`](RingBuffer::try_push) and JavaScript reads them directly from shared
+//! memory, without a native call or [`Channel`] message per record.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::ring::RingBuffer;
+//!
+//! fn telemetry(mut cx: FunctionContext) -> JsResult<JsObject> {
+//!     let (ring, mut writer) = RingBuffer::create(&mut cx, 8, 1024)?;
+//!
+//!     std::thread::spawn(move || {
+//!         for i in 0u64.. {
+//!             // Drop samples if JavaScript falls behind
+//!             let _ = writer.try_push(&i.to_le_bytes());
+//!             std::thread::sleep(std::time::Duration::from_millis(1));
+//!         }
+//!     });
+//!
+//!     Ok(ring)
+//! }
+//! ```
+//!
+//! ```js
+//! const ring = addon.telemetry();
+//!
+//! setInterval(() => {
+//!     for (let record; (record = ring.read()); ) {
+//!         console.log(new DataView(record.buffer).getBigUint64(0, true));
+//!     }
+//! }, 100);
+//! ```
+//!
+//! ## Layout
+//!
+//! The JavaScript object returned by [`RingBuffer::create`] has the following properties:
+//!
+//! * `buffer`: the `SharedArrayBuffer`
+//! * `indices`: a `Uint32Array` of `[head, tail, dropped]`, where `head` is the slot
+//!   of the next record to read, `tail` is the slot of the next record to write and
+//!   `dropped` counts records discarded by [`Overflow::DropOldest`]
+//! * `records`: a `Uint8Array` of `capacity + 1` slots of `recordSize` bytes
+//! * `recordSize` and `capacity`
+//! * `read()`: returns a copy of the next record as a `Uint8Array`, or `undefined`
+//!   if the ring is empty
+//!
+//! The ring is empty when `head === tail`. A reader advances `head` with
+//! `Atomics.compareExchange` after copying a record and discards the copy if it fails, since the record
+//! was dropped by the writer while it was being read.
+//!
+//! [`Channel`]: crate::event::Channel
+
+use std::{
+    error::Error,
+    fmt, ptr,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+};
+
+use crate::{
+    context::Context,
+    event::Channel,
+    handle::{Handle, Managed, Root},
+    object::Object,
+    result::NeonResult,
+    sys,
+    types::{buffer::TypedArray, JsFunction, JsObject, JsTypedArray, JsValue},
+};
+
+// Size of the `[head, tail, dropped, reserved]` header
+const HEADER_SIZE: usize = 16;
+
+const HEAD: usize = 0;
+const TAIL: usize = 1;
+const DROPPED: usize = 2;
+
+/// Behavior of [`RingBuffer::try_push`] when the ring is full
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Overflow {
+    /// Reject the new record with [`Full`]. This is the default.
+    #[default]
+    Reject,
+    /// Discard the oldest unread record to make room for the new record
+    DropOldest,
+}
+
+/// The error returned by [`RingBuffer::try_push`] when the ring is full
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Full;
+
+impl fmt::Display for Full {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("ring buffer is full")
+    }
+}
+
+impl Error for Full {}
+
+/// The writer of a ring buffer of fixed-size records shared with JavaScript
+///
+/// See the [module documentation](crate::ring) for details.
+pub struct RingBuffer {
+    data: *mut u8,
+    record_size: usize,
+    capacity: usize,
+    overflow: Overflow,
+    wakeup: Option<Wakeup>,
+    // Keeps the `SharedArrayBuffer` alive while it is being written
+    _buffer: Root<JsObject>,
+}
+
+// Safety: `data` points into a `SharedArrayBuffer` kept alive by `_buffer`.
+// `RingBuffer` is the only writer and requires `&mut self` to write.
+unsafe impl Send for RingBuffer {}
+
+struct Wakeup {
+    channel: Channel,
+    callback: Arc<Root<JsFunction>>,
+    // A wakeup has been sent, but the callback has not been called
+    pending: Arc<AtomicBool>,
+}
+
+impl RingBuffer {
+    /// Creates a ring buffer that can hold `capacity` records of `record_size` bytes.
+    ///
+    /// Returns the JavaScript object used to read records and the Rust writer.
+    /// Throws a `RangeError` if either size is zero or the buffer would be too large.
+    pub fn create<'a, C: Context<'a>>(
+        cx: &mut C,
+        record_size: usize,
+        capacity: usize,
+    ) -> NeonResult<(Handle<'a, JsObject>, Self)> {
+        let len = capacity
+            .checked_add(1)
+            .and_then(|slots| slots.checked_mul(record_size))
+            .and_then(|len| len.checked_add(HEADER_SIZE))
+            .filter(|len| *len <= u32::MAX as usize);
+
+        let len = match len {
+            Some(len) if record_size > 0 && capacity > 0 => len,
+            _ => return cx.throw_range_error("invalid ring buffer size"),
+        };
+
+        let global = cx.global();
+        let buffer = global
+            .get::<JsFunction, _, _>(cx, "SharedArrayBuffer")?
+            .construct_with(cx)
+            .arg(cx.number(len as f64))
+            .apply::<JsObject, _>(cx)?;
+
+        let bytes = global
+            .get::<JsFunction, _, _>(cx, "Uint8Array")?
+            .construct_with(cx)
+            .arg(buffer)
+            .apply::<JsObject, _>(cx)?;
+
+        let data = unsafe { sys::typedarray::info(cx.env().to_raw(), bytes.to_raw()).data };
+
+        let indices = global
+            .get::<JsFunction, _, _>(cx, "Uint32Array")?
+            .construct_with(cx)
+            .arg(buffer)
+            .arg(cx.number(0))
+            .arg(cx.number((HEADER_SIZE / 4) as f64))
+            .apply::<JsObject, _>(cx)?;
+
+        let records = global
+            .get::<JsFunction, _, _>(cx, "Uint8Array")?
+            .construct_with(cx)
+            .arg(buffer)
+            .arg(cx.number(HEADER_SIZE as f64))
+            .apply::<JsObject, _>(cx)?;
+
+        let ring = cx.empty_object();
+        let record_size_value = cx.number(record_size as f64);
+        let capacity_value = cx.number(capacity as f64);
+
+        ring.set(cx, "buffer", buffer)?;
+        ring.set(cx, "indices", indices)?;
+        ring.set(cx, "records", records)?;
+        ring.set(cx, "recordSize", record_size_value)?;
+        ring.set(cx, "capacity", capacity_value)?;
+
+        let reader = Reader {
+            data: data.cast(),
+            record_size,
+            slots: capacity as u32 + 1,
+            _buffer: buffer.root(cx),
+        };
+
+        let read = JsFunction::new(cx, move |mut cx| reader.read(&mut cx))?;
+
+        ring.set(cx, "read", read)?;
+
+        let writer = Self {
+            data: data.cast(),
+            record_size,
+            capacity,
+            overflow: Overflow::default(),
+            wakeup: None,
+            _buffer: buffer.root(cx),
+        };
+
+        Ok((ring, writer))
+    }
+
+    /// Sets the behavior when pushing to a full ring. _Default: [`Overflow::Reject`]_
+    pub fn with_overflow(mut self, overflow: Overflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Calls `callback` on the JavaScript thread when a record is pushed to an
+    /// empty ring, so that JavaScript does not need to poll for records.
+    ///
+    /// Wakeups are coalesced: `callback` is called at most once for each time the
+    /// event loop processes a wakeup and should read until the ring is empty. The
+    /// callback does not prevent the Node event loop from exiting.
+    pub fn with_wakeup<'a, C: Context<'a>>(
+        mut self,
+        cx: &mut C,
+        callback: Handle<JsFunction>,
+    ) -> Self {
+        let mut channel = cx.channel();
+
+        channel.unref(cx);
+
+        self.wakeup = Some(Wakeup {
+            channel,
+            callback: Arc::new(callback.root(cx)),
+            pending: Arc::new(AtomicBool::new(false)),
+        });
+
+        self
+    }
+
+    /// The size of each record in bytes
+    pub fn record_size(&self) -> usize {
+        self.record_size
+    }
+
+    /// The maximum number of unread records
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of unread records
+    pub fn len(&self) -> usize {
+        let slots = self.slots();
+        let head = self.index(HEAD).load(Ordering::SeqCst);
+        let tail = self.index(TAIL).load(Ordering::SeqCst);
+
+        ((tail + slots - head) % slots) as usize
+    }
+
+    /// Returns `true` if all records have been read
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Writes a record to the ring.
+    ///
+    /// If the ring is full, the record is rejected with [`Full`] or the oldest record
+    /// is dropped, depending on the [`Overflow`] policy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `record` is not [`record_size`](RingBuffer::record_size).
+    pub fn try_push(&mut self, record: &[u8]) -> Result<(), Full> {
+        assert_eq!(
+            record.len(),
+            self.record_size,
+            "record must be exactly `record_size` bytes"
+        );
+
+        let slots = self.slots();
+        let head = self.index(HEAD);
+        let tail = self.index(TAIL).load(Ordering::SeqCst);
+        let next = (tail + 1) % slots;
+
+        loop {
+            let current = head.load(Ordering::SeqCst);
+
+            if current != next {
+                break;
+            }
+
+            if self.overflow == Overflow::Reject {
+                return Err(Full);
+            }
+
+            // Fails if the reader advanced `head` concurrently, in which case there is room
+            if head
+                .compare_exchange(
+                    current,
+                    (current + 1) % slots,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                )
+                .is_ok()
+            {
+                self.index(DROPPED).fetch_add(1, Ordering::SeqCst);
+                break;
+            }
+        }
+
+        unsafe {
+            let slot = self
+                .data
+                .add(HEADER_SIZE + tail as usize * self.record_size);
+
+            ptr::copy_nonoverlapping(record.as_ptr(), slot, self.record_size);
+        }
+
+        self.index(TAIL).store(next, Ordering::SeqCst);
+
+        // The reader had read every record before this one and may have stopped reading
+        if head.load(Ordering::SeqCst) == tail {
+            self.wake();
+        }
+
+        Ok(())
+    }
+
+    fn wake(&self) {
+        let wakeup = match &self.wakeup {
+            Some(wakeup) => wakeup,
+            None => return,
+        };
+
+        if wakeup.pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let callback = Arc::clone(&wakeup.callback);
+        let pending = Arc::clone(&wakeup.pending);
+
+        let _ = wakeup.channel.try_send(move |mut cx| {
+            pending.store(false, Ordering::Release);
+
+            let this = cx.undefined();
+
+            callback
+                .to_inner(&mut cx)
+                .call(&mut cx, this, [] as [Handle<JsValue>; 0])?;
+
+            Ok(())
+        });
+    }
+
+    fn slots(&self) -> u32 {
+        self.capacity as u32 + 1
+    }
+
+    fn index(&self, i: usize) -> &AtomicU32 {
+        // Safety: The header is within the buffer and aligned, since the buffer
+        // is allocated with at least 8 byte alignment
+        unsafe { &*(self.data as *const AtomicU32).add(i) }
+    }
+}
+
+// The `read()` method of a ring object
+struct Reader {
+    data: *mut u8,
+    record_size: usize,
+    slots: u32,
+    // Keeps the `SharedArrayBuffer` alive while it is being read
+    _buffer: Root<JsObject>,
+}
+
+impl Reader {
+    // Returns a copy of the next record, or `undefined` if the ring is empty
+    fn read<'a, C: Context<'a>>(&self, cx: &mut C) -> NeonResult<Handle<'a, JsValue>> {
+        loop {
+            let head = self.index(HEAD).load(Ordering::SeqCst);
+
+            if head == self.index(TAIL).load(Ordering::SeqCst) {
+                return Ok(cx.undefined().upcast());
+            }
+
+            let mut record = JsTypedArray::<u8>::new(cx, self.record_size)?;
+
+            unsafe {
+                let slot = self
+                    .data
+                    .add(HEADER_SIZE + head as usize * self.record_size);
+                let dest = record.as_mut_slice(cx).as_mut_ptr();
+
+                ptr::copy_nonoverlapping(slot, dest, self.record_size);
+            }
+
+            // Fails if the writer dropped the record while it was being copied
+            if self
+                .index(HEAD)
+                .compare_exchange(
+                    head,
+                    (head + 1) % self.slots,
+                    Ordering::SeqCst,
+                    Ordering::SeqCst,
+                )
+                .is_ok()
+            {
+                return Ok(record.upcast());
+            }
+        }
+    }
+
+    fn index(&self, i: usize) -> &AtomicU32 {
+        // Safety: See `RingBuffer::index`
+        unsafe { &*(self.data as *const AtomicU32).add(i) }
+    }
+}
+
+impl fmt::Debug for RingBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("RingBuffer")
+            .field("record_size", &self.record_size)
+            .field("capacity", &self.capacity)
+            .field("overflow", &self.overflow)
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,464 @@
+//! Rust state that can be observed from JavaScript.
+//!
+//! A [`Watchable`] holds a value that may be updated from any thread. JavaScript
+//! can read the current value and subscribe to changes through the object
+//! returned by [`Watchable::export`]:
+//!
+//! ```js
+//! const status = addon.status();
+//!
+//! console.log(status.get());
+//!
+//! const unsubscribe = status.subscribe((value) => console.log(value));
+//!
+//! for await (const value of status) {
+//!     console.log(value);
+//! }
+//! ```
+//!
+//! Change notifications are delivered on the JavaScript thread through a
+//! [`Channel`](crate::event::Channel). By default, rapid successive updates are
+//! coalesced so that subscribers only observe the latest value each time the
+//! event loop processes the notification.
+//!
+//! An async iterator buffers the changes it has not read yet. When updates are
+//! coalesced, it only keeps the latest one. Otherwise, it keeps up to
+//! [`MAX_BUFFERED`] changes and drops the oldest when another arrives.
+//!
+//! ## Example
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::{state::Watchable, thread::LocalKey};
+//!
+//! static STATUS: LocalKey<Watchable<String>> = LocalKey::new();
+//!
+//! fn status(mut cx: FunctionContext) -> JsResult<JsObject> {
+//!     let status = STATUS
+//!         .get_or_try_init(&mut cx, |cx| -> NeonResult<_> {
+//!             Ok(Watchable::new(cx, String::from("disconnected")))
+//!         })?
+//!         .clone();
+//!
+//!     std::thread::spawn({
+//!         let status = status.clone();
+//!         move || status.set(String::from("connected"))
+//!     });
+//!
+//!     status.export(&mut cx)
+//! }
+//! ```
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use crate::{
+    context::{Context, FunctionContext, TaskContext},
+    event::Channel,
+    handle::{Handle, Root},
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{extract::TryIntoJs, Deferred, JsFunction, JsObject, JsPromise, JsValue},
+};
+
+/// Number of unread changes an async iterator keeps when updates are not coalesced
+pub const MAX_BUFFERED: usize = 1024;
+
+/// A value that can be updated from any thread and observed from JavaScript
+///
+/// Cloning a `Watchable` produces another handle to the same value.
+pub struct Watchable<T> {
+    inner: Arc<Inner<T>>,
+}
+
+struct Inner<T> {
+    value: Mutex<T>,
+    subscribers: Mutex<Subscribers<T>>,
+    channel: Channel,
+    coalesce: AtomicBool,
+    // A notification has been sent, but has not executed yet
+    pending: AtomicBool,
+}
+
+struct Subscribers<T> {
+    next_id: u64,
+    callbacks: Vec<(u64, Root<JsFunction>)>,
+    iterators: Vec<(u64, Arc<Mutex<Iter<T>>>)>,
+}
+
+// State of a JavaScript async iterator over changes
+struct Iter<T> {
+    // Changes that have not been read by `next()`
+    buffered: VecDeque<T>,
+    // Pending `next()` promises awaiting a change
+    waiting: VecDeque<Deferred>,
+    done: bool,
+}
+
+impl<T> Clone for Watchable<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: Arc::clone(&self.inner),
+        }
+    }
+}
+
+impl<T> Watchable<T>
+where
+    T: Clone + Send + 'static,
+    for<'cx> T: TryIntoJs<'cx>,
+{
+    /// Creates a new `Watchable` with an initial value.
+    ///
+    /// The `Watchable` does not prevent the Node event loop from exiting.
+    pub fn new<'a, C: Context<'a>>(cx: &mut C, value: T) -> Self {
+        let mut channel = cx.channel();
+
+        channel.unref(cx);
+
+        Self {
+            inner: Arc::new(Inner {
+                value: Mutex::new(value),
+                subscribers: Mutex::new(Subscribers {
+                    next_id: 0,
+                    callbacks: Vec::new(),
+                    iterators: Vec::new(),
+                }),
+                channel,
+                coalesce: AtomicBool::new(true),
+                pending: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    /// Controls whether rapid successive updates are coalesced into a single
+    /// notification with the latest value. _Default: `true`_
+    ///
+    /// When disabled, subscribers are notified once for every call to [`set`](Watchable::set),
+    /// and async iterators buffer up to [`MAX_BUFFERED`] changes that were not read.
+    pub fn set_coalesce(&self, coalesce: bool) -> &Self {
+        self.inner.coalesce.store(coalesce, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns a clone of the current value
+    pub fn get(&self) -> T {
+        self.inner.value.lock().unwrap().clone()
+    }
+
+    /// Updates the value and notifies JavaScript subscribers. May be called from
+    /// any thread.
+    ///
+    /// Notifications are silently dropped if the JavaScript instance has shut down.
+    pub fn set(&self, value: T) {
+        let inner = &self.inner;
+
+        if !inner.coalesce.load(Ordering::Relaxed) {
+            *inner.value.lock().unwrap() = value.clone();
+
+            let inner = Arc::clone(inner);
+            let _ = self
+                .inner
+                .channel
+                .try_send(move |mut cx| inner.notify(&mut cx, value));
+
+            return;
+        }
+
+        *inner.value.lock().unwrap() = value;
+
+        // A notification is already scheduled and will read the latest value
+        if inner.pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
+
+        let inner = Arc::clone(inner);
+        let _ = self.inner.channel.try_send(move |mut cx| {
+            inner.pending.store(false, Ordering::Release);
+
+            let value = inner.value.lock().unwrap().clone();
+
+            inner.notify(&mut cx, value)
+        });
+    }
+
+    /// Creates a JavaScript object for observing the value.
+    ///
+    /// The object has the following methods:
+    /// * `get()`: returns the current value
+    /// * `subscribe(callback)`: calls `callback` with each new value and returns
+    ///   a function that unsubscribes
+    /// * `[Symbol.asyncIterator]()`: returns an async iterator of changes
+    pub fn export<'a, C: Context<'a>>(&self, cx: &mut C) -> JsResult<'a, JsObject> {
+        let obj = cx.empty_object();
+
+        let get = JsFunction::new(cx, {
+            let watchable = self.clone();
+
+            move |mut cx| {
+                let value = watchable.get();
+
+                value.try_into_js(&mut cx).map(|v| v.upcast::<JsValue>())
+            }
+        })?;
+
+        let subscribe = JsFunction::new(cx, {
+            let watchable = self.clone();
+
+            move |mut cx| watchable.subscribe(&mut cx)
+        })?;
+
+        let iterator = JsFunction::new(cx, {
+            let watchable = self.clone();
+
+            move |mut cx| watchable.iterator(&mut cx)
+        })?;
+
+        let symbol = async_iterator_symbol(cx)?;
+
+        obj.set(cx, "get", get)?;
+        obj.set(cx, "subscribe", subscribe)?;
+        obj.set(cx, symbol, iterator)?;
+
+        Ok(obj)
+    }
+
+    // JavaScript `subscribe(callback)` method
+    pub(crate) fn subscribe<'a>(&self, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsFunction> {
+        let callback = cx.argument::<JsFunction>(0)?.root(cx);
+        let id = {
+            let mut subscribers = self.inner.subscribers.lock().unwrap();
+            let id = subscribers.next_id();
+
+            subscribers.callbacks.push((id, callback));
+            id
+        };
+
+        let inner = Arc::clone(&self.inner);
+
+        JsFunction::new(cx, move |mut cx| {
+            let callback = {
+                let mut subscribers = inner.subscribers.lock().unwrap();
+                let index = subscribers.callbacks.iter().position(|(i, _)| *i == id);
+
+                index.map(|index| subscribers.callbacks.remove(index).1)
+            };
+
+            // Unsubscribing more than once has no effect
+            if let Some(callback) = callback {
+                callback.drop(&mut cx);
+            }
+
+            Ok(cx.undefined())
+        })
+    }
+
+    // JavaScript `[Symbol.asyncIterator]()` method
+    fn iterator<'a>(&self, cx: &mut FunctionContext<'a>) -> JsResult<'a, JsObject> {
+        let iter = Arc::new(Mutex::new(Iter {
+            buffered: VecDeque::new(),
+            waiting: VecDeque::new(),
+            done: false,
+        }));
+
+        let id = {
+            let mut subscribers = self.inner.subscribers.lock().unwrap();
+            let id = subscribers.next_id();
+
+            subscribers.iterators.push((id, Arc::clone(&iter)));
+            id
+        };
+
+        let obj = cx.empty_object();
+
+        let next = JsFunction::new(cx, {
+            let iter = Arc::clone(&iter);
+
+            move |mut cx| {
+                let mut state = iter.lock().unwrap();
+                let (deferred, promise) = cx.promise();
+
+                if let Some(value) = state.buffered.pop_front() {
+                    drop(state);
+
+                    let value = value.try_into_js(&mut cx)?.upcast();
+                    let result = iter_result(&mut cx, value, false)?;
+
+                    deferred.resolve(&mut cx, result);
+                } else if state.done {
+                    drop(state);
+
+                    let value = cx.undefined().upcast();
+                    let result = iter_result(&mut cx, value, true)?;
+
+                    deferred.resolve(&mut cx, result);
+                } else {
+                    state.waiting.push_back(deferred);
+                }
+
+                Ok(promise)
+            }
+        })?;
+
+        let finish = JsFunction::new(cx, {
+            let inner = Arc::clone(&self.inner);
+            let iter = Arc::clone(&iter);
+
+            move |mut cx| -> JsResult<JsPromise> {
+                {
+                    let mut subscribers = inner.subscribers.lock().unwrap();
+
+                    subscribers.iterators.retain(|(i, _)| *i != id);
+                }
+
+                let waiting = {
+                    let mut state = iter.lock().unwrap();
+
+                    state.done = true;
+                    state.buffered.clear();
+                    std::mem::take(&mut state.waiting)
+                };
+
+                for deferred in waiting {
+                    let value = cx.undefined().upcast();
+                    let result = iter_result(&mut cx, value, true)?;
+
+                    deferred.resolve(&mut cx, result);
+                }
+
+                let (deferred, promise) = cx.promise();
+                let value = cx.undefined().upcast();
+                let result = iter_result(&mut cx, value, true)?;
+
+                deferred.resolve(&mut cx, result);
+
+                Ok(promise)
+            }
+        })?;
+
+        let this = JsFunction::new(cx, |mut cx| Ok(cx.this_value()))?;
+        let symbol = async_iterator_symbol(cx)?;
+
+        obj.set(cx, "next", next)?;
+        obj.set(cx, "return", finish)?;
+        obj.set(cx, symbol, this)?;
+
+        Ok(obj)
+    }
+}
+
+impl<T> Inner<T>
+where
+    T: Clone + Send + 'static,
+    for<'cx> T: TryIntoJs<'cx>,
+{
+    // Deliver a value to all subscribers. Every callback is called even if
+    // an earlier callback throws; the first exception is re-thrown.
+    fn notify(&self, cx: &mut TaskContext, value: T) -> NeonResult<()> {
+        let (callbacks, iterators) = {
+            let subscribers = self.subscribers.lock().unwrap();
+            let callbacks = subscribers
+                .callbacks
+                .iter()
+                .map(|(_, callback)| callback.to_inner(cx))
+                .collect::<Vec<_>>();
+
+            let iterators = subscribers
+                .iterators
+                .iter()
+                .map(|(_, iter)| Arc::clone(iter))
+                .collect::<Vec<_>>();
+
+            (callbacks, iterators)
+        };
+
+        let coalesce = self.coalesce.load(Ordering::Relaxed);
+
+        for iter in iterators {
+            let deferred = {
+                let mut state = iter.lock().unwrap();
+
+                match state.waiting.pop_front() {
+                    Some(deferred) => deferred,
+                    None => {
+                        state.buffer(value.clone(), coalesce);
+                        continue;
+                    }
+                }
+            };
+
+            // Converted without the lock, since the conversion may call into JavaScript
+            let value = value.clone().try_into_js(cx)?.upcast();
+            let result = iter_result(cx, value, false)?;
+
+            deferred.resolve(cx, result);
+        }
+
+        if callbacks.is_empty() {
+            return Ok(());
+        }
+
+        let value = value.try_into_js(cx)?.upcast::<JsValue>();
+        let this = cx.undefined();
+        let mut exception = None;
+
+        for callback in callbacks {
+            if let Err(err) = cx.try_catch(|cx| callback.call(cx, this, [value])) {
+                exception.get_or_insert(err);
+            }
+        }
+
+        match exception {
+            Some(err) => cx.throw(err),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<T> Iter<T> {
+    // Keeps a change until it is read by `next()`
+    fn buffer(&mut self, value: T, coalesce: bool) {
+        if coalesce {
+            self.buffered.clear();
+        } else if self.buffered.len() == MAX_BUFFERED {
+            self.buffered.pop_front();
+        }
+
+        self.buffered.push_back(value);
+    }
+}
+
+impl<T> Subscribers<T> {
+    fn next_id(&mut self) -> u64 {
+        let id = self.next_id;
+
+        self.next_id += 1;
+        id
+    }
+}
+
+// Create an iterator result object, `{ value, done }`
+fn iter_result<'a, C: Context<'a>>(
+    cx: &mut C,
+    value: Handle<'a, JsValue>,
+    done: bool,
+) -> JsResult<'a, JsObject> {
+    let result = cx.empty_object();
+    let done = cx.boolean(done);
+
+    result.set(cx, "value", value)?;
+    result.set(cx, "done", done)?;
+
+    Ok(result)
+}
+
+fn async_iterator_symbol<'a, C: Context<'a>>(cx: &mut C) -> JsResult<'a, JsValue> {
+    cx.global()
+        .get::<JsFunction, _, _>(cx, "Symbol")?
+        .get_value(cx, "asyncIterator")
+}
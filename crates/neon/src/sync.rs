@@ -0,0 +1,757 @@
+//! Synchronization primitives that are aware of the JavaScript thread.
+//!
+//! Blocking the JavaScript thread on a lock held by a background thread freezes
+//! the event loop. [`JsRwCell`] is a reader-writer lock that never blocks when
+//! acquired from JavaScript. Instead, contention is reported as a catchable
+//! JavaScript exception. [`InstanceLock`] is a mutual exclusion lock with the same
+//! guarantee, intended for instance-local state that is updated as a unit.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::sync::Arc;
+//!
+//! use neon::sync::JsRwCell;
+//!
+//! type Counter = JsBox<Arc<JsRwCell<u32>>>;
+//!
+//! fn increment(mut cx: FunctionContext) -> JsResult<JsNumber> {
+//!     let counter = cx.argument::<Counter>(0)?;
+//!     let mut n = counter.write(&mut cx)?;
+//!
+//!     *n += 1;
+//!
+//!     Ok(cx.number(*n))
+//! }
+//! ```
+
+use std::{
+    fmt,
+    ops::{Deref, DerefMut},
+    sync::{Condvar, Mutex, MutexGuard, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError},
+    thread::{self, ThreadId},
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "napi-4")]
+use std::sync::Arc;
+
+#[cfg(feature = "futures")]
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context as TaskCx, Poll, Waker},
+};
+
+use crate::{
+    context::{internal::IS_RUNNING, Context},
+    result::NeonResult,
+    types::Finalize,
+};
+
+#[cfg(feature = "napi-4")]
+use crate::event::Channel;
+
+/// A reader-writer lock that does not block the JavaScript thread.
+///
+/// * [`read`](JsRwCell::read) and [`write`](JsRwCell::write) acquire the lock from
+///   JavaScript and throw if it is held elsewhere
+/// * [`blocking_read_off_thread`](JsRwCell::blocking_read_off_thread) and
+///   [`blocking_write_off_thread`](JsRwCell::blocking_write_off_thread) block a Rust
+///   thread until the lock is available
+/// * With the `futures` feature, [`read_async`](JsRwCell::read_async) and
+///   [`write_async`](JsRwCell::write_async) wait without blocking
+///
+/// Attempting to acquire a lock that conflicts with a guard already held by the
+/// same JavaScript thread would deadlock; this is detected and reported with a
+/// different error message than contention from another thread. A read may also
+/// fail while the JavaScript thread holds a read guard, if a writer on another thread
+/// is waiting for the lock; this is reported as contention.
+pub struct JsRwCell<T> {
+    lock: RwLock<T>,
+    // Threads holding a guard acquired with `read` or `write`, and whether the guard
+    // is exclusive
+    holders: Mutex<Vec<(ThreadId, bool)>>,
+    #[cfg(feature = "futures")]
+    waiters: Mutex<Vec<Waker>>,
+}
+
+impl<T> JsRwCell<T> {
+    /// Creates a new cell containing `value`
+    pub fn new(value: T) -> Self {
+        Self {
+            lock: RwLock::new(value),
+            holders: Mutex::new(Vec::new()),
+            #[cfg(feature = "futures")]
+            waiters: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Consumes the cell, returning the contained value
+    pub fn into_inner(self) -> T {
+        self.lock
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Acquires shared read access from the JavaScript thread.
+    ///
+    /// Throws an `Error` if the cell is locked for writing.
+    pub fn read<'a, C: Context<'a>>(&self, cx: &mut C) -> NeonResult<JsRwReadGuard<'_, T>> {
+        match self.try_read() {
+            Some(guard) => Ok(self.track(guard)),
+            None => self.throw_contended(cx, false),
+        }
+    }
+
+    /// Acquires exclusive write access from the JavaScript thread.
+    ///
+    /// Throws an `Error` if the cell is locked.
+    pub fn write<'a, C: Context<'a>>(&self, cx: &mut C) -> NeonResult<JsRwWriteGuard<'_, T>> {
+        match self.try_write() {
+            Some(guard) => Ok(self.track(guard)),
+            None => self.throw_contended(cx, true),
+        }
+    }
+
+    /// Attempts to acquire shared read access without blocking
+    pub fn try_read(&self) -> Option<JsRwReadGuard<'_, T>> {
+        let guard = match self.lock.try_read() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+
+        Some(JsRwReadGuard {
+            cell: self,
+            guard: Some(guard),
+            tracked: false,
+        })
+    }
+
+    /// Attempts to acquire exclusive write access without blocking
+    pub fn try_write(&self) -> Option<JsRwWriteGuard<'_, T>> {
+        let guard = match self.lock.try_write() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+
+        Some(JsRwWriteGuard {
+            cell: self,
+            guard: Some(guard),
+            tracked: false,
+        })
+    }
+
+    /// Blocks the current thread until shared read access is acquired.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a JavaScript thread.
+    pub fn blocking_read_off_thread(&self) -> JsRwReadGuard<'_, T> {
+        assert_off_thread("JsRwCell::blocking_read_off_thread");
+
+        JsRwReadGuard {
+            cell: self,
+            guard: Some(self.lock.read().unwrap_or_else(|err| err.into_inner())),
+            tracked: false,
+        }
+    }
+
+    /// Blocks the current thread until exclusive write access is acquired.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a JavaScript thread.
+    pub fn blocking_write_off_thread(&self) -> JsRwWriteGuard<'_, T> {
+        assert_off_thread("JsRwCell::blocking_write_off_thread");
+
+        JsRwWriteGuard {
+            cell: self,
+            guard: Some(self.lock.write().unwrap_or_else(|err| err.into_inner())),
+            tracked: false,
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    /// Waits for shared read access without blocking the current thread
+    pub fn read_async(&self) -> impl Future<Output = JsRwReadGuard<'_, T>> {
+        Acquire {
+            cell: self,
+            acquire: Self::try_read,
+        }
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    /// Waits for exclusive write access without blocking the current thread
+    pub fn write_async(&self) -> impl Future<Output = JsRwWriteGuard<'_, T>> {
+        Acquire {
+            cell: self,
+            acquire: Self::try_write,
+        }
+    }
+
+    // Record that the current JavaScript thread holds a guard
+    fn track<G: Tracked>(&self, mut guard: G) -> G {
+        self.holders
+            .lock()
+            .unwrap()
+            .push((thread::current().id(), G::EXCLUSIVE));
+        guard.set_tracked();
+        guard
+    }
+
+    fn untrack(&self, exclusive: bool) {
+        let holder = (thread::current().id(), exclusive);
+        let mut holders = self.holders.lock().unwrap();
+
+        if let Some(i) = holders.iter().position(|h| *h == holder) {
+            holders.swap_remove(i);
+        }
+    }
+
+    fn throw_contended<'a, C: Context<'a>, U>(&self, cx: &mut C, exclusive: bool) -> NeonResult<U> {
+        let id = thread::current().id();
+        let access = if exclusive { "write" } else { "read" };
+        let (held, held_exclusive) = self
+            .holders
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(holder, _)| *holder == id)
+            .fold((false, false), |(_, any), (_, exclusive)| {
+                (true, any || *exclusive)
+            });
+
+        // A write conflicts with any guard, a read only with a write guard
+        if held_exclusive || (held && exclusive) {
+            return cx.throw_error(format!(
+                "JsRwCell {} would deadlock: the lock is already held by the JavaScript thread",
+                access
+            ));
+        }
+
+        // The JavaScript thread only holds read guards, so the lock refused another
+        // reader because a writer on another thread is waiting for it
+        if held {
+            return cx.throw_error(format!(
+                "JsRwCell {} failed: a writer on another thread is waiting for the lock",
+                access
+            ));
+        }
+
+        cx.throw_error(format!(
+            "JsRwCell {} failed: the lock is held by another thread",
+            access
+        ))
+    }
+
+    // Called after a guard is released
+    fn release(&self) {
+        #[cfg(feature = "futures")]
+        {
+            let waiters = std::mem::take(&mut *self.waiters.lock().unwrap());
+
+            for waker in waiters {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T: Default> Default for JsRwCell<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for JsRwCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("JsRwCell");
+
+        match self.lock.try_read() {
+            Ok(guard) => d.field("value", &&*guard),
+            Err(_) => d.field("value", &format_args!("<locked>")),
+        };
+
+        d.finish()
+    }
+}
+
+impl<T: Finalize> Finalize for JsRwCell<T> {
+    fn finalize<'a, C: Context<'a>>(self, cx: &mut C) {
+        self.into_inner().finalize(cx);
+    }
+}
+
+/// A mutual exclusion lock for state shared between JavaScript calls and
+/// background threads, typically stored in a [`LocalKey`](crate::thread::LocalKey).
+///
+/// Each method runs a closure with exclusive access to the value, so several
+/// related fields can be updated atomically:
+///
+/// * [`with`](InstanceLock::with) never blocks the JavaScript thread for long. If the
+///   lock is held by another thread, it sleeps until the lock is released for at most
+///   the [grace period](InstanceLock::with_grace_period) before throwing a catchable
+///   `Error`
+/// * [`with_blocking`](InstanceLock::with_blocking) blocks a Rust thread until the
+///   lock is available
+/// * With the `futures` feature, [`with_async`](InstanceLock::with_async) waits
+///   without blocking
+/// * [`defer_to_js`](InstanceLock::defer_to_js) runs the closure immediately if the
+///   lock is available, and otherwise on the JavaScript thread
+///
+/// ```
+/// # use neon::prelude::*;
+/// use std::sync::Arc;
+///
+/// use neon::{sync::InstanceLock, thread::LocalKey};
+///
+/// #[derive(Default)]
+/// struct Stats {
+///     requests: u32,
+///     bytes: u64,
+/// }
+///
+/// static STATS: LocalKey<Arc<InstanceLock<Stats>>> = LocalKey::new();
+///
+/// fn record(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let bytes = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+///     let stats = STATS.get_or_init(&mut cx, Default::default).clone();
+///     let requests = stats.with(&mut cx, |stats| {
+///         stats.requests += 1;
+///         stats.bytes += bytes;
+///         stats.requests
+///     })?;
+///
+///     Ok(cx.number(requests))
+/// }
+/// ```
+/// The lock is released when the closure returns or panics. Acquiring the lock again
+/// on the thread that holds it would deadlock; `with` throws a different `Error` than
+/// for contention, and [`with_blocking`](InstanceLock::with_blocking) panics.
+pub struct InstanceLock<T> {
+    lock: Mutex<T>,
+    // The thread holding `lock`, and the number of times it was released, which
+    // `with` waits on with `released`
+    state: Mutex<LockState>,
+    released: Condvar,
+    grace: Duration,
+    #[cfg(feature = "futures")]
+    waiters: Mutex<Vec<Waker>>,
+    // Closures of `defer_to_js` waiting for the lock to be released, which send them
+    // to the JavaScript thread
+    #[cfg(feature = "napi-4")]
+    deferred: Mutex<Vec<Box<dyn FnOnce() + Send>>>,
+}
+
+impl<T> InstanceLock<T> {
+    /// Default time [`with`](InstanceLock::with) waits for another thread to release
+    /// the lock
+    pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_micros(100);
+
+    /// Creates a new lock containing `value`
+    pub fn new(value: T) -> Self {
+        Self {
+            lock: Mutex::new(value),
+            state: Mutex::new(LockState::default()),
+            released: Condvar::new(),
+            grace: Self::DEFAULT_GRACE_PERIOD,
+            #[cfg(feature = "futures")]
+            waiters: Mutex::new(Vec::new()),
+            #[cfg(feature = "napi-4")]
+            deferred: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Sets the maximum time [`with`](InstanceLock::with) waits on the JavaScript
+    /// thread before reporting contention.
+    /// _Default: [`DEFAULT_GRACE_PERIOD`](InstanceLock::DEFAULT_GRACE_PERIOD)_
+    pub fn with_grace_period(mut self, grace: Duration) -> Self {
+        self.grace = grace;
+        self
+    }
+
+    /// Consumes the lock, returning the contained value
+    pub fn into_inner(self) -> T {
+        self.lock
+            .into_inner()
+            .unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Runs `f` with exclusive access from the JavaScript thread.
+    ///
+    /// Throws an `Error` if the lock is still held by another thread after the
+    /// grace period, or if it is held by the current thread.
+    pub fn with<'a, C, F, R>(&self, cx: &mut C, f: F) -> NeonResult<R>
+    where
+        C: Context<'a>,
+        F: FnOnce(&mut T) -> R,
+    {
+        let deadline = Instant::now() + self.grace;
+        let mut guard = loop {
+            // Read before trying, so that a release after a failed attempt is not missed
+            let releases = self.state.lock().unwrap().releases;
+
+            if let Some(guard) = self.try_lock() {
+                break guard;
+            }
+
+            let state = self.state.lock().unwrap();
+
+            if state.holder == Some(thread::current().id()) {
+                return cx.throw_error(
+                    "InstanceLock would deadlock: the lock is already held by the current thread",
+                );
+            }
+
+            let now = Instant::now();
+
+            if now >= deadline {
+                return cx.throw_error("InstanceLock failed: the lock is held by another thread");
+            }
+
+            let _ = self
+                .released
+                .wait_timeout_while(state, deadline - now, |state| state.releases == releases)
+                .unwrap();
+        };
+
+        Ok(f(&mut guard))
+    }
+
+    /// Runs `f` with exclusive access, blocking the current thread until the lock
+    /// is available.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called from a JavaScript thread, or if the lock is held by the
+    /// current thread.
+    pub fn with_blocking<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        assert_off_thread("InstanceLock::with_blocking");
+
+        if self.state.lock().unwrap().holder == Some(thread::current().id()) {
+            panic!("InstanceLock::with_blocking would deadlock: the lock is already held by the current thread");
+        }
+
+        let guard = self.lock.lock().unwrap_or_else(|err| err.into_inner());
+
+        f(&mut self.hold(guard))
+    }
+
+    #[cfg(feature = "futures")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "futures")))]
+    /// Waits for exclusive access without blocking the current thread and runs `f`
+    pub async fn with_async<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        let mut guard = AcquireInstance { lock: self }.await;
+
+        f(&mut guard)
+    }
+
+    #[cfg(feature = "napi-4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+    /// Runs `f` immediately if the lock is available. Otherwise, `f` is queued until
+    /// the lock is released, then sent to the JavaScript thread with `channel` and run
+    /// there, without blocking the event loop.
+    ///
+    /// Returns `true` if `f` ran immediately. If the JavaScript thread is torn down
+    /// before the lock is released, `f` is dropped without running.
+    pub fn defer_to_js<F>(self: &Arc<Self>, channel: &Channel, f: F) -> bool
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        match self.try_lock() {
+            Some(mut guard) => {
+                f(&mut guard);
+                true
+            }
+            None => {
+                Self::defer(Arc::clone(self), channel.clone(), f);
+                false
+            }
+        }
+    }
+
+    #[cfg(feature = "napi-4")]
+    // Queues `f` to run on the JavaScript thread once the lock is released
+    fn defer<F>(lock: Arc<Self>, channel: Channel, f: F)
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut T) + Send + 'static,
+    {
+        let this = Arc::clone(&lock);
+        let send = move || {
+            let sender = channel.clone();
+
+            // Fails if the instance was torn down, dropping `f`
+            let _ = sender.try_send(move |_| {
+                match lock.try_lock() {
+                    Some(mut guard) => f(&mut guard),
+                    // Another thread acquired the lock first
+                    None => Self::defer(Arc::clone(&lock), channel, f),
+                }
+
+                Ok(())
+            });
+        };
+
+        this.deferred.lock().unwrap().push(Box::new(send));
+
+        // The lock may have been released before `f` was queued. If so, acquiring and
+        // releasing it sends `f`.
+        drop(this.try_lock());
+    }
+
+    fn try_lock(&self) -> Option<InstanceGuard<'_, T>> {
+        let guard = match self.lock.try_lock() {
+            Ok(guard) => guard,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return None,
+        };
+
+        Some(self.hold(guard))
+    }
+
+    // Records the current thread as the holder of the lock
+    fn hold<'b>(&'b self, guard: MutexGuard<'b, T>) -> InstanceGuard<'b, T> {
+        self.state.lock().unwrap().holder = Some(thread::current().id());
+
+        InstanceGuard {
+            lock: self,
+            guard: Some(guard),
+        }
+    }
+
+    // Called after the lock is released
+    fn release(&self) {
+        self.state.lock().unwrap().releases += 1;
+        self.released.notify_all();
+
+        #[cfg(feature = "futures")]
+        {
+            let waiters = std::mem::take(&mut *self.waiters.lock().unwrap());
+
+            for waker in waiters {
+                waker.wake();
+            }
+        }
+
+        #[cfg(feature = "napi-4")]
+        {
+            let deferred = std::mem::take(&mut *self.deferred.lock().unwrap());
+
+            for send in deferred {
+                send();
+            }
+        }
+    }
+}
+
+impl<T: Default> Default for InstanceLock<T> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for InstanceLock<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut d = f.debug_struct("InstanceLock");
+
+        match self.lock.try_lock() {
+            Ok(guard) => d.field("value", &&*guard),
+            Err(_) => d.field("value", &format_args!("<locked>")),
+        };
+
+        d.field("grace", &self.grace).finish()
+    }
+}
+
+#[derive(Default)]
+struct LockState {
+    holder: Option<ThreadId>,
+    releases: u64,
+}
+
+// Exclusive access to the contents of an `InstanceLock`. The lock is released when
+// the guard is dropped, including while unwinding from a panic.
+struct InstanceGuard<'a, T> {
+    lock: &'a InstanceLock<T>,
+    guard: Option<MutexGuard<'a, T>>,
+}
+
+impl<'a, T> Deref for InstanceGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> DerefMut for InstanceGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl<'a, T> Drop for InstanceGuard<'a, T> {
+    fn drop(&mut self) {
+        // Cleared before unlocking, so that it can't clear the next holder
+        self.lock.state.lock().unwrap().holder = None;
+        drop(self.guard.take());
+        self.lock.release();
+    }
+}
+
+impl<T: Finalize> Finalize for InstanceLock<T> {
+    fn finalize<'a, C: Context<'a>>(self, cx: &mut C) {
+        self.into_inner().finalize(cx);
+    }
+}
+
+pub(crate) fn assert_off_thread(method: &str) {
+    if let Ok(true) = IS_RUNNING.try_with(|v| *v.borrow()) {
+        panic!("{} must not be called on a JavaScript thread", method);
+    }
+}
+
+trait Tracked {
+    const EXCLUSIVE: bool;
+
+    fn set_tracked(&mut self);
+}
+
+/// Shared read access to the contents of a [`JsRwCell`]
+pub struct JsRwReadGuard<'a, T> {
+    cell: &'a JsRwCell<T>,
+    guard: Option<RwLockReadGuard<'a, T>>,
+    tracked: bool,
+}
+
+impl<'a, T> Tracked for JsRwReadGuard<'a, T> {
+    const EXCLUSIVE: bool = false;
+
+    fn set_tracked(&mut self) {
+        self.tracked = true;
+    }
+}
+
+impl<'a, T> Deref for JsRwReadGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> Drop for JsRwReadGuard<'a, T> {
+    fn drop(&mut self) {
+        drop(self.guard.take());
+
+        if self.tracked {
+            self.cell.untrack(false);
+        }
+
+        self.cell.release();
+    }
+}
+
+/// Exclusive write access to the contents of a [`JsRwCell`]
+pub struct JsRwWriteGuard<'a, T> {
+    cell: &'a JsRwCell<T>,
+    guard: Option<RwLockWriteGuard<'a, T>>,
+    tracked: bool,
+}
+
+impl<'a, T> Tracked for JsRwWriteGuard<'a, T> {
+    const EXCLUSIVE: bool = true;
+
+    fn set_tracked(&mut self) {
+        self.tracked = true;
+    }
+}
+
+impl<'a, T> Deref for JsRwWriteGuard<'a, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.guard.as_ref().unwrap()
+    }
+}
+
+impl<'a, T> DerefMut for JsRwWriteGuard<'a, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.guard.as_mut().unwrap()
+    }
+}
+
+impl<'a, T> Drop for JsRwWriteGuard<'a, T> {
+    fn drop(&mut self) {
+        drop(self.guard.take());
+
+        if self.tracked {
+            self.cell.untrack(true);
+        }
+
+        self.cell.release();
+    }
+}
+
+#[cfg(feature = "futures")]
+// Future that retries acquiring a lock each time a guard is released
+struct Acquire<'a, T, G> {
+    cell: &'a JsRwCell<T>,
+    acquire: fn(&'a JsRwCell<T>) -> Option<G>,
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T, G> Future for Acquire<'a, T, G> {
+    type Output = G;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskCx<'_>) -> Poll<G> {
+        if let Some(guard) = (self.acquire)(self.cell) {
+            return Poll::Ready(guard);
+        }
+
+        self.cell.waiters.lock().unwrap().push(cx.waker().clone());
+
+        // Try again in case the lock was released before the waker was registered
+        match (self.acquire)(self.cell) {
+            Some(guard) => Poll::Ready(guard),
+            None => Poll::Pending,
+        }
+    }
+}
+
+#[cfg(feature = "futures")]
+// Future that retries acquiring an `InstanceLock` each time it is released
+struct AcquireInstance<'a, T> {
+    lock: &'a InstanceLock<T>,
+}
+
+#[cfg(feature = "futures")]
+impl<'a, T> Future for AcquireInstance<'a, T> {
+    type Output = InstanceGuard<'a, T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut TaskCx<'_>) -> Poll<Self::Output> {
+        if let Some(guard) = self.lock.try_lock() {
+            return Poll::Ready(guard);
+        }
+
+        self.lock.waiters.lock().unwrap().push(cx.waker().clone());
+
+        // Try again in case the lock was released before the waker was registered
+        match self.lock.try_lock() {
+            Some(guard) => Poll::Ready(guard),
+            None => Poll::Pending,
+        }
+    }
+}
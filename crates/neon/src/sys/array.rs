@@ -15,13 +15,13 @@ pub unsafe fn new(out: &mut Local, env: Env, length: u32) {
 /// Gets the length of a `napi_value` containing a JavaScript Array.
 ///
 /// # Panics
-/// This function panics if `array` is not an Array, or if a previous n-api call caused a pending
-/// exception.
+/// This function panics if `array` is not an Array.
 pub unsafe fn len(env: Env, array: Local) -> u32 {
     let mut len = 0;
-    assert_eq!(
-        napi::get_array_length(env, array, &mut len as *mut _),
-        napi::Status::Ok
-    );
+    let status = super::error::ignoring_pending(env, || {
+        napi::get_array_length(env, array, &mut len as *mut _)
+    });
+
+    assert_eq!(status, napi::Status::Ok);
     len
 }
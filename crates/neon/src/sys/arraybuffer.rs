@@ -26,19 +26,22 @@ where
     let mut data = Box::new(data);
     let buf = data.as_mut().as_mut();
     let length = buf.len();
+    let buf = buf.as_mut_ptr() as *mut _;
+    let data = Box::into_raw(data);
     let mut result = MaybeUninit::uninit();
 
-    assert_eq!(
+    let status = super::error::ignoring_pending(env, || {
         napi::create_external_arraybuffer(
             env,
-            buf.as_mut_ptr() as *mut _,
+            buf,
             length,
             Some(drop_external::<T>),
-            Box::into_raw(data) as *mut _,
+            data as *mut _,
             result.as_mut_ptr(),
-        ),
-        napi::Status::Ok,
-    );
+        )
+    });
+
+    assert_eq!(status, napi::Status::Ok);
 
     result.assume_init()
 }
@@ -79,3 +82,30 @@ pub unsafe fn size(env: Env, buf: Local) -> usize {
 
     size
 }
+
+#[cfg(feature = "napi-7")]
+/// # Safety
+/// * Caller must ensure `env` and `buf` are valid
+pub unsafe fn is_detached(env: Env, buf: Local) -> bool {
+    let mut result = false;
+
+    assert_eq!(
+        napi::is_detached_arraybuffer(env, buf, &mut result as *mut _),
+        napi::Status::Ok,
+    );
+
+    result
+}
+
+#[cfg(feature = "napi-7")]
+/// Detaches `buf`, returning `false` if it is not detachable
+///
+/// # Safety
+/// * Caller must ensure `env` and `buf` are valid
+pub unsafe fn detach(env: Env, buf: Local) -> bool {
+    match napi::detach_arraybuffer(env, buf) {
+        napi::Status::Ok => true,
+        napi::Status::DetachableArraybufferExpected => false,
+        status => panic!("failed to detach an ArrayBuffer: {:?}", status),
+    }
+}
@@ -11,7 +11,12 @@ use std::{
     ffi::c_void,
     mem,
     panic::{catch_unwind, resume_unwind, AssertUnwindSafe},
-    ptr, thread,
+    ptr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
 };
 
 use super::{bindings as napi, no_panic::FailureBoundary, raw::Env};
@@ -24,6 +29,40 @@ const BOUNDARY: FailureBoundary = FailureBoundary {
 
 type Execute<I, O> = fn(input: I) -> O;
 type Complete<O, D> = fn(env: Env, output: thread::Result<O>, data: D);
+type CompleteCancellable<I, O, D> = fn(env: Env, output: Output<I, O>, data: D);
+
+/// Result of an async work item passed to the `complete` callback of
+/// [`schedule_cancellable`]
+pub enum Output<I, O> {
+    /// `execute` ran to completion or panicked
+    Completed(thread::Result<O>),
+    /// The work was cancelled before `execute` started; the input is returned
+    Cancelled(I),
+}
+
+/// Handle to queued work that may be used to cancel it
+///
+/// The handle may outlive the work; `finished` is set before the work is deleted.
+pub struct Work {
+    work: napi::AsyncWork,
+    finished: Arc<AtomicBool>,
+}
+
+impl Work {
+    /// Attempt to cancel the work. Returns `true` if the work was cancelled
+    /// and `false` if it had already started or completed.
+    ///
+    /// # Safety
+    /// * `env` must be a valid `napi_env` for the current thread
+    /// * `env` must be the same `napi_env` used to schedule the work
+    pub unsafe fn cancel(&self, env: Env) -> bool {
+        if self.finished.load(Ordering::Acquire) {
+            return false;
+        }
+
+        napi::cancel_async_work(env, self.work) == napi::Status::Ok
+    }
+}
 
 /// Schedule work to execute on the libuv thread pool
 ///
@@ -42,11 +81,56 @@ pub unsafe fn schedule<I, O, D>(
     O: Send + 'static,
     D: Send + 'static,
 {
+    schedule_cancellable(
+        env,
+        "neon_async_work",
+        input,
+        execute,
+        forward_complete::<I, O, D>,
+        (complete, data),
+    );
+}
+
+// Work scheduled with `schedule` is never cancelled
+fn forward_complete<I, O, D>(
+    env: Env,
+    output: Output<I, O>,
+    (complete, data): (Complete<O, D>, D),
+) {
+    if let Output::Completed(output) = output {
+        complete(env, output, data);
+    }
+}
+
+/// Schedule work to execute on the libuv thread pool with a named async
+/// resource, returning a handle that may be used to cancel the work.
+///
+/// Unlike [`schedule`], `complete` is also called if the work is cancelled.
+///
+/// # Safety
+/// * `env` must be a valid `napi_env` for the current thread
+/// * The `thread::Result::Err` must only be used for resuming unwind if
+///   `execute` is not unwind safe
+pub unsafe fn schedule_cancellable<I, O, D>(
+    env: Env,
+    name: &str,
+    input: I,
+    execute: Execute<I, O>,
+    complete: CompleteCancellable<I, O, D>,
+    data: D,
+) -> Work
+where
+    I: Send + 'static,
+    O: Send + 'static,
+    D: Send + 'static,
+{
+    let finished = Arc::new(AtomicBool::new(false));
     let mut data = Box::new(Data {
         state: State::Input(input),
         execute,
         complete,
         data,
+        finished: finished.clone(),
         // Work is initialized as a null pointer, but set by `create_async_work`
         // `data` must not be used until this value has been set.
         work: ptr::null_mut(),
@@ -60,7 +144,7 @@ pub unsafe fn schedule<I, O, D>(
         napi::create_async_work(
             env,
             ptr::null_mut(),
-            super::string(env, "neon_async_work"),
+            super::string(env, name),
             Some(call_execute::<I, O, D>),
             Some(call_complete::<I, O, D>),
             Box::into_raw(data).cast(),
@@ -78,14 +162,20 @@ pub unsafe fn schedule<I, O, D>(
             assert_eq!(status, napi::Status::Ok);
         }
     }
+
+    Work {
+        work: *work,
+        finished,
+    }
 }
 
 /// A pointer to data is passed to the `execute` and `complete` callbacks
 struct Data<I, O, D> {
     state: State<I, O>,
     execute: Execute<I, O>,
-    complete: Complete<O, D>,
+    complete: CompleteCancellable<I, O, D>,
     data: D,
+    finished: Arc<AtomicBool>,
     work: napi::AsyncWork,
 }
 
@@ -108,11 +198,12 @@ impl<I, O> State<I, O> {
         }
     }
 
-    /// Return the output if `State::Output`, replacing with `State::Executing`
-    fn into_output(self) -> Option<thread::Result<O>> {
+    /// Return the output if `State::Output` or the input if `State::Input`
+    fn into_output(self) -> Option<Output<I, O>> {
         match self {
-            Self::Output(output) => Some(output),
-            _ => None,
+            Self::Output(output) => Some(Output::Completed(output)),
+            Self::Input(input) => Some(Output::Cancelled(input)),
+            Self::Executing => None,
         }
     }
 }
@@ -146,15 +237,18 @@ unsafe extern "C" fn call_complete<I, O, D>(env: Env, status: napi::Status, data
         state,
         complete,
         data,
+        finished,
         work,
         ..
     } = *Box::<Data<I, O, D>>::from_raw(data.cast());
 
+    finished.store(true, Ordering::Release);
     napi::delete_async_work(env, work);
 
     BOUNDARY.catch_failure(env, None, move |env| {
         // `unwrap` is okay because `call_complete` should be called exactly once
-        // if and only if `call_execute` has completed successfully
+        // if and only if `call_execute` has completed successfully or the work
+        // was cancelled before it started
         let output = state.into_output().unwrap();
 
         // The event looped has stopped if we do not have an Env
@@ -162,7 +256,7 @@ unsafe extern "C" fn call_complete<I, O, D>(env: Env, status: napi::Status, data
             env
         } else {
             // Resume panicking if necessary
-            if let Err(panic) = output {
+            if let Output::Completed(Err(panic)) = output {
                 resume_unwind(panic);
             }
 
@@ -170,8 +264,7 @@ unsafe extern "C" fn call_complete<I, O, D>(env: Env, status: napi::Status, data
         };
 
         match status {
-            napi::Status::Ok => complete(env, output, data),
-            napi::Status::Cancelled => {}
+            napi::Status::Ok | napi::Status::Cancelled => complete(env, output, data),
             _ => assert_eq!(status, napi::Status::Ok),
         }
 
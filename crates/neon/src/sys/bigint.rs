@@ -0,0 +1,60 @@
+use std::mem::MaybeUninit;
+
+use super::{
+    bindings as napi,
+    raw::{Env, Local},
+};
+
+/// Create a new BigInt from an `i64`
+///
+/// # Safety
+///
+/// `env` is a raw pointer. Please ensure it points to a napi_env that is valid for the current context.
+pub unsafe fn new_i64(env: Env, value: i64) -> Local {
+    let mut local = MaybeUninit::zeroed();
+    let status = napi::create_bigint_int64(env, value, local.as_mut_ptr());
+    assert_eq!(status, napi::Status::Ok);
+    local.assume_init()
+}
+
+/// Create a new BigInt from a `u64`
+///
+/// # Safety
+///
+/// `env` is a raw pointer. Please ensure it points to a napi_env that is valid for the current context.
+pub unsafe fn new_u64(env: Env, value: u64) -> Local {
+    let mut local = MaybeUninit::zeroed();
+    let status = napi::create_bigint_uint64(env, value, local.as_mut_ptr());
+    assert_eq!(status, napi::Status::Ok);
+    local.assume_init()
+}
+
+/// Get the value of a BigInt as an `i64` and whether the conversion was lossless
+///
+/// # Safety
+///
+/// `env` is a raw pointer. Please ensure it points to a napi_env that is valid for the current context.
+/// `Local` must be a BigInt associated with the given `Env`
+pub unsafe fn to_i64(env: Env, p: Local) -> (i64, bool) {
+    let mut value = 0;
+    let mut lossless = false;
+    let status =
+        napi::get_value_bigint_int64(env, p, &mut value as *mut _, &mut lossless as *mut _);
+    assert_eq!(status, napi::Status::Ok);
+    (value, lossless)
+}
+
+/// Get the value of a BigInt as a `u64` and whether the conversion was lossless
+///
+/// # Safety
+///
+/// `env` is a raw pointer. Please ensure it points to a napi_env that is valid for the current context.
+/// `Local` must be a BigInt associated with the given `Env`
+pub unsafe fn to_u64(env: Env, p: Local) -> (u64, bool) {
+    let mut value = 0;
+    let mut lossless = false;
+    let status =
+        napi::get_value_bigint_uint64(env, p, &mut value as *mut _, &mut lossless as *mut _);
+    assert_eq!(status, napi::Status::Ok);
+    (value, lossless)
+}
@@ -52,10 +52,13 @@ mod napi1 {
 
             fn is_arraybuffer(env: Env, value: Value, result: *mut bool) -> Status;
             fn is_typedarray(env: Env, value: Value, result: *mut bool) -> Status;
+            fn is_dataview(env: Env, value: Value, result: *mut bool) -> Status;
             fn is_buffer(env: Env, value: Value, result: *mut bool) -> Status;
             fn is_error(env: Env, value: Value, result: *mut bool) -> Status;
             fn is_array(env: Env, value: Value, result: *mut bool) -> Status;
             fn is_promise(env: Env, value: Value, result: *mut bool) -> Status;
+            fn instanceof(env: Env, object: Value, constructor: Value, result: *mut bool)
+                -> Status;
 
             fn get_value_string_utf8(
                 env: Env,
@@ -65,6 +68,14 @@ mod napi1 {
                 result: *mut usize,
             ) -> Status;
 
+            fn get_value_string_utf16(
+                env: Env,
+                value: Value,
+                buf: *mut u16,
+                bufsize: usize,
+                result: *mut usize,
+            ) -> Status;
+
             fn create_type_error(env: Env, code: Value, msg: Value, result: *mut Value) -> Status;
 
             fn create_range_error(env: Env, code: Value, msg: Value, result: *mut Value) -> Status;
@@ -76,6 +87,13 @@ mod napi1 {
                 result: *mut Value,
             ) -> Status;
 
+            fn create_string_utf16(
+                env: Env,
+                str: *const u16,
+                length: usize,
+                result: *mut Value,
+            ) -> Status;
+
             fn create_arraybuffer(
                 env: Env,
                 byte_length: usize,
@@ -109,6 +127,15 @@ mod napi1 {
                 offset: *mut usize,
             ) -> Status;
 
+            fn get_dataview_info(
+                env: Env,
+                dataview: Value,
+                byte_length: *mut usize,
+                data: *mut *mut c_void,
+                arraybuffer: *mut Value,
+                byte_offset: *mut usize,
+            ) -> Status;
+
             fn create_buffer(
                 env: Env,
                 length: usize,
@@ -170,6 +197,13 @@ mod napi1 {
 
             fn get_property(env: Env, object: Value, key: Value, result: *mut Value) -> Status;
 
+            fn define_properties(
+                env: Env,
+                object: Value,
+                property_count: usize,
+                properties: *const PropertyDescriptor,
+            ) -> Status;
+
             fn set_element(env: Env, object: Value, index: u32, value: Value) -> Status;
 
             fn get_element(env: Env, object: Value, index: u32, result: *mut Value) -> Status;
@@ -230,6 +264,7 @@ mod napi1 {
 
             fn delete_async_work(env: Env, work: AsyncWork) -> Status;
             fn queue_async_work(env: Env, work: AsyncWork) -> Status;
+            fn cancel_async_work(env: Env, work: AsyncWork) -> Status;
             fn create_promise(env: Env, deferred: *mut Deferred, promise: *mut Value) -> Status;
             fn resolve_deferred(env: Env, deferred: Deferred, resolution: Value) -> Status;
             fn reject_deferred(env: Env, deferred: Deferred, rejection: Value) -> Status;
@@ -332,6 +367,40 @@ mod napi6 {
             ) -> Status;
 
             fn get_instance_data(env: Env, data: *mut *mut c_void) -> Status;
+
+            // Part of Node-API 1, only used by features that require Node-API 6
+            fn delete_property(env: Env, object: Value, key: Value, result: *mut bool) -> Status;
+
+            fn create_bigint_int64(env: Env, value: i64, result: *mut Value) -> Status;
+
+            fn create_bigint_uint64(env: Env, value: u64, result: *mut Value) -> Status;
+
+            fn get_value_bigint_int64(
+                env: Env,
+                value: Value,
+                result: *mut i64,
+                lossless: *mut bool,
+            ) -> Status;
+
+            fn get_value_bigint_uint64(
+                env: Env,
+                value: Value,
+                result: *mut u64,
+                lossless: *mut bool,
+            ) -> Status;
+        }
+    );
+}
+
+#[cfg(feature = "napi-7")]
+mod napi7 {
+    use super::super::types::*;
+
+    generate!(
+        extern "C" {
+            fn is_detached_arraybuffer(env: Env, value: Value, result: *mut bool) -> Status;
+
+            fn detach_arraybuffer(env: Env, value: Value) -> Status;
         }
     );
 }
@@ -339,6 +408,7 @@ mod napi6 {
 #[cfg(feature = "napi-8")]
 mod napi8 {
     use super::super::types::*;
+    use std::os::raw::c_void;
 
     generate!(
         extern "C" {
@@ -351,6 +421,20 @@ mod napi8 {
                 tag: *const TypeTag,
                 result: *mut bool,
             ) -> Status;
+
+            // Part of Node-API 1, only used with type tags by `Instance`
+            fn wrap(
+                env: Env,
+                js_object: Value,
+                native_object: *mut c_void,
+                finalize_cb: Finalize,
+                finalize_hint: *mut c_void,
+                result: *mut Ref,
+            ) -> Status;
+
+            fn unwrap(env: Env, js_object: Value, result: *mut *mut c_void) -> Status;
+
+            fn remove_wrap(env: Env, js_object: Value, result: *mut *mut c_void) -> Status;
         }
     );
 }
@@ -362,6 +446,8 @@ pub(crate) use napi4::*;
 pub(crate) use napi5::*;
 #[cfg(feature = "napi-6")]
 pub(crate) use napi6::*;
+#[cfg(feature = "napi-7")]
+pub(crate) use napi7::*;
 #[cfg(feature = "napi-8")]
 pub(crate) use napi8::*;
 
@@ -398,6 +484,9 @@ pub(super) unsafe fn load(env: Env) -> Result<(), libloading::Error> {
     #[cfg(feature = "napi-6")]
     napi6::load(&host, version, 6);
 
+    #[cfg(feature = "napi-7")]
+    napi7::load(&host, version, 7);
+
     #[cfg(feature = "napi-8")]
     napi8::load(&host, version, 8);
 
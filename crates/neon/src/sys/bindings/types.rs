@@ -206,6 +206,39 @@ impl std::ops::BitAndAssign for KeyFilter {
     }
 }
 
+#[repr(transparent)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct PropertyAttributes(pub ::std::os::raw::c_uint);
+
+#[allow(dead_code)]
+impl PropertyAttributes {
+    pub(crate) const DEFAULT: PropertyAttributes = PropertyAttributes(0);
+    pub(crate) const WRITABLE: PropertyAttributes = PropertyAttributes(1);
+    pub(crate) const ENUMERABLE: PropertyAttributes = PropertyAttributes(2);
+    pub(crate) const CONFIGURABLE: PropertyAttributes = PropertyAttributes(4);
+}
+
+impl std::ops::BitOr<PropertyAttributes> for PropertyAttributes {
+    type Output = Self;
+    #[inline]
+    fn bitor(self, other: Self) -> Self {
+        PropertyAttributes(self.0 | other.0)
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Copy, Clone)]
+pub(crate) struct PropertyDescriptor {
+    pub utf8name: *const ::std::os::raw::c_char,
+    pub name: Value,
+    pub method: Callback,
+    pub getter: Callback,
+    pub setter: Callback,
+    pub value: Value,
+    pub attributes: PropertyAttributes,
+    pub data: *mut c_void,
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone)]
 pub struct AsyncWork__ {
@@ -35,19 +35,22 @@ where
     let mut data = Box::new(data);
     let buf = data.as_mut().as_mut();
     let length = buf.len();
+    let buf = buf.as_mut_ptr() as *mut _;
+    let data = Box::into_raw(data);
     let mut result = MaybeUninit::uninit();
 
-    assert_eq!(
+    let status = super::error::ignoring_pending(env, || {
         napi::create_external_buffer(
             env,
             length,
-            buf.as_mut_ptr() as *mut _,
+            buf,
             Some(drop_external::<T>),
-            Box::into_raw(data) as *mut _,
+            data as *mut _,
             result.as_mut_ptr(),
-        ),
-        napi::Status::Ok,
-    );
+        )
+    });
+
+    assert_eq!(status, napi::Status::Ok);
 
     result.assume_init()
 }
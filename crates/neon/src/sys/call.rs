@@ -31,6 +31,11 @@ impl Arguments {
 }
 
 pub unsafe fn is_construct(env: Env, info: FunctionCallbackInfo) -> bool {
+    !new_target(env, info).is_null()
+}
+
+/// Gets the `new.target` of a call, or `NULL` if the function was called without `new`
+pub unsafe fn new_target(env: Env, info: FunctionCallbackInfo) -> Local {
     let mut target: MaybeUninit<Local> = MaybeUninit::zeroed();
 
     let status = napi::get_new_target(env, info, target.as_mut_ptr());
@@ -38,12 +43,9 @@ pub unsafe fn is_construct(env: Env, info: FunctionCallbackInfo) -> bool {
     assert_eq!(status, napi::Status::Ok);
 
     // get_new_target is guaranteed to assign to target, so it's initialized.
-    let target: Local = target.assume_init();
-
-    // By the get_new_target contract, target will either be NULL if the current
-    // function was called without `new`, or a valid napi_value handle if the current
-    // function was called with `new`.
-    !target.is_null()
+    // By its contract, target will either be NULL if the current function was
+    // called without `new`, or a valid napi_value handle if it was called with `new`.
+    target.assume_init()
 }
 
 pub unsafe fn this(env: Env, info: FunctionCallbackInfo, out: &mut Local) {
@@ -43,10 +43,42 @@ pub unsafe fn clear_exception(env: Env) {
     assert_eq!(status, napi::Status::Ok);
 }
 
+/// Throws `val`, unless an exception is already pending. The pending exception is kept,
+/// since JavaScript can't throw while an exception propagates.
 pub unsafe fn throw(env: Env, val: Local) {
     let status = napi::throw(env, val);
 
-    assert_eq!(status, napi::Status::Ok);
+    assert!(matches!(
+        status,
+        napi::Status::Ok | napi::Status::PendingException
+    ));
+}
+
+/// Calls `f` with the pending exception, if any, set aside. The exception is thrown again
+/// afterward, replacing any exception thrown by `f`.
+pub unsafe fn suppressed<T>(env: Env, f: impl FnOnce() -> T) -> T {
+    let mut exception = MaybeUninit::uninit();
+
+    if !catch_error(env, exception.as_mut_ptr()) {
+        return f();
+    }
+
+    let result = f();
+
+    clear_exception(env);
+    throw(env, exception.assume_init());
+
+    result
+}
+
+/// Calls `f`, a Node-API function that fails while an exception is pending but can be
+/// completed without discarding it. If it fails for that reason, it is called again with
+/// the exception set aside.
+pub unsafe fn ignoring_pending(env: Env, mut f: impl FnMut() -> napi::Status) -> napi::Status {
+    match f() {
+        napi::Status::PendingException => suppressed(env, f),
+        status => status,
+    }
 }
 
 pub unsafe fn new_error(env: Env, out: &mut Local, msg: Local) {
@@ -5,8 +5,9 @@ use super::{
     raw::{Env, Local},
 };
 
-/// `finalize_external` is invoked immediately before a `napi_external` is garbage collected
-extern "C" fn finalize_external<T: Send + 'static>(
+/// `finalize_external` is invoked immediately before a `napi_external` is garbage collected.
+/// It is also used for objects wrapped by [`super::wrap::wrap`].
+pub(super) extern "C" fn finalize_external<T: Send + 'static>(
     env: Env,
     // Raw pointer to a `Box<T>` stored by a `napi_external`
     data: *mut std::ffi::c_void,
@@ -58,21 +59,22 @@ pub unsafe fn deref<T: Send + 'static>(env: Env, local: Local) -> Option<*const
 
 /// Creates a `napi_external` from a Rust type
 pub unsafe fn create<T: Send + 'static>(env: Env, v: T, finalizer: fn(Env, T)) -> Local {
-    let v = Box::new(v);
+    let v = Box::into_raw(Box::new(v));
     let mut result = MaybeUninit::uninit();
 
-    let status = napi::create_external(
-        env,
-        Box::into_raw(v) as *mut _,
-        Some(finalize_external::<T>),
-        // Casting to `*const ()` is required to ensure the correct layout
-        // https://rust-lang.github.io/unsafe-code-guidelines/layout/function-pointers.html
-        finalizer as *const () as *mut _,
-        result.as_mut_ptr(),
-    );
-
-    // `napi_create_external` will only fail if the VM is in a throwing state
-    // or shutting down.
+    let status = super::error::ignoring_pending(env, || {
+        napi::create_external(
+            env,
+            v as *mut _,
+            Some(finalize_external::<T>),
+            // Casting to `*const ()` is required to ensure the correct layout
+            // https://rust-lang.github.io/unsafe-code-guidelines/layout/function-pointers.html
+            finalizer as *const () as *mut _,
+            result.as_mut_ptr(),
+        )
+    });
+
+    // `napi_create_external` will only fail if the VM is shutting down
     assert_eq!(status, napi::Status::Ok);
 
     let external = result.assume_init();
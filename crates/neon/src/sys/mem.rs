@@ -5,9 +5,10 @@ use super::{
 
 pub unsafe fn strict_equals(env: Env, lhs: Local, rhs: Local) -> bool {
     let mut result = false;
-    assert_eq!(
-        napi::strict_equals(env, lhs, rhs, &mut result as *mut _),
-        napi::Status::Ok
-    );
+    let status = super::error::ignoring_pending(env, || {
+        napi::strict_equals(env, lhs, rhs, &mut result as *mut _)
+    });
+
+    assert_eq!(status, napi::Status::Ok);
     result
 }
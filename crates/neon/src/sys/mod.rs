@@ -23,6 +23,9 @@ pub mod string;
 pub mod tag;
 pub mod typedarray;
 
+#[cfg(feature = "napi-8")]
+pub mod wrap;
+
 mod bindings;
 
 #[cfg(feature = "napi-4")]
@@ -34,6 +37,9 @@ pub mod date;
 #[cfg(feature = "napi-6")]
 pub mod lifecycle;
 
+#[cfg(feature = "napi-6")]
+pub mod bigint;
+
 /// Create a JavaScript `String`, panicking if unsuccessful
 ///
 /// # Safety
@@ -171,6 +171,14 @@ unsafe fn create_error(
         set_property(env, error, "panic", error_from_panic(env, panic));
     }
 
+    // Add the operation that failed to the error
+    #[cfg(feature = "operation-ids")]
+    if let Some(id) = crate::operation::failed(env) {
+        let id = create_string(env, &id.to_string());
+
+        set_property(env, error, crate::operation::PROPERTY, id);
+    }
+
     error
 }
 
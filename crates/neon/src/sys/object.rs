@@ -1,4 +1,4 @@
-use std::mem::MaybeUninit;
+use std::{mem::MaybeUninit, ptr};
 
 use super::{
     bindings as napi,
@@ -48,6 +48,66 @@ pub unsafe fn get_own_property_names(out: &mut Local, env: Env, object: Local) -
     true
 }
 
+/// Defines an own, writable, enumerable and configurable data property named `key` on
+/// `object`, like `Object.defineProperty`. Unlike `set`, setters are never called and the
+/// prototype chain is not consulted. Returns `false` if the property couldn't be defined.
+pub unsafe fn define_own(env: Env, object: Local, key: Local, val: Local) -> bool {
+    let descriptor = napi::PropertyDescriptor {
+        utf8name: ptr::null(),
+        name: key,
+        method: None,
+        getter: None,
+        setter: None,
+        value: val,
+        attributes: napi::PropertyAttributes::WRITABLE
+            | napi::PropertyAttributes::ENUMERABLE
+            | napi::PropertyAttributes::CONFIGURABLE,
+        data: ptr::null_mut(),
+    };
+
+    napi::define_properties(env, object, 1, &descriptor as *const _) == napi::Status::Ok
+}
+
+/// Property descriptors for defining the same own data properties on many objects
+pub struct DataDescriptors(Vec<napi::PropertyDescriptor>);
+
+impl DataDescriptors {
+    /// Creates writable, enumerable and configurable descriptors named by `keys`
+    pub fn new(keys: &[Local]) -> Self {
+        let descriptors = keys
+            .iter()
+            .map(|&key| napi::PropertyDescriptor {
+                utf8name: ptr::null(),
+                name: key,
+                method: None,
+                getter: None,
+                setter: None,
+                value: ptr::null_mut(),
+                attributes: napi::PropertyAttributes::WRITABLE
+                    | napi::PropertyAttributes::ENUMERABLE
+                    | napi::PropertyAttributes::CONFIGURABLE,
+                data: ptr::null_mut(),
+            })
+            .collect();
+
+        Self(descriptors)
+    }
+
+    /// Defines each property on `object` with the value at the same index of `values`
+    /// in a single call, like `Object.defineProperties`. `values` must have one value
+    /// per key. Returns `false` if the properties couldn't be defined.
+    pub unsafe fn define<I>(&mut self, env: Env, object: Local, values: I) -> bool
+    where
+        I: IntoIterator<Item = Local>,
+    {
+        for (descriptor, value) in self.0.iter_mut().zip(values) {
+            descriptor.value = value;
+        }
+
+        napi::define_properties(env, object, self.0.len(), self.0.as_ptr()) == napi::Status::Ok
+    }
+}
+
 /// Mutate the `out` argument to refer to the value at `index` in the given `object`. Returns `false` if the value couldn't be retrieved.
 pub unsafe fn get_index(out: &mut Local, env: Env, object: Local, index: u32) -> bool {
     let status = napi::get_element(env, object, index, out as *mut _);
@@ -136,6 +196,15 @@ pub unsafe fn get(out: &mut Local, env: Env, object: Local, key: Local) -> bool
     status == napi::Status::Ok
 }
 
+#[cfg(feature = "napi-6")]
+/// Deletes the property of an `napi_value` object, named by another `value` `key`. Returns `true` if the delete succeeded.
+pub unsafe fn delete(env: Env, object: Local, key: Local) -> bool {
+    let mut deleted = false;
+    let status = napi::delete_property(env, object, key, &mut deleted as *mut _);
+
+    status == napi::Status::Ok && deleted
+}
+
 /// Sets the property value of an `napi_value` object, named by another `value` `key`. Returns `true` if the set succeeded.
 ///
 /// The `out` parameter and the return value contain the same information for historical reasons,
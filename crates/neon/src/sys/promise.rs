@@ -15,10 +15,11 @@ pub unsafe fn create(env: Env) -> (napi::Deferred, napi::Value) {
     let mut deferred = MaybeUninit::uninit();
     let mut promise = MaybeUninit::uninit();
 
-    assert_eq!(
-        napi::create_promise(env, deferred.as_mut_ptr(), promise.as_mut_ptr()),
-        napi::Status::Ok,
-    );
+    let status = super::error::ignoring_pending(env, || {
+        napi::create_promise(env, deferred.as_mut_ptr(), promise.as_mut_ptr())
+    });
+
+    assert_eq!(status, napi::Status::Ok);
 
     (deferred.assume_init(), promise.assume_init())
 }
@@ -29,10 +30,10 @@ pub unsafe fn create(env: Env) -> (napi::Deferred, napi::Value) {
 /// * `env` is a valid `napi_env` for the current thread
 /// * `resolution` is a valid `napi::Value`
 pub unsafe fn resolve(env: Env, deferred: napi::Deferred, resolution: napi::Value) {
-    assert_eq!(
-        napi::resolve_deferred(env, deferred, resolution),
-        napi::Status::Ok,
-    );
+    let status =
+        super::error::ignoring_pending(env, || napi::resolve_deferred(env, deferred, resolution));
+
+    assert_eq!(status, napi::Status::Ok);
 }
 
 /// Rejects a promise from a `napi::Deferred` handle
@@ -41,25 +42,39 @@ pub unsafe fn resolve(env: Env, deferred: napi::Deferred, resolution: napi::Valu
 /// * `env` is a valid `napi_env` for the current thread
 /// * `rejection` is a valid `napi::Value`
 pub unsafe fn reject(env: Env, deferred: napi::Deferred, rejection: napi::Value) {
-    assert_eq!(
-        napi::reject_deferred(env, deferred, rejection),
-        napi::Status::Ok,
-    );
+    let status =
+        super::error::ignoring_pending(env, || napi::reject_deferred(env, deferred, rejection));
+
+    assert_eq!(status, napi::Status::Ok);
 }
 
 #[cfg(feature = "napi-6")]
 /// Rejects a promise from a `napi::Deferred` handle with a string message
 ///
+/// Used for promises that were leaked, which may be rejected while a worker is
+/// terminating. JavaScript can't run anymore, so the calls fail with a pending
+/// exception, and the promise is left pending since nothing can observe it.
+///
 /// # Safety
 /// * `env` is a valid `napi_env` for the current thread
 pub unsafe fn reject_err_message(env: Env, deferred: napi::Deferred, msg: impl AsRef<str>) {
     let msg = super::string(env, msg);
     let mut err = MaybeUninit::uninit();
 
-    assert_eq!(
-        napi::create_error(env, std::ptr::null_mut(), msg, err.as_mut_ptr()),
-        napi::Status::Ok,
-    );
+    let status = napi::create_error(env, std::ptr::null_mut(), msg, err.as_mut_ptr());
+
+    if status == napi::Status::PendingException {
+        return;
+    }
+
+    assert_eq!(status, napi::Status::Ok);
+
+    let status = super::error::ignoring_pending(env, || {
+        napi::reject_deferred(env, deferred, err.assume_init())
+    });
 
-    reject(env, deferred, err.assume_init());
+    assert!(matches!(
+        status,
+        napi::Status::Ok | napi::Status::PendingException
+    ));
 }
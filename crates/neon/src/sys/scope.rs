@@ -28,12 +28,10 @@ impl HandleScope {
 
 impl Drop for HandleScope {
     fn drop(&mut self) {
-        unsafe {
-            debug_assert_eq!(
-                napi::close_handle_scope(self.env, self.scope),
-                napi::Status::Ok,
-            );
-        }
+        // Called outside of the assertion, which is compiled out of release builds
+        let status = unsafe { napi::close_handle_scope(self.env, self.scope) };
+
+        debug_assert_eq!(status, napi::Status::Ok);
     }
 }
 
@@ -71,12 +69,10 @@ impl EscapableHandleScope {
 
 impl Drop for EscapableHandleScope {
     fn drop(&mut self) {
-        unsafe {
-            debug_assert_eq!(
-                napi::close_escapable_handle_scope(self.env, self.scope),
-                napi::Status::Ok,
-            );
-        }
+        // Called outside of the assertion, which is compiled out of release builds
+        let status = unsafe { napi::close_escapable_handle_scope(self.env, self.scope) };
+
+        debug_assert_eq!(status, napi::Status::Ok);
     }
 }
 
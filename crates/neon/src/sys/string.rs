@@ -30,6 +30,32 @@ pub unsafe fn data(env: Env, out: *mut u8, len: isize, value: Local) -> isize {
     read.assume_init() as isize
 }
 
+pub unsafe fn new_utf16(out: &mut Local, env: Env, data: *const u16, len: usize) -> bool {
+    let status = napi::create_string_utf16(env, data, len, out);
+
+    status == napi::Status::Ok
+}
+
+pub unsafe fn utf16_len(env: Env, value: Local) -> usize {
+    let mut len = MaybeUninit::uninit();
+    let status = napi::get_value_string_utf16(env, value, ptr::null_mut(), 0, len.as_mut_ptr());
+
+    assert_eq!(status, napi::Status::Ok);
+
+    len.assume_init()
+}
+
+// Copies the UTF-16 code units of `value` into `out`, followed by a nul terminator,
+// returning the number of code units copied, excluding the terminator
+pub unsafe fn data_utf16(env: Env, out: *mut u16, len: usize, value: Local) -> usize {
+    let mut read = MaybeUninit::uninit();
+    let status = napi::get_value_string_utf16(env, value, out, len, read.as_mut_ptr());
+
+    assert_eq!(status, napi::Status::Ok);
+
+    read.assume_init()
+}
+
 pub unsafe fn run_script(out: &mut Local, env: Env, value: Local) -> bool {
     let status = napi::run_script(env, value, out as *mut _);
 
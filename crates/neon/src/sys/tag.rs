@@ -13,6 +13,26 @@ unsafe fn is_type(env: Env, val: Local, expect: napi::ValueType) -> bool {
     actual == expect
 }
 
+/// The result of the JavaScript `typeof` operator on `val`
+pub unsafe fn type_of(env: Env, val: Local) -> &'static str {
+    let mut actual = napi::ValueType::Undefined;
+    assert_eq!(
+        napi::typeof_value(env, val, &mut actual as *mut _),
+        napi::Status::Ok
+    );
+
+    match actual {
+        napi::ValueType::Undefined => "undefined",
+        napi::ValueType::Boolean => "boolean",
+        napi::ValueType::Number => "number",
+        napi::ValueType::String => "string",
+        napi::ValueType::Symbol => "symbol",
+        napi::ValueType::Function => "function",
+        napi::ValueType::BigInt => "bigint",
+        napi::ValueType::Null | napi::ValueType::Object | napi::ValueType::External => "object",
+    }
+}
+
 pub unsafe fn is_undefined(env: Env, val: Local) -> bool {
     is_type(env, val, napi::ValueType::Undefined)
 }
@@ -49,6 +69,17 @@ pub unsafe fn is_array(env: Env, val: Local) -> bool {
     result
 }
 
+/// Is `val` an instance of `constructor`, like the `instanceof` operator? Returns
+/// `None` if an exception was thrown, for example, by `Symbol.hasInstance`.
+pub unsafe fn is_instance_of(env: Env, val: Local, constructor: Local) -> Option<bool> {
+    let mut result = false;
+
+    match napi::instanceof(env, val, constructor, &mut result as *mut _) {
+        napi::Status::Ok => Some(result),
+        _ => None,
+    }
+}
+
 pub unsafe fn is_function(env: Env, val: Local) -> bool {
     is_type(env, val, napi::ValueType::Function)
 }
@@ -92,6 +123,16 @@ pub unsafe fn is_typedarray(env: Env, val: Local) -> bool {
     result
 }
 
+/// Is `val` a DataView instance?
+pub unsafe fn is_dataview(env: Env, val: Local) -> bool {
+    let mut result = false;
+    assert_eq!(
+        napi::is_dataview(env, val, &mut result as *mut _),
+        napi::Status::Ok
+    );
+    result
+}
+
 #[cfg(feature = "napi-5")]
 pub unsafe fn is_date(env: Env, val: Local) -> bool {
     let mut result = false;
@@ -102,6 +143,12 @@ pub unsafe fn is_date(env: Env, val: Local) -> bool {
     result
 }
 
+/// Is `val` a JavaScript BigInt?
+#[cfg(feature = "napi-6")]
+pub unsafe fn is_bigint(env: Env, val: Local) -> bool {
+    is_type(env, val, napi::ValueType::BigInt)
+}
+
 /// Is `val` a Promise?
 ///
 /// # Safety
@@ -117,18 +164,19 @@ pub unsafe fn is_promise(env: Env, val: Local) -> bool {
 
 #[cfg(feature = "napi-8")]
 pub unsafe fn type_tag_object(env: Env, object: Local, tag: &super::TypeTag) {
-    assert_eq!(
-        napi::type_tag_object(env, object, tag as *const _),
-        napi::Status::Ok
-    );
+    let status =
+        super::error::ignoring_pending(env, || napi::type_tag_object(env, object, tag as *const _));
+
+    assert_eq!(status, napi::Status::Ok);
 }
 
 #[cfg(feature = "napi-8")]
 pub unsafe fn check_object_type_tag(env: Env, object: Local, tag: &super::TypeTag) -> bool {
     let mut result = false;
-    assert_eq!(
-        napi::check_object_type_tag(env, object, tag as *const _, &mut result as *mut _),
-        napi::Status::Ok
-    );
+    let status = super::error::ignoring_pending(env, || {
+        napi::check_object_type_tag(env, object, tag as *const _, &mut result as *mut _)
+    });
+
+    assert_eq!(status, napi::Status::Ok);
     result
 }
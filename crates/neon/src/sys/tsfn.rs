@@ -27,10 +27,26 @@ unsafe impl Sync for Tsfn {}
 /// function for scheduling tasks to execute on a JavaScript thread.
 pub struct ThreadsafeFunction<T> {
     tsfn: Tsfn,
-    is_finalized: Arc<Mutex<bool>>,
+    is_finalized: Arc<Finalized>,
     callback: fn(Option<Env>, T),
 }
 
+type FinalizeHook = Box<dyn FnOnce(Env) + Send + 'static>;
+
+// Data of the N-API finalizer
+struct Finalized {
+    is_finalized: Mutex<bool>,
+    hook: Mutex<Option<FinalizeHook>>,
+}
+
+impl std::fmt::Debug for Finalized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Finalized")
+            .field("is_finalized", &self.is_finalized)
+            .finish()
+    }
+}
+
 #[derive(Debug)]
 struct Callback<T> {
     callback: fn(Option<Env>, T),
@@ -41,21 +57,31 @@ struct Callback<T> {
 pub struct CallError;
 
 impl<T: Send + 'static> ThreadsafeFunction<T> {
-    /// Creates a new unbounded N-API Threadsafe Function
+    /// Creates a new unbounded N-API Threadsafe Function that calls `hook` on the
+    /// JavaScript thread when it is finalized, after which calls fail. This happens
+    /// after it has been dropped or when the environment is torn down, whichever is
+    /// first. Items that were queued but not yet executed are dropped without an `Env`
+    /// after `hook` returns.
     /// Safety: `Env` must be valid for the current thread
-    pub unsafe fn new(env: Env, callback: fn(Option<Env>, T)) -> Self {
-        Self::with_capacity(env, 0, callback)
+    pub unsafe fn with_finalize_hook(
+        env: Env,
+        callback: fn(Option<Env>, T),
+        hook: impl FnOnce(Env) + Send + 'static,
+    ) -> Self {
+        Self::create(env, 0, callback, Some(Box::new(hook)))
     }
 
-    /// Creates a bounded N-API Threadsafe Function
-    /// Safety: `Env` must be valid for the current thread
-    pub unsafe fn with_capacity(
+    unsafe fn create(
         env: Env,
         max_queue_size: usize,
         callback: fn(Option<Env>, T),
+        hook: Option<FinalizeHook>,
     ) -> Self {
         let mut result = MaybeUninit::uninit();
-        let is_finalized = Arc::new(Mutex::new(false));
+        let is_finalized = Arc::new(Finalized {
+            is_finalized: Mutex::new(false),
+            hook: Mutex::new(hook),
+        });
 
         assert_eq!(
             napi::create_threadsafe_function(
@@ -98,7 +124,7 @@ impl<T: Send + 'static> ThreadsafeFunction<T> {
 
         // Hold the lock before entering `call_threadsafe_function` so that
         // `finalize_cb` would never complete.
-        let mut is_finalized = self.is_finalized.lock().unwrap();
+        let mut is_finalized = self.is_finalized.is_finalized.lock().unwrap();
 
         let status = {
             if *is_finalized {
@@ -145,10 +171,16 @@ impl<T: Send + 'static> ThreadsafeFunction<T> {
 
     // Provides a C ABI wrapper for a napi callback notifying us about tsfn
     // being finalized.
-    unsafe extern "C" fn finalize(_env: Env, data: *mut c_void, _hint: *mut c_void) {
-        let is_finalized = Arc::from_raw(data as *mut Mutex<bool>);
+    unsafe extern "C" fn finalize(env: Env, data: *mut c_void, _hint: *mut c_void) {
+        let is_finalized = Arc::from_raw(data as *mut Finalized);
 
-        *is_finalized.lock().unwrap() = true;
+        *is_finalized.is_finalized.lock().unwrap() = true;
+
+        let hook = is_finalized.hook.lock().unwrap().take();
+
+        if let Some(hook) = hook {
+            hook(env);
+        }
     }
 
     // Provides a C ABI wrapper for invoking the user supplied function pointer
@@ -179,7 +211,7 @@ impl<T: Send + 'static> ThreadsafeFunction<T> {
 
 impl<T> Drop for ThreadsafeFunction<T> {
     fn drop(&mut self) {
-        let is_finalized = self.is_finalized.lock().unwrap();
+        let is_finalized = self.is_finalized.is_finalized.lock().unwrap();
 
         // tsfn was already finalized by `Environment::CleanupHandles()` in Node.js
         if *is_finalized {
@@ -15,6 +15,20 @@ pub struct TypedArrayInfo {
     pub offset: usize,
 }
 
+impl TypedArrayInfo {
+    /// The length of the typed array in bytes
+    pub fn byte_length(&self) -> usize {
+        let size = match self.typ {
+            TypedArrayType::I8 | TypedArrayType::U8 | TypedArrayType::U8Clamped => 1,
+            TypedArrayType::I16 | TypedArrayType::U16 => 2,
+            TypedArrayType::I32 | TypedArrayType::U32 | TypedArrayType::F32 => 4,
+            TypedArrayType::F64 | TypedArrayType::I64 | TypedArrayType::U64 => 8,
+        };
+
+        self.length * size
+    }
+}
+
 /// Get [information](TypedArrayInfo) describing a JavaScript `TypedArray`
 ///
 /// # Safety
@@ -58,3 +72,29 @@ pub unsafe fn new(
 
     Ok(array.assume_init())
 }
+
+/// Get the data pointer and length in bytes of a JavaScript `DataView`
+///
+/// # Safety
+/// * `env` must be valid `napi_env` for the current scope
+/// * `value` must be a handle pointing to a `DataView`
+pub unsafe fn dataview_data(env: Env, value: Local) -> (*mut c_void, usize) {
+    let mut len = 0;
+    let mut data = std::ptr::null_mut();
+    let mut buf = MaybeUninit::uninit();
+    let mut offset = 0;
+
+    assert_eq!(
+        napi::get_dataview_info(
+            env,
+            value,
+            &mut len,
+            &mut data,
+            buf.as_mut_ptr(),
+            &mut offset,
+        ),
+        napi::Status::Ok,
+    );
+
+    (data, len)
+}
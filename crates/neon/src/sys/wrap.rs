@@ -0,0 +1,105 @@
+use std::{mem::MaybeUninit, ptr};
+
+use super::{
+    bindings as napi,
+    external::finalize_external,
+    raw::{Env, Local},
+    TypeTag,
+};
+
+/// Attaches a Rust value to a JavaScript object and tags the object with `tag`.
+/// `finalizer` is called with the value immediately before the object is garbage
+/// collected. Returns a pointer to the value, or `Err` without taking ownership of
+/// `v` if the object has already been tagged or wrapped.
+///
+/// # Safety
+/// * `env` is a valid `napi_env` for the current thread
+/// * `object` is a JavaScript object
+/// * `tag` must only be used for objects wrapping a `T`
+pub unsafe fn wrap<T: Send + 'static>(
+    env: Env,
+    object: Local,
+    tag: &TypeTag,
+    v: T,
+    finalizer: fn(Env, T),
+) -> Result<*const T, T> {
+    let v = Box::into_raw(Box::new(v));
+    let status = napi::wrap(
+        env,
+        object,
+        v as *mut _,
+        Some(finalize_external::<T>),
+        // Casting to `*const ()` is required to ensure the correct layout
+        // https://rust-lang.github.io/unsafe-code-guidelines/layout/function-pointers.html
+        finalizer as *const () as *mut _,
+        ptr::null_mut(),
+    );
+
+    if status != napi::Status::Ok {
+        return Err(*Box::from_raw(v));
+    }
+
+    // Tagged after wrapping, so that an object is never tagged as wrapping a `T`
+    // without one
+    if napi::type_tag_object(env, object, tag as *const _) != napi::Status::Ok {
+        let mut result = MaybeUninit::uninit();
+
+        // Takes back ownership of the value, the finalizer is not called
+        assert_eq!(
+            napi::remove_wrap(env, object, result.as_mut_ptr()),
+            napi::Status::Ok,
+        );
+
+        return Err(*Box::from_raw(v));
+    }
+
+    Ok(v)
+}
+
+/// Returns a pointer to the value wrapped by an object tagged with `tag`
+///
+/// # Safety
+/// * `env` is a valid `napi_env` for the current thread
+/// * `tag` must only be used for objects wrapping a `T`
+pub unsafe fn unwrap<T: Send + 'static>(env: Env, local: Local, tag: &TypeTag) -> Option<*const T> {
+    let mut result = MaybeUninit::uninit();
+
+    assert_eq!(
+        napi::typeof_value(env, local, result.as_mut_ptr()),
+        napi::Status::Ok
+    );
+
+    let result = result.assume_init();
+
+    if result != napi::ValueType::Object && result != napi::ValueType::Function {
+        return None;
+    }
+
+    if !super::tag::check_object_type_tag(env, local, tag) {
+        return None;
+    }
+
+    let mut result = MaybeUninit::uninit();
+
+    if napi::unwrap(env, local, result.as_mut_ptr()) != napi::Status::Ok {
+        return None;
+    }
+
+    Some(result.assume_init() as *const _)
+}
+
+/// Returns a pointer to the value wrapped by an object that is known to wrap a `T`,
+/// without checking its tag
+///
+/// # Safety
+/// * `env` is a valid `napi_env` for the current thread
+/// * `local` was wrapped by [`wrap`] with a `T`
+pub unsafe fn wrapped<T: Send + 'static>(env: Env, local: Local) -> *const T {
+    let mut result = MaybeUninit::uninit();
+    let status =
+        super::error::ignoring_pending(env, || napi::unwrap(env, local, result.as_mut_ptr()));
+
+    assert_eq!(status, napi::Status::Ok);
+
+    result.assume_init() as *const _
+}
@@ -0,0 +1,129 @@
+//! Raw access to Node-API for calling functions that Neon does not wrap.
+//!
+//! Neon does not expose every Node-API function, for example, experimental APIs. This
+//! module provides the raw `napi_env` and `napi_value` pointers needed to call them
+//! directly, for example with the [`nodejs-sys`](https://crates.io/crates/nodejs-sys)
+//! crate or a hand-written `extern "C"` declaration.
+//!
+//! * [`Context::raw_env`](crate::context::Context::raw_env) returns the [`RawEnv`] of a context
+//! * [`Handle::raw_value`](crate::handle::Handle::raw_value) returns the [`RawValue`] of a handle
+//! * [`Handle::from_raw`](crate::handle::Handle::from_raw) converts a [`RawValue`] back into
+//!   a `Handle<JsValue>`, which can be converted to a more specific type with a checked
+//!   [`downcast`](crate::handle::Handle::downcast)
+//!
+//! [`RawEnv`] and [`RawValue`] are branded with the lifetime of the context or handle
+//! they were created from, so safe code can't store them beyond that lifetime:
+//!
+//! ```compile_fail
+//! # use neon::prelude::*;
+//! use neon::sys_interop::RawEnv;
+//!
+//! static mut ENV: Option<RawEnv<'static>> = None;
+//!
+//! fn escape(cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     unsafe { ENV = Some(cx.raw_env()) };
+//! #   unimplemented!()
+//! }
+//! ```
+//!
+//! The raw pointers returned by [`RawEnv::as_ptr`] and [`RawValue::as_ptr`] are not
+//! branded. It is the caller's responsibility to uphold the following invariants:
+//!
+//! * A `napi_env` is only used on the JavaScript thread that owns it and while the
+//!   context it was obtained from is active; it must not be used from another thread.
+//! * A `napi_value` is only used while the context that created it is active. Values
+//!   are freed when the handle scope of the context ends; use a
+//!   [`Root`](crate::handle::Root) to keep a value alive longer.
+//! * Raw calls must not leave a JavaScript exception pending, unless the Neon function
+//!   immediately returns a [`Throw`](crate::result::Throw), for example by
+//!   [`throw`](crate::context::Context::throw)ing it again.
+//! * Raw calls must not close or escape handle scopes opened by Neon.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::{ffi::c_void, ptr};
+//!
+//! use neon::sys_interop::RawValue;
+//!
+//! // Hand-written declaration of `napi_get_boolean`
+//! type GetBoolean = unsafe extern "C" fn(*mut c_void, bool, *mut *mut c_void) -> i32;
+//!
+//! fn get_true(get_boolean: GetBoolean, mut cx: FunctionContext) -> JsResult<JsBoolean> {
+//!     let env = cx.raw_env();
+//!     let mut out = ptr::null_mut();
+//!
+//!     // Safety: `env` is used on the JavaScript thread while `cx` is active
+//!     let status = unsafe { get_boolean(env.as_ptr(), true, &mut out) };
+//!
+//!     assert_eq!(status, 0);
+//!
+//!     // Safety: `out` was created in the env of `cx` and its handle scope is still open
+//!     let value = unsafe { Handle::from_raw(&mut cx, RawValue::from_ptr(out)) };
+//!
+//!     value.downcast_or_throw(&mut cx)
+//! }
+//! ```
+
+use std::{ffi::c_void, fmt, marker::PhantomData};
+
+/// A raw `napi_env`, valid for the lifetime `'cx` of the context it was obtained from
+///
+/// See the [module documentation](crate::sys_interop) for details.
+#[derive(Clone, Copy)]
+pub struct RawEnv<'cx> {
+    ptr: *mut c_void,
+    _lifetime: PhantomData<&'cx ()>,
+}
+
+impl<'cx> RawEnv<'cx> {
+    pub(crate) fn new(ptr: *mut c_void) -> Self {
+        Self {
+            ptr,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// The `napi_env` pointer
+    pub fn as_ptr(self) -> *mut c_void {
+        self.ptr
+    }
+}
+
+impl<'cx> fmt::Debug for RawEnv<'cx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RawEnv").field(&self.ptr).finish()
+    }
+}
+
+/// A raw `napi_value`, valid for the lifetime `'cx` of the handle it was obtained from
+///
+/// See the [module documentation](crate::sys_interop) for details.
+#[derive(Clone, Copy)]
+pub struct RawValue<'cx> {
+    ptr: *mut c_void,
+    _lifetime: PhantomData<&'cx ()>,
+}
+
+impl<'cx> RawValue<'cx> {
+    /// Wraps a `napi_value` pointer, for example, one returned by a raw Node-API call.
+    ///
+    /// Wrapping a pointer is safe, but converting it with
+    /// [`Handle::from_raw`](crate::handle::Handle::from_raw) is not.
+    pub fn from_ptr(ptr: *mut c_void) -> Self {
+        Self {
+            ptr,
+            _lifetime: PhantomData,
+        }
+    }
+
+    /// The `napi_value` pointer
+    pub fn as_ptr(self) -> *mut c_void {
+        self.ptr
+    }
+}
+
+impl<'cx> fmt::Debug for RawValue<'cx> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("RawValue").field(&self.ptr).finish()
+    }
+}
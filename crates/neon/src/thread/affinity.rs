@@ -0,0 +1,165 @@
+use std::{
+    fmt,
+    thread::{self, ThreadId},
+};
+
+use crate::{
+    context::Context,
+    lifecycle::{InstanceData, InstanceId},
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::JsError,
+};
+
+/// The `code` of the error thrown by [`ThreadAffinity::assert`]
+pub const WRONG_THREAD: &str = "ERR_NEON_WRONG_THREAD";
+
+/// Records the JavaScript thread that code runs on, to verify that later code runs on
+/// the same thread
+///
+/// Some JavaScript libraries, and the Rust values that wrap them, may only be used
+/// from the thread that created them. Code that is usually called on that thread, for
+/// example, from a [`Channel`](crate::event::Channel) closure, may be moved to another
+/// thread by a refactor, and the resulting failures are often far from the cause. A
+/// `ThreadAffinity` captured when the value is created turns this into an immediate
+/// error:
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::thread::ThreadAffinity;
+///
+/// struct Widget {
+///     affinity: ThreadAffinity,
+/// }
+///
+/// impl Finalize for Widget {}
+///
+/// fn create(mut cx: FunctionContext) -> JsResult<JsBox<Widget>> {
+///     let affinity = ThreadAffinity::capture(&mut cx);
+///
+///     Ok(cx.boxed(Widget { affinity }))
+/// }
+///
+/// fn update(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let widget = cx.argument::<JsBox<Widget>>(0)?;
+///
+///     widget.affinity.assert(&mut cx)?;
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+///
+/// A `ThreadAffinity` identifies both the instance of the addon and the system thread
+/// it ran on, so a [`Context`] of another instance, for example, a worker thread,
+/// fails [`assert`](ThreadAffinity::assert). It is `Send`, so it can be moved to
+/// other threads and [checked](ThreadAffinity::check) there without a `Context`.
+#[derive(Clone)]
+pub struct ThreadAffinity {
+    instance: InstanceId,
+    thread: ThreadId,
+    name: Option<String>,
+}
+
+impl ThreadAffinity {
+    /// Records the JavaScript thread of `cx`
+    pub fn capture<'cx, C: Context<'cx>>(cx: &mut C) -> Self {
+        let thread = thread::current();
+
+        Self {
+            instance: InstanceData::id(cx),
+            thread: thread.id(),
+            name: thread.name().map(String::from),
+        }
+    }
+
+    /// Returns `true` if the current thread is the thread that was captured.
+    ///
+    /// Without a [`Context`], the instance of the addon can't be compared. Use
+    /// [`assert`](ThreadAffinity::assert) when a `Context` is available.
+    pub fn check(&self) -> bool {
+        thread::current().id() == self.thread
+    }
+
+    /// Throws an `Error` if `cx` is not the JavaScript thread that was captured
+    ///
+    /// The error has a `code` of [`WRONG_THREAD`] and `expected` and `actual`
+    /// properties describing the captured and the current threads.
+    pub fn assert<'cx, C: Context<'cx>>(&self, cx: &mut C) -> NeonResult<()> {
+        if self.check() && InstanceData::id(cx) == self.instance {
+            return Ok(());
+        }
+
+        let actual = Self::capture(cx);
+        let err = wrong_thread_error(cx, self, &actual)?;
+
+        cx.throw(err)
+    }
+
+    /// Panics if the current thread is not the thread that was captured, naming both
+    /// threads
+    #[track_caller]
+    pub fn assert_thread(&self) {
+        if !self.check() {
+            let current = thread::current();
+
+            panic!(
+                "expected to run on {}, but ran on {} ({:?})",
+                self,
+                ThreadDescription(&current.name().map(String::from)),
+                current.id(),
+            );
+        }
+    }
+}
+
+impl fmt::Debug for ThreadAffinity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThreadAffinity")
+            .field("instance", &self.instance.as_u64())
+            .field("thread", &self.thread)
+            .field("name", &self.name)
+            .finish()
+    }
+}
+
+impl fmt::Display for ThreadAffinity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the JavaScript thread of instance {} ({}, {:?})",
+            self.instance.as_u64(),
+            ThreadDescription(&self.name),
+            self.thread,
+        )
+    }
+}
+
+// Names a thread in a message
+struct ThreadDescription<'a>(&'a Option<String>);
+
+impl fmt::Display for ThreadDescription<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(name) => write!(f, "thread '{}'", name),
+            None => f.write_str("unnamed thread"),
+        }
+    }
+}
+
+fn wrong_thread_error<'a, C: Context<'a>>(
+    cx: &mut C,
+    expected: &ThreadAffinity,
+    actual: &ThreadAffinity,
+) -> JsResult<'a, JsError> {
+    let message = format!("expected to run on {}, but ran on {}", expected, actual);
+    let err = JsError::error(cx, message)?;
+    let code = cx.string(WRONG_THREAD);
+    let expected = cx.string(expected.to_string());
+    let actual = cx.string(actual.to_string());
+
+    err.set(cx, "code", code)?;
+    err.set(cx, "expected", expected)?;
+    err.set(cx, "actual", actual)?;
+
+    Ok(err)
+}
@@ -103,6 +103,8 @@
 
 use std::any::Any;
 use std::marker::PhantomData;
+#[cfg(feature = "single-instance")]
+use std::sync::atomic::AtomicPtr;
 use std::sync::atomic::{AtomicUsize, Ordering};
 
 use once_cell::sync::OnceCell;
@@ -110,6 +112,10 @@ use once_cell::sync::OnceCell;
 use crate::context::Context;
 use crate::lifecycle::LocalCell;
 
+mod affinity;
+
+pub use self::affinity::{ThreadAffinity, WRONG_THREAD};
+
 static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
 fn next_id() -> usize {
@@ -126,10 +132,15 @@ fn next_id() -> usize {
 /// of `LocalKey`, and values that implement [`Drop`](std::ops::Drop) get destructed when
 /// the JavaScript thread exits, i.e. when a worker thread terminates or the main thread
 /// terminates on process exit.
+///
+/// With the `single-instance` feature, the key also holds a pointer to its value once
+/// it is initialized, so reading it skips the lookup in the table of the instance.
 #[derive(Default)]
 pub struct LocalKey<T> {
     _type: PhantomData<T>,
     id: OnceCell<usize>,
+    #[cfg(feature = "single-instance")]
+    value: AtomicPtr<T>,
 }
 
 impl<T> LocalKey<T> {
@@ -139,12 +150,27 @@ impl<T> LocalKey<T> {
         Self {
             _type: PhantomData,
             id: OnceCell::new(),
+            #[cfg(feature = "single-instance")]
+            value: AtomicPtr::new(std::ptr::null_mut()),
         }
     }
 
     fn id(&self) -> usize {
         *self.id.get_or_init(next_id)
     }
+
+    #[cfg(feature = "single-instance")]
+    fn cached<'cx>(&self) -> Option<&'cx T> {
+        // Safety: The pointer is only set to a value owned by the table of the only
+        // instance, which is not dropped until the instance is torn down at exit.
+        unsafe { self.value.load(Ordering::Acquire).as_ref() }
+    }
+
+    #[cfg(feature = "single-instance")]
+    fn cache(&self, value: &T) {
+        self.value
+            .store(value as *const T as *mut T, Ordering::Release);
+    }
 }
 
 impl<T: Any + Send + 'static> LocalKey<T> {
@@ -154,11 +180,21 @@ impl<T: Any + Send + 'static> LocalKey<T> {
     where
         C: Context<'cx>,
     {
+        #[cfg(feature = "single-instance")]
+        if let Some(value) = self.cached() {
+            return Some(value);
+        }
+
         // Unwrap safety: The type bound LocalKey<T> and the fact that every LocalKey has a unique
         // id guarantees that the cell is only ever assigned instances of type T.
         let r: Option<&T> =
             LocalCell::get(cx, self.id()).map(|value| value.downcast_ref().unwrap());
 
+        #[cfg(feature = "single-instance")]
+        if let Some(value) = r {
+            self.cache(value);
+        }
+
         // Safety: Since the Box is immutable and heap-allocated, it's guaranteed not to
         // move or change for the duration of the context.
         unsafe { std::mem::transmute::<Option<&'a T>, Option<&'cx T>>(r) }
@@ -171,12 +207,20 @@ impl<T: Any + Send + 'static> LocalKey<T> {
         C: Context<'cx>,
         F: FnOnce() -> T,
     {
+        #[cfg(feature = "single-instance")]
+        if let Some(value) = self.cached() {
+            return value;
+        }
+
         // Unwrap safety: The type bound LocalKey<T> and the fact that every LocalKey has a unique
         // id guarantees that the cell is only ever assigned instances of type T.
         let r: &T = LocalCell::get_or_init(cx, self.id(), || Box::new(f()))
             .downcast_ref()
             .unwrap();
 
+        #[cfg(feature = "single-instance")]
+        self.cache(r);
+
         // Safety: Since the Box is immutable and heap-allocated, it's guaranteed not to
         // move or change for the duration of the context.
         unsafe { std::mem::transmute::<&'a T, &'cx T>(r) }
@@ -195,12 +239,20 @@ impl<T: Any + Send + 'static> LocalKey<T> {
         C: Context<'cx>,
         F: FnOnce(&mut C) -> Result<T, E>,
     {
+        #[cfg(feature = "single-instance")]
+        if let Some(value) = self.cached() {
+            return Ok(value);
+        }
+
         // Unwrap safety: The type bound LocalKey<T> and the fact that every LocalKey has a unique
         // id guarantees that the cell is only ever assigned instances of type T.
         let r: &T = LocalCell::get_or_try_init(cx, self.id(), |cx| Ok(Box::new(f(cx)?)))?
             .downcast_ref()
             .unwrap();
 
+        #[cfg(feature = "single-instance")]
+        self.cache(r);
+
         // Safety: Since the Box is immutable and heap-allocated, it's guaranteed not to
         // move or change for the duration of the context.
         Ok(unsafe { std::mem::transmute::<&'a T, &'cx T>(r) })
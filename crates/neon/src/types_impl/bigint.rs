@@ -0,0 +1,78 @@
+use super::{private::ValueInternal, Value};
+
+use crate::{
+    context::{internal::Env, Context},
+    handle::{internal::TransparentNoCopyWrapper, Handle, Managed},
+    sys::{self, raw},
+};
+
+/// A JavaScript BigInt value
+///
+/// Only values that fit in an [`i64`] or [`u64`] can be created or read. Most
+/// code should use the [`TryFromJs`](crate::types::extract::TryFromJs) and
+/// [`TryIntoJs`](crate::types::extract::TryIntoJs) implementations of `i64` and
+/// `u64` instead, which also accept and produce numbers.
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct JsBigInt(raw::Local);
+
+impl JsBigInt {
+    /// Creates a BigInt from an `i64`
+    pub fn from_i64<'a, C: Context<'a>>(cx: &mut C, v: i64) -> Handle<'a, JsBigInt> {
+        let local = unsafe { sys::bigint::new_i64(cx.env().to_raw(), v) };
+
+        Handle::new_internal(JsBigInt(local))
+    }
+
+    /// Creates a BigInt from a `u64`
+    pub fn from_u64<'a, C: Context<'a>>(cx: &mut C, v: u64) -> Handle<'a, JsBigInt> {
+        let local = unsafe { sys::bigint::new_u64(cx.env().to_raw(), v) };
+
+        Handle::new_internal(JsBigInt(local))
+    }
+
+    /// Gets the value as an `i64`. Returns `None` if the value is out of range.
+    pub fn to_i64<'a, C: Context<'a>>(&self, cx: &mut C) -> Option<i64> {
+        let (v, lossless) = unsafe { sys::bigint::to_i64(cx.env().to_raw(), self.to_raw()) };
+
+        lossless.then_some(v)
+    }
+
+    /// Gets the value as a `u64`. Returns `None` if the value is negative or out of range.
+    pub fn to_u64<'a, C: Context<'a>>(&self, cx: &mut C) -> Option<u64> {
+        let (v, lossless) = unsafe { sys::bigint::to_u64(cx.env().to_raw(), self.to_raw()) };
+
+        lossless.then_some(v)
+    }
+}
+
+impl Value for JsBigInt {}
+
+unsafe impl TransparentNoCopyWrapper for JsBigInt {
+    type Inner = raw::Local;
+
+    fn into_inner(self) -> Self::Inner {
+        self.0
+    }
+}
+
+impl Managed for JsBigInt {
+    fn to_raw(&self) -> raw::Local {
+        self.0
+    }
+
+    fn from_raw(_: Env, h: raw::Local) -> Self {
+        JsBigInt(h)
+    }
+}
+
+impl ValueInternal for JsBigInt {
+    fn name() -> String {
+        "bigint".to_string()
+    }
+
+    fn is_typeof<Other: Value>(env: Env, other: &Other) -> bool {
+        unsafe { sys::tag::is_bigint(env.to_raw(), other.to_raw()) }
+    }
+}
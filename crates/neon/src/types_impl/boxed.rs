@@ -11,6 +11,9 @@ use crate::{
     types::{boxed::private::JsBoxInner, private::ValueInternal, Value},
 };
 
+#[cfg(feature = "memory-stats")]
+use crate::memory::Tracker;
+
 type BoxAny = Box<dyn Any + Send + 'static>;
 
 mod private {
@@ -259,9 +262,17 @@ impl<T: Finalize + Send + 'static> JsBox<T> {
             let data = *data.downcast::<U>().unwrap();
             let env = unsafe { std::mem::transmute(env) };
 
-            FinalizeContext::with(env, move |mut cx| data.finalize(&mut cx));
+            FinalizeContext::with(env, move |mut cx| {
+                #[cfg(feature = "memory-stats")]
+                Tracker::get(&mut cx).box_finalized();
+
+                data.finalize(&mut cx)
+            });
         }
 
+        #[cfg(feature = "memory-stats")]
+        Tracker::get(cx).box_created();
+
         let v = Box::new(value) as BoxAny;
         // Since this value was just constructed, we know it is `T`
         let raw_data = &*v as *const dyn Any as *const T;
@@ -19,10 +19,15 @@ use crate::{
 };
 
 pub(crate) mod lock;
+#[cfg(feature = "napi-7")]
+mod take;
 pub(super) mod types;
 
 pub use types::Binary;
 
+#[cfg(feature = "napi-7")]
+pub use take::{TakeError, Taken};
+
 /// A trait allowing Rust to borrow binary data from the memory buffer of JavaScript
 /// [typed arrays][typed-arrays].
 ///
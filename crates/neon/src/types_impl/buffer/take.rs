@@ -0,0 +1,259 @@
+use std::{
+    any::Any,
+    collections::BTreeMap,
+    error::Error,
+    fmt,
+    ops::Deref,
+    slice,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, MutexGuard,
+    },
+};
+
+use crate::{
+    context::Context,
+    result::{NeonResult, ResultExt},
+    sys::{self, raw},
+};
+
+// Allocations of the external buffers created by Neon, by address, until their
+// buffer is finalized or they are taken. A taken allocation may be used for another
+// buffer before the buffer it was taken from is finalized, so each is owned by the
+// `Key` with the same `id`.
+static EXTERNAL: Mutex<BTreeMap<usize, Entry>> = Mutex::new(BTreeMap::new());
+
+struct Entry {
+    id: u64,
+    len: usize,
+    data: Box<dyn Any + Send>,
+}
+
+fn external() -> MutexGuard<'static, BTreeMap<usize, Entry>> {
+    EXTERNAL.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// The data of an external buffer created by Neon
+pub(crate) enum External<T> {
+    /// Stored in `EXTERNAL`, so it can be taken
+    Tracked(Key),
+    /// Empty, or the address is already tracked
+    Untracked(T),
+}
+
+/// Owns an allocation in `EXTERNAL` and drops it, unless it was taken, when the
+/// buffer is finalized
+pub(crate) struct Key {
+    ptr: *mut u8,
+    len: usize,
+    id: u64,
+}
+
+// Safety: The allocation is `Send`, and is only accessed through the buffer
+unsafe impl Send for Key {}
+
+impl<T> External<T>
+where
+    T: AsMut<[u8]> + Send + 'static,
+{
+    pub(crate) fn new(data: T) -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+        // Safety: Boxing could move the data; must box before grabbing a raw pointer
+        let mut data = Box::new(data);
+        let bytes = (*data).as_mut();
+        let (ptr, len) = (bytes.as_mut_ptr(), bytes.len());
+
+        // Empty slices may share a dangling address
+        if len == 0 {
+            return External::Untracked(*data);
+        }
+
+        let mut external = external();
+
+        if external.contains_key(&(ptr as usize)) {
+            return External::Untracked(*data);
+        }
+
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+
+        external.insert(ptr as usize, Entry { id, len, data });
+
+        External::Tracked(Key { ptr, len, id })
+    }
+}
+
+impl<T: AsMut<[u8]>> AsMut<[u8]> for External<T> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        match self {
+            // Safety: The allocation lives until the key is dropped, or it is taken
+            // after detaching the buffer, which can't be accessed anymore
+            External::Tracked(key) => unsafe { slice::from_raw_parts_mut(key.ptr, key.len) },
+            External::Untracked(data) => data.as_mut(),
+        }
+    }
+}
+
+impl Drop for Key {
+    fn drop(&mut self) {
+        let entry = {
+            let mut external = external();
+
+            match external.get(&(self.ptr as usize)) {
+                Some(entry) if entry.id == self.id => external.remove(&(self.ptr as usize)),
+                _ => None,
+            }
+        };
+
+        // Dropped without holding the lock
+        drop(entry);
+    }
+}
+
+// Allocations that can be converted to a `Vec<u8>` without copying
+fn is_vec(data: &(dyn Any + Send)) -> bool {
+    data.is::<Vec<u8>>() || data.is::<Box<[u8]>>()
+}
+
+fn into_vec(data: Box<dyn Any + Send>) -> Vec<u8> {
+    match data.downcast::<Vec<u8>>() {
+        Ok(data) => *data,
+        Err(data) => match data.downcast::<Box<[u8]>>() {
+            Ok(data) => data.into_vec(),
+            Err(_) => unreachable!("checked by `is_vec`"),
+        },
+    }
+}
+
+/// Takes the bytes of the `ArrayBuffer` `buf`, detaching it
+pub(crate) fn take(env: raw::Env, buf: raw::Local) -> Result<Taken, TakeError> {
+    if unsafe { sys::arraybuffer::is_detached(env, buf) } {
+        return Err(TakeError::Detached);
+    }
+
+    let bytes = unsafe { sys::arraybuffer::as_mut_slice(env, buf) };
+    let (ptr, len) = (bytes.as_mut_ptr() as usize, bytes.len());
+
+    // Removed before detaching, so that the buffer can be finalized while it is
+    // detached without dropping the allocation
+    let entry = {
+        let mut external = external();
+
+        match external.get(&ptr) {
+            Some(entry) if entry.len == len && is_vec(&*entry.data) => external.remove(&ptr),
+            _ => None,
+        }
+    };
+
+    let entry = match entry {
+        Some(entry) => entry,
+        None => {
+            let data = bytes.to_vec();
+
+            if !unsafe { sys::arraybuffer::detach(env, buf) } {
+                return Err(TakeError::NotDetachable);
+            }
+
+            return Ok(Taken::Copied(data));
+        }
+    };
+
+    if !unsafe { sys::arraybuffer::detach(env, buf) } {
+        external().insert(ptr, entry);
+
+        return Err(TakeError::NotDetachable);
+    }
+
+    Ok(Taken::Moved(into_vec(entry.data)))
+}
+
+/// Takes the bytes of the `Buffer` `buf`, detaching its `ArrayBuffer`
+pub(crate) fn take_buffer(env: raw::Env, buf: raw::Local) -> Result<Taken, TakeError> {
+    let info = unsafe { sys::typedarray::info(env, buf) };
+    let size = unsafe { sys::arraybuffer::size(env, info.buf) };
+
+    if info.offset != 0 || info.length != size {
+        return Err(TakeError::Shared);
+    }
+
+    take(env, info.buf)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-7")))]
+/// The bytes taken from a buffer with [`JsArrayBuffer::take`](crate::types::JsArrayBuffer::take)
+/// or [`JsBuffer::take`](crate::types::JsBuffer::take)
+pub enum Taken {
+    /// The allocation of a buffer created by Neon from a `Vec<u8>` or a `Box<[u8]>`,
+    /// which was moved without copying
+    Moved(Vec<u8>),
+    /// A copy of the bytes of a buffer allocated by JavaScript, or created by Neon
+    /// from another type
+    Copied(Vec<u8>),
+}
+
+impl Taken {
+    /// Returns `true` if the bytes were copied
+    pub fn is_copied(&self) -> bool {
+        matches!(self, Taken::Copied(_))
+    }
+
+    /// Returns the bytes
+    pub fn into_vec(self) -> Vec<u8> {
+        match self {
+            Taken::Moved(data) | Taken::Copied(data) => data,
+        }
+    }
+}
+
+impl Deref for Taken {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Taken::Moved(data) | Taken::Copied(data) => data,
+        }
+    }
+}
+
+impl From<Taken> for Vec<u8> {
+    fn from(taken: Taken) -> Self {
+        taken.into_vec()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-7")))]
+/// The reasons the bytes of a buffer could not be taken
+///
+/// [`TakeError`] may be converted to a `TypeError` with [`ResultExt::or_throw`].
+pub enum TakeError {
+    /// The buffer is detached, for example, because it was already taken
+    Detached,
+    /// The buffer can't be detached, for example, because it is the memory of a
+    /// `WebAssembly.Memory`
+    NotDetachable,
+    /// The `Buffer` is a view of a part of a larger `ArrayBuffer`, for example, of
+    /// the pool Node.js allocates small buffers from, which can't be detached
+    /// without detaching the other views
+    Shared,
+}
+
+impl fmt::Display for TakeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            TakeError::Detached => "Cannot take a detached ArrayBuffer",
+            TakeError::NotDetachable => "Cannot take an ArrayBuffer that is not detachable",
+            TakeError::Shared => "Cannot take a Buffer that shares its ArrayBuffer",
+        })
+    }
+}
+
+impl Error for TakeError {}
+
+impl ResultExt<Taken> for Result<Taken, TakeError> {
+    fn or_throw<'a, C: Context<'a>>(self, cx: &mut C) -> NeonResult<Taken> {
+        self.or_else(|err| cx.throw_type_error(err.to_string()))
+    }
+}
@@ -17,6 +17,9 @@ use crate::{
     },
 };
 
+#[cfg(feature = "napi-7")]
+use crate::types_impl::buffer::take::{self, External, TakeError, Taken};
+
 #[cfg(feature = "doc-comment")]
 use doc_comment::doc_comment;
 
@@ -89,10 +92,27 @@ impl JsBuffer {
         T: AsMut<[u8]> + Send + 'static,
     {
         let env = cx.env().to_raw();
+
+        // Tracked, so that it can be taken back without copying
+        #[cfg(feature = "napi-7")]
+        let data = External::new(data);
+
         let value = unsafe { sys::buffer::new_external(env, data) };
 
         Handle::new_internal(Self(value))
     }
+
+    #[cfg(feature = "napi-7")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-7")))]
+    /// Takes the bytes of the `Buffer`, detaching its `ArrayBuffer`, like
+    /// [`JsArrayBuffer::take`].
+    ///
+    /// Returns [`TakeError::Shared`] if the `Buffer` is a view of a part of its
+    /// `ArrayBuffer`, as buffers allocated by Node.js from its pool of small buffers
+    /// are, for example, with `Buffer.from("text")`.
+    pub fn take<'cx, C: Context<'cx>>(&self, cx: &mut C) -> Result<Taken, TakeError> {
+        take::take_buffer(cx.env().to_raw(), self.to_raw())
+    }
 }
 
 unsafe impl TransparentNoCopyWrapper for JsBuffer {
@@ -245,11 +265,47 @@ impl JsArrayBuffer {
         T: AsMut<[u8]> + Send + 'static,
     {
         let env = cx.env().to_raw();
+
+        // Tracked, so that it can be taken back without copying
+        #[cfg(feature = "napi-7")]
+        let data = External::new(data);
+
         let value = unsafe { sys::arraybuffer::new_external(env, data) };
 
         Handle::new_internal(Self(value))
     }
 
+    #[cfg(feature = "napi-7")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-7")))]
+    /// Takes the bytes of the `ArrayBuffer` and detaches it, so that they can be owned
+    /// by Rust. Afterwards, the `ArrayBuffer` is empty, and so are the typed arrays
+    /// and `Buffer`s that view it.
+    ///
+    /// If the `ArrayBuffer` was created with [`external`](JsArrayBuffer::external)
+    /// from a `Vec<u8>` or a `Box<[u8]>`, the original allocation is returned as a
+    /// [`Taken::Moved`], without copying. Otherwise, the bytes are copied into a
+    /// [`Taken::Copied`] before it is detached.
+    ///
+    /// Returns [`TakeError::Detached`] if the `ArrayBuffer` is already detached, for
+    /// example, because it was taken, and [`TakeError::NotDetachable`] if it can't
+    /// be detached. Nothing is taken if an error is returned.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn ingest(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let buf = cx.argument::<JsArrayBuffer>(0)?;
+    ///     let data = buf.take(&mut cx).or_throw(&mut cx)?.into_vec();
+    ///
+    ///     // Processes `data` without blocking JavaScript
+    ///     std::thread::spawn(move || println!("received {} bytes", data.len()));
+    ///
+    ///     Ok(cx.undefined())
+    /// }
+    /// ```
+    pub fn take<'cx, C: Context<'cx>>(&self, cx: &mut C) -> Result<Taken, TakeError> {
+        take::take(cx.env().to_raw(), self.to_raw())
+    }
+
     /// Returns a region of this buffer.
     ///
     /// See also: [`Handle<JsArrayBuffer>::region()`](Handle::region) for a more
@@ -0,0 +1,225 @@
+//! Value types defined outside of Neon.
+//!
+//! Neon's own types, such as [`JsDate`](crate::types::JsDate) or
+//! [`JsPromise`](crate::types::JsPromise), can be checked with
+//! [`Handle::downcast`], extracted with [`TryFromJs`](crate::types::extract::TryFromJs)
+//! and [`FunctionContext::argument`](crate::context::FunctionContext::argument), and
+//! returned from functions. [`declare_value_type!`](crate::declare_value_type) declares
+//! a type of objects that works the same way, identified by a [`CustomValue::is_instance`]
+//! check that is usually an `instanceof` test against a constructor:
+//!
+//! ```
+//! # #[cfg(feature = "napi-6")] {
+//! # use neon::prelude::*;
+//! use neon::{thread::LocalKey, types::custom};
+//!
+//! // The `URL` class, looked up once for each instance of the addon
+//! static URL: LocalKey<Root<JsFunction>> = LocalKey::new();
+//!
+//! fn is_url<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<bool> {
+//!     let url = URL
+//!         .get_or_try_init(cx, |cx| {
+//!             let global = cx.global();
+//!
+//!             global.get::<JsFunction, _, _>(cx, "URL").map(|url| url.root(cx))
+//!         })?
+//!         .to_inner(cx);
+//!
+//!     custom::instance_of(cx, v, url)
+//! }
+//!
+//! neon::declare_value_type!(
+//!     /// An instance of the WHATWG `URL` class
+//!     pub JsUrl, is_url, "URL"
+//! );
+//!
+//! fn host(mut cx: FunctionContext) -> JsResult<JsValue> {
+//!     // Throws a `TypeError` if the argument is not a `URL`
+//!     let url = cx.argument::<JsUrl>(0)?;
+//!
+//!     url.get_value(&mut cx, "host")
+//! }
+//! # }
+//! ```
+//!
+//! ## Invariants
+//!
+//! The value type is only as accurate as its [`CustomValue::is_instance`] check, and
+//! implementations must uphold a few rules:
+//!
+//! * `is_instance` is only called with objects and functions, so that every value of the
+//!   type can be used as an [`Object`](crate::object::Object). Primitives never match.
+//! * It is called for every downcast, argument extraction and type check, and should be
+//!   cheap and free of side effects; cache constructors rather than looking them up
+//!   every time.
+//! * It should give the same answer for the same value. A handle that was downcast once
+//!   is not checked again, even if the object changes later, e.g., by
+//!   `Object.setPrototypeOf`.
+//! * An error returned by `is_instance` makes the check fail as if it returned `false`,
+//!   but the JavaScript exception remains pending and is thrown when the calling
+//!   function returns.
+//!
+//! None of these rules are needed for memory safety: a custom type only changes how a
+//! value is viewed, and every operation on it is checked by JavaScript.
+
+use crate::{
+    context::{internal::Env, Context, TaskContext},
+    handle::{Handle, Managed},
+    result::{NeonResult, Throw},
+    sys,
+    types::{JsFunction, JsValue, Value},
+};
+
+/// A JavaScript value type defined outside of Neon, declared with
+/// [`declare_value_type!`](crate::declare_value_type)
+///
+/// See the [module documentation](self) for the invariants of implementations.
+pub trait CustomValue: Value {
+    /// Tests whether `value`, which is always an object or a function, is an instance
+    /// of the type.
+    fn is_instance<'cx, C: Context<'cx>>(
+        cx: &mut C,
+        value: Handle<'cx, JsValue>,
+    ) -> NeonResult<bool>;
+}
+
+/// Tests whether `value` is an instance of `constructor`, like the JavaScript
+/// `instanceof` operator.
+///
+/// Throws a `TypeError` if `constructor` can't be used with `instanceof`.
+pub fn instance_of<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    value: Handle<'cx, JsValue>,
+    constructor: Handle<'cx, JsFunction>,
+) -> NeonResult<bool> {
+    unsafe { sys::tag::is_instance_of(cx.env().to_raw(), value.to_raw(), constructor.to_raw()) }
+        .ok_or_else(Throw::new)
+}
+
+// The type check of types declared with `declare_value_type!`
+#[doc(hidden)]
+pub fn is_typeof<T: CustomValue, Other: Value>(env: Env, other: &Other) -> bool {
+    let raw = other.to_raw();
+    let is_object = unsafe {
+        sys::tag::is_object(env.to_raw(), raw) || sys::tag::is_function(env.to_raw(), raw)
+    };
+
+    if !is_object {
+        return false;
+    }
+
+    TaskContext::with_context(env, |mut cx| {
+        cx.execute_scoped(|mut cx| {
+            let value = Handle::new_internal(JsValue::from_raw(env, raw));
+
+            T::is_instance(&mut cx, value).unwrap_or(false)
+        })
+    })
+}
+
+// The default name of a declared type in error messages: the name of the Rust type
+// without the `Js` prefix
+#[doc(hidden)]
+pub fn name(type_name: &str) -> String {
+    type_name
+        .strip_prefix("Js")
+        .filter(|name| !name.is_empty())
+        .unwrap_or(type_name)
+        .to_string()
+}
+
+/// Declares a JavaScript value type identified by a [`CustomValue::is_instance`] check.
+///
+/// `declare_value_type!(JsUrl, is_url)` declares a type `JsUrl` that implements
+/// [`Value`](crate::types::Value), [`Object`](crate::object::Object) and
+/// [`CustomValue`](crate::types::custom::CustomValue), where `is_url` is a function with
+/// the signature of [`CustomValue::is_instance`](crate::types::custom::CustomValue::is_instance).
+/// A `Handle<JsUrl>` can be downcast to, extracted as an argument or with
+/// [`TryFromJs`](crate::types::extract::TryFromJs), upcast to a `JsObject` and returned
+/// from functions, like the handles of Neon's own types.
+///
+/// The type can be preceded by attributes, such as doc comments, and a visibility. A
+/// third argument sets the name used in error messages, e.g., "expected an instance of
+/// URL"; it defaults to the name of the type without the `Js` prefix.
+///
+/// ```
+/// # use neon::prelude::*;
+/// fn is_map<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<bool> {
+///     let global = cx.global();
+///     let map = global.get::<JsFunction, _, _>(cx, "Map")?;
+///
+///     neon::types::custom::instance_of(cx, v, map)
+/// }
+///
+/// neon::declare_value_type!(pub(crate) JsMap, is_map);
+///
+/// fn size(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     cx.argument::<JsMap>(0)?.get(&mut cx, "size")
+/// }
+/// ```
+///
+/// See [`neon::types::custom`](crate::types::custom) for the invariants `is_instance`
+/// must uphold.
+#[macro_export]
+macro_rules! declare_value_type {
+    ($(#[$attr:meta])* $vis:vis $name:ident, $is_instance:path $(,)?) => {
+        $crate::declare_value_type!(
+            $(#[$attr])* $vis $name,
+            $is_instance,
+            &$crate::macro_internal::custom_value_name(stringify!($name))
+        );
+    };
+    ($(#[$attr:meta])* $vis:vis $name:ident, $is_instance:path, $js_name:expr $(,)?) => {
+        $(#[$attr])*
+        #[derive(Debug)]
+        #[repr(transparent)]
+        $vis struct $name($crate::macro_internal::RawLocal);
+
+        unsafe impl $crate::macro_internal::TransparentNoCopyWrapper for $name {
+            type Inner = $crate::macro_internal::RawLocal;
+
+            fn into_inner(self) -> Self::Inner {
+                self.0
+            }
+        }
+
+        impl $crate::handle::Managed for $name {
+            fn to_raw(&self) -> $crate::macro_internal::RawLocal {
+                self.0
+            }
+
+            fn from_raw(
+                _: $crate::macro_internal::Env,
+                h: $crate::macro_internal::RawLocal,
+            ) -> Self {
+                $name(h)
+            }
+        }
+
+        impl $crate::macro_internal::ValueInternal for $name {
+            fn name() -> ::std::string::String {
+                ::std::string::String::from($js_name)
+            }
+
+            fn is_typeof<Other: $crate::types::Value>(
+                env: $crate::macro_internal::Env,
+                other: &Other,
+            ) -> bool {
+                $crate::macro_internal::is_custom_value::<Self, Other>(env, other)
+            }
+        }
+
+        impl $crate::types::Value for $name {}
+
+        impl $crate::object::Object for $name {}
+
+        impl $crate::types::custom::CustomValue for $name {
+            fn is_instance<'cx, C: $crate::context::Context<'cx>>(
+                cx: &mut C,
+                value: $crate::handle::Handle<'cx, $crate::types::JsValue>,
+            ) -> $crate::result::NeonResult<bool> {
+                $is_instance(cx, value)
+            }
+        }
+    };
+}
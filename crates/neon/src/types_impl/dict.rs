@@ -0,0 +1,78 @@
+//! Objects built from untrusted keys.
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::{KeyPolicy, Object},
+    result::{JsResult, NeonResult},
+    types::{JsObject, JsValue, Value},
+};
+
+/// An object with a `null` prototype that can only be modified with
+/// [`Object::set_safe_with`]
+///
+/// `SafeDict` is intended for building objects from untrusted keys, such as a parsed
+/// configuration file. Properties are always defined as own data properties, so a key
+/// can't invoke a setter or modify a prototype, and keys such as `__proto__` are
+/// handled according to a [`KeyPolicy`].
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::{object::KeyPolicy, types::SafeDict};
+///
+/// fn parse_env(mut cx: FunctionContext) -> JsResult<JsObject> {
+///     let source = cx.argument::<JsString>(0)?.value(&mut cx);
+///     let dict = SafeDict::new(&mut cx)?.with_policy(KeyPolicy::Escape);
+///
+///     for line in source.lines() {
+///         if let Some((key, value)) = line.split_once('=') {
+///             let value = cx.string(value);
+///
+///             dict.set(&mut cx, key, value)?;
+///         }
+///     }
+///
+///     Ok(dict.object())
+/// }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct SafeDict<'a> {
+    object: Handle<'a, JsObject>,
+    policy: KeyPolicy,
+}
+
+impl<'a> SafeDict<'a> {
+    /// Creates an empty dictionary with the [default](KeyPolicy::default) key policy
+    pub fn new<C: Context<'a>>(cx: &mut C) -> NeonResult<Self> {
+        Ok(Self {
+            object: cx.dict()?,
+            policy: KeyPolicy::default(),
+        })
+    }
+
+    /// Sets the policy for unsafe keys
+    pub fn with_policy(self, policy: KeyPolicy) -> Self {
+        Self { policy, ..self }
+    }
+
+    /// Defines `key` as an own data property, handling unsafe keys according to
+    /// the [`KeyPolicy`]
+    pub fn set<C: Context<'a>, V: Value>(
+        &self,
+        cx: &mut C,
+        key: &str,
+        value: Handle<V>,
+    ) -> NeonResult<()> {
+        self.object.set_safe_with(cx, key, value, self.policy)
+    }
+
+    /// Gets the value of `key`, or `undefined` if it is not set
+    pub fn get_value<C: Context<'a>>(&self, cx: &mut C, key: &str) -> JsResult<'a, JsValue> {
+        self.object.get_value(cx, key)
+    }
+
+    /// Returns the underlying object
+    pub fn object(&self) -> Handle<'a, JsObject> {
+        self.object
+    }
+}
@@ -1,6 +1,11 @@
 //! Types and traits representing JavaScript error values.
 
-use std::panic::{catch_unwind, UnwindSafe};
+use std::{
+    backtrace::{Backtrace, BacktraceStatus},
+    error::Error,
+    fmt,
+    panic::{catch_unwind, UnwindSafe},
+};
 
 use crate::{
     context::{internal::Env, Context},
@@ -8,9 +13,39 @@ use crate::{
     object::Object,
     result::{NeonResult, Throw},
     sys::{self, raw},
-    types::{build, private::ValueInternal, utf8::Utf8, Value},
+    types::{build, private::ValueInternal, utf8::Utf8, JsString, Value},
 };
 
+#[cfg(feature = "operation-ids")]
+use crate::operation;
+
+#[cfg(feature = "napi-6")]
+use {
+    crate::{
+        context::FunctionContext,
+        reflect,
+        result::JsResult,
+        thread::LocalKey,
+        types::{JsFunction, JsObject, JsValue},
+    },
+    once_cell::unsync::OnceCell,
+    std::{
+        panic::Location,
+        rc::Rc,
+        sync::{
+            atomic::{AtomicBool, AtomicUsize, Ordering},
+            Arc,
+        },
+    },
+};
+
+#[cfg(feature = "napi-6")]
+// Instances of the addon with Rust traces enabled
+static RUST_TRACE_INSTANCES: AtomicUsize = AtomicUsize::new(0);
+
+#[cfg(feature = "napi-6")]
+static RUST_TRACE_ENABLED: LocalKey<AtomicBool> = LocalKey::new();
+
 /// A JS `Error` object.
 #[repr(transparent)]
 #[derive(Debug)]
@@ -55,10 +90,15 @@ impl JsError {
         msg: S,
     ) -> NeonResult<Handle<'a, JsError>> {
         let msg = cx.string(msg.as_ref());
-        build(cx.env(), |out| unsafe {
+        let err = build(cx.env(), |out| unsafe {
             sys::error::new_error(cx.env().to_raw(), out, msg.to_raw());
             true
-        })
+        });
+
+        #[cfg(feature = "operation-ids")]
+        let err = operation::tag(cx, err?);
+
+        err
     }
 
     /// Creates an instance of the [`TypeError`](https://developer.mozilla.org/docs/Web/JavaScript/Reference/Global_Objects/TypeError) class.
@@ -67,10 +107,15 @@ impl JsError {
         msg: S,
     ) -> NeonResult<Handle<'a, JsError>> {
         let msg = cx.string(msg.as_ref());
-        build(cx.env(), |out| unsafe {
+        let err = build(cx.env(), |out| unsafe {
             sys::error::new_type_error(cx.env().to_raw(), out, msg.to_raw());
             true
-        })
+        });
+
+        #[cfg(feature = "operation-ids")]
+        let err = operation::tag(cx, err?);
+
+        err
     }
 
     /// Creates an instance of the [`RangeError`](https://developer.mozilla.org/docs/Web/JavaScript/Reference/Global_Objects/RangeError) class.
@@ -79,13 +124,503 @@ impl JsError {
         msg: S,
     ) -> NeonResult<Handle<'a, JsError>> {
         let msg = cx.string(msg.as_ref());
-        build(cx.env(), |out| unsafe {
+        let err = build(cx.env(), |out| unsafe {
             sys::error::new_range_error(cx.env().to_raw(), out, msg.to_raw());
             true
+        });
+
+        #[cfg(feature = "operation-ids")]
+        let err = operation::tag(cx, err?);
+
+        err
+    }
+}
+
+/// A snapshot of a Rust [`Error`] that can be sent to the JavaScript thread and
+/// converted to a [`JsError`].
+///
+/// Unlike formatting an error to a `String`, a `SendableError` preserves the
+/// chain of [`source`](Error::source) errors, an optional error code and the
+/// Rust backtrace. [`to_js_error`](SendableError::to_js_error) creates an `Error`
+/// with a `cause` for each source, and `code` and `backtrace` properties.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::SendableError;
+///
+/// fn read_config(mut cx: FunctionContext) -> JsResult<JsPromise> {
+///     let path = cx.argument::<JsString>(0)?.value(&mut cx);
+///     let channel = cx.channel();
+///     let (deferred, promise) = cx.promise();
+///
+///     std::thread::spawn(move || match std::fs::read_to_string(path) {
+///         Ok(config) => {
+///             deferred.settle_with(&channel, move |mut cx| Ok(cx.string(config)));
+///         }
+///         Err(err) => {
+///             let err = SendableError::new(&err).with_code("ERR_READ_CONFIG");
+///
+///             deferred.reject_sendable(&channel, err);
+///         }
+///     });
+///
+///     Ok(promise)
+/// }
+/// ```
+pub struct SendableError {
+    message: String,
+    code: Option<String>,
+    backtrace: Option<String>,
+    backtrace_limit: usize,
+    source: Option<Box<SendableError>>,
+    #[cfg(feature = "napi-6")]
+    rust_trace: Option<Arc<RustTrace>>,
+}
+
+#[cfg(feature = "napi-6")]
+// The call site and unresolved backtrace of an error with a Rust trace
+struct RustTrace {
+    location: &'static Location<'static>,
+    backtrace: Backtrace,
+    // Captured because `RUST_BACKTRACE` is set, rather than for an instance with Rust
+    // traces enabled
+    from_env: bool,
+}
+
+impl SendableError {
+    /// Default maximum number of lines of a backtrace included in a JavaScript error
+    pub const DEFAULT_BACKTRACE_LIMIT: usize = 100;
+
+    /// Captures the message and source chain of `err`.
+    ///
+    /// A backtrace is captured if enabled with the `RUST_BACKTRACE` or
+    /// `RUST_LIB_BACKTRACE` environment variables, as described by
+    /// [`Backtrace::capture`].
+    pub fn new<E: Error + ?Sized>(err: &E) -> Self {
+        Self::from_error(err).with_backtrace(Backtrace::capture())
+    }
+
+    /// Creates an error from a message, without a source
+    pub fn msg<S: Into<String>>(message: S) -> Self {
+        Self {
+            message: message.into(),
+            code: None,
+            backtrace: None,
+            backtrace_limit: Self::DEFAULT_BACKTRACE_LIMIT,
+            source: None,
+            #[cfg(feature = "napi-6")]
+            rust_trace: None,
+        }
+    }
+
+    fn from_error<E: Error + ?Sized>(err: &E) -> Self {
+        let mut error = Self::msg(err.to_string());
+
+        error.source = err
+            .source()
+            .map(|source| Box::new(Self::from_error(source)));
+        error
+    }
+
+    /// Sets the `code` property of the JavaScript error
+    pub fn with_code<S: Into<String>>(mut self, code: S) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+
+    /// Replaces the captured backtrace. Backtraces that were not captured
+    /// (e.g., [`Backtrace::disabled`]) are ignored.
+    pub fn with_backtrace(mut self, backtrace: Backtrace) -> Self {
+        self.backtrace = match backtrace.status() {
+            BacktraceStatus::Captured => Some(backtrace.to_string()),
+            _ => None,
+        };
+
+        self
+    }
+
+    /// Sets the maximum number of lines of the backtrace to include in the
+    /// JavaScript error. _Default: [`DEFAULT_BACKTRACE_LIMIT`](SendableError::DEFAULT_BACKTRACE_LIMIT)_
+    pub fn with_backtrace_limit(mut self, limit: usize) -> Self {
+        self.backtrace_limit = limit;
+        self
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    #[track_caller]
+    /// Captures the Rust call site of the error for mixed-language stack traces, if
+    /// enabled with the `RUST_BACKTRACE` or `RUST_LIB_BACKTRACE` environment variables
+    /// or for the instance of the addon with
+    /// [`set_rust_trace_enabled`](SendableError::set_rust_trace_enabled).
+    ///
+    /// The JavaScript error has a non-enumerable `rustStack` property with the Rust
+    /// backtrace, and its `stack` starts with a frame for the Rust origin, for example,
+    /// `at rust: my_addon::config::read (src/config.rs:42)`. The backtrace is only
+    /// symbolized when one of these properties is first read.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// use neon::types::SendableError;
+    ///
+    /// fn parse(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ///     let err = SendableError::msg("unexpected token").rust_trace(true);
+    ///     let err = err.to_js_error(&mut cx)?;
+    ///
+    ///     cx.throw(err)
+    /// }
+    /// ```
+    pub fn rust_trace(mut self, enabled: bool) -> Self {
+        // `capture` is called directly so that it is passed the location of the caller
+        self.rust_trace = if enabled {
+            RustTrace::capture().map(Arc::new)
+        } else {
+            None
+        };
+        self
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Enables or disables [Rust traces](SendableError::rust_trace) for the instance of
+    /// the addon, even if `RUST_BACKTRACE` is not set. _Default: disabled_
+    pub fn set_rust_trace_enabled<'a, C: Context<'a>>(cx: &mut C, enabled: bool) {
+        let was_enabled = RUST_TRACE_ENABLED
+            .get_or_init_default(cx)
+            .swap(enabled, Ordering::Relaxed);
+
+        match (was_enabled, enabled) {
+            (false, true) => RUST_TRACE_INSTANCES.fetch_add(1, Ordering::Relaxed),
+            (true, false) => RUST_TRACE_INSTANCES.fetch_sub(1, Ordering::Relaxed),
+            _ => 0,
+        };
+    }
+
+    /// The error message
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// The error code, if one was set
+    pub fn code(&self) -> Option<&str> {
+        self.code.as_deref()
+    }
+
+    /// The error that caused this error, if any
+    pub fn source(&self) -> Option<&SendableError> {
+        self.source.as_deref()
+    }
+
+    /// Creates a JavaScript `Error`. Each error in the source chain is included
+    /// as the `cause` of the previous error.
+    pub fn to_js_error<'a, C: Context<'a>>(&self, cx: &mut C) -> NeonResult<Handle<'a, JsError>> {
+        let error = JsError::error(cx, &self.message)?;
+
+        if let Some(code) = &self.code {
+            let code = cx.string(code);
+
+            error.set(cx, "code", code)?;
+        }
+
+        if let Some(backtrace) = &self.backtrace {
+            let backtrace = self.truncate_backtrace(cx, backtrace);
+
+            error.set(cx, "backtrace", backtrace)?;
+        }
+
+        if let Some(source) = &self.source {
+            let cause = source.to_js_error(cx)?;
+
+            error.set(cx, "cause", cause)?;
+        }
+
+        #[cfg(feature = "napi-6")]
+        self.define_rust_trace(cx, error)?;
+
+        Ok(error)
+    }
+
+    #[cfg(feature = "napi-6")]
+    fn define_rust_trace<'a, C: Context<'a>>(
+        &self,
+        cx: &mut C,
+        error: Handle<'a, JsError>,
+    ) -> NeonResult<()> {
+        let trace = match &self.rust_trace {
+            Some(trace) if trace.from_env || rust_trace_enabled(cx) => Arc::clone(trace),
+            _ => return Ok(()),
+        };
+
+        let lazy = Rc::new(LazyTrace {
+            trace,
+            limit: self.backtrace_limit,
+            resolved: OnceCell::new(),
+        });
+
+        // Read before `stack` is replaced
+        let stack = error
+            .get_value(cx, "stack")?
+            .downcast::<JsString, _>(cx)
+            .ok()
+            .map(|stack| stack.value(cx));
+
+        define_lazy(cx, error, "rustStack", {
+            let lazy = Rc::clone(&lazy);
+
+            move |cx| Ok(cx.string(&lazy.resolve().1))
+        })?;
+
+        if let Some(stack) = stack {
+            define_lazy(cx, error, "stack", move |cx| {
+                let frame = format!("\n    {}", lazy.resolve().0);
+                let stack = match stack.find("\n    at ") {
+                    Some(i) => format!("{}{}{}", &stack[..i], frame, &stack[i..]),
+                    None => format!("{}{}", stack, frame),
+                };
+
+                Ok(cx.string(stack))
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn truncate_backtrace<'a, C: Context<'a>>(
+        &self,
+        cx: &mut C,
+        backtrace: &str,
+    ) -> Handle<'a, JsString> {
+        cx.string(truncate(backtrace, self.backtrace_limit))
+    }
+}
+
+// Keeps the first `limit` lines of a backtrace
+fn truncate(backtrace: &str, limit: usize) -> String {
+    let lines = backtrace.lines().count();
+
+    if lines <= limit {
+        return backtrace.to_string();
+    }
+
+    let mut truncated = backtrace.lines().take(limit).collect::<Vec<_>>().join("\n");
+
+    truncated.push_str(&format!("\n... {} more lines", lines - limit));
+    truncated
+}
+
+// Resolves the Rust trace of an error at most once, when `rustStack` or `stack` is
+// first read
+#[cfg(feature = "napi-6")]
+struct LazyTrace {
+    trace: Arc<RustTrace>,
+    limit: usize,
+    // The Rust origin frame and the truncated Rust stack
+    resolved: OnceCell<(String, String)>,
+}
+
+#[cfg(feature = "napi-6")]
+impl LazyTrace {
+    fn resolve(&self) -> &(String, String) {
+        self.resolved.get_or_init(|| {
+            let (origin, stack) = self.trace.resolve();
+
+            (origin, truncate(&stack, self.limit))
         })
     }
 }
 
+// Defines a non-enumerable accessor property `key` on `error` that replaces itself
+// with a data property holding the value of `get` when it is first read, or the
+// value it is first assigned
+#[cfg(feature = "napi-6")]
+fn define_lazy<'a, C, F>(
+    cx: &mut C,
+    error: Handle<'a, JsError>,
+    key: &'static str,
+    get: F,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    F: for<'b> Fn(&mut FunctionContext<'b>) -> JsResult<'b, JsString> + 'static,
+{
+    let getter = JsFunction::new(cx, move |mut cx| {
+        let this = cx.this::<JsObject>()?;
+        let value = get(&mut cx)?;
+
+        define_property(&mut cx, this, key, |cx, descriptor| {
+            let writable = cx.boolean(true);
+
+            descriptor.set(cx, "value", value)?;
+            descriptor.set(cx, "writable", writable)?;
+
+            Ok(())
+        })?;
+
+        Ok(value)
+    })?;
+
+    let setter = JsFunction::new(cx, move |mut cx| {
+        let this = cx.this::<JsObject>()?;
+        let value = cx.argument::<JsValue>(0)?;
+
+        define_property(&mut cx, this, key, |cx, descriptor| {
+            let writable = cx.boolean(true);
+
+            descriptor.set(cx, "value", value)?;
+            descriptor.set(cx, "writable", writable)?;
+
+            Ok(())
+        })?;
+
+        Ok(cx.undefined())
+    })?;
+
+    define_property(cx, error, key, |cx, descriptor| {
+        descriptor.set(cx, "get", getter)?;
+        descriptor.set(cx, "set", setter)?;
+
+        Ok(())
+    })
+}
+
+// Defines a configurable, non-enumerable property `key` on `object`. `init` sets the
+// remaining fields of the descriptor.
+#[cfg(feature = "napi-6")]
+fn define_property<'a, C, O, F>(
+    cx: &mut C,
+    object: Handle<'a, O>,
+    key: &str,
+    init: F,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    O: Object,
+    F: FnOnce(&mut C, Handle<'a, JsObject>) -> NeonResult<()>,
+{
+    let descriptor = cx.empty_object();
+    let configurable = cx.boolean(true);
+    let enumerable = cx.boolean(false);
+    let key = cx.string(key);
+
+    descriptor.set(cx, "configurable", configurable)?;
+    descriptor.set(cx, "enumerable", enumerable)?;
+    init(cx, descriptor)?;
+
+    reflect::define_property(cx, object, key, descriptor)
+}
+
+#[cfg(feature = "napi-6")]
+fn rust_trace_enabled<'a, C: Context<'a>>(cx: &mut C) -> bool {
+    RUST_TRACE_ENABLED
+        .get(cx)
+        .map(|enabled| enabled.load(Ordering::Relaxed))
+        .unwrap_or(false)
+}
+
+#[cfg(feature = "napi-6")]
+impl RustTrace {
+    #[track_caller]
+    fn capture() -> Option<Self> {
+        let location = Location::caller();
+        let backtrace = Backtrace::capture();
+        let from_env = backtrace.status() == BacktraceStatus::Captured;
+
+        // Capturing without resolving symbols is relatively cheap, but is skipped
+        // entirely unless a trace may be included in an error
+        let backtrace = if from_env {
+            backtrace
+        } else if RUST_TRACE_INSTANCES.load(Ordering::Relaxed) > 0 {
+            Backtrace::force_capture()
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            location,
+            backtrace,
+            from_env,
+        })
+    }
+
+    // Symbolizes the backtrace, returning the origin frame and the backtrace
+    fn resolve(&self) -> (String, String) {
+        let stack = self.backtrace.to_string();
+        let file = self.location.file();
+        let line = self.location.line();
+
+        // Frames are formatted as the function on one line, followed by its location
+        // on the next line, e.g., `at ./src/lib.rs:12:5`
+        let function = stack
+            .lines()
+            .zip(stack.lines().skip(1))
+            .find(|(_, at)| {
+                let at = at.trim_start();
+                let mut parts = at.trim_start_matches("at ").rsplitn(3, ':');
+                let _column = parts.next();
+
+                at.starts_with("at ")
+                    && parts.next() == Some(&line.to_string())
+                    && parts.next().is_some_and(|path| same_file(path, file))
+            })
+            .and_then(|(function, _)| function.split_once(": "))
+            .map(|(_, function)| strip_hash(function.trim()));
+
+        let origin = match function {
+            Some(function) => format!("at rust: {} ({}:{})", function, file, line),
+            None => format!("at rust: {}:{}", file, line),
+        };
+
+        (origin, stack)
+    }
+}
+
+#[cfg(feature = "napi-6")]
+// Compares a path in a backtrace with the path of a `Location`. Either may be relative
+// to a different directory, e.g., the package or the workspace.
+fn same_file(path: &str, file: &str) -> bool {
+    let path = path.trim_start_matches("./");
+    let file = file.trim_start_matches("./");
+
+    path.ends_with(file) || file.ends_with(path)
+}
+
+#[cfg(feature = "napi-6")]
+// Removes the hash suffix of a symbol, e.g., `::h0123456789abcdef`
+fn strip_hash(function: &str) -> &str {
+    match function.rsplit_once("::h") {
+        Some((name, hash)) if hash.len() == 16 && hash.chars().all(|c| c.is_ascii_hexdigit()) => {
+            name
+        }
+        _ => function,
+    }
+}
+
+// Errors are converted explicitly with `SendableError::new`, since a blanket `From`
+// implementation for every `Error` would conflict with implementing `Error`
+impl Error for SendableError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|source| source as &(dyn Error + 'static))
+    }
+}
+
+impl fmt::Debug for SendableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SendableError")
+            .field("message", &self.message)
+            .field("code", &self.code)
+            .field("source", &self.source)
+            .finish()
+    }
+}
+
+impl fmt::Display for SendableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
 pub(crate) fn convert_panics<T, F: UnwindSafe + FnOnce() -> NeonResult<T>>(
     env: Env,
     f: F,
@@ -104,8 +639,47 @@ pub(crate) fn convert_panics<T, F: UnwindSafe + FnOnce() -> NeonResult<T>>(
             unsafe {
                 sys::error::clear_exception(env.to_raw());
                 sys::error::throw_error_from_utf8(env.to_raw(), data, len);
+
+                #[cfg(feature = "operation-ids")]
+                tag_exception(env);
+
                 Err(Throw::new())
             }
         }
     }
 }
+
+#[cfg(feature = "operation-ids")]
+// Sets the `operationId` of the pending exception to the operation that panicked
+unsafe fn tag_exception(env: Env) {
+    let id = match operation::failed(env.to_raw()) {
+        Some(id) => id,
+        None => return,
+    };
+
+    let mut err = std::mem::MaybeUninit::uninit();
+
+    if !sys::error::catch_error(env.to_raw(), err.as_mut_ptr()) {
+        return;
+    }
+
+    let err = err.assume_init();
+    let id = id.to_string();
+    let key = operation::PROPERTY;
+    let mut value = std::ptr::null_mut();
+    let mut set = false;
+
+    // The error is thrown without the id if it can't be set
+    if sys::string::new(&mut value, env.to_raw(), id.as_ptr(), id.len() as i32) {
+        sys::object::set_string(
+            env.to_raw(),
+            &mut set,
+            err,
+            key.as_ptr(),
+            key.len() as i32,
+            value,
+        );
+    }
+
+    sys::error::throw(env.to_raw(), err);
+}
@@ -0,0 +1,138 @@
+use crate::{
+    context::{Context, FunctionContext},
+    handle::Handle,
+    result::NeonResult,
+    types::{JsUndefined, JsValue},
+};
+
+use super::{ArrayOnly, TryFromJs};
+
+/// An argument that may be missing, `undefined`, or a value
+///
+/// `Option<T>` extracts a missing argument and `undefined` as `None`. `Provided`
+/// tells them apart, for APIs where omitting an argument means something else than
+/// passing `undefined`, for example, keeping a default instead of clearing a value.
+///
+/// An argument is [`Missing`](Provided::Missing) if its index is greater than or
+/// equal to the number of arguments passed, [`cx.len()`](FunctionContext::len).
+/// JavaScript calls can't skip an argument, so only trailing arguments can be
+/// missing: an argument before one that was passed is always `Undefined` or a
+/// `Value`. Extracted from a value, for example, an element of an array, `Provided`
+/// is never `Missing`.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::Provided;
+///
+/// // `setLabel(id)` keeps the label, `setLabel(id, undefined)` clears it
+/// fn set_label(mut cx: FunctionContext) -> JsResult<JsValue> {
+///     let label = match cx.extract_argument::<Provided<String>>(1)? {
+///         Some(Provided::Missing) => "kept".to_string(),
+///         Some(Provided::Undefined) => "cleared".to_string(),
+///         Some(Provided::Value(label)) => label,
+///         None => return cx.throw_type_error("label must be a string"),
+///     };
+///
+///     Ok(cx.string(label).upcast())
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Provided<T> {
+    /// The argument was not passed
+    Missing,
+    /// The argument was `undefined`
+    Undefined,
+    /// The argument was passed and extracted as `T`
+    Value(T),
+}
+
+impl<T> Provided<T> {
+    /// Returns `true` if the argument was passed, even if it was `undefined`
+    pub fn is_provided(&self) -> bool {
+        !matches!(self, Provided::Missing)
+    }
+
+    /// Converts to an `Option`, extracting `Missing` and `Undefined` as `None` like
+    /// `Option<T>`
+    pub fn value(self) -> Option<T> {
+        match self {
+            Provided::Value(v) => Some(v),
+            Provided::Missing | Provided::Undefined => None,
+        }
+    }
+}
+
+/// `undefined` is extracted as `Undefined`, and any other value as `T`
+impl<'cx, T> TryFromJs<'cx> for Provided<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if v.is_a::<JsUndefined, _>(cx) {
+            return Ok(Some(Provided::Undefined));
+        }
+
+        Ok(T::try_from_js(cx, v)?.map(Provided::Value))
+    }
+
+    fn try_from_argument(cx: &mut FunctionContext<'cx>, i: usize) -> NeonResult<Option<Self>> {
+        match cx.argument_opt(i) {
+            Some(v) => Self::try_from_js(cx, v),
+            None => Ok(Some(Provided::Missing)),
+        }
+    }
+}
+
+/// The remaining arguments of a call, like a JavaScript rest parameter
+/// (`...args`)
+///
+/// Extracted from the `i`th argument, `Rest` holds the arguments from `i` up to
+/// [`cx.len()`](FunctionContext::len), which is empty if fewer arguments were
+/// passed. Each is extracted as `T`, so `Rest<Provided<T>>` holds `Undefined` for an
+/// `undefined` argument but is never `Missing`. Extracted from a value, for example,
+/// an element of an array, `Rest` only extracts an array, like
+/// [`ArrayOnly`].
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::Rest;
+///
+/// // `sum(...numbers)`
+/// fn sum(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     match cx.extract_argument::<Rest<f64>>(0)? {
+///         Some(Rest(numbers)) => Ok(cx.number(numbers.iter().sum::<f64>())),
+///         None => cx.throw_type_error("expected numbers"),
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rest<T>(pub Vec<T>);
+
+impl<'cx, T> TryFromJs<'cx> for Rest<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(ArrayOnly::try_from_js(cx, v)?.map(|ArrayOnly(v)| Rest(v)))
+    }
+
+    fn try_from_argument(cx: &mut FunctionContext<'cx>, i: usize) -> NeonResult<Option<Self>> {
+        let len = cx.len();
+        let mut values = Vec::with_capacity(len.saturating_sub(i));
+
+        for i in i..len {
+            match T::try_from_argument(cx, i)? {
+                Some(v) => values.push(v),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(Rest(values)))
+    }
+}
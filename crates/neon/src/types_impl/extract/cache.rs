@@ -0,0 +1,285 @@
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed, Root},
+    limits::{self, Resource},
+    object::Object,
+    result::NeonResult,
+    sys,
+    thread::LocalKey,
+    types::{extract::TryFromJs, JsNumber, JsObject, JsString, JsValue},
+};
+
+static SHARED: LocalKey<StringCache> = LocalKey::new();
+
+// End of the list of entries
+const NIL: usize = usize::MAX;
+
+/// A cache of the Rust conversions of JavaScript strings that are converted
+/// repeatedly, such as header names or enum-like values.
+///
+/// Converting a JavaScript string to Rust copies and re-encodes it as UTF-8 each
+/// time. [`to_rust`](StringCache::to_rust) looks the string up by its contents in a
+/// JavaScript object, which reuses the hash the engine stores with the string, and
+/// returns the shared conversion if there is one.
+///
+/// The cache holds at most `max_entries` strings and evicts the least recently used
+/// string when it is full. Strings longer than [`MAX_LEN`](StringCache::MAX_LEN) bytes
+/// bypass the cache, since they are unlikely to repeat and expensive to hash.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::StringCache;
+///
+/// fn header(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let name = cx.argument::<JsString>(0)?;
+///     let name = StringCache::shared(&mut cx)?.to_rust(&mut cx, name);
+///
+///     assert!(!name.is_empty());
+///     Ok(cx.undefined())
+/// }
+/// ```
+///
+/// JavaScript strings are primitives and cannot be held weakly, so the cache keeps
+/// the strings it holds alive until they are evicted or the cache is
+/// [cleared](StringCache::clear).
+pub struct StringCache {
+    // Object with a `null` prototype mapping each cached string to its entry
+    index: Root<JsObject>,
+    state: Mutex<State>,
+}
+
+/// Counters of a [`StringCache`], returned by [`StringCache::stats`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StringCacheStats {
+    /// Conversions returned from the cache
+    pub hits: u64,
+    /// Conversions of strings that were not cached
+    pub misses: u64,
+    /// Conversions of strings that were too long to be cached
+    pub bypassed: u64,
+    /// Strings removed to make room for another string
+    pub evictions: u64,
+    /// Strings in the cache
+    pub entries: usize,
+}
+
+struct State {
+    max_entries: usize,
+    // Entries linked from the most to the least recently used
+    entries: Vec<Entry>,
+    head: usize,
+    tail: usize,
+    stats: StringCacheStats,
+}
+
+struct Entry {
+    value: Arc<str>,
+    prev: usize,
+    next: usize,
+}
+
+impl State {
+    fn unlink(&mut self, i: usize) {
+        let Entry { prev, next, .. } = self.entries[i];
+
+        match prev {
+            NIL => self.head = next,
+            prev => self.entries[prev].next = next,
+        }
+
+        match next {
+            NIL => self.tail = prev,
+            next => self.entries[next].prev = prev,
+        }
+    }
+
+    fn push_front(&mut self, i: usize) {
+        self.entries[i].prev = NIL;
+        self.entries[i].next = self.head;
+
+        match self.head {
+            NIL => self.tail = i,
+            head => self.entries[head].prev = i,
+        }
+
+        self.head = i;
+    }
+}
+
+impl StringCache {
+    /// Strings longer than this many bytes of UTF-8 are not cached
+    pub const MAX_LEN: usize = 256;
+
+    /// Number of entries of the [shared](StringCache::shared) cache
+    pub const DEFAULT_MAX_ENTRIES: usize = 1024;
+
+    /// Creates a cache holding at most `max_entries` strings
+    pub fn new<'a, C: Context<'a>>(cx: &mut C, max_entries: usize) -> NeonResult<Self> {
+        let index = cx.dict()?.root(cx);
+
+        Ok(Self {
+            index,
+            state: Mutex::new(State {
+                max_entries,
+                entries: Vec::new(),
+                head: NIL,
+                tail: NIL,
+                stats: Default::default(),
+            }),
+        })
+    }
+
+    /// The cache of the instance of the addon, holding
+    /// [`DEFAULT_MAX_ENTRIES`](StringCache::DEFAULT_MAX_ENTRIES) strings. It is used by
+    /// [`CachedString`].
+    pub fn shared<'a, C: Context<'a>>(cx: &mut C) -> NeonResult<&'a Self> {
+        SHARED.get_or_try_init(cx, |cx| Self::new(cx, Self::DEFAULT_MAX_ENTRIES))
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Converts `s` to a Rust string, returning the cached conversion if `s` is equal
+    /// to a cached string
+    pub fn to_rust<'a, C: Context<'a>>(&self, cx: &mut C, s: Handle<JsString>) -> Arc<str> {
+        if s.size(cx) as usize > Self::MAX_LEN {
+            self.lock().stats.bypassed += 1;
+            return Arc::from(s.value(cx));
+        }
+
+        let index = self.index.to_inner(cx);
+
+        if let Some(i) = self.lookup(cx, index, s) {
+            let mut state = self.lock();
+
+            state.stats.hits += 1;
+            state.unlink(i);
+            state.push_front(i);
+
+            return Arc::clone(&state.entries[i].value);
+        }
+
+        let value = Arc::<str>::from(s.value(cx));
+        let mut state = self.lock();
+
+        state.stats.misses += 1;
+
+        if state.max_entries == 0 {
+            return value;
+        }
+
+        let i = if state.entries.len() < state.max_entries {
+            state.entries.push(Entry {
+                value: Arc::clone(&value),
+                prev: NIL,
+                next: NIL,
+            });
+            state.entries.len() - 1
+        } else {
+            let i = state.tail;
+            let evicted = std::mem::replace(&mut state.entries[i].value, Arc::clone(&value));
+
+            state.stats.evictions += 1;
+            state.unlink(i);
+
+            let evicted = cx.string(&*evicted);
+
+            unsafe {
+                sys::object::delete(cx.env().to_raw(), index.to_raw(), evicted.to_raw());
+            }
+
+            i
+        };
+
+        state.push_front(i);
+        state.stats.entries = state.entries.len();
+        drop(state);
+
+        let entry = cx.number(i as f64);
+
+        // Setting an own property of an object without a prototype can only fail if
+        // an exception is pending, in which case the string is not cached
+        let _ = index.set(cx, s, entry);
+
+        value
+    }
+
+    // Index of the entry of `s`, if it is cached
+    fn lookup<'a, C: Context<'a>>(
+        &self,
+        cx: &mut C,
+        index: Handle<JsObject>,
+        s: Handle<JsString>,
+    ) -> Option<usize> {
+        let entry = index.get_value(cx, s).ok()?;
+        let i = entry.downcast::<JsNumber, _>(cx).ok()?.value(cx) as usize;
+
+        (i < self.lock().entries.len()).then_some(i)
+    }
+
+    /// Removes every string from the cache. The counters are not reset.
+    pub fn clear<'a, C: Context<'a>>(&self, cx: &mut C) {
+        let index = self.index.to_inner(cx);
+        let entries = {
+            let mut state = self.lock();
+
+            state.head = NIL;
+            state.tail = NIL;
+            state.stats.entries = 0;
+            std::mem::take(&mut state.entries)
+        };
+
+        for entry in entries {
+            let key = cx.string(&*entry.value);
+
+            unsafe {
+                sys::object::delete(cx.env().to_raw(), index.to_raw(), key.to_raw());
+            }
+        }
+    }
+
+    /// Returns the counters of the cache
+    pub fn stats(&self) -> StringCacheStats {
+        self.lock().stats
+    }
+}
+
+/// A string extracted with the [shared](StringCache::shared) [`StringCache`]
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{CachedString, TryFromJs};
+///
+/// fn method(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+///     let v = cx.argument::<JsValue>(0)?;
+///     let is_get = match CachedString::try_from_js(&mut cx, v)? {
+///         Some(CachedString(method)) => &*method == "GET",
+///         None => false,
+///     };
+///
+///     Ok(cx.boolean(is_get))
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CachedString(pub Arc<str>);
+
+impl<'cx> TryFromJs<'cx> for CachedString {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let s = match v.downcast::<JsString, _>(cx) {
+            Ok(s) => s,
+            Err(_) => return Ok(None),
+        };
+
+        let len = s.size(cx) as usize;
+
+        limits::charge(cx, Resource::StringBytes, len)?;
+
+        Ok(Some(Self(StringCache::shared(cx)?.to_rust(cx, s))))
+    }
+}
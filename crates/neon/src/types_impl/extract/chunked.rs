@@ -0,0 +1,174 @@
+use std::{any, cell::RefCell, rc::Rc};
+
+use crate::{
+    context::{Context, TaskContext},
+    handle::{Handle, Root},
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{extract::TryFromJs, Deferred, JsArray, JsFunction, JsPromise, Value},
+};
+
+/// Extracts a `Vec` from a large array a chunk of elements at a time, yielding to the
+/// event loop between chunks.
+///
+/// Extracting every element of an array with millions of elements in one call blocks
+/// the event loop until it is done. [`from_array`](ChunkedVec::from_array) extracts
+/// `chunk_size` elements in each turn of the event loop, scheduling the next chunk with
+/// `setImmediate`, so timers and I/O callbacks run in between. When every element is
+/// extracted, the `Vec` is passed to a continuation, which settles the returned
+/// promise:
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::ChunkedVec;
+///
+/// fn sum(mut cx: FunctionContext) -> JsResult<JsPromise> {
+///     let numbers = cx.argument::<JsArray>(0)?;
+///
+///     ChunkedVec::<f64>::from_array(&mut cx, numbers, 10_000, |mut cx, numbers| {
+///         Ok(cx.number(numbers.iter().sum::<f64>()))
+///     })
+/// }
+/// ```
+///
+/// The length of the array is read once, when the extraction starts; elements added
+/// afterwards are ignored, and elements removed before they are reached are extracted
+/// as `undefined`. Elements changed before they are reached are extracted with their
+/// new value.
+///
+/// The promise is rejected with a `TypeError` naming the index of the first element
+/// that can't be extracted, or with any exception thrown while reading an element or
+/// by the continuation.
+pub struct ChunkedVec<T> {
+    values: Vec<T>,
+    array: Root<JsArray>,
+    len: u32,
+    chunk_size: u32,
+}
+
+// A conversion between chunks, taken by the callback that continues it
+type Pending<T, F> = Rc<RefCell<Option<(ChunkedVec<T>, Deferred, F)>>>;
+
+impl<T> ChunkedVec<T>
+where
+    T: for<'cx> TryFromJs<'cx> + 'static,
+{
+    /// Extracts the elements of `array`, `chunk_size` elements at a time, and settles
+    /// the returned promise with the result of `complete`. A `chunk_size` of `0` is
+    /// treated as `1`.
+    pub fn from_array<'cx, C, F, V>(
+        cx: &mut C,
+        array: Handle<JsArray>,
+        chunk_size: usize,
+        complete: F,
+    ) -> JsResult<'cx, JsPromise>
+    where
+        C: Context<'cx>,
+        F: FnOnce(TaskContext, Vec<T>) -> JsResult<V> + 'static,
+        V: Value,
+    {
+        let len = array.len(cx);
+        let conversion = ChunkedVec {
+            values: Vec::with_capacity(len as usize),
+            array: array.root(cx),
+            len,
+            chunk_size: chunk_size.clamp(1, u32::MAX as usize) as u32,
+        };
+
+        let (deferred, promise) = cx.promise();
+        let pending = Rc::new(RefCell::new(Some((conversion, deferred, complete))));
+
+        schedule(cx, pending);
+
+        Ok(promise)
+    }
+
+    // Extracts the next chunk of elements. Returns `true` if every element is extracted.
+    fn convert_chunk<'cx, C: Context<'cx>>(&mut self, cx: &mut C) -> NeonResult<bool> {
+        let array = self.array.to_inner(cx);
+        let start = self.values.len() as u32;
+        let end = self.len.min(start.saturating_add(self.chunk_size));
+
+        for i in start..end {
+            let v = array.get_value(cx, i)?;
+
+            match T::try_from_js(cx, v)? {
+                Some(v) => self.values.push(v),
+                None => {
+                    return cx.throw_type_error(format!(
+                        "failed to extract `{}` from the element at index {}",
+                        any::type_name::<T>(),
+                        i,
+                    ))
+                }
+            }
+        }
+
+        Ok(end == self.len)
+    }
+}
+
+// Schedules the next chunk of `pending` with `setImmediate`, or rejects its promise if
+// the chunk can't be scheduled
+fn schedule<'cx, C, T, F, V>(cx: &mut C, pending: Pending<T, F>)
+where
+    C: Context<'cx>,
+    T: for<'a> TryFromJs<'a> + 'static,
+    F: FnOnce(TaskContext, Vec<T>) -> JsResult<V> + 'static,
+    V: Value,
+{
+    let next = pending.clone();
+    let scheduled = cx.try_catch(move |cx| {
+        let f = JsFunction::new(cx, move |mut cx| {
+            step(&mut cx, next.clone());
+            Ok(cx.undefined())
+        })?;
+
+        let global = cx.global();
+        let set_immediate = global.get::<JsFunction, _, _>(cx, "setImmediate")?;
+        let undefined = cx.undefined();
+
+        set_immediate.exec(cx, undefined, [f.upcast()])
+    });
+
+    if let Err(err) = scheduled {
+        if let Some((conversion, deferred, _)) = pending.borrow_mut().take() {
+            conversion.array.drop(cx);
+            deferred.reject(cx, err);
+        }
+    }
+}
+
+// Extracts the next chunk of `pending` and schedules the following chunk, or settles
+// the promise
+fn step<'cx, C, T, F, V>(cx: &mut C, pending: Pending<T, F>)
+where
+    C: Context<'cx>,
+    T: for<'a> TryFromJs<'a> + 'static,
+    F: FnOnce(TaskContext, Vec<T>) -> JsResult<V> + 'static,
+    V: Value,
+{
+    let (mut conversion, deferred, complete) = match pending.borrow_mut().take() {
+        Some(pending) => pending,
+        None => return,
+    };
+
+    match cx.try_catch(|cx| conversion.convert_chunk(cx)) {
+        Ok(false) => {
+            *pending.borrow_mut() = Some((conversion, deferred, complete));
+            schedule(cx, pending);
+        }
+        Ok(true) => {
+            let ChunkedVec { values, array, .. } = conversion;
+
+            array.drop(cx);
+            TaskContext::with_context(cx.env(), move |cx| {
+                deferred.try_catch_settle(cx, move |cx| complete(cx, values))
+            });
+        }
+        Err(err) => {
+            conversion.array.drop(cx);
+            deferred.reject(cx, err);
+        }
+    }
+}
@@ -0,0 +1,400 @@
+//! Conversions of maps and sets, preserving the order of ordered collections
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    limits::{self, Resource},
+    object::Object,
+    result::{JsResult, NeonResult, Throw},
+    sys,
+    types::{
+        extract::{path, PathSegment, TryFromJs, TryIntoJs},
+        JsArray, JsFunction, JsObject, JsString, JsValue,
+    },
+};
+
+// Keys and values of entries converted to JavaScript
+type Pairs<'cx> = Vec<(Handle<'cx, JsValue>, Handle<'cx, JsValue>)>;
+
+// The global constructor `name`, such as `Map`
+fn global_constructor<'cx, C: Context<'cx>>(cx: &mut C, name: &str) -> JsResult<'cx, JsFunction> {
+    let global = cx.global();
+
+    global.get(cx, name)
+}
+
+// Is `v` an instance of the global constructor `name`?
+fn is_instance<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+    name: &str,
+) -> NeonResult<bool> {
+    let constructor = global_constructor(cx, name)?;
+
+    unsafe { sys::tag::is_instance_of(cx.env().to_raw(), v.to_raw(), constructor.to_raw()) }
+        .ok_or_else(Throw::new)
+}
+
+// Calls the static method `name` of the global constructor `constructor`, such as
+// `Array.from`, with `v`
+fn call_static<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    constructor: &str,
+    name: &str,
+    v: Handle<'cx, JsValue>,
+) -> JsResult<'cx, JsArray> {
+    let constructor = global_constructor(cx, constructor)?;
+    let f: Handle<JsFunction> = constructor.get(cx, name)?;
+
+    f.call_with(cx).this(constructor).arg(v).apply(cx)
+}
+
+// Converts each value, annotating an error with the index of the value
+fn values_to_js<'cx, C, I>(cx: &mut C, values: I) -> NeonResult<Vec<Handle<'cx, JsValue>>>
+where
+    C: Context<'cx>,
+    I: IntoIterator,
+    I::Item: TryIntoJs<'cx>,
+{
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, v)| Ok(path::try_into_js_at(cx, i, v)?.upcast()))
+        .collect()
+}
+
+// Creates a `Set` of `values`
+fn new_set<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    values: Vec<Handle<'cx, JsValue>>,
+) -> JsResult<'cx, JsObject> {
+    let values = values.try_into_js(cx)?;
+
+    global_constructor(cx, "Set")?.construct(cx, [values.upcast()])
+}
+
+// Creates a `Map` of `entries`
+fn new_map<'cx, C: Context<'cx>>(cx: &mut C, entries: Pairs<'cx>) -> JsResult<'cx, JsObject> {
+    let pairs = JsArray::new(cx, entries.len() as u32);
+
+    for (i, (k, v)) in entries.into_iter().enumerate() {
+        let pair = JsArray::new(cx, 2);
+
+        pair.set(cx, 0, k)?;
+        pair.set(cx, 1, v)?;
+        pairs.set(cx, i as u32, pair)?;
+    }
+
+    global_constructor(cx, "Map")?.construct(cx, [pairs.upcast()])
+}
+
+// Converts each entry. Values are annotated with their key if every key is a string
+// and with the index of their entry otherwise.
+fn entries_to_js<'cx, C, I, K, V>(cx: &mut C, entries: I) -> NeonResult<(Pairs<'cx>, bool)>
+where
+    C: Context<'cx>,
+    I: IntoIterator<Item = (K, V)>,
+    K: TryIntoJs<'cx>,
+    V: TryIntoJs<'cx>,
+{
+    let entries = entries.into_iter();
+    let mut converted = Vec::with_capacity(entries.size_hint().0);
+    let mut all_strings = true;
+
+    for (i, (k, v)) in entries.enumerate() {
+        let k = path::try_into_js_at(cx, i, k)?.upcast::<JsValue>();
+        let v = match v.try_into_js(cx) {
+            Ok(v) => v.upcast(),
+            Err(throw) => {
+                let key = k.downcast::<JsString, _>(cx).ok().map(|k| k.value(cx));
+                let segment = match &key {
+                    Some(key) => PathSegment::Key(key),
+                    None => PathSegment::Index(i),
+                };
+
+                return path::annotate(cx, segment, throw);
+            }
+        };
+
+        all_strings &= k.is_a::<JsString, _>(cx);
+        converted.push((k, v));
+    }
+
+    Ok((converted, all_strings))
+}
+
+// Creates an object with a property for each entry
+fn new_object<'cx, C: Context<'cx>>(cx: &mut C, entries: Pairs<'cx>) -> JsResult<'cx, JsObject> {
+    let obj = cx.empty_object();
+    let env = cx.env().to_raw();
+
+    for (k, v) in entries {
+        // Like `HashMap`, a key such as `__proto__` becomes an own property
+        if unsafe { sys::object::define_own(env, obj.to_raw(), k.to_raw(), v.to_raw()) } {
+            continue;
+        }
+
+        if unsafe { sys::error::is_throwing(env) } {
+            return Err(Throw::new());
+        }
+
+        let k = k.downcast_or_throw::<JsString, _>(cx)?.value(cx);
+
+        return cx.throw_type_error(format!("cannot define property `{}`", k));
+    }
+
+    Ok(obj)
+}
+
+/// Converts to an object with a property for each entry, defined in the order of the
+/// keys, or to a `Map` if a key is not converted to a string
+///
+/// The order of the keys is observable with `Object.keys`, except that JavaScript
+/// lists keys that are array indices, such as `"1"`, first and in numeric order.
+/// Like [`HashMap`], a key such as `__proto__` becomes an own property.
+///
+/// ```
+/// # use neon::prelude::*;
+/// # use std::collections::BTreeMap;
+/// use neon::types::extract::TryIntoJs;
+///
+/// fn totals(mut cx: FunctionContext) -> JsResult<JsObject> {
+///     let mut totals = BTreeMap::new();
+///
+///     totals.insert("b", 2.0);
+///     totals.insert("a", 1.0);
+///
+///     // `Object.keys` returns `["a", "b"]`
+///     totals.try_into_js(&mut cx)
+/// }
+/// ```
+impl<'cx, K, V> TryIntoJs<'cx> for BTreeMap<K, V>
+where
+    K: TryIntoJs<'cx>,
+    V: TryIntoJs<'cx>,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let (entries, all_strings) = entries_to_js(cx, self)?;
+
+        if all_strings {
+            new_object(cx, entries)
+        } else {
+            new_map(cx, entries)
+        }
+    }
+}
+
+/// Converts to a `Set`, in the iteration order of the set, which is unspecified
+impl<'cx, T, S> TryIntoJs<'cx> for HashSet<T, S>
+where
+    T: TryIntoJs<'cx>,
+    S: BuildHasher,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let values = values_to_js(cx, self)?;
+
+        new_set(cx, values)
+    }
+}
+
+/// Converts to a `Set`, in sorted order
+impl<'cx, T> TryIntoJs<'cx> for BTreeSet<T>
+where
+    T: TryIntoJs<'cx>,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let values = values_to_js(cx, self)?;
+
+        new_set(cx, values)
+    }
+}
+
+/// Converts a list of entries to a `Map`, preserving their order
+///
+/// Like `new Map(entries)`, if a key appears more than once, the value of the last
+/// entry with the key wins, at the position of the first entry with the key.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{Entries, TryIntoJs};
+///
+/// fn ranking(mut cx: FunctionContext) -> JsResult<JsObject> {
+///     let ranking = vec![("gold", 3.0), ("silver", 2.0), ("bronze", 1.0)];
+///
+///     Entries(ranking).try_into_js(&mut cx)
+/// }
+/// ```
+///
+/// An `Entries` is extracted from a `Map` or from the own enumerable string-keyed
+/// properties of an object, in order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Entries<K, V>(pub Vec<(K, V)>);
+
+impl<'cx, K, V> TryIntoJs<'cx> for Entries<K, V>
+where
+    K: TryIntoJs<'cx>,
+    V: TryIntoJs<'cx>,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let (entries, _) = entries_to_js(cx, self.0)?;
+
+        new_map(cx, entries)
+    }
+}
+
+// The entries of a `Map`, or the own enumerable string-keyed properties of an object
+// other than an array
+fn entries_from_js<'cx, C, K, V>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+) -> NeonResult<Option<Vec<(K, V)>>>
+where
+    C: Context<'cx>,
+    K: TryFromJs<'cx>,
+    V: TryFromJs<'cx>,
+{
+    if !v.is_a::<JsObject, _>(cx) || v.is_a::<JsArray, _>(cx) {
+        return Ok(None);
+    }
+
+    let pairs = if is_instance(cx, v, "Map")? {
+        call_static(cx, "Array", "from", v)?
+    } else {
+        call_static(cx, "Object", "entries", v)?
+    };
+
+    let _guard = limits::enter(cx)?;
+    let len = pairs.len(cx);
+
+    limits::charge(cx, Resource::Items, len as usize)?;
+
+    let mut entries = Vec::with_capacity(len as usize);
+
+    for i in 0..len {
+        let pair: Handle<JsArray> = pairs.get(cx, i)?;
+        let k = pair.get_value(cx, 0)?;
+        let v = pair.get_value(cx, 1)?;
+
+        match (K::try_from_js(cx, k)?, V::try_from_js(cx, v)?) {
+            (Some(k), Some(v)) => entries.push((k, v)),
+            _ => return Ok(None),
+        }
+    }
+
+    Ok(Some(entries))
+}
+
+// The values of a `Set` or an array
+fn values_from_js<'cx, C, T>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<Option<Vec<T>>>
+where
+    C: Context<'cx>,
+    T: TryFromJs<'cx>,
+{
+    let values = if v.is_a::<JsArray, _>(cx) {
+        v.downcast_or_throw::<JsArray, _>(cx)?
+    } else if v.is_a::<JsObject, _>(cx) && is_instance(cx, v, "Set")? {
+        call_static(cx, "Array", "from", v)?
+    } else {
+        return Ok(None);
+    };
+
+    let _guard = limits::enter(cx)?;
+    let len = values.len(cx);
+
+    limits::charge(cx, Resource::Items, len as usize)?;
+
+    let mut extracted = Vec::with_capacity(len as usize);
+
+    for v in values.to_vec(cx)? {
+        match T::try_from_js(cx, v)? {
+            Some(v) => extracted.push(v),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(extracted))
+}
+
+/// Extracts the entries of a `Map`, or the own enumerable string-keyed properties of
+/// an object. If a key is repeated after extraction, the last entry wins.
+impl<'cx, K, V, S> TryFromJs<'cx> for HashMap<K, V, S>
+where
+    K: TryFromJs<'cx> + Eq + Hash,
+    V: TryFromJs<'cx>,
+    S: BuildHasher + Default,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(entries_from_js(cx, v)?.map(|entries| entries.into_iter().collect()))
+    }
+}
+
+/// Extracts the entries of a `Map`, or the own enumerable string-keyed properties of
+/// an object. If a key is repeated after extraction, the last entry wins.
+impl<'cx, K, V> TryFromJs<'cx> for BTreeMap<K, V>
+where
+    K: TryFromJs<'cx> + Ord,
+    V: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(entries_from_js(cx, v)?.map(|entries| entries.into_iter().collect()))
+    }
+}
+
+impl<'cx, K, V> TryFromJs<'cx> for Entries<K, V>
+where
+    K: TryFromJs<'cx>,
+    V: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(entries_from_js(cx, v)?.map(Entries))
+    }
+}
+
+/// Extracts the values of a `Set` or an array
+impl<'cx, T, S> TryFromJs<'cx> for HashSet<T, S>
+where
+    T: TryFromJs<'cx> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(values_from_js(cx, v)?.map(|values| values.into_iter().collect()))
+    }
+}
+
+/// Extracts the values of a `Set` or an array
+impl<'cx, T> TryFromJs<'cx> for BTreeSet<T>
+where
+    T: TryFromJs<'cx> + Ord,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(values_from_js(cx, v)?.map(|values| values.into_iter().collect()))
+    }
+}
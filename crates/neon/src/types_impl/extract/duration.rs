@@ -0,0 +1,187 @@
+use std::{convert::TryFrom, time::Duration};
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::Object,
+    result::{self, JsResult, NeonResult},
+    types::{JsBigInt, JsNumber, JsObject, JsValue, Value},
+};
+
+use super::{Int64, TryFromJs, TryIntoJs};
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+
+/// Converts to an object `{ secs, nanos }`, without losing precision. `secs` is
+/// converted like a [`u64`] and `nanos` is a number less than `1e9`.
+///
+/// Use [`Millis`] or [`Nanos`] to convert to a single number or BigInt instead.
+impl<'cx> TryIntoJs<'cx> for Duration {
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let obj = cx.empty_object();
+        let secs = self.as_secs().try_into_js(cx)?;
+        let nanos = cx.number(self.subsec_nanos());
+
+        obj.set(cx, "secs", secs)?;
+        obj.set(cx, "nanos", nanos)?;
+
+        Ok(obj)
+    }
+}
+
+/// Extracts a number of milliseconds, a BigInt number of nanoseconds, or an object
+/// `{ secs, nanos }`, the representations of [`Millis`], [`Nanos`] and `Duration`.
+///
+/// A negative duration, or `nanos` of `1e9` or more, throws a `RangeError`.
+impl<'cx> TryFromJs<'cx> for Duration {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if let Ok(v) = v.downcast::<JsNumber, _>(cx) {
+            return from_millis(cx, v).map(Some);
+        }
+
+        if let Ok(v) = v.downcast::<JsBigInt, _>(cx) {
+            return from_nanos(cx, v).map(Some);
+        }
+
+        let obj = match v.downcast::<JsObject, _>(cx) {
+            Ok(obj) => obj,
+            Err(_) => return Ok(None),
+        };
+
+        let secs = obj.get_value(cx, "secs")?;
+        let nanos = obj.get_value(cx, "nanos")?;
+
+        let (secs, nanos) = match (u64::try_from_js(cx, secs)?, u64::try_from_js(cx, nanos)?) {
+            (Some(secs), Some(nanos)) => (secs, nanos),
+            _ => return Ok(None),
+        };
+
+        if nanos >= NANOS_PER_SEC {
+            return result::throw_out_of_range(
+                cx,
+                Some("nanos"),
+                ">= 0 and <= 999999999",
+                &nanos.to_string(),
+                || format!("{} nanoseconds is not less than a second", nanos),
+            );
+        }
+
+        Ok(Some(Duration::new(secs, nanos as u32)))
+    }
+}
+
+/// A [`Duration`] represented as a number of milliseconds
+///
+/// A number can't represent every nanosecond of a long duration. Converting to a
+/// number rounds to the nearest representable value, and extracting rounds to the
+/// nearest nanosecond. [`Nanos`] is exact.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use std::time::Instant;
+///
+/// use neon::types::extract::{Millis, TryIntoJs};
+///
+/// fn time_it(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let start = Instant::now();
+///
+///     cx.argument::<JsFunction>(0)?.call_with(&cx).exec(&mut cx)?;
+///
+///     Millis(start.elapsed()).try_into_js(&mut cx)
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Millis(pub Duration);
+
+impl<'cx> TryIntoJs<'cx> for Millis {
+    type Value = JsNumber;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        Ok(cx.number(self.0.as_secs_f64() * 1e3))
+    }
+}
+
+/// Extracts only a number, throwing a `RangeError` if it is negative or not finite
+impl<'cx> TryFromJs<'cx> for Millis {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        match v.downcast::<JsNumber, _>(cx) {
+            Ok(v) => from_millis(cx, v).map(|d| Some(Millis(d))),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+/// A [`Duration`] represented as a BigInt number of nanoseconds, like
+/// [`process.hrtime.bigint()`](https://nodejs.org/api/process.html#processhrtimebigint)
+///
+/// Only durations shorter than 2<sup>64</sup> nanoseconds, about 584 years, can be
+/// represented; converting a longer duration throws a `RangeError`.
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Nanos(pub Duration);
+
+impl<'cx> TryIntoJs<'cx> for Nanos {
+    type Value = JsBigInt;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        match u64::try_from(self.0.as_nanos()) {
+            Ok(nanos) => Ok(JsBigInt::from_u64(cx, nanos)),
+            Err(_) => cx.throw_range_error(format!(
+                "duration of {:?} is too long to convert to nanoseconds",
+                self.0
+            )),
+        }
+    }
+}
+
+/// Extracts only a BigInt, throwing a `RangeError` if it is negative or does not fit
+/// in a [`u64`]
+impl<'cx> TryFromJs<'cx> for Nanos {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        match v.downcast::<JsBigInt, _>(cx) {
+            Ok(v) => from_nanos(cx, v).map(|d| Some(Nanos(d))),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+fn from_millis<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsNumber>) -> NeonResult<Duration> {
+    let ms = v.value(cx);
+
+    if ms >= 0.0 {
+        if let Ok(d) = Duration::try_from_secs_f64(ms / 1e3) {
+            return Ok(d);
+        }
+    }
+
+    let value = v.to_string(cx)?.value(cx);
+
+    result::throw_out_of_range(cx, None, "a finite number >= 0", &value, || {
+        format!("{} milliseconds is not a valid duration", value)
+    })
+}
+
+fn from_nanos<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsBigInt>) -> NeonResult<Duration> {
+    match v.to_u64(cx) {
+        Some(nanos) => Ok(Duration::from_nanos(nanos)),
+        None => {
+            let value = v.to_string(cx)?.value(cx);
+
+            result::throw_out_of_range(cx, None, u64::BIGINT_RANGE, &format!("{}n", value), || {
+                format!("{}n nanoseconds is not a valid duration", value)
+            })
+        }
+    }
+}
@@ -0,0 +1,238 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    result::{self, JsResult, NeonResult},
+    thread::LocalKey,
+    types::{JsNull, JsNumber, JsValue},
+};
+
+use super::{TryFromJs, TryIntoJs};
+
+/// How the plain `f64` and `f32` conversions of [`TryFromJs`] and [`TryIntoJs`]
+/// handle `NaN`, `Infinity` and `-Infinity`
+///
+/// The policy is set for each instance of the addon with [`set_float_policy`]. It
+/// also applies to `Vec<f64>`, which is extracted from arrays and typed arrays, and
+/// to the numbers written and parsed by the [`json`](crate::types::json) helpers.
+/// [`Finite`] and [`NanAsNull`] convert with a fixed policy instead.
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FloatPolicy {
+    /// Non-finite values are converted unchanged. _Default_
+    #[default]
+    Allow,
+    /// Converting a non-finite value throws a `RangeError`, like [`Finite`]
+    Reject,
+}
+
+// Whether the instance has the `Reject` policy
+static REJECT: LocalKey<AtomicBool> = LocalKey::new();
+
+// Number of instances with a policy other than `Allow`, so that conversions only look
+// up the policy of the instance if some instance set one
+static REJECT_INSTANCES: AtomicUsize = AtomicUsize::new(0);
+
+/// Sets the [`FloatPolicy`] of the instance of the addon.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{self, FloatPolicy};
+///
+/// #[neon::main]
+/// fn main(mut cx: ModuleContext) -> NeonResult<()> {
+///     // `NaN` and `Infinity` throw instead of reaching Rust math or JSON output
+///     extract::set_float_policy(&mut cx, FloatPolicy::Reject);
+///
+///     Ok(())
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub fn set_float_policy<'cx, C: Context<'cx>>(cx: &mut C, policy: FloatPolicy) {
+    let reject = policy == FloatPolicy::Reject;
+    let was_reject = REJECT
+        .get_or_init_default(cx)
+        .swap(reject, Ordering::Relaxed);
+
+    match (was_reject, reject) {
+        (false, true) => REJECT_INSTANCES.fetch_add(1, Ordering::Relaxed),
+        (true, false) => REJECT_INSTANCES.fetch_sub(1, Ordering::Relaxed),
+        _ => 0,
+    };
+}
+
+/// The [`FloatPolicy`] of the instance of the addon
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub fn float_policy<'cx, C: Context<'cx>>(cx: &mut C) -> FloatPolicy {
+    if REJECT_INSTANCES.load(Ordering::Relaxed) == 0 {
+        return FloatPolicy::Allow;
+    }
+
+    match REJECT.get(cx) {
+        Some(reject) if reject.load(Ordering::Relaxed) => FloatPolicy::Reject,
+        _ => FloatPolicy::Allow,
+    }
+}
+
+// Throws a `RangeError` if `v` is not finite and the policy of the instance rejects it
+pub(crate) fn check_policy<'cx, C: Context<'cx>>(cx: &mut C, v: f64) -> NeonResult<f64> {
+    if v.is_finite() || float_policy(cx) == FloatPolicy::Allow {
+        return Ok(v);
+    }
+
+    throw_non_finite(cx, v, "a finite number")
+}
+
+// Like `check_policy` for each element of a `Vec`, naming the index of the first
+// non-finite element
+pub(crate) fn check_policy_all<'cx, C: Context<'cx>>(cx: &mut C, values: &[f64]) -> NeonResult<()> {
+    if float_policy(cx) == FloatPolicy::Allow {
+        return Ok(());
+    }
+
+    match values.iter().position(|v| !v.is_finite()) {
+        Some(i) => {
+            let received = display(values[i]);
+
+            result::throw_out_of_range(cx, None, "a finite number", &received, || {
+                format!(
+                    "element {} is {}, which is not a finite number",
+                    i, received
+                )
+            })
+        }
+        None => Ok(()),
+    }
+}
+
+fn throw_non_finite<'cx, C: Context<'cx>, T>(cx: &mut C, v: f64, range: &str) -> NeonResult<T> {
+    let received = display(v);
+
+    result::throw_out_of_range(cx, None, range, &received, || {
+        format!("{} is not {}", received, range)
+    })
+}
+
+// Formats a number like JavaScript
+fn display(v: f64) -> String {
+    match v {
+        v if v.is_nan() => String::from("NaN"),
+        f64::INFINITY => String::from("Infinity"),
+        f64::NEG_INFINITY => String::from("-Infinity"),
+        v => v.to_string(),
+    }
+}
+
+/// An `f64` or `f32` that is always finite
+///
+/// Extracting `NaN`, `Infinity` or `-Infinity`, or a number too large for an `f32`,
+/// throws a `RangeError`, and so does converting a non-finite value to JavaScript.
+/// Unlike the plain conversions, the [`FloatPolicy`] of the instance is not consulted.
+/// Negative zero is preserved in both directions.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{Finite, TryFromJs, TryIntoJs};
+///
+/// fn ratio(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let a = cx.argument::<JsValue>(0)?;
+///     let b = cx.argument::<JsValue>(1)?;
+///     let a = Finite::<f64>::try_from_js(&mut cx, a)?.map_or(0.0, |Finite(a)| a);
+///     let b = Finite::<f64>::try_from_js(&mut cx, b)?.map_or(1.0, |Finite(b)| b);
+///
+///     // Throws a `RangeError` if `b` is `0`
+///     Finite(a / b).try_into_js(&mut cx)
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct Finite<T>(pub T);
+
+/// An `f64` or `f32` that is converted to `null` if it is not finite, like
+/// `JSON.stringify`
+///
+/// `NaN`, `Infinity` and `-Infinity` are converted to `null`, and finite values to a
+/// number. Extracting is the reverse: `null` is extracted as `NaN`, and any number is
+/// extracted unchanged. The [`FloatPolicy`] of the instance is not consulted.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{NanAsNull, TryIntoJs};
+///
+/// // `[1, null]` instead of `[1, NaN]`
+/// fn averages(mut cx: FunctionContext) -> JsResult<JsArray> {
+///     let averages = vec![NanAsNull(1.0), NanAsNull(f64::NAN)];
+///
+///     averages.try_into_js(&mut cx)
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct NanAsNull<T>(pub T);
+
+macro_rules! impl_float {
+    ($ty:ty, $range:expr) => {
+        impl<'cx> TryFromJs<'cx> for Finite<$ty> {
+            fn try_from_js<C: Context<'cx>>(
+                cx: &mut C,
+                v: Handle<'cx, JsValue>,
+            ) -> NeonResult<Option<Self>> {
+                let v = match v.downcast::<JsNumber, _>(cx) {
+                    Ok(v) => v.value(cx),
+                    Err(_) => return Ok(None),
+                };
+
+                let converted = v as $ty;
+
+                if !converted.is_finite() {
+                    return throw_non_finite(cx, v, $range);
+                }
+
+                Ok(Some(Finite(converted)))
+            }
+        }
+
+        impl<'cx> TryIntoJs<'cx> for Finite<$ty> {
+            type Value = JsNumber;
+
+            fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                if !self.0.is_finite() {
+                    return throw_non_finite(cx, self.0.into(), "a finite number");
+                }
+
+                Ok(cx.number(self.0))
+            }
+        }
+
+        impl<'cx> TryFromJs<'cx> for NanAsNull<$ty> {
+            fn try_from_js<C: Context<'cx>>(
+                cx: &mut C,
+                v: Handle<'cx, JsValue>,
+            ) -> NeonResult<Option<Self>> {
+                if v.is_a::<JsNull, _>(cx) {
+                    return Ok(Some(NanAsNull(<$ty>::NAN)));
+                }
+
+                Ok(v.downcast::<JsNumber, _>(cx)
+                    .ok()
+                    .map(|v| NanAsNull(v.value(cx) as $ty)))
+            }
+        }
+
+        impl<'cx> TryIntoJs<'cx> for NanAsNull<$ty> {
+            type Value = JsValue;
+
+            fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                if self.0.is_finite() {
+                    Ok(cx.number(self.0).upcast())
+                } else {
+                    Ok(cx.null().upcast())
+                }
+            }
+        }
+    };
+}
+
+impl_float!(f64, "a finite number");
+impl_float!(f32, "a finite number within the range of a 32-bit float");
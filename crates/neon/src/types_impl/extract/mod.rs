@@ -0,0 +1,755 @@
+//! Traits for converting Rust values to and from JavaScript values.
+//!
+//! The [`TryIntoJs`] trait converts a Rust value into a JavaScript value. It is
+//! implemented for common Rust types and for any [`Handle`], which converts
+//! to itself. The [`TryFromJs`] trait performs the reverse conversion.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use neon::types::extract::TryIntoJs;
+//!
+//! fn point(mut cx: FunctionContext) -> JsResult<JsArray> {
+//!     vec![1.0, 2.0].try_into_js(&mut cx)
+//! }
+//! ```
+
+use std::{collections::HashMap, hash::BuildHasher};
+
+use crate::{
+    context::{Context, FunctionContext},
+    handle::Handle,
+    limits::{self, Quota, Resource},
+    object::{KeyPolicy, Object},
+    result::{JsResult, NeonResult},
+    types::{
+        JsArray, JsBoolean, JsNull, JsNumber, JsObject, JsString, JsUndefined, JsValue, SafeDict,
+        Value,
+    },
+};
+
+#[cfg(feature = "napi-6")]
+use crate::{result, types::JsBigInt};
+
+pub use self::{
+    args::{Provided, Rest},
+    collections::Entries,
+    partial::{ElementError, Partial},
+    path::{try_into_js_at, PathSegment},
+    wide::WideString,
+};
+#[cfg(feature = "napi-6")]
+pub use self::{
+    cache::{CachedString, StringCache, StringCacheStats},
+    chunked::ChunkedVec,
+    duration::{Millis, Nanos},
+    float::{float_policy, set_float_policy, Finite, FloatPolicy, NanAsNull},
+};
+
+mod args;
+#[cfg(feature = "napi-6")]
+mod cache;
+#[cfg(feature = "napi-6")]
+mod chunked;
+mod collections;
+#[cfg(feature = "napi-6")]
+mod duration;
+#[cfg(feature = "napi-6")]
+pub(crate) mod float;
+mod numeric;
+mod partial;
+mod path;
+mod wide;
+
+/// Extract Rust data from a JavaScript value
+pub trait TryFromJs<'cx>: Sized {
+    /// Convert a JavaScript value into `Self`. Returns `Ok(None)` if the value
+    /// is not of the expected type. An `Err` indicates a JavaScript exception,
+    /// for example, thrown by a getter.
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>>;
+
+    #[doc(hidden)]
+    /// Extracts a `Vec<Self>`. Numeric types override this to also accept typed
+    /// arrays.
+    fn try_from_js_vec<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Vec<Self>>> {
+        ArrayOnly::try_from_js(cx, v).map(|v| v.map(|ArrayOnly(v)| v))
+    }
+
+    /// Extracts the `i`th argument of a function call, with access to the number of
+    /// arguments passed, [`cx.len()`](FunctionContext::len). Called by
+    /// [`FunctionContext::extract_argument`].
+    ///
+    /// By default, a missing argument is extracted like `undefined`. [`Provided`]
+    /// overrides this to tell a missing argument from `undefined`, and [`Rest`] to
+    /// extract the remaining arguments.
+    fn try_from_argument(cx: &mut FunctionContext<'cx>, i: usize) -> NeonResult<Option<Self>> {
+        let v = match cx.argument_opt(i) {
+            Some(v) => v,
+            None => cx.undefined().upcast(),
+        };
+
+        Self::try_from_js(cx, v)
+    }
+}
+
+impl<'cx, T: Value> TryFromJs<'cx> for Handle<'cx, T> {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(v.downcast(cx).ok())
+    }
+}
+
+impl<'cx> TryFromJs<'cx> for () {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(v.is_a::<JsUndefined, _>(cx).then_some(()))
+    }
+}
+
+impl<'cx> TryFromJs<'cx> for bool {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Ok(v.downcast::<JsBoolean, _>(cx).ok().map(|v| v.value(cx)))
+    }
+}
+
+/// Extracts a number. With [`FloatPolicy::Reject`], `NaN` and `±Infinity` throw a
+/// `RangeError`.
+impl<'cx> TryFromJs<'cx> for f64 {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let v = match v.downcast::<JsNumber, _>(cx) {
+            Ok(v) => v.value(cx),
+            Err(_) => return Ok(None),
+        };
+
+        #[cfg(feature = "napi-6")]
+        let v = float::check_policy(cx, v)?;
+
+        Ok(Some(v))
+    }
+
+    fn try_from_js_vec<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Vec<Self>>> {
+        numeric::f64_vec(cx, v)
+    }
+}
+
+/// Largest integer that can be represented exactly by a JavaScript number
+/// (`Number.MAX_SAFE_INTEGER`)
+#[cfg(feature = "napi-6")]
+const MAX_SAFE_INTEGER: f64 = 9_007_199_254_740_991.0;
+
+/// 64-bit integers that can be extracted from both a BigInt and a number
+#[cfg(feature = "napi-6")]
+trait Int64: Copy + Sized {
+    const NAME: &'static str;
+    // Range of a BigInt, completing the sentence "It must be"
+    const BIGINT_RANGE: &'static str;
+    // Range of a number, completing the sentence "It must be"
+    const NUMBER_RANGE: &'static str;
+
+    fn from_bigint<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsBigInt>) -> Option<Self>;
+
+    fn into_bigint<'cx, C: Context<'cx>>(self, cx: &mut C) -> Handle<'cx, JsBigInt>;
+
+    fn from_safe_integer(v: f64) -> Option<Self>;
+
+    fn to_safe_integer(self) -> Option<f64>;
+}
+
+#[cfg(feature = "napi-6")]
+impl Int64 for u64 {
+    const NAME: &'static str = "u64";
+    const BIGINT_RANGE: &'static str = ">= 0n and <= 18446744073709551615n";
+    const NUMBER_RANGE: &'static str = ">= 0 and <= 9007199254740991";
+
+    fn from_bigint<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsBigInt>) -> Option<Self> {
+        v.to_u64(cx)
+    }
+
+    fn into_bigint<'cx, C: Context<'cx>>(self, cx: &mut C) -> Handle<'cx, JsBigInt> {
+        JsBigInt::from_u64(cx, self)
+    }
+
+    fn from_safe_integer(v: f64) -> Option<Self> {
+        (v >= 0.0).then_some(v as u64)
+    }
+
+    fn to_safe_integer(self) -> Option<f64> {
+        (self as f64 <= MAX_SAFE_INTEGER).then_some(self as f64)
+    }
+}
+
+#[cfg(feature = "napi-6")]
+impl Int64 for i64 {
+    const NAME: &'static str = "i64";
+    const BIGINT_RANGE: &'static str = ">= -9223372036854775808n and <= 9223372036854775807n";
+    const NUMBER_RANGE: &'static str = ">= -9007199254740991 and <= 9007199254740991";
+
+    fn from_bigint<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsBigInt>) -> Option<Self> {
+        v.to_i64(cx)
+    }
+
+    fn into_bigint<'cx, C: Context<'cx>>(self, cx: &mut C) -> Handle<'cx, JsBigInt> {
+        JsBigInt::from_i64(cx, self)
+    }
+
+    fn from_safe_integer(v: f64) -> Option<Self> {
+        Some(v as i64)
+    }
+
+    fn to_safe_integer(self) -> Option<f64> {
+        ((self.unsigned_abs() as f64) <= MAX_SAFE_INTEGER).then_some(self as f64)
+    }
+}
+
+// Extracts from a BigInt, throwing a `RangeError` with the value if it is out of range
+#[cfg(feature = "napi-6")]
+fn int64_from_bigint<'cx, C, T>(cx: &mut C, v: Handle<'cx, JsBigInt>) -> NeonResult<T>
+where
+    C: Context<'cx>,
+    T: Int64,
+{
+    match T::from_bigint(cx, v) {
+        Some(n) => Ok(n),
+        None => {
+            let value = v.to_string(cx)?.value(cx);
+
+            result::throw_out_of_range(cx, None, T::BIGINT_RANGE, &format!("{}n", value), || {
+                format!("BigInt {}n is out of range for `{}`", value, T::NAME)
+            })
+        }
+    }
+}
+
+// Extracts from a number, throwing a `RangeError` with the value if it is not a safe
+// integer in range
+#[cfg(feature = "napi-6")]
+fn int64_from_number<'cx, C, T>(cx: &mut C, v: Handle<'cx, JsNumber>) -> NeonResult<T>
+where
+    C: Context<'cx>,
+    T: Int64,
+{
+    let n = v.value(cx);
+
+    if n.fract() == 0.0 && n.abs() <= MAX_SAFE_INTEGER {
+        if let Some(n) = T::from_safe_integer(n) {
+            return Ok(n);
+        }
+    }
+
+    let range = if n.fract() == 0.0 {
+        T::NUMBER_RANGE
+    } else {
+        "an integer"
+    };
+
+    let value = v.to_string(cx)?.value(cx);
+
+    result::throw_out_of_range(cx, None, range, &value, || {
+        format!(
+            "number {} is not a safe integer in the range of `{}`",
+            n,
+            T::NAME
+        )
+    })
+}
+
+#[cfg(feature = "napi-6")]
+macro_rules! impl_int64 {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            /// Extracts a BigInt or a number. A BigInt must be in range and a number
+            /// must also be a safe integer, otherwise a `RangeError` is thrown.
+            impl<'cx> TryFromJs<'cx> for $ty {
+                fn try_from_js<C: Context<'cx>>(
+                    cx: &mut C,
+                    v: Handle<'cx, JsValue>,
+                ) -> NeonResult<Option<Self>> {
+                    if let Ok(v) = v.downcast::<JsBigInt, _>(cx) {
+                        return int64_from_bigint(cx, v).map(Some);
+                    }
+
+                    match v.downcast::<JsNumber, _>(cx) {
+                        Ok(v) => int64_from_number(cx, v).map(Some),
+                        Err(_) => Ok(None),
+                    }
+                }
+
+                fn try_from_js_vec<C: Context<'cx>>(
+                    cx: &mut C,
+                    v: Handle<'cx, JsValue>,
+                ) -> NeonResult<Option<Vec<Self>>> {
+                    numeric::int64_vec(cx, v)
+                }
+            }
+
+            impl<'cx> TryFromJs<'cx> for BigIntOnly<$ty> {
+                fn try_from_js<C: Context<'cx>>(
+                    cx: &mut C,
+                    v: Handle<'cx, JsValue>,
+                ) -> NeonResult<Option<Self>> {
+                    match v.downcast::<JsBigInt, _>(cx) {
+                        Ok(v) => int64_from_bigint(cx, v).map(|v| Some(BigIntOnly(v))),
+                        Err(_) => Ok(None),
+                    }
+                }
+            }
+
+            /// Converts to a number if it is a safe integer and a BigInt otherwise
+            impl<'cx> TryIntoJs<'cx> for $ty {
+                type Value = JsValue;
+
+                fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                    Ok(match self.to_safe_integer() {
+                        Some(n) => cx.number(n).upcast(),
+                        None => self.into_bigint(cx).upcast(),
+                    })
+                }
+            }
+
+            impl<'cx> TryIntoJs<'cx> for AlwaysBigInt<$ty> {
+                type Value = JsBigInt;
+
+                fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                    Ok(self.0.into_bigint(cx))
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "napi-6")]
+impl_int64!(u64, i64);
+
+/// Extracts a 64-bit integer only from a BigInt
+///
+/// By default, [`u64`] and [`i64`] are also extracted from a number that is a safe
+/// integer. `BigIntOnly` rejects numbers, for APIs where silently accepting a
+/// possibly rounded number would hide a bug in the caller.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{BigIntOnly, TryFromJs};
+///
+/// fn lookup(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let v = cx.argument::<JsValue>(0)?;
+///
+///     let BigIntOnly(id) = match BigIntOnly::<u64>::try_from_js(&mut cx, v)? {
+///         Some(id) => id,
+///         None => return cx.throw_type_error("expected a BigInt id"),
+///     };
+///
+///     println!("looking up {}", id);
+///
+///     Ok(cx.undefined())
+/// }
+/// ```
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BigIntOnly<T>(pub T);
+
+/// Converts a 64-bit integer into a BigInt, even if it could be represented
+/// exactly as a number
+///
+/// By default, [`u64`] and [`i64`] are converted to a number if they are a safe
+/// integer. `AlwaysBigInt` keeps the JavaScript type consistent for every value.
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AlwaysBigInt<T>(pub T);
+
+impl<'cx> TryFromJs<'cx> for String {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let s = match v.downcast::<JsString, _>(cx) {
+            Ok(s) => s,
+            Err(_) => return Ok(None),
+        };
+
+        let len = s.size(cx) as usize;
+
+        limits::charge(cx, Resource::StringBytes, len)?;
+
+        Ok(Some(s.value(cx)))
+    }
+}
+
+/// `null` and `undefined` are extracted as `None`
+impl<'cx, T> TryFromJs<'cx> for Option<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if v.is_a::<JsNull, _>(cx) || v.is_a::<JsUndefined, _>(cx) {
+            return Ok(Some(None));
+        }
+
+        T::try_from_js(cx, v).map(|v| v.map(Some))
+    }
+}
+
+/// Extracts `T` only if the value is within a [`Quota`]
+///
+/// The usage of the value is counted like [`measure`](limits::measure) while it is
+/// extracted. A `RangeError` is thrown as soon as a limit is exceeded, before memory
+/// is allocated for the part of the value that exceeds it. Since the value is only
+/// read once, a getter can't return a small value to a check and a large value to
+/// the extraction. Extracting with [`TryFromJs`] uses the [default](Quota::default)
+/// quota.
+///
+/// Only the data copied into Rust is counted: strings, the elements of `Vec`s, maps
+/// and sets, and typed arrays copied into a `Vec`. Extracting a [`Handle`] allocates
+/// nothing and is not counted.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::{limits::Quota, types::extract::Limited};
+///
+/// fn sum(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let v = cx.argument::<JsValue>(0)?;
+///     let quota = Quota {
+///         max_items: 1000,
+///         ..Quota::default()
+///     };
+///
+///     match Limited::<Vec<f64>>::try_from_js_with(&mut cx, v, quota)? {
+///         Some(Limited(numbers)) => Ok(cx.number(numbers.iter().sum::<f64>())),
+///         None => cx.throw_type_error("expected an array of numbers"),
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Limited<T>(pub T);
+
+impl<'cx, T> Limited<T>
+where
+    T: TryFromJs<'cx>,
+{
+    /// Extracts `T` if the value is within `quota`, throwing a `RangeError` otherwise
+    pub fn try_from_js_with<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+        quota: Quota,
+    ) -> NeonResult<Option<Self>> {
+        let _budget = limits::limit(quota);
+
+        T::try_from_js(cx, v).map(|v| v.map(Limited))
+    }
+}
+
+impl<'cx, T> TryFromJs<'cx> for Limited<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Self::try_from_js_with(cx, v, Quota::default())
+    }
+}
+
+/// Extracts each element of an array.
+///
+/// A `Vec` of numbers, `Vec<f64>`, `Vec<u64>` or `Vec<i64>`, is also extracted from a
+/// typed array. Elements of a typed array of the same type are copied, and elements of
+/// other typed arrays are converted like numbers: every typed array except
+/// `BigInt64Array` and `BigUint64Array` is converted exactly to `f64`, and converting
+/// to `u64` or `i64` throws a `RangeError` if an element is not an integer in range.
+///
+/// If an element of an array of numbers can't be extracted, a `TypeError` naming its
+/// index and the accepted representations is thrown. Other `Vec`s are not extracted
+/// if an element can't be extracted. [`ArrayOnly`] only extracts arrays.
+impl<'cx, T> TryFromJs<'cx> for Vec<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        T::try_from_js_vec(cx, v)
+    }
+}
+
+/// Extracts a `Vec` only from an array
+///
+/// A `Vec` of numbers is also extracted from a typed array by default. `ArrayOnly`
+/// rejects typed arrays, for APIs that must distinguish them from arrays, and is not
+/// extracted if an element can't be extracted.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{ArrayOnly, TryFromJs};
+///
+/// fn sum(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let v = cx.argument::<JsValue>(0)?;
+///
+///     match ArrayOnly::<f64>::try_from_js(&mut cx, v)? {
+///         Some(ArrayOnly(numbers)) => Ok(cx.number(numbers.iter().sum::<f64>())),
+///         None => cx.throw_type_error("expected an array of numbers"),
+///     }
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArrayOnly<T>(pub Vec<T>);
+
+impl<'cx, T> TryFromJs<'cx> for ArrayOnly<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let arr = match v.downcast::<JsArray, _>(cx) {
+            Ok(arr) => arr,
+            Err(_) => return Ok(None),
+        };
+
+        let _guard = limits::enter(cx)?;
+        let len = arr.len(cx) as usize;
+
+        limits::charge(cx, Resource::Items, len)?;
+
+        let mut values = Vec::with_capacity(len);
+
+        for v in arr.to_vec(cx)? {
+            match T::try_from_js(cx, v)? {
+                Some(v) => values.push(v),
+                None => return Ok(None),
+            }
+        }
+
+        Ok(Some(ArrayOnly(values)))
+    }
+}
+
+/// Convert Rust data into a JavaScript value
+///
+/// Errors thrown while converting the contents of a container, such as a `Vec`, report
+/// the path to the value that failed. See [`try_into_js_at`].
+pub trait TryIntoJs<'cx> {
+    /// The type of JavaScript value that will be created
+    type Value: Value;
+
+    /// Convert `self` into a JavaScript value
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value>;
+}
+
+impl<'cx, T: Value> TryIntoJs<'cx> for Handle<'cx, T> {
+    type Value = T;
+
+    fn try_into_js<C: Context<'cx>>(self, _cx: &mut C) -> JsResult<'cx, Self::Value> {
+        Ok(self)
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for () {
+    type Value = JsUndefined;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        Ok(cx.undefined())
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for bool {
+    type Value = JsBoolean;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        Ok(cx.boolean(self))
+    }
+}
+
+macro_rules! impl_number {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl<'cx> TryIntoJs<'cx> for $ty {
+                type Value = JsNumber;
+
+                fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                    Ok(cx.number(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u8, u16, u32, i8, i16, i32);
+
+macro_rules! impl_float {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            /// With [`FloatPolicy::Reject`], `NaN` and `±Infinity` throw a `RangeError`.
+            impl<'cx> TryIntoJs<'cx> for $ty {
+                type Value = JsNumber;
+
+                fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+                    #[cfg(feature = "napi-6")]
+                    float::check_policy(cx, self.into())?;
+
+                    Ok(cx.number(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_float!(f32, f64);
+
+impl<'cx> TryIntoJs<'cx> for &str {
+    type Value = JsString;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        cx.try_string(self)
+            .or_else(|err| cx.throw_range_error(err.to_string()))
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for &String {
+    type Value = JsString;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        self.as_str().try_into_js(cx)
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for String {
+    type Value = JsString;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        self.as_str().try_into_js(cx)
+    }
+}
+
+/// `None` is converted to `null`
+///
+/// `null` is a value that is present but empty, matching `JSON.stringify`. APIs that
+/// treat `None` as an absent value use `undefined` instead, for example,
+/// [`CallOptions::arg_opt`](crate::types::function::CallOptions::arg_opt) and
+/// [`ObjectBuilder::opt`](crate::object::ObjectBuilder::opt). A nested
+/// `Option<Option<T>>` converts both `None` and `Some(None)` to `null`.
+impl<'cx, T> TryIntoJs<'cx> for Option<T>
+where
+    T: TryIntoJs<'cx>,
+{
+    type Value = JsValue;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        match self {
+            Some(v) => v.try_into_js(cx).map(|v| v.upcast()),
+            None => Ok(JsNull::new(cx).upcast()),
+        }
+    }
+}
+
+impl<'cx, T> TryIntoJs<'cx> for Vec<T>
+where
+    T: TryIntoJs<'cx>,
+{
+    type Value = JsArray;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let arr = JsArray::new(cx, self.len() as u32);
+
+        for (i, v) in self.into_iter().enumerate() {
+            let v = try_into_js_at(cx, i, v)?;
+
+            arr.set(cx, i as u32, v)?;
+        }
+
+        Ok(arr)
+    }
+}
+
+/// Converts to an object with a property for each entry, in the iteration order of the
+/// map, which is unspecified. Use a [`BTreeMap`](std::collections::BTreeMap) or
+/// [`Entries`] to preserve an order.
+///
+/// Properties are defined with [`Object::set_safe_with`] and [`KeyPolicy::AllowOwn`],
+/// so a key such as `__proto__` becomes an own property instead of replacing the
+/// prototype of the object. Use [`Dict`] to also reject unsafe keys.
+impl<'cx, K, V, S> TryIntoJs<'cx> for HashMap<K, V, S>
+where
+    K: AsRef<str>,
+    V: TryIntoJs<'cx>,
+    S: BuildHasher,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let obj = cx.empty_object();
+
+        for (k, v) in self {
+            let v = try_into_js_at(cx, k.as_ref(), v)?;
+
+            obj.set_safe_with(cx, k.as_ref(), v, KeyPolicy::AllowOwn)?;
+        }
+
+        Ok(obj)
+    }
+}
+
+/// Converts a map into a [`SafeDict`], an object with a `null` prototype
+///
+/// Unsafe keys, such as `__proto__`, are rejected with a `TypeError`.
+///
+/// ```
+/// # use neon::prelude::*;
+/// # use std::collections::HashMap;
+/// use neon::types::extract::{Dict, TryIntoJs};
+///
+/// fn headers(mut cx: FunctionContext) -> JsResult<JsObject> {
+///     let mut headers = HashMap::new();
+///
+///     headers.insert("content-type", "text/plain");
+///
+///     Dict(headers).try_into_js(&mut cx)
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Dict<T>(pub T);
+
+impl<'cx, K, V, S> TryIntoJs<'cx> for Dict<HashMap<K, V, S>>
+where
+    K: AsRef<str>,
+    V: TryIntoJs<'cx>,
+    S: BuildHasher,
+{
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let dict = SafeDict::new(cx)?;
+
+        for (k, v) in self.0 {
+            let v = try_into_js_at(cx, k.as_ref(), v)?;
+
+            dict.set(cx, k.as_ref(), v)?;
+        }
+
+        Ok(dict.object())
+    }
+}
@@ -0,0 +1,271 @@
+//! Extraction of numeric `Vec`s from both arrays and typed arrays
+
+use std::slice;
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    limits::{self, Resource},
+    result::NeonResult,
+    sys::{self, typedarray::TypedArrayInfo, TypedArrayType},
+    types::{extract::TryFromJs, JsArray, JsValue},
+};
+
+#[cfg(feature = "napi-6")]
+use {
+    super::{Int64, MAX_SAFE_INTEGER},
+    crate::{result, types::buffer::Binary},
+    std::convert::TryFrom,
+};
+
+// Information about `v`, if it is a typed array
+fn typed_array_info<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+) -> Option<TypedArrayInfo> {
+    let env = cx.env().to_raw();
+
+    unsafe {
+        if !sys::tag::is_typedarray(env, v.to_raw()) {
+            return None;
+        }
+
+        Some(sys::typedarray::info(env, v.to_raw()))
+    }
+}
+
+// The elements of a typed array
+//
+// # Safety
+// `T` must be the element type of the typed array, and the slice must not be used
+// after JavaScript runs, which could detach the buffer
+unsafe fn elements<T>(info: &TypedArrayInfo) -> &[T] {
+    if info.length == 0 {
+        return &[];
+    }
+
+    slice::from_raw_parts(info.data.cast(), info.length)
+}
+
+// Converts the elements of a typed array to `f64`
+//
+// # Safety
+// See `elements`
+unsafe fn widen<T: Copy + Into<f64>>(info: &TypedArrayInfo) -> Vec<f64> {
+    elements::<T>(info).iter().map(|&v| v.into()).collect()
+}
+
+// Extracts each element of an array, throwing a `TypeError` naming the index of the
+// first element that can't be extracted and the accepted representations
+fn vec_from_array<'cx, C, T>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+    element: &str,
+    accepted: &str,
+) -> NeonResult<Option<Vec<T>>>
+where
+    C: Context<'cx>,
+    T: TryFromJs<'cx>,
+{
+    let arr = match v.downcast::<JsArray, _>(cx) {
+        Ok(arr) => arr,
+        Err(_) => return Ok(None),
+    };
+
+    let _guard = limits::enter(cx)?;
+    let len = arr.len(cx) as usize;
+
+    limits::charge(cx, Resource::Items, len)?;
+
+    let mut values = Vec::with_capacity(len);
+
+    for v in arr.to_vec(cx)? {
+        match T::try_from_js(cx, v)? {
+            Some(v) => values.push(v),
+            None => {
+                return cx.throw_type_error(format!(
+                    "expected {} at index {}; `Vec<{}>` can be extracted from {}",
+                    element,
+                    values.len(),
+                    std::any::type_name::<T>(),
+                    accepted,
+                ))
+            }
+        }
+    }
+
+    Ok(Some(values))
+}
+
+// Extracts a `Vec<f64>` from an array of numbers or a typed array. The elements of a
+// `Float64Array` are copied and the elements of other typed arrays are converted
+// exactly. BigInt typed arrays are not accepted, like BigInts.
+pub(super) fn f64_vec<'cx, C: Context<'cx>>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+) -> NeonResult<Option<Vec<f64>>> {
+    let info = match typed_array_info(cx, v) {
+        Some(info) => info,
+        None => {
+            return vec_from_array(
+                cx,
+                v,
+                "a number",
+                "an array of numbers or a typed array other than BigInt64Array and \
+                 BigUint64Array",
+            )
+        }
+    };
+
+    limits::charge(cx, Resource::BufferBytes, info.byte_length())?;
+
+    let values = unsafe {
+        match info.typ {
+            TypedArrayType::F64 => elements::<f64>(&info).to_vec(),
+            TypedArrayType::F32 => widen::<f32>(&info),
+            TypedArrayType::I8 => widen::<i8>(&info),
+            TypedArrayType::U8 | TypedArrayType::U8Clamped => widen::<u8>(&info),
+            TypedArrayType::I16 => widen::<i16>(&info),
+            TypedArrayType::U16 => widen::<u16>(&info),
+            TypedArrayType::I32 => widen::<i32>(&info),
+            TypedArrayType::U32 => widen::<u32>(&info),
+            TypedArrayType::I64 | TypedArrayType::U64 => return Ok(None),
+        }
+    };
+
+    // Elements of other typed arrays are always finite
+    #[cfg(feature = "napi-6")]
+    if matches!(info.typ, TypedArrayType::F64 | TypedArrayType::F32) {
+        super::float::check_policy_all(cx, &values)?;
+    }
+
+    Ok(Some(values))
+}
+
+// Extracts a `Vec` of 64-bit integers from an array of numbers and BigInts or a typed
+// array. The elements of a typed array of the same type are copied. Other elements
+// are converted like numbers and BigInts, throwing a `RangeError` naming the index of
+// the first element that is out of range.
+#[cfg(feature = "napi-6")]
+pub(super) fn int64_vec<'cx, C, T>(
+    cx: &mut C,
+    v: Handle<'cx, JsValue>,
+) -> NeonResult<Option<Vec<T>>>
+where
+    C: Context<'cx>,
+    T: Int64 + Binary + TryFrom<i128> + TryFromJs<'cx>,
+{
+    let info = match typed_array_info(cx, v) {
+        Some(info) => info,
+        None => {
+            return vec_from_array(
+                cx,
+                v,
+                "a number or BigInt",
+                "an array of numbers and BigInts or a typed array",
+            )
+        }
+    };
+
+    limits::charge(cx, Resource::BufferBytes, info.byte_length())?;
+
+    let values = unsafe {
+        match info.typ {
+            typ if typ == T::TYPE_TAG => Ok(elements::<T>(&info).to_vec()),
+            TypedArrayType::F32 => to_int64::<f32, T>(&info),
+            TypedArrayType::F64 => to_int64::<f64, T>(&info),
+            TypedArrayType::I8 => to_int64::<i8, T>(&info),
+            TypedArrayType::U8 | TypedArrayType::U8Clamped => to_int64::<u8, T>(&info),
+            TypedArrayType::I16 => to_int64::<i16, T>(&info),
+            TypedArrayType::U16 => to_int64::<u16, T>(&info),
+            TypedArrayType::I32 => to_int64::<i32, T>(&info),
+            TypedArrayType::U32 => to_int64::<u32, T>(&info),
+            TypedArrayType::I64 => to_int64::<i64, T>(&info),
+            TypedArrayType::U64 => to_int64::<u64, T>(&info),
+        }
+    };
+
+    let (i, value) = match values {
+        Ok(values) => return Ok(Some(values)),
+        Err(invalid) => invalid,
+    };
+
+    let (range, value) = match info.typ {
+        TypedArrayType::I64 | TypedArrayType::U64 => (T::BIGINT_RANGE, format!("{}n", value)),
+        _ => (T::NUMBER_RANGE, value),
+    };
+
+    let name = format!("element {}", i);
+
+    result::throw_out_of_range(cx, Some(&name), range, &value, || {
+        format!(
+            "element {} ({}) is out of range for `{}`",
+            i,
+            value,
+            T::NAME
+        )
+    })
+}
+
+// Converts the elements of a typed array to a 64-bit integer, returning the index and
+// value of the first element that is out of range
+//
+// # Safety
+// See `elements`
+#[cfg(feature = "napi-6")]
+unsafe fn to_int64<E, T>(info: &TypedArrayInfo) -> Result<Vec<T>, (usize, String)>
+where
+    E: Element,
+    T: Int64 + TryFrom<i128>,
+{
+    elements::<E>(info)
+        .iter()
+        .enumerate()
+        .map(|(i, &v)| v.to_int64().ok_or_else(|| (i, v.to_string())))
+        .collect()
+}
+
+// An element of a typed array that can be converted to a 64-bit integer
+#[cfg(feature = "napi-6")]
+trait Element: Copy + ToString {
+    fn to_int64<T: Int64 + TryFrom<i128>>(self) -> Option<T>;
+}
+
+#[cfg(feature = "napi-6")]
+macro_rules! impl_int_element {
+    ($($ty:ty),*) => {
+        $(
+            impl Element for $ty {
+                fn to_int64<T: Int64 + TryFrom<i128>>(self) -> Option<T> {
+                    T::try_from(i128::from(self)).ok()
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "napi-6")]
+impl_int_element!(i8, u8, i16, u16, i32, u32, i64, u64);
+
+#[cfg(feature = "napi-6")]
+macro_rules! impl_float_element {
+    ($($ty:ty),*) => {
+        $(
+            // Like numbers, floats must be safe integers
+            impl Element for $ty {
+                fn to_int64<T: Int64 + TryFrom<i128>>(self) -> Option<T> {
+                    let n = f64::from(self);
+
+                    if n.fract() != 0.0 || n.abs() > MAX_SAFE_INTEGER {
+                        return None;
+                    }
+
+                    T::from_safe_integer(n)
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "napi-6")]
+impl_float_element!(f32, f64);
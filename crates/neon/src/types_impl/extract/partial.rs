@@ -0,0 +1,190 @@
+use std::{any, fmt};
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    limits::{self, Resource},
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{
+        extract::{path, TryFromJs, TryIntoJs},
+        JsArray, JsObject, JsValue, Value,
+    },
+};
+
+/// An element of an array that could not be extracted by [`Partial`]
+///
+/// Converted to JavaScript as an object with the properties `index`, `path` and
+/// `message`, so a list of errors can be returned with the extracted values.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ElementError {
+    /// The index of the element
+    pub index: usize,
+    /// The path to the value that failed, starting with the index, for example,
+    /// `[12]` or `[12].name` if the error of a nested extraction had a
+    /// `conversionPath`
+    pub path: String,
+    /// The message of the exception thrown while extracting the element, or a
+    /// description of the expected type if it was not of that type
+    pub message: String,
+}
+
+impl fmt::Display for ElementError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (at {})", self.message, self.path)
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for ElementError {
+    type Value = JsObject;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        let obj = cx.empty_object();
+        let index = cx.number(self.index as f64);
+        let path = cx.string(self.path);
+        let message = cx.string(self.message);
+
+        obj.set(cx, "index", index)?;
+        obj.set(cx, "path", path)?;
+        obj.set(cx, "message", message)?;
+
+        Ok(obj)
+    }
+}
+
+/// Extracts the elements of an array that can be extracted, and an [`ElementError`]
+/// for each one that can't
+///
+/// Extracting a `Vec` fails as a whole if a single element can't be extracted.
+/// `Partial` keeps going: the values of the elements that were extracted are in the
+/// first `Vec`, in order, and the errors of the others in the second, so the
+/// function can process the good elements and report the bad ones. An element is an
+/// error if it is not of the expected type or if an exception is thrown while reading
+/// or extracting it.
+///
+/// After more than [`max_errors`](Partial::try_from_js_with) errors, the extraction
+/// stops and a `RangeError` is thrown, so that a completely wrong input fails fast.
+/// Extracting with [`TryFromJs`] allows [`DEFAULT_MAX_ERRORS`](Partial::DEFAULT_MAX_ERRORS).
+/// Only arrays are extracted.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{Partial, TryFromJs, TryIntoJs};
+///
+/// // Returns `[sum, errors]`
+/// fn sum_valid(mut cx: FunctionContext) -> JsResult<JsArray> {
+///     let v = cx.argument::<JsValue>(0)?;
+///     let Partial(numbers, errors) = match Partial::<f64>::try_from_js_with(&mut cx, v, 10)? {
+///         Some(partial) => partial,
+///         None => return cx.throw_type_error("expected an array"),
+///     };
+///
+///     let result = cx.empty_array();
+///     let sum = cx.number(numbers.iter().sum::<f64>());
+///     let errors = errors.try_into_js(&mut cx)?;
+///
+///     result.set(&mut cx, 0, sum)?;
+///     result.set(&mut cx, 1, errors)?;
+///
+///     Ok(result)
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Partial<T>(pub Vec<T>, pub Vec<ElementError>);
+
+impl<T> Partial<T> {
+    /// The number of errors allowed when extracting with [`TryFromJs`]
+    pub const DEFAULT_MAX_ERRORS: usize = 100;
+}
+
+impl<'cx, T> Partial<T>
+where
+    T: TryFromJs<'cx>,
+{
+    /// Extracts the elements of an array, throwing a `RangeError` after more than
+    /// `max_errors` errors
+    pub fn try_from_js_with<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+        max_errors: usize,
+    ) -> NeonResult<Option<Self>> {
+        let arr = match v.downcast::<JsArray, _>(cx) {
+            Ok(arr) => arr,
+            Err(_) => return Ok(None),
+        };
+
+        let _guard = limits::enter(cx)?;
+        let len = arr.len(cx);
+
+        limits::charge(cx, Resource::Items, len as usize)?;
+
+        let mut values = Vec::with_capacity(len as usize);
+        let mut errors = Vec::new();
+
+        for i in 0..len {
+            let error = match cx.try_catch(|cx| {
+                let v = arr.get_value(cx, i)?;
+
+                T::try_from_js(cx, v)
+            }) {
+                Ok(Some(v)) => {
+                    values.push(v);
+                    continue;
+                }
+                Ok(None) => ElementError {
+                    index: i as usize,
+                    path: format!("[{}]", i),
+                    message: format!("failed to extract `{}`", any::type_name::<T>()),
+                },
+                Err(err) => element_error(cx, i as usize, err),
+            };
+
+            if errors.len() == max_errors {
+                return cx.throw_range_error(format!(
+                    "more than {} elements could not be extracted; the first was {}",
+                    max_errors,
+                    errors.first().unwrap_or(&error),
+                ));
+            }
+
+            errors.push(error);
+        }
+
+        Ok(Some(Partial(values, errors)))
+    }
+}
+
+impl<'cx, T> TryFromJs<'cx> for Partial<T>
+where
+    T: TryFromJs<'cx>,
+{
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        Self::try_from_js_with(cx, v, Self::DEFAULT_MAX_ERRORS)
+    }
+}
+
+// Describes an exception thrown while extracting the element at `index`
+fn element_error<'cx, C>(cx: &mut C, index: usize, err: Handle<JsValue>) -> ElementError
+where
+    C: Context<'cx>,
+{
+    // Reading the message may throw again, e.g., from a getter
+    let described = cx.try_catch(|cx| match err.downcast::<JsObject, _>(cx) {
+        Ok(err) => Ok((
+            path::string_prop(cx, err, "message")?,
+            path::string_prop(cx, err, path::PATH_KEY)?,
+        )),
+        Err(_) => Ok((Some(err.to_string(cx)?.value(cx)), None)),
+    });
+
+    let (message, inner) = described.unwrap_or((None, None));
+
+    ElementError {
+        index,
+        path: format!("[{}]{}", index, inner.unwrap_or_default()),
+        message: message.unwrap_or_else(|| "an exception was thrown".to_string()),
+    }
+}
@@ -0,0 +1,181 @@
+use std::{fmt, mem::MaybeUninit};
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::Object,
+    result::{JsResult, NeonResult, Throw},
+    sys,
+    types::{extract::TryIntoJs, JsError, JsObject, JsString, JsUndefined, JsValue},
+};
+
+// Property of an error with the path to the value that failed to convert
+pub(super) const PATH_KEY: &str = "conversionPath";
+
+/// A step in the path from a value to a nested field or element, for reporting
+/// errors with [`try_into_js_at`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PathSegment<'a> {
+    /// An element of an array, displayed as `[12]`
+    Index(usize),
+    /// A property of an object, displayed as `.name`, or `["content-type"]` if the key
+    /// is not an identifier
+    Key(&'a str),
+}
+
+impl From<usize> for PathSegment<'_> {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl<'a> From<&'a str> for PathSegment<'a> {
+    fn from(key: &'a str) -> Self {
+        Self::Key(key)
+    }
+}
+
+impl fmt::Display for PathSegment<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "[{}]", index),
+            Self::Key(key) if is_identifier(key) => write!(f, ".{}", key),
+            Self::Key(key) => write!(f, "[{:?}]", key),
+        }
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    matches!(chars.next(), Some(c) if c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Converts `value`, a field or element of a larger value, adding `segment` to the
+/// path of the error if the conversion throws.
+///
+/// The containers implementing [`TryIntoJs`], such as `Vec` and `HashMap`, convert
+/// their contents with this function. Implementations for structs can do the same for
+/// each field, so that an error deep inside a structure reports where it occurred:
+///
+/// ```text
+/// Error: name is too long (while converting .results[12].name)
+/// ```
+///
+/// The error is not modified. Instead, a new `Error` with the path in its message is
+/// thrown, with the original error as its `cause` and the path as its
+/// `conversionPath` property, for example, `".results[12].name"`. An error thrown by
+/// a nested conversion is wrapped only once, by the outermost conversion. Values
+/// thrown that are not objects are thrown unchanged.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{try_into_js_at, TryIntoJs};
+///
+/// struct User {
+///     name: String,
+///     tags: Vec<String>,
+/// }
+///
+/// impl<'cx> TryIntoJs<'cx> for User {
+///     type Value = JsObject;
+///
+///     fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+///         let obj = cx.empty_object();
+///         let name = try_into_js_at(cx, "name", self.name)?;
+///         let tags = try_into_js_at(cx, "tags", self.tags)?;
+///
+///         obj.set(cx, "name", name)?;
+///         obj.set(cx, "tags", tags)?;
+///
+///         Ok(obj)
+///     }
+/// }
+/// ```
+pub fn try_into_js_at<'cx, 'a, C, T, S>(cx: &mut C, segment: S, value: T) -> JsResult<'cx, T::Value>
+where
+    C: Context<'cx>,
+    T: TryIntoJs<'cx>,
+    S: Into<PathSegment<'a>>,
+{
+    match value.try_into_js(cx) {
+        Ok(v) => Ok(v),
+        Err(throw) => annotate(cx, segment.into(), throw),
+    }
+}
+
+// Catches the pending exception and throws an error that wraps it, with `segment`
+// added to its path
+pub(super) fn annotate<'cx, C, T>(cx: &mut C, segment: PathSegment, throw: Throw) -> NeonResult<T>
+where
+    C: Context<'cx>,
+{
+    let mut local = MaybeUninit::zeroed();
+
+    // An implementation may return `Err` without throwing; there is nothing to annotate
+    if !unsafe { sys::error::catch_error(cx.env().to_raw(), local.as_mut_ptr()) } {
+        return Err(throw);
+    }
+
+    let error = JsValue::new_internal(unsafe { local.assume_init() });
+    let object = match error.downcast::<JsObject, _>(cx) {
+        Ok(object) => object,
+        Err(_) => return cx.throw(error),
+    };
+
+    // Reading the message may throw, e.g., from a getter. The original error is
+    // thrown then.
+    match cx.try_catch(|cx| wrap(cx, object, segment)) {
+        Ok(wrapper) => cx.throw(wrapper),
+        Err(_) => cx.throw(error),
+    }
+}
+
+// Creates an error with the path in its message, caused by `error`
+fn wrap<'cx, C>(
+    cx: &mut C,
+    error: Handle<'cx, JsObject>,
+    segment: PathSegment,
+) -> JsResult<'cx, JsError>
+where
+    C: Context<'cx>,
+{
+    let inner = string_prop(cx, error, PATH_KEY)?;
+    let cause = error.get_value(cx, "cause")?;
+
+    // An error thrown by a nested conversion was already wrapped; wrap its cause
+    // instead, with the longer path
+    let (cause, inner) = match inner {
+        Some(inner) if !cause.is_a::<JsUndefined, _>(cx) => (cause, inner),
+        _ => (error.upcast(), String::new()),
+    };
+
+    let path = format!("{}{}", segment, inner);
+    let message = match cause.downcast::<JsObject, _>(cx) {
+        Ok(cause) => string_prop(cx, cause, "message")?,
+        Err(_) => None,
+    };
+
+    let message = message.unwrap_or_else(|| "an exception was thrown".to_string());
+    let wrapper = cx.error(format!("{} (while converting {})", message, path))?;
+    let path = cx.string(path);
+
+    wrapper.set(cx, "cause", cause)?;
+    wrapper.set(cx, PATH_KEY, path)?;
+
+    Ok(wrapper)
+}
+
+pub(super) fn string_prop<'cx, C>(
+    cx: &mut C,
+    obj: Handle<JsObject>,
+    key: &str,
+) -> NeonResult<Option<String>>
+where
+    C: Context<'cx>,
+{
+    let v = obj.get_value(cx, key)?;
+
+    Ok(v.downcast::<JsString, _>(cx).ok().map(|v| v.value(cx)))
+}
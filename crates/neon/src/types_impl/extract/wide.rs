@@ -0,0 +1,66 @@
+use crate::{
+    context::Context,
+    handle::Handle,
+    limits::{self, Resource},
+    result::{JsResult, NeonResult},
+    types::{JsString, JsValue},
+};
+
+use super::{TryFromJs, TryIntoJs};
+
+/// A string as UTF-16 code units, such as for the wide strings of Windows APIs
+///
+/// Extracts only a string, copying its code units once with [`JsString::to_wide`]
+/// instead of converting to UTF-8 and back. Unpaired surrogates and embedded nuls are
+/// kept unchanged, and no nul terminator is added; push a `0` before passing the
+/// code units to an API expecting a nul-terminated string.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::extract::{TryFromJs, TryIntoJs, WideString};
+///
+/// fn upper(mut cx: FunctionContext) -> JsResult<JsString> {
+///     let v = cx.argument::<JsValue>(0)?;
+///     let mut wide = match WideString::try_from_js(&mut cx, v)? {
+///         Some(WideString(wide)) => wide,
+///         None => return cx.throw_type_error("expected a string"),
+///     };
+///
+///     for c in &mut wide {
+///         if (u16::from(b'a')..=u16::from(b'z')).contains(c) {
+///             *c -= 32;
+///         }
+///     }
+///
+///     WideString(wide).try_into_js(&mut cx)
+/// }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct WideString(pub Vec<u16>);
+
+impl<'cx> TryFromJs<'cx> for WideString {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let s = match v.downcast::<JsString, _>(cx) {
+            Ok(s) => s,
+            Err(_) => return Ok(None),
+        };
+
+        let len = s.size(cx) as usize;
+
+        limits::charge(cx, Resource::StringBytes, len)?;
+
+        Ok(Some(WideString(s.to_wide(cx))))
+    }
+}
+
+/// Converts the code units unchanged, like [`JsString::from_wide`]
+impl<'cx> TryIntoJs<'cx> for WideString {
+    type Value = JsString;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        JsString::try_from_wide(cx, &self.0).or_else(|err| cx.throw_range_error(err.to_string()))
+    }
+}
@@ -7,10 +7,19 @@ use crate::{
     handle::Handle,
     object::Object,
     result::{JsResult, NeonResult},
-    types::{JsFunction, JsObject, JsValue, Value},
+    types::{
+        extract::{TryFromJs, TryIntoJs},
+        JsFunction, JsObject, JsUndefined, JsValue, Value,
+    },
 };
 
 pub(crate) mod private;
+#[cfg(feature = "napi-6")]
+pub(crate) mod wrap;
+
+#[cfg(feature = "napi-6")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+pub use self::wrap::{Hooks, Invocation};
 
 /// A builder for making a JavaScript function call like `parseInt("42")`.
 ///
@@ -47,6 +56,26 @@ impl<'a> CallOptions<'a> {
         self
     }
 
+    /// Add an optional argument to the arguments list, converted with [`TryIntoJs`].
+    /// `None` is passed as `undefined`, like an omitted argument, so that JavaScript
+    /// default parameters apply.
+    ///
+    /// Since the argument is `Option<T>`, a nested `Option<Option<T>>` passes `undefined`
+    /// for `None` and `null` for `Some(None)`.
+    pub fn arg_opt<C: Context<'a>, T: TryIntoJs<'a>>(
+        &mut self,
+        cx: &mut C,
+        arg: Option<T>,
+    ) -> NeonResult<&mut Self> {
+        let arg = match arg {
+            Some(arg) => arg.try_into_js(cx)?.upcast(),
+            None => cx.undefined().upcast(),
+        };
+
+        self.args.push(arg);
+        Ok(self)
+    }
+
     /// Replaces the arguments list with the given arguments.
     pub fn args<A: Arguments<'a>>(&mut self, args: A) -> &mut Self {
         self.args = args.into_args_vec();
@@ -61,6 +90,38 @@ impl<'a> CallOptions<'a> {
         v.downcast_or_throw(cx)
     }
 
+    /// Make the function call and extract the result value into a Rust type `T`.
+    ///
+    /// If the function throws, the exception is propagated. If the result value can't be
+    /// extracted, a `TypeError` naming the expected Rust type is thrown instead.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # fn foo(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    /// # let global = cx.global();
+    /// # let parse_int: Handle<JsFunction> = global.get(&mut cx, "parseInt")?;
+    /// let x: f64 = parse_int
+    ///     .call_with(&cx)
+    ///     .arg(cx.string("42"))
+    ///     .apply_into(&mut cx)?;
+    /// # Ok(cx.number(x))
+    /// # }
+    /// ```
+    pub fn apply_into<'b: 'a, T: TryFromJs<'b>, C: Context<'b>>(
+        &self,
+        cx: &mut C,
+    ) -> NeonResult<T> {
+        let v: Handle<JsValue> = self.apply(cx)?;
+
+        match T::try_from_js(cx, v)? {
+            Some(v) => Ok(v),
+            None => cx.throw_type_error(format!(
+                "expected function to return a value convertible to `{}`",
+                std::any::type_name::<T>(),
+            )),
+        }
+    }
+
     /// Make the function call for side effect, discarding the result value. This method is
     /// preferable to [`apply()`](CallOptions::apply) when the result value isn't needed,
     /// since it doesn't require specifying a result type.
@@ -69,6 +130,21 @@ impl<'a> CallOptions<'a> {
         self.callee.call(cx, this, &self.args)?;
         Ok(())
     }
+
+    /// Make the function call for side effect, like [`exec()`](CallOptions::exec), but
+    /// throw a `TypeError` if the function returns anything other than `undefined`.
+    ///
+    /// This is stricter than most JavaScript APIs and is useful for catching callbacks
+    /// that accidentally return a value, such as a `Promise`, when none was expected.
+    pub fn exec_checked<'b: 'a, C: Context<'b>>(&self, cx: &mut C) -> NeonResult<()> {
+        let v: Handle<JsValue> = self.apply(cx)?;
+
+        if !v.is_a::<JsUndefined, _>(cx) {
+            return cx.throw_type_error("expected function to return `undefined`");
+        }
+
+        Ok(())
+    }
 }
 
 /// A builder for making a JavaScript constructor call like `new Array(16)`.
@@ -99,6 +175,26 @@ impl<'a> ConstructOptions<'a> {
         self
     }
 
+    /// Add an optional argument to the arguments list, converted with [`TryIntoJs`].
+    /// `None` is passed as `undefined`, like an omitted argument, so that JavaScript
+    /// default parameters apply.
+    ///
+    /// Since the argument is `Option<T>`, a nested `Option<Option<T>>` passes `undefined`
+    /// for `None` and `null` for `Some(None)`.
+    pub fn arg_opt<C: Context<'a>, T: TryIntoJs<'a>>(
+        &mut self,
+        cx: &mut C,
+        arg: Option<T>,
+    ) -> NeonResult<&mut Self> {
+        let arg = match arg {
+            Some(arg) => arg.try_into_js(cx)?.upcast(),
+            None => cx.undefined().upcast(),
+        };
+
+        self.args.push(arg);
+        Ok(self)
+    }
+
     /// Replaces the arguments list with the given arguments.
     pub fn args<A: Arguments<'a>>(&mut self, args: A) -> &mut Self {
         self.args = args.into_args_vec();
@@ -0,0 +1,203 @@
+use crate::{
+    context::{CallKind, Context, FunctionContext},
+    handle::Handle,
+    object::Object,
+    result::{JsResult, NeonResult},
+    types::{JsArray, JsFunction, JsObject, JsValue},
+};
+
+/// Hooks called by a function created with [`JsFunction::wrap`](crate::types::JsFunction::wrap).
+///
+/// Every hook has a default implementation that passes the call through unchanged.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::function::{Hooks, Invocation};
+///
+/// // Logs the number of arguments for each call
+/// struct Trace;
+///
+/// impl Hooks for Trace {
+///     fn before<'a>(
+///         &self,
+///         _cx: &mut FunctionContext<'a>,
+///         call: &Invocation<'a>,
+///     ) -> NeonResult<Option<Handle<'a, JsValue>>> {
+///         println!("called with {} arguments", call.args().len());
+///         Ok(None)
+///     }
+/// }
+///
+/// fn trace(mut cx: FunctionContext) -> JsResult<JsFunction> {
+///     let target = cx.argument::<JsFunction>(0)?;
+///
+///     JsFunction::wrap(&mut cx, target, Trace)
+/// }
+/// ```
+pub trait Hooks: 'static {
+    /// Called before the target function. Returning `Some(value)` skips the
+    /// target and the other hooks, and `value` is returned to the caller.
+    fn before<'a>(
+        &self,
+        cx: &mut FunctionContext<'a>,
+        call: &Invocation<'a>,
+    ) -> NeonResult<Option<Handle<'a, JsValue>>> {
+        let _ = (cx, call);
+        Ok(None)
+    }
+
+    /// Called with the value returned by the target function. The value returned
+    /// by this hook is returned to the caller.
+    fn after<'a>(
+        &self,
+        cx: &mut FunctionContext<'a>,
+        result: Handle<'a, JsValue>,
+    ) -> JsResult<'a, JsValue> {
+        let _ = cx;
+        Ok(result)
+    }
+
+    /// Called with the exception thrown by the target function. After the hook
+    /// returns, the exception is re-thrown. If the hook throws, its exception
+    /// replaces the original one.
+    fn on_throw<'a>(
+        &self,
+        cx: &mut FunctionContext<'a>,
+        error: Handle<'a, JsValue>,
+    ) -> NeonResult<()> {
+        let _ = (cx, error);
+        Ok(())
+    }
+}
+
+/// Describes a call to a function created with [`JsFunction::wrap`](crate::types::JsFunction::wrap)
+pub struct Invocation<'a> {
+    this: Handle<'a, JsValue>,
+    args: Vec<Handle<'a, JsValue>>,
+    kind: CallKind,
+    new_target: Option<Handle<'a, JsValue>>,
+}
+
+impl<'a> Invocation<'a> {
+    /// The `this` binding of the call
+    pub fn this(&self) -> Handle<'a, JsValue> {
+        self.this
+    }
+
+    /// Arguments passed to the call
+    pub fn args(&self) -> &[Handle<'a, JsValue>] {
+        &self.args
+    }
+
+    /// Indicates whether the function was called with `new`
+    pub fn kind(&self) -> CallKind {
+        self.kind
+    }
+
+    /// The `new.target` of a call with `new`: the wrapper, or a class extending it
+    pub fn new_target(&self) -> Option<Handle<'a, JsValue>> {
+        self.new_target
+    }
+}
+
+pub(crate) fn wrap<'a, C, H>(
+    cx: &mut C,
+    target: Handle<JsFunction>,
+    hooks: H,
+) -> JsResult<'a, JsFunction>
+where
+    C: Context<'a>,
+    H: Hooks,
+{
+    let root = target.root(cx);
+    let wrapper = JsFunction::new(cx, move |mut cx| {
+        let args = (0..cx.len())
+            .filter_map(|i| cx.argument_opt(i))
+            .collect::<Vec<_>>();
+
+        let new_target = cx.new_target();
+        let call = Invocation {
+            this: cx.this_value(),
+            args,
+            kind: cx.kind(),
+            new_target,
+        };
+
+        if let Some(value) = hooks.before(&mut cx, &call)? {
+            return Ok(value);
+        }
+
+        let target = root.to_inner(&mut cx);
+        let result = cx.try_catch(|cx| match call.new_target {
+            Some(new_target) => construct(cx, target, &call.args, new_target),
+            None => target.call(cx, call.this, &call.args),
+        });
+
+        match result {
+            Ok(value) => hooks.after(&mut cx, value),
+            Err(err) => {
+                hooks.on_throw(&mut cx, err)?;
+                cx.throw(err)
+            }
+        }
+    })?;
+
+    copy_property(cx, target, wrapper, "name")?;
+    copy_property(cx, target, wrapper, "length")?;
+
+    // Instances created with `new` on the wrapper, which is their `new.target`, get
+    // the prototype of the target
+    let prototype = target.get_value(cx, "prototype")?;
+
+    if prototype.is_a::<JsObject, _>(cx) {
+        wrapper.set(cx, "prototype", prototype)?;
+    }
+
+    Ok(wrapper)
+}
+
+// Constructs `target` with the `new.target` of the call to the wrapper, like
+// `Reflect.construct`, so that a class extending the wrapper constructs an instance
+// of the class
+fn construct<'a, C: Context<'a>>(
+    cx: &mut C,
+    target: Handle<JsFunction>,
+    args: &[Handle<'a, JsValue>],
+    new_target: Handle<'a, JsValue>,
+) -> JsResult<'a, JsValue> {
+    let reflect = cx.global().get::<JsObject, _, _>(cx, "Reflect")?;
+    let construct = reflect.get::<JsFunction, _, _>(cx, "construct")?;
+    let array = JsArray::new(cx, args.len() as u32);
+
+    for (i, arg) in args.iter().enumerate() {
+        array.set(cx, i as u32, *arg)?;
+    }
+
+    construct.call(cx, reflect, [target.upcast(), array.upcast(), new_target])
+}
+
+// `name` and `length` are read-only, but configurable, and must be redefined
+// with `Object.defineProperty`
+fn copy_property<'a, C: Context<'a>>(
+    cx: &mut C,
+    from: Handle<JsFunction>,
+    to: Handle<JsFunction>,
+    key: &str,
+) -> NeonResult<()> {
+    let object = cx.global().get::<JsFunction, _, _>(cx, "Object")?;
+    let define = object.get::<JsFunction, _, _>(cx, "defineProperty")?;
+    let descriptor = cx.empty_object();
+    let value = from.get_value(cx, key)?;
+    let configurable = cx.boolean(true);
+    let key = cx.string(key);
+
+    descriptor.set(cx, "value", value)?;
+    descriptor.set(cx, "configurable", configurable)?;
+    define.call(
+        cx,
+        object,
+        [to.upcast(), key.upcast(), descriptor.upcast::<JsValue>()],
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use std::{any, ops::Deref};
+
+use crate::{
+    context::{internal::Env, Context, FinalizeContext},
+    handle::{internal::TransparentNoCopyWrapper, Handle, Managed},
+    object::Object,
+    result::JsResult,
+    sys::{self, raw},
+    types::{
+        instance::private::InstanceInner, private::ValueInternal, Finalize, JsObject, JsValue,
+        Value,
+    },
+};
+
+mod private {
+    pub struct InstanceInner<T: Send + 'static> {
+        pub(super) local: crate::sys::raw::Local,
+        // Cached raw pointer to the data wrapped by the object. See `JsBoxInner` for
+        // the safety requirements; the data is owned by the object and is only dropped
+        // when the object is garbage collected.
+        pub(super) raw_data: *const T,
+    }
+}
+
+/// A JavaScript object wrapping Rust data of type `T`.
+///
+/// Like a [`JsBox`](crate::types::JsBox), an `Instance` gives access to the Rust data
+/// with `Deref`. Unlike a `JsBox`, the data is attached to an ordinary JavaScript object
+/// that may have properties and a prototype, for example, the `this` object of a class
+/// constructor. This allows passing instances of a class between functions without
+/// losing their type.
+///
+/// Objects are marked with a [type tag](https://nodejs.org/api/n-api.html#object-type-tagging)
+/// that is unique to `T`, so a `Handle<Instance<T>>` can only be downcast from objects
+/// wrapping a `T`. Instances of a JavaScript subclass are objects wrapping a `T` and may
+/// be downcast. An object can only wrap a single value.
+///
+/// Since `Instance<T>` is a [`Value`], `Handle<Instance<T>>` implements
+/// [`TryFromJs`](crate::types::extract::TryFromJs) and
+/// [`TryIntoJs`](crate::types::extract::TryIntoJs), and may be rooted and sent across
+/// threads as a [`Root`](crate::handle::Root).
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::Instance;
+///
+/// struct Database {
+///     name: String,
+/// }
+///
+/// impl Finalize for Database {}
+///
+/// // Called as `new Database(name)`
+/// fn database_new(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+///     let name = cx.argument::<JsString>(0)?.value(&mut cx);
+///     let this = cx.this::<JsObject>()?;
+///
+///     Instance::wrap(&mut cx, this, Database { name })?;
+///
+///     Ok(cx.undefined())
+/// }
+///
+/// fn query(mut cx: FunctionContext) -> JsResult<JsString> {
+///     // Throws a `TypeError` if the argument is not a `Database`
+///     let db = cx.argument::<Instance<Database>>(0)?;
+///     let sql = cx.argument::<JsString>(1)?.value(&mut cx);
+///
+///     Ok(cx.string(format!("{}: {}", db.name, sql)))
+/// }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-8")))]
+#[repr(transparent)]
+pub struct Instance<T: Send + 'static>(InstanceInner<T>);
+
+impl<T: Send + 'static> std::fmt::Debug for InstanceInner<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Instance<{}>", class_name::<T>())
+    }
+}
+
+impl<T: Send + 'static> std::fmt::Debug for Instance<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+// Custom `Clone` implementation since `T` might not be `Clone`
+impl<T: Send + 'static> Clone for InstanceInner<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T: Send + 'static> Copy for InstanceInner<T> {}
+
+impl<T: Send + 'static> Object for Instance<T> {}
+
+impl<T: Send + 'static> Value for Instance<T> {}
+
+unsafe impl<T: Send + 'static> TransparentNoCopyWrapper for Instance<T> {
+    type Inner = InstanceInner<T>;
+
+    fn into_inner(self) -> Self::Inner {
+        self.0
+    }
+}
+
+impl<T: Send + 'static> Managed for Instance<T> {
+    fn to_raw(&self) -> raw::Local {
+        self.0.local
+    }
+
+    // Only called with values that were already checked to be an `Instance<T>`, so
+    // reads the pointer without checking the type again, which could fail while an
+    // exception is pending
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn from_raw(env: Env, local: raw::Local) -> Self {
+        let raw_data = unsafe { sys::wrap::wrapped::<T>(env.to_raw(), local) };
+
+        Self(InstanceInner { local, raw_data })
+    }
+}
+
+impl<T: Send + 'static> ValueInternal for Instance<T> {
+    fn name() -> String {
+        class_name::<T>()
+    }
+
+    fn is_typeof<Other: Value>(env: Env, other: &Other) -> bool {
+        unsafe { unwrap::<T>(env, other.to_raw()) }.is_some()
+    }
+
+    fn downcast<Other: Value>(env: Env, other: &Other) -> Option<Self> {
+        let local = other.to_raw();
+
+        unsafe { unwrap::<T>(env, local) }.map(|raw_data| Self(InstanceInner { local, raw_data }))
+    }
+}
+
+impl<T: Finalize + Send + 'static> Instance<T> {
+    /// Attaches `value` to `object`, for example, the `this` object of a constructor.
+    ///
+    /// Throws a `TypeError` if `object` already wraps a value.
+    pub fn wrap<'a, C: Context<'a>>(
+        cx: &mut C,
+        object: Handle<JsObject>,
+        value: T,
+    ) -> JsResult<'a, Instance<T>> {
+        // Called immediately before the object is garbage collected
+        fn finalizer<U: Finalize + 'static>(env: raw::Env, data: U) {
+            let env = unsafe { std::mem::transmute::<raw::Env, Env>(env) };
+
+            FinalizeContext::with(env, move |mut cx| data.finalize(&mut cx));
+        }
+
+        let env = cx.env().to_raw();
+        let local = object.to_raw();
+        let tag = crate::type_tag::<T>();
+
+        match unsafe { sys::wrap::wrap(env, local, &tag, value, finalizer::<T>) } {
+            Ok(raw_data) => Ok(Handle::new_internal(Self(InstanceInner {
+                local,
+                raw_data,
+            }))),
+            Err(_) => cx.throw_type_error("object already wraps a value"),
+        }
+    }
+
+    /// Creates an empty object wrapping `value`
+    pub fn new<'a, C: Context<'a>>(cx: &mut C, value: T) -> JsResult<'a, Instance<T>> {
+        let object = cx.empty_object();
+
+        Self::wrap(cx, object, value)
+    }
+}
+
+impl<T: Send + 'static> Instance<T> {
+    /// Downcasts `v` to an `Instance<T>`, throwing a `TypeError` of the form
+    /// `expected Database instance` if it does not wrap a `T`.
+    pub fn extract<'a, C: Context<'a>>(
+        cx: &mut C,
+        v: Handle<'a, JsValue>,
+    ) -> JsResult<'a, Instance<T>> {
+        match v.downcast::<Instance<T>, _>(cx) {
+            Ok(v) => Ok(v),
+            Err(_) => cx.throw_type_error(format!("expected {} instance", class_name::<T>())),
+        }
+    }
+}
+
+impl<T: Send + 'static> Deref for Instance<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        // Safety: This depends on a `Handle<'a, Instance<T>>` wrapper to provide
+        // a proper lifetime.
+        unsafe { &*self.0.raw_data }
+    }
+}
+
+unsafe fn unwrap<T: Send + 'static>(env: Env, local: raw::Local) -> Option<*const T> {
+    sys::wrap::unwrap(env.to_raw(), local, &crate::type_tag::<T>())
+}
+
+// The name of `T` without its module path or generic arguments, e.g., `Database`
+fn class_name<T>() -> String {
+    let name = any::type_name::<T>();
+    let name = name.split('<').next().unwrap_or(name);
+
+    name.rsplit("::").next().unwrap_or(name).to_string()
+}
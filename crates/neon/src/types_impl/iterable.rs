@@ -0,0 +1,207 @@
+//! Driving JavaScript iterables from Rust.
+
+use std::ops::ControlFlow;
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    object::Object,
+    result::NeonResult,
+    types::{
+        extract::TryFromJs, JsArray, JsBoolean, JsFunction, JsNull, JsNumber, JsObject,
+        JsUndefined, JsValue, Value,
+    },
+};
+
+/// A JavaScript object that implements the
+/// [iterable protocol](https://developer.mozilla.org/en-US/docs/Web/JavaScript/Reference/Iteration_protocols#the_iterable_protocol),
+/// such as an `Array`, `Set`, `Map` or generator.
+///
+/// If iteration stops before the iterator is done, because the callback breaks or
+/// throws, the iterator's `return()` method is called so that it can clean up, for
+/// example, by running the `finally` block of a generator. This matches the behavior
+/// of a JavaScript `for...of` loop.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::JsIterable;
+///
+/// fn sum(mut cx: FunctionContext) -> JsResult<JsNumber> {
+///     let v = cx.argument::<JsValue>(0)?;
+///     let numbers: Vec<f64> = JsIterable::from(&mut cx, v)?.collect_vec(&mut cx)?;
+///
+///     Ok(cx.number(numbers.iter().sum::<f64>()))
+/// }
+/// ```
+pub struct JsIterable<'a> {
+    value: Handle<'a, JsObject>,
+    iterator: Handle<'a, JsFunction>,
+}
+
+impl<'a> JsIterable<'a> {
+    /// Checks that `value` is an iterable object.
+    ///
+    /// Throws a `TypeError` if `value` is not an object, including `null` and
+    /// `undefined`, or if its `[Symbol.iterator]` property is not a function.
+    pub fn from<C: Context<'a>, V: Value>(cx: &mut C, value: Handle<'a, V>) -> NeonResult<Self> {
+        let value = match value.downcast::<JsObject, _>(cx) {
+            Ok(value) => value,
+            Err(_) => return cx.throw_type_error("value is not iterable"),
+        };
+
+        let symbol = cx
+            .global()
+            .get::<JsFunction, _, _>(cx, "Symbol")?
+            .get_value(cx, "iterator")?;
+
+        match value.get_value(cx, symbol)?.downcast::<JsFunction, _>(cx) {
+            Ok(iterator) => Ok(Self { value, iterator }),
+            Err(_) => cx.throw_type_error("value is not iterable"),
+        }
+    }
+
+    /// Returns the iterable object
+    pub fn value(&self) -> Handle<'a, JsObject> {
+        self.value
+    }
+
+    /// Returns the number of items, if known without iterating.
+    ///
+    /// The length of an `Array` is used, otherwise the numeric `length` or `size`
+    /// property (e.g., of a `Set` or `Map`), if present.
+    pub fn size_hint<C: Context<'a>>(&self, cx: &mut C) -> NeonResult<Option<usize>> {
+        if let Ok(arr) = self.value.downcast::<JsArray, _>(cx) {
+            return Ok(Some(arr.len(cx) as usize));
+        }
+
+        for key in ["length", "size"] {
+            let size = self.value.get_value(cx, key)?;
+
+            if let Ok(size) = size.downcast::<JsNumber, _>(cx) {
+                let size = size.value(cx);
+
+                if size >= 0.0 && size.fract() == 0.0 && size <= usize::MAX as f64 {
+                    return Ok(Some(size as usize));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Calls `f` with each item until the iterator is done or `f` returns
+    /// [`ControlFlow::Break`].
+    ///
+    /// If `f` breaks or throws, the iterator is closed by calling its `return()`
+    /// method. An exception thrown by `f` takes precedence over an exception thrown
+    /// by `return()`. If the iterator itself throws, it is not closed.
+    pub fn try_for_each<C, F>(&self, cx: &mut C, mut f: F) -> NeonResult<()>
+    where
+        C: Context<'a>,
+        F: FnMut(&mut C, Handle<'a, JsValue>) -> NeonResult<ControlFlow<()>>,
+    {
+        let iterator = self
+            .iterator
+            .call(cx, self.value, [])?
+            .downcast::<JsObject, _>(cx)
+            .or_else(|_| cx.throw_type_error("iterator is not an object"))?;
+
+        let next = iterator
+            .get_value(cx, "next")?
+            .downcast::<JsFunction, _>(cx)
+            .or_else(|_| cx.throw_type_error("iterator `next` is not a function"))?;
+
+        loop {
+            let result = next
+                .call(cx, iterator, [])?
+                .downcast::<JsObject, _>(cx)
+                .or_else(|_| cx.throw_type_error("iterator result is not an object"))?;
+
+            let done = result.get_value(cx, "done")?;
+
+            if is_truthy(cx, done)? {
+                return Ok(());
+            }
+
+            let value = result.get_value(cx, "value")?;
+
+            match cx.try_catch(|cx| f(cx, value)) {
+                Ok(ControlFlow::Continue(())) => {}
+                Ok(ControlFlow::Break(())) => return close(cx, iterator),
+                Err(err) => {
+                    // The original exception takes precedence
+                    let _ = cx.try_catch(|cx| close(cx, iterator));
+
+                    return cx.throw(err);
+                }
+            }
+        }
+    }
+
+    /// Collects the items into a `Vec`, using the [size hint](JsIterable::size_hint)
+    /// to reserve capacity for up to 1024 items. The hint is controlled by
+    /// JavaScript, e.g., a sparse array or an object with a large `length`, so more
+    /// capacity is only allocated as the items are collected.
+    ///
+    /// Throws a `TypeError` naming the expected Rust type if an item can't be
+    /// extracted.
+    pub fn collect_vec<C, T>(&self, cx: &mut C) -> NeonResult<Vec<T>>
+    where
+        C: Context<'a>,
+        T: TryFromJs<'a>,
+    {
+        const MAX_RESERVED: usize = 1024;
+
+        let reserved = self.size_hint(cx)?.unwrap_or(0).min(MAX_RESERVED);
+        let mut items = Vec::with_capacity(reserved);
+
+        self.try_for_each(cx, |cx, v| match T::try_from_js(cx, v)? {
+            Some(item) => {
+                items.push(item);
+                Ok(ControlFlow::Continue(()))
+            }
+            None => cx.throw_type_error(format!(
+                "iterable item at index {} could not be converted to `{}`",
+                items.len(),
+                std::any::type_name::<T>(),
+            )),
+        })?;
+
+        Ok(items)
+    }
+}
+
+// Calls the iterator's `return()` method, if it has one
+fn close<'a, C: Context<'a>>(cx: &mut C, iterator: Handle<'a, JsObject>) -> NeonResult<()> {
+    let finish = iterator.get_value(cx, "return")?;
+
+    if finish.is_a::<JsUndefined, _>(cx) || finish.is_a::<JsNull, _>(cx) {
+        return Ok(());
+    }
+
+    finish
+        .downcast::<JsFunction, _>(cx)
+        .or_else(|_| cx.throw_type_error("iterator `return` is not a function"))?
+        .call(cx, iterator, [])?;
+
+    Ok(())
+}
+
+// JavaScript `Boolean(v)`
+fn is_truthy<'a, C: Context<'a>>(cx: &mut C, v: Handle<'a, JsValue>) -> NeonResult<bool> {
+    if let Ok(b) = v.downcast::<JsBoolean, _>(cx) {
+        return Ok(b.value(cx));
+    }
+
+    if v.is_a::<JsUndefined, _>(cx) {
+        return Ok(false);
+    }
+
+    let boolean = cx.global().get::<JsFunction, _, _>(cx, "Boolean")?;
+    let this = cx.undefined();
+
+    boolean
+        .call(cx, this, [v])?
+        .downcast_or_throw::<JsBoolean, _>(cx)
+        .map(|b| b.value(cx))
+}
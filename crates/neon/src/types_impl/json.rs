@@ -0,0 +1,654 @@
+//! Streaming conversion of JSON text to and from JavaScript.
+//!
+//! Serializing a large Rust structure to a single JavaScript string requires memory
+//! for both the Rust `String` and the JavaScript string. [`stringify_stream`] instead
+//! writes JSON text into a fixed size buffer that is passed to a JavaScript callback
+//! as a string each time it fills. [`JsonParser`] is the reverse; it builds a
+//! JavaScript value incrementally from chunks of JSON text.
+//!
+//! ```
+//! # use neon::prelude::*;
+//! use std::fmt::Write;
+//!
+//! use neon::types::json;
+//!
+//! // Writes `[0,1,2,...]` to `sink` in chunks of at most 64 KiB
+//! fn range_json(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+//!     let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+//!     let sink = cx.argument::<JsFunction>(1)?;
+//!
+//!     json::stringify_stream(
+//!         &mut cx,
+//!         |w| {
+//!             w.write_char('[')?;
+//!
+//!             for i in 0..n {
+//!                 if i > 0 {
+//!                     w.write_char(',')?;
+//!                 }
+//!
+//!                 write!(w, "{}", i)?;
+//!             }
+//!
+//!             w.write_char(']')
+//!         },
+//!         64 * 1024,
+//!         sink,
+//!     )?;
+//!
+//!     Ok(cx.undefined())
+//! }
+//! ```
+
+use std::{
+    char,
+    fmt::{self, Write},
+    mem,
+};
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    limits,
+    object::{self, Object},
+    result::{JsResult, NeonResult, Throw},
+    types::{JsArray, JsFunction, JsObject, JsString, JsValue},
+};
+
+/// Writes JSON text to `sink` in chunks of at most `chunk_size` bytes of UTF-8.
+///
+/// `write` produces the JSON text with the [`fmt::Write`] implementation of
+/// [`JsonWriter`]. Each time the buffer fills, `sink` is called with the chunk as a
+/// string. Chunks always end on a character boundary, even if a character is larger
+/// than `chunk_size`. Each chunk is created in its own handle scope, so that it may be
+/// garbage collected once `sink` returns.
+///
+/// If `sink` throws, `write` fails with [`fmt::Error`] and the exception is
+/// propagated. If `write` fails for any other reason, an `Error` is thrown.
+pub fn stringify_stream<'a, C, F>(
+    cx: &mut C,
+    write: F,
+    chunk_size: usize,
+    sink: Handle<'a, JsFunction>,
+) -> NeonResult<()>
+where
+    C: Context<'a>,
+    F: FnOnce(&mut JsonWriter<'_, 'a, C>) -> fmt::Result,
+{
+    let chunk_size = chunk_size.max(1);
+    let mut writer = JsonWriter {
+        cx,
+        sink,
+        buf: String::with_capacity(chunk_size),
+        chunk_size,
+        throw: None,
+    };
+
+    let result = write(&mut writer).and_then(|_| writer.flush());
+
+    match (result, writer.throw) {
+        (_, Some(throw)) => Err(throw),
+        (Ok(()), None) => Ok(()),
+        (Err(_), None) => writer.cx.throw_error("failed to write JSON"),
+    }
+}
+
+/// The buffer of JSON text written by [`stringify_stream`]
+pub struct JsonWriter<'w, 'a, C: Context<'a>> {
+    cx: &'w mut C,
+    sink: Handle<'a, JsFunction>,
+    buf: String,
+    chunk_size: usize,
+    // Exception thrown by `sink`
+    throw: Option<Throw>,
+}
+
+impl<'w, 'a, C: Context<'a>> JsonWriter<'w, 'a, C> {
+    /// Writes `s` as a quoted and escaped JSON string
+    pub fn write_json_string(&mut self, s: &str) -> fmt::Result {
+        self.write_char('"')?;
+
+        let mut start = 0;
+
+        for (i, c) in s.char_indices() {
+            let escaped = match c {
+                '"' => "\\\"",
+                '\\' => "\\\\",
+                '\n' => "\\n",
+                '\r' => "\\r",
+                '\t' => "\\t",
+                '\u{8}' => "\\b",
+                '\u{c}' => "\\f",
+                c if c < ' ' => "",
+                _ => continue,
+            };
+
+            self.write_str(&s[start..i])?;
+
+            if escaped.is_empty() {
+                write!(self, "\\u{:04x}", c as u32)?;
+            } else {
+                self.write_str(escaped)?;
+            }
+
+            start = i + c.len_utf8();
+        }
+
+        self.write_str(&s[start..])?;
+        self.write_char('"')
+    }
+
+    /// Writes `n` as a JSON number.
+    ///
+    /// JSON can't represent `NaN` or `±Infinity`. They are written as `null`, like
+    /// `JSON.stringify`, unless the instance of the addon has the
+    /// [`FloatPolicy::Reject`](crate::types::extract::FloatPolicy::Reject) policy, in
+    /// which case a `RangeError` is thrown. Negative zero is written as `-0`.
+    pub fn write_json_number(&mut self, n: f64) -> fmt::Result {
+        if self.throw.is_some() {
+            return Err(fmt::Error);
+        }
+
+        if !n.is_finite() {
+            #[cfg(feature = "napi-6")]
+            if let Err(throw) = crate::types::extract::float::check_policy(self.cx, n) {
+                self.throw = Some(throw);
+                return Err(fmt::Error);
+            }
+
+            return self.write_str("null");
+        }
+
+        // Exponents are used outside of the same range as JavaScript
+        if n == 0.0 || (1e-7..1e21).contains(&n.abs()) {
+            write!(self, "{}", n)
+        } else {
+            write!(self, "{:e}", n)
+        }
+    }
+
+    // Sends the buffered chunk to the sink
+    fn flush(&mut self) -> fmt::Result {
+        if self.throw.is_some() {
+            return Err(fmt::Error);
+        }
+
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+
+        let sink = self.sink;
+        let buf = &self.buf;
+        let result = self.cx.execute_scoped(|mut cx| {
+            let chunk = cx.string(buf).upcast::<JsValue>();
+            let this = cx.undefined();
+
+            sink.exec(&mut cx, this, [chunk])
+        });
+
+        self.buf.clear();
+
+        result.map_err(|throw| {
+            self.throw = Some(throw);
+            fmt::Error
+        })
+    }
+}
+
+impl<'w, 'a, C: Context<'a>> Write for JsonWriter<'w, 'a, C> {
+    fn write_str(&mut self, mut s: &str) -> fmt::Result {
+        if self.throw.is_some() {
+            return Err(fmt::Error);
+        }
+
+        while self.buf.len() + s.len() > self.chunk_size {
+            let mut end = self.chunk_size - self.buf.len();
+
+            while !s.is_char_boundary(end) {
+                end -= 1;
+            }
+
+            // A character larger than the chunk size gets a chunk to itself
+            if end == 0 && self.buf.is_empty() {
+                end = s.chars().next().map(char::len_utf8).unwrap_or_default();
+            }
+
+            self.buf.push_str(&s[..end]);
+            self.flush()?;
+            s = &s[end..];
+        }
+
+        self.buf.push_str(s);
+
+        Ok(())
+    }
+}
+
+/// An incremental JSON parser that builds a JavaScript value from chunks of text.
+///
+/// Unlike `JSON.parse`, the text does not need to be held in memory at once; only the
+/// partially built value and the current token are retained between chunks. The value
+/// is built with the handles of the context passed to [`push`](JsonParser::push), so
+/// every chunk must be pushed with the same context.
+///
+/// Object properties are defined as own properties, like `JSON.parse`, so a
+/// `"__proto__"` key does not change the prototype of the object. Arrays and objects
+/// nested deeper than the [conversion depth](crate::limits#conversion-depth) of the
+/// instance throw a `RangeError`.
+///
+/// ```
+/// # use neon::prelude::*;
+/// use neon::types::json::JsonParser;
+///
+/// fn parse_chunks(mut cx: FunctionContext) -> JsResult<JsValue> {
+///     let mut parser = JsonParser::new();
+///
+///     for chunk in ["{\"na", "me\": \"ne", "on\"}"] {
+///         parser.push(&mut cx, chunk)?;
+///     }
+///
+///     parser.finish(&mut cx)
+/// }
+/// ```
+pub struct JsonParser<'a> {
+    stack: Vec<Container<'a>>,
+    root: Option<Handle<'a, JsValue>>,
+    expect: Expect,
+    token: Token,
+    // Characters consumed, for error messages
+    position: usize,
+}
+
+enum Container<'a> {
+    Array(Handle<'a, JsArray>, u32),
+    Object(Handle<'a, JsObject>, Option<Handle<'a, JsString>>),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    Value,
+    // After `[`
+    ValueOrEnd,
+    // After `{`
+    KeyOrEnd,
+    Key,
+    Colon,
+    CommaOrEnd,
+    // After the root value
+    Nothing,
+}
+
+enum Token {
+    None,
+    // The raw text of a string, before escapes are decoded
+    String { raw: String, escaped: bool },
+    Number(String),
+    Literal(String),
+}
+
+impl<'a> Default for JsonParser<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> JsonParser<'a> {
+    /// Creates a parser for a single JSON value
+    pub fn new() -> Self {
+        Self {
+            stack: Vec::new(),
+            root: None,
+            expect: Expect::Value,
+            token: Token::None,
+            position: 0,
+        }
+    }
+
+    /// Parses the next chunk of JSON text. Throws a `SyntaxError` if the text is not
+    /// valid JSON.
+    pub fn push<C: Context<'a>>(&mut self, cx: &mut C, chunk: &str) -> NeonResult<()> {
+        for c in chunk.chars() {
+            self.push_char(cx, c)?;
+            self.position += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Parses the next chunk of JSON text from a JavaScript string
+    pub fn push_string<C: Context<'a>>(
+        &mut self,
+        cx: &mut C,
+        chunk: Handle<JsString>,
+    ) -> NeonResult<()> {
+        let chunk = chunk.value(cx);
+
+        self.push(cx, &chunk)
+    }
+
+    /// Completes parsing and returns the value. Throws a `SyntaxError` if the text
+    /// ended before a complete value.
+    pub fn finish<C: Context<'a>>(mut self, cx: &mut C) -> JsResult<'a, JsValue> {
+        self.end_token(cx)?;
+
+        match self.root {
+            Some(root) if self.stack.is_empty() && matches!(self.token, Token::None) => Ok(root),
+            _ => throw_syntax_error(cx, "Unexpected end of JSON input"),
+        }
+    }
+
+    fn push_char<C: Context<'a>>(&mut self, cx: &mut C, c: char) -> NeonResult<()> {
+        match &mut self.token {
+            Token::String { raw, escaped } => {
+                if *escaped {
+                    *escaped = false;
+                } else if c == '\\' {
+                    *escaped = true;
+                } else if c == '"' {
+                    let raw = mem::take(raw);
+
+                    self.token = Token::None;
+
+                    return self.end_string(cx, &raw);
+                } else if c < ' ' {
+                    return self.unexpected(cx, c);
+                }
+
+                raw.push(c);
+
+                return Ok(());
+            }
+            Token::Number(text) if matches!(c, '0'..='9' | '+' | '-' | '.' | 'e' | 'E') => {
+                text.push(c);
+
+                return Ok(());
+            }
+            Token::Literal(text) if c.is_ascii_alphabetic() => {
+                text.push(c);
+
+                return Ok(());
+            }
+            _ => self.end_token(cx)?,
+        }
+
+        if matches!(c, ' ' | '\t' | '\n' | '\r') {
+            return Ok(());
+        }
+
+        match (self.expect, c) {
+            (Expect::ValueOrEnd, ']') | (Expect::KeyOrEnd, '}') => self.end_container(cx),
+            (Expect::Value | Expect::ValueOrEnd, _) => self.start_value(cx, c),
+            (Expect::Key | Expect::KeyOrEnd, '"') => {
+                self.start_string();
+
+                Ok(())
+            }
+            (Expect::Colon, ':') => {
+                self.expect = Expect::Value;
+
+                Ok(())
+            }
+            (Expect::CommaOrEnd, ',') => {
+                self.expect = match self.stack.last() {
+                    Some(Container::Object(..)) => Expect::Key,
+                    _ => Expect::Value,
+                };
+
+                Ok(())
+            }
+            (Expect::CommaOrEnd, ']')
+                if matches!(self.stack.last(), Some(Container::Array(..))) =>
+            {
+                self.end_container(cx)
+            }
+            (Expect::CommaOrEnd, '}')
+                if matches!(self.stack.last(), Some(Container::Object(..))) =>
+            {
+                self.end_container(cx)
+            }
+            _ => self.unexpected(cx, c),
+        }
+    }
+
+    fn start_value<C: Context<'a>>(&mut self, cx: &mut C, c: char) -> NeonResult<()> {
+        match c {
+            '"' => self.start_string(),
+            '-' | '0'..='9' => self.token = Token::Number(c.to_string()),
+            'a'..='z' => self.token = Token::Literal(c.to_string()),
+            '[' => {
+                limits::check_depth(cx, self.stack.len() + 1)?;
+
+                let array = cx.empty_array();
+
+                self.stack.push(Container::Array(array, 0));
+                self.expect = Expect::ValueOrEnd;
+            }
+            '{' => {
+                limits::check_depth(cx, self.stack.len() + 1)?;
+
+                let object = cx.empty_object();
+
+                self.stack.push(Container::Object(object, None));
+                self.expect = Expect::KeyOrEnd;
+            }
+            _ => return self.unexpected(cx, c),
+        }
+
+        Ok(())
+    }
+
+    fn start_string(&mut self) {
+        self.token = Token::String {
+            raw: String::new(),
+            escaped: false,
+        };
+    }
+
+    // Completes a number or literal that was terminated by the end of its text
+    fn end_token<C: Context<'a>>(&mut self, cx: &mut C) -> NeonResult<()> {
+        let v = match &self.token {
+            Token::Number(text) => match parse_number(text) {
+                Some(n) => {
+                    // Numbers too large for an `f64` are parsed as `±Infinity`
+                    #[cfg(feature = "napi-6")]
+                    let n = crate::types::extract::float::check_policy(cx, n)?;
+
+                    cx.number(n).upcast()
+                }
+                None => return throw_syntax_error(cx, format!("Invalid number {} in JSON", text)),
+            },
+            Token::Literal(text) => match text.as_str() {
+                "true" => cx.boolean(true).upcast(),
+                "false" => cx.boolean(false).upcast(),
+                "null" => cx.null().upcast(),
+                _ => return throw_syntax_error(cx, format!("Unexpected token {} in JSON", text)),
+            },
+            _ => return Ok(()),
+        };
+
+        self.token = Token::None;
+        self.end_value(cx, v)
+    }
+
+    fn end_string<C: Context<'a>>(&mut self, cx: &mut C, raw: &str) -> NeonResult<()> {
+        let s = if !raw.contains('\\') {
+            cx.string(raw)
+        } else {
+            match unescape(raw) {
+                Some(units) => JsString::from_wide(cx, &units),
+                None => return throw_syntax_error(cx, "Bad escaped character in JSON"),
+            }
+        };
+
+        if let Expect::Key | Expect::KeyOrEnd = self.expect {
+            if let Some(Container::Object(_, key)) = self.stack.last_mut() {
+                *key = Some(s);
+            }
+
+            self.expect = Expect::Colon;
+
+            return Ok(());
+        }
+
+        self.end_value(cx, s.upcast())
+    }
+
+    fn end_container<C: Context<'a>>(&mut self, cx: &mut C) -> NeonResult<()> {
+        let v = match self.stack.pop() {
+            Some(Container::Array(array, _)) => array.upcast(),
+            Some(Container::Object(object, _)) => object.upcast(),
+            None => unreachable!("JsonParser ended a container that was never started"),
+        };
+
+        self.end_value(cx, v)
+    }
+
+    // Adds a complete value to the container being parsed
+    fn end_value<C: Context<'a>>(&mut self, cx: &mut C, v: Handle<'a, JsValue>) -> NeonResult<()> {
+        self.expect = Expect::CommaOrEnd;
+
+        match self.stack.last_mut() {
+            Some(Container::Array(array, len)) => {
+                array.set(cx, *len, v)?;
+                *len += 1;
+            }
+            Some(Container::Object(object, key)) => {
+                let key = match key.take() {
+                    Some(key) => key,
+                    None => cx.string(""),
+                };
+
+                // Like `set_safe_with` with `KeyPolicy::AllowOwn`, for keys that
+                // are not valid UTF-8
+                object::define_own(cx, &**object, key, v)?;
+            }
+            None => {
+                self.root = Some(v);
+                self.expect = Expect::Nothing;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn unexpected<C: Context<'a>, T>(&self, cx: &mut C, c: char) -> NeonResult<T> {
+        throw_syntax_error(
+            cx,
+            format!(
+                "Unexpected token {} in JSON at position {}",
+                c.escape_debug(),
+                self.position
+            ),
+        )
+    }
+}
+
+fn throw_syntax_error<'a, C: Context<'a>, T>(cx: &mut C, msg: impl AsRef<str>) -> NeonResult<T> {
+    let msg = cx.string(msg).upcast::<JsValue>();
+    let err = cx
+        .global()
+        .get::<JsFunction, _, _>(cx, "SyntaxError")?
+        .construct(cx, [msg])?;
+
+    cx.throw(err)
+}
+
+// Parses a number with the grammar of JSON, which is stricter than `f64::from_str`
+fn parse_number(text: &str) -> Option<f64> {
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let rest = text.strip_prefix('-').unwrap_or(text);
+    let int = digits(rest);
+
+    if int == 0 || (int > 1 && rest.starts_with('0')) {
+        return None;
+    }
+
+    let mut rest = &rest[int..];
+
+    if let Some(fraction) = rest.strip_prefix('.') {
+        let n = digits(fraction);
+
+        if n == 0 {
+            return None;
+        }
+
+        rest = &fraction[n..];
+    }
+
+    if let Some(exponent) = rest.strip_prefix(|c| c == 'e' || c == 'E') {
+        let exponent = exponent
+            .strip_prefix(|c| c == '+' || c == '-')
+            .unwrap_or(exponent);
+        let n = digits(exponent);
+
+        if n == 0 {
+            return None;
+        }
+
+        rest = &exponent[n..];
+    }
+
+    if !rest.is_empty() {
+        return None;
+    }
+
+    text.parse().ok()
+}
+
+// Decodes the escapes of a JSON string to UTF-16. Like `JSON.parse`, escaped lone
+// surrogates are kept.
+fn unescape(raw: &str) -> Option<Vec<u16>> {
+    let mut units = Vec::with_capacity(raw.len());
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            let mut buf = [0; 2];
+
+            units.extend_from_slice(c.encode_utf16(&mut buf));
+            continue;
+        }
+
+        let unit = match chars.next()? {
+            '"' => b'"' as u16,
+            '\\' => b'\\' as u16,
+            '/' => b'/' as u16,
+            'b' => 0x8,
+            'f' => 0xc,
+            'n' => b'\n' as u16,
+            'r' => b'\r' as u16,
+            't' => b'\t' as u16,
+            'u' => {
+                let hex = chars.by_ref().take(4).collect::<String>();
+
+                // Exactly four hex digits; `from_str_radix` also accepts a sign
+                if hex.len() != 4 || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return None;
+                }
+
+                u16::from_str_radix(&hex, 16).ok()?
+            }
+            _ => return None,
+        };
+
+        units.push(unit);
+    }
+
+    Some(units)
+}
+
+/// Parses chunks of JSON text into a JavaScript value with a [`JsonParser`]
+pub fn parse_stream<'a, C, I>(cx: &mut C, chunks: I) -> JsResult<'a, JsValue>
+where
+    C: Context<'a>,
+    I: IntoIterator,
+    I::Item: AsRef<str>,
+{
+    let mut parser = JsonParser::new();
+
+    for chunk in chunks {
+        parser.push(cx, chunk.as_ref())?;
+    }
+
+    parser.finish(cx)
+}
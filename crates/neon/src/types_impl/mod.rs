@@ -1,20 +1,34 @@
 // See types_docs.rs for top-level module API docs.
 
+#[cfg(feature = "napi-6")]
+pub(crate) mod bigint;
 pub(crate) mod boxed;
 pub mod buffer;
+pub mod custom;
 #[cfg(feature = "napi-5")]
 pub(crate) mod date;
+pub(crate) mod dict;
 pub(crate) mod error;
+pub mod extract;
 pub mod function;
+#[cfg(feature = "napi-8")]
+pub(crate) mod instance;
+pub(crate) mod iterable;
+pub mod json;
 pub(crate) mod promise;
 
 pub(crate) mod private;
+#[cfg(feature = "napi-8")]
+pub(crate) mod shared_box;
+pub(crate) mod string;
+#[cfg(feature = "napi-6")]
+pub(crate) mod url;
 pub(crate) mod utf8;
 
 use std::{
+    convert::TryFrom,
     fmt::{self, Debug},
     marker::PhantomData,
-    os::raw::c_void,
 };
 
 use smallvec::smallvec;
@@ -41,13 +55,33 @@ pub use self::{
         JsInt16Array, JsInt32Array, JsInt8Array, JsTypedArray, JsUint16Array, JsUint32Array,
         JsUint8Array,
     },
-    error::JsError,
+    dict::SafeDict,
+    error::{JsError, SendableError},
+    iterable::JsIterable,
     promise::{Deferred, JsPromise},
+    string::{PathEncoding, Utf8ErrorAt},
 };
 
+#[cfg(feature = "napi-8")]
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-8")))]
+pub use self::{instance::Instance, shared_box::SharedBoxToken};
+
 #[cfg(feature = "napi-5")]
 pub use self::date::{DateError, DateErrorKind, JsDate};
 
+#[cfg(feature = "napi-6")]
+pub use self::{bigint::JsBigInt, url::JsUrl};
+
+#[cfg(feature = "napi-6")]
+use {
+    crate::{handle::Root, thread::LocalKey},
+    std::ops::Range,
+};
+
+#[cfg(feature = "napi-6")]
+// `Array.prototype.fill`
+static ARRAY_FILL: LocalKey<Root<JsFunction>> = LocalKey::new();
+
 #[cfg(all(feature = "napi-5", feature = "futures"))]
 #[cfg_attr(docsrs, doc(cfg(all(feature = "napi-5", feature = "futures"))))]
 pub use self::promise::JsFuture;
@@ -139,16 +173,12 @@ impl JsValue {
 pub struct JsUndefined(raw::Local);
 
 impl JsUndefined {
+    #[track_caller]
     pub fn new<'a, C: Context<'a>>(cx: &mut C) -> Handle<'a, JsUndefined> {
-        JsUndefined::new_internal(cx.env())
-    }
+        let env = cx.env();
+        let local = cx.constants().undefined(env);
 
-    pub(crate) fn new_internal<'a>(env: Env) -> Handle<'a, JsUndefined> {
-        unsafe {
-            let mut local: raw::Local = std::mem::zeroed();
-            sys::primitive::undefined(&mut local, env.to_raw());
-            Handle::new_internal(JsUndefined(local))
-        }
+        Handle::new_internal(JsUndefined(local))
     }
 }
 
@@ -188,16 +218,12 @@ impl private::ValueInternal for JsUndefined {
 pub struct JsNull(raw::Local);
 
 impl JsNull {
+    #[track_caller]
     pub fn new<'a, C: Context<'a>>(cx: &mut C) -> Handle<'a, JsNull> {
-        JsNull::new_internal(cx.env())
-    }
+        let env = cx.env();
+        let local = cx.constants().null(env);
 
-    pub(crate) fn new_internal<'a>(env: Env) -> Handle<'a, JsNull> {
-        unsafe {
-            let mut local: raw::Local = std::mem::zeroed();
-            sys::primitive::null(&mut local, env.to_raw());
-            Handle::new_internal(JsNull(local))
-        }
+        Handle::new_internal(JsNull(local))
     }
 }
 
@@ -237,16 +263,12 @@ impl private::ValueInternal for JsNull {
 pub struct JsBoolean(raw::Local);
 
 impl JsBoolean {
+    #[track_caller]
     pub fn new<'a, C: Context<'a>>(cx: &mut C, b: bool) -> Handle<'a, JsBoolean> {
-        JsBoolean::new_internal(cx.env(), b)
-    }
+        let env = cx.env();
+        let local = cx.constants().boolean(env, b);
 
-    pub(crate) fn new_internal<'a>(env: Env, b: bool) -> Handle<'a, JsBoolean> {
-        unsafe {
-            let mut local: raw::Local = std::mem::zeroed();
-            sys::primitive::boolean(&mut local, env.to_raw(), b);
-            Handle::new_internal(JsBoolean(local))
-        }
+        Handle::new_internal(JsBoolean(local))
     }
 
     pub fn value<'a, C: Context<'a>>(&self, cx: &mut C) -> bool {
@@ -398,16 +420,12 @@ impl JsString {
 pub struct JsNumber(raw::Local);
 
 impl JsNumber {
+    #[track_caller]
     pub fn new<'a, C: Context<'a>, T: Into<f64>>(cx: &mut C, x: T) -> Handle<'a, JsNumber> {
-        JsNumber::new_internal(cx.env(), x.into())
-    }
+        let env = cx.env();
+        let local = cx.constants().number(env, x.into());
 
-    pub(crate) fn new_internal<'a>(env: Env, v: f64) -> Handle<'a, JsNumber> {
-        unsafe {
-            let mut local: raw::Local = std::mem::zeroed();
-            sys::primitive::number(&mut local, env.to_raw(), v);
-            Handle::new_internal(JsNumber(local))
-        }
+        Handle::new_internal(JsNumber(local))
     }
 
     pub fn value<'a, C: Context<'a>>(&self, cx: &mut C) -> f64 {
@@ -546,6 +564,127 @@ impl JsArray {
     pub fn is_empty<'a, C: Context<'a>>(&self, cx: &mut C) -> bool {
         self.len(cx) == 0
     }
+
+    /// Sets the length of the array by writing its `length` property.
+    ///
+    /// Growing the array allocates once for the new length instead of each time an
+    /// element is written past the end, which is useful when the array is filled
+    /// out of order. The new elements are holes that read as `undefined`. Shrinking
+    /// the array deletes the elements past the new length.
+    pub fn set_len<'a, C: Context<'a>>(&self, cx: &mut C, len: u32) -> NeonResult<()> {
+        let len = cx.number(len);
+
+        self.set(cx, "length", len)?;
+
+        Ok(())
+    }
+
+    /// Shortens the array to `len` elements, deleting the rest. Has no effect if the
+    /// array is already `len` elements or shorter.
+    pub fn truncate<'a, C: Context<'a>>(&self, cx: &mut C, len: u32) -> NeonResult<()> {
+        if len < self.len(cx) {
+            self.set_len(cx, len)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes `values` to the elements starting at index `start`. If the range is past
+    /// the end of the array, the array is grown once with
+    /// [`set_len`](JsArray::set_len) before any element is written.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn chunks(mut cx: FunctionContext) -> JsResult<JsArray> {
+    ///     let array = cx.empty_array();
+    ///     let chunk = [cx.number(3), cx.number(4)];
+    ///
+    ///     // [<2 empty items>, 3, 4]
+    ///     array.set_range(&mut cx, 2, &chunk)?;
+    ///
+    ///     Ok(array)
+    /// }
+    /// ```
+    ///
+    /// Throws a `RangeError` if the range ends past the largest possible length of
+    /// an array.
+    pub fn set_range<'a, C: Context<'a>, V: Value>(
+        &self,
+        cx: &mut C,
+        start: u32,
+        values: &[Handle<V>],
+    ) -> NeonResult<()> {
+        let end = match u32::try_from(values.len())
+            .ok()
+            .and_then(|len| start.checked_add(len))
+        {
+            Some(end) => end,
+            None => return cx.throw_range_error("Invalid array length"),
+        };
+
+        if end > self.len(cx) {
+            self.set_len(cx, end)?;
+        }
+
+        for (i, v) in (start..end).zip(values) {
+            self.set(cx, i, *v)?;
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Writes `value` to each element in `range` with `Array.prototype.fill`. If the
+    /// range is past the end of the array, the array is grown once with
+    /// [`set_len`](JsArray::set_len) before the elements are written.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// fn zeros(mut cx: FunctionContext) -> JsResult<JsArray> {
+    ///     let array = cx.empty_array();
+    ///     let zero = cx.number(0);
+    ///
+    ///     array.fill(&mut cx, zero, 0..1_000_000)?;
+    ///
+    ///     Ok(array)
+    /// }
+    /// ```
+    pub fn fill<'a, C: Context<'a>, V: Value>(
+        &self,
+        cx: &mut C,
+        value: Handle<'a, V>,
+        range: Range<u32>,
+    ) -> NeonResult<()> {
+        if range.is_empty() {
+            return Ok(());
+        }
+
+        if range.end > self.len(cx) {
+            self.set_len(cx, range.end)?;
+        }
+
+        let fill = ARRAY_FILL
+            .get_or_try_init(cx, |cx| {
+                let global = cx.global();
+                let array = global.get::<JsFunction, _, _>(cx, "Array")?;
+                let prototype = array.get::<JsObject, _, _>(cx, "prototype")?;
+
+                prototype
+                    .get::<JsFunction, _, _>(cx, "fill")
+                    .map(|fill| fill.root(cx))
+            })?
+            .to_inner(cx);
+
+        let this = Handle::new_internal(JsArray(self.to_raw()));
+
+        fill.call_with(cx)
+            .this(this)
+            .arg(value)
+            .arg(cx.number(range.start))
+            .arg(cx.number(range.end))
+            .exec(cx)
+    }
 }
 
 impl Value for JsArray {}
@@ -663,14 +802,26 @@ impl<T: Object> Object for JsFunction<T> {}
 // Maximum number of function arguments in V8.
 const V8_ARGC_LIMIT: usize = 65535;
 
-unsafe fn prepare_call<'a, 'b, C: Context<'a>>(
+// The arguments of a call as an array of `napi_value`
+#[cfg(not(feature = "debug-handles"))]
+type Argv<'c> = &'c [raw::Local];
+
+// With `debug-handles`, a `Handle` also carries its brand, and each argument is
+// checked when it is copied. Calls with few arguments copy them to the stack.
+#[cfg(feature = "debug-handles")]
+type Argv<'c> = smallvec::SmallVec<[raw::Local; 8]>;
+
+unsafe fn prepare_call<'a, 'b, 'c, C: Context<'a>>(
     cx: &mut C,
-    args: &[Handle<'b, JsValue>],
-) -> NeonResult<(i32, *const c_void)> {
+    args: &'c [Handle<'b, JsValue>],
+) -> NeonResult<(i32, Argv<'c>)> {
     // Note: This cast is only save because `Handle<'_, JsValue>` is
     // guaranteed to have the same layout as a pointer because `Handle`
     // and `JsValue` are both `repr(C)` newtypes.
-    let argv = args.as_ptr().cast();
+    #[cfg(not(feature = "debug-handles"))]
+    let argv = std::slice::from_raw_parts(args.as_ptr().cast::<raw::Local>(), args.len());
+    #[cfg(feature = "debug-handles")]
+    let argv = args.iter().map(|arg| arg.to_raw()).collect();
     let argc = args.len();
     if argc > V8_ARGC_LIMIT {
         return cx.throw_range_error("too many arguments");
@@ -756,7 +907,14 @@ impl<CL: Object> JsFunction<CL> {
         let (argc, argv) = unsafe { prepare_call(cx, args.as_ref()) }?;
         let env = cx.env().to_raw();
         build(cx.env(), |out| unsafe {
-            sys::fun::call(out, env, self.to_raw(), this.to_raw(), argc, argv)
+            sys::fun::call(
+                out,
+                env,
+                self.to_raw(),
+                this.to_raw(),
+                argc,
+                argv.as_ptr().cast(),
+            )
         })
     }
 
@@ -774,6 +932,63 @@ impl<CL: Object> JsFunction<CL> {
         Ok(())
     }
 
+    /// Calls the function with one argument, like [`call`](JsFunction::call).
+    ///
+    /// `call1`, [`call2`](JsFunction::call2) and [`call3`](JsFunction::call3) pass
+    /// their arguments to the engine in an array on the stack, without assembling an
+    /// arguments list, for callbacks invoked many times.
+    pub fn call1<'a, C: Context<'a>, T: Value, A: Value>(
+        &self,
+        cx: &mut C,
+        this: Handle<T>,
+        a: Handle<A>,
+    ) -> JsResult<'a, JsValue> {
+        self.call_raw(cx, this, &[a.to_raw()])
+    }
+
+    /// Calls the function with two arguments, like [`call1`](JsFunction::call1).
+    pub fn call2<'a, C: Context<'a>, T: Value, A: Value, B: Value>(
+        &self,
+        cx: &mut C,
+        this: Handle<T>,
+        a: Handle<A>,
+        b: Handle<B>,
+    ) -> JsResult<'a, JsValue> {
+        self.call_raw(cx, this, &[a.to_raw(), b.to_raw()])
+    }
+
+    /// Calls the function with three arguments, like [`call1`](JsFunction::call1).
+    pub fn call3<'a, C: Context<'a>, T: Value, A: Value, B: Value, D: Value>(
+        &self,
+        cx: &mut C,
+        this: Handle<T>,
+        a: Handle<A>,
+        b: Handle<B>,
+        c: Handle<D>,
+    ) -> JsResult<'a, JsValue> {
+        self.call_raw(cx, this, &[a.to_raw(), b.to_raw(), c.to_raw()])
+    }
+
+    fn call_raw<'a, C: Context<'a>, T: Value>(
+        &self,
+        cx: &mut C,
+        this: Handle<T>,
+        argv: &[raw::Local],
+    ) -> JsResult<'a, JsValue> {
+        let env = cx.env().to_raw();
+
+        build(cx.env(), |out| unsafe {
+            sys::fun::call(
+                out,
+                env,
+                self.to_raw(),
+                this.to_raw(),
+                argv.len() as i32,
+                argv.as_ptr().cast(),
+            )
+        })
+    }
+
     pub fn construct<'a, 'b, C: Context<'a>, AS>(&self, cx: &mut C, args: AS) -> JsResult<'a, CL>
     where
         AS: AsRef<[Handle<'b, JsValue>]>,
@@ -781,7 +996,7 @@ impl<CL: Object> JsFunction<CL> {
         let (argc, argv) = unsafe { prepare_call(cx, args.as_ref()) }?;
         let env = cx.env().to_raw();
         build(cx.env(), |out| unsafe {
-            sys::fun::construct(out, env, self.to_raw(), argc, argv)
+            sys::fun::construct(out, env, self.to_raw(), argc, argv.as_ptr().cast())
         })
     }
 }
@@ -813,6 +1028,26 @@ impl JsFunction {
         }
     }
 
+    #[cfg(feature = "napi-6")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-6")))]
+    /// Creates a function that calls `target`, with [`Hooks`](function::Hooks)
+    /// called before and after.
+    ///
+    /// The wrapper passes through its `this` binding and all arguments. When called
+    /// with `new`, the wrapper constructs `target`. The `name` and `length`
+    /// properties are copied from `target`.
+    pub fn wrap<'a, C, H>(
+        cx: &mut C,
+        target: Handle<JsFunction>,
+        hooks: H,
+    ) -> JsResult<'a, JsFunction>
+    where
+        C: Context<'a>,
+        H: function::Hooks,
+    {
+        function::wrap::wrap(cx, target, hooks)
+    }
+
     /// # Safety
     /// The caller must wrap in a `Handle` with an appropriate lifetime.
     unsafe fn clone(&self) -> Self {
@@ -12,14 +12,15 @@ use crate::{
 #[cfg(feature = "napi-4")]
 use crate::{
     context::TaskContext,
-    event::{Channel, JoinHandle, SendError},
+    event::{Channel, JoinHandle, Priority, SendError},
+    types::{JsError, SendableError},
 };
 
 #[cfg(feature = "napi-6")]
-use crate::{
-    lifecycle::{DropData, InstanceData},
-    sys::tsfn::ThreadsafeFunction,
-};
+use crate::lifecycle::{DropData, DropQueue, InstanceData};
+
+#[cfg(feature = "operation-ids")]
+use crate::operation::{self, OperationId};
 
 #[cfg(all(feature = "napi-5", feature = "futures"))]
 use {
@@ -61,6 +62,8 @@ impl JsPromise {
             internal: Some(NodeApiDeferred(deferred)),
             #[cfg(feature = "napi-6")]
             drop_queue: InstanceData::drop_queue(cx),
+            #[cfg(feature = "operation-ids")]
+            operation: operation::current(cx),
         };
 
         (deferred, Handle::new_internal(JsPromise(promise)))
@@ -201,7 +204,9 @@ impl Object for JsPromise {}
 pub struct Deferred {
     internal: Option<NodeApiDeferred>,
     #[cfg(feature = "napi-6")]
-    drop_queue: Arc<ThreadsafeFunction<DropData>>,
+    drop_queue: Arc<DropQueue>,
+    #[cfg(feature = "operation-ids")]
+    operation: Option<OperationId>,
 }
 
 impl Deferred {
@@ -245,7 +250,8 @@ impl Deferred {
         V: Value,
         F: FnOnce(TaskContext) -> JsResult<V> + Send + 'static,
     {
-        channel.try_send(move |cx| {
+        // Settled with a high priority, so that a flooded channel doesn't delay it
+        channel.try_send_with_priority(Priority::High, move |cx| {
             self.try_catch_settle(cx, complete);
             Ok(())
         })
@@ -277,12 +283,31 @@ impl Deferred {
         self.try_settle_with(channel, complete).unwrap()
     }
 
+    #[cfg(feature = "napi-4")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "napi-4")))]
+    /// Reject the [`JsPromise`] with a [`SendableError`] converted to a JavaScript
+    /// `Error` on the main JavaScript thread.
+    ///
+    /// Panics if there is a libuv error.
+    pub fn reject_sendable(self, channel: &Channel, error: SendableError) -> JoinHandle<()> {
+        self.settle_with(channel, move |mut cx| -> JsResult<JsError> {
+            let error = error.to_js_error(&mut cx)?;
+
+            cx.throw(error)
+        })
+    }
+
     pub(crate) fn try_catch_settle<'a, C, V, F>(self, cx: C, f: F)
     where
         C: Context<'a>,
         V: Value,
         F: FnOnce(C) -> JsResult<'a, V>,
     {
+        // Settled in the operation the promise was created in, even if the channel
+        // or task settling it is not part of it
+        #[cfg(feature = "operation-ids")]
+        let _scope = operation::Scope::restore(cx.env().to_raw(), self.operation);
+
         unsafe {
             BOUNDARY.catch_failure(
                 cx.env().to_raw(),
@@ -295,6 +320,12 @@ impl Deferred {
         }
     }
 
+    #[cfg(feature = "operation-ids")]
+    /// The operation the promise was created in
+    pub(crate) fn operation(&self) -> Option<OperationId> {
+        self.operation
+    }
+
     pub(crate) fn into_inner(mut self) -> sys::Deferred {
         self.internal.take().unwrap().0
     }
@@ -341,7 +372,7 @@ impl Drop for Deferred {
     fn drop(&mut self) {
         // If `None`, the `Deferred` has already been settled
         if let Some(internal) = self.internal.take() {
-            let _ = self.drop_queue.call(DropData::Deferred(internal), None);
+            self.drop_queue.push(DropData::Deferred(internal));
         }
     }
 }
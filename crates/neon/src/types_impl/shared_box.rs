@@ -0,0 +1,279 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    convert::TryInto,
+    sync::{Arc, Mutex, MutexGuard, Weak},
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{
+    context::Context,
+    handle::Handle,
+    result::{JsResult, NeonResult},
+    sys::TypeTag,
+    types::{
+        buffer::TypedArray, extract::TryFromJs, extract::TryIntoJs, Finalize, JsArrayBuffer, JsBox,
+        JsUint8Array, JsValue,
+    },
+};
+
+const ID_LEN: usize = 16;
+const TOKEN_LEN: usize = ID_LEN + 16;
+
+type Data = Weak<dyn Any + Send + Sync>;
+
+// Minimum number of entries before the registry is swept for dropped data
+const MIN_SWEEP_LEN: usize = 16;
+
+// Boxes shared by every instance of the addon in the process, keyed by a random id.
+// Entries only hold a `Weak` reference; the data is owned by the boxes and is dropped
+// once every instance has finalized its box.
+static REGISTRY: Lazy<Mutex<Registry>> = Lazy::new(Default::default);
+
+#[derive(Default)]
+struct Registry {
+    entries: HashMap<[u8; ID_LEN], Registered>,
+    // Id of the entry of each shared `Arc`, keyed by the address of its data
+    ids: HashMap<usize, [u8; ID_LEN]>,
+    // Number of entries after the last sweep
+    swept_len: usize,
+}
+
+struct Registered {
+    tag: TypeTag,
+    data: Data,
+}
+
+impl Registry {
+    // Returns the id of `data` if it is registered and still alive
+    fn existing(&self, addr: usize, data: &Data) -> Option<[u8; ID_LEN]> {
+        let id = self.ids.get(&addr)?;
+        let entry = self.entries.get(id)?;
+
+        // The address may have been reused by new data after the data was dropped
+        (Weak::ptr_eq(&entry.data, data) && entry.data.strong_count() > 0).then_some(*id)
+    }
+
+    fn insert(&mut self, id: [u8; ID_LEN], addr: usize, entry: Registered) {
+        self.entries.insert(id, entry);
+        self.ids.insert(addr, id);
+
+        // Sweeping once the registry has doubled keeps the cost of each insertion
+        // constant on average, and frees the allocations kept by the `Weak` of data
+        // that was dropped without being shared or redeemed again
+        if self.entries.len() >= MIN_SWEEP_LEN.max(self.swept_len * 2) {
+            self.sweep();
+        }
+    }
+
+    fn remove(&mut self, id: &[u8; ID_LEN]) {
+        if let Some(entry) = self.entries.remove(id) {
+            let addr = addr(&entry.data);
+
+            if self.ids.get(&addr) == Some(id) {
+                self.ids.remove(&addr);
+            }
+        }
+    }
+
+    // Removes the entries of data that has been dropped
+    fn sweep(&mut self) {
+        let ids = &mut self.ids;
+
+        self.entries.retain(|id, entry| {
+            let alive = entry.data.strong_count() > 0;
+
+            if !alive && ids.get(&addr(&entry.data)) == Some(id) {
+                ids.remove(&addr(&entry.data));
+            }
+
+            alive
+        });
+
+        self.swept_len = self.entries.len();
+    }
+}
+
+// The address of the data of a shared `Arc`
+fn addr(data: &Data) -> usize {
+    data.as_ptr() as *const () as usize
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+fn same_tag(a: TypeTag, b: TypeTag) -> bool {
+    a.lower == b.lower && a.upper == b.upper
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-8")))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+/// A token for redeeming a [`JsBox`] that was shared with
+/// [`JsBox::share`](JsBox::share) in another instance of the addon, for example,
+/// in a worker thread.
+///
+/// The token is only bytes; it is converted to a JavaScript `Uint8Array` that may be
+/// sent with `postMessage`. It does not keep the shared data alive. Once every box of
+/// the shared data has been garbage collected, the token can no longer be redeemed.
+pub struct SharedBoxToken([u8; TOKEN_LEN]);
+
+impl SharedBoxToken {
+    /// The bytes of the token
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Reads a token from bytes, returning `None` if `bytes` is the wrong length
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        bytes.try_into().ok().map(Self)
+    }
+
+    fn id(&self) -> [u8; ID_LEN] {
+        let mut id = [0; ID_LEN];
+
+        id.copy_from_slice(&self.0[..ID_LEN]);
+        id
+    }
+
+    fn tag(&self) -> TypeTag {
+        let (lower, upper) = self.0[ID_LEN..].split_at(8);
+
+        TypeTag {
+            lower: u64::from_le_bytes(lower.try_into().unwrap()),
+            upper: u64::from_le_bytes(upper.try_into().unwrap()),
+        }
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for SharedBoxToken {
+    type Value = JsUint8Array;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        JsUint8Array::from_slice(cx, &self.0)
+    }
+}
+
+/// Extracted from a `Uint8Array` or `ArrayBuffer` containing the bytes of the token
+impl<'cx> TryFromJs<'cx> for SharedBoxToken {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if let Ok(v) = v.downcast::<JsUint8Array, _>(cx) {
+            return Ok(Self::from_bytes(v.as_slice(cx)));
+        }
+
+        if let Ok(v) = v.downcast::<JsArrayBuffer, _>(cx) {
+            return Ok(Self::from_bytes(v.as_slice(cx)));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(feature = "napi-8")))]
+impl<T: Finalize + Send + Sync + 'static> JsBox<Arc<T>> {
+    /// Registers the data of the box in a process-wide registry and returns a token
+    /// that can be redeemed with [`JsBox::from_token`] in any instance of the addon.
+    ///
+    /// Throws an `Error` if a random id for the token can't be generated.
+    ///
+    /// ```
+    /// # use neon::prelude::*;
+    /// # use std::sync::Arc;
+    /// use neon::types::{
+    ///     extract::{TryFromJs, TryIntoJs},
+    ///     SharedBoxToken,
+    /// };
+    ///
+    /// fn share_config(mut cx: FunctionContext) -> JsResult<JsValue> {
+    ///     let config = cx.argument::<JsBox<Arc<String>>>(0)?;
+    ///
+    ///     // Send the token to a worker with `postMessage`
+    ///     Ok(config.share(&mut cx)?.try_into_js(&mut cx)?.upcast())
+    /// }
+    ///
+    /// fn receive_config(mut cx: FunctionContext) -> JsResult<JsBox<Arc<String>>> {
+    ///     let token = cx.argument::<JsValue>(0)?;
+    ///     let token = match SharedBoxToken::try_from_js(&mut cx, token)? {
+    ///         Some(token) => token,
+    ///         None => return cx.throw_type_error("expected a shared box token"),
+    ///     };
+    ///
+    ///     JsBox::from_token(&mut cx, &token)
+    /// }
+    /// ```
+    pub fn share<'a, C: Context<'a>>(&self, cx: &mut C) -> NeonResult<SharedBoxToken> {
+        let tag = crate::type_tag::<T>();
+        let data = Arc::downgrade(&**self) as Data;
+        let addr = addr(&data);
+        let mut registry = lock(&REGISTRY);
+
+        // Sharing the same data again returns the same token
+        let id = match registry.existing(addr, &data) {
+            Some(id) => id,
+            None => loop {
+                let mut id = [0; ID_LEN];
+
+                if let Err(err) = getrandom::getrandom(&mut id) {
+                    drop(registry);
+
+                    return cx
+                        .throw_error(format!("failed to generate a shared box token: {}", err));
+                }
+
+                if !registry.entries.contains_key(&id) {
+                    registry.insert(id, addr, Registered { tag, data });
+                    break id;
+                }
+            },
+        };
+
+        let mut token = [0; TOKEN_LEN];
+
+        token[..ID_LEN].copy_from_slice(&id);
+        token[ID_LEN..ID_LEN + 8].copy_from_slice(&tag.lower.to_le_bytes());
+        token[ID_LEN + 8..].copy_from_slice(&tag.upper.to_le_bytes());
+
+        Ok(SharedBoxToken(token))
+    }
+
+    /// Creates a box in the current instance containing the same `Arc` as the box
+    /// that created `token` with [`JsBox::share`].
+    ///
+    /// Throws a `TypeError` if the token was shared from a box of a different type
+    /// and an `Error` if every box of the shared data has been garbage collected.
+    pub fn from_token<'a, C: Context<'a>>(
+        cx: &mut C,
+        token: &SharedBoxToken,
+    ) -> JsResult<'a, JsBox<Arc<T>>> {
+        let tag = crate::type_tag::<T>();
+
+        if !same_tag(token.tag(), tag) {
+            return cx.throw_type_error("shared box token is for a different type");
+        }
+
+        let data = {
+            let mut registry = lock(&REGISTRY);
+            let id = token.id();
+            let entry = registry.entries.get(&id);
+            let data = entry
+                .filter(|entry| same_tag(entry.tag, tag))
+                .and_then(|entry| entry.data.upgrade());
+
+            // The data was dropped, so the token can't be redeemed again
+            if entry.is_some_and(|entry| entry.data.strong_count() == 0) {
+                registry.remove(&id);
+            }
+
+            data
+        };
+
+        match data.and_then(|data| data.downcast::<T>().ok()) {
+            Some(data) => Ok(JsBox::new(cx, data)),
+            None => cx.throw_error("shared box token is no longer valid"),
+        }
+    }
+}
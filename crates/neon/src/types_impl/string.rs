@@ -0,0 +1,284 @@
+//! Creation of strings from bytes and paths that may not be valid UTF-8, and conversion
+//! of strings to and from UTF-16
+
+use std::{
+    borrow::Cow,
+    error::Error,
+    ffi::OsStr,
+    fmt,
+    path::{Path, PathBuf},
+    str,
+};
+
+use crate::{
+    context::Context,
+    handle::{Handle, Managed},
+    sys,
+    types::{JsString, StringResult},
+};
+
+use super::StringOverflow;
+
+/// An error produced when creating a string from bytes that are not valid UTF-8
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Utf8ErrorAt {
+    offset: usize,
+    len: Option<usize>,
+}
+
+impl Utf8ErrorAt {
+    /// The byte offset of the first invalid sequence. The bytes before it are valid
+    /// UTF-8.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The length in bytes of the invalid sequence, or `None` if the bytes end in the
+    /// middle of a sequence, which could be completed by more bytes
+    pub fn error_len(&self) -> Option<usize> {
+        self.len
+    }
+}
+
+impl From<str::Utf8Error> for Utf8ErrorAt {
+    fn from(err: str::Utf8Error) -> Self {
+        Self {
+            offset: err.valid_up_to(),
+            len: err.error_len(),
+        }
+    }
+}
+
+impl fmt::Display for Utf8ErrorAt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.len {
+            Some(len) => write!(
+                f,
+                "invalid UTF-8 sequence of {} bytes at byte {}",
+                len, self.offset
+            ),
+            None => write!(f, "incomplete UTF-8 sequence at byte {}", self.offset),
+        }
+    }
+}
+
+impl Error for Utf8ErrorAt {}
+
+/// How a path that is not valid Unicode is represented as a JavaScript string
+///
+/// On Unix, a path is a sequence of bytes, which may not be valid UTF-8. On Windows, a
+/// path is a sequence of 16-bit units, which may contain unpaired surrogates. Paths
+/// that are valid Unicode are unchanged by [`Lossy`](PathEncoding::Lossy).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PathEncoding {
+    /// Each invalid sequence is replaced with U+FFFD, like
+    /// [`Path::to_string_lossy`]. The original path can't be recovered.
+    #[default]
+    Lossy,
+    /// `%` and each byte that is not part of valid UTF-8 are percent-encoded, for
+    /// example, as `%25` and `%FF`. [`JsString::to_path`] recovers the original path
+    /// on Unix. On Windows, a path with unpaired surrogates is recovered lossily.
+    Percent,
+}
+
+impl JsString {
+    /// Creates a string from bytes, replacing each invalid UTF-8 sequence with U+FFFD,
+    /// like [`String::from_utf8_lossy`].
+    ///
+    /// Valid UTF-8 is copied directly into the engine. Only bytes with invalid
+    /// sequences are converted before they are copied.
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    pub fn from_utf8_lossy<'a, C: Context<'a>>(cx: &mut C, bytes: &[u8]) -> Handle<'a, JsString> {
+        JsString::new(cx, String::from_utf8_lossy(bytes))
+    }
+
+    /// Creates a string from bytes, returning an error with the offset of the first
+    /// invalid sequence if they are not valid UTF-8.
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    pub fn try_from_utf8<'a, C: Context<'a>>(
+        cx: &mut C,
+        bytes: &[u8],
+    ) -> Result<Handle<'a, JsString>, Utf8ErrorAt> {
+        Ok(JsString::new(cx, str::from_utf8(bytes)?))
+    }
+
+    /// Creates a string from a path, representing a path that is not valid Unicode
+    /// with `encoding`.
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    pub fn from_path<'a, C: Context<'a>>(
+        cx: &mut C,
+        path: &Path,
+        encoding: PathEncoding,
+    ) -> Handle<'a, JsString> {
+        match encoding {
+            PathEncoding::Lossy => JsString::new(cx, path.to_string_lossy()),
+            PathEncoding::Percent => JsString::new(cx, percent_encode(path.as_os_str())),
+        }
+    }
+
+    /// Converts the string to a path, reversing the `encoding` used to create it with
+    /// [`JsString::from_path`].
+    ///
+    /// With [`PathEncoding::Percent`], each `%` followed by two hexadecimal digits is
+    /// decoded, and any other `%` is kept.
+    pub fn to_path<'a, C: Context<'a>>(&self, cx: &mut C, encoding: PathEncoding) -> PathBuf {
+        let value = self.value(cx);
+
+        match encoding {
+            PathEncoding::Lossy => PathBuf::from(value),
+            PathEncoding::Percent => percent_decode(&value),
+        }
+    }
+
+    /// Creates a string from UTF-16 code units, such as the wide strings of Windows
+    /// APIs.
+    ///
+    /// The code units are copied into the engine unchanged. Unpaired surrogates are
+    /// kept, and a nul is an ordinary character; a nul terminator should be removed.
+    ///
+    /// If the string exceeds the limits of the JS engine, this method panics.
+    pub fn from_wide<'a, C: Context<'a>>(cx: &mut C, wide: &[u16]) -> Handle<'a, JsString> {
+        JsString::try_from_wide(cx, wide).unwrap()
+    }
+
+    /// Creates a string from UTF-16 code units, like [`JsString::from_wide`].
+    ///
+    /// If the string exceeds the limits of the JS engine, this method returns an
+    /// `Err` value.
+    pub fn try_from_wide<'a, C: Context<'a>>(cx: &mut C, wide: &[u16]) -> StringResult<'a> {
+        let env = cx.env();
+
+        unsafe {
+            let mut local = std::mem::zeroed();
+
+            if sys::string::new_utf16(&mut local, env.to_raw(), wide.as_ptr(), wide.len()) {
+                Ok(Handle::new_internal(JsString::from_raw(env, local)))
+            } else {
+                Err(StringOverflow(wide.len()))
+            }
+        }
+    }
+
+    /// Copies the UTF-16 code units of the string, such as for the wide strings of
+    /// Windows APIs.
+    ///
+    /// JavaScript strings are sequences of UTF-16 code units, so they are copied
+    /// once, without converting to UTF-8. Unpaired surrogates are kept unchanged.
+    /// Embedded nuls are kept as well; see [`JsString::to_wide_nul`].
+    pub fn to_wide<'a, C: Context<'a>>(&self, cx: &mut C) -> Vec<u16> {
+        let mut wide = self.to_wide_nul(cx);
+
+        wide.pop();
+        wide
+    }
+
+    /// Copies the UTF-16 code units of the string, like [`JsString::to_wide`],
+    /// followed by a nul terminator.
+    ///
+    /// Embedded nuls are kept, so the result always has one more code unit than the
+    /// string. An API reading a nul-terminated string stops at the first nul; check
+    /// for embedded nuls with `wide[..wide.len() - 1].contains(&0)` where truncation
+    /// matters.
+    pub fn to_wide_nul<'a, C: Context<'a>>(&self, cx: &mut C) -> Vec<u16> {
+        let env = cx.env().to_raw();
+
+        unsafe {
+            let capacity = sys::string::utf16_len(env, self.to_raw()) + 1;
+            let mut wide = Vec::<u16>::with_capacity(capacity);
+            let len = sys::string::data_utf16(env, wide.as_mut_ptr(), capacity, self.to_raw());
+
+            // Safety: the engine wrote `len` code units and the terminator
+            wide.set_len(len + 1);
+            wide
+        }
+    }
+}
+
+// Escapes `%` and the bytes of `s` that are not part of valid UTF-8. On Windows, the
+// encoded bytes are WTF-8, in which unpaired surrogates are not valid UTF-8.
+fn percent_encode(s: &OsStr) -> Cow<'_, str> {
+    let mut bytes = s.as_encoded_bytes();
+
+    if let Ok(s) = str::from_utf8(bytes) {
+        if !s.contains('%') {
+            return Cow::Borrowed(s);
+        }
+    }
+
+    let mut encoded = String::with_capacity(bytes.len());
+
+    loop {
+        let (valid, invalid) = match str::from_utf8(bytes) {
+            Ok(valid) => (valid, &[][..]),
+            Err(err) => {
+                let (valid, rest) = bytes.split_at(err.valid_up_to());
+                let len = err.error_len().unwrap_or(rest.len());
+
+                // Safety: `valid_up_to` is the length of the valid prefix
+                let valid = unsafe { str::from_utf8_unchecked(valid) };
+
+                bytes = &rest[len..];
+                (valid, &rest[..len])
+            }
+        };
+
+        for c in valid.chars() {
+            match c {
+                '%' => encoded.push_str("%25"),
+                c => encoded.push(c),
+            }
+        }
+
+        for byte in invalid {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+
+        if invalid.is_empty() {
+            return Cow::Owned(encoded);
+        }
+    }
+}
+
+fn percent_decode(s: &str) -> PathBuf {
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut rest = s.as_bytes();
+
+    while let Some((&byte, tail)) = rest.split_first() {
+        let escaped = tail
+            .get(..2)
+            .filter(|hex| hex.iter().all(u8::is_ascii_hexdigit))
+            .and_then(|hex| str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match escaped {
+            Some(escaped) if byte == b'%' => {
+                bytes.push(escaped);
+                rest = &tail[2..];
+            }
+            _ => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    path_from_bytes(bytes)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::{ffi::OsString, os::unix::ffi::OsStringExt};
+
+    PathBuf::from(OsString::from_vec(bytes))
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    match String::from_utf8(bytes) {
+        Ok(s) => PathBuf::from(s),
+        Err(err) => PathBuf::from(String::from_utf8_lossy(err.as_bytes()).into_owned()),
+    }
+}
@@ -0,0 +1,81 @@
+// Compares the cost of operations with their faster alternatives, which the
+// tests don't compare because timings are too noisy to assert on. Run with
+// `node bench.js` after building the addon in release mode.
+const addon = require(".");
+
+// Times `iterations` calls of `f`, which each perform `per` operations
+function bench(name, iterations, f, per = 1) {
+  // Warm up, so the call is optimized before it is timed
+  for (let i = 0; i < iterations / 10; i++) {
+    f();
+  }
+
+  const start = process.hrtime.bigint();
+
+  for (let i = 0; i < iterations; i++) {
+    f();
+  }
+
+  const ns = Number(process.hrtime.bigint() - start) / iterations / per;
+
+  console.log(`${name}: ${ns.toFixed(1)} ns`);
+}
+
+// Writing into an existing typed array instead of allocating the output
+{
+  const input = new Float64Array(100000).fill(1);
+  const output = new Float64Array(100000);
+
+  bench("scale_into", 2000, () => addon.scale_into(input, 2, output));
+  bench("scale", 2000, () => addon.scale(input, 2));
+}
+
+// Converting a string to UTF-16 directly instead of through UTF-8
+{
+  const s = "wide ünïcödé 😀 ".repeat(10000);
+
+  bench("string_to_wide", 100, () => addon.string_to_wide_repeat(s, 10), 10);
+  bench(
+    "string_to_wide_via_utf8",
+    100,
+    () => addon.string_to_wide_via_utf8_repeat(s, 10),
+    10
+  );
+}
+
+// Instantiating an object template instead of setting each field
+{
+  bench("template_results", 100, () => addon.template_results(1000), 1000);
+  bench("naive_results", 100, () => addon.naive_results(1000), 1000);
+}
+
+// Extracting a `Vec<f64>` from a `Float64Array` instead of an array
+{
+  const array = Array.from({ length: 100000 }, (_, i) => i);
+  const typed = new Float64Array(array);
+
+  bench("sum_f64_vec Float64Array", 200, () => addon.sum_f64_vec(typed));
+  bench("sum_f64_vec array", 200, () => addon.sum_f64_vec(array));
+}
+
+// Writing an array back to front in chunks with `set_range` instead of `set`
+{
+  const n = 100000;
+
+  bench("array_build_reversed set_range", 20, () =>
+    addon.array_build_reversed(n, true)
+  );
+  bench("array_build_reversed set", 20, () =>
+    addon.array_build_reversed(n, false)
+  );
+}
+
+// Creating a cached small integer instead of a number that is not cached
+{
+  const n = 10000;
+
+  bench("create_numbers cached", 200, () => addon.create_numbers(n, 7), n);
+  bench("create_numbers", 200, () => addon.create_numbers(n, 7.5), n);
+  bench("create_numbers call cached", 100000, () => addon.create_numbers(1, 7));
+  bench("create_numbers call", 100000, () => addon.create_numbers(1, 7.5));
+}
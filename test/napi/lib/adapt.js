@@ -0,0 +1,100 @@
+const assert = require("chai").assert;
+const addon = require("..");
+
+const describeCounters = addon.memory_stats_enabled
+  ? describe
+  : describe.skip;
+
+// A sink that collects the chunks written to it as strings
+function collector() {
+  return {
+    chunks: [],
+    write(chunk) {
+      this.chunks.push(Buffer.from(chunk).toString());
+    },
+    end() {
+      return this.chunks.join("").length;
+    },
+  };
+}
+
+describe("Interface adapters", () => {
+  it("should call the methods of an object from another thread", async () => {
+    const sink = collector();
+
+    const written = await addon.adapt_drive_sink(sink, ["hello", " world"]);
+
+    assert.strictEqual(written, 11);
+    assert.deepEqual(sink.chunks, ["hello", " world"]);
+  });
+
+  it("should list the missing methods", () => {
+    assert.throws(
+      () => addon.adapt_new_sink({ write() {} }),
+      TypeError,
+      /^expected a Sink object, missing methods: end$/
+    );
+
+    assert.throws(
+      () => addon.adapt_new_sink({ write: 1 }),
+      TypeError,
+      /missing methods: write, end$/
+    );
+
+    assert.throws(
+      () => addon.adapt_new_sink("sink"),
+      TypeError,
+      /^expected a Sink object$/
+    );
+  });
+
+  it("should return an error when a method throws", async () => {
+    const sink = collector();
+
+    sink.write = () => {
+      throw new Error("boom");
+    };
+
+    assert.strictEqual(
+      await addon.adapt_drive_sink(sink, ["hello"]),
+      "Sink.write threw an exception: Error: boom"
+    );
+  });
+
+  it("should return an error for a value of the wrong type", async () => {
+    const sink = collector();
+
+    sink.end = () => "done";
+
+    assert.strictEqual(
+      await addon.adapt_drive_sink(sink, []),
+      "Sink.end returned a value that cannot be converted to `f64`"
+    );
+  });
+
+  it("should not block the JavaScript thread", () => {
+    assert.strictEqual(
+      addon.adapt_write_on_main_thread(collector()),
+      "Sink.write cannot be called on the JavaScript thread without blocking it"
+    );
+  });
+
+  describeCounters("teardown", () => {
+    it("should release the roots when the adapter is dropped", async () => {
+      const before = addon.memory_stats().roots;
+      const sink = collector();
+      let rooted;
+
+      // The object and its two methods are rooted while the adapter is alive
+      sink.write = () => {
+        rooted = addon.memory_stats().roots;
+      };
+
+      await addon.adapt_drive_sink(sink, ["hello"]);
+      assert.strictEqual(rooted, before + 3);
+      await new Promise((resolve) => setTimeout(resolve, 10));
+
+      assert.strictEqual(addon.memory_stats().roots, before);
+    });
+  });
+});
@@ -0,0 +1,25 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("ThreadAffinity", () => {
+  it("should pass on the thread it was captured on", () => {
+    assert.isTrue(addon.affinity_same_thread());
+  });
+
+  it("should pass in a channel closure of the same instance", (cb) => {
+    addon.affinity_channel((err) => {
+      assert.isNull(err);
+      cb();
+    });
+  });
+
+  it("should fail on a spawned thread", () => {
+    const [check, message] = addon.affinity_spawned_thread();
+
+    assert.isFalse(check);
+    assert.match(
+      message,
+      /^expected to run on the JavaScript thread of instance \d+ \(.*\), but ran on thread 'affinity-test' \(ThreadId\(\d+\)\)$/
+    );
+  });
+});
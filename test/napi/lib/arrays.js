@@ -22,3 +22,68 @@ describe("JsArray", function () {
     assert.strictEqual(addon.read_js_array([]), undefined);
   });
 });
+
+describe("JsArray bulk writes", function () {
+  it("should read holes left by set_len as undefined", function () {
+    const array = addon.array_set_len([1], 3);
+
+    assert.strictEqual(array.length, 3);
+    assert.strictEqual(array[0], 1);
+    assert.strictEqual(array[2], undefined);
+    assert.isFalse(2 in array);
+  });
+
+  it("should drop elements when truncated", function () {
+    const array = [1, 2, 3, 4];
+
+    addon.array_truncate(array, 2);
+    assert.deepEqual(array, [1, 2]);
+    assert.isFalse(3 in array);
+
+    addon.array_truncate(array, 10);
+    assert.deepEqual(array, [1, 2]);
+  });
+
+  it("should write a range within the array", function () {
+    assert.deepEqual(addon.array_set_range([1, 2, 3, 4], 1, [5, 6]), [
+      1, 5, 6, 4,
+    ]);
+  });
+
+  it("should extend the array for a range past the end", function () {
+    const array = addon.array_set_range([1], 3, ["a", "b"]);
+
+    assert.strictEqual(array.length, 5);
+    assert.deepEqual(array.slice(3), ["a", "b"]);
+    assert.isFalse(1 in array);
+    assert.strictEqual(array[2], undefined);
+  });
+
+  it("should throw for a range past the largest length", function () {
+    assert.throws(
+      () => addon.array_set_range([], 2 ** 32 - 2, [1, 2]),
+      RangeError
+    );
+  });
+
+  it("should fill a range", function () {
+    assert.deepEqual(addon.array_fill([1, 2, 3], 0, 1, 2), [1, 0, 3]);
+    assert.deepEqual(addon.array_fill([], "x", 0, 3), ["x", "x", "x"]);
+    assert.deepEqual(addon.array_fill([1], true, 2, 3), [1, , true]);
+  });
+
+  it("should fill a large array", function () {
+    const array = addon.array_fill([], 7, 0, 1000000);
+
+    assert.strictEqual(array.length, 1000000);
+    assert.strictEqual(array[999999], 7);
+  });
+
+  it("should write chunks back to front with set_range", function () {
+    // Three chunks, the first of them partial
+    const expected = Array.from({ length: 3000 }, (_, i) => i);
+
+    assert.deepEqual(addon.array_build_reversed(3000, true), expected);
+    assert.deepEqual(addon.array_build_reversed(3000, false), expected);
+  });
+});
@@ -0,0 +1,197 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+const shared = (length) =>
+  new Int32Array(new SharedArrayBuffer(length * Int32Array.BYTES_PER_ELEMENT));
+
+describe("atomics", function () {
+  it("creates views of shared arrays", function () {
+    assert.strictEqual(addon.atomics_len(shared(4)), 4);
+    assert.strictEqual(
+      addon.atomics_len(new Int32Array(new SharedArrayBuffer(32), 8, 2)),
+      2
+    );
+  });
+
+  it("rejects arrays that are not shared", function () {
+    assert.throws(
+      () => addon.atomics_len(new Int32Array(4)),
+      TypeError,
+      /SharedArrayBuffer/
+    );
+  });
+
+  it("wakes a worker blocked in Atomics.wait", function (cb) {
+    const array = shared(2);
+    const worker = new Worker(
+      `
+      const { parentPort, workerData: array } = require("worker_threads");
+
+      parentPort.postMessage("waiting");
+      parentPort.postMessage(Atomics.wait(array, 1, 0, 5000));
+      `,
+      { eval: true, workerData: array }
+    );
+
+    const messages = [];
+
+    // Checks the messages once the worker returned from `Atomics.wait` and the
+    // number of woken waiters was reported, in either order
+    const received = (message) => {
+      messages.push(message);
+
+      if (messages.length < 3) {
+        return;
+      }
+
+      try {
+        assert.sameMembers(messages, ["waiting", "ok", 1]);
+        assert.strictEqual(Atomics.load(array, 1), 42);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    };
+
+    worker.on("error", cb);
+    worker.on("message", (message) => {
+      received(message);
+
+      if (message === "waiting") {
+        // Give the worker time to block in `Atomics.wait`
+        setTimeout(
+          () => addon.atomics_store_notify(array, 1, 42, received),
+          50
+        );
+      }
+    });
+  });
+
+  it("reports the number of woken waiters", function (cb) {
+    addon.atomics_store_notify(shared(1), 0, 1, (woken) => {
+      try {
+        assert.strictEqual(woken, 0);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("wakes a Rust thread when JavaScript changes the value", function (cb) {
+    const array = shared(1);
+
+    addon.atomics_wait(array, 0, 0, null, (result) => {
+      try {
+        assert.strictEqual(result, "ok");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    setTimeout(() => {
+      Atomics.store(array, 0, 1);
+      Atomics.notify(array, 0);
+    }, 20);
+  });
+
+  it("wakes a Rust thread when JavaScript increments a counter", function (cb) {
+    const array = shared(1);
+
+    Atomics.store(array, 0, 41);
+    addon.atomics_wait(array, 0, 41, null, (result) => {
+      try {
+        assert.strictEqual(result, "ok");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    setTimeout(() => {
+      Atomics.add(array, 0, 1);
+      Atomics.notify(array, 0);
+    }, 20);
+  });
+
+  it("does not observe a notify that does not change the value", function (cb) {
+    const array = shared(1);
+
+    addon.atomics_wait(array, 0, 0, 100, (result) => {
+      try {
+        assert.strictEqual(result, "timed-out");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    setTimeout(() => Atomics.notify(array, 0), 20);
+  });
+
+  it("waits without a deadline for a huge timeout", function (cb) {
+    const array = shared(1);
+
+    addon.atomics_wait(array, 0, 0, 2 ** 64, (result) => {
+      try {
+        assert.strictEqual(result, "ok");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    setTimeout(() => Atomics.store(array, 0, 1), 20);
+  });
+
+  it("times out while the value is unchanged", function (cb) {
+    const start = Date.now();
+
+    addon.atomics_wait(shared(1), 0, 0, 30, (result) => {
+      try {
+        assert.strictEqual(result, "timed-out");
+        assert.isAtLeast(Date.now() - start, 30);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("does not wait if the value is not expected", function (cb) {
+    const array = shared(1);
+
+    Atomics.store(array, 0, 7);
+    addon.atomics_wait(array, 0, 0, 1000, (result) => {
+      try {
+        assert.strictEqual(result, "not-equal");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("checks bounds", function (cb) {
+    addon.atomics_wait(shared(2), 2, 0, 0, (result) => {
+      try {
+        assert.strictEqual(
+          result,
+          "index 2 is out of bounds for a view of 2 elements"
+        );
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("refuses to block the JavaScript thread", function () {
+    assert.throws(
+      () => addon.atomics_wait_on_js_thread(shared(1)),
+      /neon::atomics::wait_for_change must not be called on a JavaScript thread/
+    );
+  });
+});
@@ -55,7 +55,9 @@ describe("boxed", function () {
   });
 
   it("should dynamically check borrowing rules", function () {
-    assert.throws(() => new RefPerson("World").fail(), /BorrowMutError/);
+    // Older versions of Rust panic with `already borrowed: BorrowMutError`,
+    // newer ones with `RefCell already borrowed`
+    assert.throws(() => new RefPerson("World").fail(), /already borrowed/);
   });
 
   it("should type check externals", function () {
@@ -63,7 +65,7 @@ describe("boxed", function () {
     // failed downcast to neon::types::boxed::JsBox<napi::js::boxed::Person>
     assert.throws(
       () => addon.person_greet({}),
-      /failed to downcast.*JsBox.*Person/
+      /must be of type .*JsBox.*Person/
     );
   });
 
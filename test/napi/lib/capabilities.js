@@ -0,0 +1,49 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+function assertMissing(f, capability, reason) {
+  try {
+    f();
+  } catch (err) {
+    assert.instanceOf(err, Error);
+    assert.strictEqual(err.code, "ERR_NEON_MISSING_CAPABILITY");
+    assert.strictEqual(err.capability, capability);
+    assert.include(err.message, capability);
+    assert.include(err.message, reason);
+
+    return;
+  }
+
+  assert.fail(`expected \`${f.name}\` to throw`);
+}
+
+describe("capabilities", () => {
+  it("should export the registered capabilities", () => {
+    assert.deepEqual(addon.capabilities, { present: true, missing: false });
+    assert.isTrue(Object.isFrozen(addon.capabilities));
+  });
+
+  it("should look up registered capabilities", () => {
+    const { capability_is_available: isAvailable } = addon;
+
+    assert.strictEqual(isAvailable("present"), true);
+    assert.strictEqual(isAvailable("missing"), false);
+    assert.strictEqual(isAvailable("unregistered"), undefined);
+  });
+
+  it("should call a function whose capability is available", () => {
+    assert.strictEqual(addon.capability_present(), "called");
+  });
+
+  it("should throw if the capability is missing", () => {
+    assertMissing(addon.capability_missing, "missing", "is not available");
+  });
+
+  it("should throw if the capability was never registered", () => {
+    assertMissing(
+      addon.capability_unregistered,
+      "unregistered",
+      "was not registered"
+    );
+  });
+});
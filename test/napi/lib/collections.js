@@ -0,0 +1,163 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("Collections", () => {
+  it("should define the properties of a BTreeMap in key order", () => {
+    const obj = addon.btree_map_round_trip({ b: 2, c: 3, a: 1 });
+
+    assert.deepEqual(Object.keys(obj), ["a", "b", "c"]);
+    assert.deepEqual(obj, { a: 1, b: 2, c: 3 });
+  });
+
+  it("should extract a BTreeMap from a Map", () => {
+    const obj = addon.btree_map_round_trip(
+      new Map([
+        ["y", 2],
+        ["x", 1],
+      ])
+    );
+
+    assert.deepEqual(Object.keys(obj), ["x", "y"]);
+  });
+
+  it("should convert a BTreeMap with non-string keys to a Map", () => {
+    const map = addon.btree_map_numeric_keys();
+
+    assert.instanceOf(map, Map);
+    assert.deepEqual(Array.from(map), [
+      [1, "1"],
+      [2, "2"],
+      [3, "3"],
+    ]);
+  });
+
+  it("should round trip a HashMap", () => {
+    assert.deepEqual(addon.hash_map_round_trip({ a: 1, b: 2 }), { a: 1, b: 2 });
+    assert.deepEqual(addon.hash_map_round_trip(new Map([["a", 1]])), { a: 1 });
+  });
+
+  it("should not extract a map from an array or with invalid values", () => {
+    assert.throws(() => addon.hash_map_round_trip([1, 2]), TypeError);
+    assert.throws(() => addon.hash_map_round_trip({ a: "x" }), TypeError);
+    assert.throws(
+      () => addon.hash_map_round_trip(new Map([[1, 1]])),
+      TypeError
+    );
+  });
+
+  it("should round trip a Set", () => {
+    const set = addon.hash_set_round_trip(new Set(["a", "b"]));
+
+    assert.instanceOf(set, Set);
+    assert.sameMembers(Array.from(set), ["a", "b"]);
+    assert.sameMembers(Array.from(addon.hash_set_round_trip(["a", "a"])), [
+      "a",
+    ]);
+    assert.throws(() => addon.hash_set_round_trip(new Set([1])), TypeError);
+  });
+
+  it("should convert a BTreeSet to a Set in sorted order", () => {
+    const set = addon.btree_set_round_trip(new Set(["c", "a", "b"]));
+
+    assert.deepEqual(Array.from(set), ["a", "b", "c"]);
+  });
+
+  it("should preserve the order of Entries", () => {
+    const map = addon.entries_round_trip(
+      new Map([
+        ["b", 2],
+        ["a", 1],
+      ])
+    );
+
+    assert.instanceOf(map, Map);
+    assert.deepEqual(Array.from(map.keys()), ["b", "a"]);
+    assert.deepEqual(Array.from(addon.entries_round_trip({ z: 1, y: 2 })), [
+      ["z", 1],
+      ["y", 2],
+    ]);
+  });
+
+  it("should keep the last value of duplicate keys in Entries", () => {
+    assert.deepEqual(Array.from(addon.entries_duplicates()), [
+      ["a", 3],
+      ["b", 2],
+    ]);
+  });
+});
+
+describe("Partial", () => {
+  function rows(n, bad) {
+    return Array.from({ length: n }, (_, i) =>
+      bad.includes(i) ? { id: i, name: null } : { id: i, name: `row ${i}` }
+    );
+  }
+
+  it("should extract the valid rows and report the others", () => {
+    const bad = [0, 7, 100, 101, 250, 500, 501, 502, 998, 999];
+    const [valid, errors] = addon.partial_rows(rows(1000, bad), 10);
+
+    assert.strictEqual(valid.length, 990);
+    assert.strictEqual(valid[0], "1:row 1");
+    assert.strictEqual(valid[989], "997:row 997");
+    assert.deepEqual(errors.map((err) => err.index), bad);
+    assert.deepEqual(
+      errors.map((err) => err.path),
+      bad.map((i) => `[${i}]`)
+    );
+  });
+
+  it("should convert errors to objects with stable properties", () => {
+    const [, [err]] = addon.partial_rows([{ id: 1 }], 10);
+
+    assert.deepEqual(Object.keys(err), ["index", "path", "message"]);
+    assert.strictEqual(err.index, 0);
+    assert.strictEqual(err.path, "[0]");
+    assert.include(err.message, "Row");
+  });
+
+  it("should report exceptions thrown by an element", () => {
+    const row = {
+      id: 1,
+      get name() {
+        throw new Error("no name");
+      },
+    };
+
+    const [valid, errors] = addon.partial_rows(
+      [row, { id: 2, name: "b" }],
+      10
+    );
+
+    assert.deepEqual(valid, ["2:b"]);
+    assert.deepEqual(errors, [{ index: 0, path: "[0]", message: "no name" }]);
+  });
+
+  it("should abort after too many errors", () => {
+    let read = 0;
+    const array = rows(100, []);
+
+    array.forEach((_, i) => {
+      Object.defineProperty(array, i, {
+        get() {
+          read++;
+          return null;
+        },
+      });
+    });
+
+    assert.throws(
+      () => addon.partial_rows(array, 3),
+      RangeError,
+      "more than 3 elements could not be extracted; the first was"
+    );
+    assert.strictEqual(read, 4);
+  });
+
+  it("should allow the default number of errors", () => {
+    const numbers = Array.from({ length: 200 }, (_, i) => (i < 100 ? "x" : i));
+
+    assert.strictEqual(addon.partial_numbers(numbers).length, 100);
+    assert.throws(() => addon.partial_numbers(["x", ...numbers]), RangeError);
+  });
+});
@@ -0,0 +1,204 @@
+const addon = require("..");
+const assert = require("chai").assert;
+
+describe("deep_equals", function () {
+  it("compares primitives", function () {
+    assert.isTrue(addon.deep_equals(1, 1));
+    assert.isTrue(addon.deep_equals("a", "a"));
+    assert.isTrue(addon.deep_equals(undefined, undefined));
+    assert.isFalse(addon.deep_equals(null, undefined));
+    assert.isFalse(addon.deep_equals(1, "1"));
+  });
+
+  it("compares nested objects and arrays", function () {
+    const a = { a: [1, { b: 2 }] };
+
+    assert.isTrue(addon.deep_equals(a, { a: [1, { b: 2 }] }));
+    assert.isFalse(addon.deep_equals(a, { a: [1, { b: 3 }] }));
+    assert.isFalse(addon.deep_equals({ a: undefined }, {}));
+    assert.isFalse(addon.deep_equals([1, 2], [1, 2, 3]));
+    assert.isFalse(addon.deep_equals([], {}));
+  });
+
+  it("compares dates", function () {
+    assert.isTrue(addon.deep_equals(new Date(1000), new Date(1000)));
+    assert.isFalse(addon.deep_equals(new Date(1000), new Date(2000)));
+  });
+
+  it("handles cycles", function () {
+    const a = { name: "a" };
+    const b = { name: "a" };
+
+    a.self = a;
+    b.self = b;
+
+    assert.isTrue(addon.deep_equals(a, b));
+
+    b.name = "b";
+
+    assert.isFalse(addon.deep_equals(a, b));
+  });
+
+  it("compares Maps with object keys", function () {
+    const a = new Map([
+      [{ id: 1 }, "one"],
+      [{ id: 2 }, "two"],
+    ]);
+    const b = new Map([
+      [{ id: 2 }, "two"],
+      [{ id: 1 }, "one"],
+    ]);
+    const c = new Map([
+      [{ id: 1 }, "one"],
+      [{ id: 2 }, "three"],
+    ]);
+
+    assert.isTrue(addon.deep_equals(a, b));
+    assert.isFalse(addon.deep_equals(a, c));
+  });
+
+  it("compares Sets", function () {
+    const a = new Set([1, { a: 1 }]);
+
+    assert.isTrue(addon.deep_equals(a, new Set([{ a: 1 }, 1])));
+    assert.isFalse(addon.deep_equals(new Set([1, 2]), new Set([1, 3])));
+  });
+
+  it("compares typed arrays by contents", function () {
+    const a = new Uint8Array([1, 2, 3]);
+
+    assert.isTrue(addon.deep_equals(a, new Uint8Array([1, 2, 3])));
+    assert.isFalse(addon.deep_equals(a, new Uint8Array([1, 2, 4])));
+    assert.isFalse(addon.deep_equals(a, new Int8Array([1, 2, 3])));
+  });
+
+  it("compares float typed arrays as numbers", function () {
+    const nan = new Float64Array([NaN]);
+    const zero = new Float32Array([0]);
+    const negZero = new Float32Array([-0]);
+
+    assert.isTrue(addon.deep_equals(nan, new Float64Array([NaN])));
+    assert.isFalse(addon.deep_equals(nan, nan.slice(), { nanEqual: false }));
+    assert.isTrue(addon.deep_equals(zero, negZero));
+
+    const options = { distinguishNegativeZero: true };
+
+    assert.isFalse(addon.deep_equals(zero, negZero, options));
+    assert.isTrue(addon.deep_equals(negZero, negZero.slice(), options));
+  });
+
+  it("compares data views by contents", function () {
+    const buffer = new Uint8Array([1, 2, 3, 4]).buffer;
+    const a = new DataView(buffer, 1, 2);
+
+    assert.isTrue(addon.deep_equals(a, new DataView(buffer.slice(1, 3))));
+    assert.isFalse(addon.deep_equals(a, new DataView(buffer, 2, 2)));
+    assert.isFalse(addon.deep_equals(a, new Uint8Array([2, 3])));
+  });
+
+  it("compares boxed primitives by their values", function () {
+    assert.isTrue(addon.deep_equals(new Number(1), new Number(1)));
+    assert.isFalse(addon.deep_equals(new Number(1), new Number(2)));
+    assert.isFalse(addon.deep_equals(new String("a"), new String("b")));
+    assert.isFalse(addon.deep_equals(new Boolean(true), new Boolean(false)));
+    assert.isFalse(addon.deep_equals(Object(1n), Object(2n)));
+    assert.isFalse(addon.deep_equals(Object(Symbol()), Object(Symbol())));
+    assert.isFalse(addon.deep_equals(new Number(1), new String("1")));
+
+    // Objects that only claim to be boxed are compared by their keys
+    const fake = { [Symbol.toStringTag]: "Number", x: 1 };
+
+    assert.isTrue(addon.deep_equals(fake, { ...fake }));
+    assert.isFalse(addon.deep_equals(fake, { ...fake, x: 2 }));
+  });
+
+  it("compares regular expressions by source and flags", function () {
+    assert.isTrue(addon.deep_equals(/a+/g, /a+/g));
+    assert.isFalse(addon.deep_equals(/a+/g, /a+/i));
+    assert.isFalse(addon.deep_equals(/a+/, /b+/));
+  });
+
+  it("respects options", function () {
+    assert.isTrue(addon.deep_equals(NaN, NaN));
+    assert.isFalse(addon.deep_equals(NaN, NaN, { nanEqual: false }));
+
+    assert.isTrue(addon.deep_equals(0, -0));
+    assert.isFalse(addon.deep_equals(0, -0, { distinguishNegativeZero: true }));
+
+    const map = new Map([[1, 2]]);
+    const bytes = new Uint8Array([1]);
+
+    assert.isFalse(
+      addon.deep_equals(map, new Map(map), { compareCollections: false })
+    );
+    assert.isFalse(
+      addon.deep_equals(bytes, bytes.slice(), { compareTypedArrays: false })
+    );
+
+    class Point {
+      constructor() {
+        this.x = 1;
+      }
+    }
+
+    assert.isTrue(addon.deep_equals(new Point(), { x: 1 }));
+    const options = { comparePrototypes: true };
+
+    assert.isFalse(addon.deep_equals(new Point(), { x: 1 }, options));
+  });
+
+  it("propagates exceptions from getters", function () {
+    const a = {
+      get x() {
+        throw new Error("getter failed");
+      },
+    };
+
+    assert.throws(() => addon.deep_equals(a, { x: 1 }), /getter failed/);
+  });
+});
+
+describe("deep_diff", function () {
+  it("returns no differences for equal values", function () {
+    assert.deepEqual(addon.deep_diff({ a: [1] }, { a: [1] }), []);
+  });
+
+  it("reports the path to each difference", function () {
+    const a = { a: { b: 1, c: 2 } };
+    const diff = addon.deep_diff(a, { a: { b: 1, c: 3, d: 4 } });
+
+    assert.deepEqual(diff, [
+      { path: ["a", "c"], left: 2, right: 3 },
+      { path: ["a", "d"], left: undefined, right: 4 },
+    ]);
+  });
+
+  it("includes the index of a differing typed array element", function () {
+    const diff = addon.deep_diff(
+      { data: new Uint8Array([1, 2, 3, 4]) },
+      { data: new Uint8Array([1, 2, 9, 4]) }
+    );
+
+    assert.deepEqual(diff, [{ path: ["data", 2], left: 3, right: 9 }]);
+  });
+
+  it("includes the index of a differing float element", function () {
+    const diff = addon.deep_diff(
+      new Float64Array([0, 1, NaN]),
+      new Float64Array([-0, 2, NaN])
+    );
+
+    assert.deepEqual(diff, [{ path: [1], left: 1, right: 2 }]);
+  });
+
+  it("reports differences in Map values by key", function () {
+    const key = { id: 1 };
+    const a = new Map([[key, "one"]]);
+    const diff = addon.deep_diff(a, new Map([[{ id: 1 }, "uno"]]));
+
+    assert.strictEqual(diff.length, 1);
+    assert.deepEqual(diff[0].path, [key]);
+    assert.strictEqual(diff[0].left, "one");
+    assert.strictEqual(diff[0].right, "uno");
+  });
+});
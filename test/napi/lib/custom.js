@@ -0,0 +1,68 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("custom value types", () => {
+  it("should downcast instances", () => {
+    assert.isTrue(addon.custom_is_url(new URL("https://neon-rs.dev/")));
+    assert.isTrue(
+      addon.custom_is_url(new (class extends URL {})("https://neon-rs.dev/"))
+    );
+  });
+
+  it("should fail to downcast other values", () => {
+    assert.isFalse(addon.custom_is_url({ href: "https://neon-rs.dev/" }));
+    assert.isFalse(addon.custom_is_url("https://neon-rs.dev/"));
+    assert.isFalse(addon.custom_is_url(null));
+    assert.isFalse(addon.custom_is_url(URL));
+  });
+
+  it("should extract arguments", () => {
+    assert.strictEqual(
+      addon.custom_url_host(new URL("https://neon-rs.dev:8080/docs")),
+      "neon-rs.dev:8080"
+    );
+    assert.strictEqual(addon.custom_map_size(new Map([[1, 2]])), 1);
+  });
+
+  it("should throw for arguments of the wrong type", () => {
+    assert.throws(
+      () => addon.custom_url_host("https://neon-rs.dev/"),
+      TypeError,
+      /URL/
+    );
+    assert.throws(() => addon.custom_map_size(new Set()), TypeError, /Map/);
+  });
+
+  it("should extract with TryFromJs", () => {
+    assert.strictEqual(
+      addon.custom_try_from_js(new URL("https://neon-rs.dev/")),
+      "https://neon-rs.dev/"
+    );
+    assert.isNull(addon.custom_try_from_js({}));
+    assert.isNull(addon.custom_try_from_js(42));
+  });
+
+  it("should be returned from functions", () => {
+    const url = addon.custom_parse_url("https://neon-rs.dev/docs");
+
+    assert.instanceOf(url, URL);
+    assert.strictEqual(url.pathname, "/docs");
+  });
+
+  it("should distinguish between custom types", () => {
+    assert.strictEqual(
+      addon.custom_classify(new URL("https://neon-rs.dev/")),
+      "url"
+    );
+    assert.strictEqual(addon.custom_classify(new Map()), "map");
+    assert.strictEqual(addon.custom_classify(new Set()), "other");
+    assert.strictEqual(addon.custom_classify(1), "other");
+  });
+
+  it("should leave exceptions thrown by the check pending", () => {
+    assert.throws(
+      () => addon.custom_is_throwing({ throws: true }),
+      /is_throwing failed/
+    );
+  });
+});
@@ -0,0 +1,119 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+// Collects the warnings emitted while `f` runs and a turn of the event loop,
+// since warnings are emitted on the next tick
+async function warnings(f) {
+  const emitted = [];
+  const listener = (warning) => emitted.push(warning);
+
+  process.on("warning", listener);
+
+  try {
+    f();
+    await new Promise((resolve) => setImmediate(resolve));
+  } finally {
+    process.off("warning", listener);
+  }
+
+  return emitted;
+}
+
+describe("deprecation", () => {
+  it("should derive stable codes", () => {
+    assert.strictEqual(
+      addon.deprecation_code("parse_uri"),
+      "NEON_DEP_PARSE_URI"
+    );
+    assert.strictEqual(
+      addon.deprecation_code("parseUri"),
+      "NEON_DEP_PARSE_URI"
+    );
+    assert.strictEqual(
+      addon.deprecation_code("getHTTPStatus"),
+      "NEON_DEP_GET_HTTP_STATUS"
+    );
+    assert.strictEqual(addon.deprecation_code("toV8"), "NEON_DEP_TO_V8");
+  });
+
+  it("should warn once and call the function", async () => {
+    const emitted = await warnings(() => {
+      assert.strictEqual(addon.deprecated_once(), "called");
+      assert.strictEqual(addon.deprecated_once(), "called");
+    });
+
+    assert.lengthOf(emitted, 1);
+
+    const [warning] = emitted;
+
+    assert.strictEqual(warning.name, "DeprecationWarning");
+    assert.strictEqual(warning.code, "NEON_DEP_DEPRECATED_ONCE");
+    assert.strictEqual(warning.message, "use deprecated_target() instead");
+
+    assert.isEmpty(await warnings(() => addon.deprecated_once()));
+  });
+
+  it("should throw with `throwDeprecation`", () => {
+    process.throwDeprecation = true;
+
+    try {
+      for (let i = 0; i < 2; i++) {
+        assert.throws(
+          () => addon.deprecated_throws(),
+          /deprecatedThrows\(\) is deprecated/
+        );
+      }
+
+      try {
+        addon.deprecated_throws();
+      } catch (err) {
+        assert.instanceOf(err, Error);
+        assert.strictEqual(err.name, "DeprecationWarning");
+        assert.strictEqual(err.code, "NEON_DEP_DEPRECATED_THROWS");
+
+        return;
+      }
+    } finally {
+      process.throwDeprecation = false;
+    }
+
+    assert.fail("expected `deprecated_throws` to throw");
+  });
+
+  it("should delegate from a deprecated alias", async () => {
+    const self = {};
+    const emitted = await warnings(() => {
+      assert.deepEqual(addon.deprecated_alias.call(self, 1, "a"), [
+        self,
+        1,
+        "a",
+      ]);
+    });
+
+    assert.lengthOf(emitted, 1);
+    assert.strictEqual(emitted[0].code, "NEON_DEP_DEPRECATED_ALIAS");
+    assert.strictEqual(
+      emitted[0].message,
+      "`deprecated_alias` is deprecated. Use `deprecated_target` instead."
+    );
+  });
+
+  it("should not warn with `noDeprecation`", async () => {
+    process.noDeprecation = true;
+
+    try {
+      const emitted = await warnings(() => {
+        assert.strictEqual(addon.deprecated_throws(), "called");
+      });
+
+      assert.isEmpty(emitted);
+    } finally {
+      process.noDeprecation = false;
+    }
+
+    const emitted = await warnings(() => addon.deprecated_throws());
+
+    assert.lengthOf(emitted, 1);
+    assert.strictEqual(emitted[0].code, "NEON_DEP_DEPRECATED_THROWS");
+  });
+});
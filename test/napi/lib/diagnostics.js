@@ -0,0 +1,223 @@
+const { spawnSync } = require("child_process");
+const addon = require("..");
+const { assert } = require("chai");
+
+const THRESHOLD = 50;
+
+function delay(ms) {
+  return new Promise((resolve) => setTimeout(resolve, ms));
+}
+
+describe("block_watchdog", () => {
+  afterEach(() => {
+    addon.watchdog_disable();
+    addon.watchdog_reports();
+  });
+
+  it("should report a slow export before it returns", () => {
+    addon.watchdog_enable(THRESHOLD);
+
+    const reportedWhileRunning = addon.watchdog_slow(THRESHOLD * 6);
+    const reports = addon.watchdog_reports();
+
+    assert.strictEqual(reportedWhileRunning, 1);
+    assert.lengthOf(reports, 1);
+    assert.strictEqual(reports[0].name, "watchdog_slow");
+    assert.strictEqual(reports[0].current, "watchdog_slow");
+    assert.isAtLeast(reports[0].duration, THRESHOLD);
+  });
+
+  it("should not report fast calls", async () => {
+    addon.watchdog_enable(THRESHOLD);
+
+    for (let i = 0; i < 1000; i++) {
+      addon.watchdog_fast();
+    }
+
+    await delay(THRESHOLD * 3);
+
+    assert.lengthOf(addon.watchdog_reports(), 0);
+  });
+
+  it("should measure a slow export from its start across nested calls", () => {
+    addon.watchdog_enable(THRESHOLD);
+
+    // Calls another export twice per threshold
+    addon.watchdog_nested(6, THRESHOLD / 2, () => addon.watchdog_fast());
+
+    const reports = addon.watchdog_reports();
+
+    assert.lengthOf(reports, 1);
+    assert.strictEqual(reports[0].name, "watchdog_nested");
+    assert.isAtLeast(reports[0].duration, THRESHOLD);
+  });
+
+  it("should be enabled and disabled at runtime", () => {
+    addon.watchdog_slow(THRESHOLD * 4);
+    assert.lengthOf(addon.watchdog_reports(), 0);
+
+    addon.watchdog_enable(THRESHOLD);
+    addon.watchdog_slow(THRESHOLD * 4);
+    assert.lengthOf(addon.watchdog_reports(), 1);
+
+    addon.watchdog_disable();
+    addon.watchdog_slow(THRESHOLD * 4);
+    assert.lengthOf(addon.watchdog_reports(), 0);
+
+    addon.watchdog_enable(THRESHOLD);
+    addon.watchdog_slow(THRESHOLD * 4);
+    assert.lengthOf(addon.watchdog_reports(), 1);
+  });
+});
+
+describe("diagnostics_channel", () => {
+  const dc = require("diagnostics_channel");
+  // `subscribe` and `tracingChannel` are missing from older versions of Node,
+  // so these tests are skipped instead of failing when the file is loaded
+  const describeSubscribe = dc.subscribe ? describe : describe.skip;
+  const describeTrace = dc.tracingChannel ? describe : describe.skip;
+
+  describeSubscribe("subscribe", () => {
+    it("should publish to a subscriber", () => {
+      const messages = [];
+      const subscriber = (message, name) => messages.push([message, name]);
+      const message = { id: 1, label: "query" };
+
+      assert.isFalse(addon.channel_publish("neon:publish", message));
+
+      dc.subscribe("neon:publish", subscriber);
+
+      try {
+        assert.isTrue(addon.channel_publish("neon:publish", message));
+      } finally {
+        dc.unsubscribe("neon:publish", subscriber);
+      }
+
+      assert.isFalse(addon.channel_publish("neon:publish", message));
+      assert.lengthOf(messages, 1);
+      assert.strictEqual(messages[0][0], message);
+      assert.strictEqual(messages[0][1], "neon:publish");
+    });
+
+    it("should not send from other threads without subscribers", async () => {
+      const sent = addon.channel_publish_from("neon:threads", 0);
+
+      assert.strictEqual(addon.channel_publish_from("neon:threads", 100), sent);
+
+      const messages = [];
+      const subscriber = (message) => messages.push(message);
+
+      dc.subscribe("neon:threads", subscriber);
+
+      try {
+        assert.strictEqual(
+          addon.channel_publish_from("neon:threads", 3),
+          sent + 3
+        );
+
+        await delay(10);
+      } finally {
+        dc.unsubscribe("neon:threads", subscriber);
+      }
+
+      assert.deepEqual(messages, ["message 0", "message 1", "message 2"]);
+      assert.strictEqual(
+        addon.channel_publish_from("neon:threads", 100),
+        sent + 3
+      );
+    });
+  });
+
+  describeTrace("trace", () => {
+    let channel;
+    let events;
+    let subscribers;
+
+    beforeEach(() => {
+      channel = dc.tracingChannel("neon:test");
+      events = [];
+      subscribers = {
+        start: (context) => events.push(["start", { ...context }]),
+        end: (context) => events.push(["end", { ...context }]),
+        error: (context) => events.push(["error", { ...context }]),
+      };
+
+      channel.subscribe(subscribers);
+    });
+
+    afterEach(() => {
+      channel.unsubscribe(subscribers);
+    });
+
+    it("should publish the start and end of a call", () => {
+      assert.strictEqual(addon.traced_add(1, 2), 3);
+
+      assert.deepEqual(events, [
+        ["start", { name: "traced_add", arguments: [1, 2] }],
+        ["end", { name: "traced_add", arguments: [1, 2] }],
+      ]);
+    });
+
+    it("should publish the error of a call that throws", () => {
+      assert.throws(() => addon.traced_add(1, "2"), TypeError);
+
+      const names = events.map(([name]) => name);
+      const [, context] = events[1];
+
+      assert.deepEqual(names, ["start", "error", "end"]);
+      assert.instanceOf(context.error, TypeError);
+      assert.deepEqual(context.arguments, [1, "2"]);
+    });
+
+    it("should publish errors thrown by the error mode", () => {
+      assert.strictEqual(addon.traced_parse("1.5"), 1.5);
+      assert.throws(() => addon.traced_parse("x"), "invalid float literal");
+
+      const names = events.map(([name]) => name);
+
+      assert.deepEqual(names, ["start", "end", "start", "error", "end"]);
+      assert.strictEqual(events[3][1].error, "invalid float literal");
+    });
+
+    it("should not publish without subscribers", () => {
+      channel.unsubscribe(subscribers);
+
+      assert.strictEqual(addon.traced_add(1, 2), 3);
+      assert.lengthOf(events, 0);
+
+      channel.subscribe(subscribers);
+    });
+  });
+
+  it("should throw without `process.getBuiltinModule` in an ES module", () => {
+    // Like Node versions before 20.16, where an ES module has no main module
+    const { stdout, stderr, status } = spawnSync(
+      process.execPath,
+      [
+        "--input-type=module",
+        "-e",
+        `
+        import { createRequire } from "module";
+
+        delete process.getBuiltinModule;
+
+        const require = createRequire(${JSON.stringify(__filename)});
+        const addon = require(${JSON.stringify(require.resolve(".."))});
+
+        try {
+          addon.channel_publish("neon:esm", {});
+        } catch (err) {
+          console.log(err.message);
+        }
+        `,
+      ],
+      { encoding: "utf8" }
+    );
+
+    assert.strictEqual(status, 0, stderr);
+    assert.strictEqual(
+      stdout.trim(),
+      "the diagnostics_channel module is not available"
+    );
+  });
+});
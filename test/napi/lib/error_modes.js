@@ -0,0 +1,43 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("export error modes", () => {
+  it("should throw the error", () => {
+    assert.strictEqual(addon.error_mode_throw(true), 1);
+
+    let thrown;
+
+    try {
+      addon.error_mode_throw(false);
+    } catch (err) {
+      thrown = err;
+    }
+
+    assert.strictEqual(thrown, "failed");
+  });
+
+  it("should return the error", () => {
+    assert.strictEqual(addon.error_mode_value(true), 1);
+    assert.strictEqual(addon.error_mode_value(false), "failed");
+    assert.deepEqual(addon.error_mode_plain(), ["not", "found"]);
+  });
+
+  it("should return a pair", () => {
+    assert.deepEqual(addon.error_mode_tuple(true), [null, 1]);
+    assert.deepEqual(addon.error_mode_tuple(false), ["failed", null]);
+  });
+
+  it("should throw exceptions in every mode", () => {
+    for (const f of [
+      addon.error_mode_throw,
+      addon.error_mode_value,
+      addon.error_mode_tuple,
+    ]) {
+      assert.throws(() => f("not a boolean"), TypeError);
+    }
+  });
+
+  it("should throw if the error can't be converted", () => {
+    assert.throws(() => addon.error_mode_conversion(), RangeError, /NaN/);
+  });
+});
@@ -1,5 +1,6 @@
 const addon = require("..");
 const assert = require("chai").assert;
+const { spawnSync } = require("child_process");
 
 describe("errors", function () {
   it("should be able to create an error", function () {
@@ -38,4 +39,330 @@ describe("errors", function () {
     let msg = addon.downcast_error();
     assert.strictEqual(msg, "failed to downcast string to number");
   });
+  it("should reject with a sendable error and its cause chain", async function () {
+    try {
+      await addon.reject_sendable_error();
+    } catch (err) {
+      assert.instanceOf(err, Error);
+      assert.strictEqual(err.message, "outer");
+      assert.strictEqual(err.code, "E_OUTER");
+      assert.instanceOf(err.cause, Error);
+      assert.strictEqual(err.cause.message, "middle");
+      assert.instanceOf(err.cause.cause, Error);
+      assert.strictEqual(err.cause.cause.message, "inner");
+      assert.strictEqual(err.cause.cause.cause, undefined);
+
+      return;
+    }
+
+    throw new Error("Expected promise to reject");
+  });
+
+  it("should keep the chain of a sendable error captured again", function () {
+    const err = addon.sendable_error_from_sendable();
+
+    assert.strictEqual(err.message, "outer");
+    assert.strictEqual(err.cause.message, "inner");
+    assert.strictEqual(err.cause.cause, undefined);
+  });
+
+  it("should include a truncated backtrace in a sendable error", function () {
+    const full = addon.sendable_error_with_backtrace(1e6);
+    const truncated = addon.sendable_error_with_backtrace(2);
+
+    assert.strictEqual(full.message, "with backtrace");
+    assert.typeOf(full.backtrace, "string");
+    assert.isAbove(full.backtrace.split("\n").length, 3);
+
+    const lines = truncated.backtrace.split("\n");
+
+    assert.lengthOf(lines, 3);
+    assert.match(lines[2], /^\.\.\. \d+ more lines$/);
+  });
+
+  describe("rust traces", function () {
+    before(() => addon.rust_trace_set_enabled(true));
+    after(() => addon.rust_trace_set_enabled(false));
+
+    // Runs `source` in a new process with `RUST_BACKTRACE` disabled and returns
+    // the value it logs
+    function withoutBacktrace(source) {
+      const { stdout, stderr, status } = spawnSync(
+        process.execPath,
+        [
+          "-e",
+          `
+          const addon = require(${JSON.stringify(require.resolve(".."))});
+          ${source}
+          `,
+        ],
+        {
+          env: {
+            ...process.env,
+            RUST_BACKTRACE: "0",
+            RUST_LIB_BACKTRACE: "0",
+          },
+          encoding: "utf8",
+        }
+      );
+
+      assert.strictEqual(status, 0, stderr);
+
+      return JSON.parse(stdout);
+    }
+
+    it("symbolizes the trace when it is first read", function () {
+      const err = addon.rust_trace_error();
+      const lazy = Object.getOwnPropertyDescriptor(err, "rustStack");
+
+      assert.typeOf(lazy.get, "function");
+      assert.isFalse(lazy.enumerable);
+
+      const rustStack = err.rustStack;
+      const resolved = Object.getOwnPropertyDescriptor(err, "rustStack");
+
+      assert.typeOf(rustStack, "string");
+      assert.include(rustStack, "rust_trace_error");
+      assert.strictEqual(resolved.value, rustStack);
+      assert.isFalse(resolved.enumerable);
+      assert.strictEqual(err.rustStack, rustStack);
+      assert.notInclude(Object.keys(err), "rustStack");
+    });
+
+    it("adds the Rust origin as the first frame of the stack", function () {
+      const lines = addon.rust_trace_error().stack.split("\n");
+
+      assert.strictEqual(lines[0], "Error: with rust trace");
+      assert.match(
+        lines[1],
+        /^ {4}at rust: napi_tests::js::errors::rust_trace_error \(.*src\/js\/errors\.rs:\d+\)$/
+      );
+      assert.match(lines[2], /^ {4}at /);
+    });
+
+    it("can assign the stack", function () {
+      const err = addon.rust_trace_error();
+
+      err.stack = "replaced";
+
+      assert.strictEqual(err.stack, "replaced");
+    });
+
+    it("adds nothing when disabled", function () {
+      const [names, stack] = withoutBacktrace(`
+        const err = addon.rust_trace_error();
+
+        console.log(JSON.stringify([Object.getOwnPropertyNames(err), err.stack]));
+      `);
+
+      assert.notInclude(names, "rustStack");
+      assert.notInclude(stack, "at rust:");
+    });
+
+    it("can be enabled for an instance", function () {
+      const rustStack = withoutBacktrace(`
+        addon.rust_trace_set_enabled(true);
+        console.log(JSON.stringify(addon.rust_trace_error().rustStack));
+      `);
+
+      assert.include(rustStack, "rust_trace_error");
+    });
+  });
+
+  describe("argument errors", function () {
+    function error(f) {
+      try {
+        f();
+      } catch (err) {
+        return err;
+      }
+
+      throw new Error("Expected function to throw");
+    }
+
+    it("should throw ERR_INVALID_ARG_TYPE for the wrong type", function () {
+      const err = error(() => addon.argument_string(42));
+
+      assert.instanceOf(err, TypeError);
+      assert.strictEqual(err.code, "ERR_INVALID_ARG_TYPE");
+      assert.strictEqual(err.argument, "arguments[0]");
+      assert.strictEqual(err.expected, "string");
+      assert.strictEqual(err.received, "type number (42)");
+      assert.strictEqual(
+        err.message,
+        'The "arguments[0]" argument must be of type string. Received type number (42)'
+      );
+    });
+
+    it("should use the name of a named argument", function () {
+      const err = error(() => addon.named_argument_string(true));
+
+      assert.strictEqual(err.argument, "name");
+      assert.strictEqual(
+        err.message,
+        'The "name" argument must be of type string. Received type boolean (true)'
+      );
+    });
+
+    it("should summarize the received value", function () {
+      const received = (v) => error(() => addon.argument_string(v)).received;
+
+      assert.strictEqual(
+        error(() => addon.argument_string()).received,
+        "undefined"
+      );
+      assert.strictEqual(received(undefined), "undefined");
+      assert.strictEqual(received(null), "null");
+      assert.strictEqual(received(1n), "type bigint (1n)");
+      assert.strictEqual(received(Symbol("s")), "type symbol (Symbol(s))");
+      assert.strictEqual(received(new Date(0)), "an instance of Date");
+      assert.strictEqual(received(Object.create(null)), "type object");
+      assert.strictEqual(received(function foo() {}), "function foo");
+      assert.strictEqual(
+        error(() => addon.argument_array("a".repeat(100))).received,
+        "type string ('aaaaaaaaaaaaaaaaaaaaaaaa...)"
+      );
+    });
+
+    it("should name a class as an instance", function () {
+      const err = error(() => addon.argument_array({}));
+
+      assert.strictEqual(
+        err.message,
+        'The "arguments[0]" argument must be an instance of Array. Received an instance of Object'
+      );
+    });
+
+    it("should throw ERR_OUT_OF_RANGE for a value out of range", function () {
+      const err = error(() => addon.extract_u64(1.5));
+
+      assert.instanceOf(err, RangeError);
+      assert.strictEqual(err.code, "ERR_OUT_OF_RANGE");
+      assert.strictEqual(err.received, "1.5");
+      assert.strictEqual(
+        err.message,
+        "The value is out of range. It must be an integer. Received 1.5"
+      );
+      assert.strictEqual(
+        error(() => addon.extract_u64(-1n)).message,
+        "The value is out of range. It must be >= 0n and <= 18446744073709551615n. Received -1n"
+      );
+    });
+
+    it("should throw plain errors for compatibility", function () {
+      addon.set_plain_argument_errors(true);
+
+      try {
+        const err = error(() => addon.argument_string(42));
+
+        assert.instanceOf(err, TypeError);
+        assert.strictEqual(err.message, "failed to downcast any to string");
+        assert.isUndefined(err.code);
+        assert.strictEqual(
+          error(() => addon.argument_string()).message,
+          "not enough arguments"
+        );
+        assert.strictEqual(
+          error(() => addon.extract_u64(1.5)).message,
+          "number 1.5 is not a safe integer in the range of `u64`"
+        );
+      } finally {
+        addon.set_plain_argument_errors(false);
+      }
+    });
+  });
+
+  describe("pending exceptions", function () {
+    function error(f) {
+      try {
+        f();
+      } catch (err) {
+        return err;
+      }
+
+      throw new Error("Expected function to throw");
+    }
+
+    it("should allow operations that don't run JavaScript", function () {
+      const err = new Error("first");
+      const { caught, checks } = addon.pending_safe_operations(err);
+
+      assert.strictEqual(caught, err);
+      assert.deepEqual(checks, {
+        string: true,
+        number: true,
+        array: true,
+        error: true,
+        box: true,
+        lock: true,
+        root: true,
+        date: true,
+        promise: true,
+      });
+    });
+
+    it("should fail operations that run JavaScript", function () {
+      const err = new Error("first");
+      const object = {
+        get a() {
+          throw new Error("getter called");
+        },
+      };
+      let called = false;
+      const { caught, checks } = addon.pending_failing_operations(
+        err,
+        object,
+        () => {
+          called = true;
+        }
+      );
+
+      assert.strictEqual(caught, err);
+      assert.isFalse(called);
+      assert.deepEqual(checks, {
+        get: true,
+        set: true,
+        call: true,
+        construct: true,
+        coerce: true,
+        buffer: true,
+      });
+    });
+
+    it("should restore a suppressed exception", function () {
+      const err = new Error("original");
+      let cleaned = false;
+
+      const caught = error(() =>
+        addon.suppress_exception(err, () => {
+          cleaned = addon.new_error("cleanup") instanceof Error;
+        })
+      );
+
+      assert.strictEqual(caught, err);
+      assert.isTrue(cleaned);
+    });
+
+    it("should restore a suppressed exception if cleanup throws", function () {
+      const err = { original: true };
+
+      const caught = error(() =>
+        addon.suppress_exception(err, () => {
+          throw new Error("cleanup");
+        })
+      );
+
+      assert.strictEqual(caught, err);
+    });
+
+    it("should throw from cleanup without a pending exception", function () {
+      assert.throws(
+        () =>
+          addon.suppress_exception(undefined, () => {
+            throw new Error("cleanup");
+          }),
+        /^cleanup$/
+      );
+    });
+  });
 });
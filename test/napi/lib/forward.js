@@ -0,0 +1,94 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+const range = (n) => Array.from({ length: n }, (_, i) => i);
+
+describe("forward", function () {
+  it("delivers items to a callback in order", function (cb) {
+    const received = [];
+
+    addon.forward_callback(
+      1000,
+      (i) => received.push(i),
+      () => {
+        try {
+          assert.deepEqual(received, range(1000));
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      }
+    );
+  });
+
+  it("emits items as events in order", function (cb) {
+    const received = [];
+
+    addon.forward_event(100, (i) => {
+      received.push(i);
+
+      if (received.length === 100) {
+        try {
+          assert.deepEqual(received, range(100));
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      }
+    });
+  });
+
+  it("blocks a bounded sender while JavaScript is behind", function (cb) {
+    const received = [];
+
+    addon.forward_back_pressure(100, 2, 2, (i) => {
+      received.push(i);
+
+      if (received.length === 100) {
+        try {
+          assert.deepEqual(received, range(100));
+          assert.strictEqual(addon.forward_sent(), 100);
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      }
+    });
+
+    // Nothing is delivered while the JavaScript thread is busy: two items are in
+    // flight, two fill the channel and the sender blocks on the fifth
+    const start = Date.now();
+
+    while (Date.now() - start < 50) {}
+
+    assert.strictEqual(addon.forward_sent(), 4);
+    assert.deepEqual(received, []);
+  });
+
+  it("tears down an instance while forwarding", function (cb) {
+    const worker = new Worker(
+      `
+      const addon = require(${JSON.stringify(require.resolve(".."))});
+
+      addon.forward_endless(() => {});
+      setTimeout(() => process.exit(0), 10);
+      `,
+      { eval: true, stderr: true }
+    );
+
+    let stderr = "";
+
+    worker.stderr.on("data", (chunk) => (stderr += chunk));
+    worker.on("error", cb);
+    worker.on("exit", (code) => {
+      try {
+        assert.strictEqual(code, 0);
+        assert.strictEqual(stderr, "");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+});
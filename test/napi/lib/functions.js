@@ -216,6 +216,77 @@ describe("JsFunction", function () {
     );
   });
 
+  it("extracts a Rust struct from a function result with apply_into", function () {
+    assert.strictEqual(
+      addon.call_and_extract_point(() => ({ x: 3, y: 4 })),
+      5
+    );
+  });
+
+  it("propagates an exception thrown by the callback from apply_into", function () {
+    const err = new Error("callback failed");
+
+    assert.throws(
+      () =>
+        addon.call_and_extract_point(() => {
+          throw err;
+        }),
+      err
+    );
+  });
+
+  it("throws a TypeError naming the Rust type from apply_into", function () {
+    assert.throws(
+      () => addon.call_and_extract_point(() => ({ x: 3, y: "4" })),
+      TypeError,
+      /`[\w:]*Point`/
+    );
+  });
+
+  it("checks that the callback returns undefined with exec_checked", function () {
+    let called = false;
+
+    addon.call_and_exec_checked(() => {
+      called = true;
+    });
+
+    assert.isTrue(called);
+    assert.throws(
+      () => addon.call_and_exec_checked(() => 42),
+      TypeError,
+      /expected function to return `undefined`/
+    );
+  });
+
+  it("passes undefined for a None argument", function () {
+    const args = addon.call_with_optional_args((...args) => args);
+
+    assert.deepStrictEqual(args, [1, undefined, null, "nested"]);
+    assert.strictEqual(
+      addon.call_with_optional_args((a, b = "default") => b),
+      "default"
+    );
+  });
+
+  it("passes undefined for a None constructor argument", function () {
+    function Pair(a = "a", b) {
+      this.a = a;
+      this.b = b;
+    }
+
+    const pair = addon.construct_with_optional_args(Pair);
+
+    assert.instanceOf(pair, Pair);
+    assert.strictEqual(pair.a, "a");
+    assert.strictEqual(pair.b, "b");
+  });
+
+  it("converts None handles to undefined", function () {
+    assert.strictEqual(addon.undefined_if_none(42), 42);
+    assert.strictEqual(addon.undefined_if_none(null), null);
+    assert.strictEqual(addon.undefined_if_none(true), undefined);
+  });
+
   it("can return Rust type from cx.try_catch", function () {
     const n = Math.random();
     assert.strictEqual(addon.get_number_or_default(n), n);
@@ -246,4 +317,243 @@ describe("JsFunction", function () {
       global.gc();
     }
   );
+  describe("JsFunction::wrap", function () {
+    it("transforms the return value", function () {
+      const wrapped = addon.wrap_with_hooks((x) => x + 1, {
+        after: (result) => result * 10,
+      });
+
+      assert.strictEqual(wrapped(1), 20);
+    });
+
+    it("short-circuits from the before hook", function () {
+      let called = false;
+      const wrapped = addon.wrap_with_hooks(
+        () => {
+          called = true;
+          return "target";
+        },
+        {
+          before: ([arg]) => (arg === "skip" ? "cached" : undefined),
+          after: () => "after",
+        }
+      );
+
+      assert.strictEqual(wrapped("skip"), "cached");
+      assert.isFalse(called);
+      assert.strictEqual(wrapped("call"), "after");
+      assert.isTrue(called);
+    });
+
+    it("preserves this and the number of arguments", function () {
+      const seen = [];
+      const wrapped = addon.wrap_with_hooks(
+        function (...args) {
+          return [this, args.length];
+        },
+        { before: (args) => void seen.push(args.length) }
+      );
+      const obj = { wrapped };
+      const [self, len] = obj.wrapped(undefined, undefined, undefined);
+
+      assert.strictEqual(self, obj);
+      assert.strictEqual(len, 3);
+      assert.deepEqual(seen, [3]);
+    });
+
+    it("constructs the target when called with new", function () {
+      class Point {
+        constructor(x, y) {
+          this.x = x;
+          this.y = y;
+        }
+      }
+
+      let constructed;
+      const Wrapped = addon.wrap_with_hooks(Point, {
+        before: (_, isConstruct) => void (constructed = isConstruct),
+      });
+      const point = new Wrapped(1, 2);
+
+      assert.instanceOf(point, Point);
+      assert.strictEqual(point.x, 1);
+      assert.strictEqual(point.y, 2);
+      assert.isTrue(constructed);
+      assert.throws(() => Wrapped(1, 2), TypeError);
+      assert.isFalse(constructed);
+    });
+
+    it("passes new.target through to the target", function () {
+      let target;
+
+      class Point {
+        constructor(x, y) {
+          target = new.target;
+          this.x = x;
+          this.y = y;
+        }
+      }
+
+      const Wrapped = addon.wrap_with_hooks(Point, {});
+
+      class Point3 extends Wrapped {
+        constructor(x, y, z) {
+          super(x, y);
+          this.z = z;
+        }
+
+        sum() {
+          return this.x + this.y + this.z;
+        }
+      }
+
+      const point = new Point3(1, 2, 3);
+
+      assert.strictEqual(target, Point3);
+      assert.instanceOf(point, Point3);
+      assert.instanceOf(point, Wrapped);
+      assert.instanceOf(point, Point);
+      assert.strictEqual(point.sum(), 6);
+      assert.instanceOf(new Wrapped(1, 2), Wrapped);
+    });
+
+    it("copies name and length from the target", function () {
+      function add(a, b, c) {
+        return a + b + c;
+      }
+
+      const wrapped = addon.wrap_with_hooks(add, {});
+
+      assert.strictEqual(wrapped.name, "add");
+      assert.strictEqual(wrapped.length, 3);
+    });
+
+    it("observes and re-throws exceptions", function () {
+      const error = new Error("Oh, no!");
+      let observed;
+      const wrapped = addon.wrap_with_hooks(
+        () => {
+          throw error;
+        },
+        { onThrow: (err) => (observed = err) }
+      );
+
+      assert.throws(() => wrapped(), error);
+      assert.strictEqual(observed, error);
+    });
+  });
+
+  describe("passthrough", function () {
+    it("returns the same object or function", function () {
+      const obj = {};
+      const arr = [];
+      const f = () => {};
+
+      assert.strictEqual(addon.passthrough_nth(1, obj), obj);
+      assert.strictEqual(addon.passthrough_nth(2, 0, arr), arr);
+      assert.strictEqual(addon.passthrough_nth(1, f), f);
+      assert.strictEqual(addon.passthrough_nth(0), 0);
+    });
+
+    it("returns primitives unchanged", function () {
+      assert.isTrue(Object.is(addon.passthrough_nth(1, -0), -0));
+      assert.isNaN(addon.passthrough_nth(1, NaN));
+      assert.isUndefined(addon.passthrough_nth(1, undefined));
+      assert.strictEqual(addon.passthrough_nth(1, 10n), 10n);
+    });
+
+    it("throws a RangeError for a missing argument", function () {
+      assert.throws(
+        () => addon.passthrough_nth(1),
+        RangeError,
+        "cannot return arguments[1]: the function was called with 1 argument"
+      );
+      assert.throws(
+        () => addon.passthrough_nth(3, 1, 2),
+        RangeError,
+        /called with 3 arguments/
+      );
+    });
+
+    it("returns `this` unchanged", function () {
+      const obj = { passthrough: addon.passthrough_this };
+
+      assert.strictEqual(obj.passthrough(), obj);
+      assert.strictEqual(addon.passthrough_this.call(addon), addon);
+    });
+
+    it("passes through with extracted arguments in an exported function", function () {
+      const value = { nested: [1] };
+
+      assert.strictEqual(addon.passthrough_labeled("label", value), value);
+      assert.throws(
+        () => addon.passthrough_labeled(1, value),
+        TypeError,
+        /"valueLabel"/
+      );
+      assert.throws(() => addon.passthrough_labeled("label"), RangeError);
+    });
+  });
+
+  describe("Provided", function () {
+    it("tells a missing last argument from undefined", function () {
+      assert.strictEqual(addon.provided_nth(1), "missing");
+      assert.strictEqual(addon.provided_nth(1, undefined), "undefined");
+      assert.strictEqual(addon.provided_nth(1, "label"), "label");
+      assert.throws(() => addon.provided_nth(1, 42), TypeError);
+    });
+
+    it("is never missing before a passed argument", function () {
+      assert.strictEqual(addon.provided_nth(1, undefined, "b"), "undefined");
+      assert.strictEqual(addon.provided_nth(1, "a", undefined), "a");
+      assert.strictEqual(addon.provided_nth(2, "a"), "missing");
+    });
+
+    it("extracts the remaining arguments with Rest", function () {
+      assert.deepEqual(addon.provided_rest(), ["missing", []]);
+      assert.deepEqual(addon.provided_rest(undefined), ["undefined", []]);
+      assert.deepEqual(addon.provided_rest(1, 2, undefined, 3), [
+        1,
+        [2, "undefined", 3],
+      ]);
+      assert.throws(() => addon.provided_rest(1, 2, "3"), TypeError);
+    });
+  });
+
+  describe("call1, call2 and call3", function () {
+    it("call the function with its arguments", function () {
+      const f = function (...args) {
+        return [this, ...args];
+      };
+      const obj = { call_n: addon.call_n };
+
+      assert.deepEqual(obj.call_n(f, 1, "a", "b", "c"), [obj, "a"]);
+      assert.deepEqual(obj.call_n(f, 2, "a", "b", "c"), [obj, "a", "b"]);
+      assert.deepEqual(obj.call_n(f, 3, "a", "b", "c"), [obj, "a", "b", "c"]);
+    });
+
+    it("propagate exceptions", function () {
+      const f = () => {
+        throw new RangeError("failed");
+      };
+
+      assert.throws(() => addon.call_n(f, 2, 1, 2, 3), RangeError, "failed");
+    });
+
+    it("pass more arguments than fit inline with call_with", function () {
+      const args = addon.call_with_count((...args) => args, 20);
+
+      assert.deepEqual(args, Array.from({ length: 20 }, (_, i) => i));
+    });
+
+    it("call without allocating", function () {
+      const f = (a, b) => a + b;
+      const n = 100000;
+
+      // Any allocation for each call would allocate at least `n` bytes
+      assert.isBelow(addon.call_repeat(f, n, "call_with"), n);
+      assert.isBelow(addon.call_repeat(f, n, "call2"), n);
+      assert.isAtLeast(addon.call_repeat(f, n, "vec"), n);
+    });
+  });
 });
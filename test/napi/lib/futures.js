@@ -54,5 +54,30 @@ describe("Futures", () => {
         });
       }, /exception/i);
     });
+
+    it("should convert an already resolved promise", async () => {
+      const promise = Promise.resolve(21);
+
+      assert.strictEqual(await addon.future_double(promise), 42);
+      assert.strictEqual(await addon.future_double(promise), 42);
+    });
+
+    it("should convert an already rejected promise", async () => {
+      const promise = Promise.reject(new Error("Oh, no!"));
+
+      promise.catch(() => {});
+
+      await assertRejects(() => addon.future_double(promise), /exception/i);
+    });
+
+    it("should wait for a pending promise", async () => {
+      let resolve;
+      const promise = new Promise((r) => (resolve = r));
+      const result = addon.future_double(promise);
+
+      resolve(4);
+
+      assert.strictEqual(await result, 8);
+    });
   });
 });
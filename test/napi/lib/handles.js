@@ -0,0 +1,21 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+const describeDebugHandles = addon.debug_handles_enabled
+  ? describe
+  : describe.skip;
+
+describeDebugHandles("debug-handles", () => {
+  it("should allow handles from outer contexts in nested scopes", () => {
+    assert.strictEqual(addon.handles_use_outer(5), 16);
+  });
+
+  it("should report where an escaped handle was created and used", () => {
+    addon.handles_escape();
+
+    assert.throws(
+      () => addon.handles_use_escaped(),
+      /handle created at .*handles\.rs:\d+:\d+ used at .*handles\.rs:\d+:\d+ after its scope ended/
+    );
+  });
+});
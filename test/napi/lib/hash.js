@@ -0,0 +1,128 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+const itGc = typeof global.gc === "function" ? it : it.skip;
+
+describe("hash", () => {
+  it("should hash equal primitives equally across calls", () => {
+    const values = [undefined, null, true, false, 0, 1.5, -7, "", "héllo"];
+
+    for (const value of values) {
+      const copy = typeof value === "string" ? [...value].join("") : value;
+
+      assert.strictEqual(addon.hash_values([value]), addon.hash_values([copy]));
+      assert.isTrue(addon.hash_equal(value, copy));
+    }
+
+    const long = "x".repeat(10000);
+
+    assert.strictEqual(
+      addon.hash_values([long]),
+      addon.hash_values(["x".repeat(10000)])
+    );
+  });
+
+  it("should distinguish different values and types", () => {
+    const values = [undefined, null, true, false, 0, 1, "", "0", "1", "true"];
+    const hashes = values.map((value) => addon.hash_values([value]));
+
+    assert.strictEqual(new Set(hashes).size, hashes.length);
+    assert.isFalse(addon.hash_equal(0, "0"));
+    assert.isFalse(addon.hash_equal(null, undefined));
+    assert.isFalse(addon.hash_equal("a", "b"));
+  });
+
+  it("should hash tuples with delimited values", () => {
+    assert.strictEqual(
+      addon.hash_values(["a", 1]),
+      addon.hash_values(["a", 1])
+    );
+    assert.notStrictEqual(
+      addon.hash_values(["ab", "c"]),
+      addon.hash_values(["a", "bc"])
+    );
+    assert.notStrictEqual(
+      addon.hash_values(["a", 1]),
+      addon.hash_values([1, "a"])
+    );
+  });
+
+  it("should treat NaN as equal to NaN and -0 as equal to +0", () => {
+    assert.strictEqual(addon.hash_values([NaN]), addon.hash_values([0 / 0]));
+    assert.isTrue(addon.hash_equal(NaN, 0 / 0));
+    assert.strictEqual(addon.hash_values([-0]), addon.hash_values([0]));
+    assert.isTrue(addon.hash_equal(-0, 0));
+  });
+
+  it("should throw for objects without the identity option", () => {
+    assert.throws(() => addon.hash_values([{}]), TypeError, /identity/);
+    assert.throws(() => addon.hash_values([[1]]), TypeError, /identity/);
+    assert.throws(() => addon.hash_equal({}, {}), TypeError, /identity/);
+    assert.throws(() => addon.hash_values([Symbol()]), TypeError, /symbol/);
+    assert.throws(() => addon.hash_values([1n]), TypeError, /bigint/);
+  });
+
+  it("should hash objects by identity", () => {
+    const a = {};
+    const b = {};
+    const f = () => {};
+
+    assert.strictEqual(
+      addon.hash_values([a], true),
+      addon.hash_values([a], true)
+    );
+    assert.notStrictEqual(
+      addon.hash_values([a], true),
+      addon.hash_values([b], true)
+    );
+    assert.strictEqual(
+      addon.hash_values([f], true),
+      addon.hash_values([f], true)
+    );
+    assert.isTrue(addon.hash_equal(a, a, true));
+    assert.isFalse(addon.hash_equal(a, b, true));
+    assert.isFalse(addon.hash_equal(a, "a", true));
+    assert.strictEqual(
+      addon.hash_values(["a"], true),
+      addon.hash_values(["a"])
+    );
+  });
+
+  it("should hash frozen objects by identity", () => {
+    const frozen = Object.freeze({});
+
+    assert.strictEqual(
+      addon.hash_values([frozen], true),
+      addon.hash_values([frozen], true)
+    );
+    assert.deepEqual(Reflect.ownKeys(frozen), []);
+  });
+
+  itGc("should keep identity hashes stable across collections", async () => {
+    const a = { value: 1 };
+    const before = addon.hash_values([a], true);
+
+    for (let i = 0; i < 3; i++) {
+      // Allocate and discard objects that are hashed by identity
+      for (let j = 0; j < 1000; j++) {
+        addon.hash_values([{ j }], true);
+      }
+
+      global.gc();
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    assert.strictEqual(addon.hash_values([a], true), before);
+  });
+
+  it("should hash strings without allocating", () => {
+    const { direct, converted } = addon.hash_allocations("a short string");
+
+    assert.strictEqual(direct, 0);
+    assert.isAbove(converted, 0);
+
+    const long = addon.hash_allocations("x".repeat(10000));
+
+    assert.isAtMost(long.direct, long.converted);
+  });
+});
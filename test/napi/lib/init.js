@@ -0,0 +1,128 @@
+const { spawnSync } = require("child_process");
+const { assert } = require("chai");
+
+// Requires the addon in a new process with the environment variables `env` and
+// returns the error thrown by `require()`, if any, and the output to stderr
+function load(env) {
+  const { stdout, stderr, status } = spawnSync(
+    process.execPath,
+    [
+      "-e",
+      `
+      try {
+        require(${JSON.stringify(require.resolve(".."))});
+        console.log(JSON.stringify(null));
+      } catch (err) {
+        const { name, message, code, phase } = err;
+        const cause = err.cause && {
+          name: err.cause.name,
+          message: err.cause.message,
+        };
+        console.log(JSON.stringify({ name, message, code, phase, cause }));
+      }
+      `,
+    ],
+    {
+      env: { ...process.env, RUST_BACKTRACE: "0", ...env },
+      encoding: "utf8",
+    }
+  );
+
+  assert.strictEqual(status, 0, stderr);
+
+  return { err: JSON.parse(stdout), stderr };
+}
+
+function traceLines(stderr) {
+  return stderr.split("\n").filter((line) => line.startsWith("[neon] "));
+}
+
+describe("Module initialization", function () {
+  it("should throw a catchable error from require() on a panic", function () {
+    const { err } = load({ NEON_TEST_INIT_FAILURE: "panic" });
+
+    assert.deepEqual(err, {
+      name: "Error",
+      message: "napi-tests: panicked during main: main panicked",
+      code: "ERR_NEON_INIT_PANIC",
+      phase: "main",
+    });
+  });
+
+  it("should wrap errors thrown by main with a prefixed message", function () {
+    const { err } = load({ NEON_TEST_INIT_FAILURE: "throw" });
+
+    assert.strictEqual(err.name, "Error");
+    assert.strictEqual(err.message, "napi-tests: main failed");
+    assert.isUndefined(err.code);
+    assert.deepEqual(err.cause, { name: "TypeError", message: "main failed" });
+  });
+
+  it("should trace the phases with NEON_INIT_TRACE", function () {
+    const { err, stderr } = load({ NEON_INIT_TRACE: "1" });
+    const lines = traceLines(stderr);
+
+    assert.isNull(err);
+    assert.deepEqual(
+      lines.map((line) => line.replace(/ in .*$/, "")),
+      [
+        "[neon] napi-tests: pre-export started",
+        "[neon] napi-tests: pre-export finished",
+        "[neon] napi-tests: main started",
+        "[neon] napi-tests: main finished",
+      ]
+    );
+  });
+
+  it("should trace the phase that failed", function () {
+    const { stderr } = load({
+      NEON_INIT_TRACE: "1",
+      NEON_TEST_INIT_FAILURE: "panic",
+    });
+
+    assert.include(
+      traceLines(stderr),
+      "[neon] napi-tests: main failed: napi-tests: panicked during main: main panicked"
+    );
+  });
+
+  it("should not trace without NEON_INIT_TRACE", function () {
+    assert.isEmpty(traceLines(load({ NEON_INIT_TRACE: "" }).stderr));
+    assert.isEmpty(traceLines(load({ NEON_INIT_TRACE: "0" }).stderr));
+  });
+});
+
+describe("Code generation from strings", function () {
+  it("should not be needed by Neon", function () {
+    const { stdout, stderr, status } = spawnSync(
+      process.execPath,
+      [
+        "--disallow-code-generation-from-strings",
+        "-e",
+        `
+        const addon = require(${JSON.stringify(require.resolve(".."))});
+        const ring = addon.ring_create(1, 2, false);
+
+        addon.ring_push(ring.writer, [7]);
+        addon.rust_trace_set_enabled(true);
+
+        console.log(JSON.stringify({
+          record: Array.from(ring.read()),
+          heapSizeLimit: typeof addon.memory_stats().heapSizeLimit,
+          published: addon.channel_publish("neon:codegen", {}),
+          rustStack: typeof addon.rust_trace_error().rustStack,
+        }));
+        `,
+      ],
+      { encoding: "utf8" }
+    );
+
+    assert.strictEqual(status, 0, stderr);
+    assert.deepEqual(JSON.parse(stdout), {
+      record: [7],
+      heapSizeLimit: "number",
+      published: false,
+      rustStack: "string",
+    });
+  });
+});
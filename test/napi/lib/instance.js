@@ -0,0 +1,76 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("Instance", () => {
+  it("should pass typed instances between functions", () => {
+    const db = new addon.Database("users");
+
+    assert.strictEqual(addon.database_query(db, "SELECT 1"), "users: SELECT 1");
+    assert.strictEqual(addon.database_identity(db), db);
+    assert.strictEqual(addon.database_name(db), "users");
+    assert.strictEqual(
+      addon.database_name(addon.database_create("logs")),
+      "logs"
+    );
+  });
+
+  it("should extract an instance of a subclass", () => {
+    class CachedDatabase extends addon.Database {
+      cached() {
+        return true;
+      }
+    }
+
+    const db = new CachedDatabase("cache");
+
+    assert.instanceOf(db, addon.Database);
+    assert.strictEqual(addon.database_query(db, "SELECT 2"), "cache: SELECT 2");
+    assert.strictEqual(addon.database_identity(db), db);
+    assert.isTrue(addon.database_identity(db).cached());
+  });
+
+  it("should reject objects that do not wrap the class", () => {
+    const plain = { name: "users" };
+
+    assert.throws(
+      () => addon.database_name(plain),
+      TypeError,
+      "expected Database instance"
+    );
+    assert.throws(
+      () => addon.database_query(plain, "SELECT 1"),
+      TypeError,
+      /must be an instance of Database/
+    );
+    assert.throws(
+      () => addon.database_name(addon.person_new("Alice")),
+      TypeError
+    );
+    assert.throws(() => addon.database_name(42), TypeError);
+  });
+
+  it("should not wrap an object twice", () => {
+    const db = new addon.Database("users");
+
+    assert.throws(
+      () => addon.Database.call(db, "other"),
+      TypeError,
+      /already wraps/
+    );
+    assert.strictEqual(addon.database_name(db), "users");
+  });
+
+  it("should survive a Root round-trip through a channel", (cb) => {
+    const db = new addon.Database("rooted");
+
+    addon.database_root_roundtrip(db, (returned, name) => {
+      try {
+        assert.strictEqual(returned, db);
+        assert.strictEqual(name, "rooted");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+});
@@ -0,0 +1,113 @@
+const assert = require("assert");
+const { once } = require("events");
+const {
+  Worker,
+  isMainThread,
+  parentPort,
+  workerData,
+} = require("worker_threads");
+
+const addon = require("..");
+
+// Report the id of this instance and each message routed to it
+if (!isMainThread) {
+  if (workerData && workerData.unlisted) {
+    addon.instance_set_listed(false);
+  }
+
+  global.onInstanceMessage = (message) => {
+    parentPort.postMessage({ message, local: addon.instance_message() });
+  };
+
+  // Keep the worker alive until it is terminated
+  parentPort.on("message", () => {});
+  parentPort.postMessage({ id: addon.instance_id() });
+
+  return;
+}
+
+// Starts a worker and resolves with the worker and the id of its instance
+async function spawn(workerData) {
+  const worker = new Worker(__filename, { workerData });
+  const [{ id }] = await once(worker, "message");
+
+  return { worker, id };
+}
+
+describe("Instance registry", () => {
+  it("should list the instance of the main thread", () => {
+    const id = addon.instance_id();
+
+    assert.strictEqual(typeof id, "number");
+    assert.strictEqual(addon.instance_id(), id);
+    assert.ok(addon.instance_ids().includes(id));
+  });
+
+  it("should send a closure to the instance of the main thread", async () => {
+    const received = new Promise((resolve) => {
+      global.onInstanceMessage = resolve;
+    });
+
+    try {
+      addon.instance_send_to(addon.instance_id(), "to main");
+
+      assert.strictEqual(await received, "to main");
+      assert.strictEqual(addon.instance_message(), "to main");
+    } finally {
+      delete global.onInstanceMessage;
+    }
+  });
+});
+
+describe("Instance registry with workers", () => {
+  it("should broadcast a closure to every instance", async () => {
+    const workers = [await spawn(), await spawn()];
+
+    try {
+      const ids = addon.instance_ids();
+
+      for (const { id } of workers) {
+        assert.ok(ids.includes(id));
+      }
+
+      const received = workers.map(({ worker }) => once(worker, "message"));
+      const count = addon.instance_broadcast("hello");
+
+      assert.ok(count >= 3);
+
+      for (const [{ message, local }] of await Promise.all(received)) {
+        assert.strictEqual(message, "hello");
+        assert.strictEqual(local, "hello");
+      }
+    } finally {
+      await Promise.all(workers.map(({ worker }) => worker.terminate()));
+    }
+  });
+
+  it("should fail to send to an instance that exited", async () => {
+    const { worker, id } = await spawn();
+
+    await worker.terminate();
+
+    assert.ok(!addon.instance_ids().includes(id));
+    assert.throws(
+      () => addon.instance_send_to(id, "too late"),
+      new RegExp(`^Error: instance ${id} is not running or not listed$`)
+    );
+  });
+
+  it("should not list an instance that opted out", async () => {
+    const { worker, id } = await spawn({ unlisted: true });
+
+    try {
+      assert.ok(!addon.instance_ids().includes(id));
+      assert.ok(addon.instance_ids().includes(addon.instance_id()));
+      assert.throws(
+        () => addon.instance_send_to(id, "unlisted"),
+        /is not running or not listed/
+      );
+    } finally {
+      await worker.terminate();
+    }
+  });
+});
@@ -0,0 +1,21 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("sys_interop", () => {
+  it("should round trip a value through a raw napi_value", () => {
+    const o = {};
+
+    assert.strictEqual(addon.interop_round_trip(o), o);
+    assert.strictEqual(addon.interop_round_trip("hello"), "hello");
+    assert.strictEqual(addon.interop_round_trip(undefined), undefined);
+  });
+
+  it("should downcast a raw napi_value", () => {
+    assert.strictEqual(addon.interop_string_from_raw("hello"), "hello");
+    assert.throws(() => addon.interop_string_from_raw(42), TypeError);
+  });
+
+  it("should call Node-API with a raw napi_env", () => {
+    assert.strictEqual(addon.interop_create_uint32(42), 42);
+  });
+});
@@ -0,0 +1,102 @@
+const addon = require("..");
+const assert = require("chai").assert;
+
+// Yields `values`, recording whether the generator's `finally` block ran
+function* track(state, values) {
+  try {
+    yield* values;
+  } finally {
+    state.finished = true;
+  }
+}
+
+describe("JsIterable", () => {
+  it("should collect an array, set and generator", () => {
+    assert.strictEqual(addon.iterable_sum([1, 2, 3]), 6);
+    assert.strictEqual(addon.iterable_sum(new Set([1, 2, 3])), 6);
+    assert.strictEqual(addon.iterable_sum(track({}, [1, 2, 3])), 6);
+  });
+
+  it("should provide size hints", () => {
+    assert.strictEqual(addon.iterable_size_hint([1, 2, 3]), 3);
+    assert.strictEqual(addon.iterable_size_hint(new Set([1, 2])), 2);
+    assert.strictEqual(addon.iterable_size_hint(new Map([[1, 2]])), 1);
+    assert.isNull(addon.iterable_size_hint(track({}, [1])));
+  });
+
+  it("should not trust the size hint to reserve capacity", () => {
+    const iterable = {
+      length: Number.MAX_SAFE_INTEGER,
+      *[Symbol.iterator]() {
+        yield* [1, 2];
+      },
+    };
+
+    assert.strictEqual(
+      addon.iterable_size_hint(iterable),
+      Number.MAX_SAFE_INTEGER
+    );
+    assert.strictEqual(addon.iterable_sum(iterable), 3);
+  });
+
+  it("should throw a TypeError for non-iterable values", () => {
+    for (const v of [null, undefined, 42, {}, { [Symbol.iterator]: 1 }]) {
+      assert.throws(() => addon.iterable_sum(v), TypeError, /not iterable/);
+    }
+  });
+
+  it("should propagate an exception thrown by next()", () => {
+    function* throwing() {
+      yield 1;
+      yield 2;
+      throw new Error("third");
+    }
+
+    assert.throws(() => addon.iterable_sum(throwing()), /third/);
+
+    const [sum, err] = addon.iterable_try_sum(throwing());
+
+    assert.isUndefined(sum);
+    assert.match(err.message, /third/);
+
+    // No exception is left pending
+    assert.deepEqual(addon.iterable_try_sum([1, 2]), [3]);
+  });
+
+  it("should call return() when breaking early", () => {
+    const state = { finished: false };
+    const iter = track(state, [1, 2, 3, 4]);
+
+    assert.deepEqual(addon.iterable_take(iter, 2), [1, 2]);
+    assert.isTrue(state.finished);
+    assert.isTrue(iter.next().done);
+  });
+
+  it("should call return() when the callback throws", () => {
+    const state = { finished: false };
+
+    assert.throws(
+      () =>
+        addon.iterable_for_each(track(state, [1, 2, 3]), (v) => {
+          if (v === 2) {
+            throw new Error("stop");
+          }
+        }),
+      /stop/
+    );
+
+    assert.isTrue(state.finished);
+  });
+
+  it("should call return() when an item has the wrong type", () => {
+    const state = { finished: false };
+
+    assert.throws(
+      () => addon.iterable_sum(track(state, [1, "2", 3])),
+      TypeError,
+      /index 1 .*`f64`/
+    );
+
+    assert.isTrue(state.finished);
+  });
+});
@@ -0,0 +1,155 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+const RECORD_BYTES = (i) =>
+  JSON.stringify({ id: i, name: `item ${i}`, tags: ["a", "b"] }).length;
+
+describe("JSON streaming", () => {
+  it("should stream a large structure with bounded memory", function () {
+    this.timeout(60000);
+
+    // ~200MB of JSON text
+    const count = 4000000;
+    const chunkSize = 64 * 1024;
+    const before = process.memoryUsage().rss;
+
+    let expected = 2 + count - 1;
+    let length = 0;
+    let chunks = 0;
+    let peak = 0;
+    let first = "";
+
+    for (let i = 0; i < count; i++) {
+      expected += RECORD_BYTES(i);
+    }
+
+    addon.json_stringify_records(count, chunkSize, (chunk) => {
+      assert.isAtMost(Buffer.byteLength(chunk), chunkSize);
+
+      if (chunks === 0) {
+        first = chunk.slice(0, 20);
+      }
+
+      length += chunk.length;
+      chunks += 1;
+
+      if (chunks % 256 === 0) {
+        peak = Math.max(peak, process.memoryUsage().rss - before);
+      }
+    });
+
+    assert.isAbove(length, 200 * 1000 * 1000);
+    assert.strictEqual(length, expected);
+    assert.strictEqual(chunks, Math.ceil(expected / chunkSize));
+    assert.strictEqual(first, '[{"id":0,"name":"ite');
+    assert.isBelow(peak, 100 * 1024 * 1024);
+  });
+
+  it("should never split a multi-byte character across chunks", () => {
+    const s = 'aé€😀"\n'.repeat(10);
+
+    for (let chunkSize = 1; chunkSize <= 8; chunkSize++) {
+      const chunks = [];
+
+      addon.json_stringify_string(s, chunkSize, (chunk) => chunks.push(chunk));
+
+      for (const chunk of chunks) {
+        assert.isTrue(chunk.isWellFormed(), `chunk size ${chunkSize}`);
+        assert.isAtMost(Buffer.byteLength(chunk), Math.max(chunkSize, 4));
+      }
+
+      assert.strictEqual(chunks.join(""), JSON.stringify(s));
+    }
+  });
+
+  it("should propagate exceptions thrown by the sink", () => {
+    let calls = 0;
+
+    assert.throws(
+      () =>
+        addon.json_stringify_records(1000, 16, () => {
+          calls += 1;
+          throw new Error("sink failed");
+        }),
+      /sink failed/
+    );
+
+    assert.strictEqual(calls, 1);
+  });
+
+  it("should parse concatenated chunks back to an equal structure", () => {
+    const chunks = [];
+
+    addon.json_stringify_records(1000, 100, (chunk) => chunks.push(chunk));
+
+    const expected = Array.from({ length: 1000 }, (_, i) => ({
+      id: i,
+      name: `item ${i}`,
+      tags: ["a", "b"],
+    }));
+
+    assert.deepEqual(JSON.parse(chunks.join("")), expected);
+    assert.deepEqual(addon.json_parse_chunks(chunks), expected);
+  });
+
+  it("should parse text split at every character", () => {
+    const text = JSON.stringify({
+      string: 'quote " backslash \\ tab \t é 😀 \u0001',
+      numbers: [0, -1, 1.5, -2.5e-3, 1e21, 123456789],
+      literals: [true, false, null],
+      nested: { empty: {}, list: [[], [{}]] },
+    });
+
+    assert.deepEqual(
+      addon.json_parse_chunks(Array.from(text)),
+      JSON.parse(text)
+    );
+    assert.deepEqual(
+      addon.json_parse_chunks([" [ 1 ,", ' "\\ud83d', '\\ude00" ] ']),
+      [1, "😀"]
+    );
+  });
+
+  it("should keep escaped lone surrogates like `JSON.parse`", () => {
+    const text = '{"\\ud800": ["\\udc00", "a\\ud83db", "\\ude00\\ud83d"]}';
+    const parsed = addon.json_parse_chunks([text]);
+
+    assert.deepEqual(parsed, JSON.parse(text));
+    assert.deepEqual(Object.keys(parsed), ["\ud800"]);
+    assert.strictEqual(parsed["\ud800"][0], "\udc00");
+  });
+
+  it("should define `__proto__` as an own property", () => {
+    const o = addon.json_parse_chunks(['{"__proto__": {"polluted": true}}']);
+
+    assert.strictEqual(Object.getPrototypeOf(o), Object.prototype);
+    assert.deepEqual(Object.keys(o), ["__proto__"]);
+    assert.isUndefined({}.polluted);
+  });
+
+  it("should throw a SyntaxError for invalid JSON", () => {
+    const invalid = [
+      "",
+      "[1,]",
+      '{"a" 1}',
+      "01",
+      "tru",
+      "[1] 2",
+      '"a',
+      "nul l",
+      '"\\u+abc"',
+      '"\\u-abc"',
+      '"\\u 123"',
+      '"\\u12"',
+    ];
+
+    for (const text of invalid) {
+      assert.throws(
+        () => addon.json_parse_chunks([text]),
+        SyntaxError,
+        undefined,
+        JSON.stringify(text)
+      );
+    }
+  });
+});
@@ -0,0 +1,63 @@
+const addon = require("..");
+const assert = require("chai").assert;
+
+describe("ModuleContext::export_lazy", function () {
+  it("includes the lazy export in the keys before it is read", function () {
+    const keys = Object.keys(addon);
+
+    assert.include(keys, "lazyMath");
+    assert.include(keys, "lazyStrings");
+    assert.include(keys, "syntheticLazy");
+  });
+
+  it("initializes the export once, when it is first read", function () {
+    assert.strictEqual(addon.lazy_inits(), 0);
+    assert.typeOf(
+      Object.getOwnPropertyDescriptor(addon, "lazyMath").get,
+      "function"
+    );
+
+    const math = addon.lazyMath;
+
+    assert.strictEqual(addon.lazy_inits(), 1);
+    assert.strictEqual(addon.lazyMath, math);
+    assert.strictEqual(addon.lazy_inits(), 1);
+  });
+
+  it("replaces the getter with the object", function () {
+    const math = addon.lazyMath;
+    const descriptor = Object.getOwnPropertyDescriptor(addon, "lazyMath");
+
+    assert.strictEqual(descriptor.value, math);
+    assert.isTrue(descriptor.enumerable);
+    assert.strictEqual(math.add(1, 2), 3);
+  });
+
+  it("initializes the export again if it throws", function () {
+    assert.throws(() => addon.lazyFailing, /first initialization fails/);
+    assert.typeOf(addon.lazyFailing, "object");
+  });
+
+  it("exports lazy namespaces with #[neon::export_config]", function () {
+    assert.typeOf(
+      Object.getOwnPropertyDescriptor(addon, "lazyStrings").get,
+      "function"
+    );
+    assert.strictEqual(addon.lazyStrings.toUpper("abc"), "ABC");
+    assert.strictEqual(addon.lazyStrings.toLower("ABC"), "abc");
+    assert.deepEqual(Object.keys(addon.lazyStrings), ["toUpper", "toLower"]);
+  });
+
+  it("exports eager namespaces with #[neon::export_config]", function () {
+    const descriptor = Object.getOwnPropertyDescriptor(addon, "eagerStrings");
+
+    assert.strictEqual(descriptor.get, undefined);
+    assert.strictEqual(addon.eagerStrings.stringLength("abc"), 3);
+  });
+
+  it("creates no functions until a lazy namespace is read", function () {
+    assert.deepEqual(addon.lazyCreated, { eager: 300, lazy: 0 });
+    assert.lengthOf(Object.keys(addon.syntheticLazy), 300);
+    assert.lengthOf(Object.keys(addon.syntheticEager), 300);
+  });
+});
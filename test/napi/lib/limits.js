@@ -0,0 +1,244 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+// `depth` nested arrays around `0`
+function nest(depth) {
+  let value = 0;
+
+  for (let i = 0; i < depth; i++) {
+    value = [value];
+  }
+
+  return value;
+}
+
+describe("limits", () => {
+  it("should measure a value", () => {
+    const usage = addon.limits_measure(
+      { name: "neon", tags: ["a", "b"], data: new Uint8Array(16) },
+      {}
+    );
+
+    assert.deepEqual(usage, {
+      depth: 2,
+      items: 5,
+      string_bytes: 18,
+      buffer_bytes: 16,
+    });
+  });
+
+  it("should accept a value just under all limits", () => {
+    const value = { a: ["xy", new ArrayBuffer(8)] };
+
+    addon.limits_measure(value, {
+      max_depth: 2,
+      max_items: 3,
+      max_string_bytes: 3,
+      max_buffer_bytes: 8,
+    });
+  });
+
+  it("should enforce max_depth", () => {
+    assert.throws(
+      () => addon.limits_measure({ a: { b: [{}] } }, { max_depth: 3 }),
+      RangeError,
+      /`max_depth` limit of 3 at `\$\.a\.b\[0\]`/
+    );
+  });
+
+  it("should enforce max_items", () => {
+    assert.throws(
+      () => addon.limits_measure({ a: [1, 2, 3] }, { max_items: 3 }),
+      RangeError,
+      /`max_items` limit of 3 at `\$\.a`/
+    );
+  });
+
+  it("should enforce max_string_bytes", () => {
+    assert.throws(
+      () => addon.limits_measure(["abc", "déf"], { max_string_bytes: 6 }),
+      RangeError,
+      /`max_string_bytes` limit of 6 at `\$\[1\]`/
+    );
+  });
+
+  it("should enforce max_buffer_bytes", () => {
+    assert.throws(
+      () =>
+        addon.limits_measure(
+          { small: Buffer.alloc(4), large: new Float64Array(4) },
+          { max_buffer_bytes: 32 }
+        ),
+      RangeError,
+      /`max_buffer_bytes` limit of 32 at `\$\.large`/
+    );
+  });
+
+  it("should read the length of a typed array without calling getters", () => {
+    class Shrinking extends Uint8Array {
+      get byteLength() {
+        return 0;
+      }
+    }
+
+    assert.throws(
+      () => addon.limits_measure(new Shrinking(64), { max_buffer_bytes: 32 }),
+      RangeError,
+      /`max_buffer_bytes` limit of 32/
+    );
+  });
+
+  it("should measure cyclic values", () => {
+    const value = { a: 1 };
+
+    value.self = value;
+
+    assert.strictEqual(addon.limits_measure(value, {}).items, 2);
+  });
+
+  it("should extract a value within the quota", () => {
+    const result = addon.limits_extract_numbers([1, 2, 3], { max_items: 3 });
+
+    assert.strictEqual(result.sum, 6);
+    assert.isUndefined(result.error);
+  });
+
+  it("should reject a value before allocating", () => {
+    const result = addon.limits_extract_numbers(new Array(10_000_000).fill(1), {
+      max_items: 1000,
+    });
+
+    assert.instanceOf(result.error, RangeError);
+    assert.match(result.error.message, /`max_items`/);
+
+    // Extracting would allocate at least 80 MB
+    assert.isBelow(result.allocated, 1 << 20);
+  });
+
+  it("should enforce the quota while extracting", () => {
+    let reads = 0;
+    const strings = ["a"];
+
+    // Returns a short string when it is first read, like a value that changes
+    // between a check and the extraction
+    Object.defineProperty(strings, 1, {
+      enumerable: true,
+      get() {
+        reads++;
+        return reads === 1 ? "b" : "c".repeat(1000);
+      },
+    });
+
+    assert.strictEqual(
+      addon.limits_extract_strings(strings, { max_string_bytes: 2 }),
+      2
+    );
+    assert.throws(
+      () => addon.limits_extract_strings(strings, { max_string_bytes: 2 }),
+      RangeError,
+      /`max_string_bytes` limit of 2/
+    );
+
+    // Each extraction read the element once
+    assert.strictEqual(reads, 2);
+  });
+
+  it("should enforce max_depth and max_buffer_bytes while extracting", () => {
+    assert.strictEqual(addon.limits_tree_depth(nest(3), { max_depth: 3 }), 3);
+    assert.throws(
+      () => addon.limits_tree_depth(nest(4), { max_depth: 3 }),
+      RangeError,
+      /`max_depth` limit of 3/
+    );
+
+    const numbers = new Float64Array(8);
+
+    assert.strictEqual(
+      addon.limits_extract_numbers(numbers, { max_buffer_bytes: 64 }).sum,
+      0
+    );
+    const { error } = addon.limits_extract_numbers(numbers, {
+      max_buffer_bytes: 63,
+    });
+
+    assert.match(error.message, /`max_buffer_bytes` limit of 63/);
+  });
+
+  describe("conversion depth", () => {
+    const tooDeep = /nested more than 128 levels deep/;
+
+    it("should convert values nested up to the limit", () => {
+      assert.strictEqual(addon.limits_max_conversion_depth(), 128);
+      assert.strictEqual(addon.limits_tree_depth(nest(128)), 128);
+      assert.isTrue(addon.deep_equals(nest(128), nest(128)));
+
+      const json = JSON.stringify(nest(128));
+
+      assert.deepEqual(addon.json_parse_chunks([json]), nest(128));
+    });
+
+    it("should throw instead of overflowing the stack", () => {
+      const deep = nest(1_000_000);
+
+      assert.throws(() => addon.limits_tree_depth(deep), RangeError, tooDeep);
+      assert.throws(
+        () => addon.deep_equals(deep, nest(1_000_000)),
+        RangeError,
+        tooDeep
+      );
+      assert.throws(
+        () => addon.limits_measure(deep, { max_depth: 2_000_000 }),
+        RangeError,
+        tooDeep
+      );
+      assert.throws(
+        () => addon.json_parse_chunks(["[".repeat(1_000_000)]),
+        RangeError,
+        tooDeep
+      );
+    });
+
+    it("should set the limit of the instance", () => {
+      addon.limits_set_max_conversion_depth(4);
+
+      try {
+        assert.strictEqual(addon.limits_max_conversion_depth(), 4);
+        assert.strictEqual(addon.limits_tree_depth(nest(4)), 4);
+        assert.throws(
+          () => addon.limits_tree_depth(nest(5)),
+          RangeError,
+          "value is nested more than 4 levels deep"
+        );
+      } finally {
+        addon.limits_set_max_conversion_depth(128);
+      }
+    });
+
+    it("should not set the limit of other instances", (cb) => {
+      addon.limits_set_max_conversion_depth(4);
+
+      const worker = new Worker(
+        `
+        const { parentPort } = require("worker_threads");
+        const addon = require(${JSON.stringify(require.resolve(".."))});
+
+        parentPort.postMessage(addon.limits_max_conversion_depth());
+        `,
+        { eval: true }
+      );
+
+      worker.on("message", (max) => {
+        addon.limits_set_max_conversion_depth(128);
+
+        try {
+          assert.strictEqual(max, 128);
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      });
+      worker.on("error", cb);
+    });
+  });
+});
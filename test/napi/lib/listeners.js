@@ -0,0 +1,150 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+describe("ListenerRegistry", function () {
+  afterEach(() => addon.listeners_clear());
+
+  it("calls listeners in registration order with the arguments", function () {
+    const calls = [];
+
+    addon.listeners_on("data", (a, b) => calls.push(["first", a, b]));
+    addon.listeners_on("other", () => calls.push(["other"]));
+    addon.listeners_on("data", (a, b) => calls.push(["second", a, b]));
+    addon.listeners_emit("data", 1, "two");
+
+    assert.deepEqual(calls, [
+      ["first", 1, "two"],
+      ["second", 1, "two"],
+    ]);
+  });
+
+  it("removes a listener", function () {
+    const calls = [];
+    const id = addon.listeners_on("data", () => calls.push("removed"));
+
+    addon.listeners_on("data", () => calls.push("kept"));
+
+    assert.isTrue(addon.listeners_off(id));
+    assert.isFalse(addon.listeners_off(id));
+
+    addon.listeners_emit("data");
+
+    assert.deepEqual(calls, ["kept"]);
+    assert.strictEqual(addon.listeners_count("data"), 1);
+  });
+
+  it("can add and remove listeners during emit", function () {
+    const calls = [];
+    let second;
+
+    addon.listeners_on("data", () => {
+      calls.push("first");
+      addon.listeners_off(second);
+      addon.listeners_on("data", () => calls.push("added"));
+    });
+    second = addon.listeners_on("data", () => calls.push("second"));
+
+    // Listeners registered when the event was emitted are called
+    addon.listeners_emit("data");
+    assert.deepEqual(calls, ["first", "second"]);
+
+    calls.length = 0;
+    addon.listeners_emit("data");
+    assert.deepEqual(calls, ["first", "added"]);
+  });
+
+  it("calls every listener when one throws", function () {
+    const calls = [];
+
+    addon.listeners_on("data", () => calls.push("first"));
+    addon.listeners_on("data", () => {
+      throw new Error("listener failed");
+    });
+    addon.listeners_on("data", () => {
+      throw new Error("later listener failed");
+    });
+    addon.listeners_on("data", () => calls.push("last"));
+
+    assert.throws(() => addon.listeners_emit("data"), /^listener failed$/);
+    assert.deepEqual(calls, ["first", "last"]);
+  });
+
+  it("delivers events from a background thread in order", function (cb) {
+    const values = Array.from({ length: 100 }, (_, i) => i);
+    const received = [];
+
+    addon.listeners_on("data", (value) => {
+      received.push(value);
+
+      if (received.length === values.length) {
+        try {
+          assert.deepEqual(received, values);
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      }
+    });
+
+    addon.listeners_emit_from_thread("data", values);
+  });
+
+  it("clears every listener", function () {
+    const calls = [];
+
+    addon.listeners_on("a", () => calls.push("a"));
+    addon.listeners_on("b", () => calls.push("b"));
+    addon.listeners_clear();
+    addon.listeners_emit("a");
+    addon.listeners_emit("b");
+
+    assert.deepEqual(calls, []);
+    assert.strictEqual(addon.listeners_count("a"), 0);
+  });
+
+  it("drops the roots of removed listeners", function () {
+    if (!addon.memory_stats_enabled) {
+      this.skip();
+    }
+
+    const before = addon.memory_stats().roots;
+
+    addon.listeners_on("data", () => {});
+    addon.listeners_on("data", () => {});
+
+    assert.strictEqual(addon.memory_stats().roots, before + 2);
+
+    addon.listeners_clear();
+
+    assert.strictEqual(addon.memory_stats().roots, before);
+  });
+
+  it("tears down an instance with listeners registered", function (cb) {
+    const worker = new Worker(
+      `
+      const addon = require(${JSON.stringify(require.resolve(".."))});
+
+      addon.listeners_on("data", () => {});
+      addon.listeners_on("data", () => {});
+      addon.listeners_emit_from_thread("data", [1, 2, 3]);
+      setTimeout(() => process.exit(0), 10);
+      `,
+      { eval: true, stderr: true }
+    );
+
+    let stderr = "";
+
+    worker.stderr.on("data", (chunk) => (stderr += chunk));
+    worker.on("error", cb);
+    worker.on("exit", (code) => {
+      try {
+        assert.strictEqual(code, 0);
+        assert.strictEqual(stderr, "");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+});
@@ -0,0 +1,137 @@
+const { spawnSync } = require("child_process");
+const addon = require("..");
+const { assert } = require("chai");
+
+const describeCounters = addon.memory_stats_enabled
+  ? describe
+  : describe.skip;
+const itGc = typeof global.gc === "function" ? it : it.skip;
+
+// Collects garbage until `done` returns true, allowing finalizers to run in between
+async function collectUntil(done) {
+  for (let i = 0; i < 100; i++) {
+    global.gc();
+    await new Promise((resolve) => setImmediate(resolve));
+
+    if (done()) {
+      return;
+    }
+  }
+
+  assert.fail("expected values to be finalized");
+}
+
+describe("memory stats", () => {
+  it("should report plausible heap sizes", () => {
+    const stats = addon.memory_stats();
+
+    assert.isAbove(stats.rss, 0);
+    assert.isAbove(stats.heapTotal, 0);
+    assert.isAbove(stats.heapUsed, 0);
+    assert.isAtMost(stats.heapUsed, stats.heapTotal);
+    assert.isAtLeast(stats.external, stats.arrayBuffers);
+
+    if ("heapSizeLimit" in stats) {
+      assert.isAtLeast(stats.heapSizeLimit, stats.heapTotal);
+    }
+  });
+
+  it("should omit the heap size limit without a main module", () => {
+    // Like Node versions before 20.16, where an ES module has no main module
+    const { stdout, stderr, status } = spawnSync(
+      process.execPath,
+      [
+        "--input-type=module",
+        "-e",
+        `
+        import { createRequire } from "module";
+
+        delete process.getBuiltinModule;
+
+        const require = createRequire(${JSON.stringify(__filename)});
+        const addon = require(${JSON.stringify(require.resolve(".."))});
+
+        console.log(JSON.stringify(addon.memory_stats()));
+        `,
+      ],
+      { encoding: "utf8" }
+    );
+
+    assert.strictEqual(status, 0, stderr);
+
+    const stats = JSON.parse(stdout);
+
+    assert.notProperty(stats, "heapSizeLimit");
+    assert.isAbove(stats.heapTotal, 0);
+  });
+
+  it("should track heap usage", () => {
+    const before = addon.memory_stats().arrayBuffers;
+    const buffer = new ArrayBuffer(16 * 1024 * 1024);
+    const after = addon.memory_stats().arrayBuffers;
+
+    assert.isAtLeast(after - before, buffer.byteLength);
+  });
+
+  (addon.memory_stats_enabled ? it.skip : it)(
+    "should report zero counts without the feature",
+    () => {
+      addon.memory_hold_roots(10);
+
+      const stats = addon.memory_stats();
+
+      addon.memory_release_roots(false);
+
+      assert.strictEqual(stats.roots, 0);
+      assert.strictEqual(stats.boxes, 0);
+      assert.strictEqual(stats.channelQueueBytes, 0);
+    }
+  );
+
+  describeCounters("counters", () => {
+    it("should count roots until they are dropped", () => {
+      const before = addon.memory_stats().roots;
+
+      addon.memory_hold_roots(10);
+      assert.strictEqual(addon.memory_stats().roots, before + 10);
+
+      addon.memory_release_roots(false);
+      assert.strictEqual(addon.memory_stats().roots, before);
+    });
+
+    it("should count roots dropped without a context", () => {
+      const before = addon.memory_stats().roots;
+
+      addon.memory_hold_roots(10);
+      addon.memory_release_roots(true);
+
+      assert.strictEqual(addon.memory_stats().roots, before);
+    });
+
+    itGc("should count boxes until they are finalized", async () => {
+      const before = addon.memory_stats().boxes;
+      let boxes = Array.from({ length: 100 }, () => addon.memory_create_box());
+
+      assert.isAtLeast(addon.memory_stats().boxes, before + boxes.length);
+
+      boxes = null;
+
+      await collectUntil(() => addon.memory_stats().boxes <= before);
+    });
+
+    it("should count queued closures until they are executed", async () => {
+      // Let closures queued by earlier tests run, e.g., to drop the roots of
+      // collected functions
+      await new Promise((resolve) => setTimeout(resolve, 10));
+
+      const before = addon.memory_stats().channelQueueBytes;
+      const queued = addon.memory_queue_closures(10).channelQueueBytes;
+
+      assert.isAtLeast(queued - before, 10 * 256);
+
+      await new Promise((resolve) => setTimeout(resolve, 10));
+
+      assert.strictEqual(addon.memory_stats().channelQueueBytes, before);
+    });
+  });
+});
@@ -0,0 +1,34 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+describe("export_config", () => {
+  it("should export functions with camel case names", () => {
+    assert.typeOf(addon.parseUrlFast, "function");
+    assert.typeOf(addon._withOptions, "function");
+    assert.notProperty(addon, "parse_url_fast");
+    assert.notProperty(addon, "notExported");
+    assert.strictEqual(
+      addon.parseUrlFast("https://neon-rs.dev"),
+      "https://neon-rs.dev"
+    );
+  });
+
+  it("should name parameters in camel case in errors", () => {
+    assert.throws(
+      () => addon.parseUrlFast(42),
+      TypeError,
+      'The "inputUrl" argument must be of type string'
+    );
+    assert.throws(() => addon._withOptions(), TypeError, '"retryCount"');
+  });
+
+  it("should prefer the name of the function", () => {
+    assert.strictEqual(addon.getHTTPStatus(), 200);
+    assert.notProperty(addon, "getHttpStatus");
+  });
+
+  it("should prefer the rename rule of the function", () => {
+    assert.strictEqual(addon.keep_snake_case(1), 1);
+    assert.throws(() => addon.keep_snake_case("1"), TypeError, '"max_size"');
+  });
+});
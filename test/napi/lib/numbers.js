@@ -56,4 +56,531 @@ describe("JsNumber", function () {
       assert.equal(addon.accept_and_return_negative_js_number(-55), -55);
     });
   });
+
+  describe("64-bit integers", function () {
+    const MAX_SAFE = 2 ** 53 - 1;
+
+    it("extracts a safe integer number", function () {
+      assert.strictEqual(addon.extract_u64(MAX_SAFE), MAX_SAFE);
+      assert.strictEqual(addon.extract_i64(-MAX_SAFE), -MAX_SAFE);
+    });
+
+    it("extracts a BigInt losslessly", function () {
+      assert.strictEqual(addon.extract_u64(2n ** 53n + 1n), 2n ** 53n + 1n);
+      assert.strictEqual(addon.extract_u64(2n ** 64n - 1n), 2n ** 64n - 1n);
+      assert.strictEqual(addon.extract_i64(-(2n ** 63n)), -(2n ** 63n));
+    });
+
+    it("returns a BigInt only for values beyond the safe integer range", function () {
+      assert.strictEqual(addon.extract_u64(2n ** 53n - 1n), MAX_SAFE);
+      assert.strictEqual(addon.u64_to_js(String(2n ** 53n - 1n)), MAX_SAFE);
+      assert.strictEqual(addon.u64_to_js(String(2n ** 53n)), 2n ** 53n);
+      assert.strictEqual(
+        addon.u64_to_js(String(2n ** 53n + 1n)),
+        2n ** 53n + 1n
+      );
+      assert.strictEqual(addon.i64_to_js(String(-(2n ** 53n) + 1n)), -MAX_SAFE);
+      assert.strictEqual(
+        addon.i64_to_js(String(-(2n ** 53n) - 1n)),
+        -(2n ** 53n) - 1n
+      );
+    });
+
+    it("rejects numbers that are not safe integers", function () {
+      assert.throws(
+        () => addon.extract_u64(2 ** 53),
+        RangeError,
+        /9007199254740992/
+      );
+      assert.throws(() => addon.extract_u64(1.5), RangeError, /Received 1\.5/);
+      assert.throws(() => addon.extract_i64(NaN), RangeError, /NaN/);
+    });
+
+    it("rejects a negative value for u64", function () {
+      assert.throws(
+        () => addon.extract_u64(-1),
+        RangeError,
+        /It must be >= 0 .*Received -1$/
+      );
+      assert.throws(
+        () => addon.extract_u64(-1n),
+        RangeError,
+        /It must be >= 0n .*Received -1n$/
+      );
+    });
+
+    it("rejects a BigInt that overflows", function () {
+      assert.throws(
+        () => addon.extract_u64(2n ** 64n),
+        RangeError,
+        "The value is out of range. It must be >= 0n and <= 18446744073709551615n. Received 18446744073709551616n"
+      );
+      assert.throws(
+        () => addon.extract_i64(2n ** 63n),
+        RangeError,
+        /9223372036854775808n/
+      );
+    });
+
+    it("rejects other types", function () {
+      assert.throws(() => addon.extract_u64("1"), TypeError);
+    });
+
+    it("accepts only a BigInt with BigIntOnly", function () {
+      assert.strictEqual(addon.extract_bigint_only_u64(1n), 1n);
+      assert.throws(
+        () => addon.extract_bigint_only_u64(1),
+        TypeError,
+        /expected a BigInt/
+      );
+    });
+
+    it("always returns a BigInt with AlwaysBigInt", function () {
+      assert.strictEqual(addon.u64_to_js_always_bigint(1), 1n);
+    });
+  });
+
+  describe("numeric Vecs", function () {
+    it("extracts the same Vec from arrays and typed arrays", function () {
+      const expected = [1, 2, 3];
+
+      assert.deepEqual(addon.extract_f64_vec([1, 2, 3]), expected);
+      assert.deepEqual(
+        addon.extract_f64_vec(new Float64Array([1, 2, 3])),
+        expected
+      );
+      assert.deepEqual(
+        addon.extract_f64_vec(new Int32Array([1, 2, 3])),
+        expected
+      );
+      assert.deepEqual(addon.extract_u64_vec([1, 2n, 3]), expected);
+      assert.deepEqual(
+        addon.extract_u64_vec(new BigUint64Array([1n, 2n, 3n])),
+        expected
+      );
+      assert.deepEqual(
+        addon.extract_u64_vec(new Uint8Array([1, 2, 3])),
+        expected
+      );
+    });
+
+    it("extracts an empty Vec", function () {
+      assert.deepEqual(addon.extract_f64_vec([]), []);
+      assert.deepEqual(addon.extract_f64_vec(new Float64Array(0)), []);
+      assert.deepEqual(addon.extract_u64_vec(new BigUint64Array(0)), []);
+    });
+
+    it("extracts the elements of a view of a buffer", function () {
+      const buffer = new Float64Array([1, 2, 3, 4]).buffer;
+      const view = new Float64Array(buffer, 8, 2);
+
+      assert.deepEqual(addon.extract_f64_vec(view), [2, 3]);
+    });
+
+    it("widens a Float32Array exactly", function () {
+      const values = new Float32Array([0.5, 1.25, 0.1]);
+
+      assert.deepEqual(addon.extract_f64_vec(values), Array.from(values));
+    });
+
+    it("names the index of an element that can't be extracted", function () {
+      assert.throws(
+        () => addon.extract_f64_vec([1, "x"]),
+        TypeError,
+        /expected a number at index 1; `Vec<f64>` can be extracted from an array of numbers or a typed array/
+      );
+      assert.throws(
+        () => addon.extract_u64_vec([1, 2, {}]),
+        TypeError,
+        /at index 2/
+      );
+    });
+
+    it("names the index of an element that is out of range", function () {
+      assert.throws(
+        () => addon.extract_u64_vec(new Int8Array([1, -1])),
+        RangeError,
+        /The value of "element 1" is out of range. It must be >= 0 .*Received -1$/
+      );
+      assert.throws(
+        () => addon.extract_u64_vec(new Float64Array([1.5])),
+        RangeError,
+        /"element 0"/
+      );
+      assert.throws(
+        () => addon.extract_u64_vec(new BigInt64Array([-1n])),
+        RangeError,
+        /-1n/
+      );
+      assert.throws(
+        () => addon.extract_u64_vec([0, -1]),
+        RangeError,
+        /Received -1$/
+      );
+    });
+
+    it("rejects BigInt typed arrays for Vec<f64>", function () {
+      assert.throws(
+        () => addon.extract_f64_vec(new BigInt64Array(1)),
+        TypeError
+      );
+    });
+
+    it("accepts only an array with ArrayOnly", function () {
+      assert.deepEqual(addon.extract_array_only_f64([1, 2]), [1, 2]);
+      assert.throws(
+        () => addon.extract_array_only_f64(new Float64Array([1, 2])),
+        TypeError
+      );
+      assert.throws(
+        () => addon.extract_array_only_f64([1, "x"]),
+        TypeError,
+        /expected an array of numbers/
+      );
+    });
+  });
+
+  describe("chunked Vecs", function () {
+    it("extracts the same Vec as a synchronous extraction", async function () {
+      const array = Array.from({ length: 100000 }, (_, i) => i / 2);
+      const expected = addon.extract_f64_vec(array);
+      const small = [1, 2, 3];
+
+      assert.deepEqual(
+        await addon.extract_f64_vec_chunked(array, 1000),
+        expected
+      );
+      assert.deepEqual(
+        await addon.extract_f64_vec_chunked(array, 1e9),
+        expected
+      );
+      assert.deepEqual(await addon.extract_f64_vec_chunked(small, 0), small);
+      assert.deepEqual(await addon.extract_f64_vec_chunked([], 10), []);
+    });
+
+    it("lets timers run between chunks", async function () {
+      const array = Array.from({ length: 2000000 }, (_, i) => i);
+      let ticks = 0;
+      const timer = setInterval(() => ticks++, 1);
+
+      try {
+        const values = await addon.extract_f64_vec_chunked(array, 10000);
+
+        assert.strictEqual(values.length, array.length);
+      } finally {
+        clearInterval(timer);
+      }
+
+      assert.isAbove(ticks, 0);
+    });
+
+    it("rejects with the index of an element that can't be extracted", async function () {
+      const promise = addon.extract_f64_vec_chunked([1, 2, 3, "x", 5], 2);
+
+      try {
+        await promise;
+      } catch (err) {
+        assert.instanceOf(err, TypeError);
+        assert.match(err.message, /`f64` from the element at index 3$/);
+        return;
+      }
+
+      assert.fail("expected the promise to be rejected");
+    });
+
+    it("uses the length of the array when the extraction starts", async function () {
+      const array = [1, 2, 3, 4];
+      const promise = addon.extract_f64_vec_chunked(array, 2);
+
+      array.push(5);
+      array[3] = 40;
+
+      assert.deepEqual(await promise, [1, 2, 3, 40]);
+
+      const truncated = [1, 2, 3, 4];
+      const rejected = addon.extract_f64_vec_chunked(truncated, 2);
+
+      truncated.length = 3;
+
+      try {
+        await rejected;
+      } catch (err) {
+        assert.match(err.message, /at index 3$/);
+        return;
+      }
+
+      assert.fail("expected the promise to be rejected");
+    });
+  });
+
+  describe("durations", function () {
+    it("round trips a { secs, nanos } object", function () {
+      const d = { secs: 3, nanos: 500 };
+
+      assert.deepStrictEqual(addon.duration_convert(d, "object"), d);
+      assert.strictEqual(addon.duration_convert(d, "nanos"), 3000000500n);
+      assert.strictEqual(addon.duration_convert(d, "millis"), 3000.0005);
+    });
+
+    it("round trips a number of milliseconds", function () {
+      assert.strictEqual(addon.duration_convert(1.5, "millis"), 1.5);
+      assert.strictEqual(addon.duration_from_millis(250), 250);
+      assert.deepStrictEqual(addon.duration_convert(1.5, "object"), {
+        secs: 0,
+        nanos: 1500000,
+      });
+    });
+
+    it("round trips a BigInt of nanoseconds", function () {
+      const ns = 1234567891234n;
+
+      assert.strictEqual(addon.duration_convert(ns, "nanos"), ns);
+      assert.strictEqual(addon.duration_from_nanos(ns), ns);
+      assert.deepStrictEqual(addon.duration_convert(ns, "object"), {
+        secs: 1234,
+        nanos: 567891234,
+      });
+    });
+
+    it("preserves sub-millisecond precision as a BigInt", function () {
+      for (const ns of [1n, 999n, 1000001n, 2n ** 64n - 1n]) {
+        assert.strictEqual(addon.duration_from_nanos(ns), ns);
+      }
+    });
+
+    it("converts seconds beyond the safe integer range to a BigInt", function () {
+      const d = { secs: 2n ** 60n, nanos: 1 };
+
+      assert.deepStrictEqual(addon.duration_convert(d, "object"), d);
+      assert.throws(
+        () => addon.duration_convert(d, "nanos"),
+        RangeError,
+        /too long/
+      );
+    });
+
+    it("rejects negative durations", function () {
+      assert.throws(
+        () => addon.duration_convert(-1, "object"),
+        RangeError,
+        /It must be a finite number >= 0. Received -1$/
+      );
+      assert.throws(
+        () => addon.duration_convert(-1n, "object"),
+        RangeError,
+        /Received -1n$/
+      );
+      assert.throws(
+        () => addon.duration_convert({ secs: -1, nanos: 0 }, "object"),
+        RangeError
+      );
+      assert.throws(() => addon.duration_from_millis(-0.5), RangeError);
+      assert.throws(() => addon.duration_from_nanos(-1n), RangeError);
+    });
+
+    it("rejects invalid durations", function () {
+      assert.throws(() => addon.duration_from_millis(NaN), RangeError);
+      assert.throws(() => addon.duration_from_millis(Infinity), RangeError);
+      assert.throws(
+        () => addon.duration_convert({ secs: 0, nanos: 1e9 }, "object"),
+        RangeError,
+        /"nanos"/
+      );
+      assert.throws(() => addon.duration_convert("1s", "object"), TypeError);
+      assert.throws(() => addon.duration_convert({}, "object"), TypeError);
+    });
+
+    it("extracts only the representation of a wrapper", function () {
+      assert.throws(() => addon.duration_from_millis(1n), TypeError);
+      assert.throws(() => addon.duration_from_nanos(1), TypeError);
+    });
+
+    it("reads the same clock as process.hrtime.bigint()", function () {
+      const before = process.hrtime.bigint();
+      const times = [addon.duration_hrtime(), addon.duration_hrtime()];
+      const after = process.hrtime.bigint();
+
+      assert.isTrue(before <= times[0]);
+      assert.isTrue(times[0] <= times[1]);
+      assert.isTrue(times[1] <= after);
+    });
+  });
+
+  describe("floats", function () {
+    // Runs `f` with the `Reject` policy for the instance
+    function withReject(f) {
+      addon.float_set_policy(true);
+
+      try {
+        f();
+      } finally {
+        addon.float_set_policy(false);
+      }
+    }
+
+    it("converts non-finite values by default", function () {
+      assert.isNaN(addon.float_convert(NaN, "plain", "plain"));
+      assert.strictEqual(
+        addon.float_convert(Infinity, "plain", "plain"),
+        Infinity
+      );
+      assert.isNaN(addon.float_nan());
+      assert.strictEqual(
+        addon.float_convert([1, Infinity], "vec", "plain"),
+        Infinity
+      );
+    });
+
+    it("rejects non-finite values with Finite", function () {
+      assert.strictEqual(addon.float_convert(1.5, "finite", "finite"), 1.5);
+
+      for (const v of [NaN, Infinity, -Infinity]) {
+        assert.throws(
+          () => addon.float_convert(v, "finite", "plain"),
+          RangeError,
+          /It must be a finite number. Received/
+        );
+        assert.throws(
+          () => addon.float_convert(v, "plain", "finite"),
+          RangeError
+        );
+      }
+
+      assert.throws(
+        () => addon.float_convert("1", "finite", "plain"),
+        TypeError
+      );
+    });
+
+    it("rejects numbers too large for an f32 with Finite", function () {
+      assert.strictEqual(addon.float_convert(0.5, "finite32", "plain"), 0.5);
+      assert.throws(
+        () => addon.float_convert(1e39, "finite32", "plain"),
+        RangeError,
+        /32-bit float/
+      );
+    });
+
+    it("converts non-finite values to null with NanAsNull", function () {
+      assert.strictEqual(addon.float_convert(2, "plain", "nan_as_null"), 2);
+
+      for (const v of [NaN, Infinity, -Infinity]) {
+        assert.isNull(addon.float_convert(v, "plain", "nan_as_null"));
+      }
+
+      assert.isNaN(addon.float_convert(null, "nan_as_null", "plain"));
+      assert.strictEqual(
+        addon.float_convert(Infinity, "nan_as_null", "plain"),
+        Infinity
+      );
+      assert.throws(
+        () => addon.float_convert(undefined, "nan_as_null", "plain"),
+        TypeError
+      );
+    });
+
+    it("preserves negative zero", function () {
+      for (const [from, to] of [
+        ["plain", "plain"],
+        ["finite", "finite"],
+        ["finite32", "f32"],
+        ["nan_as_null", "nan_as_null"],
+      ]) {
+        assert.isTrue(Object.is(addon.float_convert(-0, from, to), -0));
+      }
+    });
+
+    it("applies the policy of the instance to plain floats", function () {
+      withReject(() => {
+        assert.strictEqual(addon.float_convert(1.5, "plain", "plain"), 1.5);
+        assert.throws(
+          () => addon.float_convert(NaN, "plain", "plain"),
+          RangeError,
+          /Received NaN$/
+        );
+        assert.throws(() => addon.float_nan(), RangeError);
+        assert.throws(
+          () => addon.float_convert(Infinity, "nan_as_null", "f32"),
+          RangeError,
+          /Received Infinity$/
+        );
+        assert.throws(
+          () => addon.float_convert([1, -Infinity], "vec", "plain"),
+          RangeError,
+          /Received -Infinity$/
+        );
+        assert.throws(
+          () => addon.float_convert(new Float64Array([1, NaN]), "vec", "plain"),
+          RangeError
+        );
+        assert.strictEqual(
+          addon.float_convert(new Float32Array([1, 2]), "vec", "plain"),
+          3
+        );
+
+        // The wrappers have a fixed policy
+        assert.isNull(addon.float_convert(NaN, "nan_as_null", "nan_as_null"));
+      });
+
+      assert.isNaN(addon.float_convert(NaN, "plain", "plain"));
+    });
+
+    it("applies the policy of the instance to JSON", function () {
+      const stringify = (numbers) => {
+        let json = "";
+
+        addon.json_stringify_numbers(numbers, (chunk) => {
+          json += chunk;
+        });
+
+        return json;
+      };
+
+      const numbers = [1, -0, 0.1, 1e21, 1e-7, -2.5e-300, 123456789];
+      const json = stringify(numbers);
+
+      assert.deepStrictEqual(JSON.parse(json), numbers);
+      assert.isTrue(Object.is(JSON.parse(json)[1], -0));
+      assert.strictEqual(stringify([1, NaN, Infinity]), "[1,null,null]");
+      assert.strictEqual(addon.json_parse_chunks(["1e400"]), Infinity);
+
+      withReject(() => {
+        assert.throws(() => stringify([1, NaN]), RangeError, /Received NaN$/);
+        assert.throws(
+          () => addon.json_parse_chunks(["[1e4", "00]"]),
+          RangeError,
+          /Received Infinity$/
+        );
+        assert.deepStrictEqual(addon.json_parse_chunks(["[1e3", "00]"]), [
+          1e300,
+        ]);
+      });
+    });
+  });
+
+  describe("constants", function () {
+    it("creates the same values as JavaScript", function () {
+      const expected = [true, false, undefined, null, -2, -1, 0, -0, 0.5, 256];
+      const pairs = addon.return_constants();
+
+      expected.push(257, NaN);
+      assert.strictEqual(pairs.length, expected.length);
+
+      pairs.forEach(([first, second], i) => {
+        assert.ok(Object.is(first, expected[i]), String(expected[i]));
+        assert.ok(Object.is(second, expected[i]), String(expected[i]));
+      });
+    });
+
+    it("creates valid values in nested scopes", function () {
+      assert.deepEqual(addon.constants_in_scopes(), [
+        true,
+        true,
+        true,
+        true,
+        true,
+        true,
+      ]);
+    });
+  });
 });
@@ -124,4 +124,391 @@ describe("JsObject", function () {
 
     assert.strictEqual(addon.call_symbol_method(obj, sym), "hello");
   });
+
+  it("optionally gets a property from a nullish value", function () {
+    const fallback = {};
+
+    assert.strictEqual(addon.opt_get_or(null, "a", fallback), fallback);
+    assert.strictEqual(addon.opt_get_or(undefined, "a", fallback), fallback);
+    assert.strictEqual(addon.opt_get_or({ a: 1 }, "a", fallback), 1);
+    assert.strictEqual(addon.opt_get_or({}, "a", fallback), undefined);
+    assert.strictEqual(addon.opt_get_or("abc", "length", fallback), 3);
+  });
+
+  it("short-circuits an optional chain on a missing intermediate", function () {
+    const fallback = {};
+
+    assert.strictEqual(
+      addon.opt_chain_or({ a: { b: { c: 42 } } }, "a", "b", "c", fallback),
+      42
+    );
+    assert.strictEqual(
+      addon.opt_chain_or({ a: {} }, "a", "b", "c", fallback),
+      fallback
+    );
+    assert.strictEqual(
+      addon.opt_chain_or({ a: null }, "a", "b", "c", fallback),
+      fallback
+    );
+    assert.strictEqual(
+      addon.opt_chain_or(null, "a", "b", "c", fallback),
+      fallback
+    );
+  });
+
+  it("propagates exceptions from getters in an optional chain", function () {
+    const obj = {
+      a: {
+        get b() {
+          throw new Error("getter failed");
+        },
+      },
+    };
+
+    assert.throws(
+      () => addon.opt_chain_or(obj, "a", "b", "c", null),
+      /getter failed/
+    );
+  });
+
+  it("optionally calls a method", function () {
+    const fallback = {};
+    const obj = {
+      value: 1,
+      add(x) {
+        return this.value + x;
+      },
+      notAMethod: 42,
+    };
+
+    assert.strictEqual(addon.opt_call_method_or(obj, "add", 2, fallback), 3);
+    assert.strictEqual(
+      addon.opt_call_method_or(obj, "missing", 2, fallback),
+      fallback
+    );
+    assert.strictEqual(
+      addon.opt_call_method_or(null, "add", 2, fallback),
+      fallback
+    );
+    assert.throws(
+      () => addon.opt_call_method_or(obj, "notAMethod", 2, fallback),
+      TypeError
+    );
+  });
+
+  describe("numeric keys", function () {
+    const MAX_INDEX = 2 ** 32 - 2;
+
+    it("sets array indices up to 2^32 - 2", function () {
+      const array = [];
+
+      const set = addon.set_typed_key(array, "f64", MAX_INDEX, "a");
+
+      assert.strictEqual(set, true);
+      assert.strictEqual(array.length, MAX_INDEX + 1);
+      assert.strictEqual(array[MAX_INDEX], "a");
+    });
+
+    it("sets 2^32 - 1 as an ordinary property", function () {
+      const array = [];
+
+      addon.set_typed_key(array, "f64", MAX_INDEX + 1, "a");
+      addon.set_typed_key(array, "index", MAX_INDEX + 1, "b");
+
+      assert.strictEqual(array.length, 0);
+      assert.strictEqual(array["4294967295"], "b");
+      assert.strictEqual(addon.get_typed_key(array, "f64", MAX_INDEX + 1), "b");
+    });
+
+    it("rejects numbers that are not u32 keys", function () {
+      for (const key of [2 ** 32, -1, 1.5, NaN, Infinity, -Infinity]) {
+        const obj = {};
+
+        assert.throws(
+          () => addon.set_typed_key(obj, "f64", key, "a"),
+          RangeError,
+          `expected an integer property key from 0 to 4294967295, found ${key}`
+        );
+        assert.throws(() => addon.get_typed_key(obj, "f64", key), RangeError);
+        assert.deepEqual(Object.keys(obj), []);
+      }
+    });
+
+    it("converts -0 to the key 0", function () {
+      const obj = {};
+
+      addon.set_typed_key(obj, "f64", -0, "a");
+      assert.deepEqual(obj, { 0: "a" });
+    });
+
+    it("agrees on keys set as an index and read as a name", function () {
+      const obj = {};
+
+      addon.set_typed_key(obj, "index", 17, "a");
+      addon.set_typed_key(obj, "name", "4294967296", "b");
+
+      assert.strictEqual(addon.get_typed_key(obj, "name", "17"), "a");
+      assert.strictEqual(addon.get_typed_key(obj, "f64", 17), "a");
+      assert.strictEqual(obj[2 ** 32], "b");
+      assert.deepEqual(Object.keys(obj), ["17", "4294967296"]);
+    });
+  });
+
+  describe("safe keys", function () {
+    it("does not modify the prototype with a `__proto__` key", function () {
+      const obj = addon.set_safe({}, "__proto__", "polluted", "allow-own");
+
+      assert.strictEqual(Object.getPrototypeOf(obj), Object.prototype);
+      assert.strictEqual(Object.prototype.polluted, undefined);
+      assert.deepEqual(Object.getOwnPropertyDescriptor(obj, "__proto__"), {
+        value: "polluted",
+        writable: true,
+        enumerable: true,
+        configurable: true,
+      });
+    });
+
+    it("does not invoke a setter on the prototype", function () {
+      let called = false;
+      const proto = {
+        set name(_) {
+          called = true;
+        },
+      };
+      const target = Object.create(proto);
+      const obj = addon.set_safe(target, "name", "neon", "reject");
+
+      assert.isFalse(called);
+      assert.strictEqual(
+        Object.getOwnPropertyDescriptor(obj, "name").value,
+        "neon"
+      );
+    });
+
+    it("rejects unsafe keys", function () {
+      for (const key of ["__proto__", "constructor", "prototype"]) {
+        assert.throws(
+          () => addon.set_safe({}, key, 1, "reject"),
+          TypeError,
+          /unsafe property key/
+        );
+      }
+    });
+
+    it("accepts keys that look like escaped unsafe keys", function () {
+      const obj = addon.set_safe({}, "$__proto__", 1, "reject");
+
+      addon.set_safe(obj, "$$constructor", 2, "reject");
+
+      assert.deepEqual(Object.keys(obj), ["$__proto__", "$$constructor"]);
+    });
+
+    it("escapes unsafe keys", function () {
+      const obj = addon.set_safe({}, "__proto__", 1, "escape");
+
+      addon.set_safe(obj, "$constructor", 2, "escape");
+      addon.set_safe(obj, "name", 3, "escape");
+
+      const keys = ["$__proto__", "$$constructor", "name"];
+
+      assert.deepEqual(Object.keys(obj), keys);
+    });
+
+    it("throws if the property can't be defined", function () {
+      assert.throws(
+        () => addon.set_safe(Object.freeze({}), "a", 1, "reject"),
+        TypeError
+      );
+    });
+
+    it("creates a null prototype dictionary", function () {
+      const entries = [
+        ["a", "1"],
+        ["__proto__", "2"],
+      ];
+      const dict = addon.safe_dict_from_entries(entries, "escape");
+
+      assert.strictEqual(Object.getPrototypeOf(dict), null);
+      assert.strictEqual(dict.a, "1");
+      assert.strictEqual(dict.$__proto__, "2");
+      assert.throws(
+        () => addon.safe_dict_from_entries([["constructor", "x"]], "reject"),
+        TypeError
+      );
+    });
+
+    it("converts a HashMap with own properties", function () {
+      const obj = addon.hash_map_to_js([
+        ["a", "1"],
+        ["__proto__", "2"],
+      ]);
+
+      assert.strictEqual(Object.getPrototypeOf(obj), Object.prototype);
+      assert.deepEqual(Object.keys(obj).sort(), ["__proto__", "a"]);
+      assert.strictEqual(
+        Object.getOwnPropertyDescriptor(obj, "__proto__").value,
+        "2"
+      );
+    });
+
+    it("converts a HashMap to a dictionary", function () {
+      const dict = addon.hash_map_to_dict([["a", "1"]]);
+
+      assert.strictEqual(Object.getPrototypeOf(dict), null);
+      assert.strictEqual(dict.a, "1");
+      assert.throws(
+        () => addon.hash_map_to_dict([["__proto__", "2"]]),
+        TypeError
+      );
+    });
+
+    it("sets undefined for None options by default", function () {
+      const obj = addon.build_optional_object(false);
+
+      assert.strictEqual(obj.name, "neon");
+      assert.strictEqual(obj.null, null);
+      assert.isTrue("missing" in obj);
+      assert.strictEqual(obj.missing, undefined);
+      assert.strictEqual(obj.present, 1);
+      assert.strictEqual(obj.cleared, null);
+      assert.isTrue("unset" in obj);
+      assert.strictEqual(obj.unset, undefined);
+      assert.strictEqual(obj.nested, 2);
+    });
+
+    it("omits None options with skip_none", function () {
+      const obj = addon.build_optional_object(true);
+
+      assert.isFalse("missing" in obj);
+      assert.isFalse("unset" in obj);
+      assert.isTrue("null" in obj);
+      assert.strictEqual(obj.null, null);
+      assert.strictEqual(obj.cleared, null);
+      assert.deepStrictEqual(Object.keys(obj), [
+        "name",
+        "null",
+        "present",
+        "cleared",
+        "nested",
+      ]);
+    });
+
+    it("reports the path of a nested conversion error", function () {
+      let error;
+
+      try {
+        addon.convert_nested(12, "name");
+      } catch (err) {
+        error = err;
+      }
+
+      assert.instanceOf(error, Error);
+      assert.strictEqual(
+        error.message,
+        "invalid leaf (while converting .results[12].name)"
+      );
+      assert.isTrue("conversionPath" in error);
+      assert.strictEqual(error.conversionPath, ".results[12].name");
+
+      // The original error is the cause, unchanged
+      assert.instanceOf(error.cause, Error);
+      assert.strictEqual(error.cause.message, "invalid leaf");
+      assert.isFalse("conversionPath" in error.cause);
+    });
+
+    it("quotes keys that are not identifiers in a conversion path", function () {
+      assert.throws(
+        () => addon.convert_nested(3, "content-type"),
+        Error,
+        /while converting \.results\[3\]\["content-type"\]\)$/
+      );
+    });
+
+    it("converts normally after a failed conversion", function () {
+      assert.throws(() => addon.convert_nested(0, "name"), Error);
+
+      const obj = addon.convert_nested(-1, "name");
+
+      assert.strictEqual(obj.results.length, 16);
+      assert.strictEqual(obj.results[12].name, "leaf");
+    });
+  });
+
+  describe("ObjectTemplate", function () {
+    const expected = (i) => ({
+      id: i,
+      name: `item ${i}`,
+      score: i / 2,
+      active: i % 2 === 0,
+      rank: i * 2,
+      parent: null,
+      label: "result",
+      weight: i * 1.5,
+    });
+
+    it("defines the fields of each object in order", function () {
+      const results = addon.template_results(3);
+
+      assert.deepStrictEqual(results, [0, 1, 2].map(expected));
+      assert.deepStrictEqual(Object.keys(results[1]), [
+        "id",
+        "name",
+        "score",
+        "active",
+        "rank",
+        "parent",
+        "label",
+        "weight",
+      ]);
+      assert.deepStrictEqual(addon.naive_results(3), results);
+    });
+
+    it("creates the template once and reuses it across calls", function () {
+      const initial = addon.templates_created();
+
+      addon.template_results(1);
+
+      const created = addon.templates_created();
+
+      // Created by the first call, unless an earlier test already called it
+      assert.isAtMost(created - initial, 1);
+
+      for (let n = 0; n < 100; n++) {
+        addon.template_results(n);
+      }
+
+      assert.strictEqual(addon.templates_created(), created);
+    });
+
+    it("creates objects with a prototype", function () {
+      const prototype = { greet: () => "hello" };
+      const object = addon.template_with_prototype(prototype);
+
+      assert.strictEqual(Object.getPrototypeOf(object), prototype);
+      assert.strictEqual(object.greet(), "hello");
+      assert.deepStrictEqual(Object.keys(object), ["a", "b"]);
+
+      const dict = addon.template_with_prototype(null);
+
+      assert.strictEqual(Object.getPrototypeOf(dict), null);
+      assert.strictEqual(dict.a, 1);
+      assert.strictEqual(dict.b, 2);
+    });
+
+    it("rejects duplicate keys", function () {
+      assert.throws(
+        () => addon.template_duplicate_keys(),
+        Error,
+        /duplicate key `a`/
+      );
+    });
+
+    it("requires one value per key", function () {
+      assert.throws(
+        () => addon.template_wrong_count(),
+        RangeError,
+        /expected 2 values for the object template, found 1/
+      );
+    });
+  });
 });
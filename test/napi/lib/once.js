@@ -0,0 +1,54 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+// Runs `source` in a worker with `addon` in scope and resolves with the first
+// message posted by the worker after it has exited
+function inWorker(source) {
+  return new Promise((resolve, reject) => {
+    const worker = new Worker(
+      `
+      const { parentPort } = require("worker_threads");
+      const addon = require(${JSON.stringify(require.resolve(".."))});
+      ${source}
+      `,
+      { eval: true }
+    );
+
+    let message;
+
+    worker.once("message", (m) => (message = m));
+    worker.on("error", reject);
+    worker.on("exit", () => resolve(message));
+  });
+}
+
+describe("process_once", function () {
+  it("shares a single value across instances", async function () {
+    const ptr = addon.once_library_ptr();
+    const workerPtr = await inWorker(
+      "parentPort.postMessage(addon.once_library_ptr());"
+    );
+
+    assert.strictEqual(workerPtr, ptr);
+    assert.strictEqual(addon.once_library_ptr(), ptr);
+    assert.strictEqual(addon.once_library_inits(), 1);
+  });
+
+  it("releases the value when the last instance detaches", async function () {
+    const before = addon.once_detachable_deinits();
+
+    await inWorker("addon.once_attach_detachable();");
+
+    assert.strictEqual(addon.once_detachable_deinits(), before + 1);
+  });
+
+  it("poisons the key when the initializer panics", function () {
+    assert.throws(() => addon.once_panic(), /init failed/);
+    assert.throws(() => addon.once_panic(), /poisoned/);
+  });
+
+  it("throws a TypeError if the type does not match", function () {
+    assert.throws(() => addon.once_wrong_type(), TypeError, /not of type/);
+  });
+});
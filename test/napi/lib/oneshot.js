@@ -0,0 +1,57 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+async function assertRejects(promise, pattern) {
+  try {
+    await promise;
+  } catch (err) {
+    assert.match(err.message, pattern);
+
+    return;
+  }
+
+  assert.fail("expected promise to reject");
+}
+
+describe("JsOneshot", () => {
+  it("should receive a value from a timer callback", async () => {
+    const n = await addon.oneshot_number((send) => {
+      setTimeout(() => send(42), 10);
+    });
+
+    assert.strictEqual(n, 42);
+  });
+
+  it("should receive a value in a future", async () => {
+    const n = await addon.oneshot_number_async((send) => {
+      setTimeout(() => send(7), 10);
+    });
+
+    assert.strictEqual(n, 7);
+  });
+
+  it("should throw when called twice", async () => {
+    const n = await addon.oneshot_number((send) => {
+      send(1);
+      assert.throws(() => send(2), /only be called once/);
+    });
+
+    assert.strictEqual(n, 1);
+  });
+
+  it("should fail the receiver when the sender is dropped", () => {
+    assert.strictEqual(addon.oneshot_dropped(), "Dropped");
+  });
+
+  it("should time out if no value is sent", () => {
+    assert.strictEqual(addon.oneshot_timeout(), "Timeout");
+  });
+
+  it("should propagate a conversion failure to the receiver", async () => {
+    const result = addon.oneshot_number((send) => {
+      assert.throws(() => send("hello"), TypeError, /wrong type/);
+    });
+
+    await assertRejects(result, /wrong type/);
+  });
+});
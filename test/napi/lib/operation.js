@@ -0,0 +1,134 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+// `operationId` is formatted as 16 hexadecimal digits
+function hex(id) {
+  return id.toString(16).padStart(16, "0");
+}
+
+describe("Operation ids", () => {
+  it("should not have a current id outside of an operation", () => {
+    assert.isNull(addon.operation_current());
+  });
+
+  it("should restore the previous id after a nested operation", () => {
+    assert.deepEqual(addon.operation_nested(1, 2), [hex(1), hex(2), hex(1)]);
+    assert.isNull(addon.operation_current());
+  });
+
+  it("should set the operationId of thrown errors", () => {
+    try {
+      addon.operation_throw(42);
+    } catch (err) {
+      assert.instanceOf(err, TypeError);
+      assert.strictEqual(err.message, "operation failed");
+      assert.strictEqual(err.operationId, "000000000000002a");
+      return;
+    }
+
+    assert.fail("expected an exception");
+  });
+
+  it("should set the operationId of panics in functions", () => {
+    try {
+      addon.operation_panic(7);
+    } catch (err) {
+      assert.match(err.message, /operation panicked/);
+      assert.strictEqual(err.operationId, hex(7));
+      return;
+    }
+
+    assert.fail("expected an exception");
+  });
+
+  it("should not set the operationId outside of an operation", () => {
+    try {
+      addon.throw_error("no operation");
+    } catch (err) {
+      assert.notProperty(err, "operationId");
+      return;
+    }
+
+    assert.fail("expected an exception");
+  });
+
+  it("should reject with the id of the task", async () => {
+    try {
+      await addon.operation_task_reject(3, 0);
+    } catch (err) {
+      assert.strictEqual(err.message, "task failed");
+      assert.strictEqual(err.operationId, hex(3));
+      return;
+    }
+
+    assert.fail("expected a rejection");
+  });
+
+  it("should not mix the ids of interleaved tasks", async () => {
+    // Completed in the reverse order they were started
+    const results = await Promise.allSettled([
+      addon.operation_task_reject(10, 60),
+      addon.operation_task_reject(11, 30),
+      addon.operation_task_reject(12, 0),
+    ]);
+
+    assert.deepEqual(
+      results.map(({ reason }) => reason.operationId),
+      [hex(10), hex(11), hex(12)]
+    );
+    assert.isNull(addon.operation_current());
+  });
+
+  it("should set the operationId of panics in tasks", async () => {
+    try {
+      await addon.operation_task_panic(5);
+    } catch (err) {
+      assert.match(err.message, /panic/);
+      assert.strictEqual(err.panic.message, "task panicked");
+      assert.strictEqual(err.operationId, hex(5));
+      return;
+    }
+
+    assert.fail("expected a rejection");
+  });
+
+  it("should restore the id in the completion of a task", (done) => {
+    addon.operation_task_and_then(8, (err, current) => {
+      try {
+        assert.strictEqual(err.operationId, hex(8));
+        assert.strictEqual(current, hex(8));
+        done();
+      } catch (err) {
+        done(err);
+      }
+    });
+  });
+
+  it("should restore the id in closures sent on a channel", (done) => {
+    addon.operation_channel(9, (err) => {
+      try {
+        assert.strictEqual(err.message, "sent");
+        assert.strictEqual(err.operationId, hex(9));
+        done();
+      } catch (err) {
+        done(err);
+      }
+    });
+  });
+
+  it("should set the operationId of panics in a channel", (done) => {
+    process.removeAllListeners("unhandledRejection");
+    process.once("unhandledRejection", (err) => {
+      try {
+        assert.strictEqual(err.panic.message, "channel panicked");
+        assert.strictEqual(err.operationId, hex(6));
+        assert.isNull(addon.operation_current());
+        done();
+      } catch (err) {
+        done(err);
+      }
+    });
+
+    addon.operation_channel_panic(6);
+  });
+});
@@ -0,0 +1,136 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+const itGc = typeof global.gc === "function" ? it : it.skip;
+
+// Hashes all of the chunks with a new hasher
+function hash(...chunks) {
+  const hasher = addon.patterns_new_hasher(Infinity);
+
+  for (const chunk of chunks) {
+    hasher.update(chunk);
+  }
+
+  return hasher.digest();
+}
+
+function assertThrowsCode(f, code) {
+  try {
+    f();
+  } catch (err) {
+    assert.strictEqual(err.code, code);
+    return;
+  }
+
+  assert.fail("expected an exception");
+}
+
+describe("Accumulator", () => {
+  const data = Buffer.from("the quick brown fox jumps over the lazy dog");
+
+  it("should produce the same result for one or many chunks", () => {
+    const expected = hash(data);
+
+    assert.lengthOf(expected, 4);
+    assert.deepEqual(hash(data.subarray(0, 10), data.subarray(10)), expected);
+    assert.deepEqual(
+      hash(...Array.from(data, (byte) => Uint8Array.of(byte))),
+      expected
+    );
+    assert.notDeepEqual(hash(data.subarray(1)), expected);
+  });
+
+  it("should accept typed arrays and array buffers", () => {
+    const expected = hash(data);
+    const copy = new Uint8Array(data);
+    const words = new Uint16Array(copy.buffer, 0, 10);
+
+    assert.deepEqual(
+      hash(words, copy.buffer.slice(20)),
+      hash(data.subarray(0, 20), data.subarray(20))
+    );
+    assert.deepEqual(hash(copy.buffer), expected);
+    assert.deepEqual(
+      hash(new Uint8Array(0), data, new ArrayBuffer(0)),
+      expected
+    );
+
+    const hasher = addon.patterns_new_hasher(Infinity);
+
+    assert.throws(() => hasher.update("text"), TypeError);
+    assert.throws(() => hasher.update([1, 2, 3]), TypeError);
+  });
+
+  it("should copy the result into a target", () => {
+    const expected = hash(data);
+    const hasher = addon.patterns_new_hasher(Infinity);
+    const target = new Uint8Array(6);
+
+    hasher.update(data);
+
+    assert.throws(() => hasher.digestInto(new Uint8Array(3)), RangeError);
+    assert.strictEqual(hasher.digestInto(target), 4);
+    assert.deepEqual(Buffer.from(target.subarray(0, 4)), expected);
+    assertThrowsCode(() => hasher.digest(), "ERR_NEON_ACCUMULATOR_FINISHED");
+  });
+
+  it("should throw when used after the result is returned", () => {
+    const hasher = addon.patterns_new_hasher(Infinity);
+
+    hasher.update(data);
+    hasher.digest();
+
+    assertThrowsCode(
+      () => hasher.update(data),
+      "ERR_NEON_ACCUMULATOR_FINISHED"
+    );
+    assertThrowsCode(() => hasher.digest(), "ERR_NEON_ACCUMULATOR_FINISHED");
+    assertThrowsCode(
+      () => hasher.updateAsync(data),
+      "ERR_NEON_ACCUMULATOR_FINISHED"
+    );
+  });
+
+  it("should update asynchronously without blocking", async () => {
+    const hasher = addon.patterns_new_hasher(16, 100);
+    let ticks = 0;
+    const timer = setInterval(() => ticks++, 5);
+
+    try {
+      const small = hasher.updateAsync(data.subarray(0, 10));
+      const pending = hasher.updateAsync(data.subarray(10));
+
+      assertThrowsCode(() => hasher.update(data), "ERR_NEON_ACCUMULATOR_BUSY");
+      assertThrowsCode(() => hasher.digest(), "ERR_NEON_ACCUMULATOR_BUSY");
+
+      await small;
+      await pending;
+    } finally {
+      clearInterval(timer);
+    }
+
+    assert.isAbove(ticks, 0);
+    assert.deepEqual(hasher.digest(), hash(data));
+  });
+
+  itGc("should drop the state of an abandoned accumulator", async () => {
+    const before = addon.patterns_dropped_hashers();
+
+    (() => {
+      const hasher = addon.patterns_new_hasher(Infinity);
+
+      hasher.update(data);
+    })();
+
+    for (let i = 0; i < 100; i++) {
+      global.gc();
+      await new Promise((resolve) => setImmediate(resolve));
+
+      if (addon.patterns_dropped_hashers() > before) {
+        return;
+      }
+    }
+
+    assert.fail("expected the state to be dropped");
+  });
+});
@@ -0,0 +1,84 @@
+const addon = require("..");
+const { assert } = require("chai");
+
+function catchError(f) {
+  try {
+    f();
+  } catch (err) {
+    return err;
+  }
+
+  assert.fail("expected an error");
+}
+
+describe("reentrancy guards", () => {
+  it("should block direct recursion", () => {
+    let err;
+
+    addon.reentrant_for_each(() => {
+      err = catchError(() => addon.reentrant_for_each(() => {}));
+    });
+
+    assert.instanceOf(err, Error);
+    assert.strictEqual(err.code, "ERR_NEON_REENTRANT_CALL");
+    assert.strictEqual(err.active, "reentrant_for_each");
+    assert.strictEqual(err.attempted, "reentrant_for_each");
+    assert.include(err.message, "reentrant_for_each");
+  });
+
+  it("should allow nesting an allowed export", () => {
+    let result;
+
+    addon.reentrant_for_each(() => {
+      result = addon.reentrant_peek();
+    });
+
+    assert.strictEqual(result, "peek");
+  });
+
+  it("should block nesting an export that is not allowed", () => {
+    let err;
+
+    addon.reentrant_update(() => {
+      err = catchError(() => addon.reentrant_peek());
+    });
+
+    assert.strictEqual(err.code, "ERR_NEON_REENTRANT_CALL");
+    assert.strictEqual(err.active, "reentrant_update");
+    assert.strictEqual(err.attempted, "reentrant_peek");
+    assert.include(err.message, "reentrant_update");
+    assert.include(err.message, "reentrant_peek");
+  });
+
+  it("should release the guard after an error is thrown", () => {
+    const err = catchError(() =>
+      addon.reentrant_update(() => {
+        throw new Error("callback failed");
+      })
+    );
+
+    assert.strictEqual(err.message, "callback failed");
+
+    let called = false;
+
+    addon.reentrant_update(() => {
+      called = true;
+    });
+
+    assert.isTrue(called);
+    assert.strictEqual(addon.reentrant_peek(), "peek");
+  });
+
+  it("should guard with a manual reentrancy guard", () => {
+    let err;
+
+    addon.reentrant_manual(() => {
+      err = catchError(() => addon.reentrant_manual(() => {}));
+    });
+
+    assert.strictEqual(err.code, "ERR_NEON_REENTRANT_CALL");
+    assert.strictEqual(err.attempted, "reentrant_manual");
+
+    addon.reentrant_manual(() => {});
+  });
+});
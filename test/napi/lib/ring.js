@@ -0,0 +1,125 @@
+const addon = require("..");
+const assert = require("chai").assert;
+
+function readAll(ring) {
+  const records = [];
+
+  for (let record; (record = ring.read()); ) {
+    records.push(Array.from(record));
+  }
+
+  return records;
+}
+
+describe("RingBuffer", () => {
+  it("should describe the shared memory layout", () => {
+    const ring = addon.ring_create(4, 3, false);
+
+    assert.instanceOf(ring.buffer, SharedArrayBuffer);
+    assert.instanceOf(ring.indices, Uint32Array);
+    assert.strictEqual(ring.records.length, 4 * 4);
+    assert.strictEqual(ring.recordSize, 4);
+    assert.strictEqual(ring.capacity, 3);
+    assert.isUndefined(ring.read());
+  });
+
+  it("should reject records when full", () => {
+    const ring = addon.ring_create(2, 3, false);
+
+    assert.isTrue(addon.ring_push(ring.writer, [1, 1]));
+    assert.isTrue(addon.ring_push(ring.writer, [2, 2]));
+    assert.isTrue(addon.ring_push(ring.writer, [3, 3]));
+    assert.isFalse(addon.ring_push(ring.writer, [4, 4]));
+
+    assert.deepEqual(readAll(ring), [
+      [1, 1],
+      [2, 2],
+      [3, 3],
+    ]);
+  });
+
+  it("should wrap around the end of the buffer", () => {
+    const ring = addon.ring_create(2, 3, false);
+    const read = [];
+
+    for (let i = 0; i < 20; i++) {
+      assert.isTrue(addon.ring_push(ring.writer, [i, 255 - i]));
+
+      // Keep the ring partially full so the head and tail cross the end at different times
+      if (i % 3 !== 0) {
+        read.push(...readAll(ring));
+      }
+    }
+
+    read.push(...readAll(ring));
+
+    assert.deepEqual(
+      read,
+      Array.from({ length: 20 }, (_, i) => [i, 255 - i])
+    );
+  });
+
+  it("should drop the oldest records when configured", () => {
+    const ring = addon.ring_create(1, 3, true);
+
+    for (let i = 1; i <= 5; i++) {
+      assert.isTrue(addon.ring_push(ring.writer, [i]));
+    }
+
+    assert.deepEqual(readAll(ring), [[3], [4], [5]]);
+    assert.strictEqual(ring.indices[2], 2);
+  });
+
+  it("should stream records from a thread without corruption", async () => {
+    const count = 100_000;
+    const ring = addon.ring_create(8, 64, false);
+    let finished = false;
+    let next = 0;
+
+    addon.ring_produce_in_thread(ring.writer, count).then(() => (finished = true));
+
+    while (next < count) {
+      for (let record; (record = ring.read()); next++) {
+        const view = new DataView(record.buffer);
+
+        assert.strictEqual(view.getUint32(0, true), next);
+        assert.strictEqual(view.getUint32(4, true), ~next >>> 0);
+      }
+
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    while (!finished) {
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    assert.isUndefined(ring.read());
+  });
+
+  it("should wake JavaScript when a record is pushed to an empty ring", async () => {
+    const count = 1000;
+    let ring;
+    let next = 0;
+    let wakeups = 0;
+
+    const done = new Promise((resolve) => {
+      ring = addon.ring_create(8, 16, false, () => {
+        wakeups++;
+
+        for (let record; (record = ring.read()); next++) {
+          assert.strictEqual(new DataView(record.buffer).getUint32(0, true), next);
+        }
+
+        if (next === count) {
+          resolve();
+        }
+      });
+    });
+
+    await addon.ring_produce_in_thread(ring.writer, count);
+    await done;
+
+    assert.isAbove(wakeups, 0);
+    assert.isAtMost(wakeups, count);
+  });
+});
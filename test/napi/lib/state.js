@@ -0,0 +1,191 @@
+const addon = require("..");
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+// Resolves after the event loop has had a chance to deliver notifications
+function tick() {
+  return new Promise((resolve) => setTimeout(resolve, 50));
+}
+
+describe("Watchable", function () {
+  it("can get the current value", function () {
+    const watchable = addon.watchable_new(1, true);
+
+    assert.strictEqual(watchable.get(), 1);
+  });
+
+  it("notifies subscribers of changes from a background thread", function (cb) {
+    const watchable = addon.watchable_new(0, false);
+    const received = [];
+
+    watchable.subscribe((value) => {
+      received.push(value);
+
+      if (received.length === 3) {
+        try {
+          assert.deepEqual(received, [1, 2, 3]);
+          assert.strictEqual(watchable.get(), 3);
+          cb();
+        } catch (err) {
+          cb(err);
+        }
+      }
+    });
+
+    addon.watchable_set_from_thread(watchable.handle, [1, 2, 3]);
+  });
+
+  it("stops delivering changes after unsubscribing", async function () {
+    const watchable = addon.watchable_new(0, false);
+    const received = [];
+    const unsubscribe = watchable.subscribe((value) => received.push(value));
+
+    addon.watchable_set_from_thread(watchable.handle, [1]);
+    await tick();
+
+    unsubscribe();
+    unsubscribe();
+
+    addon.watchable_set_from_thread(watchable.handle, [2]);
+    await tick();
+
+    assert.deepEqual(received, [1]);
+    assert.strictEqual(watchable.get(), 2);
+  });
+
+  it("coalesces rapid changes to the latest value", async function () {
+    const watchable = addon.watchable_new(0, true);
+    const received = [];
+
+    watchable.subscribe((value) => received.push(value));
+
+    const values = [...new Array(1000)].map((_, i) => i + 1);
+
+    addon.watchable_set_from_thread(watchable.handle, values);
+
+    while (watchable.get() !== 1000) {
+      await tick();
+    }
+
+    await tick();
+
+    assert.isBelow(received.length, values.length);
+    assert.strictEqual(received[received.length - 1], 1000);
+  });
+
+  it("can iterate changes asynchronously", async function () {
+    const watchable = addon.watchable_new(0, false);
+    const received = [];
+
+    addon.watchable_set_from_thread(watchable.handle, [1, 2, 3]);
+
+    for await (const value of watchable) {
+      received.push(value);
+
+      if (value === 3) {
+        break;
+      }
+    }
+
+    assert.deepEqual(received, [1, 2, 3]);
+  });
+
+  it("drops its values when an instance shuts down", function (cb) {
+    const worker = new Worker(
+      `
+      const addon = require(${JSON.stringify(require.resolve(".."))});
+      const watchable = addon.watchable_counted_new(0, false);
+
+      watchable.subscribe(() => {});
+      (async () => {
+        for await (const _ of watchable) {}
+      })();
+
+      addon.watchable_counted_set_from_thread(watchable.handle, [1, 2, 3]);
+      setTimeout(() => process.exit(0), 10);
+      `,
+      { eval: true }
+    );
+
+    worker.on("error", cb);
+    worker.on("exit", async (code) => {
+      try {
+        assert.strictEqual(code, 0);
+
+        // The thread setting the values may still hold its clone
+        for (let i = 0; i < 20 && addon.counted_live() > 0; i++) {
+          await tick();
+        }
+
+        // Every value was dropped, so the `Watchable` holding the roots of the
+        // subscriptions was dropped as well
+        assert.strictEqual(addon.counted_live(), 0);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("keeps only the latest unread change when coalescing", async function () {
+    const watchable = addon.watchable_new(0, true);
+    const iterator = watchable[Symbol.asyncIterator]();
+
+    // Each value is set in its own turn of the event loop, while `next()` is
+    // not waiting, so each one is buffered
+    for (const value of [1, 2, 3]) {
+      addon.watchable_set_from_thread(watchable.handle, [value]);
+
+      while (watchable.get() !== value) {
+        await tick();
+      }
+
+      await tick();
+    }
+
+    assert.deepEqual(await iterator.next(), { value: 3, done: false });
+    await iterator.return();
+  });
+});
+
+describe("ModuleContext::export_live", function () {
+  it("calls the getter each time the export is read", function () {
+    const first = addon.liveReads;
+
+    assert.strictEqual(addon.liveReads, first + 1);
+    assert.strictEqual(addon.liveReads, first + 2);
+    assert.include(Object.keys(addon), "liveReads");
+  });
+
+  it("is read-only", function () {
+    const status = addon.liveStatus;
+
+    addon.liveStatus = 42;
+
+    assert.strictEqual(addon.liveStatus, status);
+  });
+
+  it("observes a watchable set from a background thread", async function () {
+    const start = addon.liveStatus;
+    const values = [start + 1, start + 2, start + 3];
+    const received = [];
+    const reads = [];
+    const unsubscribe = addon.subscribeLiveStatus((value) => {
+      received.push(value);
+      reads.push(addon.liveStatus);
+    });
+
+    addon.live_status_set_from_thread(values);
+
+    while (received.length < values.length) {
+      await tick();
+    }
+
+    unsubscribe();
+
+    // Reads observe the latest value, and subscribers receive every value
+    assert.deepEqual(received, values);
+    assert.strictEqual(reads[reads.length - 1], start + 3);
+    assert.strictEqual(addon.liveStatus, start + 3);
+  });
+});
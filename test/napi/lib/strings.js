@@ -21,3 +21,232 @@ describe("JsString", function () {
     });
   });
 });
+
+describe("Strings from bytes", function () {
+  it("should replace invalid sequences", function () {
+    const bytes = Buffer.from([0x61, 0x80, 0x62, 0xe2, 0x82, 0x63, 0xff]);
+
+    assert.strictEqual(
+      addon.string_from_utf8_lossy(bytes),
+      "a\ufffdb\ufffdc\ufffd"
+    );
+    assert.strictEqual(
+      addon.string_from_utf8_lossy(bytes),
+      bytes.toString("utf8")
+    );
+    assert.strictEqual(
+      addon.string_from_utf8_lossy(Buffer.from("héllo")),
+      "héllo"
+    );
+  });
+
+  it("should report the offset of the first invalid sequence", function () {
+    assert.strictEqual(
+      addon.try_string_from_utf8(Buffer.from("héllo")),
+      "héllo"
+    );
+
+    try {
+      addon.try_string_from_utf8(Buffer.from([0x68, 0xc3, 0xa9, 0x80, 0x61]));
+      assert.fail("should have thrown");
+    } catch (err) {
+      assert.strictEqual(err.offset, 3);
+      assert.match(err.message, /invalid UTF-8 sequence of 1 bytes at byte 3/);
+    }
+
+    try {
+      addon.try_string_from_utf8(Buffer.from([0x61, 0xe2, 0x82]));
+      assert.fail("should have thrown");
+    } catch (err) {
+      assert.strictEqual(err.offset, 1);
+      assert.match(err.message, /incomplete UTF-8 sequence at byte 1/);
+    }
+  });
+
+  const unix = process.platform !== "win32" ? it : it.skip;
+
+  unix("should round trip a non-UTF-8 path with percent-encoding", function () {
+    const bytes = Buffer.from([0x2f, 0x74, 0xff, 0x25, 0x32, 0xc3, 0xa9]);
+    const s = addon.string_from_path_bytes(bytes, true);
+
+    assert.strictEqual(s, "/t%FF%252é");
+    assert.deepEqual(addon.path_bytes_from_string(s, true), bytes);
+  });
+
+  unix("should replace invalid bytes of a path", function () {
+    const bytes = Buffer.from([0x2f, 0x74, 0xff, 0x25]);
+    const s = addon.string_from_path_bytes(bytes, false);
+
+    assert.strictEqual(s, "/t\ufffd%");
+    assert.notDeepEqual(addon.path_bytes_from_string(s, false), bytes);
+  });
+
+  unix("should keep a % that is not an escape", function () {
+    assert.deepEqual(
+      addon.path_bytes_from_string("%zz%4", true),
+      Buffer.from("%zz%4")
+    );
+  });
+});
+
+describe("StringCache", function () {
+  // Builds the string at runtime, so that it is a distinct string from any literal
+  function distinct(s) {
+    return [...s].join("");
+  }
+
+  it("should return cached conversions of repeated strings", function () {
+    const cache = addon.string_cache_new(10);
+
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, "content-type"),
+      "content-type"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, "content-type"),
+      "content-type"
+    );
+    assert.deepEqual(addon.string_cache_stats(cache), {
+      hits: 1,
+      misses: 1,
+      bypassed: 0,
+      evictions: 0,
+      entries: 1,
+    });
+  });
+
+  it("should match equal strings that are distinct values", function () {
+    const cache = addon.string_cache_new(10);
+
+    addon.string_cache_to_rust(cache, "accept");
+    addon.string_cache_to_rust(cache, "accept-encoding");
+
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, distinct("accept")),
+      "accept"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, distinct("accept-encoding")),
+      "accept-encoding"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, distinct("accepts")),
+      "accepts"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, "__proto__"),
+      "__proto__"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, "héllo 🌍"),
+      "héllo 🌍"
+    );
+    assert.strictEqual(
+      addon.string_cache_to_rust(cache, distinct("héllo 🌍")),
+      "héllo 🌍"
+    );
+
+    const { hits, misses } = addon.string_cache_stats(cache);
+
+    assert.strictEqual(hits, 3);
+    assert.strictEqual(misses, 5);
+  });
+
+  it("should evict the least recently used string", function () {
+    const cache = addon.string_cache_new(2);
+
+    addon.string_cache_to_rust(cache, "a");
+    addon.string_cache_to_rust(cache, "b");
+    addon.string_cache_to_rust(cache, "a");
+    addon.string_cache_to_rust(cache, "c");
+
+    let stats = addon.string_cache_stats(cache);
+
+    assert.strictEqual(stats.evictions, 1);
+    assert.strictEqual(stats.entries, 2);
+
+    // `b` was evicted, `a` and `c` remain
+    assert.strictEqual(addon.string_cache_to_rust(cache, "a"), "a");
+    assert.strictEqual(addon.string_cache_to_rust(cache, "c"), "c");
+    assert.strictEqual(addon.string_cache_stats(cache).hits, 3);
+    assert.strictEqual(addon.string_cache_to_rust(cache, "b"), "b");
+
+    stats = addon.string_cache_stats(cache);
+
+    assert.strictEqual(stats.misses, 4);
+    assert.strictEqual(stats.evictions, 2);
+    assert.strictEqual(stats.entries, 2);
+  });
+
+  it("should bypass the cache for long strings", function () {
+    const cache = addon.string_cache_new(10);
+    const long = "x".repeat(1000);
+
+    assert.strictEqual(addon.string_cache_to_rust(cache, long), long);
+    assert.strictEqual(addon.string_cache_to_rust(cache, long), long);
+
+    const { bypassed, entries } = addon.string_cache_stats(cache);
+
+    assert.strictEqual(bypassed, 2);
+    assert.strictEqual(entries, 0);
+  });
+
+  it("should remove strings when cleared", function () {
+    const cache = addon.string_cache_new(10);
+
+    addon.string_cache_to_rust(cache, "a");
+    addon.string_cache_clear(cache);
+    assert.strictEqual(addon.string_cache_stats(cache).entries, 0);
+
+    addon.string_cache_to_rust(cache, "a");
+
+    const { hits, misses } = addon.string_cache_stats(cache);
+
+    assert.strictEqual(hits, 0);
+    assert.strictEqual(misses, 2);
+  });
+
+  it("should extract strings with the shared cache", function () {
+    assert.strictEqual(addon.extract_cached_string("GET"), "GET");
+    assert.strictEqual(addon.extract_cached_string(distinct("GET")), "GET");
+    assert.strictEqual(addon.extract_cached_string(42), undefined);
+  });
+});
+
+describe("UTF-16 strings", function () {
+  const codes = (s) =>
+    Array.from({ length: s.length }, (_, i) => s.charCodeAt(i));
+
+  it("should copy the code units of a string", function () {
+    const s = "héllo 😀";
+
+    assert.deepEqual(addon.string_to_wide(s, false), codes(s));
+    assert.deepEqual(addon.string_to_wide("", false), []);
+  });
+
+  it("should keep unpaired surrogates", function () {
+    const s = "a\ud800b\udc00\ud83d";
+    const wide = addon.string_to_wide(s, false);
+
+    assert.deepEqual(wide, [0x61, 0xd800, 0x62, 0xdc00, 0xd83d]);
+    assert.strictEqual(addon.string_from_wide(wide), s);
+    assert.strictEqual(addon.string_wide_reverse("ab\udc00\ud800"), "𐀀ba");
+  });
+
+  it("should keep embedded nuls", function () {
+    const s = "a\0b\0";
+
+    assert.deepEqual(addon.string_to_wide(s, false), [0x61, 0, 0x62, 0]);
+    assert.deepEqual(addon.string_to_wide(s, true), [0x61, 0, 0x62, 0, 0]);
+    assert.strictEqual(addon.string_from_wide([0x61, 0, 0x62]), "a\0b");
+  });
+
+  it("should append a nul terminator", function () {
+    assert.deepEqual(addon.string_to_wide("ab", true), [0x61, 0x62, 0]);
+    assert.deepEqual(addon.string_to_wide("", true), [0]);
+  });
+
+  it("should only extract strings as WideString", function () {
+    assert.strictEqual(addon.string_wide_reverse(42), undefined);
+  });
+});
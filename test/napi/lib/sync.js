@@ -0,0 +1,157 @@
+const addon = require("..");
+const assert = require("chai").assert;
+
+describe("JsRwCell", () => {
+  it("should read and write from JavaScript", () => {
+    const cell = addon.rw_cell_new(1);
+
+    assert.strictEqual(addon.rw_cell_read(cell), 1);
+    assert.strictEqual(addon.rw_cell_increment(cell), 2);
+    assert.strictEqual(addon.rw_cell_read(cell), 2);
+  });
+
+  it("should throw a catchable error when locked by another thread", () => {
+    const cell = addon.rw_cell_new(1);
+
+    addon.rw_cell_increment_in_thread(cell, 100);
+
+    assert.throws(() => addon.rw_cell_read(cell), /another thread/);
+    assert.throws(() => addon.rw_cell_increment(cell), /another thread/);
+  });
+
+  it("should resolve async reads when the lock is released", async () => {
+    const cell = addon.rw_cell_new(1);
+
+    addon.rw_cell_increment_in_thread(cell, 50);
+
+    assert.strictEqual(await addon.rw_cell_read_async(cell), 2);
+    assert.strictEqual(addon.rw_cell_read(cell), 2);
+  });
+
+  it("should detect self-deadlock on the JavaScript thread", () => {
+    const cell = addon.rw_cell_new(1);
+
+    assert.throws(() => addon.rw_cell_write_while_reading(cell), /deadlock/);
+
+    // Guards are released after the error
+    assert.strictEqual(addon.rw_cell_increment(cell), 2);
+  });
+
+  it("should not report a waiting writer as self-deadlock", () => {
+    const cell = addon.rw_cell_new(1);
+
+    assert.throws(
+      () => addon.rw_cell_read_while_writer_waits(cell),
+      /a writer on another thread is waiting/
+    );
+    assert.strictEqual(addon.rw_cell_read(cell), 2);
+  });
+});
+
+describe("InstanceLock", () => {
+  it("should update the value from JavaScript", () => {
+    const lock = addon.instance_lock_new(1, 0);
+
+    assert.strictEqual(addon.instance_lock_get(lock), 1);
+    assert.strictEqual(addon.instance_lock_increment(lock), 2);
+    assert.strictEqual(addon.instance_lock_get(lock), 2);
+  });
+
+  it("should throw a catchable error when locked by another thread", () => {
+    const lock = addon.instance_lock_new(1, 10);
+
+    addon.instance_lock_hold_in_thread(lock, 1000);
+
+    assert.throws(() => addon.instance_lock_increment(lock), /another thread/);
+
+    // Threw while the other thread still held the lock, instead of waiting for it
+    assert.isFalse(addon.instance_lock_defer_increment(lock));
+  });
+
+  it("should release the lock when the closure panics", async () => {
+    const lock = addon.instance_lock_new(1, 0);
+
+    assert.throws(() => addon.instance_lock_panic(lock), /panic/);
+
+    // The increment deferred while the lock was held is sent when it is released
+    while (addon.instance_lock_get(lock) < 2) {
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    assert.strictEqual(addon.instance_lock_increment(lock), 3);
+  });
+
+  it("should report acquiring the lock again on the same thread", () => {
+    const lock = addon.instance_lock_new(1, 0);
+
+    assert.match(
+      addon.instance_lock_reenter_blocking(lock),
+      /would deadlock: the lock is already held by the current thread/
+    );
+    assert.strictEqual(addon.instance_lock_increment(lock), 2);
+  });
+
+  it("should wait for a lock released within the grace period", () => {
+    const lock = addon.instance_lock_new(1, 1000);
+
+    addon.instance_lock_hold_in_thread(lock, 20);
+
+    assert.strictEqual(addon.instance_lock_increment(lock), 3);
+  });
+
+  it("should resolve async updates when the lock is released", async () => {
+    const lock = addon.instance_lock_new(1, 0);
+
+    addon.instance_lock_hold_in_thread(lock, 50);
+
+    assert.strictEqual(await addon.instance_lock_increment_async(lock), 3);
+  });
+
+  it("should defer updates to JavaScript when contended", async () => {
+    const lock = addon.instance_lock_new(1, 0);
+
+    assert.isTrue(addon.instance_lock_defer_increment(lock));
+    assert.strictEqual(addon.instance_lock_get(lock), 2);
+
+    addon.instance_lock_hold_in_thread(lock, 50);
+
+    assert.isFalse(addon.instance_lock_defer_increment(lock));
+
+    await new Promise((resolve) => setTimeout(resolve, 100));
+
+    assert.strictEqual(addon.instance_lock_get(lock), 4);
+  });
+
+  it("should apply increments from JavaScript and threads consistently", async () => {
+    const lock = addon.instance_lock_local();
+    const start = addon.instance_lock_get(lock);
+    const done = addon.instance_lock_increment_in_threads(lock, 4, 1000);
+    let finished = false;
+    let js = 0;
+
+    done.then(() => (finished = true));
+
+    while (!finished) {
+      try {
+        addon.instance_lock_increment(lock);
+        js++;
+      } catch (err) {
+        assert.match(err.message, /another thread/);
+      }
+
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    // Wait for updates deferred to JavaScript
+    const expected = start + 4 * 1000 + js;
+
+    while (addon.instance_lock_get(lock) < expected) {
+      await new Promise((resolve) => setImmediate(resolve));
+    }
+
+    assert.strictEqual(addon.instance_lock_get(lock), expected);
+
+    // State is shared across calls through instance-local storage
+    assert.strictEqual(addon.instance_lock_get(addon.instance_lock_local()), expected);
+  });
+});
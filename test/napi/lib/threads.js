@@ -459,6 +459,95 @@ const assert = require("chai").assert;
     }
   });
 
+  it("should reject a task promise when the timeout passes", async function () {
+    const returns = addon.task_timeout_returns();
+
+    try {
+      await addon.task_timeout(500, 20);
+
+      throw new Error("Did not throw");
+    } catch (err) {
+      assert.instanceOf(err, Error);
+      assert.strictEqual(err.name, "TimeoutError");
+      assert.strictEqual(err.code, "TIMEOUT_ERR");
+      // Rejected while the task was still running, instead of after it returned
+      assert.strictEqual(addon.task_timeout_returns(), returns);
+    }
+  });
+
+  it("should resolve a task promise that completes before the timeout", async function () {
+    assert.strictEqual(await addon.task_timeout(0, 5000), "done");
+  });
+
+  it("should discard the result of a task that completes after the timeout", async function () {
+    const completions = addon.task_timeout_completions();
+    const onRejection = () => assert.fail("unexpected unhandledRejection");
+    const onWarning = (warning) =>
+      assert.fail(`unexpected warning: ${warning}`);
+
+    process.on("unhandledRejection", onRejection);
+    process.on("warning", onWarning);
+
+    try {
+      const promise = addon.task_timeout(50, 1);
+
+      await promise.then(
+        () => assert.fail("Did not throw"),
+        (err) => assert.strictEqual(err.name, "TimeoutError")
+      );
+
+      // Wait for the task to complete
+      await new Promise((resolve) => setTimeout(resolve, 200));
+
+      assert.strictEqual(addon.task_timeout_completions(), completions);
+    } finally {
+      process.off("unhandledRejection", onRejection);
+      process.off("warning", onWarning);
+    }
+  });
+
+  it("should accept timeouts longer than setTimeout allows", async function () {
+    const onWarning = (warning) =>
+      assert.fail(`unexpected warning: ${warning}`);
+
+    process.on("warning", onWarning);
+
+    try {
+      // Longer than the maximum delay of `setTimeout`
+      assert.strictEqual(await addon.task_timeout(20, 2 ** 32), "done");
+      // Too far in the future to be a deadline
+      assert.strictEqual(await addon.task_timeout(0, 2 ** 64), "done");
+    } finally {
+      process.off("warning", onWarning);
+    }
+  });
+
+  it("should cancel a task that has not started when the timeout passes", async function () {
+    const size = Number(process.env.UV_THREADPOOL_SIZE) || 4;
+    const executions = addon.task_timeout_executions();
+
+    // Occupy every thread of the pool
+    const busy = Array.from({ length: size }, () =>
+      addon.task_timeout(200, 5000)
+    );
+
+    await addon.task_timeout(0, 10).then(
+      () => assert.fail("Did not throw"),
+      (err) => assert.strictEqual(err.name, "TimeoutError")
+    );
+
+    await Promise.all(busy);
+    await new Promise((resolve) => setTimeout(resolve, 50));
+
+    assert.strictEqual(addon.task_timeout_executions(), executions + size);
+  });
+
+  it("should allow a task to observe its deadline", async function () {
+    const before = await addon.task_deadline_exceeded(20);
+
+    assert.strictEqual(before, false);
+  });
+
   it("should be able to reject a promise settling with a channel", async function () {
     const msg = "Rejected!";
 
@@ -501,4 +590,219 @@ const assert = require("chai").assert;
       assert.instanceOf(err.panic, Error);
     }
   });
+
+  it("should be able to cancel async work before it starts", function (cb) {
+    const cancelled = addon.work_cancel_before_start((completeCancelled) => {
+      try {
+        assert.strictEqual(completeCancelled, true);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    assert.strictEqual(cancelled, true);
+  });
+
+  it("should not cancel async work after it starts", function (cb) {
+    const cancelled = addon.work_cancel_after_start((completeCancelled) => {
+      try {
+        assert.strictEqual(completeCancelled, false);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    assert.strictEqual(cancelled, false);
+  });
+
+  it("should be able to queue the same async work many times", function (cb) {
+    addon.work_requeue(5, (executed) => {
+      try {
+        assert.strictEqual(executed, 5);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+
+  it("should name the async work resource", function (cb) {
+    const types = [];
+    const hook = require("async_hooks")
+      .createHook({
+        init(asyncId, type) {
+          types.push(type);
+        },
+      })
+      .enable();
+
+    addon.work_cancel_after_start(() => {
+      hook.disable();
+
+      try {
+        assert.include(types, "neon_test_gated_work");
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+  });
+});
+
+describe("Channel priorities", function () {
+  it("settles a promise before a flood of low priority closures", async function () {
+    const executed = await addon.channel_priority_flood(200);
+
+    assert.isAtMost(executed, 16);
+  });
+
+  it("executes a low priority closure after at most ratio others", async function () {
+    assert.strictEqual(await addon.channel_priority_ratio(100, 4), 4);
+    assert.strictEqual(await addon.channel_priority_ratio(100, 16), 16);
+    assert.strictEqual(await addon.channel_priority_ratio(10, 1000), 10);
+    assert.strictEqual(await addon.channel_priority_ratio(10, 0), 1);
+  });
+
+  it("reports stats for each priority", async function () {
+    const { queued, executed } = addon.channel_priority_stats();
+
+    assert.deepEqual(queued, {
+      high: { sent: 2, executed: 0, pending: 2 },
+      normal: { sent: 1, executed: 0, pending: 1 },
+      low: { sent: 3, executed: 0, pending: 3 },
+    });
+    assert.deepEqual(await executed, {
+      high: { sent: 2, executed: 2, pending: 0 },
+      normal: { sent: 1, executed: 1, pending: 0 },
+      low: { sent: 4, executed: 4, pending: 0 },
+    });
+  });
+});
+
+describe("Channel closing", function () {
+  const { Worker } = require("worker_threads");
+
+  // Starts producers in a worker that exits after `delay` milliseconds
+  function runProducers(delay) {
+    return new Promise((resolve, reject) => {
+      const worker = new Worker(
+        `
+        const addon = require(${JSON.stringify(require.resolve(".."))});
+        addon.channel_start_producers();
+        setTimeout(() => {}, ${delay});
+        `,
+        { eval: true }
+      );
+
+      worker.on("error", reject);
+      worker.on("exit", resolve);
+    });
+  }
+
+  // Waits for the producers of a closed channel to observe that it is closed
+  async function waitForProducers(stopped) {
+    for (let i = 0; i < 500; i++) {
+      if (addon.channel_close_stats().producersStopped >= stopped) {
+        return addon.channel_close_stats();
+      }
+
+      await new Promise((resolve) => setTimeout(resolve, 10));
+    }
+
+    throw new Error("producers did not stop");
+  }
+
+  it("stops producers and calls on_close once", async function () {
+    const before = addon.channel_close_stats();
+
+    await runProducers(20);
+
+    const after = await waitForProducers(before.producersStopped + 3);
+
+    assert.strictEqual(after.closeCallbacks, before.closeCallbacks + 1);
+    assert.strictEqual(after.producersStopped, before.producersStopped + 3);
+    assert.isAbove(after.sent, before.sent);
+  });
+
+  it("executes or rejects every send", async function () {
+    this.timeout(30000);
+
+    const before = addon.channel_close_stats();
+    const iterations = 20;
+
+    for (let i = 0; i < iterations; i++) {
+      await runProducers(i % 5);
+    }
+
+    const after = await waitForProducers(
+      before.producersStopped + iterations * 3
+    );
+
+    assert.strictEqual(
+      after.closeCallbacks,
+      before.closeCallbacks + iterations
+    );
+    assert.strictEqual(
+      after.executed - before.executed,
+      after.sent - before.sent
+    );
+  });
+});
+
+describe("Dropping roots in bulk", function () {
+  const itGc = typeof global.gc === "function" ? it : it.skip;
+  const n = 10000;
+
+  function holdObjects() {
+    const objects = Array.from({ length: n }, (_, i) => ({ i }));
+
+    addon.hold_roots(objects);
+
+    return objects.map((object) => new WeakRef(object));
+  }
+
+  it("drops roots with a single closure with drop_all_via", async function () {
+    const roots = addon.memory_stats().roots;
+
+    holdObjects();
+
+    assert.strictEqual(await addon.drop_held_roots(true), 1);
+    assert.strictEqual(addon.memory_stats().roots, roots);
+  });
+
+  it("coalesces roots dropped from another thread", async function () {
+    const roots = addon.memory_stats().roots;
+
+    holdObjects();
+
+    assert.strictEqual(await addon.drop_held_roots(false), 1);
+    assert.strictEqual(addon.memory_stats().roots, roots);
+  });
+
+  for (const bulk of [true, false]) {
+    itGc(
+      `allows the objects to be collected (bulk: ${bulk})`,
+      async function () {
+        const refs = holdObjects();
+
+        await addon.drop_held_roots(bulk);
+
+        // `deref` keeps the object alive until the end of the job, so each
+        // collection starts a new job
+        for (let i = 0; i < 100; i++) {
+          await new Promise((resolve) => setImmediate(resolve));
+          global.gc();
+          await new Promise((resolve) => setImmediate(resolve));
+
+          if (refs.every((ref) => ref.deref() === undefined)) {
+            return;
+          }
+        }
+
+        assert.fail("expected the objects to be collected");
+      }
+    );
+  }
 });
@@ -1,6 +1,3 @@
-var addon = require("..");
-var assert = require("chai").assert;
-
 const { Worker, isMainThread, parentPort } = require("worker_threads");
 
 if (!isMainThread) {
@@ -12,6 +9,11 @@ if (!isMainThread) {
   return;
 }
 
+// The addon is only loaded in the main thread since the detach worker does not
+// need it, and it may have been built with the `single-instance` feature
+var addon = require("..");
+var assert = require("chai").assert;
+
 // A background thread we can transfer buffers to as a way to force
 // them to be detached (see the `detach` function).
 const DETACH_WORKER = new Worker(__filename);
@@ -552,3 +554,168 @@ describe("Typed arrays", function () {
     } catch (expected) {}
   });
 });
+
+describe("Taking buffers", function () {
+  const bytes = (len) => Buffer.from(Array.from({ length: len }, (_, i) => i));
+
+  it("moves the allocation of an external ArrayBuffer", function () {
+    const buf = addon.tracked_external_array_buffer(64);
+    const { copied, sameAllocation, data } = addon.take_array_buffer(buf);
+
+    assert.isFalse(copied);
+    assert.isTrue(sameAllocation);
+    assert.deepEqual(data, bytes(64));
+    assert.strictEqual(buf.byteLength, 0);
+  });
+
+  it("copies an ArrayBuffer allocated by JavaScript", function () {
+    const buf = new ArrayBuffer(16);
+    const view = new Uint8Array(buf);
+
+    view.set(bytes(16));
+
+    const { copied, data } = addon.take_array_buffer(buf);
+
+    assert.isTrue(copied);
+    assert.deepEqual(data, bytes(16));
+    assert.strictEqual(buf.byteLength, 0);
+    assert.strictEqual(view.length, 0);
+  });
+
+  it("detaches the ArrayBuffer that was taken", function () {
+    const buf = addon.tracked_external_array_buffer(8);
+
+    addon.take_array_buffer(buf);
+
+    assert.throws(() => buf.slice(0), TypeError);
+    assert.throws(() => new Uint8Array(buf), TypeError);
+  });
+
+  it("throws when taking an ArrayBuffer twice", function () {
+    const buf = addon.tracked_external_array_buffer(8);
+
+    addon.take_array_buffer(buf);
+
+    assert.throws(() => addon.take_array_buffer(buf), TypeError, /detached/);
+  });
+
+  it("throws when taking an ArrayBuffer detached by JavaScript", async function () {
+    const buf = new ArrayBuffer(8);
+
+    await detach(buf);
+
+    assert.throws(() => addon.take_array_buffer(buf), TypeError, /detached/);
+  });
+
+  it("moves the allocation of an external Buffer", function () {
+    const buf = addon.tracked_external_buffer(32);
+    const { copied, sameAllocation, data } = addon.take_buffer(buf);
+
+    assert.isFalse(copied);
+    assert.isTrue(sameAllocation);
+    assert.deepEqual(data, bytes(32));
+    assert.strictEqual(buf.length, 0);
+    assert.throws(() => addon.take_buffer(buf), TypeError, /detached/);
+  });
+
+  it("copies a Buffer allocated by Node", function () {
+    const buf = Buffer.alloc(8192, 7);
+    const { copied, data } = addon.take_buffer(buf);
+
+    assert.isTrue(copied);
+    assert.deepEqual(data, Buffer.alloc(8192, 7));
+    assert.strictEqual(buf.length, 0);
+  });
+
+  it("throws when taking a Buffer that shares its ArrayBuffer", function () {
+    const buf = Buffer.from("pooled");
+
+    assert.throws(() => addon.take_buffer(buf), TypeError, /shares/);
+    assert.strictEqual(buf.toString(), "pooled");
+  });
+});
+
+describe("Typed array outputs", function () {
+  it("writes into the caller's array", function () {
+    const input = new Float64Array([1, 2, 3]);
+    const output = new Float64Array(3);
+
+    assert.strictEqual(addon.scale_into(input, 2, output), 3);
+    assert.deepEqual(Array.from(output), [2, 4, 6]);
+    assert.deepEqual(Array.from(input), [1, 2, 3]);
+  });
+
+  it("does not allocate for the output", function () {
+    function allocated(len) {
+      const input = new Float64Array(len);
+      const output = new Float64Array(len);
+      const before = addon.limits_allocated();
+
+      addon.scale_into(input, 2, output);
+
+      return addon.limits_allocated() - before;
+    }
+
+    assert.isBelow(allocated(100000), 8 * 1000);
+    assert.strictEqual(allocated(100000), allocated(1000));
+  });
+
+  it("throws if the output length does not match", function () {
+    const input = new Float64Array(3);
+
+    assert.throws(
+      () => addon.scale_into(input, 2, new Float64Array(2)),
+      RangeError,
+      'The "output" argument must have 3 elements, like the "input" argument. Received 2'
+    );
+    assert.throws(
+      () => addon.scale_into(input, 2, new Float32Array(3)),
+      TypeError
+    );
+  });
+
+  it("fills part of an output with `partial`", function () {
+    const input = new Float64Array([1, 2, 3]);
+    const short = new Float64Array(2);
+    const long = new Float64Array(4);
+
+    assert.strictEqual(addon.copy_into(input, short), 2);
+    assert.deepEqual(Array.from(short), [1, 2]);
+    assert.strictEqual(addon.copy_into(input, long), 3);
+    assert.deepEqual(Array.from(long), [1, 2, 3, 0]);
+  });
+
+  it("throws if the output is detached", function () {
+    const input = new Float64Array(0);
+    const output = new Float64Array(4);
+
+    detach(output.buffer);
+
+    assert.throws(
+      () => addon.scale_into(input, 2, output),
+      TypeError,
+      'The "output" argument is backed by a detached ArrayBuffer'
+    );
+  });
+
+  it("throws if an array is shared", function () {
+    const shared = new Float64Array(new SharedArrayBuffer(24));
+
+    assert.throws(
+      () => addon.scale_into(new Float64Array(3), 2, shared),
+      TypeError,
+      'The "output" argument must not be backed by a SharedArrayBuffer'
+    );
+    assert.throws(
+      () => addon.scale_into(shared, 2, new Float64Array(3)),
+      TypeError,
+      'The "input" argument must not be backed by a SharedArrayBuffer'
+    );
+  });
+
+  it("throws if the output overlaps an input", function () {
+    const array = new Float64Array(3);
+
+    assert.throws(() => addon.copy_into(array, array), Error, "overlaps");
+  });
+});
@@ -17,6 +17,32 @@ if (!isMainThread) {
 
   addon.get_or_init_thread_id(threadId);
   parentPort.once("message", (message) => {
+    if (message && message.type === "shared_box") {
+      let boxed = addon.shared_box_from_token(message.token);
+      let wrongType = null;
+
+      try {
+        addon.shared_box_from_token_wrong_type(message.token);
+      } catch (err) {
+        wrongType = err;
+      }
+
+      parentPort.postMessage({
+        ptr: addon.shared_box_ptr(boxed),
+        data: addon.shared_box_data(boxed),
+        wrongType,
+      });
+
+      // Drop this instance's box and report back once it has been collected
+      parentPort.once("message", () => {
+        boxed = null;
+        global.gc();
+        setTimeout(() => parentPort.postMessage("collected"), 10);
+      });
+
+      return;
+    }
+
     try {
       switch (message) {
         case "get_and_replace":
@@ -28,6 +54,26 @@ if (!isMainThread) {
         case "get_or_init_clone":
           addon.get_or_init_clone(() => ({}));
           break;
+        case "affinity_assert_main":
+          try {
+            addon.affinity_assert_main();
+          } catch (err) {
+            // Custom properties are not cloned with errors
+            const { code, message, expected, actual } = err;
+
+            parentPort.postMessage({ code, message, expected, actual });
+            return;
+          }
+          break;
+        case "template_results":
+          parentPort.postMessage({
+            results: addon.template_results(2),
+            created: addon.templates_created(),
+          });
+          return;
+        case "return_constants":
+          parentPort.postMessage(addon.return_constants());
+          return;
         case "get_thread_id":
           {
             let id = addon.get_or_init_thread_id(NaN);
@@ -126,6 +172,48 @@ describe("Worker / Root Tagging Tests", () => {
 
       worker.postMessage("get_or_init_clone");
     });
+
+    it("should fail to assert the thread affinity of another instance", (cb) => {
+      const worker = new Worker(__filename);
+
+      addon.affinity_capture_main();
+
+      worker.once("message", (err) => {
+        assert.strictEqual(err.code, "ERR_NEON_WRONG_THREAD");
+        assert.match(err.message, /^expected to run on the JavaScript thread/);
+        assert.notStrictEqual(err.expected, err.actual);
+        cb();
+      });
+
+      worker.postMessage("affinity_assert_main");
+    });
+
+    it("should create an object template for each instance", (cb) => {
+      const worker = new Worker(__filename);
+      const main = addon.template_results(2);
+      const created = addon.templates_created();
+
+      worker.once("message", (message) => {
+        assert.deepStrictEqual(message.results, main);
+        assert.strictEqual(message.created, created + 1);
+        worker.terminate().then(() => cb());
+      });
+
+      worker.postMessage("template_results");
+    });
+
+    it("should create constants in each instance", (cb) => {
+      const worker = new Worker(__filename);
+      const main = addon.return_constants();
+
+      worker.once("message", (constants) => {
+        assert.deepStrictEqual(constants, main);
+        assert.deepStrictEqual(addon.return_constants(), main);
+        worker.terminate().then(() => cb());
+      });
+
+      worker.postMessage("return_constants");
+    });
   });
 });
 
@@ -200,3 +288,80 @@ describe("Instance-local storage", () => {
     setTimeout(cb, 100);
   });
 });
+
+describe("JsBox::share", () => {
+  it("should share a boxed `Arc` with a worker", (cb) => {
+    let boxed = addon.shared_box_new("shared");
+    const token = addon.shared_box_share(boxed);
+    const ptr = addon.shared_box_ptr(boxed);
+    const finalized = addon.shared_box_finalized();
+    const worker = new Worker(__filename);
+
+    const done = (err) => {
+      worker.terminate();
+      cb(err);
+    };
+
+    worker.once("message", (message) => {
+      try {
+        assert.strictEqual(message.ptr, ptr);
+        assert.strictEqual(message.data, "shared");
+        assert.ok(message.wrongType instanceof Error);
+        assert.match(message.wrongType.message, /different type/);
+      } catch (err) {
+        return done(err);
+      }
+
+      worker.once("message", async () => {
+        try {
+          // The worker's box was collected, but the main thread still holds the data
+          assert.strictEqual(addon.shared_box_finalized(), finalized);
+          assert.strictEqual(
+            addon.shared_box_data(addon.shared_box_from_token(token)),
+            "shared"
+          );
+
+          boxed = null;
+
+          for (let i = 0; i < 10; i++) {
+            global.gc();
+            await new Promise((resolve) => setTimeout(resolve, 10));
+
+            if (addon.shared_box_finalized() > finalized) {
+              break;
+            }
+          }
+
+          assert.strictEqual(addon.shared_box_finalized(), finalized + 1);
+          assert.throws(
+            () => addon.shared_box_from_token(token),
+            /no longer valid/
+          );
+
+          done();
+        } catch (err) {
+          done(err);
+        }
+      });
+
+      worker.postMessage("collect");
+    });
+
+    worker.postMessage({ type: "shared_box", token });
+  });
+
+  it("should keep the token of each box that is alive", () => {
+    const boxes = Array.from({ length: 100 }, (_, i) =>
+      addon.shared_box_new(String(i))
+    );
+    const tokens = boxes.map((boxed) => addon.shared_box_share(boxed));
+
+    // The registry was swept several times while the boxes were shared
+    assert.deepEqual(addon.shared_box_share(boxes[0]), tokens[0]);
+    tokens.forEach((token, i) => {
+      const boxed = addon.shared_box_from_token(token);
+
+      assert.strictEqual(addon.shared_box_data(boxed), String(i));
+    });
+  });
+});
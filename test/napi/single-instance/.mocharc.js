@@ -0,0 +1,26 @@
+// Configuration of `npm run test:single-instance`. A second instance of an addon
+// built with the `single-instance` feature can't be loaded, so the tests that load
+// the addon in a worker thread are excluded by their full titles.
+const multiInstance = [
+  "tears down an instance while forwarding",
+  "Instance registry with workers",
+  "should not set the limit of other instances",
+  "tears down an instance with listeners registered",
+  "shares a single value across instances",
+  "releases the value when the last instance detaches",
+  "drops its values when an instance shuts down",
+  "Channel closing",
+  "Multi-Threaded",
+  "should allocate separate locals for each addon instance",
+  "should be able to exit a worker without a crash",
+  "should share a boxed `Arc` with a worker",
+];
+
+module.exports = {
+  "node-option": ["expose-gc"],
+  timeout: 5000,
+  recursive: true,
+  spec: ["lib", "single-instance"],
+  grep: multiInstance.join("|"),
+  invert: true,
+};
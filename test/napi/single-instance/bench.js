@@ -0,0 +1,30 @@
+// Compares the cost of reading instance data with and without the
+// `single-instance` feature. Run with `node single-instance/bench.js` after
+// building the addon in release mode, with and without
+// `--features single-instance`.
+const addon = require("..");
+
+// Times `iterations` calls of `f`, which each perform `per` operations
+function bench(name, iterations, f, per = 1) {
+  // Warm up, so the call is optimized before it is timed
+  for (let i = 0; i < iterations / 10; i++) {
+    f();
+  }
+
+  const start = process.hrtime.bigint();
+
+  for (let i = 0; i < iterations; i++) {
+    f();
+  }
+
+  const ns = Number(process.hrtime.bigint() - start) / iterations / per;
+
+  console.log(`${name}: ${ns.toFixed(1)} ns`);
+}
+
+console.log(`single-instance: ${addon.single_instance}`);
+
+bench("call with LocalKey::get_or_init", 10_000_000, () =>
+  addon.get_or_init_thread_id(0)
+);
+bench("LocalKey::get", 100_000, () => addon.get_thread_id_repeat(1000), 1000);
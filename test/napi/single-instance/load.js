@@ -0,0 +1,28 @@
+const assert = require("chai").assert;
+const { Worker } = require("worker_threads");
+
+// Only run by `npm run test:single-instance`, against an addon built with
+// `npm run build:single-instance`
+describe("single-instance", () => {
+  it("should throw when a second instance is loaded", (cb) => {
+    const worker = new Worker(
+      `require(${JSON.stringify(require.resolve(".."))});`,
+      { eval: true }
+    );
+
+    worker.once("error", (err) => {
+      try {
+        assert.match(err.message, /single-instance/);
+        cb();
+      } catch (err) {
+        cb(err);
+      }
+    });
+
+    worker.once("exit", (code) => {
+      if (code === 0) {
+        cb(new Error("Expected the worker to fail to load the addon"));
+      }
+    });
+  });
+});
@@ -0,0 +1,80 @@
+use std::thread;
+
+use neon::{adapt::interface, prelude::*};
+
+interface! {
+    /// A destination for chunks of bytes
+    pub trait Sink = "Sink" {
+        fn write(&self, chunk: Vec<u8>);
+        /// Returns the number of bytes written
+        #[nonblocking]
+        fn end(&self) -> f64;
+    }
+
+    pub struct JsSink;
+}
+
+// Resolves with the number returned by `f` on another thread, or its error message
+fn settle<'cx, F>(cx: &mut FunctionContext<'cx>, f: F) -> JsResult<'cx, JsPromise>
+where
+    F: FnOnce() -> Result<f64, String> + Send + 'static,
+{
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    thread::spawn(move || {
+        let result = f();
+
+        deferred.settle_with(&channel, move |mut cx| match result {
+            Ok(n) => Ok(cx.number(n).upcast::<JsValue>()),
+            Err(message) => Ok(cx.string(message).upcast()),
+        });
+    });
+
+    Ok(promise)
+}
+
+pub fn adapt_new_sink(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let sink = cx.argument::<JsValue>(0)?;
+
+    JsSink::new(&mut cx, sink)?;
+
+    Ok(cx.undefined())
+}
+
+// Writes each chunk from another thread, then ends the sink. Resolves with the result
+// of `end` or the message of the first error. The sink is dropped on that thread.
+pub fn adapt_drive_sink(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let sink = cx.argument::<JsValue>(0)?;
+    let chunks = cx.argument::<JsArray>(1)?.to_vec(&mut cx)?;
+    let chunks = chunks
+        .into_iter()
+        .map(|chunk| {
+            Ok(chunk
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx))
+        })
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    let sink: Box<dyn Sink + Send> = Box::new(JsSink::new(&mut cx, sink)?);
+
+    settle(&mut cx, move || {
+        for chunk in chunks {
+            sink.write(chunk.into_bytes())
+                .map_err(|err| err.to_string())?;
+        }
+
+        sink.end().join().map_err(|err| err.to_string())
+    })
+}
+
+// Calls a blocking method on the JavaScript thread, returning the error message
+pub fn adapt_write_on_main_thread(mut cx: FunctionContext) -> JsResult<JsString> {
+    let sink = cx.argument::<JsValue>(0)?;
+    let sink = JsSink::new(&mut cx, sink)?;
+
+    match sink.write(vec![1]) {
+        Ok(()) => cx.throw_error("expected the call to fail"),
+        Err(err) => Ok(cx.string(err.to_string())),
+    }
+}
@@ -0,0 +1,90 @@
+use std::{panic, sync::Mutex, thread};
+
+use neon::{prelude::*, thread::ThreadAffinity};
+
+// Captured on the main thread and asserted by workers
+static MAIN: Mutex<Option<ThreadAffinity>> = Mutex::new(None);
+
+fn message(err: Box<dyn std::any::Any + Send>) -> String {
+    match err.downcast::<String>() {
+        Ok(message) => *message,
+        Err(err) => err.downcast_ref::<&str>().unwrap_or(&"").to_string(),
+    }
+}
+
+pub fn affinity_same_thread(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let affinity = ThreadAffinity::capture(&mut cx);
+
+    affinity.assert(&mut cx)?;
+    affinity.assert_thread();
+
+    Ok(cx.boolean(affinity.check()))
+}
+
+// Asserts the affinity in a closure sent to the channel from another thread and calls
+// `callback` with the exception, if any
+pub fn affinity_channel(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+    let affinity = ThreadAffinity::capture(&mut cx);
+    let channel = cx.channel();
+
+    thread::spawn(move || {
+        channel.send(move |mut cx| {
+            let err = match cx.try_catch(|cx| affinity.assert(cx)) {
+                Ok(()) => cx.null().upcast::<JsValue>(),
+                Err(err) => err,
+            };
+
+            callback
+                .into_inner(&mut cx)
+                .call_with(&cx)
+                .arg(err)
+                .exec(&mut cx)
+        });
+    });
+
+    Ok(cx.undefined())
+}
+
+// Returns `[check(), panic message of assert_thread()]` from a spawned thread
+pub fn affinity_spawned_thread(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let affinity = ThreadAffinity::capture(&mut cx);
+
+    let (check, message) = thread::Builder::new()
+        .name("affinity-test".into())
+        .spawn(move || {
+            let check = affinity.check();
+            let result = panic::catch_unwind(|| affinity.assert_thread());
+
+            (check, result.err().map(message).unwrap_or_default())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    let result = cx.empty_array();
+    let check = cx.boolean(check);
+    let message = cx.string(message);
+
+    result.set(&mut cx, 0, check)?;
+    result.set(&mut cx, 1, message)?;
+
+    Ok(result)
+}
+
+pub fn affinity_capture_main(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    *MAIN.lock().unwrap() = Some(ThreadAffinity::capture(&mut cx));
+
+    Ok(cx.undefined())
+}
+
+pub fn affinity_assert_main(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let affinity = MAIN.lock().unwrap().clone();
+
+    match affinity {
+        Some(affinity) => affinity.assert(&mut cx)?,
+        None => return cx.throw_error("the main thread was not captured"),
+    }
+
+    Ok(cx.undefined())
+}
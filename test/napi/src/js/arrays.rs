@@ -24,3 +24,75 @@ pub fn read_js_array(mut cx: FunctionContext) -> JsResult<JsValue> {
 
     Ok(first_element)
 }
+
+pub fn array_set_len(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let array = cx.argument::<JsArray>(0)?;
+    let len = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+
+    array.set_len(&mut cx, len)?;
+
+    Ok(array)
+}
+
+pub fn array_truncate(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let array = cx.argument::<JsArray>(0)?;
+    let len = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+
+    array.truncate(&mut cx, len)?;
+
+    Ok(array)
+}
+
+pub fn array_set_range(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let array = cx.argument::<JsArray>(0)?;
+    let start = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let values = cx.argument::<JsArray>(2)?.to_vec(&mut cx)?;
+
+    array.set_range(&mut cx, start, &values)?;
+
+    Ok(array)
+}
+
+pub fn array_fill(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let array = cx.argument::<JsArray>(0)?;
+    let value = cx.argument::<JsValue>(1)?;
+    let start = cx.argument::<JsNumber>(2)?.value(&mut cx) as u32;
+    let end = cx.argument::<JsNumber>(3)?.value(&mut cx) as u32;
+
+    array.fill(&mut cx, value, start..end)?;
+
+    Ok(array)
+}
+
+// Builds `[0, 1, ..., n - 1]` from chunks produced from back to front, writing each
+// element with `set` or each chunk with `set_range`
+pub fn array_build_reversed(mut cx: FunctionContext) -> JsResult<JsArray> {
+    const CHUNK: u32 = 1024;
+
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let set_range = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    let array = cx.empty_array();
+    let mut end = n;
+
+    while end > 0 {
+        let start = end.saturating_sub(CHUNK);
+
+        cx.execute_scoped(|mut cx| {
+            let chunk = (start..end).map(|i| cx.number(i)).collect::<Vec<_>>();
+
+            if set_range {
+                return array.set_range(&mut cx, start, &chunk);
+            }
+
+            for (i, v) in (start..end).zip(chunk).rev() {
+                array.set(&mut cx, i, v)?;
+            }
+
+            Ok(())
+        })?;
+
+        end = start;
+    }
+
+    Ok(array)
+}
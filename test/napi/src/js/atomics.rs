@@ -0,0 +1,94 @@
+use std::{thread, time::Duration};
+
+use neon::{
+    atomics::{self, AtomicI32View, WaitResult},
+    prelude::*,
+};
+
+fn wait_result(result: WaitResult) -> &'static str {
+    match result {
+        WaitResult::Ok => "ok",
+        WaitResult::NotEqual => "not-equal",
+        WaitResult::TimedOut => "timed-out",
+    }
+}
+
+pub fn atomics_len(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let array = cx.argument::<JsInt32Array>(0)?;
+    let view = AtomicI32View::new(&mut cx, array)?;
+
+    Ok(cx.number(view.len() as f64))
+}
+
+// Stores `value` at `index` from a Rust thread and calls `callback` with the number of
+// woken waiters
+pub fn atomics_store_notify(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let array = cx.argument::<JsInt32Array>(0)?;
+    let index = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let value = cx.argument::<JsNumber>(2)?.value(&mut cx) as i32;
+    let callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+    let view = AtomicI32View::new(&mut cx, array)?;
+    let channel = cx.channel();
+
+    thread::spawn(move || {
+        let woken = atomics::store_notify(&view, index, value, u32::MAX)
+            .unwrap()
+            .join()
+            .unwrap();
+
+        channel.send(move |mut cx| {
+            let this = cx.undefined();
+            let woken = cx.number(woken);
+
+            callback
+                .into_inner(&mut cx)
+                .exec(&mut cx, this, [woken.upcast()])
+        });
+    });
+
+    Ok(cx.undefined())
+}
+
+// Waits on a Rust thread while the value at `index` is `expected`, then calls `callback`
+// with the result, or an out of bounds error
+pub fn atomics_wait(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let array = cx.argument::<JsInt32Array>(0)?;
+    let index = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let expected = cx.argument::<JsNumber>(2)?.value(&mut cx) as i32;
+    let timeout = cx
+        .argument_opt(3)
+        .and_then(|v| v.downcast::<JsNumber, _>(&mut cx).ok())
+        .map(|v| Duration::try_from_secs_f64(v.value(&mut cx) / 1000.0).unwrap_or(Duration::MAX));
+    let callback = cx.argument::<JsFunction>(4)?.root(&mut cx);
+    let view = AtomicI32View::new(&mut cx, array)?;
+    let channel = cx.channel();
+
+    thread::spawn(move || {
+        let result = atomics::wait_for_change(&view, index, expected, timeout)
+            .map(wait_result)
+            .map_err(|err| err.to_string());
+
+        channel.send(move |mut cx| {
+            let this = cx.undefined();
+            let result = match result {
+                Ok(result) => cx.string(result),
+                Err(err) => cx.string(err),
+            };
+
+            callback
+                .into_inner(&mut cx)
+                .exec(&mut cx, this, [result.upcast()])
+        });
+    });
+
+    Ok(cx.undefined())
+}
+
+// Calls `wait_for_change` on the JavaScript thread, which panics
+pub fn atomics_wait_on_js_thread(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let array = cx.argument::<JsInt32Array>(0)?;
+    let view = AtomicI32View::new(&mut cx, array)?;
+    let _ = atomics::wait_for_change(&view, 0, 0, Some(Duration::from_millis(1)));
+
+    Ok(cx.undefined())
+}
@@ -1,6 +1,18 @@
-use std::cell::RefCell;
-
-use neon::prelude::*;
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use neon::{
+    prelude::*,
+    types::{
+        extract::{TryFromJs, TryIntoJs},
+        SharedBoxToken,
+    },
+};
 
 pub struct Person {
     name: String,
@@ -72,3 +84,67 @@ pub fn ref_person_fail(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 pub fn external_unit(mut cx: FunctionContext) -> JsResult<JsBox<()>> {
     Ok(cx.boxed(()))
 }
+
+static SHARED_FINALIZED: AtomicUsize = AtomicUsize::new(0);
+
+pub struct SharedData(String);
+
+impl Finalize for SharedData {
+    fn finalize<'a, C: Context<'a>>(self, _: &mut C) {
+        SHARED_FINALIZED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+type SharedBox = JsBox<Arc<SharedData>>;
+
+fn token_argument(cx: &mut FunctionContext, i: usize) -> NeonResult<SharedBoxToken> {
+    let v = cx.argument::<JsValue>(i)?;
+
+    match SharedBoxToken::try_from_js(cx, v)? {
+        Some(token) => Ok(token),
+        None => cx.throw_type_error("expected a shared box token"),
+    }
+}
+
+pub fn shared_box_new(mut cx: FunctionContext) -> JsResult<SharedBox> {
+    let data = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    Ok(cx.boxed(Arc::new(SharedData(data))))
+}
+
+pub fn shared_box_share(mut cx: FunctionContext) -> JsResult<JsUint8Array> {
+    let boxed = cx.argument::<SharedBox>(0)?;
+
+    boxed.share(&mut cx)?.try_into_js(&mut cx)
+}
+
+pub fn shared_box_from_token(mut cx: FunctionContext) -> JsResult<SharedBox> {
+    let token = token_argument(&mut cx, 0)?;
+
+    JsBox::from_token(&mut cx, &token)
+}
+
+pub fn shared_box_from_token_wrong_type(mut cx: FunctionContext) -> JsResult<JsBox<Arc<String>>> {
+    let token = token_argument(&mut cx, 0)?;
+
+    JsBox::from_token(&mut cx, &token)
+}
+
+pub fn shared_box_data(mut cx: FunctionContext) -> JsResult<JsString> {
+    let boxed = cx.argument::<SharedBox>(0)?;
+    let data = boxed.0.clone();
+
+    Ok(cx.string(data))
+}
+
+// Address of the shared data, for checking that instances share the same `Arc`
+pub fn shared_box_ptr(mut cx: FunctionContext) -> JsResult<JsString> {
+    let boxed = cx.argument::<SharedBox>(0)?;
+    let ptr = Arc::as_ptr(&boxed) as usize;
+
+    Ok(cx.string(ptr.to_string()))
+}
+
+pub fn shared_box_finalized(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(SHARED_FINALIZED.load(Ordering::SeqCst) as f64))
+}
@@ -0,0 +1,28 @@
+use neon::prelude::*;
+
+// Registered as available by `main`
+#[neon::function(requires = "present")]
+pub fn capability_present(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("called"))
+}
+
+// Registered as unavailable by `main`
+#[neon::function(requires = "missing")]
+pub fn capability_missing(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("called"))
+}
+
+// Never registered
+#[neon::function(requires = "unregistered")]
+pub fn capability_unregistered(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("called"))
+}
+
+pub fn capability_is_available(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    match neon::capabilities::is_available(&mut cx, &name) {
+        Some(available) => Ok(cx.boolean(available).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
@@ -0,0 +1,106 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+use neon::{
+    prelude::*,
+    types::extract::{Entries, Partial, TryFromJs, TryIntoJs},
+};
+
+fn extract<'cx, T: TryFromJs<'cx>>(cx: &mut FunctionContext<'cx>, expected: &str) -> NeonResult<T> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match T::try_from_js(cx, v)? {
+        Some(v) => Ok(v),
+        None => cx.throw_type_error(format!("expected {}", expected)),
+    }
+}
+
+pub fn btree_map_round_trip(mut cx: FunctionContext) -> JsResult<JsObject> {
+    extract::<BTreeMap<String, f64>>(&mut cx, "a map of numbers")?.try_into_js(&mut cx)
+}
+
+pub fn btree_map_numeric_keys(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let map = (1..=3u32)
+        .rev()
+        .map(|i| (i, i.to_string()))
+        .collect::<BTreeMap<_, _>>();
+
+    map.try_into_js(&mut cx)
+}
+
+pub fn hash_map_round_trip(mut cx: FunctionContext) -> JsResult<JsObject> {
+    extract::<HashMap<String, f64>>(&mut cx, "a map of numbers")?.try_into_js(&mut cx)
+}
+
+pub fn hash_set_round_trip(mut cx: FunctionContext) -> JsResult<JsObject> {
+    extract::<HashSet<String>>(&mut cx, "a set of strings")?.try_into_js(&mut cx)
+}
+
+pub fn btree_set_round_trip(mut cx: FunctionContext) -> JsResult<JsObject> {
+    extract::<BTreeSet<String>>(&mut cx, "a set of strings")?.try_into_js(&mut cx)
+}
+
+pub fn entries_round_trip(mut cx: FunctionContext) -> JsResult<JsObject> {
+    extract::<Entries<String, f64>>(&mut cx, "entries of numbers")?.try_into_js(&mut cx)
+}
+
+pub fn entries_duplicates(mut cx: FunctionContext) -> JsResult<JsObject> {
+    Entries(vec![("a", 1.0), ("b", 2.0), ("a", 3.0)]).try_into_js(&mut cx)
+}
+
+// A row of an import, `{ id, name }`
+struct Row {
+    id: f64,
+    name: String,
+}
+
+impl<'cx> TryFromJs<'cx> for Row {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let obj = match v.downcast::<JsObject, _>(cx) {
+            Ok(obj) => obj,
+            Err(_) => return Ok(None),
+        };
+
+        let id = obj.get_value(cx, "id")?;
+        let name = obj.get_value(cx, "name")?;
+
+        match (f64::try_from_js(cx, id)?, String::try_from_js(cx, name)?) {
+            (Some(id), Some(name)) => Ok(Some(Row { id, name })),
+            _ => Ok(None),
+        }
+    }
+}
+
+// Returns `[rows, errors]`, with the rows formatted as `"id:name"`, allowing at most
+// `max_errors` errors
+pub fn partial_rows(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+    let max_errors = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let Partial(rows, errors) = match Partial::<Row>::try_from_js_with(&mut cx, v, max_errors)? {
+        Some(partial) => partial,
+        None => return cx.throw_type_error("expected an array of rows"),
+    };
+
+    let rows = rows
+        .into_iter()
+        .map(|row| format!("{}:{}", row.id, row.name))
+        .collect::<Vec<_>>()
+        .try_into_js(&mut cx)?;
+
+    let errors = errors.try_into_js(&mut cx)?;
+    let result = cx.empty_array();
+
+    result.set(&mut cx, 0, rows)?;
+    result.set(&mut cx, 1, errors)?;
+
+    Ok(result)
+}
+
+// Extracts with the default cap
+pub fn partial_numbers(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let Partial(numbers, _) = extract::<Partial<f64>>(&mut cx, "an array")?;
+
+    numbers.try_into_js(&mut cx)
+}
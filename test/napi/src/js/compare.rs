@@ -0,0 +1,42 @@
+use neon::{
+    compare::{self, Options},
+    prelude::*,
+};
+
+pub fn deep_equals(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let a = cx.argument::<JsValue>(0)?;
+    let b = cx.argument::<JsValue>(1)?;
+    let mut options = Options::default();
+
+    if let Some(o) = cx.argument_opt(2) {
+        let o = o.downcast_or_throw::<JsObject, _>(&mut cx)?;
+        let get = |cx: &mut FunctionContext, key: &str, default: bool| -> NeonResult<bool> {
+            Ok(o.get_opt::<JsBoolean, _, _>(cx, key)?
+                .map(|v| v.value(cx))
+                .unwrap_or(default))
+        };
+
+        options.nan_equal = get(&mut cx, "nanEqual", options.nan_equal)?;
+        options.distinguish_negative_zero = get(
+            &mut cx,
+            "distinguishNegativeZero",
+            options.distinguish_negative_zero,
+        )?;
+        options.compare_collections =
+            get(&mut cx, "compareCollections", options.compare_collections)?;
+        options.compare_typed_arrays =
+            get(&mut cx, "compareTypedArrays", options.compare_typed_arrays)?;
+        options.compare_prototypes = get(&mut cx, "comparePrototypes", options.compare_prototypes)?;
+    }
+
+    let equal = compare::deep_equals(&mut cx, a, b, options)?;
+
+    Ok(cx.boolean(equal))
+}
+
+pub fn deep_diff(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let a = cx.argument::<JsValue>(0)?;
+    let b = cx.argument::<JsValue>(1)?;
+
+    compare::deep_diff(&mut cx, a, b)
+}
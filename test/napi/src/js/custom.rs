@@ -0,0 +1,112 @@
+use neon::{
+    prelude::*,
+    thread::LocalKey,
+    types::{custom, extract::TryFromJs},
+};
+
+static URL: LocalKey<Root<JsFunction>> = LocalKey::new();
+
+fn is_url<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<bool> {
+    let url = URL
+        .get_or_try_init(cx, |cx| {
+            let global = cx.global();
+
+            global
+                .get::<JsFunction, _, _>(cx, "URL")
+                .map(|url| url.root(cx))
+        })?
+        .to_inner(cx);
+
+    custom::instance_of(cx, v, url)
+}
+
+fn is_map<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<bool> {
+    let global = cx.global();
+    let map = global.get::<JsFunction, _, _>(cx, "Map")?;
+
+    custom::instance_of(cx, v, map)
+}
+
+// Throws if `v` has a `throws` property, to test a failed check
+fn is_throwing<'cx, C: Context<'cx>>(cx: &mut C, v: Handle<'cx, JsValue>) -> NeonResult<bool> {
+    let v = v.downcast_or_throw::<JsObject, _>(cx)?;
+
+    if v.get_opt::<JsValue, _, _>(cx, "throws")?.is_some() {
+        return cx.throw_error("is_throwing failed");
+    }
+
+    Ok(true)
+}
+
+neon::declare_value_type!(
+    /// An instance of `URL`
+    pub JsUrl,
+    is_url,
+    "URL"
+);
+
+neon::declare_value_type!(pub JsMap, is_map);
+
+neon::declare_value_type!(JsThrowing, is_throwing);
+
+pub fn custom_is_url(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let v = cx.argument::<JsValue>(0)?;
+    let is_url = v.is_a::<JsUrl, _>(&mut cx);
+
+    Ok(cx.boolean(is_url))
+}
+
+// Classifies the argument with downcasts to both custom types
+pub fn custom_classify(mut cx: FunctionContext) -> JsResult<JsString> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    let kind = if v.downcast::<JsUrl, _>(&mut cx).is_ok() {
+        "url"
+    } else if v.downcast::<JsMap, _>(&mut cx).is_ok() {
+        "map"
+    } else {
+        "other"
+    };
+
+    Ok(cx.string(kind))
+}
+
+pub fn custom_url_host(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let url = cx.argument::<JsUrl>(0)?;
+
+    url.get_value(&mut cx, "host")
+}
+
+pub fn custom_map_size(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let map = cx.argument::<JsMap>(0)?;
+
+    map.get_value(&mut cx, "size")
+}
+
+// Extracts a `URL` with `TryFromJs`, returning its `href` or `null`
+pub fn custom_try_from_js(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Handle::<JsUrl>::try_from_js(&mut cx, v)? {
+        Some(url) => url.get_value(&mut cx, "href"),
+        None => Ok(cx.null().upcast()),
+    }
+}
+
+// Constructs a `URL` and returns it as a `JsUrl`
+pub fn custom_parse_url(mut cx: FunctionContext) -> JsResult<JsUrl> {
+    let href = cx.argument::<JsString>(0)?;
+    let url = cx.global().get::<JsFunction, _, _>(&mut cx, "URL")?;
+    let url = url.construct(&mut cx, [href.upcast()])?;
+
+    url.downcast_or_throw(&mut cx)
+}
+
+// Returns whether the argument is a `JsThrowing`; the exception thrown by the check
+// remains pending
+pub fn custom_is_throwing(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let v = cx.argument::<JsValue>(0)?;
+    let is_throwing = v.is_a::<JsThrowing, _>(&mut cx);
+
+    Ok(cx.boolean(is_throwing))
+}
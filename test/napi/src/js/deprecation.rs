@@ -0,0 +1,35 @@
+use neon::prelude::*;
+
+#[neon::function(deprecated = "use deprecated_target() instead")]
+pub fn deprecated_once(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("called"))
+}
+
+// Only called with `process.throwDeprecation` set
+#[neon::function(deprecated = "deprecatedThrows() is deprecated")]
+pub fn deprecated_throws(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("called"))
+}
+
+// Exported as `deprecated_alias` with `export_deprecated_alias`. Returns `this` and
+// its arguments.
+pub fn deprecated_target(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let this = cx.this_value();
+    let result = cx.empty_array();
+
+    result.set(&mut cx, 0, this)?;
+
+    for i in 0..cx.len() {
+        if let Some(arg) = cx.argument_opt(i) {
+            result.set(&mut cx, i as u32 + 1, arg)?;
+        }
+    }
+
+    Ok(result)
+}
+
+pub fn deprecation_code(mut cx: FunctionContext) -> JsResult<JsString> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    Ok(cx.string(neon::deprecation::code(&name)))
+}
@@ -0,0 +1,138 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use neon::{
+    diagnostics::{self, BlockReport, BlockWatchdog},
+    prelude::*,
+};
+
+static WATCHDOG: Mutex<Option<BlockWatchdog>> = Mutex::new(None);
+static REPORTS: Mutex<Vec<BlockReport>> = Mutex::new(Vec::new());
+
+// Reports are recorded with the export that was executing when they were made
+static CURRENT: Mutex<Vec<Option<String>>> = Mutex::new(Vec::new());
+
+pub fn watchdog_enable(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let threshold = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let threshold = Duration::from_millis(threshold as u64);
+    let watchdog = diagnostics::block_watchdog(&mut cx, threshold, |report| {
+        CURRENT.lock().unwrap().push(report.current_export());
+        REPORTS.lock().unwrap().push(report.clone());
+    });
+
+    *WATCHDOG.lock().unwrap() = Some(watchdog);
+
+    Ok(cx.undefined())
+}
+
+pub fn watchdog_disable(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    WATCHDOG.lock().unwrap().take();
+
+    Ok(cx.undefined())
+}
+
+pub fn watchdog_reports(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let reports = std::mem::take(&mut *REPORTS.lock().unwrap());
+    let current = std::mem::take(&mut *CURRENT.lock().unwrap());
+    let array = cx.empty_array();
+
+    for (i, (report, current)) in reports.into_iter().zip(current).enumerate() {
+        let o = cx.empty_object();
+        let name = cx.string(report.name);
+        let duration = cx.number(report.duration.as_secs_f64() * 1000.0);
+        let current = match current {
+            Some(current) => cx.string(current).upcast::<JsValue>(),
+            None => cx.null().upcast(),
+        };
+
+        o.set(&mut cx, "name", name)?;
+        o.set(&mut cx, "duration", duration)?;
+        o.set(&mut cx, "current", current)?;
+        array.set(&mut cx, i as u32, o)?;
+    }
+
+    Ok(array)
+}
+
+// Blocks for `ms` milliseconds and returns the number of reports made while blocking
+pub fn watchdog_slow(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let ms = cx.argument::<JsNumber>(0)?.value(&mut cx);
+
+    thread::sleep(Duration::from_millis(ms as u64));
+
+    let reports = REPORTS.lock().unwrap().len();
+
+    Ok(cx.number(reports as f64))
+}
+
+pub fn watchdog_fast(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    Ok(cx.undefined())
+}
+
+// Blocks for `steps` times `ms` milliseconds, calling `callback` after each step
+pub fn watchdog_nested(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let steps = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let ms = cx.argument::<JsNumber>(1)?.value(&mut cx);
+    let callback = cx.argument::<JsFunction>(2)?;
+
+    for _ in 0..steps {
+        thread::sleep(Duration::from_millis(ms as u64));
+        callback.call_with(&cx).exec(&mut cx)?;
+    }
+
+    Ok(cx.undefined())
+}
+
+// Number of messages sent to the JavaScript thread by `publish_from`
+static SENT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn channel_publish(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let message = cx.argument::<JsValue>(1)?;
+    let channel = diagnostics::channel(&mut cx, &name)?;
+
+    channel.publish(&mut cx, message)?;
+
+    Ok(cx.boolean(channel.has_subscribers()))
+}
+
+// Publishes `"message i"` for each `i` below `n` from another thread, and returns
+// the number of messages sent so far
+pub fn channel_publish_from(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let n = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let channel = diagnostics::channel(&mut cx, &name)?;
+
+    thread::spawn(move || {
+        for i in 0..n {
+            if channel.publish_from(format!("message {}", i)) {
+                SENT.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    })
+    .join()
+    .unwrap();
+
+    Ok(cx.number(SENT.load(Ordering::Relaxed) as f64))
+}
+
+#[neon::function(trace = "neon:test")]
+pub fn traced_add(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let a = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let b = cx.argument::<JsNumber>(1)?.value(&mut cx);
+
+    Ok(cx.number(a + b))
+}
+
+#[neon::function(trace = "neon:test", error = "throw")]
+pub fn traced_parse(mut cx: FunctionContext) -> NeonResult<Result<f64, String>> {
+    let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    Ok(s.parse::<f64>().map_err(|err| err.to_string()))
+}
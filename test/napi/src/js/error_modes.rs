@@ -0,0 +1,43 @@
+use neon::{prelude::*, types::extract::Finite};
+
+// Returns `Ok(value)` if the first argument is truthy, or `Err("failed")`
+fn result(cx: &mut FunctionContext, value: f64) -> NeonResult<Result<f64, String>> {
+    let ok = cx.argument::<JsBoolean>(0)?.value(cx);
+
+    Ok(if ok {
+        Ok(value)
+    } else {
+        Err(String::from("failed"))
+    })
+}
+
+#[neon::function(error = "throw")]
+pub fn error_mode_throw(mut cx: FunctionContext) -> NeonResult<Result<f64, String>> {
+    result(&mut cx, 1.0)
+}
+
+#[neon::function(error = "value")]
+pub fn error_mode_value(mut cx: FunctionContext) -> NeonResult<Result<f64, String>> {
+    result(&mut cx, 1.0)
+}
+
+#[neon::function(error = "tuple")]
+pub fn error_mode_tuple(mut cx: FunctionContext) -> NeonResult<Result<f64, String>> {
+    result(&mut cx, 1.0)
+}
+
+// A plain `Result`, with an early return
+#[neon::function(error = "value")]
+pub fn error_mode_plain(_cx: FunctionContext) -> Result<&'static str, Vec<&'static str>> {
+    if true {
+        return Err(vec!["not", "found"]);
+    }
+
+    Ok("found")
+}
+
+// The error can't be converted: `NaN` is not finite
+#[neon::function(error = "value")]
+pub fn error_mode_conversion(_cx: FunctionContext) -> Result<f64, Finite<f64>> {
+    Err(Finite(f64::NAN))
+}
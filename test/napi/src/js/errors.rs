@@ -1,4 +1,6 @@
-use neon::prelude::*;
+use std::cell::RefCell;
+
+use neon::{prelude::*, types::buffer::TypedArray};
 
 pub fn new_error(mut cx: FunctionContext) -> JsResult<JsError> {
     let msg = cx.argument::<JsString>(0)?.value(&mut cx);
@@ -32,3 +34,240 @@ pub fn downcast_error(mut cx: FunctionContext) -> JsResult<JsString> {
         panic!()
     }
 }
+
+#[derive(Debug)]
+struct ChainError {
+    message: &'static str,
+    source: Option<Box<ChainError>>,
+}
+
+impl std::fmt::Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(self.message)
+    }
+}
+
+impl std::error::Error for ChainError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.source
+            .as_deref()
+            .map(|err| err as &(dyn std::error::Error + 'static))
+    }
+}
+
+// Rejects with a three-deep error chain created on another thread
+pub fn reject_sendable_error(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    std::thread::spawn(move || {
+        let err = ChainError {
+            message: "outer",
+            source: Some(Box::new(ChainError {
+                message: "middle",
+                source: Some(Box::new(ChainError {
+                    message: "inner",
+                    source: None,
+                })),
+            })),
+        };
+
+        let err = neon::types::SendableError::new(&err).with_code("E_OUTER");
+
+        deferred.reject_sendable(&channel, err);
+    });
+
+    Ok(promise)
+}
+
+// Captures a `SendableError` from another `SendableError`, which is also an `Error`
+pub fn sendable_error_from_sendable(mut cx: FunctionContext) -> JsResult<JsError> {
+    let err = ChainError {
+        message: "outer",
+        source: Some(Box::new(ChainError {
+            message: "inner",
+            source: None,
+        })),
+    };
+
+    let err = neon::types::SendableError::new(&err);
+
+    neon::types::SendableError::new(&err).to_js_error(&mut cx)
+}
+
+pub fn sendable_error_with_backtrace(mut cx: FunctionContext) -> JsResult<JsError> {
+    let limit = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+
+    neon::types::SendableError::msg("with backtrace")
+        .with_backtrace(std::backtrace::Backtrace::force_capture())
+        .with_backtrace_limit(limit)
+        .to_js_error(&mut cx)
+}
+
+pub fn argument_string(mut cx: FunctionContext) -> JsResult<JsString> {
+    cx.argument::<JsString>(0)
+}
+
+pub fn argument_array(mut cx: FunctionContext) -> JsResult<JsArray> {
+    cx.argument::<JsArray>(0)
+}
+
+pub fn named_argument_string(mut cx: FunctionContext) -> JsResult<JsString> {
+    cx.named_argument::<JsString>(0, "name")
+}
+
+pub fn set_plain_argument_errors(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let plain = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let style = if plain {
+        neon::result::ArgumentErrors::Plain
+    } else {
+        neon::result::ArgumentErrors::Node
+    };
+
+    style.set();
+
+    Ok(cx.undefined())
+}
+
+pub fn rust_trace_set_enabled(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let enabled = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+
+    neon::types::SendableError::set_rust_trace_enabled(&mut cx, enabled);
+
+    Ok(cx.undefined())
+}
+
+pub fn rust_trace_error(mut cx: FunctionContext) -> JsResult<JsError> {
+    neon::types::SendableError::msg("with rust trace")
+        .rust_trace(true)
+        .to_js_error(&mut cx)
+}
+
+// Throws `err` and runs operations that are safe while an exception is pending.
+// Returns `{ caught, checks }`, where `checks` maps each operation to whether it
+// behaved normally.
+pub fn pending_safe_operations(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let err = cx.argument::<JsValue>(0)?;
+    let mut buffer = JsBuffer::new(&mut cx, 4)?;
+    let mut checks = Vec::new();
+
+    let caught = cx.try_catch(|cx| {
+        let _ = cx.throw::<_, ()>(err);
+
+        let string = cx.string("string");
+        checks.push(("string", string.value(cx) == "string"));
+
+        let number = cx.number(1);
+        checks.push(("number", number.value(cx) == 1.0));
+
+        let object = cx.empty_object();
+        let array = JsArray::new(cx, 3);
+        checks.push(("array", array.len(cx) == 3));
+        checks.push(("error", cx.error("error").is_ok()));
+
+        let boxed = cx.boxed(RefCell::new(1));
+        *boxed.borrow_mut() += 1;
+        let downcast = boxed
+            .upcast::<JsValue>()
+            .downcast::<JsBox<RefCell<i32>>, _>(cx)
+            .is_ok();
+        checks.push(("box", downcast && *boxed.borrow() == 2));
+
+        let lock = cx.lock();
+        let borrowed = buffer.try_borrow_mut(&lock).map(|mut data| data[0] = 1);
+        drop(lock);
+        checks.push(("lock", borrowed.is_ok()));
+
+        let root = object.root(cx);
+        let inner = root.to_inner(cx);
+        checks.push(("root", inner.strict_equals(cx, object)));
+        root.drop(cx);
+
+        let date = cx.date(5.0).map(|date| date.value(cx) == 5.0);
+        checks.push(("date", date.unwrap_or(false)));
+
+        let (deferred, promise) = cx.promise();
+        let value = cx.number(1);
+        deferred.resolve(cx, value);
+        checks.push(("promise", promise.is_a::<JsPromise, _>(cx)));
+
+        // The original exception is kept
+        cx.throw_error("second")
+    });
+
+    pending_report(&mut cx, caught, checks)
+}
+
+fn pending_report<'cx>(
+    cx: &mut FunctionContext<'cx>,
+    caught: Result<(), Handle<'cx, JsValue>>,
+    checks: Vec<(&str, bool)>,
+) -> JsResult<'cx, JsObject> {
+    let caught = match caught {
+        Ok(()) => cx.undefined().upcast(),
+        Err(caught) => caught,
+    };
+    let result = cx.empty_object();
+    let report = cx.empty_object();
+
+    for (name, ok) in checks {
+        let ok = cx.boolean(ok);
+
+        report.set(cx, name, ok)?;
+    }
+
+    result.set(cx, "caught", caught)?;
+    result.set(cx, "checks", report)?;
+
+    Ok(result)
+}
+
+// Throws `err` and runs operations that run JavaScript, which fail while an
+// exception is pending. Returns `{ caught, checks }`, where `checks` maps each
+// operation to whether it returned an `Err`.
+pub fn pending_failing_operations(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let err = cx.argument::<JsValue>(0)?;
+    let object = cx.argument::<JsObject>(1)?;
+    let f = cx.argument::<JsFunction>(2)?;
+    let mut checks = Vec::new();
+
+    let caught = cx.try_catch(|cx| {
+        let _ = cx.throw::<_, ()>(err);
+
+        checks.push(("get", object.get_value(cx, "a").is_err()));
+
+        let value = cx.number(1);
+        checks.push(("set", object.set(cx, "a", value).is_err()));
+        checks.push(("call", f.call_with(cx).exec(cx).is_err()));
+        checks.push((
+            "construct",
+            f.construct_with(cx).apply::<JsObject, _>(cx).is_err(),
+        ));
+        checks.push(("coerce", object.to_string(cx).is_err()));
+        checks.push(("buffer", JsBuffer::new(cx, 4).is_err()));
+
+        cx.throw_error("second")
+    });
+
+    pending_report(&mut cx, caught, checks)
+}
+
+// Throws `err` unless it is `undefined`, then calls `cleanup` with the exception
+// suppressed
+pub fn suppress_exception(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let err = cx.argument::<JsValue>(0)?;
+    let cleanup = cx.argument::<JsFunction>(1)?;
+
+    let result = if err.is_a::<JsUndefined, _>(&mut cx) {
+        Ok(())
+    } else {
+        cx.throw(err)
+    };
+
+    let cleaned = cx.with_suppressed_exception(|cx| cleanup.call_with(cx).exec(cx));
+
+    result?;
+    cleaned?;
+
+    Ok(cx.undefined())
+}
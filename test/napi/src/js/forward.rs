@@ -0,0 +1,106 @@
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+};
+
+use neon::{
+    event::{self, ForwardOptions, ListenerRegistry},
+    prelude::*,
+};
+
+// Items sent by the producer of `forward_back_pressure`
+static SENT: AtomicUsize = AtomicUsize::new(0);
+
+// Forwards the numbers `0..count` to `callback`, then calls `done` once the pump stopped
+// because the sender was closed
+pub fn forward_callback(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+    let done = cx.argument::<JsFunction>(2)?.root(&mut cx);
+    let channel = cx.channel();
+    let (tx, rx) = mpsc::channel();
+    let forwarder = event::forward(rx, channel.clone(), callback);
+
+    thread::spawn(move || {
+        for i in 0..count {
+            tx.send(i).unwrap();
+        }
+
+        drop(tx);
+        forwarder.join();
+
+        channel.send(move |mut cx| {
+            let this = cx.undefined();
+
+            done.into_inner(&mut cx).exec(&mut cx, this, [])
+        });
+    });
+
+    Ok(cx.undefined())
+}
+
+// Forwards the numbers `0..count` as `data` events to `listener`
+pub fn forward_event(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let listener = cx.argument::<JsFunction>(1)?;
+    let registry = ListenerRegistry::new();
+    let (tx, rx) = mpsc::sync_channel(4);
+
+    registry.on(&mut cx, "data", listener);
+    event::forward(rx, cx.channel(), (registry, "data"));
+
+    thread::spawn(move || {
+        for i in 0..count {
+            tx.send(i).unwrap();
+        }
+    });
+
+    Ok(cx.undefined())
+}
+
+// Forwards the numbers `0..count` to `callback` through a bounded tokio channel of
+// `capacity`, with at most `max_in_flight` items in flight, counting the items sent
+pub fn forward_back_pressure(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let capacity = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let max_in_flight = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+    let callback = cx.argument::<JsFunction>(3)?.root(&mut cx);
+    let (tx, rx) = tokio::sync::mpsc::channel(capacity);
+
+    SENT.store(0, Ordering::SeqCst);
+    event::forward_with(rx, cx.channel(), callback, ForwardOptions { max_in_flight });
+
+    thread::spawn(move || {
+        for i in 0..count {
+            tx.blocking_send(i).unwrap();
+            SENT.fetch_add(1, Ordering::SeqCst);
+        }
+    });
+
+    Ok(cx.undefined())
+}
+
+pub fn forward_sent(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(SENT.load(Ordering::SeqCst) as f64))
+}
+
+// Forwards numbers to `callback` until the pump stops and drops the receiver
+pub fn forward_endless(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+    let (tx, rx) = mpsc::sync_channel(16);
+
+    event::forward(rx, cx.channel(), callback);
+
+    thread::spawn(move || {
+        let mut i = 0u32;
+
+        while tx.send(i).is_ok() {
+            i = i.wrapping_add(1);
+        }
+    });
+
+    Ok(cx.undefined())
+}
@@ -1,4 +1,9 @@
-use neon::prelude::*;
+use neon::{
+    prelude::*,
+    types::extract::{Provided, Rest, TryIntoJs},
+};
+
+use crate::js::limits::allocated;
 
 fn add1(mut cx: FunctionContext) -> JsResult<JsNumber> {
     let x = cx.argument::<JsNumber>(0)?.value(&mut cx);
@@ -237,6 +242,76 @@ pub fn get_number_or_default(mut cx: FunctionContext) -> JsResult<JsNumber> {
     Ok(cx.number(n))
 }
 
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl<'cx> neon::types::extract::TryFromJs<'cx> for Point {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        let o = match v.downcast::<JsObject, _>(cx) {
+            Ok(o) => o,
+            Err(_) => return Ok(None),
+        };
+
+        let x = o.get_value(cx, "x")?;
+        let y = o.get_value(cx, "y")?;
+
+        match (f64::try_from_js(cx, x)?, f64::try_from_js(cx, y)?) {
+            (Some(x), Some(y)) => Ok(Some(Point { x, y })),
+            _ => Ok(None),
+        }
+    }
+}
+
+// Calls the callback and returns the distance of the `{ x, y }` point it returns from the origin
+pub fn call_and_extract_point(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let f: Handle<JsFunction> = cx.argument(0)?;
+    let Point { x, y } = f.call_with(&cx).apply_into(&mut cx)?;
+
+    Ok(cx.number(x.hypot(y)))
+}
+
+pub fn call_and_exec_checked(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let f: Handle<JsFunction> = cx.argument(0)?;
+
+    f.call_with(&cx).exec_checked(&mut cx)?;
+
+    Ok(cx.undefined())
+}
+
+pub fn call_with_optional_args(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let f: Handle<JsFunction> = cx.argument(0)?;
+    let nested: Option<Option<f64>> = Some(None);
+
+    f.call_with(&cx)
+        .arg_opt(&mut cx, Some(1.0))?
+        .arg_opt(&mut cx, None::<f64>)?
+        .arg_opt(&mut cx, nested)?
+        .arg_opt(&mut cx, Some(Some("nested")))?
+        .apply(&mut cx)
+}
+
+pub fn construct_with_optional_args(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let f: Handle<JsFunction> = cx.argument(0)?;
+
+    f.construct_with(&cx)
+        .arg_opt(&mut cx, None::<&str>)?
+        .arg_opt(&mut cx, Some("b"))?
+        .apply(&mut cx)
+}
+
+pub fn undefined_if_none(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx
+        .argument_opt(0)
+        .filter(|v| !v.is_a::<JsBoolean, _>(&mut cx));
+
+    Ok(Handle::undefined_if_none(&mut cx, v))
+}
+
 pub fn is_construct(mut cx: FunctionContext) -> JsResult<JsObject> {
     let this = cx.this::<JsObject>()?;
     let construct = matches!(cx.kind(), CallKind::Construct);
@@ -294,3 +369,208 @@ pub fn caller_with_drop_callback(mut cx: FunctionContext) -> JsResult<JsFunction
         callback.f.to_inner(&mut cx).call(&mut cx, this, args)
     })
 }
+
+// Wraps `target` with hooks that forward to the optional `before`, `after` and
+// `onThrow` functions of the `hooks` object
+pub fn wrap_with_hooks(mut cx: FunctionContext) -> JsResult<JsFunction> {
+    use neon::types::function::{Hooks, Invocation};
+
+    struct JsHooks {
+        before: Option<Root<JsFunction>>,
+        after: Option<Root<JsFunction>>,
+        on_throw: Option<Root<JsFunction>>,
+    }
+
+    impl Hooks for JsHooks {
+        fn before<'a>(
+            &self,
+            cx: &mut FunctionContext<'a>,
+            call: &Invocation<'a>,
+        ) -> NeonResult<Option<Handle<'a, JsValue>>> {
+            let before = match &self.before {
+                Some(before) => before.to_inner(cx),
+                None => return Ok(None),
+            };
+
+            let args = JsArray::new(cx, call.args().len() as u32);
+
+            for (i, arg) in call.args().iter().enumerate() {
+                args.set(cx, i as u32, *arg)?;
+            }
+
+            let is_construct = cx.boolean(matches!(call.kind(), CallKind::Construct));
+            let result = before.call(cx, call.this(), [args.upcast(), is_construct.upcast()])?;
+
+            if result.is_a::<JsUndefined, _>(cx) {
+                Ok(None)
+            } else {
+                Ok(Some(result))
+            }
+        }
+
+        fn after<'a>(
+            &self,
+            cx: &mut FunctionContext<'a>,
+            result: Handle<'a, JsValue>,
+        ) -> JsResult<'a, JsValue> {
+            match &self.after {
+                Some(after) => {
+                    let this = cx.undefined();
+
+                    after.to_inner(cx).call(cx, this, [result])
+                }
+                None => Ok(result),
+            }
+        }
+
+        fn on_throw<'a>(
+            &self,
+            cx: &mut FunctionContext<'a>,
+            error: Handle<'a, JsValue>,
+        ) -> NeonResult<()> {
+            if let Some(on_throw) = &self.on_throw {
+                let this = cx.undefined();
+
+                on_throw.to_inner(cx).call(cx, this, [error])?;
+            }
+
+            Ok(())
+        }
+    }
+
+    let target = cx.argument::<JsFunction>(0)?;
+    let hooks = cx.argument::<JsObject>(1)?;
+    let mut hook = |name: &str| -> NeonResult<Option<Root<JsFunction>>> {
+        let f = hooks.get_opt::<JsFunction, _, _>(&mut cx, name)?;
+
+        Ok(f.map(|f| f.root(&mut cx)))
+    };
+
+    let hooks = JsHooks {
+        before: hook("before")?,
+        after: hook("after")?,
+        on_throw: hook("onThrow")?,
+    };
+
+    JsFunction::wrap(&mut cx, target, hooks)
+}
+
+// Returns `arguments[i]` unchanged, where `i` is the first argument
+pub fn passthrough_nth(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let i = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+
+    cx.passthrough(i)
+}
+
+// Returns `value` after extracting `label` from the same call
+#[neon::function(rename_all = "camelCase")]
+pub fn passthrough_labeled(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let _label = cx.named_argument::<JsString>(0, "value_label")?;
+
+    cx.passthrough(1)
+}
+
+pub fn passthrough_this(mut cx: FunctionContext) -> JsResult<JsValue> {
+    cx.return_this()
+}
+
+// Converts `Missing` and `Undefined` to their names, and a value to itself
+fn provided_state<'a, T>(cx: &mut FunctionContext<'a>, v: Provided<T>) -> JsResult<'a, JsValue>
+where
+    T: TryIntoJs<'a>,
+{
+    match v {
+        Provided::Missing => Ok(cx.string("missing").upcast()),
+        Provided::Undefined => Ok(cx.string("undefined").upcast()),
+        Provided::Value(v) => Ok(v.try_into_js(cx)?.upcast()),
+    }
+}
+
+// Extracts `arguments[i]` as `Provided<String>`, where `i` is the first argument
+pub fn provided_nth(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let i = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+
+    match cx.extract_argument::<Provided<String>>(i)? {
+        Some(v) => provided_state(&mut cx, v),
+        None => cx.throw_type_error("expected a string"),
+    }
+}
+
+// Extracts `(first, ...rest)` as `Provided<f64>` and `Rest<Provided<f64>>`, returning
+// `[first, rest]`
+pub fn provided_rest(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let first = cx.extract_argument::<Provided<f64>>(0)?;
+    let rest = cx.extract_argument::<Rest<Provided<f64>>>(1)?;
+    let (first, Rest(rest)) = match first.zip(rest) {
+        Some(args) => args,
+        None => return cx.throw_type_error("expected numbers"),
+    };
+
+    let result = cx.empty_array();
+    let first = provided_state(&mut cx, first)?;
+    let rest_array = cx.empty_array();
+
+    for (i, v) in rest.into_iter().enumerate() {
+        let v = provided_state(&mut cx, v)?;
+
+        rest_array.set(&mut cx, i as u32, v)?;
+    }
+
+    result.set(&mut cx, 0, first)?;
+    result.set(&mut cx, 1, rest_array)?;
+
+    Ok(result)
+}
+
+// Calls `f` with the first `n` of `a`, `b` and `c` with `call1`, `call2` or `call3`
+pub fn call_n(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let f = cx.argument::<JsFunction>(0)?;
+    let n = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let this = cx.this_value();
+    let a = cx.argument::<JsValue>(2)?;
+    let b = cx.argument::<JsValue>(3)?;
+    let c = cx.argument::<JsValue>(4)?;
+
+    match n {
+        1 => f.call1(&mut cx, this, a),
+        2 => f.call2(&mut cx, this, a, b),
+        3 => f.call3(&mut cx, this, a, b, c),
+        _ => cx.throw_range_error("expected 1, 2 or 3 arguments"),
+    }
+}
+
+// Calls `f` with the numbers `0..count` as arguments with `call_with`
+pub fn call_with_count(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let f = cx.argument::<JsFunction>(0)?;
+    let count = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let mut call = f.call_with(&cx);
+
+    for i in 0..count {
+        call.arg(cx.number(i));
+    }
+
+    call.apply(&mut cx)
+}
+
+// Calls `f` with `(1, 2)` `n` times in the way named by `how`, returning the number of
+// bytes allocated by Rust
+pub fn call_repeat(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let f = cx.argument::<JsFunction>(0)?;
+    let n = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let how = cx.argument::<JsString>(2)?.value(&mut cx);
+    let this = cx.undefined();
+    let a = cx.number(1);
+    let b = cx.number(2);
+    let before = allocated();
+
+    for _ in 0..n {
+        match how.as_str() {
+            "call_with" => f.call_with(&cx).arg(a).arg(b).exec(&mut cx)?,
+            "call2" => f.call2(&mut cx, this, a, b).map(drop)?,
+            // Allocates the arguments on the heap for each call
+            _ => f.exec(&mut cx, this, vec![a.upcast(), b.upcast()])?,
+        }
+    }
+
+    Ok(cx.number((allocated() - before) as f64))
+}
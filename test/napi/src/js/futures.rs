@@ -80,3 +80,57 @@ pub fn lazy_async_sum(mut cx: FunctionContext) -> JsResult<JsPromise> {
 
     Ok(promise)
 }
+
+// Accepts a `Promise<number>` and resolves with double the number.
+// Purpose: Test `JsPromise::to_future` with settled and pending promises.
+pub fn future_double(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let promise = cx.argument::<JsPromise>(0)?;
+    let n = promise.to_future(&mut cx, |mut cx, n| {
+        let n = n
+            .or_throw(&mut cx)?
+            .downcast_or_throw::<JsNumber, _>(&mut cx)?
+            .value(&mut cx);
+
+        Ok(n)
+    })?;
+
+    let (deferred, promise) = cx.promise();
+    let channel = cx.channel();
+    let runtime = runtime(&mut cx)?;
+
+    runtime.spawn(async move {
+        let result = n.await.map(|n| n * 2.0);
+
+        deferred.settle_with(&channel, move |mut cx| {
+            let result = result.or_throw(&mut cx)?;
+
+            Ok(cx.number(result))
+        });
+    });
+
+    Ok(promise)
+}
+
+// Calls `f` with a oneshot callback and resolves with the number sent to it
+// Purpose: Test the `Future` implementation on `JsOneshotReceiver`
+pub fn oneshot_number_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let f = cx.argument::<JsFunction>(0)?;
+    let (tx, rx) = neon::event::JsOneshot::<f64>::channel();
+    let callback = tx.to_js_function(&mut cx)?;
+    let channel = cx.channel();
+    let runtime = runtime(&mut cx)?;
+    let (deferred, promise) = cx.promise();
+
+    runtime.spawn(async move {
+        let result = rx.await;
+
+        deferred.settle_with(&channel, move |mut cx| match result {
+            Ok(n) => Ok(cx.number(n)),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    });
+
+    f.call_with(&cx).arg(callback).exec(&mut cx)?;
+
+    Ok(promise)
+}
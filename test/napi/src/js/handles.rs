@@ -0,0 +1,42 @@
+use std::{cell::Cell, mem};
+
+use neon::prelude::*;
+
+thread_local! {
+    static ESCAPED: Cell<Option<Handle<'static, JsValue>>> = const { Cell::new(None) };
+}
+
+// Stores a handle past the end of its context. Using it is only safe with the
+// `debug-handles` feature, which catches the use instead of reading a dead handle.
+pub fn handles_escape(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let n = cx.number(42).upcast::<JsValue>();
+    let escaped = unsafe { mem::transmute::<Handle<JsValue>, Handle<'static, JsValue>>(n) };
+
+    ESCAPED.with(|handle| handle.set(Some(escaped)));
+
+    Ok(cx.undefined())
+}
+
+pub fn handles_use_escaped(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let escaped = ESCAPED
+        .with(|handle| handle.take())
+        .expect("no escaped handle");
+    let is_number = escaped.is_a::<JsNumber, _>(&mut cx);
+
+    Ok(cx.boolean(is_number))
+}
+
+// Uses handles from an outer context in nested scopes, which is always valid
+pub fn handles_use_outer(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let a = cx.argument::<JsNumber>(0)?;
+    let b = cx.compute_scoped(|mut cx| {
+        let a = a.value(&mut cx);
+        let b = cx.number(a * 2.0);
+        let b = b.value(&mut cx);
+
+        Ok(cx.number(b + 1.0))
+    })?;
+    let sum = cx.execute_scoped(|mut cx| a.value(&mut cx) + b.value(&mut cx));
+
+    Ok(cx.number(sum))
+}
@@ -0,0 +1,66 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use neon::{
+    hash::{hash_value_with, values_hash_equal_with, Options},
+    prelude::*,
+};
+
+use crate::js::limits::allocated;
+
+fn options(cx: &mut FunctionContext, i: usize) -> NeonResult<Options> {
+    let identity = match cx.argument_opt(i) {
+        Some(v) => v.downcast_or_throw::<JsBoolean, _>(cx)?.value(cx),
+        None => false,
+    };
+
+    Ok(Options { identity })
+}
+
+// Hashes the elements of an array as a tuple and returns the hash in hex
+pub fn hash_values(mut cx: FunctionContext) -> JsResult<JsString> {
+    let values = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+    let options = options(&mut cx, 1)?;
+    let mut hasher = DefaultHasher::new();
+
+    for value in values {
+        hash_value_with(&mut cx, value, &mut hasher, options)?;
+    }
+
+    Ok(cx.string(format!("{:016x}", hasher.finish())))
+}
+
+pub fn hash_equal(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let a = cx.argument::<JsValue>(0)?;
+    let b = cx.argument::<JsValue>(1)?;
+    let options = options(&mut cx, 2)?;
+    let equal = values_hash_equal_with(&mut cx, a, b, options)?;
+
+    Ok(cx.boolean(equal))
+}
+
+// Returns the number of bytes allocated by Rust to hash a string directly and by
+// converting it to a Rust `String`, as `{ direct, converted }`
+pub fn hash_allocations(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let s = cx.argument::<JsString>(0)?;
+    let mut hasher = DefaultHasher::new();
+
+    let before = allocated();
+    hash_value_with(&mut cx, s, &mut hasher, Options::default())?;
+    let direct = allocated() - before;
+
+    let before = allocated();
+    s.value(&mut cx).hash(&mut hasher);
+    let converted = allocated() - before;
+
+    let result = cx.empty_object();
+    let direct = cx.number(direct as f64);
+    let converted = cx.number(converted as f64);
+
+    result.set(&mut cx, "direct", direct)?;
+    result.set(&mut cx, "converted", converted)?;
+
+    Ok(result)
+}
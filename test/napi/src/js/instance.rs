@@ -0,0 +1,62 @@
+use neon::{prelude::*, types::Instance};
+
+pub struct Database {
+    name: String,
+}
+
+impl Finalize for Database {}
+
+// Called as `new Database(name)`
+pub fn database_new(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let this = cx.this::<JsObject>()?;
+
+    Instance::wrap(&mut cx, this, Database { name })?;
+
+    Ok(cx.undefined())
+}
+
+pub fn database_create(mut cx: FunctionContext) -> JsResult<Instance<Database>> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    Instance::new(&mut cx, Database { name })
+}
+
+pub fn database_query(mut cx: FunctionContext) -> JsResult<JsString> {
+    let db = cx.argument::<Instance<Database>>(0)?;
+    let sql = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    Ok(cx.string(format!("{}: {}", db.name, sql)))
+}
+
+pub fn database_name(mut cx: FunctionContext) -> JsResult<JsString> {
+    let v = cx.argument::<JsValue>(0)?;
+    let db = Instance::<Database>::extract(&mut cx, v)?;
+
+    Ok(cx.string(&db.name))
+}
+
+// Returns the same instance with its Rust type
+pub fn database_identity(mut cx: FunctionContext) -> JsResult<Instance<Database>> {
+    cx.argument::<Instance<Database>>(0)
+}
+
+// Roots the instance, sends it to another thread and back, and calls `f` with it
+pub fn database_root_roundtrip(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let db = cx.argument::<Instance<Database>>(0)?.root(&mut cx);
+    let f = cx.argument::<JsFunction>(1)?.root(&mut cx);
+    let channel = cx.channel();
+
+    std::thread::spawn(move || {
+        channel.send(move |mut cx| {
+            let db = db.into_inner(&mut cx);
+            let f = f.into_inner(&mut cx);
+            let this = cx.undefined();
+            let name = cx.string(&db.name).upcast::<JsValue>();
+
+            f.exec(&mut cx, this, [db.upcast(), name])
+        });
+    });
+
+    Ok(cx.undefined())
+}
@@ -0,0 +1,98 @@
+use std::sync::Mutex;
+
+use neon::{
+    instance::{self, InstanceId},
+    prelude::*,
+    thread::LocalKey,
+    types::extract::TryIntoJs,
+};
+
+// The ids returned to JavaScript by each instance, to look them up by number after
+// the instance exits
+static IDS: Mutex<Vec<InstanceId>> = Mutex::new(Vec::new());
+
+// The last message received by each instance
+static MESSAGE: LocalKey<Mutex<Option<String>>> = LocalKey::new();
+
+// Stores the message in the receiving instance and calls the global
+// `onInstanceMessage`, if any
+fn receive(mut cx: TaskContext, message: &str) -> NeonResult<()> {
+    *MESSAGE.get_or_init_default(&mut cx).lock().unwrap() = Some(message.to_string());
+
+    let callback = cx.global().get_value(&mut cx, "onInstanceMessage")?;
+
+    if let Ok(callback) = callback.downcast::<JsFunction, _>(&mut cx) {
+        callback
+            .call_with(&cx)
+            .arg(cx.string(message))
+            .exec(&mut cx)?;
+    }
+
+    Ok(())
+}
+
+fn lookup<'cx>(cx: &mut FunctionContext<'cx>, id: f64) -> NeonResult<InstanceId> {
+    let ids = IDS.lock().unwrap();
+
+    match ids.iter().find(|known| known.as_u64() as f64 == id) {
+        Some(&id) => Ok(id),
+        None => cx.throw_error(format!("unknown instance {}", id)),
+    }
+}
+
+pub fn instance_id(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let id = instance::id(&mut cx);
+
+    IDS.lock().unwrap().push(id);
+
+    Ok(cx.number(id.as_u64() as f64))
+}
+
+pub fn instance_ids(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let ids = instance::registry()
+        .ids()
+        .into_iter()
+        .map(|id| id.as_u64() as f64)
+        .collect::<Vec<_>>();
+
+    ids.try_into_js(&mut cx)
+}
+
+pub fn instance_set_listed(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let listed = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+
+    instance::set_listed(&mut cx, listed);
+
+    Ok(cx.undefined())
+}
+
+// Returns the number of instances the message was sent to
+pub fn instance_broadcast(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let message = cx.argument::<JsString>(0)?.value(&mut cx);
+    let handles = instance::registry().broadcast(move |cx| receive(cx, &message));
+
+    Ok(cx.number(handles.len() as f64))
+}
+
+pub fn instance_send_to(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let id = lookup(&mut cx, id)?;
+    let message = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    if let Err(err) = instance::registry().send_to(id, move |cx| receive(cx, &message)) {
+        return cx.throw_error(err.to_string());
+    }
+
+    Ok(cx.undefined())
+}
+
+pub fn instance_message(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let message = MESSAGE
+        .get(&mut cx)
+        .and_then(|message| message.lock().unwrap().clone());
+
+    match message {
+        Some(message) => Ok(cx.string(message).upcast()),
+        None => Ok(cx.null().upcast()),
+    }
+}
@@ -0,0 +1,46 @@
+use std::{ffi::c_void, ptr};
+
+use neon::{prelude::*, sys_interop::RawValue};
+
+type CreateUint32 = unsafe extern "C" fn(*mut c_void, u32, *mut *mut c_void) -> i32;
+
+// Loads a Node-API function from the host process, like Neon does
+fn create_uint32() -> libloading::Symbol<'static, CreateUint32> {
+    static HOST: once_cell::sync::Lazy<libloading::Library> = once_cell::sync::Lazy::new(|| {
+        #[cfg(not(windows))]
+        let host = libloading::os::unix::Library::this().into();
+        #[cfg(windows)]
+        let host = libloading::os::windows::Library::this().unwrap().into();
+
+        host
+    });
+
+    unsafe { HOST.get(b"napi_create_uint32").unwrap() }
+}
+
+pub fn interop_round_trip(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+    let raw = v.raw_value();
+
+    Ok(unsafe { Handle::from_raw(&mut cx, raw) })
+}
+
+pub fn interop_string_from_raw(mut cx: FunctionContext) -> JsResult<JsString> {
+    let raw = cx.argument::<JsValue>(0)?.raw_value();
+    let v = unsafe { Handle::from_raw(&mut cx, raw) };
+
+    v.downcast_or_throw(&mut cx)
+}
+
+pub fn interop_create_uint32(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let env = cx.raw_env();
+    let mut out = ptr::null_mut();
+    let status = unsafe { create_uint32()(env.as_ptr(), n, &mut out) };
+
+    assert_eq!(status, 0);
+
+    let v = unsafe { Handle::from_raw(&mut cx, RawValue::from_ptr(out)) };
+
+    v.downcast_or_throw(&mut cx)
+}
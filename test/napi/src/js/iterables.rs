@@ -0,0 +1,88 @@
+use std::ops::ControlFlow;
+
+use neon::{prelude::*, types::JsIterable};
+
+pub fn iterable_sum(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let v = cx.argument::<JsValue>(0)?;
+    let numbers: Vec<f64> = JsIterable::from(&mut cx, v)?.collect_vec(&mut cx)?;
+
+    Ok(cx.number(numbers.iter().sum::<f64>()))
+}
+
+// Returns `[sum]` on success or `[undefined, error]` if iteration throws. Continues
+// using the context after catching to verify no exception is left pending.
+pub fn iterable_try_sum(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+    let result = cx.try_catch(|cx| {
+        let numbers: Vec<f64> = JsIterable::from(cx, v)?.collect_vec(cx)?;
+
+        Ok(numbers.iter().sum::<f64>())
+    });
+
+    let arr = cx.empty_array();
+
+    match result {
+        Ok(sum) => {
+            let sum = cx.number(sum);
+
+            arr.set(&mut cx, 0, sum)?;
+        }
+        Err(err) => {
+            let undefined = cx.undefined();
+
+            arr.set(&mut cx, 0, undefined)?;
+            arr.set(&mut cx, 1, err)?;
+        }
+    }
+
+    Ok(arr)
+}
+
+pub fn iterable_size_hint(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match JsIterable::from(&mut cx, v)?.size_hint(&mut cx)? {
+        Some(n) => Ok(cx.number(n as f64).upcast()),
+        None => Ok(cx.null().upcast()),
+    }
+}
+
+// Returns the first `n` items
+pub fn iterable_take(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+    let n = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let arr = cx.empty_array();
+    let mut i = 0;
+
+    JsIterable::from(&mut cx, v)?.try_for_each(&mut cx, |cx, item| {
+        if i >= n {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        arr.set(cx, i, item)?;
+        i += 1;
+
+        // Stop without reading another item
+        if i >= n {
+            return Ok(ControlFlow::Break(()));
+        }
+
+        Ok(ControlFlow::Continue(()))
+    })?;
+
+    Ok(arr)
+}
+
+// Calls `callback` with each item
+pub fn iterable_for_each(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let v = cx.argument::<JsValue>(0)?;
+    let callback = cx.argument::<JsFunction>(1)?;
+
+    JsIterable::from(&mut cx, v)?.try_for_each(&mut cx, |cx, item| {
+        callback.call_with(cx).arg(item).exec(cx)?;
+
+        Ok(ControlFlow::Continue(()))
+    })?;
+
+    Ok(cx.undefined())
+}
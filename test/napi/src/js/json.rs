@@ -0,0 +1,88 @@
+use std::fmt::Write;
+
+use neon::{prelude::*, types::json};
+
+// Streams `[{"id":0,"name":"item 0","tags":["a","b"]},...]` with `count` records to `sink`
+// without ever holding the complete text
+pub fn json_stringify_records(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let chunk_size = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let sink = cx.argument::<JsFunction>(2)?;
+
+    json::stringify_stream(
+        &mut cx,
+        |w| {
+            w.write_char('[')?;
+
+            for i in 0..count {
+                if i > 0 {
+                    w.write_char(',')?;
+                }
+
+                write!(w, "{{\"id\":{},\"name\":", i)?;
+                w.write_json_string(&format!("item {}", i))?;
+                w.write_str(",\"tags\":[\"a\",\"b\"]}")?;
+            }
+
+            w.write_char(']')
+        },
+        chunk_size,
+        sink,
+    )?;
+
+    Ok(cx.undefined())
+}
+
+pub fn json_stringify_string(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let s = cx.argument::<JsString>(0)?.value(&mut cx);
+    let chunk_size = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let sink = cx.argument::<JsFunction>(2)?;
+
+    json::stringify_stream(&mut cx, |w| w.write_json_string(&s), chunk_size, sink)?;
+
+    Ok(cx.undefined())
+}
+
+// Writes an array of numbers, which may include `NaN` and `±Infinity`
+pub fn json_stringify_numbers(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let numbers = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+    let sink = cx.argument::<JsFunction>(1)?;
+    let numbers = numbers
+        .into_iter()
+        .map(|n| Ok(n.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx)))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    json::stringify_stream(
+        &mut cx,
+        |w| {
+            w.write_char('[')?;
+
+            for (i, n) in numbers.iter().enumerate() {
+                if i > 0 {
+                    w.write_char(',')?;
+                }
+
+                w.write_json_number(*n)?;
+            }
+
+            w.write_char(']')
+        },
+        64,
+        sink,
+    )?;
+
+    Ok(cx.undefined())
+}
+
+pub fn json_parse_chunks(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let chunks = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+    let mut parser = json::JsonParser::new();
+
+    for chunk in chunks {
+        let chunk = chunk.downcast_or_throw::<JsString, _>(&mut cx)?;
+
+        parser.push_string(&mut cx, chunk)?;
+    }
+
+    parser.finish(&mut cx)
+}
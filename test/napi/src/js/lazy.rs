@@ -0,0 +1,118 @@
+use std::{
+    cell::Cell,
+    sync::atomic::{AtomicU32, Ordering},
+};
+
+use neon::prelude::*;
+
+// Number of functions in the synthetic namespaces used to compare eager and lazy
+// exports
+const SYNTHETIC_EXPORTS: usize = 300;
+
+static INITS: AtomicU32 = AtomicU32::new(0);
+static FAILING_INITS: AtomicU32 = AtomicU32::new(0);
+
+thread_local! {
+    // Number of synthetic functions created on this thread, counted per thread so
+    // that instances loading concurrently in workers are not counted
+    static SYNTHETIC_CREATED: Cell<usize> = const { Cell::new(0) };
+}
+
+fn lazy_add(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let a = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let b = cx.argument::<JsNumber>(1)?.value(&mut cx);
+
+    Ok(cx.number(a + b))
+}
+
+fn synthetic(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    Ok(cx.undefined())
+}
+
+fn synthetic_namespace<'a, C: Context<'a>>(cx: &mut C) -> JsResult<'a, JsObject> {
+    let namespace = cx.empty_object();
+
+    for i in 0..SYNTHETIC_EXPORTS {
+        let f = JsFunction::new(cx, synthetic)?;
+
+        SYNTHETIC_CREATED.with(|n| n.set(n.get() + 1));
+        namespace.set(cx, format!("f{}", i).as_str(), f)?;
+    }
+
+    Ok(namespace)
+}
+
+#[neon::export_config(rename_all = "camelCase")]
+pub mod api {
+    use neon::prelude::*;
+
+    #[neon::function(namespace = "lazyStrings", lazy)]
+    fn to_upper(mut cx: FunctionContext) -> JsResult<JsString> {
+        let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+        Ok(cx.string(s.to_uppercase()))
+    }
+
+    #[neon::function(namespace = "lazyStrings", lazy)]
+    fn to_lower(mut cx: FunctionContext) -> JsResult<JsString> {
+        let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+        Ok(cx.string(s.to_lowercase()))
+    }
+
+    #[neon::function(namespace = "eagerStrings")]
+    fn string_length(mut cx: FunctionContext) -> JsResult<JsNumber> {
+        let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+        Ok(cx.number(s.len() as f64))
+    }
+}
+
+// Exports `lazyMath`, `lazyFailing` and `lazyCreated`, the number of functions
+// created while exporting a synthetic namespace of 300 functions eagerly and lazily
+pub fn export(cx: &mut ModuleContext) -> NeonResult<()> {
+    cx.export_lazy("lazyMath", |cx| {
+        INITS.fetch_add(1, Ordering::Relaxed);
+
+        let math = cx.empty_object();
+        let add = JsFunction::new(cx, lazy_add)?;
+
+        math.set(cx, "add", add)?;
+
+        Ok(math)
+    })?;
+
+    // Throws the first time it is read
+    cx.export_lazy("lazyFailing", |cx| {
+        if FAILING_INITS.fetch_add(1, Ordering::Relaxed) == 0 {
+            return cx.throw_error("first initialization fails");
+        }
+
+        Ok(cx.empty_object())
+    })?;
+
+    let before = SYNTHETIC_CREATED.with(Cell::get);
+    let eager = synthetic_namespace(cx)?;
+
+    cx.export_value("syntheticEager", eager)?;
+
+    let eager = SYNTHETIC_CREATED.with(Cell::get) - before;
+    let before = SYNTHETIC_CREATED.with(Cell::get);
+
+    cx.export_lazy("syntheticLazy", |cx| synthetic_namespace(cx))?;
+
+    let lazy = SYNTHETIC_CREATED.with(Cell::get) - before;
+    let created = cx.empty_object();
+    let eager = cx.number(eager as f64);
+    let lazy = cx.number(lazy as f64);
+
+    created.set(cx, "eager", eager)?;
+    created.set(cx, "lazy", lazy)?;
+    cx.export_value("lazyCreated", created)?;
+
+    api::export(cx)
+}
+
+pub fn lazy_inits(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(INITS.load(Ordering::Relaxed)))
+}
@@ -0,0 +1,195 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use neon::{
+    limits::{self, measure, Quota},
+    prelude::*,
+    types::extract::{Limited, TryFromJs},
+};
+
+// Counts the bytes allocated by Rust on each thread. Frees are not subtracted, so
+// the count also shows temporary allocations of an operation. Only the thread of
+// the caller is counted, so the allocations of threads running concurrently, e.g.,
+// of libuv or tokio, are not attributed to it.
+struct CountingAllocator;
+
+thread_local! {
+    // Doesn't allocate, and has no destructor that could run after the thread
+    // stops accepting new thread-locals
+    static ALLOCATED: Cell<usize> = const { Cell::new(0) };
+}
+
+// Returns the total number of bytes allocated by Rust on the current thread
+pub(crate) fn allocated() -> usize {
+    ALLOCATED.try_with(Cell::get).unwrap_or(0)
+}
+
+fn count(size: usize) {
+    let _ = ALLOCATED.try_with(|n| n.set(n.get() + size));
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+// Reads a `Quota` from an object with optional properties
+fn quota(cx: &mut FunctionContext, i: usize) -> NeonResult<Quota> {
+    let o = cx.argument::<JsObject>(i)?;
+    let mut quota = Quota::default();
+
+    for (key, limit) in [
+        ("max_depth", &mut quota.max_depth),
+        ("max_items", &mut quota.max_items),
+        ("max_string_bytes", &mut quota.max_string_bytes),
+        ("max_buffer_bytes", &mut quota.max_buffer_bytes),
+    ] {
+        if let Some(n) = o.get_opt::<JsNumber, _, _>(cx, key)? {
+            *limit = n.value(cx) as usize;
+        }
+    }
+
+    Ok(quota)
+}
+
+// Total number of bytes allocated by Rust on the JavaScript thread
+pub fn limits_allocated(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(allocated() as f64))
+}
+
+pub fn limits_measure(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let v = cx.argument::<JsValue>(0)?;
+    let quota = quota(&mut cx, 1)?;
+    let usage = measure(&mut cx, v, quota)?;
+    let o = cx.empty_object();
+
+    for (key, n) in [
+        ("depth", usage.depth),
+        ("items", usage.items),
+        ("string_bytes", usage.string_bytes),
+        ("buffer_bytes", usage.buffer_bytes),
+    ] {
+        let n = cx.number(n as f64);
+
+        o.set(&mut cx, key, n)?;
+    }
+
+    Ok(o)
+}
+
+// Extracts an array of numbers, returning `{ sum, error, allocated }` where
+// `allocated` is the number of bytes allocated by Rust during extraction
+pub fn limits_extract_numbers(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let v = cx.argument::<JsValue>(0)?;
+    let quota = quota(&mut cx, 1)?;
+    let before = allocated();
+    let result = cx.try_catch(|cx| Limited::<Vec<f64>>::try_from_js_with(cx, v, quota));
+    let allocated = allocated() - before;
+    let o = cx.empty_object();
+
+    match result {
+        Ok(Some(Limited(numbers))) => {
+            let sum = cx.number(numbers.iter().sum::<f64>());
+
+            o.set(&mut cx, "sum", sum)?;
+        }
+        Ok(None) => return cx.throw_type_error("expected an array of numbers"),
+        Err(err) => {
+            o.set(&mut cx, "error", err)?;
+        }
+    }
+
+    let allocated = cx.number(allocated as f64);
+
+    o.set(&mut cx, "allocated", allocated)?;
+
+    Ok(o)
+}
+
+// A number, or an array of trees, extracted recursively
+enum Tree {
+    Leaf,
+    Node(Vec<Tree>),
+}
+
+impl<'cx> TryFromJs<'cx> for Tree {
+    fn try_from_js<C: Context<'cx>>(
+        cx: &mut C,
+        v: Handle<'cx, JsValue>,
+    ) -> NeonResult<Option<Self>> {
+        if v.is_a::<JsNumber, _>(cx) {
+            return Ok(Some(Tree::Leaf));
+        }
+
+        Ok(Vec::try_from_js(cx, v)?.map(Tree::Node))
+    }
+}
+
+impl Tree {
+    fn depth(&self) -> usize {
+        match self {
+            Tree::Leaf => 0,
+            Tree::Node(children) => 1 + children.iter().map(Tree::depth).max().unwrap_or(0),
+        }
+    }
+}
+
+// Extracts a `Tree`, within a quota if one is passed, returning its depth
+pub fn limits_tree_depth(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let v = cx.argument::<JsValue>(0)?;
+    let tree = if cx.len() > 1 {
+        let quota = quota(&mut cx, 1)?;
+
+        Limited::<Tree>::try_from_js_with(&mut cx, v, quota)?.map(|Limited(tree)| tree)
+    } else {
+        Tree::try_from_js(&mut cx, v)?
+    };
+
+    match tree {
+        Some(tree) => Ok(cx.number(tree.depth() as f64)),
+        None => cx.throw_type_error("expected a tree"),
+    }
+}
+
+// Extracts an array of strings within a quota, returning their total length
+pub fn limits_extract_strings(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let v = cx.argument::<JsValue>(0)?;
+    let quota = quota(&mut cx, 1)?;
+
+    match Limited::<Vec<String>>::try_from_js_with(&mut cx, v, quota)? {
+        Some(Limited(strings)) => {
+            Ok(cx.number(strings.iter().map(String::len).sum::<usize>() as f64))
+        }
+        None => cx.throw_type_error("expected an array of strings"),
+    }
+}
+
+pub fn limits_set_max_conversion_depth(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let max_depth = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+
+    limits::set_max_conversion_depth(&mut cx, max_depth);
+
+    Ok(cx.undefined())
+}
+
+pub fn limits_max_conversion_depth(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let max_depth = limits::max_conversion_depth(&mut cx);
+
+    Ok(cx.number(max_depth as f64))
+}
@@ -0,0 +1,72 @@
+use neon::{
+    event::{ListenerId, ListenerRegistry},
+    prelude::*,
+    thread::LocalKey,
+};
+
+static LISTENERS: LocalKey<ListenerRegistry> = LocalKey::new();
+
+fn listeners<'a, C: Context<'a>>(cx: &mut C) -> &'a ListenerRegistry {
+    LISTENERS.get_or_init_default(cx)
+}
+
+pub fn listeners_on(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let listener = cx.argument::<JsFunction>(1)?;
+    let id = listeners(&mut cx).on(&mut cx, &name, listener);
+
+    Ok(cx.number(id.as_u64() as f64))
+}
+
+pub fn listeners_off(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let id = cx.argument::<JsNumber>(0)?.value(&mut cx) as u64;
+    let removed = listeners(&mut cx).off(&mut cx, ListenerId::from_u64(id));
+
+    Ok(cx.boolean(removed))
+}
+
+// Emits the event named by the first argument with the remaining arguments
+pub fn listeners_emit(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let args = (1..cx.len())
+        .map(|i| cx.argument::<JsValue>(i))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    listeners(&mut cx).emit(&mut cx, &name, args)?;
+
+    Ok(cx.undefined())
+}
+
+pub fn listeners_emit_from_thread(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let values = cx
+        .argument::<JsArray>(1)?
+        .to_vec(&mut cx)?
+        .into_iter()
+        .map(|v| Ok(v.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx)))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    let registry = listeners(&mut cx).clone();
+    let channel = cx.channel();
+
+    std::thread::spawn(move || {
+        for value in values {
+            registry.emit_from(&channel, &name, value);
+        }
+    });
+
+    Ok(cx.undefined())
+}
+
+pub fn listeners_count(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let name = cx.argument::<JsString>(0)?.value(&mut cx);
+    let count = listeners(&mut cx).listener_count(&name);
+
+    Ok(cx.number(count as f64))
+}
+
+pub fn listeners_clear(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    listeners(&mut cx).clear(&mut cx);
+
+    Ok(cx.undefined())
+}
@@ -0,0 +1,54 @@
+use std::sync::Mutex;
+
+use neon::{memory, prelude::*};
+
+static ROOTS: Mutex<Vec<Root<JsObject>>> = Mutex::new(Vec::new());
+
+pub fn memory_stats(mut cx: FunctionContext) -> JsResult<JsObject> {
+    memory::stats(&mut cx)?.to_js(&mut cx)
+}
+
+pub fn memory_hold_roots(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let roots = (0..n)
+        .map(|_| cx.empty_object().root(&mut cx))
+        .collect::<Vec<_>>();
+
+    ROOTS.lock().unwrap().extend(roots);
+
+    Ok(cx.undefined())
+}
+
+// Releases the held roots with `Root::drop`, or by dropping them if `queued` is true
+pub fn memory_release_roots(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let queued = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let roots = std::mem::take(&mut *ROOTS.lock().unwrap());
+
+    for root in roots {
+        if queued {
+            drop(root);
+        } else {
+            root.drop(&mut cx);
+        }
+    }
+
+    Ok(cx.undefined())
+}
+
+pub fn memory_create_box(mut cx: FunctionContext) -> JsResult<JsBox<Vec<u8>>> {
+    Ok(cx.boxed(vec![0; 16]))
+}
+
+// Sends `n` closures and returns the stats while they are queued
+pub fn memory_queue_closures(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let channel = cx.channel();
+
+    for _ in 0..n {
+        let data = [0u8; 256];
+
+        channel.send(move |_| Ok(data.len()));
+    }
+
+    memory::stats(&mut cx)?.to_js(&mut cx)
+}
@@ -0,0 +1,30 @@
+#[neon::export_config(rename_all = "camelCase")]
+pub mod api {
+    use neon::prelude::*;
+
+    #[neon::function]
+    fn parse_url_fast(mut cx: FunctionContext) -> JsResult<JsString> {
+        cx.named_argument::<JsString>(0, "input_url")
+    }
+
+    #[neon::function(name = "getHTTPStatus")]
+    fn get_http_status(mut cx: FunctionContext) -> JsResult<JsNumber> {
+        Ok(cx.number(200))
+    }
+
+    #[neon::function(rename_all = "snake_case")]
+    fn keep_snake_case(mut cx: FunctionContext) -> JsResult<JsNumber> {
+        cx.named_argument::<JsNumber>(0, "max_size")
+    }
+
+    #[neon::function(non_reentrant)]
+    fn _with_options(mut cx: FunctionContext) -> JsResult<JsNumber> {
+        cx.named_argument::<JsNumber>(0, "retry_count")
+    }
+
+    // Not exported
+    #[allow(dead_code)]
+    fn not_exported(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+        Ok(cx.undefined())
+    }
+}
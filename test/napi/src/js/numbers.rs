@@ -1,4 +1,15 @@
-use neon::prelude::*;
+use std::time::Duration;
+
+use neon::{
+    prelude::*,
+    types::{
+        extract::{
+            self, AlwaysBigInt, ArrayOnly, BigIntOnly, ChunkedVec, Finite, FloatPolicy, Millis,
+            NanAsNull, Nanos, TryFromJs, TryIntoJs,
+        },
+        JsBigInt,
+    },
+};
 
 pub fn return_js_number(mut cx: FunctionContext) -> JsResult<JsNumber> {
     Ok(cx.number(9000_f64))
@@ -39,3 +50,266 @@ pub fn accept_and_return_negative_js_number(mut cx: FunctionContext) -> JsResult
     let number: Handle<JsNumber> = cx.argument(0)?;
     Ok(number)
 }
+
+pub fn extract_u64(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match u64::try_from_js(&mut cx, v)? {
+        Some(n) => n.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a number or BigInt"),
+    }
+}
+
+pub fn extract_i64(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match i64::try_from_js(&mut cx, v)? {
+        Some(n) => n.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a number or BigInt"),
+    }
+}
+
+pub fn extract_bigint_only_u64(mut cx: FunctionContext) -> JsResult<JsBigInt> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match BigIntOnly::<u64>::try_from_js(&mut cx, v)? {
+        Some(BigIntOnly(n)) => AlwaysBigInt(n).try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a BigInt"),
+    }
+}
+
+pub fn u64_to_js(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    match s.parse::<u64>() {
+        Ok(n) => n.try_into_js(&mut cx),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+pub fn i64_to_js(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let s = cx.argument::<JsString>(0)?.value(&mut cx);
+
+    match s.parse::<i64>() {
+        Ok(n) => n.try_into_js(&mut cx),
+        Err(err) => cx.throw_error(err.to_string()),
+    }
+}
+
+pub fn u64_to_js_always_bigint(mut cx: FunctionContext) -> JsResult<JsBigInt> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx);
+
+    AlwaysBigInt(n as u64).try_into_js(&mut cx)
+}
+
+pub fn extract_f64_vec(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Vec::<f64>::try_from_js(&mut cx, v)? {
+        Some(values) => values.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected an array of numbers or a typed array"),
+    }
+}
+
+pub fn extract_u64_vec(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Vec::<u64>::try_from_js(&mut cx, v)? {
+        Some(values) => values.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected an array of integers or a typed array"),
+    }
+}
+
+// Resolves with the elements of an array, extracted a chunk at a time
+pub fn extract_f64_vec_chunked(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let array = cx.argument::<JsArray>(0)?;
+    let chunk_size = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+
+    ChunkedVec::<f64>::from_array(&mut cx, array, chunk_size, |mut cx, values| {
+        values.try_into_js(&mut cx)
+    })
+}
+
+pub fn extract_array_only_f64(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match ArrayOnly::<f64>::try_from_js(&mut cx, v)? {
+        Some(ArrayOnly(values)) => values.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected an array of numbers"),
+    }
+}
+
+pub fn sum_f64_vec(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Vec::<f64>::try_from_js(&mut cx, v)? {
+        Some(values) => Ok(cx.number(values.iter().sum::<f64>())),
+        None => cx.throw_type_error("expected an array of numbers or a typed array"),
+    }
+}
+
+// Extracts a `Duration` from any representation and converts it to the representation
+// named by the second argument
+pub fn duration_convert(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+    let to = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    let d = match Duration::try_from_js(&mut cx, v)? {
+        Some(d) => d,
+        None => return cx.throw_type_error("expected a duration"),
+    };
+
+    match to.as_str() {
+        "millis" => Ok(Millis(d).try_into_js(&mut cx)?.upcast()),
+        "nanos" => Ok(Nanos(d).try_into_js(&mut cx)?.upcast()),
+        _ => Ok(d.try_into_js(&mut cx)?.upcast()),
+    }
+}
+
+// Extracts a `Duration` only from a number of milliseconds
+pub fn duration_from_millis(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Millis::try_from_js(&mut cx, v)? {
+        Some(d) => d.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a number"),
+    }
+}
+
+// Extracts a `Duration` only from a BigInt number of nanoseconds
+pub fn duration_from_nanos(mut cx: FunctionContext) -> JsResult<JsBigInt> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match Nanos::try_from_js(&mut cx, v)? {
+        Some(d) => d.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a BigInt"),
+    }
+}
+
+pub fn duration_hrtime(mut cx: FunctionContext) -> JsResult<JsBigInt> {
+    let time = cx.hrtime()?;
+
+    Nanos(time).try_into_js(&mut cx)
+}
+
+// Converts a number with a float wrapper, or with the plain `f64` or `f32` impls. The
+// wrappers extract with `from` and convert back with `to`.
+pub fn float_convert(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+    let from = cx.argument::<JsString>(1)?.value(&mut cx);
+    let to = cx.argument::<JsString>(2)?.value(&mut cx);
+
+    let n = match from.as_str() {
+        "finite" => Finite::<f64>::try_from_js(&mut cx, v)?.map(|Finite(n)| n),
+        "finite32" => Finite::<f32>::try_from_js(&mut cx, v)?.map(|Finite(n)| n.into()),
+        "nan_as_null" => NanAsNull::<f64>::try_from_js(&mut cx, v)?.map(|NanAsNull(n)| n),
+        "vec" => Vec::<f64>::try_from_js(&mut cx, v)?.map(|v| v.iter().sum()),
+        _ => f64::try_from_js(&mut cx, v)?,
+    };
+
+    let n = match n {
+        Some(n) => n,
+        None => return cx.throw_type_error("expected a number"),
+    };
+
+    match to.as_str() {
+        "finite" => Ok(Finite(n).try_into_js(&mut cx)?.upcast()),
+        "nan_as_null" => NanAsNull(n).try_into_js(&mut cx),
+        "f32" => Ok((n as f32).try_into_js(&mut cx)?.upcast()),
+        _ => Ok(n.try_into_js(&mut cx)?.upcast()),
+    }
+}
+
+// Converts `NaN` with the plain `f64` impl, which does not require extracting it
+pub fn float_nan(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    f64::NAN.try_into_js(&mut cx)
+}
+
+pub fn float_set_policy(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let reject = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let policy = if reject {
+        FloatPolicy::Reject
+    } else {
+        FloatPolicy::Allow
+    };
+
+    extract::set_float_policy(&mut cx, policy);
+
+    Ok(cx.undefined())
+}
+
+// Creates each constant twice, including numbers next to the cached integers, and
+// returns the pairs
+pub fn return_constants(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let constants: [for<'cx> fn(&mut FunctionContext<'cx>) -> Handle<'cx, JsValue>; 4] = [
+        |cx| cx.boolean(true).upcast(),
+        |cx| cx.boolean(false).upcast(),
+        |cx| cx.undefined().upcast(),
+        |cx| cx.null().upcast(),
+    ];
+
+    let numbers = [-2.0, -1.0, 0.0, -0.0, 0.5, 256.0, 257.0, f64::NAN];
+    let array = cx.empty_array();
+
+    for (i, constant) in constants.iter().enumerate() {
+        let pair = [constant(&mut cx), constant(&mut cx)];
+        let pair = pair.to_vec().try_into_js(&mut cx)?;
+
+        array.set(&mut cx, i as u32, pair)?;
+    }
+
+    for (i, &n) in numbers.iter().enumerate() {
+        let pair = [cx.number(n), cx.number(n)];
+        let pair = pair.to_vec().try_into_js(&mut cx)?;
+
+        array.set(&mut cx, (constants.len() + i) as u32, pair)?;
+    }
+
+    Ok(array)
+}
+
+// Creates the same constants in the function scope and in nested scopes, returning
+// whether each is strictly equal to the value of the outer scope
+pub fn constants_in_scopes(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let outer = cx.number(7);
+    let inner = cx.execute_scoped(|mut cx| {
+        let inner = cx.number(7);
+        let nested = cx.execute_scoped(|mut cx| {
+            let nested = cx.number(7);
+            nested.strict_equals(&mut cx, outer) && nested.value(&mut cx) == 7.0
+        });
+
+        inner.strict_equals(&mut cx, outer) && nested
+    });
+
+    let escaped = cx.compute_scoped(|mut cx| Ok(cx.number(7)))?;
+    let truthy = cx.compute_scoped(|mut cx| Ok(cx.boolean(true)))?;
+
+    // Created for the first time in the outer scope, after the nested scopes closed
+    let after = cx.number(8);
+
+    // Replaced by the nested scopes, so created again in the outer scope
+    let again = cx.number(7);
+    let results = [
+        inner,
+        escaped.strict_equals(&mut cx, outer),
+        escaped.value(&mut cx) == 7.0,
+        truthy.value(&mut cx),
+        after.value(&mut cx) == 8.0,
+        again.value(&mut cx) == 7.0,
+    ];
+
+    results.to_vec().try_into_js(&mut cx)
+}
+
+// Creates `n` numbers with the value `x`, for the benchmark of cached integers
+pub fn create_numbers(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let x = cx.argument::<JsNumber>(1)?.value(&mut cx);
+
+    for _ in 0..n {
+        cx.number(x);
+    }
+
+    Ok(cx.undefined())
+}
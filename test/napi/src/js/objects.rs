@@ -1,6 +1,19 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
-use neon::{prelude::*, types::buffer::TypedArray};
+use neon::{
+    object::{Key, KeyPolicy, ObjectBuilder, ObjectTemplate},
+    prelude::*,
+    thread::LocalKey,
+    types::{
+        buffer::TypedArray,
+        extract::{Dict, TryIntoJs},
+        SafeDict,
+    },
+};
 
 pub fn return_js_global_object(mut cx: FunctionContext) -> JsResult<JsObject> {
     Ok(cx.global())
@@ -95,3 +108,299 @@ pub fn call_symbol_method(mut cx: FunctionContext) -> JsResult<JsString> {
     let sym: Handle<JsValue> = cx.argument::<JsValue>(1)?;
     obj.call_method_with(&mut cx, sym)?.apply(&mut cx)
 }
+
+// Returns `fallback` if the optional chain short-circuits
+pub fn opt_get_or(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let value = cx.argument::<JsValue>(0)?;
+    let key = cx.argument::<JsString>(1)?.value(&mut cx);
+    let fallback = cx.argument::<JsValue>(2)?;
+
+    Ok(value.opt_get(&mut cx, key.as_str())?.unwrap_or(fallback))
+}
+
+pub fn opt_chain_or(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let value = cx.argument::<JsValue>(0)?;
+    let a = cx.argument::<JsString>(1)?.value(&mut cx);
+    let b = cx.argument::<JsString>(2)?.value(&mut cx);
+    let c = cx.argument::<JsString>(3)?.value(&mut cx);
+    let fallback = cx.argument::<JsValue>(4)?;
+
+    Ok(neon::opt_chain!(&mut cx, value, a.as_str(), b.as_str(), c.as_str())?.unwrap_or(fallback))
+}
+
+pub fn opt_call_method_or(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let value = cx.argument::<JsValue>(0)?;
+    let key = cx.argument::<JsString>(1)?.value(&mut cx);
+    let arg = cx.argument::<JsValue>(2)?;
+    let fallback = cx.argument::<JsValue>(3)?;
+
+    Ok(value
+        .opt_call_method(&mut cx, key.as_str(), [arg])?
+        .unwrap_or(fallback))
+}
+
+fn key_policy(cx: &mut FunctionContext, i: usize) -> NeonResult<KeyPolicy> {
+    match cx.argument::<JsString>(i)?.value(cx).as_str() {
+        "reject" => Ok(KeyPolicy::Reject),
+        "escape" => Ok(KeyPolicy::Escape),
+        "allow-own" => Ok(KeyPolicy::AllowOwn),
+        policy => cx.throw_error(format!("unknown policy {}", policy)),
+    }
+}
+
+fn entries(cx: &mut FunctionContext, i: usize) -> NeonResult<Vec<(String, String)>> {
+    let entries = cx.argument::<JsArray>(i)?.to_vec(cx)?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let entry = entry.downcast_or_throw::<JsArray, _>(cx)?;
+            let key = entry.get::<JsString, _, _>(cx, 0)?.value(cx);
+            let value = entry.get::<JsString, _, _>(cx, 1)?.value(cx);
+
+            Ok((key, value))
+        })
+        .collect()
+}
+
+pub fn set_safe(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let obj = cx.argument::<JsObject>(0)?;
+    let key = cx.argument::<JsString>(1)?.value(&mut cx);
+    let value = cx.argument::<JsValue>(2)?;
+    let policy = key_policy(&mut cx, 3)?;
+
+    obj.set_safe_with(&mut cx, &key, value, policy)?;
+
+    Ok(obj)
+}
+
+// Sets a property with a key of the type named by the second argument: `f64`, `index`
+// for `Key::Index` or `name` for `Key::Name`
+pub fn set_typed_key(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let obj = cx.argument::<JsObject>(0)?;
+    let kind = cx.argument::<JsString>(1)?.value(&mut cx);
+    let key = cx.argument::<JsValue>(2)?;
+    let value = cx.argument::<JsValue>(3)?;
+    let set = match kind.as_str() {
+        "f64" => {
+            let key = key
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.set(&mut cx, key, value)?
+        }
+        "index" => {
+            let key = key
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.set(&mut cx, Key::Index(key as u32), value)?
+        }
+        _ => {
+            let key = key
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.set(&mut cx, Key::Name(&key), value)?
+        }
+    };
+
+    Ok(cx.boolean(set))
+}
+
+// Gets a property with a key of the type named by the second argument, like
+// `set_typed_key`
+pub fn get_typed_key(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let obj = cx.argument::<JsObject>(0)?;
+    let kind = cx.argument::<JsString>(1)?.value(&mut cx);
+    let key = cx.argument::<JsValue>(2)?;
+
+    match kind.as_str() {
+        "f64" => {
+            let key = key
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.get_value(&mut cx, key)
+        }
+        "index" => {
+            let key = key
+                .downcast_or_throw::<JsNumber, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.get_value(&mut cx, Key::Index(key as u32))
+        }
+        _ => {
+            let key = key
+                .downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx);
+
+            obj.get_value(&mut cx, Key::Name(&key))
+        }
+    }
+}
+
+pub fn safe_dict_from_entries(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let entries = entries(&mut cx, 0)?;
+    let policy = key_policy(&mut cx, 1)?;
+    let dict = SafeDict::new(&mut cx)?.with_policy(policy);
+
+    for (key, value) in entries {
+        let value = cx.string(value);
+
+        dict.set(&mut cx, &key, value)?;
+    }
+
+    Ok(dict.object())
+}
+
+pub fn hash_map_to_js(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let map = entries(&mut cx, 0)?.into_iter().collect::<HashMap<_, _>>();
+
+    map.try_into_js(&mut cx)
+}
+
+pub fn hash_map_to_dict(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let map = entries(&mut cx, 0)?.into_iter().collect::<HashMap<_, _>>();
+
+    Dict(map).try_into_js(&mut cx)
+}
+
+pub fn build_optional_object(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let skip_none = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let mut builder = ObjectBuilder::new(&mut cx);
+
+    if skip_none {
+        builder = builder.skip_none();
+    }
+
+    let missing: Option<f64> = None;
+    let cleared: Option<Option<f64>> = Some(None);
+    let unset: Option<Option<f64>> = None;
+
+    Ok(builder
+        .prop("name", "neon")?
+        .prop("null", missing)?
+        .opt("missing", missing)?
+        .opt("present", Some(1.0))?
+        .opt("cleared", cleared)?
+        .opt("unset", unset)?
+        .opt("nested", Some(Some(2.0)))?
+        .build())
+}
+
+struct Leaf(bool);
+
+impl<'cx> TryIntoJs<'cx> for Leaf {
+    type Value = JsString;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, Self::Value> {
+        if self.0 {
+            return cx.throw_error("invalid leaf");
+        }
+
+        Ok(cx.string("leaf"))
+    }
+}
+
+pub fn convert_nested(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let fail_at = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let key = cx.argument::<JsString>(1)?.value(&mut cx);
+    let results = (0..16)
+        .map(|i| HashMap::from([(key.as_str(), Leaf(f64::from(i) == fail_at))]))
+        .collect::<Vec<_>>();
+
+    HashMap::from([("results", results)]).try_into_js(&mut cx)
+}
+
+const RESULT_KEYS: [&str; 8] = [
+    "id", "name", "score", "active", "rank", "parent", "label", "weight",
+];
+
+// Number of templates created by all instances
+static TEMPLATES_CREATED: AtomicUsize = AtomicUsize::new(0);
+
+static RESULT_TEMPLATE: LocalKey<ObjectTemplate> = LocalKey::new();
+
+fn result_fields<'cx>(cx: &mut FunctionContext<'cx>, i: u32) -> [Handle<'cx, JsValue>; 8] {
+    [
+        cx.number(i).upcast(),
+        cx.string(format!("item {}", i)).upcast(),
+        cx.number(f64::from(i) / 2.0).upcast(),
+        cx.boolean(i.is_multiple_of(2)).upcast(),
+        cx.number(i * 2).upcast(),
+        cx.null().upcast(),
+        cx.string("result").upcast(),
+        cx.number(f64::from(i) * 1.5).upcast(),
+    ]
+}
+
+// Creates `n` results with a template kept by the instance between calls
+pub fn template_results(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let template = RESULT_TEMPLATE.get_or_try_init(&mut cx, |cx| {
+        TEMPLATES_CREATED.fetch_add(1, Ordering::SeqCst);
+        ObjectTemplate::new(cx, &RESULT_KEYS)
+    })?;
+    let mut template = template.bind(&mut cx)?;
+    let results = JsArray::new(&mut cx, n);
+
+    for i in 0..n {
+        let fields = result_fields(&mut cx, i);
+        let result = template.instantiate(&mut cx, &fields)?;
+
+        results.set(&mut cx, i, result)?;
+    }
+
+    Ok(results)
+}
+
+// Creates `n` results by setting each field
+pub fn naive_results(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let results = JsArray::new(&mut cx, n);
+
+    for i in 0..n {
+        let fields = result_fields(&mut cx, i);
+        let result = cx.empty_object();
+
+        for (key, value) in RESULT_KEYS.iter().zip(fields) {
+            result.set(&mut cx, *key, value)?;
+        }
+
+        results.set(&mut cx, i, result)?;
+    }
+
+    Ok(results)
+}
+
+pub fn templates_created(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(TEMPLATES_CREATED.load(Ordering::SeqCst) as f64))
+}
+
+// Instantiates `{ a: 1, b: 2 }` with the prototype argument, or a `null` prototype
+pub fn template_with_prototype(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let prototype = cx.argument::<JsValue>(0)?;
+    let prototype = prototype.downcast::<JsObject, _>(&mut cx).ok();
+    let template = ObjectTemplate::new(&mut cx, &["a", "b"])?.with_prototype(&mut cx, prototype)?;
+    let a = cx.number(1).upcast();
+    let b = cx.number(2).upcast();
+    let object = template.instantiate(&mut cx, &[a, b])?;
+
+    template.drop(&mut cx);
+
+    Ok(object)
+}
+
+pub fn template_duplicate_keys(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    ObjectTemplate::new(&mut cx, &["a", "b", "a"])?;
+
+    Ok(cx.undefined())
+}
+
+pub fn template_wrong_count(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let template = ObjectTemplate::new(&mut cx, &["a", "b"])?;
+    let a = cx.number(1).upcast();
+
+    template.instantiate(&mut cx, &[a])
+}
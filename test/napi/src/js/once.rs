@@ -0,0 +1,60 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use neon::{once::Key, prelude::*};
+
+static LIBRARY_INITS: AtomicUsize = AtomicUsize::new(0);
+static DETACHABLE_DEINITS: AtomicUsize = AtomicUsize::new(0);
+
+struct Library;
+
+impl Library {
+    fn init() -> Self {
+        LIBRARY_INITS.fetch_add(1, Ordering::SeqCst);
+        Library
+    }
+}
+
+struct Detachable;
+
+impl Drop for Detachable {
+    fn drop(&mut self) {
+        DETACHABLE_DEINITS.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+// Returns the address of the shared `Library` so that instances may compare them
+pub fn once_library_ptr(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let library = neon::once::process_once(&mut cx, Key::of::<Library>(), Library::init)?;
+
+    Ok(cx.number(Arc::as_ptr(&library) as usize as f64))
+}
+
+pub fn once_library_inits(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(LIBRARY_INITS.load(Ordering::SeqCst) as f64))
+}
+
+pub fn once_attach_detachable(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    neon::once::process_once(&mut cx, "detachable", || Detachable)?;
+
+    Ok(cx.undefined())
+}
+
+pub fn once_detachable_deinits(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(DETACHABLE_DEINITS.load(Ordering::SeqCst) as f64))
+}
+
+pub fn once_panic(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    neon::once::process_once::<_, _, (), _>(&mut cx, "panic", || panic!("init failed"))?;
+
+    Ok(cx.undefined())
+}
+
+pub fn once_wrong_type(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    neon::once::process_once(&mut cx, "wrong_type", || 0u32)?;
+    neon::once::process_once(&mut cx, "wrong_type", String::new)?;
+
+    Ok(cx.undefined())
+}
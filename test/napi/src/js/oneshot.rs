@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+use neon::{event::JsOneshot, prelude::*};
+
+// Calls `f` with a oneshot callback and resolves with the number sent to it, received
+// on another thread
+pub fn oneshot_number(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let f = cx.argument::<JsFunction>(0)?;
+    let (tx, rx) = JsOneshot::<f64>::channel();
+    let callback = tx.to_js_function(&mut cx)?;
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    std::thread::spawn(move || {
+        let result = rx.recv_timeout(Duration::from_secs(5));
+
+        deferred.settle_with(&channel, move |mut cx| match result {
+            Ok(n) => Ok(cx.number(n)),
+            Err(err) => cx.throw_error(err.to_string()),
+        });
+    });
+
+    f.call_with(&cx).arg(callback).exec(&mut cx)?;
+
+    Ok(promise)
+}
+
+// Returns the error received after dropping the sender
+pub fn oneshot_dropped(mut cx: FunctionContext) -> JsResult<JsString> {
+    let (tx, rx) = JsOneshot::<f64>::channel();
+
+    drop(tx);
+
+    let err = rx.recv().unwrap_err();
+
+    Ok(cx.string(format!("{:?}", err)))
+}
+
+// Returns the error received when nothing is sent before the timeout
+pub fn oneshot_timeout(mut cx: FunctionContext) -> JsResult<JsString> {
+    let (_tx, rx) = JsOneshot::<f64>::channel();
+    let err = rx.recv_timeout(Duration::from_millis(10)).unwrap_err();
+
+    Ok(cx.string(format!("{:?}", err)))
+}
@@ -0,0 +1,116 @@
+use std::{thread, time::Duration};
+
+use neon::{operation::OperationId, prelude::*};
+
+fn operation_id(cx: &mut FunctionContext, i: usize) -> NeonResult<OperationId> {
+    let id = cx.argument::<JsNumber>(i)?.value(cx);
+
+    Ok(OperationId::new(id as u64))
+}
+
+fn current<'cx>(cx: &mut impl Context<'cx>) -> Handle<'cx, JsValue> {
+    match cx.operation_id() {
+        Some(id) => cx.string(id.to_string()).upcast(),
+        None => cx.null().upcast(),
+    }
+}
+
+pub fn operation_current(mut cx: FunctionContext) -> JsResult<JsValue> {
+    Ok(current(&mut cx))
+}
+
+// Returns the current id of the outer, the inner and again the outer operation
+pub fn operation_nested(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let outer = operation_id(&mut cx, 0)?;
+    let inner = operation_id(&mut cx, 1)?;
+
+    cx.with_operation_id(outer, |cx| {
+        let ids = cx.empty_array();
+        let before = current(cx);
+        let nested = cx.with_operation_id(inner, current);
+        let after = current(cx);
+
+        ids.set(cx, 0, before)?;
+        ids.set(cx, 1, nested)?;
+        ids.set(cx, 2, after)?;
+
+        Ok(ids)
+    })
+}
+
+pub fn operation_throw(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = operation_id(&mut cx, 0)?;
+
+    cx.with_operation_id(id, |cx| cx.throw_type_error("operation failed"))
+}
+
+pub fn operation_panic(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = operation_id(&mut cx, 0)?;
+
+    cx.with_operation_id(id, |_| panic!("operation panicked"))
+}
+
+// Rejects with an error created by the completion of a task that sleeps for `delay`
+pub fn operation_task_reject(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let id = operation_id(&mut cx, 0)?;
+    let delay = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+
+    Ok(cx.with_operation_id(id, |cx| {
+        cx.task(move || thread::sleep(Duration::from_millis(delay)))
+            .promise(|mut cx, _| -> JsResult<JsUndefined> { cx.throw_error("task failed") })
+    }))
+}
+
+pub fn operation_task_panic(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let id = operation_id(&mut cx, 0)?;
+
+    Ok(cx.with_operation_id(id, |cx| {
+        cx.task(|| ())
+            .promise(|_, _| -> JsResult<JsUndefined> { panic!("task panicked") })
+    }))
+}
+
+// Calls `callback` with an error and the current id from the completion of a task
+pub fn operation_task_and_then(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = operation_id(&mut cx, 0)?;
+    let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    cx.with_operation_id(id, |cx| {
+        cx.task(|| ()).and_then(move |mut cx, _| {
+            let callback = callback.into_inner(&mut cx);
+            let err = cx.error("completed")?.upcast::<JsValue>();
+            let current = current(&mut cx);
+
+            callback.call_with(&cx).args((err, current)).exec(&mut cx)
+        });
+    });
+
+    Ok(cx.undefined())
+}
+
+// Calls `callback` with an error created in a closure sent from another thread
+pub fn operation_channel(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = operation_id(&mut cx, 0)?;
+    let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+    let channel = cx.with_operation_id(id, |cx| cx.channel());
+
+    thread::spawn(move || {
+        channel.send(move |mut cx| {
+            let callback = callback.into_inner(&mut cx);
+            let err = cx.error("sent")?;
+
+            callback.call_with(&cx).arg(err).exec(&mut cx)
+        })
+    });
+
+    Ok(cx.undefined())
+}
+
+pub fn operation_channel_panic(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let id = operation_id(&mut cx, 0)?;
+    let channel = cx.with_operation_id(id, |cx| cx.channel());
+
+    thread::spawn(move || channel.send(move |_| -> NeonResult<()> { panic!("channel panicked") }));
+
+    Ok(cx.undefined())
+}
@@ -0,0 +1,63 @@
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::Duration,
+};
+
+use neon::{
+    patterns::{Accumulate, Accumulator},
+    prelude::*,
+};
+
+// The number of hashers that were dropped
+static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+// A 32-bit FNV-1a hash that sleeps while processing a chunk, to simulate expensive work
+struct Fnv {
+    hash: u32,
+    delay: Duration,
+}
+
+impl Accumulate for Fnv {
+    fn update(&mut self, chunk: &[u8]) {
+        thread::sleep(self.delay);
+
+        for &byte in chunk {
+            self.hash = (self.hash ^ byte as u32).wrapping_mul(0x0100_0193);
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        self.hash.to_be_bytes().to_vec()
+    }
+}
+
+impl Drop for Fnv {
+    fn drop(&mut self) {
+        DROPPED.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+pub fn patterns_new_hasher(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let threshold = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let delay = cx.argument_opt(1);
+    let delay = match delay {
+        Some(delay) => delay
+            .downcast_or_throw::<JsNumber, _>(&mut cx)?
+            .value(&mut cx),
+        None => 0.0,
+    };
+
+    let hasher = Fnv {
+        hash: 0x811c_9dc5,
+        delay: Duration::from_millis(delay as u64),
+    };
+
+    Accumulator::new(hasher)
+        .async_threshold(threshold as usize)
+        .build(&mut cx)
+}
+
+pub fn patterns_dropped_hashers(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(DROPPED.load(Ordering::SeqCst) as f64))
+}
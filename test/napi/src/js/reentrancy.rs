@@ -0,0 +1,34 @@
+use neon::prelude::*;
+
+fn call(cx: &mut FunctionContext) -> NeonResult<()> {
+    let callback = cx.argument::<JsFunction>(0)?;
+
+    callback.call_with(cx).exec(cx)
+}
+
+#[neon::function(non_reentrant, allow_nested("reentrant_peek"))]
+pub fn reentrant_for_each(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    call(&mut cx)?;
+
+    Ok(cx.undefined())
+}
+
+#[neon::function(non_reentrant)]
+pub fn reentrant_update(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    call(&mut cx)?;
+
+    Ok(cx.undefined())
+}
+
+#[neon::function(non_reentrant)]
+pub fn reentrant_peek(mut cx: FunctionContext) -> JsResult<JsString> {
+    Ok(cx.string("peek"))
+}
+
+pub fn reentrant_manual(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let _guard = cx.reentrancy_guard("reentrant_manual")?;
+
+    call(&mut cx)?;
+
+    Ok(cx.undefined())
+}
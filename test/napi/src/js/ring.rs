@@ -0,0 +1,88 @@
+use std::cell::RefCell;
+
+use neon::{
+    prelude::*,
+    ring::{Overflow, RingBuffer},
+};
+
+pub struct Writer(RefCell<Option<RingBuffer>>);
+
+impl Finalize for Writer {}
+
+type BoxedWriter = JsBox<Writer>;
+
+// `ring_create(recordSize, capacity, dropOldest, wakeup?)` returns the ring
+// object with the boxed writer stored as `writer`
+pub fn ring_create(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let record_size = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let capacity = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let drop_oldest = cx.argument::<JsBoolean>(2)?.value(&mut cx);
+    let wakeup = cx.argument_opt(3);
+    let (ring, mut writer) = RingBuffer::create(&mut cx, record_size, capacity)?;
+
+    if drop_oldest {
+        writer = writer.with_overflow(Overflow::DropOldest);
+    }
+
+    if let Some(wakeup) = wakeup {
+        let wakeup = wakeup.downcast_or_throw::<JsFunction, _>(&mut cx)?;
+
+        writer = writer.with_wakeup(&mut cx, wakeup);
+    }
+
+    let writer = cx.boxed(Writer(RefCell::new(Some(writer))));
+
+    ring.set(&mut cx, "writer", writer)?;
+
+    Ok(ring)
+}
+
+// Pushes a record from an array of bytes, returning `false` if the ring is full
+pub fn ring_push(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let writer = cx.argument::<BoxedWriter>(0)?;
+    let record = cx
+        .argument::<JsArray>(1)?
+        .to_vec(&mut cx)?
+        .into_iter()
+        .map(|v| Ok(v.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx) as u8))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    let mut writer = writer.0.borrow_mut();
+    let writer = match writer.as_mut() {
+        Some(writer) => writer,
+        None => return cx.throw_error("writer was moved to a thread"),
+    };
+
+    Ok(cx.boolean(writer.try_push(&record).is_ok()))
+}
+
+// Moves the writer to a thread that pushes `count` records of `[i, !i]` as
+// little-endian `u32`, retrying while the ring is full. Resolves when done.
+pub fn ring_produce_in_thread(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let writer = cx.argument::<BoxedWriter>(0)?;
+    let count = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let mut writer = match writer.0.borrow_mut().take() {
+        Some(writer) => writer,
+        None => return cx.throw_error("writer was moved to a thread"),
+    };
+
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    std::thread::spawn(move || {
+        for i in 0..count {
+            let mut record = [0; 8];
+
+            record[..4].copy_from_slice(&i.to_le_bytes());
+            record[4..].copy_from_slice(&(!i).to_le_bytes());
+
+            while writer.try_push(&record).is_err() {
+                std::thread::yield_now();
+            }
+        }
+
+        deferred.settle_with(&channel, |mut cx| Ok(cx.undefined()));
+    });
+
+    Ok(promise)
+}
@@ -0,0 +1,119 @@
+use std::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+use neon::{prelude::*, state::Watchable, types::extract::TryIntoJs};
+
+pub struct BoxedWatchable<T>(Watchable<T>);
+
+impl<T> Finalize for BoxedWatchable<T> {}
+
+// Number of `Counted` values alive in the process, in every instance
+static COUNTED: AtomicUsize = AtomicUsize::new(0);
+
+// A number that counts its copies, to check that a `Watchable` and its values are
+// dropped when an instance shuts down
+pub struct Counted(f64);
+
+impl From<f64> for Counted {
+    fn from(n: f64) -> Self {
+        COUNTED.fetch_add(1, Ordering::SeqCst);
+        Self(n)
+    }
+}
+
+impl Clone for Counted {
+    fn clone(&self) -> Self {
+        Self::from(self.0)
+    }
+}
+
+impl Drop for Counted {
+    fn drop(&mut self) {
+        COUNTED.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl<'cx> TryIntoJs<'cx> for Counted {
+    type Value = JsNumber;
+
+    fn try_into_js<C: Context<'cx>>(self, cx: &mut C) -> JsResult<'cx, JsNumber> {
+        Ok(cx.number(self.0))
+    }
+}
+
+pub fn counted_live(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(COUNTED.load(Ordering::SeqCst) as f64))
+}
+
+// Returns the exported observer with the boxed `Watchable` stored as `handle`
+pub fn watchable_new<T>(mut cx: FunctionContext) -> JsResult<JsObject>
+where
+    T: From<f64> + Clone + Send + 'static,
+    for<'cx> T: TryIntoJs<'cx>,
+{
+    let initial = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let coalesce = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    let watchable = Watchable::new(&mut cx, T::from(initial));
+
+    watchable.set_coalesce(coalesce);
+
+    let exported = watchable.export(&mut cx)?;
+    let handle = cx.boxed(BoxedWatchable(watchable));
+
+    exported.set(&mut cx, "handle", handle)?;
+
+    Ok(exported)
+}
+
+pub fn watchable_set_from_thread<T>(mut cx: FunctionContext) -> JsResult<JsUndefined>
+where
+    T: From<f64> + Clone + Send + 'static,
+    for<'cx> T: TryIntoJs<'cx>,
+{
+    let watchable = cx.argument::<JsBox<BoxedWatchable<T>>>(0)?.0.clone();
+    let values = cx
+        .argument::<JsArray>(1)?
+        .to_vec(&mut cx)?
+        .into_iter()
+        .map(|v| Ok(v.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx)))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    std::thread::spawn(move || {
+        for value in values {
+            watchable.set(T::from(value));
+        }
+    });
+
+    Ok(cx.undefined())
+}
+
+// Exports `liveReads`, which counts how many times it has been read, and
+// `liveStatus` and `subscribeLiveStatus` backed by a `Watchable`
+pub fn export_live(cx: &mut ModuleContext) -> NeonResult<()> {
+    let reads = AtomicU32::new(0);
+
+    cx.export_live("liveReads", move |mut cx| {
+        Ok(cx.number(reads.fetch_add(1, Ordering::Relaxed) + 1))
+    })?;
+
+    let status = Watchable::new(cx, 0.0);
+
+    status.set_coalesce(false);
+    cx.export_watchable("liveStatus", &status)?;
+    cx.export_function("live_status_set_from_thread", move |mut cx| {
+        let status = status.clone();
+        let values = cx
+            .argument::<JsArray>(0)?
+            .to_vec(&mut cx)?
+            .into_iter()
+            .map(|v| Ok(v.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx)))
+            .collect::<NeonResult<Vec<_>>>()?;
+
+        std::thread::spawn(move || {
+            for value in values {
+                status.set(value);
+            }
+        });
+
+        Ok(cx.undefined())
+    })
+}
@@ -1,4 +1,13 @@
-use neon::{prelude::*, reflect::eval};
+use neon::{
+    object::ObjectBuilder,
+    prelude::*,
+    reflect::eval,
+    types::{
+        buffer::TypedArray,
+        extract::{CachedString, StringCache, StringCacheStats, TryFromJs, TryIntoJs, WideString},
+        PathEncoding,
+    },
+};
 
 pub fn return_js_string(mut cx: FunctionContext) -> JsResult<JsString> {
     Ok(cx.string("hello node"))
@@ -8,3 +17,184 @@ pub fn run_string_as_script(mut cx: FunctionContext) -> JsResult<JsValue> {
     let string_script = cx.argument::<JsString>(0)?;
     eval(&mut cx, string_script)
 }
+
+pub struct TestStringCache(StringCache);
+
+impl Finalize for TestStringCache {}
+
+pub fn string_cache_new(mut cx: FunctionContext) -> JsResult<JsBox<TestStringCache>> {
+    let max_entries = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let cache = StringCache::new(&mut cx, max_entries)?;
+
+    Ok(cx.boxed(TestStringCache(cache)))
+}
+
+pub fn string_cache_to_rust(mut cx: FunctionContext) -> JsResult<JsString> {
+    let cache = cx.argument::<JsBox<TestStringCache>>(0)?;
+    let s = cx.argument::<JsString>(1)?;
+    let s = cache.0.to_rust(&mut cx, s);
+
+    Ok(cx.string(&*s))
+}
+
+pub fn string_cache_stats(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let cache = cx.argument::<JsBox<TestStringCache>>(0)?;
+    let StringCacheStats {
+        hits,
+        misses,
+        bypassed,
+        evictions,
+        entries,
+    } = cache.0.stats();
+
+    Ok(ObjectBuilder::new(&mut cx)
+        .prop("hits", hits as f64)?
+        .prop("misses", misses as f64)?
+        .prop("bypassed", bypassed as f64)?
+        .prop("evictions", evictions as f64)?
+        .prop("entries", entries as f64)?
+        .build())
+}
+
+pub fn string_cache_clear(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cache = cx.argument::<JsBox<TestStringCache>>(0)?;
+
+    cache.0.clear(&mut cx);
+
+    Ok(cx.undefined())
+}
+
+pub fn extract_cached_string(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match CachedString::try_from_js(&mut cx, v)? {
+        Some(CachedString(s)) => Ok(cx.string(&*s).upcast()),
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+fn path_encoding(cx: &mut FunctionContext, i: usize) -> NeonResult<PathEncoding> {
+    let percent = cx.argument::<JsBoolean>(i)?.value(cx);
+
+    Ok(if percent {
+        PathEncoding::Percent
+    } else {
+        PathEncoding::Lossy
+    })
+}
+
+pub fn string_from_utf8_lossy(mut cx: FunctionContext) -> JsResult<JsString> {
+    let bytes = cx.argument::<JsBuffer>(0)?;
+    let bytes = bytes.as_slice(&cx).to_vec();
+
+    Ok(cx.string_from_utf8_lossy(&bytes))
+}
+
+pub fn try_string_from_utf8(mut cx: FunctionContext) -> JsResult<JsString> {
+    let bytes = cx.argument::<JsBuffer>(0)?;
+    let bytes = bytes.as_slice(&cx).to_vec();
+
+    match cx.try_string_from_utf8(&bytes) {
+        Ok(s) => Ok(s),
+        Err(err) => {
+            let error = cx.error(err.to_string())?;
+            let offset = cx.number(err.offset() as f64);
+
+            error.set(&mut cx, "offset", offset)?;
+            cx.throw(error)
+        }
+    }
+}
+
+// Creates a string from a path with the bytes of a buffer
+#[cfg(unix)]
+pub fn string_from_path_bytes(mut cx: FunctionContext) -> JsResult<JsString> {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt, path::Path};
+
+    let bytes = cx.argument::<JsBuffer>(0)?;
+    let bytes = bytes.as_slice(&cx).to_vec();
+    let encoding = path_encoding(&mut cx, 1)?;
+
+    Ok(cx.string_from_path(Path::new(OsStr::from_bytes(&bytes)), encoding))
+}
+
+// Returns the bytes of the path of a string
+#[cfg(unix)]
+pub fn path_bytes_from_string(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let s = cx.argument::<JsString>(0)?;
+    let encoding = path_encoding(&mut cx, 1)?;
+    let path = s.to_path(&mut cx, encoding);
+
+    JsBuffer::from_slice(&mut cx, path.as_os_str().as_bytes())
+}
+
+// Returns the UTF-16 code units of a string as an array, with a nul terminator if `nul`
+pub fn string_to_wide(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let s = cx.argument::<JsString>(0)?;
+    let nul = cx.argument::<JsBoolean>(1)?.value(&mut cx);
+    let wide = if nul {
+        s.to_wide_nul(&mut cx)
+    } else {
+        s.to_wide(&mut cx)
+    };
+
+    wide.into_iter()
+        .map(f64::from)
+        .collect::<Vec<_>>()
+        .try_into_js(&mut cx)
+}
+
+// Creates a string from an array of UTF-16 code units
+pub fn string_from_wide(mut cx: FunctionContext) -> JsResult<JsString> {
+    let wide = cx
+        .argument::<JsArray>(0)?
+        .to_vec(&mut cx)?
+        .into_iter()
+        .map(|c| Ok(c.downcast_or_throw::<JsNumber, _>(&mut cx)?.value(&mut cx) as u16))
+        .collect::<NeonResult<Vec<_>>>()?;
+
+    Ok(cx.string_from_wide(&wide))
+}
+
+// Round-trips a string through `WideString`, reversing its code units, or returns
+// `undefined` if the argument is not a string
+pub fn string_wide_reverse(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let v = cx.argument::<JsValue>(0)?;
+
+    match WideString::try_from_js(&mut cx, v)? {
+        Some(WideString(mut wide)) => {
+            wide.reverse();
+            Ok(WideString(wide).try_into_js(&mut cx)?.upcast())
+        }
+        None => Ok(cx.undefined().upcast()),
+    }
+}
+
+// Copies the string's code units `iterations` times with `to_wide`, returning the
+// number of code units of the last copy
+pub fn string_to_wide_repeat(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let s = cx.argument::<JsString>(0)?;
+    let iterations = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let mut len = 0;
+
+    for _ in 0..iterations {
+        len = s.to_wide(&mut cx).len();
+    }
+
+    Ok(cx.number(len as f64))
+}
+
+// Like `string_to_wide_repeat`, converting to a `String` and then re-encoding as UTF-16
+pub fn string_to_wide_via_utf8_repeat(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let s = cx.argument::<JsString>(0)?;
+    let iterations = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let mut len = 0;
+
+    for _ in 0..iterations {
+        len = s.value(&mut cx).encode_utf16().collect::<Vec<_>>().len();
+    }
+
+    Ok(cx.number(len as f64))
+}
@@ -0,0 +1,269 @@
+use std::{
+    sync::{mpsc, Arc},
+    thread,
+    time::Duration,
+};
+
+use {
+    neon::prelude::*,
+    neon::sync::{InstanceLock, JsRwCell},
+    neon::thread::LocalKey,
+    once_cell::sync::OnceCell,
+    tokio::runtime::Runtime,
+};
+
+type BoxedCell = JsBox<Arc<JsRwCell<f64>>>;
+type BoxedLock = JsBox<Arc<InstanceLock<f64>>>;
+
+static INSTANCE_COUNTER: LocalKey<Arc<InstanceLock<f64>>> = LocalKey::new();
+
+fn runtime<'a, C: Context<'a>>(cx: &mut C) -> NeonResult<&'static Runtime> {
+    static RUNTIME: OnceCell<Runtime> = OnceCell::new();
+
+    RUNTIME
+        .get_or_try_init(Runtime::new)
+        .or_else(|err| cx.throw_error(err.to_string()))
+}
+
+pub fn rw_cell_new(mut cx: FunctionContext) -> JsResult<BoxedCell> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx);
+
+    Ok(cx.boxed(Arc::new(JsRwCell::new(n))))
+}
+
+pub fn rw_cell_read(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let cell = cx.argument::<BoxedCell>(0)?;
+    let n = *cell.read(&mut cx)?;
+
+    Ok(cx.number(n))
+}
+
+pub fn rw_cell_increment(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let cell = cx.argument::<BoxedCell>(0)?;
+    let mut n = cell.write(&mut cx)?;
+
+    *n += 1.0;
+
+    Ok(cx.number(*n))
+}
+
+// Acquires the write lock on another thread and holds it for `ms` milliseconds
+// before incrementing. Returns only after the lock has been acquired.
+pub fn rw_cell_increment_in_thread(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cell = Arc::clone(&**cx.argument::<BoxedCell>(0)?);
+    let ms = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let mut n = cell.blocking_write_off_thread();
+
+        tx.send(()).unwrap();
+        thread::sleep(Duration::from_millis(ms));
+        *n += 1.0;
+    });
+
+    rx.recv().unwrap();
+
+    Ok(cx.undefined())
+}
+
+pub fn rw_cell_read_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let cell = Arc::clone(&**cx.argument::<BoxedCell>(0)?);
+    let runtime = runtime(&mut cx)?;
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    runtime.spawn(async move {
+        let n = *cell.read_async().await;
+
+        deferred.settle_with(&channel, move |mut cx| Ok(cx.number(n)));
+    });
+
+    Ok(promise)
+}
+
+pub fn rw_cell_write_while_reading(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cell = cx.argument::<BoxedCell>(0)?;
+    let _guard = cell.read(&mut cx)?;
+
+    cell.write(&mut cx)?;
+
+    Ok(cx.undefined())
+}
+
+// Reads again while holding a read guard and a writer on another thread waits for
+// the lock
+pub fn rw_cell_read_while_writer_waits(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let cell = Arc::clone(&**cx.argument::<BoxedCell>(0)?);
+    let guard = cell.read(&mut cx)?;
+    let writer = {
+        let cell = Arc::clone(&cell);
+
+        thread::spawn(move || *cell.blocking_write_off_thread() += 1.0)
+    };
+
+    // Gives the writer time to queue on the lock
+    thread::sleep(Duration::from_millis(50));
+
+    let result = cell.read(&mut cx).map(|_| ());
+
+    drop(guard);
+    writer.join().unwrap();
+    result?;
+
+    Ok(cx.undefined())
+}
+
+pub fn instance_lock_new(mut cx: FunctionContext) -> JsResult<BoxedLock> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let grace = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let lock = InstanceLock::new(n).with_grace_period(Duration::from_millis(grace));
+
+    Ok(cx.boxed(Arc::new(lock)))
+}
+
+// Returns the counter stored in instance-local storage
+pub fn instance_lock_local(mut cx: FunctionContext) -> JsResult<BoxedLock> {
+    let lock = INSTANCE_COUNTER
+        .get_or_init(&mut cx, Default::default)
+        .clone();
+
+    Ok(cx.boxed(lock))
+}
+
+pub fn instance_lock_get(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let lock = cx.argument::<BoxedLock>(0)?;
+    let n = lock.with(&mut cx, |n| *n)?;
+
+    Ok(cx.number(n))
+}
+
+pub fn instance_lock_increment(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let lock = cx.argument::<BoxedLock>(0)?;
+    let n = lock.with(&mut cx, |n| {
+        *n += 1.0;
+        *n
+    })?;
+
+    Ok(cx.number(n))
+}
+
+// Acquires the lock on another thread and holds it for `ms` milliseconds
+// before incrementing. Returns only after the lock has been acquired.
+pub fn instance_lock_hold_in_thread(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let ms = cx.argument::<JsNumber>(1)?.value(&mut cx) as u64;
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        lock.with_blocking(|n| {
+            tx.send(()).unwrap();
+            thread::sleep(Duration::from_millis(ms));
+            *n += 1.0;
+        });
+    });
+
+    rx.recv().unwrap();
+
+    Ok(cx.undefined())
+}
+
+pub fn instance_lock_increment_async(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let runtime = runtime(&mut cx)?;
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    runtime.spawn(async move {
+        let n = lock
+            .with_async(|n| {
+                *n += 1.0;
+                *n
+            })
+            .await;
+
+        deferred.settle_with(&channel, move |mut cx| Ok(cx.number(n)));
+    });
+
+    Ok(promise)
+}
+
+// Returns `true` if the increment was applied immediately
+pub fn instance_lock_defer_increment(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let channel = cx.channel();
+    let immediate = lock.defer_to_js(&channel, |n| *n += 1.0);
+
+    Ok(cx.boolean(immediate))
+}
+
+// Increments the lock `count` times from each of `threads` threads. Even threads
+// block on the lock and odd threads defer to JavaScript when contended.
+pub fn instance_lock_increment_in_threads(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let threads = cx.argument::<JsNumber>(1)?.value(&mut cx) as usize;
+    let count = cx.argument::<JsNumber>(2)?.value(&mut cx) as usize;
+    let channel = cx.channel();
+    let (deferred, promise) = cx.promise();
+
+    let handles = (0..threads)
+        .map(|i| {
+            let lock = Arc::clone(&lock);
+            let channel = channel.clone();
+
+            thread::spawn(move || {
+                for _ in 0..count {
+                    if i % 2 == 0 {
+                        lock.with_blocking(|n| *n += 1.0);
+                    } else {
+                        lock.defer_to_js(&channel, |n| *n += 1.0);
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    thread::spawn(move || {
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        deferred.settle_with(&channel, |mut cx| Ok(cx.undefined()));
+    });
+
+    Ok(promise)
+}
+
+// Panics while holding the lock, after another thread deferred an increment that
+// waits for the lock to be released
+pub fn instance_lock_panic(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let channel = cx.channel();
+
+    lock.with(&mut cx, |_| {
+        let deferred = {
+            let lock = Arc::clone(&lock);
+
+            thread::spawn(move || lock.defer_to_js(&channel, |n| *n += 1.0))
+        };
+
+        assert!(!deferred.join().unwrap());
+
+        panic!("panicked while holding the lock");
+    })
+}
+
+// Acquires the lock again on a thread that holds it, returning the panic message
+pub fn instance_lock_reenter_blocking(mut cx: FunctionContext) -> JsResult<JsString> {
+    let lock = Arc::clone(&**cx.argument::<BoxedLock>(0)?);
+    let result = thread::spawn(move || lock.with_blocking(|_| lock.with_blocking(|_| ()))).join();
+    let msg = match result {
+        Ok(()) => "did not panic".to_string(),
+        Err(panic) => match panic.downcast_ref::<&str>() {
+            Some(msg) => msg.to_string(),
+            None => "unknown panic".to_string(),
+        },
+    };
+
+    Ok(cx.string(msg))
+}
@@ -1,6 +1,17 @@
-use std::{cell::RefCell, sync::Arc, time::Duration};
-
-use neon::{prelude::*, types::buffer::TypedArray};
+use std::{
+    cell::RefCell,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Condvar, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use neon::{
+    event::{AsyncWork, Cancelled, ChannelStats, Priority, PriorityStats},
+    prelude::*,
+    types::buffer::TypedArray,
+};
 
 pub fn useless_root(mut cx: FunctionContext) -> JsResult<JsObject> {
     let object = cx.argument::<JsObject>(0)?;
@@ -289,13 +300,11 @@ pub fn channel_panic_throw(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let channel = cx.channel();
 
     std::thread::spawn(move || {
-        channel.send(move |mut cx| {
+        channel.send(move |mut cx| -> NeonResult<()> {
             // Throw an exception, but ignore the `Err(Throw)`
             let _ = cx.throw_error::<_, ()>(msg);
-            // Attempting to throw another error while already throwing should `panic`
-            let _ = cx.throw_error("Unreachable")?;
 
-            Ok(())
+            panic!("Panicked while throwing");
         })
     });
 
@@ -353,13 +362,11 @@ pub fn task_throw(mut cx: FunctionContext) -> JsResult<JsUndefined> {
 pub fn task_panic_throw(mut cx: FunctionContext) -> JsResult<JsUndefined> {
     let msg = cx.argument::<JsString>(0)?.value(&mut cx);
 
-    cx.task(|| {}).and_then(move |mut cx, _| {
+    cx.task(|| {}).and_then(move |mut cx, _| -> NeonResult<()> {
         // Throw an exception, but ignore the `Err(Throw)`
         let _ = cx.throw_error::<_, ()>(msg);
-        // Attempting to throw another error while already throwing should `panic`
-        let _ = cx.throw_error("Unreachable")?;
 
-        Ok(())
+        panic!("Panicked while throwing");
     });
 
     Ok(cx.undefined())
@@ -403,13 +410,78 @@ pub fn task_panic_complete_promise(mut cx: FunctionContext) -> JsResult<JsPromis
 
 pub fn task_panic_throw_promise(mut cx: FunctionContext) -> JsResult<JsPromise> {
     let msg = cx.argument::<JsString>(0)?.value(&mut cx);
-    let promise = cx.task(|| ()).promise(move |mut cx, _| {
-        // Throw an exception, but ignore the `Err(Throw)`
-        let _ = cx.throw_error::<_, ()>(msg);
-        // Attempting to throw another error while already throwing should `panic`
-        let _ = cx.throw_error("Unreachable")?;
+    let promise = cx
+        .task(|| ())
+        .promise(move |mut cx, _| -> JsResult<JsUndefined> {
+            // Throw an exception, but ignore the `Err(Throw)`
+            let _ = cx.throw_error::<_, ()>(msg);
 
-        Ok(cx.undefined())
+            panic!("Panicked while throwing");
+        });
+
+    Ok(promise)
+}
+
+// Number of times the `complete` callback of `task_timeout` was called
+static TASK_TIMEOUT_COMPLETIONS: AtomicUsize = AtomicUsize::new(0);
+
+// Number of times the `execute` callback of `task_timeout` was called
+static TASK_TIMEOUT_EXECUTIONS: AtomicUsize = AtomicUsize::new(0);
+
+// Number of times the `execute` callback of `task_timeout` returned
+static TASK_TIMEOUT_RETURNS: AtomicUsize = AtomicUsize::new(0);
+
+pub fn task_timeout(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let sleep = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let timeout = cx.argument::<JsNumber>(1)?.value(&mut cx);
+    let promise = cx
+        .task(move || {
+            TASK_TIMEOUT_EXECUTIONS.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(sleep as u64));
+            TASK_TIMEOUT_RETURNS.fetch_add(1, Ordering::SeqCst);
+        })
+        .timeout(Duration::from_millis(timeout as u64))
+        .promise(|mut cx, _| {
+            TASK_TIMEOUT_COMPLETIONS.fetch_add(1, Ordering::SeqCst);
+
+            Ok(cx.string("done"))
+        });
+
+    Ok(promise)
+}
+
+pub fn task_timeout_completions(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(TASK_TIMEOUT_COMPLETIONS.load(Ordering::SeqCst) as f64))
+}
+
+pub fn task_timeout_executions(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(TASK_TIMEOUT_EXECUTIONS.load(Ordering::SeqCst) as f64))
+}
+
+pub fn task_timeout_returns(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    Ok(cx.number(TASK_TIMEOUT_RETURNS.load(Ordering::SeqCst) as f64))
+}
+
+pub fn task_deadline_exceeded(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let timeout = cx.argument::<JsNumber>(0)?.value(&mut cx);
+    let (deferred, promise) = cx.promise();
+
+    cx.task(|| {
+        let before = TaskContext::deadline_exceeded();
+
+        while !TaskContext::deadline_exceeded() {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        before
+    })
+    .timeout(Duration::from_millis(timeout as u64))
+    .and_then(move |mut cx, before| {
+        let before = cx.boolean(before);
+
+        deferred.resolve(&mut cx, before);
+
+        Ok(())
     });
 
     Ok(promise)
@@ -451,15 +523,448 @@ pub fn deferred_settle_with_panic_throw(mut cx: FunctionContext) -> JsResult<JsP
     let channel = cx.channel();
 
     std::thread::spawn(move || {
-        deferred.try_settle_with(&channel, move |mut cx| {
+        deferred.try_settle_with(&channel, move |mut cx| -> JsResult<JsUndefined> {
             // Throw an exception, but ignore the `Err(Throw)`
             let _ = cx.throw_error::<_, ()>(msg);
-            // Attempting to throw another error while already throwing should `panic`
-            let _ = cx.throw_error("Unreachable")?;
 
-            Ok(cx.undefined())
+            panic!("Panicked while throwing");
         })
     });
 
     Ok(promise)
 }
+
+// Blocks `AsyncWork` on the worker pool until the gate is opened
+#[derive(Default)]
+struct Gate {
+    // Number of work items that have started and whether the gate is open
+    state: Mutex<(usize, bool)>,
+    cvar: Condvar,
+}
+
+impl Gate {
+    fn enter(&self) {
+        let mut state = self.state.lock().unwrap();
+
+        state.0 += 1;
+        self.cvar.notify_all();
+
+        while !state.1 {
+            state = self.cvar.wait(state).unwrap();
+        }
+    }
+
+    fn wait_for_started(&self, n: usize) {
+        let mut state = self.state.lock().unwrap();
+
+        while state.0 < n {
+            state = self.cvar.wait(state).unwrap();
+        }
+    }
+
+    fn open(&self) {
+        self.state.lock().unwrap().1 = true;
+        self.cvar.notify_all();
+    }
+}
+
+struct GatedWork {
+    gate: Arc<Gate>,
+    callback: Option<Root<JsFunction>>,
+}
+
+impl AsyncWork for GatedWork {
+    type Output = ();
+
+    const NAME: &'static str = "neon_test_gated_work";
+
+    fn execute(&mut self) {
+        self.gate.enter();
+    }
+
+    fn complete(self, cx: &mut TaskContext, output: Result<(), Cancelled>) -> NeonResult<()> {
+        if let Some(callback) = self.callback {
+            callback
+                .into_inner(cx)
+                .call_with(cx)
+                .arg(cx.boolean(output.is_err()))
+                .exec(cx)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn thread_pool_size() -> usize {
+    std::env::var("UV_THREADPOOL_SIZE")
+        .ok()
+        .and_then(|n| n.parse().ok())
+        .unwrap_or(4)
+}
+
+pub fn work_cancel_before_start(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+    let gate = Arc::new(Gate::default());
+    let n = thread_pool_size();
+
+    // Occupy every thread in the pool so that the next work item cannot start
+    for _ in 0..n {
+        cx.queue_work(GatedWork {
+            gate: gate.clone(),
+            callback: None,
+        });
+    }
+
+    gate.wait_for_started(n);
+
+    let handle = cx.queue_work(GatedWork {
+        gate: gate.clone(),
+        callback: Some(callback),
+    });
+
+    let cancelled = handle.cancel(&mut cx);
+
+    gate.open();
+
+    Ok(cx.boolean(cancelled))
+}
+
+pub fn work_cancel_after_start(mut cx: FunctionContext) -> JsResult<JsBoolean> {
+    let callback = cx.argument::<JsFunction>(0)?.root(&mut cx);
+    let gate = Arc::new(Gate::default());
+    let handle = cx.queue_work(GatedWork {
+        gate: gate.clone(),
+        callback: Some(callback),
+    });
+
+    gate.wait_for_started(1);
+
+    let cancelled = handle.cancel(&mut cx);
+
+    gate.open();
+
+    Ok(cx.boolean(cancelled))
+}
+
+// Re-queues itself from `complete` until `remaining` reaches zero
+struct Countdown {
+    remaining: u32,
+    executed: u32,
+    callback: Root<JsFunction>,
+}
+
+impl AsyncWork for Countdown {
+    type Output = ();
+
+    fn execute(&mut self) {
+        self.remaining -= 1;
+        self.executed += 1;
+    }
+
+    fn complete(self, cx: &mut TaskContext, _: Result<(), Cancelled>) -> NeonResult<()> {
+        if self.remaining > 0 {
+            cx.queue_work(self);
+
+            return Ok(());
+        }
+
+        let executed = cx.number(self.executed);
+
+        self.callback
+            .into_inner(cx)
+            .call_with(cx)
+            .arg(executed)
+            .exec(cx)
+    }
+}
+
+pub fn work_requeue(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let remaining = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let callback = cx.argument::<JsFunction>(1)?.root(&mut cx);
+
+    cx.queue_work(Countdown {
+        remaining,
+        executed: 0,
+        callback,
+    });
+
+    Ok(cx.undefined())
+}
+
+// Counters for channels closed by worker instances; the library is shared by every
+// instance in the process
+static CLOSE_CALLBACKS: AtomicUsize = AtomicUsize::new(0);
+static PRODUCERS_STOPPED: AtomicUsize = AtomicUsize::new(0);
+static CLOSE_SENT: AtomicUsize = AtomicUsize::new(0);
+static CLOSE_EXECUTED: AtomicUsize = AtomicUsize::new(0);
+static CLOSE_REJECTED: AtomicUsize = AtomicUsize::new(0);
+
+// Starts three producer threads, each with a clone of an unreferenced channel, that
+// send until the channel is closed
+pub fn channel_start_producers(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let mut channel = Channel::new(&mut cx);
+
+    channel.unref(&mut cx);
+    channel.on_close(|| {
+        CLOSE_CALLBACKS.fetch_add(1, Ordering::SeqCst);
+    });
+
+    for _ in 0..3 {
+        let channel = channel.clone();
+
+        std::thread::spawn(move || {
+            let closed = channel.closed();
+
+            // Sends once more after observing that the channel is closed, which may be
+            // before or after queued closures are executed
+            loop {
+                let was_closed = closed.is_closed();
+                let result = channel.try_send(|_| {
+                    CLOSE_EXECUTED.fetch_add(1, Ordering::SeqCst);
+                    Ok(())
+                });
+
+                match result {
+                    Ok(_) => CLOSE_SENT.fetch_add(1, Ordering::SeqCst),
+                    Err(_) => CLOSE_REJECTED.fetch_add(1, Ordering::SeqCst),
+                };
+
+                if was_closed {
+                    break;
+                }
+
+                std::thread::sleep(Duration::from_micros(50));
+            }
+
+            PRODUCERS_STOPPED.fetch_add(1, Ordering::SeqCst);
+        });
+    }
+
+    Ok(cx.undefined())
+}
+
+pub fn channel_close_stats(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let stats = cx.empty_object();
+
+    for (key, counter) in [
+        ("closeCallbacks", &CLOSE_CALLBACKS),
+        ("producersStopped", &PRODUCERS_STOPPED),
+        ("sent", &CLOSE_SENT),
+        ("executed", &CLOSE_EXECUTED),
+        ("rejected", &CLOSE_REJECTED),
+    ] {
+        let value = cx.number(counter.load(Ordering::SeqCst) as f64);
+
+        stats.set(&mut cx, key, value)?;
+    }
+
+    Ok(stats)
+}
+
+// Sends `count` slow closures with a low priority, then settles a promise with the
+// number of them that were executed first
+pub fn channel_priority_flood(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let channel = cx.channel_with_priority(Priority::Low);
+    let executed = Arc::new(AtomicUsize::new(0));
+    let (deferred, promise) = cx.promise();
+
+    for _ in 0..count {
+        let executed = executed.clone();
+
+        channel.send(move |_| {
+            let start = Instant::now();
+
+            while start.elapsed() < Duration::from_millis(1) {}
+
+            executed.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        });
+    }
+
+    deferred.settle_with(&channel, move |mut cx| {
+        Ok(cx.number(executed.load(Ordering::SeqCst) as f64))
+    });
+
+    Ok(promise)
+}
+
+// Sends a closure with a low priority, followed by `count` closures with a high
+// priority, and resolves with the position the low priority closure was executed in
+pub fn channel_priority_ratio(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let count = cx.argument::<JsNumber>(0)?.value(&mut cx) as usize;
+    let ratio = cx.argument::<JsNumber>(1)?.value(&mut cx) as u32;
+    let low = Channel::new(&mut cx).with_priority(Priority::Low);
+    let high = low.clone().with_priority(Priority::High);
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let (deferred, promise) = cx.promise();
+    let deferred = Arc::new(Mutex::new(Some(deferred)));
+
+    low.set_priority_ratio(ratio);
+
+    let record = move |channel: &Channel, priority| {
+        let order = order.clone();
+        let deferred = deferred.clone();
+
+        channel.send(move |mut cx| {
+            let mut order = order.lock().unwrap();
+
+            order.push(priority);
+
+            if order.len() == count + 1 {
+                let position = order.iter().position(|&p| p == Priority::Low).unwrap();
+                let position = cx.number(position as f64);
+
+                deferred
+                    .lock()
+                    .unwrap()
+                    .take()
+                    .unwrap()
+                    .resolve(&mut cx, position);
+            }
+
+            Ok(())
+        });
+    };
+
+    record(&low, Priority::Low);
+
+    for _ in 0..count {
+        record(&high, Priority::High);
+    }
+
+    Ok(promise)
+}
+
+fn priority_stats_to_js<'cx>(
+    cx: &mut impl Context<'cx>,
+    stats: PriorityStats,
+) -> JsResult<'cx, JsObject> {
+    let o = cx.empty_object();
+    let sent = cx.number(stats.sent as f64);
+    let executed = cx.number(stats.executed as f64);
+    let pending = cx.number(stats.pending as f64);
+
+    o.set(cx, "sent", sent)?;
+    o.set(cx, "executed", executed)?;
+    o.set(cx, "pending", pending)?;
+
+    Ok(o)
+}
+
+fn channel_stats_to_js<'cx>(
+    cx: &mut impl Context<'cx>,
+    stats: ChannelStats,
+) -> JsResult<'cx, JsObject> {
+    let o = cx.empty_object();
+    let high = priority_stats_to_js(cx, stats.high)?;
+    let normal = priority_stats_to_js(cx, stats.normal)?;
+    let low = priority_stats_to_js(cx, stats.low)?;
+
+    o.set(cx, "high", high)?;
+    o.set(cx, "normal", normal)?;
+    o.set(cx, "low", low)?;
+
+    Ok(o)
+}
+
+// Sends closures with each priority on a new channel and returns its stats, as
+// `{ queued, executed }`, where `executed` is a promise for the stats once every
+// closure is executed
+pub fn channel_priority_stats(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let mut channel = Channel::new(&mut cx);
+
+    // Dropping a clone of a referenced channel sends a closure to unreference it
+    channel.unref(&mut cx);
+    let (deferred, promise) = cx.promise();
+
+    for (priority, count) in [
+        (Priority::High, 2),
+        (Priority::Normal, 1),
+        (Priority::Low, 3),
+    ] {
+        let channel = channel.clone().with_priority(priority);
+
+        for _ in 0..count {
+            channel.send(|_| Ok(()));
+        }
+    }
+
+    let queued = channel_stats_to_js(&mut cx, channel.stats())?;
+    let low = channel.clone().with_priority(Priority::Low);
+
+    low.send(move |mut cx| {
+        let stats = channel_stats_to_js(&mut cx, channel.stats())?;
+
+        deferred.resolve(&mut cx, stats);
+        Ok(())
+    });
+
+    let result = cx.empty_object();
+
+    result.set(&mut cx, "queued", queued)?;
+    result.set(&mut cx, "executed", promise)?;
+
+    Ok(result)
+}
+
+static HELD_ROOTS: Mutex<Vec<Root<JsObject>>> = Mutex::new(Vec::new());
+
+pub fn hold_roots(mut cx: FunctionContext) -> JsResult<JsUndefined> {
+    let objects = cx.argument::<JsArray>(0)?.to_vec(&mut cx)?;
+    let mut roots = Vec::with_capacity(objects.len());
+
+    for object in objects {
+        roots.push(
+            object
+                .downcast_or_throw::<JsObject, _>(&mut cx)?
+                .root(&mut cx),
+        );
+    }
+
+    HELD_ROOTS.lock().unwrap().extend(roots);
+
+    Ok(cx.undefined())
+}
+
+// Drops the held roots on another thread, with `Root::drop_all_via` if `bulk` is true
+// and one at a time otherwise. Resolves with the number of closures sent on the shared
+// channel to drop them, once they are executed.
+pub fn drop_held_roots(mut cx: FunctionContext) -> JsResult<JsPromise> {
+    let bulk = cx.argument::<JsBoolean>(0)?.value(&mut cx);
+    let roots = std::mem::take(&mut *HELD_ROOTS.lock().unwrap());
+    let mut channel = cx.channel();
+
+    // Dropping a clone of a referenced channel sends a closure to unreference it
+    channel.unref(&mut cx);
+
+    let sent = |stats: ChannelStats| stats.high.sent + stats.normal.sent + stats.low.sent;
+    let before = sent(channel.stats());
+    let dropper = channel.clone();
+
+    // The JavaScript thread is blocked until every root is dropped, so the drops
+    // can't be executed while they are queued
+    std::thread::spawn(move || {
+        if bulk {
+            Root::drop_all_via(&dropper, roots);
+        } else {
+            drop(roots);
+        }
+    })
+    .join()
+    .unwrap();
+
+    let (deferred, promise) = cx.promise();
+    let stats = channel.clone();
+
+    // Executed after the drops, which are sent first with the same or a higher priority
+    channel.send(move |mut cx| {
+        // Excludes this closure
+        let n = cx.number((sent(stats.stats()) - before - 1) as f64);
+
+        deferred.resolve(&mut cx, n);
+        Ok(())
+    });
+
+    Ok(promise)
+}
@@ -1,6 +1,8 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use neon::{
     prelude::*,
-    types::buffer::{Binary, BorrowError, TypedArray},
+    types::buffer::{Binary, BorrowError, Taken, TypedArray},
 };
 
 pub fn return_array_buffer(mut cx: FunctionContext) -> JsResult<JsArrayBuffer> {
@@ -125,6 +127,59 @@ pub fn return_external_array_buffer(mut cx: FunctionContext) -> JsResult<JsArray
     Ok(buf)
 }
 
+// Address of the allocation of the last tracked external buffer
+static EXTERNAL_ADDRESS: AtomicUsize = AtomicUsize::new(0);
+
+fn tracked_bytes(cx: &mut FunctionContext) -> NeonResult<Vec<u8>> {
+    let len = cx.argument::<JsNumber>(0)?.value(cx) as usize;
+    let data = (0..len).map(|i| i as u8).collect::<Vec<_>>();
+
+    EXTERNAL_ADDRESS.store(data.as_ptr() as usize, Ordering::SeqCst);
+
+    Ok(data)
+}
+
+pub fn tracked_external_array_buffer(mut cx: FunctionContext) -> JsResult<JsArrayBuffer> {
+    let data = tracked_bytes(&mut cx)?;
+
+    Ok(JsArrayBuffer::external(&mut cx, data))
+}
+
+pub fn tracked_external_buffer(mut cx: FunctionContext) -> JsResult<JsBuffer> {
+    let data = tracked_bytes(&mut cx)?;
+
+    Ok(JsBuffer::external(&mut cx, data))
+}
+
+// Returns `{ copied, sameAllocation, data }`, where `data` is a copy of the bytes
+fn taken<'cx>(cx: &mut FunctionContext<'cx>, taken: Taken) -> JsResult<'cx, JsObject> {
+    let result = cx.empty_object();
+    let copied = cx.boolean(taken.is_copied());
+    let same = taken.as_ptr() as usize == EXTERNAL_ADDRESS.load(Ordering::SeqCst);
+    let same = cx.boolean(same);
+    let data = JsBuffer::from_slice(cx, &taken)?;
+
+    result.set(cx, "copied", copied)?;
+    result.set(cx, "sameAllocation", same)?;
+    result.set(cx, "data", data)?;
+
+    Ok(result)
+}
+
+pub fn take_array_buffer(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let buf = cx.argument::<JsArrayBuffer>(0)?;
+    let data = buf.take(&mut cx).or_throw(&mut cx)?;
+
+    taken(&mut cx, data)
+}
+
+pub fn take_buffer(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let buf = cx.argument::<JsBuffer>(0)?;
+    let data = buf.take(&mut cx).or_throw(&mut cx)?;
+
+    taken(&mut cx, data)
+}
+
 pub fn return_int8array_from_arraybuffer(mut cx: FunctionContext) -> JsResult<JsInt8Array> {
     let buf = cx.argument::<JsArrayBuffer>(0)?;
     JsInt8Array::from_buffer(&mut cx, buf)
@@ -379,3 +434,36 @@ pub fn write_buffer_with_borrow_mut(mut cx: FunctionContext) -> JsResult<JsUndef
 
     Ok(cx.undefined())
 }
+
+// Writes `input` scaled by `factor` into the caller's `output`, which must have the
+// same length
+#[neon::function]
+pub fn scale_into(input: &[f64], factor: f64, #[neon(out)] output: &mut [f64]) -> usize {
+    for (y, x) in output.iter_mut().zip(input) {
+        *y = x * factor;
+    }
+
+    output.len()
+}
+
+// Writes as much of `input` as fits into `output`
+#[neon::function]
+pub fn copy_into(input: &[f64], #[neon(out(partial))] output: &mut [f64]) -> usize {
+    let n = input.len().min(output.len());
+
+    output[..n].copy_from_slice(&input[..n]);
+    n
+}
+
+// Allocating version of `scale_into`
+pub fn scale(mut cx: FunctionContext) -> JsResult<JsFloat64Array> {
+    let input = cx.argument::<JsFloat64Array>(0)?;
+    let factor = cx.argument::<JsNumber>(1)?.value(&mut cx);
+    let output = input
+        .as_slice(&cx)
+        .iter()
+        .map(|x| x * factor)
+        .collect::<Vec<_>>();
+
+    JsFloat64Array::from_slice(&mut cx, &output)
+}
@@ -0,0 +1,113 @@
+use neon::{
+    prelude::*,
+    types::{extract::TryIntoJs, JsUrl},
+};
+
+pub fn url_new(mut cx: FunctionContext) -> JsResult<JsUrl> {
+    let input = cx.argument::<JsString>(0)?.value(&mut cx);
+    let base = cx.argument_opt(1);
+    let base = match base {
+        Some(base) => Some(
+            base.downcast_or_throw::<JsString, _>(&mut cx)?
+                .value(&mut cx),
+        ),
+        None => None,
+    };
+
+    JsUrl::new(&mut cx, &input, base.as_deref())
+}
+
+pub fn url_components(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let url = cx.argument::<JsUrl>(0)?;
+    let obj = cx.empty_object();
+    let href = url.href(&mut cx)?;
+    let origin = url.origin(&mut cx)?;
+    let protocol = url.protocol(&mut cx)?;
+    let username = url.username(&mut cx)?;
+    let password = url.password(&mut cx)?;
+    let host = url.host(&mut cx)?;
+    let hostname = url.hostname(&mut cx)?;
+    let port = url.port(&mut cx)?;
+    let pathname = url.pathname(&mut cx)?;
+    let search = url.search(&mut cx)?;
+    let hash = url.hash(&mut cx)?;
+
+    for (key, value) in [
+        ("href", href),
+        ("origin", origin),
+        ("protocol", protocol),
+        ("username", username),
+        ("password", password),
+        ("host", host),
+        ("hostname", hostname),
+        ("pathname", pathname),
+        ("search", search),
+        ("hash", hash),
+    ] {
+        let value = cx.string(value);
+
+        obj.set(&mut cx, key, value)?;
+    }
+
+    let port = match port {
+        Some(port) => cx.number(port).upcast::<JsValue>(),
+        None => cx.null().upcast(),
+    };
+
+    obj.set(&mut cx, "port", port)?;
+
+    Ok(obj)
+}
+
+pub fn url_search_param(mut cx: FunctionContext) -> JsResult<JsValue> {
+    let url = cx.argument::<JsUrl>(0)?;
+    let name = cx.argument::<JsString>(1)?.value(&mut cx);
+
+    match url.search_param(&mut cx, &name)? {
+        Some(value) => Ok(cx.string(value).upcast()),
+        None => Ok(cx.null().upcast()),
+    }
+}
+
+pub fn url_search_pairs(mut cx: FunctionContext) -> JsResult<JsArray> {
+    let url = cx.argument::<JsUrl>(0)?;
+    let pairs = url.search_pairs(&mut cx)?;
+    let array = cx.empty_array();
+
+    for (i, (name, value)) in pairs.into_iter().enumerate() {
+        let pair = vec![name, value].try_into_js(&mut cx)?;
+
+        array.set(&mut cx, i as u32, pair)?;
+    }
+
+    Ok(array)
+}
+
+// Extracts a `url::Url` from a `URL` or a string and converts it back to a `URL`
+pub fn url_roundtrip(mut cx: FunctionContext) -> JsResult<JsUrl> {
+    match cx.extract_argument::<url::Url>(0)? {
+        Some(url) => url.try_into_js(&mut cx),
+        None => cx.throw_type_error("expected a URL or a string"),
+    }
+}
+
+// The components of a `url::Url`, as parsed by Rust
+pub fn url_rust_components(mut cx: FunctionContext) -> JsResult<JsObject> {
+    let url = match cx.extract_argument::<url::Url>(0)? {
+        Some(url) => url,
+        None => return cx.throw_type_error("expected a URL or a string"),
+    };
+
+    let obj = cx.empty_object();
+    let username = cx.string(url.username());
+    let password = cx.string(url.password().unwrap_or_default());
+    let host = cx.string(url.host_str().unwrap_or_default());
+    let path = cx.string(url.path());
+
+    obj.set(&mut cx, "username", username)?;
+    obj.set(&mut cx, "password", password)?;
+    obj.set(&mut cx, "host", host)?;
+    obj.set(&mut cx, "path", path)?;
+
+    Ok(obj)
+}
@@ -54,6 +54,18 @@ pub fn get_or_init_thread_id(mut cx: FunctionContext) -> JsResult<JsNumber> {
     Ok(cx.number(*id))
 }
 
+// Reads `THREAD_ID` `n` times, to measure the lookup without the cost of the call
+pub fn get_thread_id_repeat(mut cx: FunctionContext) -> JsResult<JsNumber> {
+    let n = cx.argument::<JsNumber>(0)?.value(&mut cx) as u32;
+    let mut sum = 0u32;
+
+    for _ in 0..n {
+        sum = sum.wrapping_add(*THREAD_ID.get(&mut cx).unwrap_or(&0));
+    }
+
+    Ok(cx.number(sum))
+}
+
 static REENTRANT_LOCAL: LocalKey<u32> = LocalKey::new();
 
 pub fn reentrant_try_init(mut cx: FunctionContext) -> JsResult<JsNumber> {
@@ -6,24 +6,63 @@ use crate::js::{
 };
 
 mod js {
+    pub mod adapt;
+    pub mod affinity;
     pub mod arrays;
+    pub mod atomics;
     pub mod boxed;
+    pub mod capabilities;
     pub mod coercions;
+    pub mod collections;
+    pub mod compare;
+    pub mod custom;
     pub mod date;
+    pub mod deprecation;
+    pub mod diagnostics;
+    pub mod error_modes;
     pub mod errors;
+    pub mod forward;
     pub mod functions;
     pub mod futures;
+    pub mod handles;
+    pub mod hash;
+    pub mod instance;
+    pub mod instances;
+    pub mod interop;
+    pub mod iterables;
+    pub mod json;
+    pub mod lazy;
+    pub mod limits;
+    pub mod listeners;
+    pub mod memory;
+    pub mod naming;
     pub mod numbers;
     pub mod objects;
+    pub mod once;
+    pub mod oneshot;
+    pub mod operation;
+    pub mod patterns;
+    pub mod reentrancy;
+    pub mod ring;
+    pub mod state;
     pub mod strings;
+    pub mod sync;
     pub mod threads;
     pub mod typedarrays;
     pub mod types;
+    pub mod url;
     pub mod workers;
 }
 
 #[neon::main]
 fn main(mut cx: ModuleContext) -> NeonResult<()> {
+    // Fails the initialization, for the tests that load the addon in a new process
+    match std::env::var("NEON_TEST_INIT_FAILURE").as_deref() {
+        Ok("panic") => panic!("main panicked"),
+        Ok("throw") => return cx.throw_type_error("main failed"),
+        _ => {}
+    }
+
     let greeting = cx.string("Hello, World!");
     let greeting_copy = greeting.value(&mut cx);
     let greeting_copy = cx.string(greeting_copy);
@@ -31,6 +70,18 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_value("greeting", greeting)?;
     cx.export_value("greetingCopy", greeting_copy)?;
 
+    // Indicates if Neon was built with the `single-instance` feature
+    let single_instance = cx.boolean(cfg!(feature = "single-instance"));
+    cx.export_value("single_instance", single_instance)?;
+
+    // Indicates if Neon was built with the `memory-stats` feature
+    let memory_stats = cx.boolean(cfg!(feature = "memory-stats"));
+    cx.export_value("memory_stats_enabled", memory_stats)?;
+
+    // Indicates if Neon was built with the `debug-handles` feature
+    let debug_handles = cx.boolean(cfg!(feature = "debug-handles"));
+    cx.export_value("debug_handles_enabled", debug_handles)?;
+
     // Global singletons.
     let undefined = cx.undefined();
     let null = cx.null();
@@ -109,6 +160,25 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
 
     cx.export_function("return_js_string", return_js_string)?;
     cx.export_function("run_string_as_script", run_string_as_script)?;
+    cx.export_function("string_cache_new", string_cache_new)?;
+    cx.export_function("string_cache_to_rust", string_cache_to_rust)?;
+    cx.export_function("string_cache_stats", string_cache_stats)?;
+    cx.export_function("string_cache_clear", string_cache_clear)?;
+    cx.export_function("string_from_utf8_lossy", string_from_utf8_lossy)?;
+    cx.export_function("try_string_from_utf8", try_string_from_utf8)?;
+    #[cfg(unix)]
+    cx.export_function("string_from_path_bytes", string_from_path_bytes)?;
+    #[cfg(unix)]
+    cx.export_function("path_bytes_from_string", path_bytes_from_string)?;
+    cx.export_function("extract_cached_string", extract_cached_string)?;
+    cx.export_function("string_to_wide", string_to_wide)?;
+    cx.export_function("string_from_wide", string_from_wide)?;
+    cx.export_function("string_wide_reverse", string_wide_reverse)?;
+    cx.export_function("string_to_wide_repeat", string_to_wide_repeat)?;
+    cx.export_function(
+        "string_to_wide_via_utf8_repeat",
+        string_to_wide_via_utf8_repeat,
+    )?;
 
     cx.export_function("return_js_number", return_js_number)?;
     cx.export_function("return_large_js_number", return_large_js_number)?;
@@ -119,6 +189,9 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         return_negative_float_js_number,
     )?;
     cx.export_function("accept_and_return_js_number", accept_and_return_js_number)?;
+    cx.export_function("return_constants", return_constants)?;
+    cx.export_function("constants_in_scopes", constants_in_scopes)?;
+    cx.export_function("create_numbers", create_numbers)?;
     cx.export_function(
         "accept_and_return_large_js_number",
         accept_and_return_large_js_number,
@@ -131,6 +204,24 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
         "accept_and_return_negative_js_number",
         accept_and_return_negative_js_number,
     )?;
+    cx.export_function("extract_u64", extract_u64)?;
+    cx.export_function("extract_i64", extract_i64)?;
+    cx.export_function("extract_bigint_only_u64", extract_bigint_only_u64)?;
+    cx.export_function("u64_to_js", u64_to_js)?;
+    cx.export_function("i64_to_js", i64_to_js)?;
+    cx.export_function("u64_to_js_always_bigint", u64_to_js_always_bigint)?;
+    cx.export_function("extract_f64_vec", extract_f64_vec)?;
+    cx.export_function("extract_u64_vec", extract_u64_vec)?;
+    cx.export_function("extract_f64_vec_chunked", extract_f64_vec_chunked)?;
+    cx.export_function("extract_array_only_f64", extract_array_only_f64)?;
+    cx.export_function("sum_f64_vec", sum_f64_vec)?;
+    cx.export_function("duration_convert", duration_convert)?;
+    cx.export_function("duration_from_millis", duration_from_millis)?;
+    cx.export_function("duration_from_nanos", duration_from_nanos)?;
+    cx.export_function("duration_hrtime", duration_hrtime)?;
+    cx.export_function("float_convert", float_convert)?;
+    cx.export_function("float_nan", float_nan)?;
+    cx.export_function("float_set_policy", float_set_policy)?;
 
     cx.export_function("return_js_function", return_js_function)?;
     cx.export_function("call_js_function", call_js_function)?;
@@ -197,6 +288,11 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("return_js_array_with_number", return_js_array_with_number)?;
     cx.export_function("return_js_array_with_string", return_js_array_with_string)?;
     cx.export_function("read_js_array", read_js_array)?;
+    cx.export_function("array_set_len", array_set_len)?;
+    cx.export_function("array_truncate", array_truncate)?;
+    cx.export_function("array_set_range", array_set_range)?;
+    cx.export_function("array_fill", array_fill)?;
+    cx.export_function("array_build_reversed", array_build_reversed)?;
 
     cx.export_function("to_string", to_string)?;
 
@@ -233,10 +329,20 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     )?;
     cx.export_function("read_u8_typed_array", read_u8_typed_array)?;
     cx.export_function("copy_typed_array", copy_typed_array)?;
+    cx.export_function("scale_into", scale_into)?;
+    cx.export_function("copy_into", copy_into)?;
+    cx.export_function("scale", scale)?;
     cx.export_function("return_uninitialized_buffer", return_uninitialized_buffer)?;
     cx.export_function("return_buffer", return_buffer)?;
     cx.export_function("return_external_buffer", return_external_buffer)?;
     cx.export_function("return_external_array_buffer", return_external_array_buffer)?;
+    cx.export_function(
+        "tracked_external_array_buffer",
+        tracked_external_array_buffer,
+    )?;
+    cx.export_function("tracked_external_buffer", tracked_external_buffer)?;
+    cx.export_function("take_array_buffer", take_array_buffer)?;
+    cx.export_function("take_buffer", take_buffer)?;
     cx.export_function(
         "return_int8array_from_arraybuffer",
         return_int8array_from_arraybuffer,
@@ -279,6 +385,23 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("call_nullary_method", call_nullary_method)?;
     cx.export_function("call_unary_method", call_unary_method)?;
     cx.export_function("call_symbol_method", call_symbol_method)?;
+    cx.export_function("opt_get_or", opt_get_or)?;
+    cx.export_function("opt_chain_or", opt_chain_or)?;
+    cx.export_function("opt_call_method_or", opt_call_method_or)?;
+    cx.export_function("set_safe", set_safe)?;
+    cx.export_function("safe_dict_from_entries", safe_dict_from_entries)?;
+    cx.export_function("hash_map_to_js", hash_map_to_js)?;
+    cx.export_function("hash_map_to_dict", hash_map_to_dict)?;
+    cx.export_function("build_optional_object", build_optional_object)?;
+    cx.export_function("set_typed_key", set_typed_key)?;
+    cx.export_function("get_typed_key", get_typed_key)?;
+    cx.export_function("template_results", template_results)?;
+    cx.export_function("naive_results", naive_results)?;
+    cx.export_function("templates_created", templates_created)?;
+    cx.export_function("template_with_prototype", template_with_prototype)?;
+    cx.export_function("template_duplicate_keys", template_duplicate_keys)?;
+    cx.export_function("template_wrong_count", template_wrong_count)?;
+    cx.export_function("convert_nested", convert_nested)?;
 
     cx.export_function("create_date", create_date)?;
     cx.export_function("get_date_value", get_date_value)?;
@@ -307,7 +430,22 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("new_type_error", new_type_error)?;
     cx.export_function("new_range_error", new_range_error)?;
     cx.export_function("throw_error", throw_error)?;
+    cx.export_function("pending_safe_operations", pending_safe_operations)?;
+    cx.export_function("pending_failing_operations", pending_failing_operations)?;
+    cx.export_function("suppress_exception", suppress_exception)?;
     cx.export_function("downcast_error", downcast_error)?;
+    cx.export_function("reject_sendable_error", reject_sendable_error)?;
+    cx.export_function("sendable_error_from_sendable", sendable_error_from_sendable)?;
+    cx.export_function(
+        "sendable_error_with_backtrace",
+        sendable_error_with_backtrace,
+    )?;
+    cx.export_function("rust_trace_set_enabled", rust_trace_set_enabled)?;
+    cx.export_function("rust_trace_error", rust_trace_error)?;
+    cx.export_function("argument_string", argument_string)?;
+    cx.export_function("argument_array", argument_array)?;
+    cx.export_function("named_argument_string", named_argument_string)?;
+    cx.export_function("set_plain_argument_errors", set_plain_argument_errors)?;
 
     cx.export_function("panic", panic)?;
     cx.export_function("panic_after_throw", panic_after_throw)?;
@@ -315,8 +453,22 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("throw_and_catch", throw_and_catch)?;
     cx.export_function("call_and_catch", call_and_catch)?;
     cx.export_function("get_number_or_default", get_number_or_default)?;
+    cx.export_function("call_and_extract_point", call_and_extract_point)?;
+    cx.export_function("call_and_exec_checked", call_and_exec_checked)?;
+    cx.export_function("call_with_optional_args", call_with_optional_args)?;
+    cx.export_function("construct_with_optional_args", construct_with_optional_args)?;
+    cx.export_function("undefined_if_none", undefined_if_none)?;
     cx.export_function("is_construct", is_construct)?;
     cx.export_function("caller_with_drop_callback", caller_with_drop_callback)?;
+    cx.export_function("wrap_with_hooks", wrap_with_hooks)?;
+    cx.export_function("passthrough_nth", passthrough_nth)?;
+    cx.export_function("passthrough_labeled", passthrough_labeled)?;
+    cx.export_function("passthrough_this", passthrough_this)?;
+    cx.export_function("provided_nth", provided_nth)?;
+    cx.export_function("provided_rest", provided_rest)?;
+    cx.export_function("call_n", call_n)?;
+    cx.export_function("call_with_count", call_with_count)?;
+    cx.export_function("call_repeat", call_repeat)?;
 
     cx.export_function("count_called", {
         let n = std::cell::RefCell::new(0);
@@ -342,6 +494,16 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("ref_person_set_name", ref_person_set_name)?;
     cx.export_function("ref_person_fail", ref_person_fail)?;
     cx.export_function("external_unit", external_unit)?;
+    cx.export_function("shared_box_new", shared_box_new)?;
+    cx.export_function("shared_box_share", shared_box_share)?;
+    cx.export_function("shared_box_from_token", shared_box_from_token)?;
+    cx.export_function(
+        "shared_box_from_token_wrong_type",
+        shared_box_from_token_wrong_type,
+    )?;
+    cx.export_function("shared_box_data", shared_box_data)?;
+    cx.export_function("shared_box_ptr", shared_box_ptr)?;
+    cx.export_function("shared_box_finalized", shared_box_finalized)?;
 
     cx.export_function("useless_root", useless_root)?;
     cx.export_function("thread_callback", thread_callback)?;
@@ -351,6 +513,13 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("leak_channel", leak_channel)?;
     cx.export_function("drop_global_queue", drop_global_queue)?;
     cx.export_function("channel_join", channel_join)?;
+    cx.export_function("channel_start_producers", channel_start_producers)?;
+    cx.export_function("channel_close_stats", channel_close_stats)?;
+    cx.export_function("channel_priority_flood", channel_priority_flood)?;
+    cx.export_function("channel_priority_ratio", channel_priority_ratio)?;
+    cx.export_function("channel_priority_stats", channel_priority_stats)?;
+    cx.export_function("hold_roots", hold_roots)?;
+    cx.export_function("drop_held_roots", drop_held_roots)?;
     cx.export_function("sum", sum)?;
     cx.export_function("sum_manual_promise", sum_manual_promise)?;
     cx.export_function("sum_rust_thread", sum_rust_thread)?;
@@ -369,25 +538,325 @@ fn main(mut cx: ModuleContext) -> NeonResult<()> {
     cx.export_function("task_panic_execute_promise", task_panic_execute_promise)?;
     cx.export_function("task_panic_complete_promise", task_panic_complete_promise)?;
     cx.export_function("task_panic_throw_promise", task_panic_throw_promise)?;
+    cx.export_function("task_timeout", task_timeout)?;
+    cx.export_function("task_timeout_completions", task_timeout_completions)?;
+    cx.export_function("task_timeout_executions", task_timeout_executions)?;
+    cx.export_function("task_timeout_returns", task_timeout_returns)?;
+    cx.export_function("task_deadline_exceeded", task_deadline_exceeded)?;
     cx.export_function("deferred_settle_with_throw", deferred_settle_with_throw)?;
     cx.export_function("deferred_settle_with_panic", deferred_settle_with_panic)?;
     cx.export_function(
         "deferred_settle_with_panic_throw",
         deferred_settle_with_panic_throw,
     )?;
+    cx.export_function("work_cancel_before_start", work_cancel_before_start)?;
+    cx.export_function("work_cancel_after_start", work_cancel_after_start)?;
+    cx.export_function("work_requeue", work_requeue)?;
     cx.export_function("get_and_replace", js::workers::get_and_replace)?;
     cx.export_function("get_or_init", js::workers::get_or_init)?;
     cx.export_function("get_or_init_clone", js::workers::get_or_init_clone)?;
     cx.export_function("get_or_init_thread_id", js::workers::get_or_init_thread_id)?;
+    cx.export_function("get_thread_id_repeat", js::workers::get_thread_id_repeat)?;
     cx.export_function("reentrant_try_init", js::workers::reentrant_try_init)?;
     cx.export_function("get_reentrant_value", js::workers::get_reentrant_value)?;
     cx.export_function("stash_global_object", js::workers::stash_global_object)?;
     cx.export_function("unstash_global_object", js::workers::unstash_global_object)?;
     cx.export_function("reject_after", js::workers::reject_after)?;
 
+    cx.export_function("watchable_new", js::state::watchable_new::<f64>)?;
+    cx.export_function(
+        "watchable_set_from_thread",
+        js::state::watchable_set_from_thread::<f64>,
+    )?;
+    cx.export_function(
+        "watchable_counted_new",
+        js::state::watchable_new::<js::state::Counted>,
+    )?;
+    cx.export_function(
+        "watchable_counted_set_from_thread",
+        js::state::watchable_set_from_thread::<js::state::Counted>,
+    )?;
+    cx.export_function("counted_live", js::state::counted_live)?;
+    js::state::export_live(&mut cx)?;
+
+    cx.export_function("listeners_on", js::listeners::listeners_on)?;
+    cx.export_function("listeners_off", js::listeners::listeners_off)?;
+    cx.export_function("listeners_emit", js::listeners::listeners_emit)?;
+    cx.export_function(
+        "listeners_emit_from_thread",
+        js::listeners::listeners_emit_from_thread,
+    )?;
+    cx.export_function("listeners_count", js::listeners::listeners_count)?;
+    cx.export_function("listeners_clear", js::listeners::listeners_clear)?;
+
+    cx.export_function("deep_equals", js::compare::deep_equals)?;
+    cx.export_function("deep_diff", js::compare::deep_diff)?;
+
+    cx.export_function("limits_measure", js::limits::limits_measure)?;
+    cx.export_function("limits_allocated", js::limits::limits_allocated)?;
+    cx.export_function("limits_extract_numbers", js::limits::limits_extract_numbers)?;
+    cx.export_function("limits_tree_depth", js::limits::limits_tree_depth)?;
+    cx.export_function("limits_extract_strings", js::limits::limits_extract_strings)?;
+    cx.export_function(
+        "limits_set_max_conversion_depth",
+        js::limits::limits_set_max_conversion_depth,
+    )?;
+    cx.export_function(
+        "limits_max_conversion_depth",
+        js::limits::limits_max_conversion_depth,
+    )?;
+
+    cx.export_function("iterable_sum", js::iterables::iterable_sum)?;
+    cx.export_function("iterable_try_sum", js::iterables::iterable_try_sum)?;
+    cx.export_function("iterable_size_hint", js::iterables::iterable_size_hint)?;
+    cx.export_function("iterable_take", js::iterables::iterable_take)?;
+    cx.export_function("iterable_for_each", js::iterables::iterable_for_each)?;
+
+    cx.export_function("interop_round_trip", js::interop::interop_round_trip)?;
+    cx.export_function(
+        "interop_string_from_raw",
+        js::interop::interop_string_from_raw,
+    )?;
+    cx.export_function("interop_create_uint32", js::interop::interop_create_uint32)?;
+
+    cx.export_capabilities([("present", true), ("missing", false)])?;
+    cx.export_function("capability_present", js::capabilities::capability_present)?;
+    cx.export_function("capability_missing", js::capabilities::capability_missing)?;
+    cx.export_function(
+        "capability_unregistered",
+        js::capabilities::capability_unregistered,
+    )?;
+    cx.export_function(
+        "capability_is_available",
+        js::capabilities::capability_is_available,
+    )?;
+
+    cx.export_function("reentrant_for_each", js::reentrancy::reentrant_for_each)?;
+    cx.export_function("reentrant_update", js::reentrancy::reentrant_update)?;
+    cx.export_function("reentrant_peek", js::reentrancy::reentrant_peek)?;
+    cx.export_function("reentrant_manual", js::reentrancy::reentrant_manual)?;
+
+    cx.export_function("deprecated_once", js::deprecation::deprecated_once)?;
+    cx.export_function("deprecated_throws", js::deprecation::deprecated_throws)?;
+    cx.export_function("deprecated_target", js::deprecation::deprecated_target)?;
+    cx.export_deprecated_alias("deprecated_alias", "deprecated_target")?;
+    cx.export_function("deprecation_code", js::deprecation::deprecation_code)?;
+
+    cx.export_function("error_mode_throw", js::error_modes::error_mode_throw)?;
+    cx.export_function("error_mode_value", js::error_modes::error_mode_value)?;
+    cx.export_function("error_mode_tuple", js::error_modes::error_mode_tuple)?;
+    cx.export_function("error_mode_plain", js::error_modes::error_mode_plain)?;
+    cx.export_function(
+        "error_mode_conversion",
+        js::error_modes::error_mode_conversion,
+    )?;
+
+    cx.export_function("atomics_len", js::atomics::atomics_len)?;
+    cx.export_function("atomics_store_notify", js::atomics::atomics_store_notify)?;
+    cx.export_function("atomics_wait", js::atomics::atomics_wait)?;
+    cx.export_function(
+        "atomics_wait_on_js_thread",
+        js::atomics::atomics_wait_on_js_thread,
+    )?;
+
+    cx.export_function("forward_callback", js::forward::forward_callback)?;
+    cx.export_function("forward_event", js::forward::forward_event)?;
+    cx.export_function("forward_back_pressure", js::forward::forward_back_pressure)?;
+    cx.export_function("forward_sent", js::forward::forward_sent)?;
+    cx.export_function("forward_endless", js::forward::forward_endless)?;
+
+    cx.export_function("custom_is_url", js::custom::custom_is_url)?;
+    cx.export_function("custom_classify", js::custom::custom_classify)?;
+    cx.export_function("custom_url_host", js::custom::custom_url_host)?;
+    cx.export_function("custom_map_size", js::custom::custom_map_size)?;
+    cx.export_function("custom_try_from_js", js::custom::custom_try_from_js)?;
+    cx.export_function("custom_parse_url", js::custom::custom_parse_url)?;
+    cx.export_function("custom_is_throwing", js::custom::custom_is_throwing)?;
+
+    cx.export_function(
+        "btree_map_round_trip",
+        js::collections::btree_map_round_trip,
+    )?;
+    cx.export_function(
+        "btree_map_numeric_keys",
+        js::collections::btree_map_numeric_keys,
+    )?;
+    cx.export_function("hash_map_round_trip", js::collections::hash_map_round_trip)?;
+    cx.export_function("hash_set_round_trip", js::collections::hash_set_round_trip)?;
+    cx.export_function(
+        "btree_set_round_trip",
+        js::collections::btree_set_round_trip,
+    )?;
+    cx.export_function("entries_round_trip", js::collections::entries_round_trip)?;
+    cx.export_function("entries_duplicates", js::collections::entries_duplicates)?;
+    cx.export_function("partial_rows", js::collections::partial_rows)?;
+    cx.export_function("partial_numbers", js::collections::partial_numbers)?;
+
+    cx.export_function("affinity_same_thread", js::affinity::affinity_same_thread)?;
+    cx.export_function("affinity_channel", js::affinity::affinity_channel)?;
+    cx.export_function(
+        "affinity_spawned_thread",
+        js::affinity::affinity_spawned_thread,
+    )?;
+    cx.export_function("affinity_capture_main", js::affinity::affinity_capture_main)?;
+    cx.export_function("affinity_assert_main", js::affinity::affinity_assert_main)?;
+
+    cx.export_function("instance_id", js::instances::instance_id)?;
+    cx.export_function("instance_ids", js::instances::instance_ids)?;
+    cx.export_function("instance_set_listed", js::instances::instance_set_listed)?;
+    cx.export_function("instance_broadcast", js::instances::instance_broadcast)?;
+    cx.export_function("instance_send_to", js::instances::instance_send_to)?;
+    cx.export_function("instance_message", js::instances::instance_message)?;
+
+    js::naming::api::export(&mut cx)?;
+
+    js::lazy::export(&mut cx)?;
+    cx.export_function("lazy_inits", js::lazy::lazy_inits)?;
+
+    cx.export_function("memory_stats", js::memory::memory_stats)?;
+    cx.export_function("memory_hold_roots", js::memory::memory_hold_roots)?;
+    cx.export_function("memory_release_roots", js::memory::memory_release_roots)?;
+    cx.export_function("memory_create_box", js::memory::memory_create_box)?;
+    cx.export_function("memory_queue_closures", js::memory::memory_queue_closures)?;
+
+    cx.export_function("oneshot_number", js::oneshot::oneshot_number)?;
+    cx.export_function("oneshot_dropped", js::oneshot::oneshot_dropped)?;
+    cx.export_function("oneshot_timeout", js::oneshot::oneshot_timeout)?;
+    cx.export_function("oneshot_number_async", js::futures::oneshot_number_async)?;
+
+    cx.export_function("json_stringify_records", js::json::json_stringify_records)?;
+    cx.export_function("json_stringify_string", js::json::json_stringify_string)?;
+    cx.export_function("json_parse_chunks", js::json::json_parse_chunks)?;
+    cx.export_function("json_stringify_numbers", js::json::json_stringify_numbers)?;
+
+    cx.export_function("watchdog_enable", js::diagnostics::watchdog_enable)?;
+    cx.export_function("watchdog_disable", js::diagnostics::watchdog_disable)?;
+    cx.export_function("watchdog_reports", js::diagnostics::watchdog_reports)?;
+    cx.export_function("watchdog_slow", js::diagnostics::watchdog_slow)?;
+    cx.export_function("watchdog_fast", js::diagnostics::watchdog_fast)?;
+    cx.export_function("watchdog_nested", js::diagnostics::watchdog_nested)?;
+    cx.export_function("channel_publish", js::diagnostics::channel_publish)?;
+    cx.export_function(
+        "channel_publish_from",
+        js::diagnostics::channel_publish_from,
+    )?;
+    cx.export_function("traced_add", js::diagnostics::traced_add)?;
+    cx.export_function("traced_parse", js::diagnostics::traced_parse)?;
+
+    cx.export_function("url_new", js::url::url_new)?;
+    cx.export_function("url_components", js::url::url_components)?;
+    cx.export_function("url_search_param", js::url::url_search_param)?;
+    cx.export_function("url_search_pairs", js::url::url_search_pairs)?;
+    cx.export_function("url_roundtrip", js::url::url_roundtrip)?;
+    cx.export_function("url_rust_components", js::url::url_rust_components)?;
+
+    cx.export_function("operation_current", js::operation::operation_current)?;
+    cx.export_function("operation_nested", js::operation::operation_nested)?;
+    cx.export_function("operation_throw", js::operation::operation_throw)?;
+    cx.export_function("operation_panic", js::operation::operation_panic)?;
+    cx.export_function(
+        "operation_task_reject",
+        js::operation::operation_task_reject,
+    )?;
+    cx.export_function("operation_task_panic", js::operation::operation_task_panic)?;
+    cx.export_function(
+        "operation_task_and_then",
+        js::operation::operation_task_and_then,
+    )?;
+    cx.export_function("operation_channel", js::operation::operation_channel)?;
+    cx.export_function(
+        "operation_channel_panic",
+        js::operation::operation_channel_panic,
+    )?;
+
+    cx.export_function("Database", js::instance::database_new)?;
+    cx.export_function("database_create", js::instance::database_create)?;
+    cx.export_function("database_query", js::instance::database_query)?;
+    cx.export_function("database_name", js::instance::database_name)?;
+    cx.export_function("database_identity", js::instance::database_identity)?;
+    cx.export_function(
+        "database_root_roundtrip",
+        js::instance::database_root_roundtrip,
+    )?;
+
+    cx.export_function("ring_create", js::ring::ring_create)?;
+    cx.export_function("ring_push", js::ring::ring_push)?;
+    cx.export_function("ring_produce_in_thread", js::ring::ring_produce_in_thread)?;
+
+    cx.export_function("rw_cell_new", js::sync::rw_cell_new)?;
+    cx.export_function("rw_cell_read", js::sync::rw_cell_read)?;
+    cx.export_function("rw_cell_increment", js::sync::rw_cell_increment)?;
+    cx.export_function(
+        "rw_cell_increment_in_thread",
+        js::sync::rw_cell_increment_in_thread,
+    )?;
+    cx.export_function("rw_cell_read_async", js::sync::rw_cell_read_async)?;
+    cx.export_function(
+        "rw_cell_write_while_reading",
+        js::sync::rw_cell_write_while_reading,
+    )?;
+    cx.export_function(
+        "rw_cell_read_while_writer_waits",
+        js::sync::rw_cell_read_while_writer_waits,
+    )?;
+    cx.export_function("instance_lock_new", js::sync::instance_lock_new)?;
+    cx.export_function("instance_lock_local", js::sync::instance_lock_local)?;
+    cx.export_function("instance_lock_get", js::sync::instance_lock_get)?;
+    cx.export_function("instance_lock_increment", js::sync::instance_lock_increment)?;
+    cx.export_function(
+        "instance_lock_hold_in_thread",
+        js::sync::instance_lock_hold_in_thread,
+    )?;
+    cx.export_function(
+        "instance_lock_increment_async",
+        js::sync::instance_lock_increment_async,
+    )?;
+    cx.export_function(
+        "instance_lock_defer_increment",
+        js::sync::instance_lock_defer_increment,
+    )?;
+    cx.export_function(
+        "instance_lock_increment_in_threads",
+        js::sync::instance_lock_increment_in_threads,
+    )?;
+    cx.export_function("instance_lock_panic", js::sync::instance_lock_panic)?;
+    cx.export_function(
+        "instance_lock_reenter_blocking",
+        js::sync::instance_lock_reenter_blocking,
+    )?;
+
+    cx.export_function("once_library_ptr", js::once::once_library_ptr)?;
+    cx.export_function("once_library_inits", js::once::once_library_inits)?;
+    cx.export_function("once_attach_detachable", js::once::once_attach_detachable)?;
+    cx.export_function("once_detachable_deinits", js::once::once_detachable_deinits)?;
+    cx.export_function("once_panic", js::once::once_panic)?;
+    cx.export_function("once_wrong_type", js::once::once_wrong_type)?;
+
+    cx.export_function("adapt_new_sink", js::adapt::adapt_new_sink)?;
+    cx.export_function("adapt_drive_sink", js::adapt::adapt_drive_sink)?;
+    cx.export_function(
+        "adapt_write_on_main_thread",
+        js::adapt::adapt_write_on_main_thread,
+    )?;
+
+    cx.export_function("hash_values", js::hash::hash_values)?;
+    cx.export_function("hash_equal", js::hash::hash_equal)?;
+    cx.export_function("hash_allocations", js::hash::hash_allocations)?;
+
+    cx.export_function("patterns_new_hasher", js::patterns::patterns_new_hasher)?;
+    cx.export_function(
+        "patterns_dropped_hashers",
+        js::patterns::patterns_dropped_hashers,
+    )?;
+
+    cx.export_function("handles_escape", js::handles::handles_escape)?;
+    cx.export_function("handles_use_escaped", js::handles::handles_use_escaped)?;
+    cx.export_function("handles_use_outer", js::handles::handles_use_outer)?;
+
     // Futures
     cx.export_function("lazy_async_add", js::futures::lazy_async_add)?;
     cx.export_function("lazy_async_sum", js::futures::lazy_async_sum)?;
+    cx.export_function("future_double", js::futures::future_double)?;
 
     Ok(())
 }